@@ -12,8 +12,9 @@ use groot_store::db::api::{
 use groot_store::db::common::bytes::util::parse_pb;
 use groot_store::db::graph::store::GraphStore;
 use groot_store::db::proto::model::{
-    AddEdgeKindPb, AddVertexTypePropertiesPb, ConfigPb, CreateVertexTypePb, DataOperationPb,
-    DdlOperationPb, EdgeIdPb, EdgeLocationPb, OpTypePb, OperationBatchPb, OperationPb, VertexIdPb,
+    AddEdgeKindPb, AddVertexTypePropertiesPb, ConfigPb, CreateVertexIndexPb, CreateVertexTypePb,
+    DataOperationPb, DdlOperationPb, DropVertexIndexPb, EdgeIdPb, EdgeLocationPb, OpTypePb,
+    OperationBatchPb, OperationPb, VertexIdPb,
 };
 use groot_store::db::proto::model::{CommitDataLoadPb, PrepareDataLoadPb};
 use groot_store::db::proto::schema_common::{EdgeKindPb, LabelIdPb, TypeDefPb};
@@ -160,6 +161,40 @@ pub extern "C" fn getGraphStatistics(ptr: GraphHandle, snapshot_id: i64) -> Box<
     }
 }
 
+#[no_mangle]
+pub extern "C" fn getGraphChanges(
+    ptr: GraphHandle, from_snapshot_id: i64, to_snapshot_id: i64, offset: i64, limit: i32,
+) -> Box<JnaResponse> {
+    debug!("getGraphChanges");
+    if offset < 0 || limit < 0 {
+        let msg = format!("invalid offset {} or limit {} of graph changes", offset, limit);
+        return JnaResponse::new_error(&msg);
+    }
+    unsafe {
+        let graph_store_ptr = &*(ptr as *const GraphStore);
+        match graph_store_ptr.get_changes_blob(
+            from_snapshot_id,
+            to_snapshot_id,
+            offset as usize,
+            limit as usize,
+        ) {
+            Ok(blob) => {
+                let mut response = JnaResponse::new_success();
+                if let Err(e) = response.data(blob) {
+                    response.success(false);
+                    let msg = format!("{:?}", e);
+                    response.err_msg(&msg);
+                }
+                response
+            }
+            Err(e) => {
+                let msg = format!("{:?}", e);
+                JnaResponse::new_error(&msg)
+            }
+        }
+    }
+}
+
 fn do_write_batch<G: MultiVersionGraph>(
     graph: &G, snapshot_id: SnapshotId, buf: &[u8],
 ) -> GraphResult<bool> {
@@ -223,6 +258,16 @@ fn do_write_batch<G: MultiVersionGraph>(
                     has_ddl = true;
                 }
             }
+            OpTypePb::CREATE_VERTEX_INDEX => {
+                if create_vertex_index(graph, snapshot_id, op)? {
+                    has_ddl = true;
+                }
+            }
+            OpTypePb::DROP_VERTEX_INDEX => {
+                if drop_vertex_index(graph, snapshot_id, op)? {
+                    has_ddl = true;
+                }
+            }
             OpTypePb::PREPARE_DATA_LOAD => {
                 if prepare_data_load(graph, snapshot_id, op)? {
                     has_ddl = true;
@@ -306,6 +351,31 @@ fn drop_vertex_type<G: MultiVersionGraph>(
     graph.drop_vertex_type(snapshot_id, schema_version, label_id)
 }
 
+fn create_vertex_index<G: MultiVersionGraph>(
+    graph: &G, snapshot_id: i64, op: &OperationPb,
+) -> GraphResult<bool> {
+    info!("create_vertex_index");
+    let ddl_operation_pb = parse_pb::<DdlOperationPb>(op.get_dataBytes())?;
+    let schema_version = ddl_operation_pb.get_schemaVersion();
+    let create_vertex_index_pb = parse_pb::<CreateVertexIndexPb>(ddl_operation_pb.get_ddlBlob())?;
+    let label_id = create_vertex_index_pb.get_labelId().get_id();
+    let prop_id = create_vertex_index_pb.get_propertyId();
+    let table_id = create_vertex_index_pb.get_tableIdx();
+    graph.create_vertex_index(snapshot_id, schema_version, label_id, prop_id, table_id)
+}
+
+fn drop_vertex_index<G: MultiVersionGraph>(
+    graph: &G, snapshot_id: i64, op: &OperationPb,
+) -> GraphResult<bool> {
+    info!("drop_vertex_index");
+    let ddl_operation_pb = parse_pb::<DdlOperationPb>(op.get_dataBytes())?;
+    let schema_version = ddl_operation_pb.get_schemaVersion();
+    let drop_vertex_index_pb = parse_pb::<DropVertexIndexPb>(ddl_operation_pb.get_ddlBlob())?;
+    let label_id = drop_vertex_index_pb.get_labelId().get_id();
+    let prop_id = drop_vertex_index_pb.get_propertyId();
+    graph.drop_vertex_index(snapshot_id, schema_version, label_id, prop_id)
+}
+
 fn create_edge_type<G: MultiVersionGraph>(
     graph: &G, snapshot_id: i64, op: &OperationPb,
 ) -> GraphResult<bool> {
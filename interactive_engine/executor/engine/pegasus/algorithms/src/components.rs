@@ -0,0 +1,67 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::sync::Arc;
+
+use pegasus::api::{Map, Pair, Superstep, SuperstepConf};
+use pegasus::stream::Stream;
+use pegasus::BuildJobError;
+
+use crate::Topology;
+
+/// Label every vertex with the smallest vertex id of its weakly connected component.
+///
+/// Edge directions are ignored: in the first superstep each vertex tells its out neighbors
+/// about itself, so the labels can then be propagated along in edges as well.
+pub fn weakly_connected_components<G: Topology>(
+    vertices: Stream<u64>, graph: Arc<G>,
+) -> Result<Stream<(u64, u64)>, BuildJobError> {
+    // the value is (component label, in neighbors);
+    let conf = SuperstepConf::<u64, ()>::new(u32::MAX);
+    vertices
+        .map(|id| Ok(Pair { key: id, value: (id, vec![]) }))?
+        .superstep(conf, move |ctx| {
+            let id = *ctx.key();
+            if ctx.superstep() == 0 {
+                for n in graph.neighbors(id) {
+                    ctx.send_to(n, id);
+                }
+                return Ok(());
+            }
+
+            let messages = ctx.take_messages();
+            let min = messages.iter().copied().min();
+            if ctx.superstep() == 1 {
+                ctx.value_mut().1 = messages;
+            }
+            let changed = match min {
+                Some(label) if label < ctx.value().0 => {
+                    ctx.value_mut().0 = label;
+                    true
+                }
+                _ => ctx.superstep() == 1,
+            };
+            if changed {
+                let label = ctx.value().0;
+                let in_neighbors = ctx.value().1.clone();
+                for n in graph.neighbors(id).chain(in_neighbors) {
+                    ctx.send_to(n, label);
+                }
+            }
+            ctx.vote_to_halt();
+            Ok(())
+        })?
+        .map(|pair| Ok((pair.key, pair.value.0)))
+}
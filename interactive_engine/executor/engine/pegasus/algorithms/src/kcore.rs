@@ -0,0 +1,54 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::sync::Arc;
+
+use pegasus::api::{Map, Pair, Superstep, SuperstepConf};
+use pegasus::stream::Stream;
+use pegasus::BuildJobError;
+
+use crate::Topology;
+
+/// The vertices of the `k`-core of an undirected graph, i.e. the largest subgraph in which
+/// every vertex has at least `k` neighbors. Vertices with fewer than `k` remaining neighbors
+/// are peeled off in each superstep, and notify their neighbors.
+pub fn k_core<G: Topology>(
+    vertices: Stream<u64>, graph: Arc<G>, k: u32,
+) -> Result<Stream<u64>, BuildJobError> {
+    // the value is (removed, remaining degree), and messages count removed neighbors;
+    let conf = SuperstepConf::<u32, ()>::new(u32::MAX).combine_with(|a, b| a + b);
+    vertices
+        .map(|id| Ok(Pair { key: id, value: (false, 0u32) }))?
+        .superstep(conf, move |ctx| {
+            let id = *ctx.key();
+            if ctx.superstep() == 0 {
+                ctx.value_mut().1 = graph.neighbors(id).len() as u32;
+            } else {
+                let removed: u32 = ctx.messages().iter().sum();
+                let degree = &mut ctx.value_mut().1;
+                *degree = degree.saturating_sub(removed);
+            }
+            let (removed, degree) = *ctx.value();
+            if !removed && degree < k {
+                ctx.value_mut().0 = true;
+                for n in graph.neighbors(id) {
+                    ctx.send_to(n, 1);
+                }
+            }
+            ctx.vote_to_halt();
+            Ok(())
+        })?
+        .filter_map(|pair| Ok(if pair.value.0 { None } else { Some(pair.key) }))
+}
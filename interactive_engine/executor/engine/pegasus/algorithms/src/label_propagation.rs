@@ -0,0 +1,67 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use pegasus::api::{Map, Pair, Superstep, SuperstepConf};
+use pegasus::stream::Stream;
+use pegasus::BuildJobError;
+
+use crate::Topology;
+
+/// Detect communities of an undirected graph by label propagation. Starting from its own id,
+/// each vertex repeatedly takes the most frequent label among itself and its neighbors, the
+/// smallest one on ties, until no label changes or after `max_iterations`.
+pub fn label_propagation<G: Topology>(
+    vertices: Stream<u64>, graph: Arc<G>, max_iterations: u32,
+) -> Result<Stream<(u64, u64)>, BuildJobError> {
+    // the value is (label, the labels of neighbors), and messages are (sender, label);
+    let conf = SuperstepConf::<(u64, u64), ()>::new(max_iterations + 1);
+    vertices
+        .map(|id| Ok(Pair { key: id, value: (id, BTreeMap::<u64, u64>::new()) }))?
+        .superstep(conf, move |ctx| {
+            let id = *ctx.key();
+            if ctx.superstep() > 0 {
+                let messages = ctx.take_messages();
+                let (label, neighbors) = ctx.value_mut();
+                neighbors.extend(messages);
+                let mut counts = BTreeMap::new();
+                *counts.entry(*label).or_insert(0) += 1;
+                for l in neighbors.values() {
+                    *counts.entry(*l).or_insert(0) += 1;
+                }
+                // the first label with the highest count is the smallest one;
+                let mut best = (*label, 0);
+                for (l, c) in counts {
+                    if c > best.1 {
+                        best = (l, c);
+                    }
+                }
+                if best.0 == *label {
+                    ctx.vote_to_halt();
+                    return Ok(());
+                }
+                *label = best.0;
+            }
+            let label = ctx.value().0;
+            for n in graph.neighbors(id) {
+                ctx.send_to(n, (id, label));
+            }
+            ctx.vote_to_halt();
+            Ok(())
+        })?
+        .map(|pair| Ok((pair.key, pair.value.0)))
+}
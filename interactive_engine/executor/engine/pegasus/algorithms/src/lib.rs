@@ -0,0 +1,74 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! Graph algorithms written on the pegasus dataflow API.
+//!
+//! Every algorithm takes the stream of vertex ids owned by the current worker, usually given by
+//! [`local_vertices`], together with the topology shared by all workers, and extends the dataflow
+//! with the computation. Algorithms on undirected graphs (label propagation, triangle counting
+//! and k-core) expect the topology to store both directions of each edge.
+
+use std::ops::Deref;
+
+use pegasus_graph::topo::{IdTopo, Neighbors};
+
+mod components;
+mod kcore;
+mod label_propagation;
+mod pagerank;
+mod shortest_paths;
+mod triangles;
+
+pub use components::weakly_connected_components;
+pub use kcore::k_core;
+pub use label_propagation::label_propagation;
+pub use pagerank::{page_rank, PageRankConf};
+pub use shortest_paths::{bfs, dijkstra};
+pub use triangles::triangle_count;
+
+/// The graph topology an algorithm runs on, shared by all workers;
+pub trait Topology: Send + Sync + 'static {
+    fn vertices(&self) -> Box<dyn Iterator<Item = u64> + '_>;
+
+    fn neighbors(&self, id: u64) -> Neighbors;
+
+    fn total_vertices(&self) -> usize;
+}
+
+impl<B: Deref<Target = [u64]> + Send + Sync + 'static> Topology for IdTopo<B> {
+    fn vertices(&self) -> Box<dyn Iterator<Item = u64> + '_> {
+        Box::new(IdTopo::vertices(self).copied())
+    }
+
+    fn neighbors(&self, id: u64) -> Neighbors {
+        self.get_neighbors(id)
+    }
+
+    fn total_vertices(&self) -> usize {
+        IdTopo::total_vertices(self)
+    }
+}
+
+/// The vertices owned by the `index`th worker of `peers`, partitioned by `id % peers` as
+/// `repartition` does;
+pub fn local_vertices<G: Topology>(graph: &G, index: u32, peers: u32) -> Vec<u64> {
+    let peers = peers.max(1) as u64;
+    let mut vertices = graph
+        .vertices()
+        .filter(|id| *id % peers == index as u64)
+        .collect::<Vec<_>>();
+    vertices.sort_unstable();
+    vertices
+}
@@ -0,0 +1,76 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::sync::Arc;
+
+use pegasus::api::{Map, Pair, Superstep, SuperstepConf};
+use pegasus::stream::Stream;
+use pegasus::BuildJobError;
+
+use crate::Topology;
+
+#[derive(Copy, Clone, Debug)]
+pub struct PageRankConf {
+    pub damping: f64,
+    pub max_iterations: u32,
+    /// Stop once the L1 norm of the rank changes in one iteration is below `tolerance`;
+    pub tolerance: f64,
+}
+
+impl Default for PageRankConf {
+    fn default() -> Self {
+        PageRankConf { damping: 0.85, max_iterations: 20, tolerance: 1e-6 }
+    }
+}
+
+/// Compute the PageRank of every vertex, following out edges. The ranks sum up to 1, as
+/// the rank of dangling vertices is spread over all vertices.
+pub fn page_rank<G: Topology>(
+    vertices: Stream<u64>, graph: Arc<G>, conf: PageRankConf,
+) -> Result<Stream<(u64, f64)>, BuildJobError> {
+    let n = graph.total_vertices().max(1) as f64;
+    let damping = conf.damping;
+    // the aggregate is (sum of rank changes, rank of dangling vertices);
+    let superstep_conf = SuperstepConf::<f64, (f64, f64)>::new(conf.max_iterations + 1)
+        .combine_with(|a, b| a + b)
+        .aggregate_with((0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1))
+        .halt_when(move |(delta, _)| *delta < conf.tolerance);
+    vertices
+        .map(move |id| Ok(Pair { key: id, value: 1.0 / n }))?
+        .superstep(superstep_conf, move |ctx| {
+            let mut delta = *ctx.value();
+            if ctx.superstep() > 0 {
+                let dangling = ctx.aggregated().map(|a| a.1).unwrap_or(0.0);
+                let sum: f64 = ctx.messages().iter().sum();
+                let rank = (1.0 - damping) / n + damping * (sum + dangling / n);
+                delta = (rank - *ctx.value()).abs();
+                *ctx.value_mut() = rank;
+            }
+            let rank = *ctx.value();
+            let neighbors = graph.neighbors(*ctx.key());
+            let degree = neighbors.len();
+            if degree == 0 {
+                ctx.aggregate((delta, rank));
+            } else {
+                ctx.aggregate((delta, 0.0));
+                let share = rank / degree as f64;
+                for n in neighbors {
+                    ctx.send_to(n, share);
+                }
+            }
+            Ok(())
+        })?
+        .map(|pair| Ok((pair.key, pair.value)))
+}
@@ -0,0 +1,99 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::sync::Arc;
+
+use pegasus::api::{Map, Pair, Superstep, SuperstepConf};
+use pegasus::stream::Stream;
+use pegasus::BuildJobError;
+
+use crate::Topology;
+
+/// The number of hops from `source` to every vertex reachable from it along out edges;
+/// unreachable vertices are not output.
+pub fn bfs<G: Topology>(
+    vertices: Stream<u64>, graph: Arc<G>, source: u64,
+) -> Result<Stream<(u64, u32)>, BuildJobError> {
+    let conf = SuperstepConf::<u32, ()>::new(u32::MAX).combine_with(|a, b| a.min(b));
+    vertices
+        .map(|id| Ok(Pair { key: id, value: u32::MAX }))?
+        .superstep(conf, move |ctx| {
+            let hops = if ctx.superstep() == 0 {
+                if *ctx.key() == source {
+                    Some(0)
+                } else {
+                    None
+                }
+            } else {
+                ctx.messages()
+                    .iter()
+                    .copied()
+                    .min()
+                    .filter(|hops| *hops < *ctx.value())
+            };
+            if let Some(hops) = hops {
+                *ctx.value_mut() = hops;
+                for n in graph.neighbors(*ctx.key()) {
+                    ctx.send_to(n, hops + 1);
+                }
+            }
+            ctx.vote_to_halt();
+            Ok(())
+        })?
+        .filter_map(|pair| Ok(if pair.value == u32::MAX { None } else { Some((pair.key, pair.value)) }))
+}
+
+/// The weighted distance from `source` to every vertex reachable from it along out edges,
+/// where `weight(src, dst)` gives the non-negative weight of an edge; unreachable vertices
+/// are not output.
+///
+/// The distances are the ones Dijkstra's algorithm finds, computed by relaxing the edges of
+/// all improved vertices in each superstep, so that no global priority queue is needed.
+pub fn dijkstra<G, W>(
+    vertices: Stream<u64>, graph: Arc<G>, source: u64, weight: W,
+) -> Result<Stream<(u64, f64)>, BuildJobError>
+where
+    G: Topology,
+    W: Fn(u64, u64) -> f64 + Send + 'static,
+{
+    let conf = SuperstepConf::<f64, ()>::new(u32::MAX).combine_with(|a, b| a.min(b));
+    vertices
+        .map(|id| Ok(Pair { key: id, value: f64::INFINITY }))?
+        .superstep(conf, move |ctx| {
+            let distance = if ctx.superstep() == 0 {
+                if *ctx.key() == source {
+                    Some(0.0)
+                } else {
+                    None
+                }
+            } else {
+                ctx.messages()
+                    .iter()
+                    .copied()
+                    .fold(None, |min: Option<f64>, d| Some(min.map_or(d, |m| m.min(d))))
+                    .filter(|d| *d < *ctx.value())
+            };
+            if let Some(distance) = distance {
+                let id = *ctx.key();
+                *ctx.value_mut() = distance;
+                for n in graph.neighbors(id) {
+                    ctx.send_to(n, distance + weight(id, n));
+                }
+            }
+            ctx.vote_to_halt();
+            Ok(())
+        })?
+        .filter_map(|pair| Ok(if pair.value.is_finite() { Some((pair.key, pair.value)) } else { None }))
+}
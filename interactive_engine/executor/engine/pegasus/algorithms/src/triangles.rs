@@ -0,0 +1,54 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::sync::Arc;
+
+use pegasus::api::{Count, Filter, Map};
+use pegasus::stream::{SingleItem, Stream};
+use pegasus::BuildJobError;
+
+use crate::Topology;
+
+/// Count the triangles of an undirected graph, each triangle once. Every step runs on the
+/// worker owning the vertex whose neighbors it reads: a wedge `u < v < w` is built by the
+/// owners of `u` and `v`, and closed by the owner of `u`.
+pub fn triangle_count<G: Topology>(
+    vertices: Stream<u64>, graph: Arc<G>,
+) -> Result<SingleItem<u64>, BuildJobError> {
+    let g1 = graph.clone();
+    let g2 = graph.clone();
+    vertices
+        .repartition(|u| Ok(*u))
+        .flat_map(move |u| {
+            let higher = g1
+                .neighbors(u)
+                .filter(|v| *v > u)
+                .map(|v| (v, u))
+                .collect::<Vec<_>>();
+            Ok(higher.into_iter())
+        })?
+        .repartition(|(v, _)| Ok(*v))
+        .flat_map(move |(v, u)| {
+            let wedges = g2
+                .neighbors(v)
+                .filter(|w| *w > v)
+                .map(|w| (u, w))
+                .collect::<Vec<_>>();
+            Ok(wedges.into_iter())
+        })?
+        .repartition(|(u, _)| Ok(*u))
+        .filter(move |(u, w)| Ok(graph.neighbors(*u).any(|n| n == *w)))?
+        .count()
+}
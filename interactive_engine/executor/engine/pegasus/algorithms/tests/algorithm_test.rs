@@ -0,0 +1,320 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+//
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use pegasus::api::Sink;
+use pegasus::stream::Stream;
+use pegasus::{BuildJobError, Data, JobConf};
+use pegasus_algorithms::*;
+use pegasus_graph::topo::IdTopo;
+
+type Graph = IdTopo<Vec<u64>>;
+
+fn directed(edges: &[(u64, u64)]) -> Arc<Graph> {
+    Arc::new(IdTopo::from_edges(edges.iter().copied()))
+}
+
+fn undirected(edges: &[(u64, u64)]) -> Arc<Graph> {
+    Arc::new(IdTopo::from_edges(
+        edges
+            .iter()
+            .flat_map(|(s, t)| vec![(*s, *t), (*t, *s)]),
+    ))
+}
+
+/// Run an algorithm with two workers, each starting from the vertices it owns;
+fn run_algorithm<O, F>(name: &str, graph: Arc<Graph>, func: F) -> Vec<O>
+where
+    O: Data + PartialOrd,
+    F: Fn(Stream<u64>, Arc<Graph>) -> Result<Stream<O>, BuildJobError> + Send + Sync + 'static,
+{
+    let mut conf = JobConf::new(name);
+    conf.set_workers(2);
+    let func = Arc::new(func);
+    let mut results = pegasus::run(conf, move || {
+        let graph = graph.clone();
+        let func = func.clone();
+        move |input, output| {
+            let worker = pegasus::get_current_worker();
+            let vertices = local_vertices(&*graph, worker.index, worker.total_peers());
+            func(input.input_from(vertices)?, graph.clone())?.sink_into(output)
+        }
+    })
+    .expect("submit job failure")
+    .map(|item| item.unwrap())
+    .collect::<Vec<O>>();
+    results.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    results
+}
+
+fn neighbors(graph: &Graph, id: u64) -> Vec<u64> {
+    graph.get_neighbors(id).collect()
+}
+
+fn all_vertices(graph: &Graph) -> Vec<u64> {
+    let mut vertices = graph.vertices().copied().collect::<Vec<_>>();
+    vertices.sort();
+    vertices
+}
+
+fn reference_page_rank(graph: &Graph, conf: PageRankConf) -> BTreeMap<u64, f64> {
+    let vertices = all_vertices(graph);
+    let n = vertices.len() as f64;
+    let mut ranks = vertices
+        .iter()
+        .map(|v| (*v, 1.0 / n))
+        .collect::<BTreeMap<_, _>>();
+    for _ in 0..conf.max_iterations {
+        let mut next = vertices
+            .iter()
+            .map(|v| (*v, 0.0))
+            .collect::<BTreeMap<_, _>>();
+        let mut dangling = 0.0;
+        for v in vertices.iter() {
+            let out = neighbors(graph, *v);
+            if out.is_empty() {
+                dangling += ranks[v];
+            }
+            for n in out.iter() {
+                *next.get_mut(n).unwrap() += ranks[v] / out.len() as f64;
+            }
+        }
+        let mut delta = 0.0;
+        for v in vertices.iter() {
+            let rank = (1.0 - conf.damping) / n + conf.damping * (next[v] + dangling / n);
+            delta += (rank - ranks[v]).abs();
+            ranks.insert(*v, rank);
+        }
+        if delta < conf.tolerance {
+            break;
+        }
+    }
+    ranks
+}
+
+fn reference_bfs(graph: &Graph, source: u64) -> Vec<(u64, u32)> {
+    let mut hops = BTreeMap::new();
+    let mut queue = VecDeque::new();
+    hops.insert(source, 0u32);
+    queue.push_back(source);
+    while let Some(v) = queue.pop_front() {
+        let h = hops[&v];
+        for n in neighbors(graph, v) {
+            if !hops.contains_key(&n) {
+                hops.insert(n, h + 1);
+                queue.push_back(n);
+            }
+        }
+    }
+    hops.into_iter().collect()
+}
+
+fn reference_dijkstra(graph: &Graph, source: u64, weight: fn(u64, u64) -> f64) -> Vec<(u64, f64)> {
+    let mut distances = BTreeMap::new();
+    let mut heap = BinaryHeap::new();
+    // distances are integral in the tests, so they can be ordered as integers;
+    heap.push(Reverse((0u64, source)));
+    while let Some(Reverse((d, v))) = heap.pop() {
+        if distances.contains_key(&v) {
+            continue;
+        }
+        distances.insert(v, d as f64);
+        for n in neighbors(graph, v) {
+            if !distances.contains_key(&n) {
+                heap.push(Reverse((d + weight(v, n) as u64, n)));
+            }
+        }
+    }
+    distances.into_iter().collect()
+}
+
+fn reference_triangles(graph: &Graph) -> u64 {
+    let vertices = all_vertices(graph);
+    let mut count = 0;
+    for u in vertices.iter() {
+        let nu = neighbors(graph, *u)
+            .into_iter()
+            .collect::<HashSet<_>>();
+        for v in nu.iter().filter(|v| *v > u) {
+            for w in neighbors(graph, *v)
+                .into_iter()
+                .filter(|w| w > v)
+            {
+                if nu.contains(&w) {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+fn reference_k_core(graph: &Graph, k: u32) -> Vec<u64> {
+    let mut alive = all_vertices(graph)
+        .into_iter()
+        .collect::<HashSet<_>>();
+    loop {
+        let peeled = alive
+            .iter()
+            .copied()
+            .filter(|v| {
+                neighbors(graph, *v)
+                    .iter()
+                    .filter(|n| alive.contains(n))
+                    .count()
+                    < k as usize
+            })
+            .collect::<Vec<_>>();
+        if peeled.is_empty() {
+            break;
+        }
+        for v in peeled {
+            alive.remove(&v);
+        }
+    }
+    let mut alive = alive.into_iter().collect::<Vec<_>>();
+    alive.sort();
+    alive
+}
+
+// a small social graph: two dense groups {0..4} and {5..8} joined by 4 -> 5, a tail 9 -> 10,
+// and a vertex 11 without out edges;
+const EDGES: [(u64, u64); 19] = [
+    (0, 1),
+    (0, 2),
+    (1, 2),
+    (1, 3),
+    (2, 3),
+    (3, 0),
+    (3, 4),
+    (4, 0),
+    (4, 5),
+    (5, 6),
+    (5, 7),
+    (6, 7),
+    (6, 8),
+    (7, 8),
+    (8, 5),
+    (2, 9),
+    (9, 10),
+    (10, 2),
+    (10, 11),
+];
+
+#[test]
+fn page_rank_test() {
+    let graph = directed(&EDGES);
+    let conf = PageRankConf { damping: 0.85, max_iterations: 100, tolerance: 1e-10 };
+    let expected = reference_page_rank(&graph, conf);
+    let results = run_algorithm("page_rank_test", graph, move |src, graph| page_rank(src, graph, conf));
+    assert_eq!(results.len(), expected.len());
+    let mut sum = 0.0;
+    for (v, rank) in results {
+        assert!((rank - expected[&v]).abs() < 1e-8, "rank of {} is {}, expected {}", v, rank, expected[&v]);
+        sum += rank;
+    }
+    assert!((sum - 1.0).abs() < 1e-8);
+}
+
+#[test]
+fn page_rank_max_iterations_test() {
+    let graph = directed(&EDGES);
+    let conf = PageRankConf { damping: 0.85, max_iterations: 3, tolerance: 0.0 };
+    let expected = reference_page_rank(&graph, conf);
+    let results = run_algorithm("page_rank_max_iterations_test", graph, move |src, graph| {
+        page_rank(src, graph, conf)
+    });
+    for (v, rank) in results {
+        assert!((rank - expected[&v]).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn weakly_connected_components_test() {
+    // directed edges, where 4 and 6 are only reachable against the edge direction;
+    let graph = directed(&[(1, 0), (2, 1), (3, 4), (5, 4), (6, 5), (7, 7)]);
+    let results = run_algorithm("weakly_connected_components_test", graph, |src, graph| {
+        weakly_connected_components(src, graph)
+    });
+    assert_eq!(results, vec![(0, 0), (1, 0), (2, 0), (3, 3), (4, 3), (5, 3), (6, 3), (7, 7)]);
+}
+
+#[test]
+fn bfs_test() {
+    let graph = directed(&EDGES);
+    for source in vec![0, 5, 11] {
+        let expected = reference_bfs(&graph, source);
+        let results = run_algorithm("bfs_test", graph.clone(), move |src, graph| bfs(src, graph, source));
+        assert_eq!(results, expected);
+    }
+}
+
+fn edge_weight(src: u64, dst: u64) -> f64 {
+    ((src * 7 + dst * 3) % 5 + 1) as f64
+}
+
+#[test]
+fn dijkstra_test() {
+    let graph = directed(&EDGES);
+    for source in vec![0, 5, 9] {
+        let expected = reference_dijkstra(&graph, source, edge_weight);
+        let results = run_algorithm("dijkstra_test", graph.clone(), move |src, graph| {
+            dijkstra(src, graph, source, edge_weight)
+        });
+        assert_eq!(results, expected);
+    }
+}
+
+#[test]
+fn triangle_count_test() {
+    let graph = undirected(&EDGES);
+    let expected = reference_triangles(&graph);
+    let results =
+        run_algorithm("triangle_count_test", graph, |src, graph| triangle_count(src, graph)?.into_stream());
+    assert_eq!(results, vec![expected]);
+    // {0,1,2}, {1,2,3}, {0,2,3}, {0,1,3}, {0,3,4}, {5,6,7}, {6,7,8}, {5,6,8}, {5,7,8}, {2,9,10};
+    assert_eq!(expected, 10);
+}
+
+#[test]
+fn label_propagation_test() {
+    // two 4-cliques joined by the edge 3 - 4;
+    let mut edges = vec![(3, 4)];
+    for group in [[0u64, 1, 2, 3], [4, 5, 6, 7]].iter() {
+        for i in 0..4 {
+            for j in i + 1..4 {
+                edges.push((group[i], group[j]));
+            }
+        }
+    }
+    let graph = undirected(&edges);
+    let results =
+        run_algorithm("label_propagation_test", graph, |src, graph| label_propagation(src, graph, 20));
+    assert_eq!(results, vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 4), (5, 4), (6, 4), (7, 4)]);
+}
+
+#[test]
+fn k_core_test() {
+    let graph = undirected(&EDGES);
+    for k in 1..5 {
+        let expected = reference_k_core(&graph, k);
+        let results = run_algorithm("k_core_test", graph.clone(), move |src, graph| k_core(src, graph, k));
+        assert_eq!(results, expected, "{}-core", k);
+    }
+    assert_eq!(reference_k_core(&graph, 3), vec![0, 1, 2, 3, 4, 5, 6, 7, 8]);
+}
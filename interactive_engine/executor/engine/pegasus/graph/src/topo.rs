@@ -86,6 +86,25 @@ impl<B: Deref<Target = [u64]>> IdTopo<B> {
     }
 }
 
+impl IdTopo<Vec<u64>> {
+    /// Build an in-memory topology from directed `(src, dst)` edges. Every endpoint becomes
+    /// a vertex, so vertices without out edges are kept as well.
+    pub fn from_edges<I: IntoIterator<Item = (u64, u64)>>(edges: I) -> Self {
+        let mut adjacency: IntMap<u64, Vec<u64>> = IntMap::default();
+        for (src, dst) in edges {
+            adjacency.entry(src).or_default().push(dst);
+            adjacency.entry(dst).or_default();
+        }
+        let mut vertices = IntMap::default();
+        let mut neighbors = vec![];
+        for (id, targets) in adjacency {
+            vertices.insert(id, (neighbors.len() as u64, targets.len() as u64));
+            neighbors.extend(targets);
+        }
+        IdTopo::new(vertices, neighbors)
+    }
+}
+
 lazy_static! {
     static ref EMPTY_VEC: Vec<u64> = vec![];
 }
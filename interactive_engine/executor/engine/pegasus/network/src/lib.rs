@@ -42,16 +42,7 @@ pub fn start_up<D: ServerDetect + 'static, A: ToSocketAddrs>(
 ) -> Result<SocketAddr, NetError> {
     info!("start server {} ...", server_id);
     let mut mgr = manager::ServerManager::new(server_id, conf, detect);
-    {
-        let mut lock = SHUTDOWN_HOOK
-            .write()
-            .expect("SHUTDOWN_HOOK write lock failure;");
-        if lock.contains_key(&server_id) {
-            return Err(NetError::ServerStarted(server_id));
-        } else {
-            lock.insert(server_id, Arc::new(AtomicBool::new(false)));
-        }
-    }
+    register_server(server_id)?;
 
     let addr = mgr.bind(addr)?;
     let guard = std::thread::Builder::new()
@@ -69,6 +60,18 @@ pub fn start_up<D: ServerDetect + 'static, A: ToSocketAddrs>(
     Ok(addr)
 }
 
+pub(crate) fn register_server(server_id: u64) -> Result<(), NetError> {
+    let mut lock = SHUTDOWN_HOOK
+        .write()
+        .expect("SHUTDOWN_HOOK write lock failure;");
+    if lock.contains_key(&server_id) {
+        Err(NetError::ServerStarted(server_id))
+    } else {
+        lock.insert(server_id, Arc::new(AtomicBool::new(false)));
+        Ok(())
+    }
+}
+
 #[inline]
 pub fn shutdown(server_id: u64) {
     info!("server {} shutdown...", server_id);
@@ -148,6 +151,7 @@ pub use send::{check_has_network_error, IPCSender};
 #[cfg(feature = "benchmark")]
 pub use send::{MessageEncoder, SimpleEncoder, SlabEncoder};
 pub use state::check_connect;
+pub use transport::memory::{Link, MemoryNetwork, Schedule};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Server {
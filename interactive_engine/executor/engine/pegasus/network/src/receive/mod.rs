@@ -15,6 +15,7 @@
 
 use std::collections::HashMap;
 use std::io;
+use std::io::Read;
 use std::net::TcpStream;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -124,6 +125,14 @@ pub fn start_net_receiver(
         conn.set_read_timeout(timeout).ok();
     }
 
+    start_stream_receiver(local, remote, hb_sec, params, poisoned, conn);
+}
+
+/// Start a receiver reading from a stream, which should return [`io::ErrorKind::WouldBlock`]
+/// or [`io::ErrorKind::TimedOut`] if no data comes in a while;
+pub(crate) fn start_stream_receiver<R: Read + Send + 'static>(
+    local: u64, remote: Server, hb_sec: u32, params: &ConnectionParams, poisoned: Arc<AtomicBool>, conn: R,
+) {
     let slab_size = params.get_read_params().slab_size;
     let decoder = self::decode::get_reentrant_decoder(slab_size);
     let mut net_recv = NetReceiver::new(hb_sec as u64, remote.addr, conn, decoder);
@@ -27,7 +27,7 @@ use crossbeam_utils::sync::ShardedLock;
 use pegasus_common::codec::Encode;
 
 use crate::config::{BlockMode, ConnectionParams, DEFAULT_SLAB_SIZE};
-use crate::message::MessageHeader;
+use crate::message::{MessageHeader, MESSAGE_HEAD_SIZE};
 use crate::{NetError, Server};
 
 mod encode;
@@ -44,6 +44,7 @@ pub struct IPCSender<T: Encode> {
     encoder: GeneralEncoder<T>,
     outbox_tx: Sender<NetData>,
     close_guard: Arc<AtomicUsize>,
+    sent_bytes: u64,
 }
 
 impl<T: Encode> IPCSender<T> {
@@ -51,6 +52,7 @@ impl<T: Encode> IPCSender<T> {
         let mut header = MessageHeader::new(self.channel_id);
         header.sequence = self.sequence;
         let payload = self.encoder.encode(&mut header, msg)?;
+        let bytes = (MESSAGE_HEAD_SIZE as u64) + header.length;
         self.outbox_tx
             .send(NetData::AppData(self.channel_id, payload))
             .map_err(|_| {
@@ -58,9 +60,15 @@ impl<T: Encode> IPCSender<T> {
                 io::Error::from(io::ErrorKind::BrokenPipe)
             })?;
         self.sequence += 1;
+        self.sent_bytes += bytes;
         Ok(())
     }
 
+    /// The total bytes of messages sent by this sender, including headers;
+    pub fn sent_bytes(&self) -> u64 {
+        self.sent_bytes
+    }
+
     pub fn close(&mut self) -> io::Result<()> {
         if self.close_guard.fetch_sub(1, Ordering::SeqCst) == 1 {
             let mut header = MessageHeader::new(self.channel_id);
@@ -85,6 +93,7 @@ impl<T: Encode + 'static> IPCSender<T> {
             encoder: SlabEncoder::new(DEFAULT_SLAB_SIZE).into(),
             outbox_tx,
             close_guard: Arc::new(AtomicUsize::new(1)),
+            sent_bytes: 0,
         }
     }
 
@@ -107,6 +116,7 @@ impl<T: Encode + 'static> Clone for IPCSender<T> {
             encoder: self.encoder.clone(),
             outbox_tx: self.outbox_tx.clone(),
             close_guard: self.close_guard.clone(),
+            sent_bytes: 0,
         }
     }
 }
@@ -258,6 +268,28 @@ pub(crate) fn start_net_sender(
     crate::add_network_thread(local_id, guard);
 }
 
+/// Start a sender writing to an in-process stream, which is blocking and need no socket options;
+pub(crate) fn start_stream_sender<W: Write + Send + 'static>(
+    local_id: u64, remote: Server, params: &ConnectionParams, state: &Arc<AtomicBool>,
+    recv_poisoned: &Arc<AtomicBool>, conn: W,
+) {
+    let disconnected = state.clone();
+    let timeout = params.get_write_params().wait_data as u64;
+    let mut net_tx = NetSender::new(remote.addr, conn);
+    let tx = net_tx.get_outbox_tx().as_ref().expect("");
+    add_remote_sender(local_id, &remote, tx);
+    let recv_poisoned = recv_poisoned.clone();
+    let guard = std::thread::Builder::new()
+        .name(format!("net-sender-{}-{}", local_id, remote.id))
+        .spawn(move || {
+            busy_send(&mut net_tx, true, timeout, local_id, remote.id, recv_poisoned);
+            error!("Connection to server {} lost", remote.id);
+            disconnected.store(true, Ordering::SeqCst);
+        })
+        .expect("start net-sender thread failure;");
+    crate::add_network_thread(local_id, guard);
+}
+
 fn busy_send<W: Write>(
     net_tx: &mut NetSender<W>, block: bool, timeout: u64, local: u64, remote: u64,
     recv_poisoned: Arc<AtomicBool>,
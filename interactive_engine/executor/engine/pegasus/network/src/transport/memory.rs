@@ -0,0 +1,391 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! An in-memory transport connecting logical servers inside one process, with control over the
+//! order messages are delivered in, and faults like delayed links and dropped servers.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{self, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::config::ConnectionParams;
+use crate::receive::start_stream_receiver;
+use crate::send::start_stream_sender;
+use crate::{NetError, Server};
+
+/// A directed link between two servers in a trace, as `(from, to)` indexes of the servers in
+/// [`MemoryNetwork::servers`], so that a trace can be replayed by another network;
+pub type Link = (usize, usize);
+
+/// How the next message to deliver is picked among links. Messages of one link are always
+/// delivered in the order they are written, as a TCP connection does.
+#[derive(Clone, Debug)]
+pub enum Schedule {
+    /// Deliver messages in the order they are written;
+    Fifo,
+    /// Deliver the first message of a link picked randomly by the seed, so that links are
+    /// interleaved differently for each seed.
+    ///
+    /// The seed only decides the pick among links which have messages ready at the time of a
+    /// read. Messages are written by the threads of sending servers concurrently, so which links
+    /// are ready depends on thread timing, and a seed doesn't reproduce a run exactly. Record the
+    /// [`MemoryNetwork::trace`] of a run and use [`Schedule::Replay`] to reproduce it;
+    Seeded(u64),
+    /// Deliver messages by the order of links in a trace recorded by [`MemoryNetwork::trace`],
+    /// to replay a run; falls back to [`Schedule::Fifo`] if the run diverges from the trace;
+    Replay(Vec<Link>),
+}
+
+/// How long a reader waits for data before returning [`io::ErrorKind::WouldBlock`];
+const READ_WAIT: Duration = Duration::from_millis(20);
+/// How long a replay waits for the message of the next link in trace before giving up;
+const REPLAY_WAIT: Duration = Duration::from_secs(1);
+
+static NEXT_SERVER_ID: AtomicU64 = AtomicU64::new(1 << 16);
+
+struct Chunk {
+    seq: u64,
+    ready: Instant,
+    bytes: Vec<u8>,
+}
+
+#[derive(Default)]
+struct LinkState {
+    /// Messages written but not delivered yet;
+    pending: VecDeque<Chunk>,
+    /// Bytes delivered to be read;
+    delivered: VecDeque<u8>,
+    delay: Duration,
+    cut: bool,
+}
+
+struct Wire {
+    first: u64,
+    links: BTreeMap<(u64, u64), LinkState>,
+    seq: u64,
+    trace: Vec<Link>,
+    shutdown: bool,
+}
+
+struct Shared {
+    wire: Mutex<Wire>,
+    cond: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Wire> {
+        self.wire
+            .lock()
+            .expect("memory network lock poisoned")
+    }
+}
+
+struct LinkWriter {
+    link: (u64, u64),
+    shared: Arc<Shared>,
+}
+
+impl Write for LinkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut guard = self.shared.lock();
+        let wire = &mut *guard;
+        let state = wire
+            .links
+            .get_mut(&self.link)
+            .expect("link lost");
+        if state.cut {
+            return Err(io::Error::from(io::ErrorKind::BrokenPipe));
+        }
+        state.pending.push_back(Chunk {
+            seq: wire.seq,
+            ready: Instant::now() + state.delay,
+            bytes: buf.to_vec(),
+        });
+        wire.seq += 1;
+        self.shared.cond.notify_all();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct LinkReader {
+    link: (u64, u64),
+    shared: Arc<Shared>,
+}
+
+impl Read for LinkReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut wire = self.shared.lock();
+        let mut waited = false;
+        loop {
+            let shutdown = wire.shutdown;
+            let state = wire
+                .links
+                .get_mut(&self.link)
+                .expect("link lost");
+            if state.cut {
+                return Err(io::Error::from(io::ErrorKind::ConnectionReset));
+            }
+            if !state.delivered.is_empty() {
+                let len = buf.len().min(state.delivered.len());
+                for (i, b) in state.delivered.drain(..len).enumerate() {
+                    buf[i] = b;
+                }
+                return Ok(len);
+            }
+            if waited || shutdown {
+                return Err(io::Error::from(io::ErrorKind::WouldBlock));
+            }
+            wire = self
+                .shared
+                .cond
+                .wait_timeout(wire, READ_WAIT)
+                .expect("memory network lock poisoned")
+                .0;
+            waited = true;
+        }
+    }
+}
+
+struct Scheduler {
+    schedule: Schedule,
+    random: u64,
+    replayed: usize,
+    waiting: Option<Instant>,
+}
+
+impl Scheduler {
+    /// Pick the link whose first message is delivered next, or the time to wait before retry;
+    fn pick(&mut self, wire: &Wire, now: Instant) -> Result<(u64, u64), Duration> {
+        let mut ready = Vec::new();
+        let mut next_ready: Option<Instant> = None;
+        for (link, state) in wire.links.iter() {
+            if let Some(chunk) = state.pending.front() {
+                if state.cut {
+                    continue;
+                }
+                if chunk.ready <= now {
+                    ready.push((*link, chunk.seq));
+                } else {
+                    next_ready = Some(next_ready.map_or(chunk.ready, |t| t.min(chunk.ready)));
+                }
+            }
+        }
+        if ready.is_empty() {
+            let wait = next_ready.map_or(READ_WAIT, |t| t - now);
+            return Err(wait.min(READ_WAIT));
+        }
+
+        let fifo = |ready: &[((u64, u64), u64)]| {
+            ready
+                .iter()
+                .min_by_key(|(_, seq)| *seq)
+                .expect("no ready link")
+                .0
+        };
+        match &self.schedule {
+            Schedule::Fifo => Ok(fifo(&ready)),
+            Schedule::Seeded(_) => {
+                let index = (next_random(&mut self.random) % ready.len() as u64) as usize;
+                Ok(ready[index].0)
+            }
+            Schedule::Replay(trace) => {
+                if self.replayed >= trace.len() {
+                    return Ok(fifo(&ready));
+                }
+                let (from, to) = trace[self.replayed];
+                let expect = (wire.first + from as u64, wire.first + to as u64);
+                if ready.iter().any(|(link, _)| *link == expect) {
+                    self.replayed += 1;
+                    self.waiting = None;
+                    Ok(expect)
+                } else {
+                    let since = *self.waiting.get_or_insert(now);
+                    if now.duration_since(since) > REPLAY_WAIT {
+                        warn!(
+                            "replay diverges at the {}th message, which is expected from link {:?};",
+                            self.replayed, trace[self.replayed]
+                        );
+                        self.replayed = trace.len();
+                        Ok(fifo(&ready))
+                    } else {
+                        Err(Duration::from_millis(1))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The SplitMix64 generator, which is enough to interleave links;
+fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn run_scheduler(shared: Arc<Shared>, mut scheduler: Scheduler) {
+    let mut wire = shared.lock();
+    while !wire.shutdown {
+        match scheduler.pick(&wire, Instant::now()) {
+            Ok(link) => {
+                let state = wire.links.get_mut(&link).expect("link lost");
+                let chunk = state.pending.pop_front().expect("message lost");
+                state.delivered.extend(chunk.bytes);
+                let first = wire.first;
+                wire.trace
+                    .push(((link.0 - first) as usize, (link.1 - first) as usize));
+                shared.cond.notify_all();
+            }
+            Err(wait) => {
+                wire = shared
+                    .cond
+                    .wait_timeout(wire, wait)
+                    .expect("memory network lock poisoned")
+                    .0;
+            }
+        }
+    }
+}
+
+fn addr_of(server_id: u64) -> SocketAddr {
+    SocketAddr::new(Ipv4Addr::LOCALHOST.into(), server_id as u16)
+}
+
+/// A network of logical servers inside one process, which are connected with each other by
+/// in-memory links instead of TCP, so that the communication among servers can be tested in
+/// one process. Each server is given a unique id, which is used as the local server id to
+/// create channels like [`ipc_channel`](crate::ipc_channel).
+///
+/// All servers are shutdown when the network is dropped.
+pub struct MemoryNetwork {
+    servers: Vec<u64>,
+    shared: Arc<Shared>,
+    scheduler: Option<JoinHandle<()>>,
+}
+
+impl MemoryNetwork {
+    /// Start a network of `servers` logical servers, whose messages are delivered by `schedule`;
+    pub fn start(servers: usize, schedule: Schedule) -> Result<Self, NetError> {
+        let first = NEXT_SERVER_ID.fetch_add(servers as u64, Ordering::SeqCst);
+        let ids = (first..first + servers as u64).collect::<Vec<_>>();
+        let mut links = BTreeMap::new();
+        for a in ids.iter() {
+            for b in ids.iter().filter(|b| *b != a) {
+                links.insert((*a, *b), LinkState::default());
+            }
+        }
+        let wire = Wire { first, links, seq: 0, trace: vec![], shutdown: false };
+        let shared = Arc::new(Shared { wire: Mutex::new(wire), cond: Condvar::new() });
+
+        let random = if let Schedule::Seeded(seed) = schedule { seed } else { 0 };
+        let scheduler = Scheduler { schedule, random, replayed: 0, waiting: None };
+        let guard = {
+            let shared = shared.clone();
+            std::thread::Builder::new()
+                .name(format!("memory-net-{}", first))
+                .spawn(move || run_scheduler(shared, scheduler))
+                .expect("spawn memory network scheduler failure;")
+        };
+        let mut network = MemoryNetwork { servers: vec![], shared, scheduler: Some(guard) };
+
+        let params = ConnectionParams::blocking();
+        for id in ids.iter() {
+            crate::register_server(*id)?;
+            network.servers.push(*id);
+        }
+        for a in ids.iter() {
+            for b in ids.iter().filter(|b| *b != a) {
+                let remote = Server { id: *b, addr: addr_of(*b) };
+                let state = crate::state::add_connection(*a, *b, remote.addr)
+                    .ok_or(NetError::ConflictConnect(*b))?;
+                let poisoned = Arc::new(AtomicBool::new(false));
+                let writer = LinkWriter { link: (*a, *b), shared: network.shared.clone() };
+                start_stream_sender(*a, remote, &params, &state, &poisoned, writer);
+                let reader = LinkReader { link: (*b, *a), shared: network.shared.clone() };
+                start_stream_receiver(*a, remote, params.get_hb_interval_sec(), &params, poisoned, reader);
+            }
+        }
+        info!("memory network of servers {:?} started;", network.servers);
+        Ok(network)
+    }
+
+    /// The ids of servers in the network;
+    pub fn servers(&self) -> &[u64] {
+        &self.servers
+    }
+
+    /// Delay the messages written to the link from now on;
+    pub fn set_delay(&self, from: u64, to: u64, delay: Duration) {
+        let mut wire = self.shared.lock();
+        if let Some(state) = wire.links.get_mut(&(from, to)) {
+            state.delay = delay;
+        }
+    }
+
+    /// Cut all links from and to a server, as if it crashed; messages not delivered are lost,
+    /// and both ends of the links fail on the next read or write;
+    pub fn drop_server(&self, server: u64) {
+        info!("drop server {} from memory network;", server);
+        let mut wire = self.shared.lock();
+        for ((from, to), state) in wire.links.iter_mut() {
+            if *from == server || *to == server {
+                state.cut = true;
+                state.pending.clear();
+                state.delivered.clear();
+            }
+        }
+        self.shared.cond.notify_all();
+    }
+
+    /// The links of all messages delivered so far, in delivery order, which replays the run by
+    /// [`Schedule::Replay`];
+    pub fn trace(&self) -> Vec<Link> {
+        self.shared.lock().trace.clone()
+    }
+
+    /// Shutdown all servers and wait their network threads to exit;
+    pub fn shutdown(&mut self) {
+        for id in self.servers.iter() {
+            crate::shutdown(*id);
+        }
+        {
+            let mut wire = self.shared.lock();
+            wire.shutdown = true;
+            self.shared.cond.notify_all();
+        }
+        if let Some(guard) = self.scheduler.take() {
+            guard.join().ok();
+        }
+        for id in self.servers.iter() {
+            crate::await_termination(*id);
+        }
+    }
+}
+
+impl Drop for MemoryNetwork {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
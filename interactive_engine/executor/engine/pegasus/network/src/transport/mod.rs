@@ -18,6 +18,7 @@ use pegasus_common::io::{ReadExt, WriteExt};
 use crate::config::*;
 
 pub(crate) mod block;
+pub(crate) mod memory;
 mod nonblock;
 
 pub const PASS_PHRASE: u32 = 9;
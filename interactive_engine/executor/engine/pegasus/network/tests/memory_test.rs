@@ -0,0 +1,143 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::time::{Duration, Instant};
+
+use pegasus_network::{MemoryNetwork, Schedule};
+
+/// Each server sends `count` numbers to every other one, and returns the numbers it received,
+/// grouped by senders;
+fn exchange(network: &MemoryNetwork, channel_id: u128, count: u64) -> Vec<Vec<(u64, u64)>> {
+    let servers = network.servers().to_vec();
+    let mut guards = vec![];
+    for local in servers.iter().copied() {
+        let remotes = servers
+            .iter()
+            .copied()
+            .filter(|id| *id != local)
+            .collect::<Vec<_>>();
+        let ch = pegasus_network::ipc_channel::<(u64, u64)>(channel_id, local, &remotes).unwrap();
+        guards.push(std::thread::spawn(move || {
+            let (mut sends, recv) = ch.take();
+            for i in 0..count {
+                for send in sends.iter_mut() {
+                    send.send(&(local, i)).unwrap();
+                }
+            }
+            for send in sends.iter_mut() {
+                send.close().unwrap();
+            }
+            let mut received = vec![];
+            loop {
+                match recv.recv() {
+                    Ok(Some(item)) => received.push(item),
+                    Ok(None) => std::thread::yield_now(),
+                    Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => break,
+                    Err(e) => panic!("unexpected error {}", e),
+                }
+            }
+            received
+        }));
+    }
+    guards
+        .into_iter()
+        .map(|g| g.join().unwrap())
+        .collect()
+}
+
+fn check_exchange(network: &MemoryNetwork, received: Vec<Vec<(u64, u64)>>, count: u64) {
+    for (local, received) in network.servers().iter().zip(received) {
+        for remote in network
+            .servers()
+            .iter()
+            .filter(|id| *id != local)
+        {
+            // messages from one server are received in the order they are sent;
+            let from = received
+                .iter()
+                .filter(|(id, _)| id == remote)
+                .map(|(_, i)| *i)
+                .collect::<Vec<_>>();
+            assert_eq!(from, (0..count).collect::<Vec<_>>());
+        }
+    }
+}
+
+#[test]
+fn memory_network_fifo_test() {
+    pegasus_common::logs::init_log();
+    let network = MemoryNetwork::start(3, Schedule::Fifo).unwrap();
+    let received = exchange(&network, 1, 100);
+    check_exchange(&network, received, 100);
+}
+
+#[test]
+fn memory_network_seeded_test() {
+    pegasus_common::logs::init_log();
+    for seed in 0..4 {
+        let network = MemoryNetwork::start(3, Schedule::Seeded(seed)).unwrap();
+        let received = exchange(&network, 1, 100);
+        check_exchange(&network, received, 100);
+        assert!(!network.trace().is_empty());
+    }
+}
+
+#[test]
+fn memory_network_replay_test() {
+    pegasus_common::logs::init_log();
+    let trace = {
+        let network = MemoryNetwork::start(2, Schedule::Seeded(42)).unwrap();
+        let received = exchange(&network, 1, 10);
+        check_exchange(&network, received, 10);
+        network.trace()
+    };
+    let network = MemoryNetwork::start(2, Schedule::Replay(trace.clone())).unwrap();
+    let received = exchange(&network, 1, 10);
+    check_exchange(&network, received, 10);
+    assert_eq!(network.trace(), trace);
+}
+
+#[test]
+fn memory_network_delay_test() {
+    pegasus_common::logs::init_log();
+    let network = MemoryNetwork::start(2, Schedule::Fifo).unwrap();
+    let servers = network.servers().to_vec();
+    network.set_delay(servers[0], servers[1], Duration::from_millis(200));
+    let start = Instant::now();
+    let received = exchange(&network, 1, 10);
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    check_exchange(&network, received, 10);
+}
+
+#[test]
+fn memory_network_drop_server_test() {
+    pegasus_common::logs::init_log();
+    let network = MemoryNetwork::start(2, Schedule::Fifo).unwrap();
+    let servers = network.servers().to_vec();
+    let ch = pegasus_network::ipc_channel::<u64>(1, servers[0], &servers[1..]).unwrap();
+    let (_sends, recv) = ch.take();
+    network.drop_server(servers[1]);
+    let start = Instant::now();
+    loop {
+        match recv.recv() {
+            Ok(Some(_)) => panic!("unexpected message from dropped server"),
+            Ok(None) => {
+                assert!(start.elapsed() < Duration::from_secs(5), "not aware of the dropped server");
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Err(_) => break,
+        }
+    }
+}
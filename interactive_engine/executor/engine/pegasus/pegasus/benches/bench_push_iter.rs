@@ -0,0 +1,75 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+#![feature(test)]
+extern crate test;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use pegasus::api::{Count, Sink};
+use pegasus::harness::{LocalCluster, Schedule};
+use pegasus::JobConf;
+
+const RECORDS: u64 = 1 << 16;
+
+static JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+// jobs of the same name share an id, each iteration needs its own job id;
+fn exchange_conf(name: &str) -> JobConf {
+    JobConf::with_id(JOB_ID.fetch_add(1, Ordering::Relaxed), name, 2)
+}
+
+#[bench]
+fn push_iter_local_exchange(b: &mut test::Bencher) {
+    b.iter(|| {
+        let conf = exchange_conf("push_iter_local_exchange");
+        let result = pegasus::run(conf, || {
+            |input, output| {
+                input
+                    .input_from(0..RECORDS)?
+                    .repartition(|x| Ok(*x))
+                    .count()?
+                    .sink_into(output)
+            }
+        })
+        .expect("submit job failure");
+        let count: u64 = result.map(|item| item.unwrap()).sum();
+        assert_eq!(count, RECORDS * 2);
+    })
+}
+
+#[bench]
+fn push_iter_remote_exchange(b: &mut test::Bencher) {
+    let cluster = LocalCluster::start(2, Schedule::Fifo).unwrap();
+    b.iter(|| {
+        let conf = exchange_conf("push_iter_remote_exchange");
+        let results = cluster
+            .run(conf, || {
+                |input, output| {
+                    input
+                        .input_from(0..RECORDS)?
+                        .repartition(|x| Ok(*x))
+                        .count()?
+                        .sink_into(output)
+                }
+            })
+            .expect("submit job failure");
+        let count: u64 = results
+            .into_iter()
+            .flat_map(|stream| stream.map(|item| item.unwrap()))
+            .sum();
+        assert_eq!(count, RECORDS * 4);
+    })
+}
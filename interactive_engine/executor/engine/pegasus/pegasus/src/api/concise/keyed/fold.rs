@@ -17,7 +17,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 
 use crate::api::function::FnResult;
-use crate::api::Key;
+use crate::api::{Key, SkewConf};
 use crate::stream::SingleItem;
 use crate::{BuildJobError, Data};
 
@@ -38,4 +38,20 @@ pub trait FoldByKey<K: Data + Key, V: Data> {
         I: Clone + Send + Sync + Debug + 'static,
         F: FnMut(I, V) -> FnResult<I> + Send + 'static,
         B: Fn() -> F + Send + 'static;
+
+    /// Analogous to [`fold_by_key()`], but the data is exchanged by [`partition_by_key_adaptive()`],
+    /// so that a hot key may be folded on several workers, whose results of it are combined by
+    /// `merge`. Each worker folds the data it receives before any partial result is sent, which
+    /// avoids overloading the worker a hot key is hashed to.
+    ///
+    /// [`fold_by_key()`]: crate::api::FoldByKey::fold_by_key()
+    /// [`partition_by_key_adaptive()`]: crate::api::PartitionByKey::partition_by_key_adaptive()
+    fn fold_by_key_adaptive<I, B, F, M>(
+        self, init: I, conf: SkewConf, builder: B, merge: M,
+    ) -> Result<SingleItem<HashMap<K, I>>, BuildJobError>
+    where
+        I: Data,
+        F: FnMut(I, V) -> FnResult<I> + Send + 'static,
+        B: Fn() -> F + Send + 'static,
+        M: Fn(I, I) -> FnResult<I> + Send + Sync + 'static;
 }
@@ -0,0 +1,51 @@
+use std::cmp::Ordering;
+
+use crate::api::HasKey;
+use crate::stream::Stream;
+use crate::{BuildJobError, Data};
+
+/// Similar to [`SortLimitBy`], but produces the minimum data of each key instead of the whole stream.
+///
+/// [`SortLimitBy`]: crate::api::SortLimitBy
+pub trait SortLimitByKey<D: Data + HasKey> {
+    /// Produce up to `size` minimum data for each key according to `cmp`, the data of a key are
+    /// produced in order. Each partition keeps the top-`size` data of each key before the data are
+    /// repartitioned by key, where the top-`size` data of each key are finally selected.
+    ///
+    /// # Example
+    /// ```
+    /// #     use pegasus::JobConf;
+    /// #     use pegasus::api::{Sink, SortLimitByKey, KeyBy, Map, Collect};
+    /// #     let conf = JobConf::new("sort_limit_by_key_example");
+    ///       let mut results = pegasus::run(conf, || {
+    ///         move |input, output| {
+    ///                 input.input_from(0..10_u32)?
+    ///                      .key_by(|x| Ok((x % 2, x)))?
+    ///                      .sort_limit_by_key(2, |a, b| a.value.cmp(&b.value))?
+    ///                      .map(|pair| Ok(pair.value))?
+    ///                      .collect::<Vec<u32>>()?
+    ///                      .sink_into(output)
+    ///             }
+    ///         })
+    ///         .expect("run job failure;");
+    ///
+    ///     let mut expected = results.next().unwrap().unwrap();
+    ///     expected.sort();
+    ///     assert_eq!(expected, [0, 1, 2, 3]);
+    /// ```
+    fn sort_limit_by_key<F>(self, size: u32, cmp: F) -> Result<Stream<D>, BuildJobError>
+    where
+        F: Fn(&D, &D) -> Ordering + Send + 'static;
+}
+
+/// Sample the data of each key.
+pub trait SampleByKey<D: Data + HasKey> {
+    /// Produce up to `size` data for each key, which are the data of the largest priorities among the
+    /// data of the key. `priority` is invoked exactly once for each data, after the data are
+    /// repartitioned by key. A uniformly random priority in (0, 1] results in a uniform sample of each
+    /// key as reservoir sampling does, and `u^(1/w)` of such a random `u` and a positive weight `w` of
+    /// the data results in a weighted sample (A-Res).
+    fn sample_by_key<F>(self, size: u32, priority: F) -> Result<Stream<D>, BuildJobError>
+    where
+        F: Fn(&D) -> f64 + Send + 'static;
+}
@@ -21,7 +21,9 @@ pub use apply::*;
 pub use dedup::*;
 pub use fold::*;
 pub use join::*;
+pub use limit::*;
 pub use reduce::*;
+pub use skew::*;
 
 use crate::api::function::FnResult;
 use crate::codec::{Decode, Encode, ReadExt, WriteExt};
@@ -141,10 +143,25 @@ pub trait KeyBy<D: Data> {
 
 pub trait PartitionByKey<D: Data + HasKey> {
     fn partition_by_key(self) -> Stream<D>;
+
+    /// Analogous to [`partition_by_key()`], but the keys taking too many records are found at
+    /// runtime, and their records are spread over several workers instead of the one the key
+    /// is hashed to, to balance the load of workers. As a result the data of a hot key may arrive
+    /// at several workers, so keyed operators following it must combine their partial results
+    /// of a key, like [`fold_by_key_adaptive()`] and [`reduce_by_key_adaptive()`] do.
+    ///
+    /// The records sent to each worker and the skew among them are logged when the job ends.
+    ///
+    /// [`partition_by_key()`]: crate::api::PartitionByKey::partition_by_key()
+    /// [`fold_by_key_adaptive()`]: crate::api::FoldByKey::fold_by_key_adaptive()
+    /// [`reduce_by_key_adaptive()`]: crate::api::ReduceByKey::reduce_by_key_adaptive()
+    fn partition_by_key_adaptive(self, conf: SkewConf) -> Stream<D>;
 }
 
 mod apply;
 mod dedup;
 mod fold;
 mod join;
+mod limit;
 mod reduce;
+mod skew;
@@ -16,7 +16,7 @@
 use std::collections::HashMap;
 
 use crate::api::function::FnResult;
-use crate::api::Key;
+use crate::api::{Key, SkewConf};
 use crate::stream::SingleItem;
 use crate::{BuildJobError, Data};
 
@@ -28,4 +28,16 @@ pub trait ReduceByKey<K: Key + Data, V: Data> {
     where
         F: FnMut(V, V) -> FnResult<V> + Send + 'static,
         B: Fn() -> F + Send + 'static;
+
+    /// Analogous to [`reduce_by_key()`], but the data is exchanged by [`partition_by_key_adaptive()`],
+    /// so that a hot key may be reduced on several workers, whose results of it are reduced again.
+    ///
+    /// [`reduce_by_key()`]: crate::api::ReduceByKey::reduce_by_key()
+    /// [`partition_by_key_adaptive()`]: crate::api::PartitionByKey::partition_by_key_adaptive()
+    fn reduce_by_key_adaptive<B, F>(
+        self, conf: SkewConf, builder: B,
+    ) -> Result<SingleItem<HashMap<K, V>>, BuildJobError>
+    where
+        F: FnMut(V, V) -> FnResult<V> + Send + 'static,
+        B: Fn() -> F + Send + Sync + 'static;
 }
@@ -0,0 +1,38 @@
+//
+//! Copyright 2022 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+/// The configuration of adaptive key exchange, see
+/// [`partition_by_key_adaptive()`](crate::api::PartitionByKey::partition_by_key_adaptive()).
+#[derive(Clone, Copy, Debug)]
+pub struct SkewConf {
+    /// A key is hot if it takes more than this fraction of the records a worker has sent;
+    pub threshold: f64,
+    /// The number of records a worker sends before any key can be hot;
+    pub warmup: u64,
+    /// The number of workers the records of a hot key are spread over, at most all workers;
+    pub split: u32,
+}
+
+impl SkewConf {
+    pub fn new(threshold: f64, warmup: u64, split: u32) -> Self {
+        SkewConf { threshold, warmup, split }
+    }
+}
+
+impl Default for SkewConf {
+    fn default() -> Self {
+        SkewConf { threshold: 0.01, warmup: 1024, split: u32::MAX }
+    }
+}
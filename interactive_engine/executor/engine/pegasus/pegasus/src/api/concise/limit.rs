@@ -54,6 +54,12 @@ pub trait Limit<D: Data> {
 
     /// Apply `limit()` per each partition.
     fn limit_partition(self, size: u32) -> Result<Stream<D>, BuildJobError>;
+
+    /// Skip the first `skip` data and produce up to `size` data after them, i.e. the data in
+    /// the range `[skip, skip + size)` of the whole stream. Each partition keeps up to
+    /// `skip + size` data, which are aggregated into one partition before skipping, so that the
+    /// offset is counted over the data of all partitions instead of per partition.
+    fn skip_limit(self, skip: u32, size: u32) -> Result<Stream<D>, BuildJobError>;
 }
 
 /// Similar to `Limit`, but requires the output items are the minimum ones according to the
@@ -78,4 +84,11 @@ pub trait SortLimitBy<D: Data> {
     fn sort_limit_by<F>(self, size: u32, cmp: F) -> Result<Stream<D>, BuildJobError>
     where
         F: Fn(&D, &D) -> Ordering + Send + 'static;
+
+    /// Similar to `sort_limit_by()`, but skips the first `skip` minimum data and produces up to
+    /// `size` data after them in order. The top-`(skip + size)` data of every worker are merged
+    /// into the global top-`(skip + size)` before skipping.
+    fn sort_skip_limit_by<F>(self, skip: u32, size: u32, cmp: F) -> Result<Stream<D>, BuildJobError>
+    where
+        F: Fn(&D, &D) -> Ordering + Send + 'static;
 }
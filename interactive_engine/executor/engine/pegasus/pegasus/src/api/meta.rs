@@ -34,6 +34,8 @@ pub struct OperatorInfo {
     pub name: String,
     pub index: usize,
     pub scope_level: u32,
+    /// the id of the operator in the upstream plan which this operator is compiled from, if any;
+    pub plan_id: Option<usize>,
 }
 
 impl std::fmt::Debug for OperatorInfo {
@@ -44,6 +46,6 @@ impl std::fmt::Debug for OperatorInfo {
 
 impl OperatorInfo {
     pub fn new(name: &str, index: usize, scope_level: u32) -> Self {
-        OperatorInfo { name: name.to_owned(), index, scope_level }
+        OperatorInfo { name: name.to_owned(), index, scope_level, plan_id: None }
     }
 }
@@ -18,8 +18,9 @@ pub use iteration::{EmitKind, IterCondition, Iteration};
 pub use primitive::binary::Binary;
 pub use primitive::branch::Branch;
 pub use primitive::sink::{FromStream, Sink};
-pub use primitive::source::{IntoDataflow, Source};
+pub use primitive::source::{stream_channel, IntoDataflow, Source, StreamReceiver, StreamSender};
 pub use primitive::unary::Unary;
+pub use superstep::{Superstep, SuperstepConf, SuperstepContext};
 
 pub mod notification {
 
@@ -70,3 +71,4 @@ pub(crate) mod iteration;
 pub mod meta;
 pub(crate) mod primitive;
 pub(crate) mod scope;
+pub(crate) mod superstep;
@@ -13,6 +13,11 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crossbeam_channel::{Receiver, Sender};
+
 use crate::communication::output::OutputBuilderImpl;
 use crate::dataflow::DataflowBuilder;
 use crate::errors::BuildJobError;
@@ -44,7 +49,148 @@ impl<D: Data> Source<D> {
         source.into_dataflow(stream)
     }
 
+    /// Read an unbounded input from a [`StreamReceiver`] until every [`StreamSender`] of it is
+    /// dropped. Data sent in epoch `e` flows in the child scope `[e]` of the returned stream, which
+    /// is ended once `e` is closed by [`StreamSender::advance`], so aggregations like `count` emit
+    /// one result per epoch. Call `leave` to merge the epochs back into one stream.
+    ///
+    /// Every worker may read the data sent in any epoch, and clones of the receiver can be
+    /// handed to all workers of the job.
+    ///
+    /// # Examples
+    /// ```
+    /// use pegasus::api::{stream_channel, Count, Sink};
+    /// use pegasus::JobConf;
+    ///
+    /// let (tx, rx) = stream_channel::<u32>();
+    /// let mut conf = JobConf::new("input_from_stream_example");
+    /// conf.set_workers(2);
+    /// let mut results = pegasus::run(conf, move || {
+    ///     let rx = rx.clone();
+    ///     move |input, output| input.input_from_stream(rx.clone())?.count()?.into_stream()?.leave()?.sink_into(output)
+    /// })
+    /// .unwrap();
+    ///
+    /// for i in 0..3 {
+    ///     tx.send(i).unwrap();
+    /// }
+    /// tx.advance();
+    /// assert_eq!(results.next().unwrap().unwrap(), 3);
+    /// tx.send(3).unwrap();
+    /// drop(tx);
+    /// assert_eq!(results.next().unwrap().unwrap(), 1);
+    /// assert!(results.next().is_none());
+    /// ```
+    pub fn input_from_stream(&mut self, receiver: StreamReceiver<D>) -> Result<Stream<D>, BuildJobError> {
+        let output = self.output.copy_data();
+        let output = std::mem::replace(&mut self.output, output);
+        let stream = Stream::new(output, &self.dfb);
+        receiver.into_dataflow(stream)
+    }
+
     pub fn get_worker_index(&self) -> u32 {
         self.dfb.worker_id.index
     }
 }
+
+pub(crate) struct StreamProgress {
+    /// The number of closed epochs, which is also the index of the open one;
+    closed: AtomicU32,
+    /// If any data has been sent in the open epoch;
+    dirty: AtomicBool,
+    lock: Mutex<()>,
+}
+
+impl StreamProgress {
+    /// The number of closed epochs;
+    pub(crate) fn closed(&self) -> u32 {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    /// The number of epochs that exist once all senders are dropped, i.e. the closed ones and the
+    /// open one if it has data;
+    pub(crate) fn total(&self) -> u32 {
+        let closed = self.closed();
+        if self.dirty.load(Ordering::SeqCst) {
+            closed + 1
+        } else {
+            closed
+        }
+    }
+}
+
+/// Create a channel of unbounded input, whose receiver is read by [`Source::input_from_stream`].
+/// Data is sent in epochs, starting from epoch 0.
+pub fn stream_channel<D: Data>() -> (StreamSender<D>, StreamReceiver<D>) {
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let progress = Arc::new(StreamProgress {
+        closed: AtomicU32::new(0),
+        dirty: AtomicBool::new(false),
+        lock: Mutex::new(()),
+    });
+    (StreamSender { tx, progress: progress.clone() }, StreamReceiver { rx, progress })
+}
+
+/// The sending half of a [`stream_channel`]. The input ends when all clones of it are dropped,
+/// when the open epoch is closed if any data was sent in it.
+pub struct StreamSender<D> {
+    tx: Sender<(u32, D)>,
+    progress: Arc<StreamProgress>,
+}
+
+impl<D> StreamSender<D> {
+    /// Send data in the open epoch, or give it back if all receivers are dropped;
+    pub fn send(&self, data: D) -> Result<(), D> {
+        let _guard = self
+            .progress
+            .lock
+            .lock()
+            .expect("lock poisoned");
+        let epoch = self.progress.closed();
+        self.tx
+            .send((epoch, data))
+            .map_err(|e| (e.0).1)?;
+        self.progress
+            .dirty
+            .store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Close the open epoch and return its index, so that no more data is sent in it;
+    pub fn advance(&self) -> u32 {
+        let _guard = self
+            .progress
+            .lock
+            .lock()
+            .expect("lock poisoned");
+        self.progress
+            .dirty
+            .store(false, Ordering::SeqCst);
+        self.progress
+            .closed
+            .fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// The index of the open epoch;
+    pub fn epoch(&self) -> u32 {
+        self.progress.closed()
+    }
+}
+
+impl<D> Clone for StreamSender<D> {
+    fn clone(&self) -> Self {
+        StreamSender { tx: self.tx.clone(), progress: self.progress.clone() }
+    }
+}
+
+/// The receiving half of a [`stream_channel`], read by [`Source::input_from_stream`];
+pub struct StreamReceiver<D> {
+    pub(crate) rx: Receiver<(u32, D)>,
+    pub(crate) progress: Arc<StreamProgress>,
+}
+
+impl<D> Clone for StreamReceiver<D> {
+    fn clone(&self) -> Self {
+        StreamReceiver { rx: self.rx.clone(), progress: self.progress.clone() }
+    }
+}
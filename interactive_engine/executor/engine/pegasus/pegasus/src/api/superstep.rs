@@ -0,0 +1,194 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use crate::api::function::FnResult;
+use crate::api::{Key, Pair};
+use crate::errors::BuildJobError;
+use crate::stream::Stream;
+use crate::Data;
+
+pub(crate) type MergeFn<A> = Box<dyn Fn(A, A) -> A + Send>;
+pub(crate) type HaltFn<A> = Box<dyn Fn(&A) -> bool + Send>;
+
+/// The configuration of a superstep (BSP-style) iteration, see [`Superstep`].
+pub struct SuperstepConf<M, A> {
+    /// The maximum number of supersteps to run;
+    pub max_supersteps: u32,
+    pub(crate) combiner: Option<MergeFn<M>>,
+    pub(crate) aggregator: Option<(A, MergeFn<A>)>,
+    pub(crate) halt: Option<HaltFn<A>>,
+}
+
+impl<M, A> SuperstepConf<M, A> {
+    pub fn new(max_supersteps: u32) -> Self {
+        SuperstepConf { max_supersteps, combiner: None, aggregator: None, halt: None }
+    }
+
+    /// Combine messages sent to the same vertex into one, both before they are sent and
+    /// when they are received. The combiner must be commutative and associative.
+    pub fn combine_with<F>(mut self, combiner: F) -> Self
+    where
+        F: Fn(M, M) -> M + Send + 'static,
+    {
+        self.combiner = Some(Box::new(combiner));
+        self
+    }
+
+    /// Enable the global aggregator. Values given by
+    /// [`SuperstepContext::aggregate`] in one superstep are merged across all workers,
+    /// starting from `init`, and become visible to every vertex in the next superstep.
+    /// The merge function must be commutative and associative.
+    pub fn aggregate_with<F>(mut self, init: A, merge: F) -> Self
+    where
+        F: Fn(A, A) -> A + Send + 'static,
+    {
+        self.aggregator = Some((init, Box::new(merge)));
+        self
+    }
+
+    /// Stop the iteration before a superstep once the global aggregate of the
+    /// previous superstep satisfies `halt`.
+    pub fn halt_when<F>(mut self, halt: F) -> Self
+    where
+        F: Fn(&A) -> bool + Send + 'static,
+    {
+        self.halt = Some(Box::new(halt));
+        self
+    }
+}
+
+/// The view of a single vertex in a superstep, given to the compute function of [`Superstep`].
+pub struct SuperstepContext<'a, K, V, M, A> {
+    pub(crate) superstep: u32,
+    pub(crate) key: &'a K,
+    pub(crate) value: &'a mut V,
+    pub(crate) messages: Vec<M>,
+    pub(crate) aggregated: Option<&'a A>,
+    pub(crate) outbox: &'a mut Vec<(K, M)>,
+    pub(crate) partial: &'a mut Option<A>,
+    pub(crate) merge: Option<&'a (dyn Fn(A, A) -> A + Send)>,
+    pub(crate) halted: bool,
+}
+
+impl<'a, K, V, M, A> SuperstepContext<'a, K, V, M, A> {
+    /// The current superstep, starting from 0;
+    pub fn superstep(&self) -> u32 {
+        self.superstep
+    }
+
+    pub fn key(&self) -> &K {
+        self.key
+    }
+
+    pub fn value(&self) -> &V {
+        self.value
+    }
+
+    pub fn value_mut(&mut self) -> &mut V {
+        self.value
+    }
+
+    /// The messages sent to this vertex in the previous superstep, combined if a combiner is set;
+    pub fn messages(&self) -> &[M] {
+        &self.messages
+    }
+
+    pub fn take_messages(&mut self) -> Vec<M> {
+        std::mem::take(&mut self.messages)
+    }
+
+    /// The global aggregate of the previous superstep, `None` in the first superstep or
+    /// if no aggregator is configured;
+    pub fn aggregated(&self) -> Option<&A> {
+        self.aggregated
+    }
+
+    /// Send a message to the vertex `to`, which receives it in the next superstep.
+    /// Messages sent to vertices that don't exist are dropped.
+    pub fn send_to(&mut self, to: K, msg: M) {
+        self.outbox.push((to, msg));
+    }
+
+    /// Contribute a value to the global aggregator of this superstep. It has no effect
+    /// if no aggregator is configured.
+    pub fn aggregate(&mut self, value: A) {
+        if let Some(merge) = self.merge {
+            let merged = match self.partial.take() {
+                Some(prev) => merge(prev, value),
+                None => value,
+            };
+            *self.partial = Some(merged);
+        }
+    }
+
+    /// Deactivate this vertex. It is not computed again until it receives a message.
+    pub fn vote_to_halt(&mut self) {
+        self.halted = true;
+    }
+}
+
+/// Vertex-centric iteration in bulk synchronous parallel (BSP) supersteps.
+pub trait Superstep<K: Data + Key, V: Data> {
+    /// Run `compute` on every active vertex (a pair of vertex id and value) once per superstep.
+    /// Messages sent in one superstep are delivered in the next one, and the values passed to
+    /// [`SuperstepContext::aggregate`] are merged into one aggregate shared by all workers and
+    /// servers.
+    ///
+    /// A vertex becomes inactive when it votes to halt, and active again when it receives a
+    /// message. The iteration stops when every vertex is inactive and no message is in flight,
+    /// when the `halt_when` predicate holds for the aggregate of the previous superstep, or
+    /// after `max_supersteps`. The vertices are then output with their final values.
+    ///
+    /// # Example
+    /// ```
+    ///   # use pegasus::{JobConf};
+    ///   # use pegasus::api::{Sink, Collect, Map, Pair, Superstep, SuperstepConf};
+    ///
+    ///   # let conf = JobConf::new("superstep_example");
+    ///     let mut results = pegasus::run(conf, || {
+    ///         |input, output| {
+    ///             // each vertex i links to i + 1, propagate the max value along the path;
+    ///             let conf = SuperstepConf::<u32, ()>::new(10).combine_with(|a, b| a.max(b));
+    ///             input
+    ///                 .input_from(vec![(0u32, 3u32), (1, 1), (2, 2)])?
+    ///                 .map(|(key, value)| Ok(Pair { key, value }))?
+    ///                 .superstep(conf, |ctx| {
+    ///                     let max = ctx.take_messages().into_iter().fold(0, |a, b| a.max(b));
+    ///                     if ctx.superstep() == 0 || max > *ctx.value() {
+    ///                         *ctx.value_mut() = max.max(*ctx.value());
+    ///                         let (next, value) = (*ctx.key() + 1, *ctx.value());
+    ///                         ctx.send_to(next, value);
+    ///                     }
+    ///                     ctx.vote_to_halt();
+    ///                     Ok(())
+    ///                 })?
+    ///                 .map(|pair| Ok(pair.value))?
+    ///                 .collect::<Vec<u32>>()?
+    ///                 .sink_into(output)
+    ///         }
+    ///     })
+    ///     .expect("build job failure");
+    ///
+    ///     let expected = results.next().unwrap().unwrap();
+    ///     assert_eq!(expected, [3, 3, 3]);
+    /// ```
+    fn superstep<M, A, F>(
+        self, conf: SuperstepConf<M, A>, compute: F,
+    ) -> Result<Stream<Pair<K, V>>, BuildJobError>
+    where
+        M: Data,
+        A: Data,
+        F: FnMut(&mut SuperstepContext<K, V, M, A>) -> FnResult<()> + Send + 'static;
+}
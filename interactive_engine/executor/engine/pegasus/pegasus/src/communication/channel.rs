@@ -27,7 +27,8 @@ use crate::communication::output::{OutputBuilderImpl, PerChannelPush};
 use crate::data::MicroBatch;
 use crate::data_plane::{GeneralPull, GeneralPush};
 use crate::dataflow::DataflowBuilder;
-use crate::graph::Port;
+use crate::graph::{Exchange, Port};
+use crate::metrics::ChannelMetrics;
 use crate::BuildJobError;
 use crate::Data;
 
@@ -60,6 +61,16 @@ impl<T: Data> ChannelKind<T> {
             _ => false,
         }
     }
+
+    pub fn exchange(&self) -> Exchange {
+        match self {
+            ChannelKind::Pipeline => Exchange::Pipeline,
+            ChannelKind::Shuffle(_) => Exchange::Shuffle,
+            ChannelKind::BatchShuffle(_) => Exchange::BatchShuffle,
+            ChannelKind::Broadcast => Exchange::Broadcast,
+            ChannelKind::Aggregate => Exchange::Aggregate,
+        }
+    }
 }
 
 pub struct Channel<T: Data> {
@@ -103,9 +114,14 @@ pub(crate) struct MaterializedChannel<T: Data> {
     push: PerChannelPush<T>,
     pull: GeneralPull<MicroBatch<T>>,
     notify: Option<GeneralPush<MicroBatch<T>>>,
+    exchange: Exchange,
 }
 
 impl<T: Data> MaterializedChannel<T> {
+    pub fn exchange(&self) -> Exchange {
+        self.exchange
+    }
+
     pub fn take(
         self,
     ) -> (PerChannelPush<T>, GeneralPull<MicroBatch<T>>, Option<GeneralPush<MicroBatch<T>>>) {
@@ -169,7 +185,7 @@ impl<T: Data> Channel<T> {
         let push = MicroBatchPush::Pipeline(LocalMicroBatchPush::new(ch_info, tx, worker_id));
         let ch = CancelHandle::SC(SingleConsCancel::new(worker_id));
         let push = PerChannelPush::new(ch_info, self.scope_delta, push, ch, worker_id);
-        MaterializedChannel { push, pull: rx.into(), notify: None }
+        MaterializedChannel { push, pull: rx.into(), notify: None, exchange: Exchange::Pipeline }
     }
 
     fn build_remote(
@@ -184,7 +200,13 @@ impl<T: Data> Channel<T> {
         let notify = raw.swap_remove(worker_index);
         let ch_info = ChannelInfo::new(id, scope_level, raw.len(), raw.len(), self.source, target);
         let mut pushes = Vec::with_capacity(raw.len());
-        for (idx, p) in raw.into_iter().enumerate() {
+        let source_name =
+            if dfb.config.metrics_enable { dfb.operator_name(self.source.index) } else { None };
+        for (idx, mut p) in raw.into_iter().enumerate() {
+            if let Some(name) = source_name.as_ref() {
+                let metrics = ChannelMetrics::new(&dfb.config, dfb.worker_id, &ch_info, name, idx as u32);
+                p.set_metrics(metrics);
+            }
             let push = EventEmitPush::new(ch_info, dfb.worker_id, idx as u32, p, dfb.event_emitter.clone());
             pushes.push(push);
         }
@@ -215,6 +237,7 @@ impl<T: Data> Channel<T> {
         }
 
         let kind = std::mem::replace(&mut self.kind, ChannelKind::Pipeline);
+        let exchange = kind.exchange();
         match kind {
             ChannelKind::Pipeline => Ok(self.build_pipeline(target, id, dfb.worker_id.index)),
             ChannelKind::Shuffle(r) => {
@@ -233,7 +256,7 @@ impl<T: Data> Channel<T> {
                     ch,
                     dfb.worker_id.index,
                 );
-                Ok(MaterializedChannel { push, pull: pull.into(), notify: Some(notify) })
+                Ok(MaterializedChannel { push, pull: pull.into(), notify: Some(notify), exchange })
             }
             ChannelKind::BatchShuffle(route) => {
                 let (info, pushes, pull, notify) = self.build_remote(scope_level, target, id, dfb)?;
@@ -246,7 +269,7 @@ impl<T: Data> Channel<T> {
                     cancel,
                     dfb.worker_id.index,
                 );
-                Ok(MaterializedChannel { push, pull: pull.into(), notify: Some(notify) })
+                Ok(MaterializedChannel { push, pull: pull.into(), notify: Some(notify), exchange })
             }
             ChannelKind::Broadcast => {
                 let (info, pushes, pull, notify) = self.build_remote(scope_level, target, id, dfb)?;
@@ -259,7 +282,7 @@ impl<T: Data> Channel<T> {
                     ch,
                     dfb.worker_id.index,
                 );
-                Ok(MaterializedChannel { push, pull: pull.into(), notify: Some(notify) })
+                Ok(MaterializedChannel { push, pull: pull.into(), notify: Some(notify), exchange })
             }
             ChannelKind::Aggregate => {
                 let (mut ch_info, pushes, pull, notify) =
@@ -274,7 +297,7 @@ impl<T: Data> Channel<T> {
                     cancel,
                     dfb.worker_id.index,
                 );
-                Ok(MaterializedChannel { push, pull: pull.into(), notify: Some(notify) })
+                Ok(MaterializedChannel { push, pull: pull.into(), notify: Some(notify), exchange })
             }
         }
     }
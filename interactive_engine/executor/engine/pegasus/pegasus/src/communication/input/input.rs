@@ -56,6 +56,8 @@ pub struct InputHandle<D: Data> {
     cancel: TidyTagMap<()>,
     parent_cancel: AHashSet<Tag>,
     worker_id: WorkerId,
+    // the total records and batches pulled;
+    pulled: (u64, u64),
 }
 
 impl<D: Data> InputHandle<D> {
@@ -75,6 +77,7 @@ impl<D: Data> InputHandle<D> {
             cancel: TidyTagMap::new(scope_level),
             parent_cancel: AHashSet::new(),
             worker_id,
+            pulled: (0, 0),
         }
     }
 
@@ -312,6 +315,10 @@ impl<D: Data> InputHandle<D> {
                                     );
                                 }
                             }
+                            if !batch.is_empty() {
+                                self.pulled.0 += batch.len() as u64;
+                                self.pulled.1 += 1;
+                            }
                             return Ok(Some(batch));
                         }
                     }
@@ -496,6 +503,19 @@ impl<D: Data> InputProxy for RefWrapInput<D> {
     fn cancel_scope(&self, tag: &Tag) -> IOResult<()> {
         self.inbound.borrow_mut().cancel_scope(tag)
     }
+
+    fn pulled(&self) -> (u64, u64) {
+        self.inbound.borrow().pulled
+    }
+
+    fn queued(&self) -> usize {
+        self.inbound
+            .borrow()
+            .stash_index
+            .iter()
+            .map(|(_, stash)| stash.queue.len())
+            .sum()
+    }
 }
 
 struct StashedQueue<D> {
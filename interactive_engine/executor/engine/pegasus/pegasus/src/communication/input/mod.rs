@@ -34,6 +34,12 @@ pub trait InputProxy: AsAny + Send {
     fn is_exhaust(&self) -> bool;
 
     fn cancel_scope(&self, tag: &Tag) -> IOResult<()>;
+
+    /// The total records and batches pulled from this input so far;
+    fn pulled(&self) -> (u64, u64);
+
+    /// The number of batches stashed in this input, waiting to be consumed;
+    fn queued(&self) -> usize;
 }
 
 mod input;
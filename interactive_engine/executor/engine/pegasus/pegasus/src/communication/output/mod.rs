@@ -82,6 +82,9 @@ pub trait OutputProxy: AsAny + Send {
 
     /// Check if this output has been closed;
     fn is_closed(&self) -> bool;
+
+    /// The total records and batches pushed to this output so far;
+    fn pushed(&self) -> (u64, u64);
 }
 
 pub trait OutputBuilder: AsAny {
@@ -176,6 +179,11 @@ impl<D: Data> OutputProxy for RefWrapOutput<D> {
     fn is_closed(&self) -> bool {
         self.output.borrow().is_closed()
     }
+
+    #[inline]
+    fn pushed(&self) -> (u64, u64) {
+        self.output.borrow().pushed()
+    }
 }
 
 #[inline(always)]
@@ -44,12 +44,15 @@ pub struct OutputHandle<D: Data> {
     pub src: u32,
     tee: Tee<D>,
     buf_pool: ScopeBufferPool<D>,
+    batch_capacity: usize,
     block_entries: TidyTagMap<BlockEntry<D>>,
     blocks: VecDeque<BlockScope>,
     seq_emit: TidyTagMap<u64>,
     is_closed: bool,
     current_skips: TidyTagMap<()>,
     parent_skips: TidyTagMap<()>,
+    // the total records and batches pushed;
+    pushed: (u64, u64),
 }
 
 impl<D: Data> OutputHandle<D> {
@@ -70,12 +73,14 @@ impl<D: Data> OutputHandle<D> {
             src,
             tee: output,
             buf_pool,
+            batch_capacity,
             block_entries: TidyTagMap::new(scope_level),
             blocks: VecDeque::new(),
             seq_emit: TidyTagMap::new(scope_level),
             is_closed: false,
             current_skips: TidyTagMap::new(scope_level),
             parent_skips: TidyTagMap::new(parent_level),
+            pushed: (0, 0),
         }
     }
 
@@ -142,6 +147,10 @@ impl<D: Data> OutputHandle<D> {
         self.is_closed
     }
 
+    pub(crate) fn pushed(&self) -> (u64, u64) {
+        self.pushed
+    }
+
     pub(crate) fn pin(&mut self, tag: &Tag) {
         self.buf_pool.pin(tag);
     }
@@ -357,6 +366,8 @@ impl<D: Data> OutputHandle<D> {
 
         let tag = batch.tag().clone();
         if !batch.is_empty() {
+            self.pushed.0 += batch.len() as u64;
+            self.pushed.1 += 1;
             trace_worker!(
                 "output[{:?}] send {}th batch(len={}) of {:?} ;",
                 self.port,
@@ -404,11 +415,21 @@ impl<D: Data> OutputHandle<D> {
         &mut self, tag: &Tag, iter: &mut I,
     ) -> IOResult<Option<D>> {
         //self.buf_pool.pin(tag);
+        let mut sent = 0;
         loop {
             match self.buf_pool.push_iter(tag, iter) {
                 Ok(Some(buf)) => {
                     let batch = MicroBatch::new(tag.clone(), self.src, buf);
                     self.send_batch(batch)?;
+                    sent += 1;
+                    // yield after a round of batches, so that the cancel events can be accepted
+                    // even if the pushes never block: pushes to remote servers only fill an
+                    // unbounded outbox, so an endless source would never return to accept an early
+                    // stop. A round of batches keeps the cost of the yield negligible, see
+                    // `benches/bench_push_iter.rs`;
+                    if sent >= self.batch_capacity.max(1) {
+                        return would_block!("yield push iterator");
+                    }
                 }
                 Ok(None) => {
                     // all data in iter should be send;
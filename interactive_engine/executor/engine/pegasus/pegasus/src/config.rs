@@ -22,7 +22,7 @@ use serde::Deserialize;
 
 use crate::errors::StartupError;
 use crate::{get_servers, get_servers_len};
-use crate::{PROFILE_COMM_FLAG, PROFILE_TIME_FLAG};
+use crate::{METRICS_FLAG, PROFILE_COMM_FLAG, PROFILE_TIME_FLAG};
 
 #[macro_export]
 macro_rules! configure_with_default {
@@ -136,6 +136,10 @@ pub struct JobConf {
     pub trace_enable: bool,
     /// optimization factors of early-stop
     pub debug: bool,
+    /// set to record runtime metrics of operators and channels through OpenTelemetry;
+    pub metrics_enable: bool,
+    /// the directory to export the dataflow plan and runtime profile of the job as JSON into;
+    pub profile_export: Option<String>,
 }
 
 impl JobConf {
@@ -198,6 +202,8 @@ impl Default for JobConf {
             servers: ServerConf::Local,
             trace_enable: false,
             debug: false,
+            metrics_enable: *METRICS_FLAG,
+            profile_export: None,
         }
     }
 }
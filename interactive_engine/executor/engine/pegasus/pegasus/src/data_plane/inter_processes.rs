@@ -19,16 +19,22 @@ use crate::channel_id::ChannelId;
 use crate::data_plane::intra_process::IntraProcessPull;
 use crate::data_plane::{Pull, Push};
 use crate::errors::IOError;
+use crate::metrics::ChannelMetrics;
 use crate::Data;
 
 pub struct RemotePush<T: Data> {
     pub id: ChannelId,
     push: IPCSender<T>,
+    metrics: Option<ChannelMetrics>,
 }
 
 impl<T: Data> Push<T> for RemotePush<T> {
     fn push(&mut self, msg: T) -> Result<(), IOError> {
-        Ok(self.push.send(&msg)?)
+        self.push.send(&msg)?;
+        if let Some(metrics) = self.metrics.as_mut() {
+            metrics.on_sent(self.push.sent_bytes());
+        }
+        Ok(())
     }
 
     fn check_failed(&mut self) -> Option<T> {
@@ -46,7 +52,11 @@ impl<T: Data> Push<T> for RemotePush<T> {
 
 impl<T: Data> RemotePush<T> {
     pub fn new(id: ChannelId, push: IPCSender<T>) -> Self {
-        RemotePush { id, push }
+        RemotePush { id, push, metrics: None }
+    }
+
+    pub(crate) fn set_metrics(&mut self, metrics: ChannelMetrics) {
+        self.metrics = Some(metrics);
     }
 }
 
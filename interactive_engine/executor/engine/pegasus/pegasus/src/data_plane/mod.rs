@@ -106,6 +106,7 @@ use intra_process::{IntraProcessPull, IntraProcessPush};
 use intra_thread::{ThreadPull, ThreadPush};
 
 use crate::config::ServerConf;
+use crate::metrics::ChannelMetrics;
 
 #[enum_dispatch(Push<T>)]
 pub enum GeneralPush<T: Data> {
@@ -122,6 +123,13 @@ impl<T: Data> GeneralPush<T> {
             _ => true,
         }
     }
+
+    /// Record the bytes sent through this push if it sends to another server;
+    pub(crate) fn set_metrics(&mut self, metrics: ChannelMetrics) {
+        if let GeneralPush::InterProcesses(p) = self {
+            p.set_metrics(metrics);
+        }
+    }
 }
 
 #[enum_dispatch(Pull<T>)]
@@ -13,7 +13,7 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::VecDeque;
 use std::fmt::Write;
 use std::fs::File;
@@ -29,6 +29,7 @@ use crate::errors::{BuildJobError, IOResult, JobExecError};
 use crate::event::emitter::EventEmitter;
 use crate::graph::{Dependency, DotGraph, Edge, Port};
 use crate::operator::{GeneralOperator, NotifiableOperator, Operator, OperatorBuilder, OperatorCore};
+use crate::profile::{ChannelNode, JobProfile, OperatorNode, OperatorStats};
 use crate::schedule::Schedule;
 use crate::{Data, JobConf, Tag, WorkerId};
 use crate::{PROFILE_COMM_FLAG, PROFILE_TIME_FLAG};
@@ -41,6 +42,7 @@ pub struct DataflowBuilder {
     operators: Rc<RefCell<Vec<OperatorBuilder>>>,
     edges: Rc<RefCell<Vec<Edge>>>,
     sinks: Rc<RefCell<Vec<usize>>>,
+    plan_id: Rc<Cell<Option<usize>>>,
 }
 
 impl DataflowBuilder {
@@ -53,6 +55,7 @@ impl DataflowBuilder {
             event_emitter,
             ch_index: Rc::new(RefCell::new(1)),
             sinks: Rc::new(RefCell::new(vec![])),
+            plan_id: Rc::new(Cell::new(None)),
         }
     }
 
@@ -70,6 +73,28 @@ impl DataflowBuilder {
         }
     }
 
+    /// The name of the operator at `index`, the index 0 is taken by the root of the dataflow;
+    pub(crate) fn operator_name(&self, index: usize) -> Option<String> {
+        if index == 0 {
+            Some("root".to_owned())
+        } else {
+            let operators = self.operators.try_borrow().ok()?;
+            operators
+                .get(index - 1)
+                .map(|op| op.info.name.clone())
+        }
+    }
+
+    #[inline]
+    pub(crate) fn set_plan_id(&self, plan_id: Option<usize>) {
+        self.plan_id.set(plan_id);
+    }
+
+    #[inline]
+    pub(crate) fn get_plan_id(&self) -> Option<usize> {
+        self.plan_id.get()
+    }
+
     #[inline]
     pub(crate) fn next_channel_index(&self) -> u32 {
         let mut idx = self.ch_index.borrow_mut();
@@ -83,7 +108,8 @@ impl DataflowBuilder {
         F: FnOnce(&OperatorInfo) -> O,
     {
         let index = self.operators.borrow().len() + 1;
-        let info = OperatorInfo::new(name, index, scope_level);
+        let mut info = OperatorInfo::new(name, index, scope_level);
+        info.plan_id = self.plan_id.get();
         let core = Box::new(construct(&info));
         let op_b = OperatorBuilder::new(info, GeneralOperator::Simple(core), self.worker_id);
         self.operators.borrow_mut().push(op_b);
@@ -98,7 +124,8 @@ impl DataflowBuilder {
         F: FnOnce(&OperatorInfo) -> O,
     {
         let index = self.operators.borrow().len() + 1;
-        let info = OperatorInfo::new(name, index, scope_level);
+        let mut info = OperatorInfo::new(name, index, scope_level);
+        info.plan_id = self.plan_id.get();
         let core = Box::new(construct(&info));
         let op_b = OperatorBuilder::new(info, GeneralOperator::Notifiable(core), self.worker_id);
         self.operators.borrow_mut().push(op_b);
@@ -130,6 +157,8 @@ impl DataflowBuilder {
         operators.push(None);
         let mut op_names = vec![];
         op_names.push("root".to_owned());
+        let mut op_nodes = vec![];
+        op_nodes.push(OperatorNode { index: 0, name: "root".to_owned(), scope_level: 0, plan_id: None });
         let mut depends = Dependency::default();
         sch.add_schedule_op(self.worker_id, 0, 0, vec![], vec![]);
         let sinks = self.sinks.replace(vec![]);
@@ -149,8 +178,14 @@ impl DataflowBuilder {
                 inputs_notify,
                 outputs_cancel,
             );
-            let op = op_b.build();
+            let op = op_b.build(&self.config);
             op_names.push(op.info.name.clone());
+            op_nodes.push(OperatorNode {
+                index: op.info.index,
+                name: op.info.name.clone(),
+                scope_level: op.info.scope_level,
+                plan_id: op.info.plan_id,
+            });
             if report {
                 writeln!(plan_desc, "\t{}\t{}({})", op.info.index, op.info.name, op.info.index).ok();
                 if *PROFILE_TIME_FLAG | *PROFILE_COMM_FLAG {
@@ -160,6 +195,17 @@ impl DataflowBuilder {
             operators.push(Some(op));
         }
         let edges = self.edges.replace(vec![]);
+        if self.config.profile_export.is_some() {
+            crate::profile::add_plan(JobProfile {
+                job_id: self.config.job_id,
+                job_name: self.config.job_name.clone(),
+                server_index: self.worker_id.server_index,
+                total_workers: self.worker_id.total_peers(),
+                operators: op_nodes,
+                channels: edges.iter().map(ChannelNode::from).collect(),
+                stats: vec![],
+            });
+        }
         if report {
             writeln!(plan_desc, "Channels:\t").ok();
             for e in edges.iter() {
@@ -188,6 +234,7 @@ impl DataflowBuilder {
             operators: RefCell::new(operators),
             conf: self.config,
             depends,
+            stats: RefCell::new(vec![]),
         })
     }
 }
@@ -202,6 +249,7 @@ impl Clone for DataflowBuilder {
             ch_index: self.ch_index.clone(),
             edges: self.edges.clone(),
             sinks: self.sinks.clone(),
+            plan_id: self.plan_id.clone(),
         }
     }
 }
@@ -247,6 +295,8 @@ pub struct Dataflow {
     pub worker_id: WorkerId,
     operators: RefCell<Vec<Option<Operator>>>,
     depends: Dependency,
+    // stats of finished operators, only collected if the profile is exported;
+    stats: RefCell<Vec<OperatorStats>>,
 }
 
 impl Dataflow {
@@ -266,6 +316,7 @@ impl Dataflow {
                     let result = op.fire();
                     if op.is_finished() {
                         op.close();
+                        self.collect_stats(&op);
                         // debug_worker!("operator {:?} finished;", op.meta);
                     } else {
                         *op_opt = Some(op);
@@ -297,6 +348,7 @@ impl Dataflow {
             if let Some(op) = op_opt.take() {
                 if op.is_finished() {
                     op.close();
+                    self.collect_stats(&op);
                     // debug_worker!("operator {:?} finished;", op.meta);
                 } else {
                     debug_worker!("operator {:?} is unfinished;", op.info);
@@ -311,6 +363,18 @@ impl Dataflow {
         true
     }
 
+    fn collect_stats(&self, op: &Operator) {
+        if self.conf.profile_export.is_some() {
+            self.stats
+                .borrow_mut()
+                .push(op.stats(self.worker_id.index));
+        }
+    }
+
+    pub(crate) fn take_stats(&self) -> Vec<OperatorStats> {
+        self.stats.replace(vec![])
+    }
+
     pub fn try_cancel(
         &self, index: usize, discards: &mut VecDeque<(Port, Tag)>,
     ) -> Result<(), JobExecError> {
@@ -17,10 +17,11 @@ use std::borrow::Cow;
 
 use dot::LabelText::LabelStr;
 use dot::{Edges, Id, LabelText, Nodes};
+use serde::{Deserialize, Serialize};
 
 use crate::channel_id::ChannelInfo;
 
-#[derive(Copy, Clone, Hash, Eq, PartialEq, Default)]
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Default, Serialize, Deserialize)]
 pub struct Port {
     pub index: usize,
     pub port: usize,
@@ -38,6 +39,22 @@ impl ::std::fmt::Debug for Port {
     }
 }
 
+/// The way how data is exchanged between workers through a channel;
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Exchange {
+    /// data stays in the worker produced it;
+    Pipeline,
+    /// each record is routed to a worker by its key;
+    Shuffle,
+    /// each batch is routed to a worker as a whole;
+    BatchShuffle,
+    /// each batch is copied to all workers;
+    Broadcast,
+    /// all data is sent to one worker;
+    Aggregate,
+}
+
 /// Edge representation in the direct cycle graph;
 #[derive(Copy, Clone)]
 pub struct Edge {
@@ -47,10 +64,11 @@ pub struct Edge {
     pub source_peers: usize,
     pub target_peers: usize,
     pub scope_level: usize,
+    pub exchange: Exchange,
 }
 
 impl Edge {
-    pub fn new(ch_info: ChannelInfo) -> Self {
+    pub fn new(ch_info: ChannelInfo, exchange: Exchange) -> Self {
         Edge {
             id: ch_info.id.index as usize,
             source: ch_info.source_port,
@@ -58,6 +76,7 @@ impl Edge {
             source_peers: ch_info.source_peers,
             target_peers: ch_info.source_peers,
             scope_level: ch_info.scope_level as usize,
+            exchange,
         }
     }
 }
@@ -72,6 +91,7 @@ impl Default for Edge {
             source_peers: 1,
             target_peers: 1,
             scope_level: 0,
+            exchange: Exchange::Pipeline,
         }
     }
 }
@@ -0,0 +1,103 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! Run several logical servers inside one process, which exchange data through an in-memory
+//! network instead of TCP, to test the behavior of jobs across servers.
+
+use std::fmt::Debug;
+
+pub use pegasus_network::{Link, Schedule};
+use pegasus_network::{MemoryNetwork, NetError};
+
+use crate::api::Source;
+use crate::result::{ResultSink, ResultStream};
+use crate::{BuildJobError, Data, JobConf, JobSubmitError, ServerConf};
+
+/// A cluster of logical servers inside one process, connected by an in-memory network whose
+/// message delivery order and faults are controlled by tests.
+///
+/// # Examples
+/// ```
+/// use pegasus::api::{Map, Sink};
+/// use pegasus::harness::{LocalCluster, Schedule};
+/// use pegasus::JobConf;
+///
+/// let cluster = LocalCluster::start(2, Schedule::Seeded(7)).unwrap();
+/// let mut conf = JobConf::new("harness_example");
+/// conf.set_workers(2);
+/// let results = cluster
+///     .run(conf, || {
+///         |input, output| {
+///             input
+///                 .input_from(0..10u32)?
+///                 .repartition(|x| Ok(*x as u64))
+///                 .map(|x| Ok(x + 1))?
+///                 .sink_into(output)
+///         }
+///     })
+///     .unwrap();
+/// let mut sum = 0;
+/// for stream in results {
+///     for x in stream {
+///         sum += x.unwrap();
+///     }
+/// }
+/// // each of the 4 workers reads 0..10;
+/// assert_eq!(sum, 220);
+/// ```
+pub struct LocalCluster {
+    network: MemoryNetwork,
+}
+
+impl LocalCluster {
+    /// Start a cluster of `servers` logical servers, whose messages are delivered by `schedule`;
+    pub fn start(servers: usize, schedule: Schedule) -> Result<Self, NetError> {
+        pegasus_common::logs::init_log();
+        pegasus_executor::try_start_executor_async();
+        let network = MemoryNetwork::start(servers, schedule)?;
+        Ok(LocalCluster { network })
+    }
+
+    /// The ids of servers in the cluster;
+    pub fn servers(&self) -> &[u64] {
+        self.network.servers()
+    }
+
+    /// The network connecting servers, to inject faults or get the trace of messages;
+    pub fn network(&self) -> &MemoryNetwork {
+        &self.network
+    }
+
+    /// Submit the job to every server of the cluster, and return the results of each server,
+    /// in the order of [`servers`](LocalCluster::servers);
+    pub fn run<DI, DO, F, FN>(
+        &self, conf: JobConf, func: F,
+    ) -> Result<Vec<ResultStream<DO>>, JobSubmitError>
+    where
+        DI: Data,
+        DO: Debug + Send + 'static,
+        F: Fn() -> FN,
+        FN: FnOnce(&mut Source<DI>, ResultSink<DO>) -> Result<(), BuildJobError> + 'static,
+    {
+        let mut conf = conf;
+        conf.reset_servers(ServerConf::Partial(self.servers().to_vec()));
+        let mut results = Vec::with_capacity(self.servers().len());
+        for id in self.servers() {
+            let stream = crate::with_server_id(*id, || crate::run(conf.clone(), &func))?;
+            results.push(stream);
+        }
+        Ok(results)
+    }
+}
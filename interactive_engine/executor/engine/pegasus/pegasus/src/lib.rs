@@ -30,6 +30,7 @@ use std::sync::{Arc, Mutex, RwLock};
 
 mod config;
 mod graph;
+pub mod harness;
 pub mod tag;
 #[macro_use]
 mod worker_id;
@@ -44,7 +45,9 @@ pub mod communication;
 mod data_plane;
 pub mod dataflow;
 mod event;
+pub mod metrics;
 mod operator;
+pub mod profile;
 pub(crate) mod progress;
 pub mod resource;
 pub mod result;
@@ -81,6 +84,7 @@ lazy_static! {
     static ref JOB_CANCEL_MAP: RwLock<HashMap<u64, Arc<AtomicBool>>> = RwLock::new(HashMap::new());
     pub static ref PROFILE_TIME_FLAG: bool = configure_with_default!(bool, "PROFILE_TIME_FLAG", false);
     pub static ref PROFILE_COMM_FLAG: bool = configure_with_default!(bool, "PROFILE_COMM_FLAG", false);
+    pub static ref METRICS_FLAG: bool = configure_with_default!(bool, "METRICS_FLAG", false);
 }
 
 thread_local! {
@@ -105,6 +109,19 @@ pub fn server_id() -> Option<u64> {
     })
 }
 
+/// Run `func` as if on the server `server_id`, which overrides the id of current server in the
+/// current thread, so that logical servers in one process can submit jobs;
+pub(crate) fn with_server_id<R>(server_id: u64, func: impl FnOnce() -> R) -> R {
+    struct Restore(Option<u64>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            LOCAL_SERVER_ID.with(|id| id.set(self.0));
+        }
+    }
+    let _restore = Restore(LOCAL_SERVER_ID.with(|id| id.replace(Some(server_id))));
+    func()
+}
+
 pub fn get_servers() -> Vec<u64> {
     let lock = SERVERS
         .read()
@@ -0,0 +1,326 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! Runtime metrics of operators and channels, recorded through the OpenTelemetry metrics API.
+//!
+//! Metrics are only recorded for jobs with [`JobConf::metrics_enable`] set, and are reported to the
+//! global meter provider under the meter named [`METER_NAME`]. Every operator metric is tagged with
+//! the job id, job name, worker index, and the operator name and index from the dataflow plan;
+//! channel metrics are tagged with the source operator of the channel instead.
+//!
+//! The [`InMemoryExporter`] keeps all metrics in memory and reads them on demand, which is useful
+//! in tests.
+//!
+//! [`JobConf::metrics_enable`]: crate::JobConf::metrics_enable
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use opentelemetry::metrics::{Counter, Histogram, Meter, Unit};
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::metrics::data::{self, ResourceMetrics, Temporality};
+use opentelemetry_sdk::metrics::reader::{AggregationSelector, MetricReader, TemporalitySelector};
+use opentelemetry_sdk::metrics::{Aggregation, InstrumentKind, ManualReader, Pipeline, SdkMeterProvider};
+use opentelemetry_sdk::Resource;
+
+use crate::api::meta::OperatorInfo;
+use crate::channel_id::ChannelInfo;
+use crate::{JobConf, WorkerId};
+
+pub const METER_NAME: &str = "pegasus";
+
+/// Total records pulled by an operator from all its inputs;
+pub const RECORDS_IN: &str = "pegasus.operator.records_in";
+/// Total records pushed by an operator to all its outputs;
+pub const RECORDS_OUT: &str = "pegasus.operator.records_out";
+/// Total non-empty batches pulled by an operator from all its inputs;
+pub const BATCHES_IN: &str = "pegasus.operator.batches_in";
+/// Total non-empty batches pushed by an operator to all its outputs;
+pub const BATCHES_OUT: &str = "pegasus.operator.batches_out";
+/// Total time(us) an operator spends in firing;
+pub const BUSY_TIME: &str = "pegasus.operator.busy_time";
+/// Time(us) each fire of an operator takes;
+pub const FIRE_DURATION: &str = "pegasus.operator.fire_duration";
+/// Batches waiting in the inputs of an operator, sampled after each fire;
+pub const QUEUE_LENGTH: &str = "pegasus.operator.queue_length";
+/// Total bytes sent through a channel to a worker on another server, including message headers;
+pub const BYTES_SENT: &str = "pegasus.channel.bytes_sent";
+
+pub const JOB_ID: &str = "job.id";
+pub const JOB_NAME: &str = "job.name";
+pub const WORKER: &str = "worker";
+pub const OPERATOR_NAME: &str = "operator.name";
+pub const OPERATOR_INDEX: &str = "operator.index";
+pub const CHANNEL: &str = "channel";
+pub const TARGET_WORKER: &str = "target.worker";
+
+#[inline]
+fn meter() -> Meter {
+    opentelemetry::global::meter(METER_NAME)
+}
+
+fn attributes(conf: &JobConf, worker: WorkerId, op_name: &str, op_index: usize) -> Vec<KeyValue> {
+    vec![
+        KeyValue::new(JOB_ID, conf.job_id.to_string()),
+        KeyValue::new(JOB_NAME, conf.job_name.clone()),
+        KeyValue::new(WORKER, worker.index as i64),
+        KeyValue::new(OPERATOR_NAME, op_name.to_owned()),
+        KeyValue::new(OPERATOR_INDEX, op_index as i64),
+    ]
+}
+
+pub(crate) struct OperatorMetrics {
+    attributes: Vec<KeyValue>,
+    records_in: Counter<u64>,
+    records_out: Counter<u64>,
+    batches_in: Counter<u64>,
+    batches_out: Counter<u64>,
+    busy_time: Counter<u64>,
+    fire_duration: Histogram<u64>,
+    queue_length: Histogram<u64>,
+    // records in, batches in, records out, batches out already recorded;
+    recorded: [u64; 4],
+}
+
+impl OperatorMetrics {
+    pub fn new(conf: &JobConf, worker: WorkerId, info: &OperatorInfo) -> Self {
+        let meter = meter();
+        OperatorMetrics {
+            attributes: attributes(conf, worker, &info.name, info.index),
+            records_in: meter.u64_counter(RECORDS_IN).init(),
+            records_out: meter.u64_counter(RECORDS_OUT).init(),
+            batches_in: meter.u64_counter(BATCHES_IN).init(),
+            batches_out: meter.u64_counter(BATCHES_OUT).init(),
+            busy_time: meter
+                .u64_counter(BUSY_TIME)
+                .with_unit(Unit::new("us"))
+                .init(),
+            fire_duration: meter
+                .u64_histogram(FIRE_DURATION)
+                .with_unit(Unit::new("us"))
+                .init(),
+            queue_length: meter.u64_histogram(QUEUE_LENGTH).init(),
+            recorded: [0; 4],
+        }
+    }
+
+    /// Record a fire of the operator, `totals` are the records in, batches in, records out and
+    /// batches out of the operator since it was built;
+    pub fn on_fire(&mut self, elapsed: Duration, totals: [u64; 4], queued: usize) {
+        let elapsed = elapsed.as_micros() as u64;
+        self.busy_time.add(elapsed, &self.attributes);
+        self.fire_duration
+            .record(elapsed, &self.attributes);
+        self.queue_length
+            .record(queued as u64, &self.attributes);
+        let counters = [&self.records_in, &self.batches_in, &self.records_out, &self.batches_out];
+        for (i, counter) in counters.iter().enumerate() {
+            let delta = totals[i] - self.recorded[i];
+            if delta > 0 {
+                counter.add(delta, &self.attributes);
+            }
+        }
+        self.recorded = totals;
+    }
+}
+
+pub(crate) struct ChannelMetrics {
+    attributes: Vec<KeyValue>,
+    bytes_sent: Counter<u64>,
+    recorded: u64,
+}
+
+impl ChannelMetrics {
+    pub fn new(
+        conf: &JobConf, worker: WorkerId, ch_info: &ChannelInfo, source_name: &str, target_worker: u32,
+    ) -> Self {
+        let mut attributes = attributes(conf, worker, source_name, ch_info.source_port.index);
+        attributes.push(KeyValue::new(CHANNEL, ch_info.id.index as i64));
+        attributes.push(KeyValue::new(TARGET_WORKER, target_worker as i64));
+        ChannelMetrics { attributes, bytes_sent: meter().u64_counter(BYTES_SENT).init(), recorded: 0 }
+    }
+
+    /// Record the total bytes sent through the channel so far;
+    pub fn on_sent(&mut self, total_bytes: u64) {
+        if total_bytes > self.recorded {
+            self.bytes_sent
+                .add(total_bytes - self.recorded, &self.attributes);
+            self.recorded = total_bytes;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetricValue {
+    Sum(u64),
+    Histogram { count: u64, sum: u64, min: Option<u64>, max: Option<u64> },
+}
+
+/// A data point of a metric read from the [`InMemoryExporter`];
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricPoint {
+    pub name: String,
+    pub attributes: BTreeMap<String, String>,
+    pub value: MetricValue,
+}
+
+impl MetricPoint {
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(|v| v.as_str())
+    }
+
+    /// The value of a counter, or the sum of a histogram;
+    pub fn sum(&self) -> u64 {
+        match self.value {
+            MetricValue::Sum(v) => v,
+            MetricValue::Histogram { sum, .. } => sum,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SharedReader(Arc<ManualReader>);
+
+impl TemporalitySelector for SharedReader {
+    fn temporality(&self, kind: InstrumentKind) -> Temporality {
+        self.0.temporality(kind)
+    }
+}
+
+impl AggregationSelector for SharedReader {
+    fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
+        self.0.aggregation(kind)
+    }
+}
+
+impl MetricReader for SharedReader {
+    fn register_pipeline(&self, pipeline: Weak<Pipeline>) {
+        self.0.register_pipeline(pipeline)
+    }
+
+    fn collect(&self, rm: &mut ResourceMetrics) -> opentelemetry::metrics::Result<()> {
+        self.0.collect(rm)
+    }
+
+    fn force_flush(&self) -> opentelemetry::metrics::Result<()> {
+        self.0.force_flush()
+    }
+
+    fn shutdown(&self) -> opentelemetry::metrics::Result<()> {
+        self.0.shutdown()
+    }
+}
+
+/// A meter provider keeping all metrics in memory, which can be read at any time by [`collect`];
+///
+/// # Examples
+///
+/// ```
+/// use pegasus::api::Sink;
+/// use pegasus::metrics::{InMemoryExporter, RECORDS_OUT};
+/// use pegasus::JobConf;
+///
+/// let exporter = InMemoryExporter::new();
+/// exporter.install();
+///
+/// let mut conf = JobConf::new("metrics_example");
+/// conf.metrics_enable = true;
+/// let mut result = pegasus::run(conf, || |input, output| {
+///     input.input_from(0..10u32)?.sink_into(output)
+/// })
+/// .expect("submit job failure");
+/// while let Some(_) = result.next() {}
+///
+/// let out: u64 = exporter
+///     .collect()
+///     .iter()
+///     .filter(|p| p.name == RECORDS_OUT && p.attribute("operator.name") == Some("source"))
+///     .map(|p| p.sum())
+///     .sum();
+/// assert_eq!(out, 10);
+/// ```
+///
+/// [`collect`]: InMemoryExporter::collect
+pub struct InMemoryExporter {
+    reader: SharedReader,
+    provider: SdkMeterProvider,
+}
+
+impl InMemoryExporter {
+    pub fn new() -> Self {
+        let reader = SharedReader(Arc::new(ManualReader::builder().build()));
+        let provider = SdkMeterProvider::builder()
+            .with_reader(reader.clone())
+            .build();
+        InMemoryExporter { reader, provider }
+    }
+
+    /// Set this exporter as the global meter provider, all metrics recorded after are kept by it;
+    pub fn install(&self) {
+        opentelemetry::global::set_meter_provider(self.provider.clone());
+    }
+
+    pub fn provider(&self) -> &SdkMeterProvider {
+        &self.provider
+    }
+
+    /// Read the cumulative value of all counters and histograms recorded so far;
+    pub fn collect(&self) -> Vec<MetricPoint> {
+        let mut rm = ResourceMetrics { resource: Resource::empty(), scope_metrics: vec![] };
+        if let Err(e) = self.reader.collect(&mut rm) {
+            error!("collect metrics failure: {}", e);
+            return vec![];
+        }
+        let mut points = vec![];
+        for scope in rm.scope_metrics {
+            for metric in scope.metrics {
+                let name = metric.name.to_string();
+                let data = metric.data.as_any();
+                if let Some(sum) = data.downcast_ref::<data::Sum<u64>>() {
+                    for p in sum.data_points.iter() {
+                        let attributes = to_map(p.attributes.iter());
+                        points.push(MetricPoint {
+                            name: name.clone(),
+                            attributes,
+                            value: MetricValue::Sum(p.value),
+                        });
+                    }
+                } else if let Some(hist) = data.downcast_ref::<data::Histogram<u64>>() {
+                    for p in hist.data_points.iter() {
+                        let attributes = to_map(p.attributes.iter());
+                        let value =
+                            MetricValue::Histogram { count: p.count, sum: p.sum, min: p.min, max: p.max };
+                        points.push(MetricPoint { name: name.clone(), attributes, value });
+                    }
+                }
+            }
+        }
+        points
+    }
+}
+
+impl Default for InMemoryExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn to_map<'a>(
+    iter: impl Iterator<Item = (&'a opentelemetry::Key, &'a opentelemetry::Value)>,
+) -> BTreeMap<String, String> {
+    iter.map(|(k, v)| (k.as_str().to_owned(), v.as_str().into_owned()))
+        .collect()
+}
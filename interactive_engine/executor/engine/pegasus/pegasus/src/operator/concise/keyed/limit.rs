@@ -0,0 +1,130 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::cmp::Ordering;
+
+use ahash::AHashMap;
+
+use crate::api::{HasKey, PartitionByKey, SampleByKey, SortLimitByKey, Unary};
+use crate::operator::concise::limit::ShadeCmp;
+use crate::stream::Stream;
+use crate::tag::tools::map::TidyTagMap;
+use crate::{BuildJobError, Data};
+
+impl<D: Data + HasKey> SortLimitByKey<D> for Stream<D> {
+    fn sort_limit_by_key<F>(self, size: u32, cmp: F) -> Result<Stream<D>, BuildJobError>
+    where
+        F: Fn(&D, &D) -> Ordering + Send + 'static,
+    {
+        let cmp = ShadeCmp::new(cmp);
+        let local = top_by_key(self, "sort_limit_by_key_locally", size, cmp.clone())?;
+        top_by_key(local.partition_by_key(), "sort_limit_by_key_globally", size, cmp)
+    }
+}
+
+impl<D: Data + HasKey> SampleByKey<D> for Stream<D> {
+    fn sample_by_key<F>(self, size: u32, priority: F) -> Result<Stream<D>, BuildJobError>
+    where
+        F: Fn(&D) -> f64 + Send + 'static,
+    {
+        let size = size as usize;
+        self.partition_by_key()
+            .unary("sample_by_key", |info| {
+                let mut table = TidyTagMap::<AHashMap<D::Target, Vec<(f64, D)>>>::new(info.scope_level);
+                let by_priority =
+                    |a: &(f64, D), b: &(f64, D)| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal);
+                move |input, output| {
+                    input.for_each_batch(|batch| {
+                        if !batch.is_empty() {
+                            let groups = table.get_mut_or_insert(&batch.tag);
+                            for d in batch.drain() {
+                                if size == 0 {
+                                    continue;
+                                }
+                                let p = priority(&d);
+                                let group = groups
+                                    .entry(d.get_key().clone())
+                                    .or_insert_with(Vec::new);
+                                group.push((p, d));
+                                if group.len() >= size * 2 {
+                                    group.sort_by(by_priority);
+                                    group.truncate(size);
+                                }
+                            }
+                        }
+
+                        if batch.is_last() {
+                            if let Some(groups) = table.remove(&batch.tag) {
+                                let mut session = output.new_session(&batch.tag)?;
+                                for (_, mut group) in groups {
+                                    group.sort_by(by_priority);
+                                    group.truncate(size);
+                                    session.give_iterator(group.into_iter().map(|(_, d)| d))?;
+                                }
+                            }
+                        }
+                        Ok(())
+                    })
+                }
+            })
+    }
+}
+
+/// Keep the minimum `size` data of each key in the partition, which are produced in order when the
+/// data of a scope are exhausted.
+fn top_by_key<D: Data + HasKey, F>(
+    stream: Stream<D>, name: &str, size: u32, cmp: ShadeCmp<F>,
+) -> Result<Stream<D>, BuildJobError>
+where
+    F: Fn(&D, &D) -> Ordering + Send + 'static,
+{
+    let size = size as usize;
+    stream.unary(name, |info| {
+        let mut table = TidyTagMap::<AHashMap<D::Target, Vec<D>>>::new(info.scope_level);
+        move |input, output| {
+            input.for_each_batch(|batch| {
+                if !batch.is_empty() {
+                    let groups = table.get_mut_or_insert(&batch.tag);
+                    for d in batch.drain() {
+                        if size == 0 {
+                            continue;
+                        }
+                        let group = groups
+                            .entry(d.get_key().clone())
+                            .or_insert_with(Vec::new);
+                        group.push(d);
+                        // amortize the sorting by truncating only when the group doubles the size
+                        if group.len() >= size * 2 {
+                            group.sort_by(|a, b| (*cmp.cmp)(a, b));
+                            group.truncate(size);
+                        }
+                    }
+                }
+
+                if batch.is_last() {
+                    if let Some(groups) = table.remove(&batch.tag) {
+                        let mut session = output.new_session(&batch.tag)?;
+                        for (_, mut group) in groups {
+                            group.sort_by(|a, b| (*cmp.cmp)(a, b));
+                            group.truncate(size);
+                            session.give_iterator(group.into_iter())?;
+                        }
+                    }
+                }
+                Ok(())
+            })
+        }
+    })
+}
@@ -16,7 +16,8 @@
 use std::hash::{BuildHasher, Hash, Hasher};
 
 use crate::api::function::{FnResult, RouteFunction};
-use crate::api::{HasKey, Key, KeyBy, Map, Pair, PartitionByKey};
+use crate::api::{HasKey, Key, KeyBy, Map, Pair, PartitionByKey, SkewConf};
+use crate::operator::concise::keyed::skew::{RouteStats, SkewRouter};
 use crate::stream::Stream;
 use crate::{BuildJobError, Data};
 
@@ -39,6 +40,20 @@ impl<D: Data + HasKey> PartitionByKey<D> for Stream<D> {
         let job_id = self.get_worker_id().job_id;
         let bh = ahash::RandomState::with_seeds(job_id, job_id & 3, job_id & 7, job_id & 15);
         let router = KeyRouter::new(bh);
+        let stats = RouteStats::new("partition_by_key", self.get_worker_id().total_peers());
+
+        self.repartition(move |item| {
+            let route = router.route(item)?;
+            stats.record(route);
+            Ok(route)
+        })
+    }
+
+    fn partition_by_key_adaptive(self, conf: SkewConf) -> Stream<D> {
+        let worker_id = self.get_worker_id();
+        let job_id = worker_id.job_id;
+        let bh = ahash::RandomState::with_seeds(job_id, job_id & 3, job_id & 7, job_id & 15);
+        let router = SkewRouter::new(bh, conf, worker_id.total_peers());
 
         self.repartition(move |item| router.route(item))
     }
@@ -66,7 +81,9 @@ impl<D: Data + HasKey, H: BuildHasher + Send + 'static> RouteFunction<D> for Key
 mod dedup;
 mod fold;
 mod join;
+mod limit;
 mod reduce;
+mod skew;
 
 #[cfg(test)]
 mod test {
@@ -0,0 +1,226 @@
+//
+//! Copyright 2022 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::cell::{Cell, RefCell};
+use std::hash::BuildHasher;
+
+use ahash::{AHashMap, AHashSet};
+
+use crate::api::function::{FnResult, RouteFunction};
+use crate::api::{HasKey, SkewConf};
+use crate::Data;
+
+/// Count the records routed to each worker, and log them with the skew, i.e. the ratio of the
+/// maximum to the average, when dropped at the end of the job;
+pub(crate) struct RouteStats {
+    name: &'static str,
+    sent: Vec<Cell<u64>>,
+    hot_keys: Cell<usize>,
+}
+
+impl RouteStats {
+    pub(crate) fn new(name: &'static str, peers: u32) -> Self {
+        RouteStats {
+            name,
+            sent: (0..peers.max(1))
+                .map(|_| Cell::new(0))
+                .collect(),
+            hot_keys: Cell::new(0),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn record(&self, route: u64) {
+        let count = &self.sent[(route % self.sent.len() as u64) as usize];
+        count.set(count.get() + 1);
+    }
+
+    pub(crate) fn skew(&self) -> f64 {
+        let total = self.sent.iter().map(|c| c.get()).sum::<u64>();
+        let max = self
+            .sent
+            .iter()
+            .map(|c| c.get())
+            .max()
+            .unwrap_or(0);
+        if total == 0 {
+            1.0
+        } else {
+            max as f64 * self.sent.len() as f64 / total as f64
+        }
+    }
+}
+
+impl Drop for RouteStats {
+    fn drop(&mut self) {
+        if self.sent.iter().any(|c| c.get() > 0) {
+            let sent = self
+                .sent
+                .iter()
+                .map(|c| c.get())
+                .collect::<Vec<_>>();
+            info_worker!(
+                "{}: records sent to workers {:?}, skew = {:.3}, hot keys = {};",
+                self.name,
+                sent,
+                self.skew(),
+                self.hot_keys.get()
+            );
+        }
+    }
+}
+
+/// Find the keys taking more than a fraction of all records by the Misra-Gries summary, whose
+/// estimated count of a key is less than its real count by at most `total / (capacity + 1)`.
+struct HeavyHitters {
+    capacity: usize,
+    counts: AHashMap<u64, u64>,
+    total: u64,
+}
+
+impl HeavyHitters {
+    fn new(threshold: f64) -> Self {
+        // keys above the threshold are always kept with the capacity of `2 / threshold`;
+        let capacity = (2.0 / threshold.max(1e-4)).ceil() as usize;
+        HeavyHitters { capacity, counts: AHashMap::new(), total: 0 }
+    }
+
+    /// Count a record of the key, and return the estimated count of it;
+    fn update(&mut self, key: u64) -> u64 {
+        self.total += 1;
+        if let Some(count) = self.counts.get_mut(&key) {
+            *count += 1;
+            *count
+        } else if self.counts.len() < self.capacity {
+            self.counts.insert(key, 1);
+            1
+        } else {
+            self.counts.retain(|_, count| {
+                *count -= 1;
+                *count > 0
+            });
+            0
+        }
+    }
+}
+
+struct SkewState {
+    hitters: HeavyHitters,
+    hot: AHashSet<u64>,
+    next: u64,
+}
+
+/// Route data by the hash of its key like [`KeyRouter`], except the records of hot keys, which
+/// are sent to the `split` workers following the one the key is hashed to in turn.
+///
+/// [`KeyRouter`]: crate::operator::concise::keyed::KeyRouter
+pub(crate) struct SkewRouter<D: Data + HasKey, H: BuildHasher + Send + 'static> {
+    hash_builder: H,
+    conf: SkewConf,
+    peers: u64,
+    state: RefCell<SkewState>,
+    stats: RouteStats,
+    _ph: std::marker::PhantomData<D>,
+}
+
+impl<D: Data + HasKey, H: BuildHasher + Send + 'static> SkewRouter<D, H> {
+    pub(crate) fn new(hash_builder: H, mut conf: SkewConf, peers: u32) -> Self {
+        conf.split = conf.split.clamp(1, peers.max(1));
+        let state = SkewState { hitters: HeavyHitters::new(conf.threshold), hot: AHashSet::new(), next: 0 };
+        SkewRouter {
+            hash_builder,
+            conf,
+            peers: peers.max(1) as u64,
+            state: RefCell::new(state),
+            stats: RouteStats::new("partition_by_key_adaptive", peers),
+            _ph: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<D: Data + HasKey, H: BuildHasher + Send + 'static> RouteFunction<D> for SkewRouter<D, H> {
+    fn route(&self, data: &D) -> FnResult<u64> {
+        let hash = self.hash_builder.hash_one(data.get_key());
+        let mut state = self.state.borrow_mut();
+        let count = state.hitters.update(hash);
+        let total = state.hitters.total;
+        let route = if self.conf.split > 1
+            && total >= self.conf.warmup
+            && count as f64 > self.conf.threshold * total as f64
+        {
+            if state.hot.insert(hash) {
+                debug_worker!("key of hash {} is hot after {} records;", hash, total);
+                self.stats.hot_keys.set(state.hot.len());
+            }
+            state.next += 1;
+            hash % self.peers + state.next % self.conf.split as u64
+        } else {
+            hash
+        };
+        self.stats.record(route);
+        Ok(route)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::hash::BuildHasher;
+
+    use super::*;
+
+    #[test]
+    fn skew_router_test() {
+        let bh = ahash::RandomState::new();
+        let router = SkewRouter::<u32, ahash::RandomState>::new(bh.clone(), SkewConf::new(0.1, 100, 3), 4);
+        let hot = bh.hash_one(0u32);
+        let mut targets = vec![0u64; 4];
+        for i in 0..10000u32 {
+            // half of the records are of the key 0;
+            let key = if i % 2 == 0 { 0 } else { i };
+            let route = router.route(&key).unwrap();
+            if key == 0 {
+                targets[(route % 4) as usize] += 1;
+            } else {
+                assert_eq!(route, bh.hash_one(key));
+            }
+        }
+        // the key 0 is spread over 3 workers after the warmup;
+        let primary = (hot % 4) as usize;
+        for i in 0..4 {
+            let offset = (i + 4 - primary) % 4;
+            if offset < 3 {
+                assert!(targets[i] > 1000, "{:?}", targets);
+            } else {
+                assert_eq!(targets[i], 0, "{:?}", targets);
+            }
+        }
+        assert_eq!(router.stats.hot_keys.get(), 1);
+        assert!(router.stats.skew() < 2.0);
+    }
+
+    #[test]
+    fn heavy_hitters_test() {
+        let mut hitters = HeavyHitters::new(0.25);
+        for i in 0..1000u64 {
+            hitters.update(i % 3);
+            hitters.update(1000 + i);
+        }
+        // each of the keys 0, 1, 2 takes 1/6 of the records, which is kept within the error bound;
+        for key in 0..3u64 {
+            let count = hitters.counts.get(&key).copied().unwrap_or(0);
+            assert!(count + hitters.total / (hitters.capacity as u64 + 1) >= 333, "{}", count);
+        }
+    }
+}
@@ -35,7 +35,8 @@ impl<D: Data> Limit<D> for Stream<D> {
     }
 
     fn limit_partition(mut self, size: u32) -> Result<Stream<D>, BuildJobError> {
-        self.set_upstream_batch_size(size as usize);
+        let batch_size = std::cmp::min(size as usize, self.get_upstream_batch_size());
+        self.set_upstream_batch_size(batch_size);
         self.set_upstream_batch_capacity(1);
         self.unary("limit_partition", |info| {
             let mut table = TidyTagMap::new(info.scope_level);
@@ -65,6 +66,59 @@ impl<D: Data> Limit<D> for Stream<D> {
             }
         })
     }
+
+    fn skip_limit(self, skip: u32, size: u32) -> Result<Stream<D>, BuildJobError> {
+        if skip == 0 {
+            return self.limit(size);
+        }
+        if self.get_partitions() > 1 {
+            let stream = self.limit_partition(skip.saturating_add(size))?;
+            skip_limit_partition(stream.aggregate(), skip, size)
+        } else {
+            skip_limit_partition(self, skip, size)
+        }
+    }
+}
+
+/// Skip the first `skip` data of each scope in the partition and output up to `size` data after them.
+/// The data are counted per scope across batches, so the batches are kept no larger than usual however
+/// many data are skipped.
+fn skip_limit_partition<D: Data>(
+    mut stream: Stream<D>, skip: u32, size: u32,
+) -> Result<Stream<D>, BuildJobError> {
+    let total = skip.saturating_add(size);
+    let batch_size = std::cmp::min(total as usize, stream.get_upstream_batch_size());
+    stream.set_upstream_batch_size(batch_size);
+    stream.set_upstream_batch_capacity(1);
+    stream.unary("skip_limit_partition", |info| {
+        let mut table = TidyTagMap::new(info.scope_level);
+        move |input, output| {
+            input.for_each_batch(|batch| {
+                if !batch.is_empty() {
+                    let mut session = output.new_session(&batch.tag)?;
+                    let count = table.get_mut_or_else(&batch.tag, || 0u32);
+                    if *count < total {
+                        for d in batch.drain() {
+                            *count += 1;
+                            if *count > skip {
+                                session.give(d)?;
+                            }
+                            if *count >= total {
+                                break;
+                            }
+                        }
+                        if *count >= total {
+                            // trigger early-stop
+                            batch.discard();
+                        }
+                    } else {
+                        batch.discard();
+                    }
+                }
+                Ok(())
+            })
+        }
+    })
 }
 
 impl<D: Data + Ord> SortLimit<D> for Stream<D> {
@@ -84,12 +138,30 @@ impl<D: Data> SortLimitBy<D> for Stream<D> {
         let local_sort = sort_limit_by_partition(self, "sort_limit_by_partition_locally", size, cmp_clone)?;
         sort_limit_by_partition(local_sort.aggregate(), "sort_limit_by_partition_globally", size, cmp)
     }
+
+    fn sort_skip_limit_by<F>(self, skip: u32, size: u32, cmp: F) -> Result<Stream<D>, BuildJobError>
+    where
+        F: Fn(&D, &D) -> Ordering + Send + 'static,
+    {
+        if skip == 0 || size == 0 {
+            return self.sort_limit_by(size, cmp);
+        }
+        // the global top-(skip + size) data are on a single partition in order, ready to be skipped
+        let sorted = self.sort_limit_by(skip.saturating_add(size), cmp)?;
+        skip_limit_partition(sorted, skip, size)
+    }
 }
 
 type Cmp<D> = Arc<dyn Fn(&D, &D) -> Ordering + Send + 'static>;
 
-struct ShadeCmp<C> {
-    cmp: Arc<C>,
+pub(crate) struct ShadeCmp<C> {
+    pub(crate) cmp: Arc<C>,
+}
+
+impl<C> ShadeCmp<C> {
+    pub(crate) fn new(cmp: C) -> Self {
+        ShadeCmp { cmp: Arc::new(cmp) }
+    }
 }
 
 unsafe impl<C: Send> Send for ShadeCmp<C> {}
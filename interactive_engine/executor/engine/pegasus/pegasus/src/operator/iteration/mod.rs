@@ -6,6 +6,7 @@ use crate::stream::Stream;
 use crate::{BuildJobError, Data};
 
 mod feedback;
+mod superstep;
 mod switch;
 use feedback::FeedbackOperator;
 use switch::SwitchOperator;
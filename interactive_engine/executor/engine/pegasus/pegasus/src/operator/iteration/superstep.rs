@@ -0,0 +1,304 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::hash::BuildHasher;
+
+use ahash::AHashMap;
+
+use crate::api::function::FnResult;
+use crate::api::{
+    IterCondition, Iteration, Key, Map, Pair, Superstep, SuperstepConf, SuperstepContext, Unary,
+};
+use crate::codec::{Decode, Encode, ReadExt, WriteExt};
+use crate::stream::Stream;
+use crate::tag::tools::map::TidyTagMap;
+use crate::{BuildJobError, Data};
+
+/// The local contribution of one worker to the global state of a superstep;
+#[derive(Clone, Debug)]
+struct Partial<A> {
+    worker: u32,
+    agg: Option<A>,
+    active: u64,
+    messages: u64,
+}
+
+/// The data flowing around the loop of a superstep iteration;
+#[derive(Clone, Debug)]
+enum Step<K, V, M, A> {
+    Vertex(K, V, bool),
+    Message(K, M),
+    /// a partial aggregate, sent to the worker with the given index;
+    Partial(u32, Partial<A>),
+    /// a vertex of a converged iteration, leaving the loop;
+    Done(K, V),
+}
+
+impl<K, V, M, A> Step<K, V, M, A> {
+    fn is_done(&self) -> bool {
+        matches!(self, Step::Done(..))
+    }
+}
+
+struct SuperstepState<K, V, M, A> {
+    vertices: AHashMap<K, (V, bool)>,
+    inbox: AHashMap<K, Vec<M>>,
+    partials: Vec<Partial<A>>,
+}
+
+impl<K: Key, V, M, A> Default for SuperstepState<K, V, M, A> {
+    fn default() -> Self {
+        SuperstepState { vertices: AHashMap::new(), inbox: AHashMap::new(), partials: vec![] }
+    }
+}
+
+impl<K: Data + Key, V: Data> Superstep<K, V> for Stream<Pair<K, V>> {
+    fn superstep<M, A, F>(
+        self, conf: SuperstepConf<M, A>, mut compute: F,
+    ) -> Result<Stream<Pair<K, V>>, BuildJobError>
+    where
+        M: Data,
+        A: Data,
+        F: FnMut(&mut SuperstepContext<K, V, M, A>) -> FnResult<()> + Send + 'static,
+    {
+        if conf.max_supersteps == 0 {
+            Err("at least one superstep")?;
+        }
+        let worker_id = self.get_worker_id();
+        let (worker, peers) = (worker_id.index, worker_id.total_peers());
+        let job_id = worker_id.job_id;
+        let hasher = ahash::RandomState::with_seeds(job_id, job_id & 3, job_id & 7, job_id & 15);
+        let SuperstepConf { max_supersteps, combiner, aggregator, halt } = conf;
+
+        let mut until = IterCondition::max_iters(max_supersteps);
+        until.until(|step: &Step<K, V, M, A>| Ok(step.is_done()));
+        self.map(|pair| Ok(Step::Vertex(pair.key, pair.value, false)))?
+            .iterate_until(until, move |start| {
+                start
+                    .repartition(move |step| {
+                        let key = match step {
+                            Step::Vertex(key, ..) | Step::Message(key, _) | Step::Done(key, _) => key,
+                            Step::Partial(target, _) => return Ok(*target as u64),
+                        };
+                        Ok(hasher.hash_one(key))
+                    })
+                    .unary("superstep", |info| {
+                        let mut table = TidyTagMap::<SuperstepState<K, V, M, A>>::new(info.scope_level);
+                        move |input, output| {
+                            input.for_each_batch(|batch| {
+                                if !batch.is_empty() {
+                                    let state = table.get_mut_or_insert(&batch.tag);
+                                    for step in batch.drain() {
+                                        match step {
+                                            Step::Vertex(key, value, halted) => {
+                                                state.vertices.insert(key, (value, halted));
+                                            }
+                                            Step::Message(key, msg) => {
+                                                let inbox = state.inbox.entry(key).or_default();
+                                                push_message(inbox, msg, combiner.as_deref());
+                                            }
+                                            Step::Partial(_, partial) => state.partials.push(partial),
+                                            Step::Done(..) => unreachable!("converged vertex in superstep"),
+                                        }
+                                    }
+                                }
+
+                                if batch.is_last() {
+                                    let mut state = table.remove(&batch.tag).unwrap_or_default();
+                                    let superstep = batch.tag.current_uncheck();
+                                    let mut session = output.new_session(&batch.tag)?;
+
+                                    // fold the partials of the previous superstep into the global state;
+                                    state.partials.sort_by_key(|p| p.worker);
+                                    let (mut active, mut messages) = (0, 0);
+                                    let mut aggregated = aggregator
+                                        .as_ref()
+                                        .map(|(init, _)| init.clone());
+                                    for partial in state.partials.drain(..) {
+                                        active += partial.active;
+                                        messages += partial.messages;
+                                        if let (Some(agg), Some((_, merge))) =
+                                            (partial.agg, aggregator.as_ref())
+                                        {
+                                            aggregated = aggregated.map(|prev| merge(prev, agg));
+                                        }
+                                    }
+                                    let aggregated = if superstep > 0 { aggregated } else { None };
+                                    if superstep > 0 {
+                                        let halted = match (&aggregated, &halt) {
+                                            (Some(agg), Some(halt)) => halt(agg),
+                                            _ => false,
+                                        };
+                                        if halted || (active == 0 && messages == 0) {
+                                            trace_worker!(
+                                                "superstep iteration of {:?} converged;",
+                                                batch.tag
+                                            );
+                                            session.give_iterator(
+                                                state
+                                                    .vertices
+                                                    .into_iter()
+                                                    .map(|(k, (v, _))| Step::Done(k, v)),
+                                            )?;
+                                            return Ok(());
+                                        }
+                                    }
+
+                                    let mut outbox = vec![];
+                                    let mut partial = None;
+                                    let mut active = 0;
+                                    let merge = aggregator
+                                        .as_ref()
+                                        .map(|(_, merge)| merge.as_ref());
+                                    for (key, (value, halted)) in state.vertices.iter_mut() {
+                                        let messages = state.inbox.remove(key).unwrap_or_default();
+                                        if *halted && messages.is_empty() {
+                                            continue;
+                                        }
+                                        let mut ctx = SuperstepContext {
+                                            superstep,
+                                            key,
+                                            value,
+                                            messages,
+                                            aggregated: aggregated.as_ref(),
+                                            outbox: &mut outbox,
+                                            partial: &mut partial,
+                                            merge,
+                                            halted: false,
+                                        };
+                                        compute(&mut ctx)?;
+                                        *halted = ctx.halted;
+                                        if !*halted {
+                                            active += 1;
+                                        }
+                                    }
+
+                                    let outbox = if let Some(combiner) = combiner.as_deref() {
+                                        let mut combined = AHashMap::<K, Vec<M>>::new();
+                                        for (to, msg) in outbox {
+                                            let inbox = combined.entry(to).or_default();
+                                            push_message(inbox, msg, Some(combiner));
+                                        }
+                                        combined
+                                            .into_iter()
+                                            .flat_map(|(to, msgs)| {
+                                                msgs.into_iter().map(move |m| (to.clone(), m))
+                                            })
+                                            .collect::<Vec<_>>()
+                                    } else {
+                                        outbox
+                                    };
+                                    let partial = Partial {
+                                        worker,
+                                        agg: partial,
+                                        active,
+                                        messages: outbox.len() as u64,
+                                    };
+                                    session.give_iterator(
+                                        state
+                                            .vertices
+                                            .into_iter()
+                                            .map(|(k, (v, halted))| Step::Vertex(k, v, halted)),
+                                    )?;
+                                    session.give_iterator(
+                                        outbox
+                                            .into_iter()
+                                            .map(|(to, msg)| Step::Message(to, msg)),
+                                    )?;
+                                    session.give_iterator(
+                                        (0..peers)
+                                            .map(move |target| Step::Partial(target, partial.clone())),
+                                    )?;
+                                }
+                                Ok(())
+                            })
+                        }
+                    })
+            })?
+            .filter_map(|step| match step {
+                Step::Vertex(key, value, _) | Step::Done(key, value) => Ok(Some(Pair { key, value })),
+                _ => Ok(None),
+            })
+    }
+}
+
+fn push_message<M>(inbox: &mut Vec<M>, msg: M, combiner: Option<&(dyn Fn(M, M) -> M + Send)>) {
+    match (combiner, inbox.pop()) {
+        (Some(combine), Some(prev)) => inbox.push(combine(prev, msg)),
+        (_, prev) => {
+            inbox.extend(prev);
+            inbox.push(msg);
+        }
+    }
+}
+
+impl<A: Encode> Encode for Partial<A> {
+    fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_u32(self.worker)?;
+        self.agg.write_to(writer)?;
+        writer.write_u64(self.active)?;
+        writer.write_u64(self.messages)
+    }
+}
+
+impl<A: Decode> Decode for Partial<A> {
+    fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
+        let worker = reader.read_u32()?;
+        let agg = Option::<A>::read_from(reader)?;
+        let active = reader.read_u64()?;
+        let messages = reader.read_u64()?;
+        Ok(Partial { worker, agg, active, messages })
+    }
+}
+
+impl<K: Encode, V: Encode, M: Encode, A: Encode> Encode for Step<K, V, M, A> {
+    fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
+        match self {
+            Step::Vertex(key, value, halted) => {
+                writer.write_u8(0)?;
+                key.write_to(writer)?;
+                value.write_to(writer)?;
+                halted.write_to(writer)
+            }
+            Step::Message(key, msg) => {
+                writer.write_u8(1)?;
+                key.write_to(writer)?;
+                msg.write_to(writer)
+            }
+            Step::Partial(target, partial) => {
+                writer.write_u8(2)?;
+                writer.write_u32(*target)?;
+                partial.write_to(writer)
+            }
+            Step::Done(key, value) => {
+                writer.write_u8(3)?;
+                key.write_to(writer)?;
+                value.write_to(writer)
+            }
+        }
+    }
+}
+
+impl<K: Decode, V: Decode, M: Decode, A: Decode> Decode for Step<K, V, M, A> {
+    fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
+        match reader.read_u8()? {
+            0 => Ok(Step::Vertex(K::read_from(reader)?, V::read_from(reader)?, bool::read_from(reader)?)),
+            1 => Ok(Step::Message(K::read_from(reader)?, M::read_from(reader)?)),
+            2 => Ok(Step::Partial(reader.read_u32()?, Partial::read_from(reader)?)),
+            3 => Ok(Step::Done(K::read_from(reader)?, V::read_from(reader)?)),
+            e => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("unknown step {}", e))),
+        }
+    }
+}
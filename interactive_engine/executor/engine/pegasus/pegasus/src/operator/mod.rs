@@ -29,11 +29,13 @@ use crate::data_plane::{GeneralPull, GeneralPush};
 use crate::errors::{IOResult, JobExecError};
 use crate::event::emitter::EventEmitter;
 use crate::graph::Port;
+use crate::metrics::OperatorMetrics;
+use crate::profile::OperatorStats;
 use crate::progress::EndOfScope;
 use crate::schedule::state::inbound::InputEndNotify;
 use crate::schedule::state::outbound::OutputCancelState;
 use crate::tag::tools::map::TidyTagMap;
-use crate::{Data, Tag, WorkerId};
+use crate::{Data, JobConf, Tag, WorkerId};
 use crate::{PROFILE_COMM_FLAG, PROFILE_TIME_FLAG};
 
 pub trait Notifiable: Send + 'static {
@@ -285,6 +287,7 @@ pub struct Operator {
     core: Box<dyn NotifiableOperator>,
     fire_times: u128,
     exec_st: UnsafeRcPtr<Cell<u128>>,
+    metrics: Option<OperatorMetrics>,
 }
 
 impl Operator {
@@ -320,6 +323,7 @@ impl Operator {
     #[inline]
     pub fn fire(&mut self) -> Result<(), JobExecError> {
         let _f = Finally::new(self.exec_st.clone());
+        let start = Instant::now();
         debug_worker!("fire operator {:?}", self.info);
         self.fire_times += 1;
 
@@ -342,6 +346,9 @@ impl Operator {
         for output in self.outputs.iter() {
             output.flush()?;
         }
+        if self.metrics.is_some() {
+            self.record_metrics(start);
+        }
         if *PROFILE_COMM_FLAG {
             info_worker!("after fire operator \t\t{:?}\t\t", self.info);
         } else {
@@ -390,6 +397,44 @@ impl Operator {
         }
     }
 
+    pub fn stats(&self, worker: u32) -> OperatorStats {
+        let [records_in, batches_in, records_out, batches_out] = self.totals();
+        OperatorStats {
+            operator: self.info.index,
+            worker,
+            fire_times: self.fire_times as u64,
+            busy_us: self.exec_st.get() as u64,
+            records_in,
+            batches_in,
+            records_out,
+            batches_out,
+        }
+    }
+
+    /// The records in, batches in, records out and batches out of the operator since it was built;
+    fn totals(&self) -> [u64; 4] {
+        let mut totals = [0u64; 4];
+        for input in self.inputs.iter() {
+            let (records, batches) = input.pulled();
+            totals[0] += records;
+            totals[1] += batches;
+        }
+        for output in self.outputs.iter() {
+            let (records, batches) = output.pushed();
+            totals[2] += records;
+            totals[3] += batches;
+        }
+        totals
+    }
+
+    fn record_metrics(&mut self, start: Instant) {
+        let totals = self.totals();
+        let queued = self.inputs.iter().map(|i| i.queued()).sum();
+        if let Some(metrics) = self.metrics.as_mut() {
+            metrics.on_fire(start.elapsed(), totals, queued);
+        }
+    }
+
     fn fire_inner(&mut self) -> Result<(), JobExecError> {
         for output in self.outputs.iter() {
             if let Err(e) = output.try_unblock() {
@@ -482,7 +527,7 @@ impl OperatorBuilder {
         vec
     }
 
-    pub(crate) fn build(self) -> Operator {
+    pub(crate) fn build(self, conf: &JobConf) -> Operator {
         let op_index = self.index();
         let mut outputs = Vec::new();
         for ob in self.outputs {
@@ -500,6 +545,11 @@ impl OperatorBuilder {
             }
             GeneralOperator::Notifiable(op) => op,
         };
+        let metrics = if conf.metrics_enable {
+            Some(OperatorMetrics::new(conf, self.worker_id, &self.info))
+        } else {
+            None
+        };
         Operator {
             info: self.info,
             inputs: self.inputs,
@@ -507,6 +557,7 @@ impl OperatorBuilder {
             core,
             fire_times: 0,
             exec_st: UnsafeRcPtr::new(Cell::new(0)),
+            metrics,
         }
     }
 }
@@ -13,10 +13,15 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use crate::api::{IntoDataflow, Unary};
-use crate::errors::BuildJobError;
+use crossbeam_channel::TryRecvError;
+
+use crate::api::{IntoDataflow, StreamReceiver, Unary};
+use crate::communication::output::OutputProxy;
+use crate::communication::Output;
+use crate::errors::{BuildJobError, IOError, JobExecError};
+use crate::progress::{DynPeers, EndOfScope};
 use crate::stream::Stream;
-use crate::Data;
+use crate::{Data, Tag};
 
 impl<D: Data, T: IntoIterator<Item = D>> IntoDataflow<D> for T
 where
@@ -38,3 +43,86 @@ where
         })
     }
 }
+
+impl<D: Data> StreamReceiver<D> {
+    pub(crate) fn into_dataflow(self, entry: Stream<D>) -> Result<Stream<D>, BuildJobError> {
+        entry.enter()?.unary("stream_source", |_info| {
+            let peers = crate::worker_id::get_current_worker().total_peers();
+            let mut reader = EpochReader { receiver: self, epoch: 0, pending: None, peers };
+            move |input, output| {
+                // the end of scope [0] entered from the root triggers the reading, and is kept back
+                // until the input ends, so that this operator keeps being scheduled;
+                input.for_each_batch(|trigger| {
+                    if reader.poll(output)? {
+                        trigger.take_end();
+                        Ok(())
+                    } else {
+                        would_block!("wait for stream input")?
+                    }
+                })
+            }
+        })
+    }
+}
+
+struct EpochReader<D> {
+    receiver: StreamReceiver<D>,
+    /// The epoch being output, whose scope is `[epoch]`;
+    epoch: u32,
+    /// The data of a later epoch, received before ending the current one;
+    pending: Option<(u32, D)>,
+    peers: u32,
+}
+
+impl<D: Data> EpochReader<D> {
+    /// Output all data received, and end the epochs closed by senders, return true if the
+    /// input has ended;
+    fn poll(&mut self, output: &Output<D>) -> Result<bool, JobExecError> {
+        loop {
+            let (epoch, data) = match self.pending.take() {
+                Some(next) => next,
+                None => match self.receiver.rx.try_recv() {
+                    Ok(next) => next,
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        let total = self.receiver.progress.total();
+                        self.end_until(total, output)?;
+                        output.flush()?;
+                        return Ok(true);
+                    }
+                },
+            };
+            if epoch > self.epoch {
+                // data of earlier epochs is received before, so they can be ended;
+                self.pending = Some((epoch, data));
+                self.end_until(epoch, output)?;
+            } else {
+                let tag = Tag::inherit(&Tag::Root, self.epoch);
+                output.new_session(&tag)?.give(data)?;
+            }
+        }
+        // an epoch is closed after all its data has been sent, once it is drained from the channel
+        // it can be ended;
+        let closed = self.receiver.progress.closed();
+        if closed > self.epoch && self.receiver.rx.is_empty() {
+            self.end_until(closed, output)?;
+        }
+        output.flush()?;
+        // nothing is waited for here even if nothing is received: the worker is not ready while this
+        // operator is only blocked on its trigger, so it is polled again at the select interval of
+        // the executor, instead of parking the executor thread shared with other jobs;
+        Ok(false)
+    }
+
+    fn end_until(&mut self, epoch: u32, output: &Output<D>) -> Result<(), JobExecError> {
+        while self.epoch < epoch {
+            let tag = Tag::inherit(&Tag::Root, self.epoch);
+            let end = EndOfScope::new(tag.clone(), DynPeers::all(self.peers), 0, 0);
+            let result = output.new_session(&tag)?.notify_end(end);
+            // the end is buffered in output even if it would block;
+            self.epoch += 1;
+            result?;
+        }
+        Ok(())
+    }
+}
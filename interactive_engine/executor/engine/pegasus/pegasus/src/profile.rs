@@ -0,0 +1,160 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! Machine-readable export of the compiled dataflow plan and its runtime profile.
+//!
+//! For jobs with [`JobConf::profile_export`] set, the operators and channels of the dataflow are
+//! recorded when it is built, and the runtime stats of each operator are added as workers finish.
+//! After all workers of a server finished, the [`JobProfile`] is written as JSON into the export
+//! directory, named as `{job_name}_{job_id}_{server_index}.json`.
+//!
+//! [`JobConf::profile_export`]: crate::JobConf::profile_export
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::graph::Edge;
+pub use crate::graph::{Exchange, Port};
+use crate::JobConf;
+
+/// An operator in the dataflow plan;
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatorNode {
+    pub index: usize,
+    pub name: String,
+    pub scope_level: u32,
+    /// the id of the operator in the upstream plan which this operator is compiled from;
+    pub plan_id: Option<usize>,
+}
+
+/// A channel between two operators in the dataflow plan;
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelNode {
+    pub id: usize,
+    pub source: Port,
+    pub target: Port,
+    pub scope_level: usize,
+    pub exchange: Exchange,
+}
+
+impl From<&Edge> for ChannelNode {
+    fn from(edge: &Edge) -> Self {
+        ChannelNode {
+            id: edge.id,
+            source: edge.source,
+            target: edge.target,
+            scope_level: edge.scope_level,
+            exchange: edge.exchange,
+        }
+    }
+}
+
+/// The runtime stats of an operator in a worker;
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OperatorStats {
+    pub operator: usize,
+    pub worker: u32,
+    pub fire_times: u64,
+    /// total time(us) the operator spends in firing;
+    pub busy_us: u64,
+    pub records_in: u64,
+    pub batches_in: u64,
+    pub records_out: u64,
+    pub batches_out: u64,
+}
+
+/// The dataflow plan of a job in a server, with the runtime stats of its operators;
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobProfile {
+    pub job_id: u64,
+    pub job_name: String,
+    pub server_index: u32,
+    pub total_workers: u32,
+    /// operators ordered by their index, the root of the dataflow is at 0;
+    pub operators: Vec<OperatorNode>,
+    pub channels: Vec<ChannelNode>,
+    pub stats: Vec<OperatorStats>,
+}
+
+impl JobProfile {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// The stats of the operator at `index` in all workers;
+    pub fn stats_of(&self, index: usize) -> impl Iterator<Item = &OperatorStats> {
+        self.stats
+            .iter()
+            .filter(move |s| s.operator == index)
+    }
+}
+
+lazy_static! {
+    static ref JOB_PROFILES: Mutex<HashMap<(u64, u32), JobProfile>> = Mutex::new(HashMap::new());
+}
+
+/// Record the dataflow plan of a job in this server, the plan built by other workers is ignored;
+pub(crate) fn add_plan(plan: JobProfile) {
+    if let Ok(mut profiles) = JOB_PROFILES.lock() {
+        profiles
+            .entry((plan.job_id, plan.server_index))
+            .or_insert(plan);
+    } else {
+        error!("JOB_PROFILES is poisoned!");
+    }
+}
+
+pub(crate) fn add_stats(job_id: u64, server_index: u32, stats: Vec<OperatorStats>) {
+    if let Ok(mut profiles) = JOB_PROFILES.lock() {
+        if let Some(profile) = profiles.get_mut(&(job_id, server_index)) {
+            profile.stats.extend(stats);
+        }
+    } else {
+        error!("JOB_PROFILES is poisoned!");
+    }
+}
+
+/// Write the profile of a finished job into the export directory, and release it;
+pub(crate) fn export(conf: &JobConf, server_index: u32) {
+    let profile = remove(conf.job_id, server_index);
+    if let (Some(mut profile), Some(dir)) = (profile, conf.profile_export.as_ref()) {
+        profile
+            .stats
+            .sort_by_key(|s| (s.operator, s.worker));
+        let mut path = PathBuf::from(dir);
+        path.push(format!("{}_{}_{}.json", conf.job_name, conf.job_id, server_index));
+        let result = profile
+            .to_json()
+            .map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            error!("export profile of job[{}] to {:?} failure: {}", conf.job_id, path, e);
+        }
+    }
+}
+
+pub(crate) fn remove(job_id: u64, server_index: u32) -> Option<JobProfile> {
+    JOB_PROFILES
+        .lock()
+        .ok()
+        .and_then(|mut profiles| profiles.remove(&(job_id, server_index)))
+}
@@ -61,6 +61,17 @@ impl<D: Data> Stream<D> {
         self.builder.worker_id
     }
 
+    /// Set the id of the operator in an upstream plan, e.g. the physical plan of a query, which the
+    /// operators added to the dataflow after are compiled from. The id is exported with the dataflow
+    /// plan to map its operators back, set `None` to stop tagging operators;
+    pub fn set_plan_id(&self, plan_id: Option<usize>) {
+        self.builder.set_plan_id(plan_id);
+    }
+
+    pub fn get_plan_id(&self) -> Option<usize> {
+        self.builder.get_plan_id()
+    }
+
     pub fn get_upstream_port(&self) -> Port {
         self.upstream.get_port()
     }
@@ -395,11 +406,12 @@ impl<D: Data> Stream<D> {
         let target = op.next_input_port();
         let ch = std::mem::replace(&mut self.ch, Channel::default());
         let channel = ch.connect_to(target, &self.builder)?;
+        let exchange = channel.exchange();
         let (push, pull, notify) = channel.take();
         let ch_info = push.ch_info;
         self.upstream.set_push(push);
         op.add_input(ch_info, pull, notify, &self.builder.event_emitter);
-        let edge = Edge::new(ch_info);
+        let edge = Edge::new(ch_info, exchange);
         Ok(edge)
     }
 
@@ -151,6 +151,9 @@ impl<D: Data, T: Debug + Send + 'static> Worker<D, T> {
     fn release(&mut self) {
         if self.peer_guard.load(Ordering::SeqCst) == 0 {
             pegasus_memory::alloc::remove_task(self.conf.job_id as usize);
+            if self.conf.profile_export.is_some() {
+                crate::profile::remove(self.conf.job_id, self.id.server_index);
+            }
         }
         if !crate::remove_cancel_hook(self.conf.job_id).is_ok() {
             error!("JOB_CANCEL_MAP is poisoned!");
@@ -269,8 +272,17 @@ impl<D: Data, T: Debug + Send + 'static> Task for Worker<D, T> {
                         .set_attribute(KeyValue::new("used_ms", elapsed.to_string()));
                     self.span.set_status(trace::Status::Ok);
                     self.span.end();
+                    if self.conf.profile_export.is_some() {
+                        if let WorkerTask::Dataflow(df, _) = &self.task {
+                            let stats = df.take_stats();
+                            crate::profile::add_stats(self.conf.job_id, self.id.server_index, stats);
+                        }
+                    }
                     // if this is last worker, return Finished
                     if self.peer_guard.fetch_sub(1, Ordering::SeqCst) == 1 {
+                        if self.conf.profile_export.is_some() {
+                            crate::profile::export(&self.conf, self.id.server_index);
+                        }
                         state
                     } else {
                         // if other workers are not finished, return NotReady until all workers finished
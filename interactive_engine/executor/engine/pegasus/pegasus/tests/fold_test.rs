@@ -14,9 +14,11 @@
 //! limitations under the License.
 //
 
+use std::collections::{HashMap, HashSet};
+
 use pegasus::api::{
-    Collect, CorrelatedSubTask, Count, Filter, Fold, FoldByKey, HasAny, KeyBy, Limit, Map, Sink, SortBy,
-    SortLimitBy,
+    Collect, CorrelatedSubTask, Count, Filter, Fold, FoldByKey, HasAny, KeyBy, Limit, Map, PartitionByKey,
+    Sink, SkewConf, SortBy, SortLimitBy,
 };
 use pegasus::JobConf;
 
@@ -252,6 +254,74 @@ fn fold_by_key_test() {
     assert_eq!(*cnt_3, (0..num * 2).filter(|x| x % 4 == 3).count() as u32);
 }
 
+#[test]
+fn fold_by_key_adaptive_test() {
+    let mut conf = JobConf::new("fold_by_key_adaptive");
+    conf.set_workers(4);
+    let num = 4000u32;
+    let mut result = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        let src = index * num..(index + 1) * num;
+        move |input, output| {
+            input
+                .input_from(src)?
+                // the key 0 takes half of the records;
+                .key_by(|x| Ok((if x % 2 == 0 { 0 } else { x % 100 }, x as u64)))?
+                .fold_by_key_adaptive(
+                    (0u32, 0u64),
+                    SkewConf::new(0.1, 100, 4),
+                    || |(cnt, sum), x| Ok((cnt + 1, sum + x)),
+                    |a, b| Ok((a.0 + b.0, a.1 + b.1)),
+                )?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure:");
+    let groups = result.next().unwrap().unwrap();
+    assert!(result.next().is_none());
+
+    let mut expected = HashMap::new();
+    for x in 0..num * 4 {
+        let key = if x % 2 == 0 { 0 } else { x % 100 };
+        let (cnt, sum) = expected.entry(key).or_insert((0u32, 0u64));
+        *cnt += 1;
+        *sum += x as u64;
+    }
+    assert_eq!(groups, expected);
+}
+
+#[test]
+fn partition_by_key_adaptive_test() {
+    let mut conf = JobConf::new("partition_by_key_adaptive");
+    conf.set_workers(4);
+    let mut result = pegasus::run(conf, || {
+        move |input, output| {
+            input
+                .input_from(0..4000u32)?
+                .key_by(|x| Ok((if x % 2 == 0 { 0 } else { x }, x)))?
+                .partition_by_key_adaptive(SkewConf::new(0.1, 100, 3))
+                .map(|pair| Ok((pegasus::get_current_worker().index, pair.key, pair.value)))?
+                .collect::<Vec<_>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure:");
+    let records = result.next().unwrap().unwrap();
+    assert_eq!(records.len(), 16000);
+    // the cold keys are kept on one worker, while the hot key 0 is spread over 3 workers;
+    let mut workers = HashMap::new();
+    for (worker, key, _) in records {
+        workers
+            .entry(key)
+            .or_insert_with(HashSet::new)
+            .insert(worker);
+    }
+    assert_eq!(workers[&0].len(), 3);
+    assert!(workers
+        .iter()
+        .all(|(key, workers)| *key == 0 || workers.len() == 1));
+}
+
 #[test]
 fn fold_partition_by_key_test() {
     let mut conf = JobConf::new("fold_partition_by_key");
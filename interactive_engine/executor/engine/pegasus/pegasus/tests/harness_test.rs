@@ -0,0 +1,198 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+//
+
+use std::time::{Duration, Instant};
+
+use pegasus::api::{Limit, Map, Sink};
+use pegasus::harness::{LocalCluster, Schedule};
+use pegasus::result::ResultStream;
+use pegasus::JobConf;
+
+/// Every worker of the cluster emits 100 numbers, which are exchanged to workers by value, and
+/// returns `(worker index, number)` pairs received by each worker;
+fn exchange(cluster: &LocalCluster, name: &str) -> Vec<(u32, u64)> {
+    let mut conf = JobConf::new(name);
+    conf.set_workers(2);
+    let results = cluster
+        .run(conf, || {
+            |input, output| {
+                let index = pegasus::get_current_worker().index as u64;
+                input
+                    .input_from(index * 1000..index * 1000 + 100)?
+                    .repartition(|x| Ok(*x))
+                    .map(|x| Ok((pegasus::get_current_worker().index, x)))?
+                    .sink_into(output)
+            }
+        })
+        .expect("submit job failure");
+    let mut received = results
+        .into_iter()
+        .flat_map(|stream| stream.map(|item| item.expect("job failure")))
+        .collect::<Vec<_>>();
+    received.sort();
+    received
+}
+
+fn check_exchange(cluster: &LocalCluster, received: Vec<(u32, u64)>) {
+    let peers = cluster.servers().len() as u64 * 2;
+    let mut expected = vec![];
+    for index in 0..peers {
+        for x in index * 1000..index * 1000 + 100 {
+            expected.push(((x % peers) as u32, x));
+        }
+    }
+    expected.sort();
+    assert_eq!(received, expected);
+}
+
+#[test]
+fn harness_exchange_fifo_test() {
+    let cluster = LocalCluster::start(3, Schedule::Fifo).unwrap();
+    let received = exchange(&cluster, "harness_exchange_fifo_test");
+    check_exchange(&cluster, received);
+}
+
+#[test]
+fn harness_exchange_seeded_test() {
+    for seed in 0..3 {
+        let cluster = LocalCluster::start(3, Schedule::Seeded(seed)).unwrap();
+        let received = exchange(&cluster, "harness_exchange_seeded_test");
+        check_exchange(&cluster, received);
+    }
+}
+
+#[test]
+fn harness_exchange_delay_test() {
+    let cluster = LocalCluster::start(2, Schedule::Fifo).unwrap();
+    let servers = cluster.servers().to_vec();
+    cluster
+        .network()
+        .set_delay(servers[1], servers[0], Duration::from_millis(100));
+    let received = exchange(&cluster, "harness_exchange_delay_test");
+    check_exchange(&cluster, received);
+}
+
+#[test]
+fn harness_exchange_replay_test() {
+    let (expected, trace) = {
+        let cluster = LocalCluster::start(2, Schedule::Seeded(11)).unwrap();
+        let received = exchange(&cluster, "harness_exchange_replay_test");
+        (received, cluster.network().trace())
+    };
+    let cluster = LocalCluster::start(2, Schedule::Replay(trace)).unwrap();
+    let received = exchange(&cluster, "harness_exchange_replay_test");
+    assert_eq!(received, expected);
+    check_exchange(&cluster, received);
+}
+
+/// Submit an endless job exchanging data among servers;
+fn run_endless(cluster: &LocalCluster, name: &str) -> Vec<ResultStream<u64>> {
+    let mut conf = JobConf::new(name);
+    conf.set_workers(2);
+    cluster
+        .run(conf, || {
+            |input, output| {
+                input
+                    .input_from(0..)?
+                    .repartition(|x| Ok(*x))
+                    .sink_into(output)
+            }
+        })
+        .expect("submit job failure")
+}
+
+/// Drain a result stream in another thread until it ends or fails, and return whether it is
+/// drained in `timeout`, with the number of results and whether it fails;
+fn drain(stream: ResultStream<u64>, timeout: Duration) -> Option<(usize, bool)> {
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    std::thread::spawn(move || {
+        let mut count = 0;
+        let mut failed = false;
+        for item in stream {
+            match item {
+                Ok(_) => count += 1,
+                Err(_) => {
+                    failed = true;
+                    break;
+                }
+            }
+        }
+        tx.send((count, failed)).ok();
+    });
+    rx.recv_timeout(timeout).ok()
+}
+
+#[test]
+fn harness_cancel_test() {
+    let cluster = LocalCluster::start(2, Schedule::Seeded(3)).unwrap();
+    let mut results = run_endless(&cluster, "harness_cancel_test");
+    let mut first = results.remove(0);
+    let start = Instant::now();
+    let mut count = 0;
+    while count < 1000 {
+        first.next();
+        count += 1;
+    }
+    first.cancel();
+    for stream in results.iter() {
+        stream.cancel();
+    }
+    assert!(drain(first, Duration::from_secs(10)).is_some(), "job not canceled");
+    for stream in results {
+        assert!(drain(stream, Duration::from_secs(10)).is_some(), "job not canceled");
+    }
+    assert!(start.elapsed() < Duration::from_secs(20));
+}
+
+#[test]
+fn harness_early_stop_test() {
+    let cluster = LocalCluster::start(3, Schedule::Seeded(5)).unwrap();
+    let mut conf = JobConf::new("harness_early_stop_test");
+    conf.set_workers(2);
+    let results = cluster
+        .run(conf, || {
+            |input, output| {
+                input
+                    .input_from(0u64..)?
+                    .repartition(|x| Ok(*x))
+                    .limit(10)?
+                    .sink_into(output)
+            }
+        })
+        .expect("submit job failure");
+    let mut count = 0;
+    for stream in results {
+        let (n, failed) = drain(stream, Duration::from_secs(10)).expect("job not stopped early");
+        assert!(!failed);
+        count += n;
+    }
+    assert_eq!(count, 10);
+}
+
+#[test]
+fn harness_drop_server_test() {
+    let cluster = LocalCluster::start(2, Schedule::Fifo).unwrap();
+    let mut results = run_endless(&cluster, "harness_drop_server_test");
+    let mut first = results.remove(0);
+    for _ in 0..1000 {
+        first.next();
+    }
+    cluster
+        .network()
+        .drop_server(cluster.servers()[1]);
+    let (_, failed) = drain(first, Duration::from_secs(10)).expect("job hangs after a peer is dropped");
+    assert!(failed);
+}
@@ -18,7 +18,8 @@
 extern crate lazy_static;
 
 use pegasus::api::{
-    Collect, CorrelatedSubTask, Count, HasAny, Iteration, Limit, Map, Merge, Sink, SortLimit, SortLimitBy,
+    Collect, CorrelatedSubTask, Count, HasAny, Iteration, KeyBy, Limit, Map, Merge, SampleByKey, Sink,
+    SortLimit, SortLimitBy, SortLimitByKey,
 };
 use pegasus::JobConf;
 
@@ -452,3 +453,224 @@ fn modern_graph_sort_limit_1_by_test() {
 
     assert_eq!(results, vec![1.0]);
 }
+
+#[test]
+fn skip_limit_test() {
+    let mut conf = JobConf::new("skip_limit_test");
+    conf.set_workers(2);
+    let mut result = pegasus::run(conf, || {
+        |input, output| {
+            input
+                .input_from(0..100u32)?
+                .repartition(|x: &u32| Ok(*x as u64))
+                .skip_limit(20, 10)?
+                .sink_into(output)
+        }
+    })
+    .expect("build job failure");
+
+    let mut count = 0;
+    while let Some(Ok(_)) = result.next() {
+        count += 1;
+    }
+
+    // 200 data in total, skipped over all partitions rather than per partition
+    assert_eq!(count, 10);
+}
+
+#[test]
+fn skip_limit_exceed_test() {
+    let mut conf = JobConf::new("skip_limit_exceed_test");
+    conf.set_workers(2);
+    let mut result = pegasus::run(conf, || {
+        |input, output| {
+            input
+                .input_from(0..10u32)?
+                .repartition(|x: &u32| Ok(*x as u64))
+                .skip_limit(15, 10)?
+                .sink_into(output)
+        }
+    })
+    .expect("build job failure");
+
+    let mut count = 0;
+    while let Some(Ok(_)) = result.next() {
+        count += 1;
+    }
+
+    assert_eq!(count, 5);
+}
+
+#[test]
+fn skip_limit_across_batches_test() {
+    let mut conf = JobConf::new("skip_limit_across_batches_test");
+    conf.batch_size = 16;
+    let mut result = pegasus::run(conf, || {
+        |input, output| {
+            input
+                .input_from(0..1000u32)?
+                .skip_limit(500, 10)?
+                .sink_into(output)
+        }
+    })
+    .expect("build job failure");
+
+    let mut data = vec![];
+    while let Some(Ok(d)) = result.next() {
+        data.push(d);
+    }
+    data.sort();
+    assert_eq!(data, (500..510u32).collect::<Vec<_>>());
+}
+
+#[test]
+fn sort_skip_limit_test() {
+    let mut conf = JobConf::new("sort_skip_limit_test");
+    let num_workers = 2;
+    conf.set_workers(num_workers);
+
+    let result_stream = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        move |input, output| {
+            input
+                .input_from((1_u32..2000).filter(move |x| *x % num_workers == index))?
+                .sort_skip_limit_by(20, 3, |x, y| x.cmp(y))?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+
+    let results: Vec<u32> = result_stream.map(|x| x.unwrap()).collect();
+
+    assert_eq!(results, vec![21_u32, 22, 23]);
+}
+
+#[test]
+fn multi_scope_sort_skip_limit_test() {
+    let mut conf = JobConf::new("multi_scope_sort_skip_limit_test");
+    let num_workers = 2;
+    conf.set_workers(num_workers);
+
+    let result_stream = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        move |input, output| {
+            input
+                .input_from((1_u32..5).filter(move |x| *x % num_workers == index))?
+                .apply(|sub| {
+                    sub.flat_map(|x| Ok(0..x))?
+                        .sort_skip_limit_by(1, 2, |x, y| x.cmp(y))?
+                        .collect::<Vec<u32>>()
+                })?
+                .sort_skip_limit_by(1, 2, |x, y| y.0.cmp(&x.0))?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+
+    let results: Vec<(u32, Vec<u32>)> = result_stream.map(|x| x.unwrap()).collect();
+
+    assert_eq!(results, vec![(3, vec![1, 2]), (2, vec![1])]);
+}
+
+#[test]
+fn sort_limit_by_key_test() {
+    let mut conf = JobConf::new("sort_limit_by_key_test");
+    let num_workers = 2;
+    conf.set_workers(num_workers);
+
+    let result_stream = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        move |input, output| {
+            input
+                .input_from((0_u32..1000).filter(move |x| *x % num_workers == index))?
+                .key_by(|x| Ok((x % 3, x)))?
+                .sort_limit_by_key(2, |a, b| b.value.cmp(&a.value))?
+                .map(|pair| Ok((pair.key, pair.value)))?
+                .collect::<Vec<(u32, u32)>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+
+    let mut results: Vec<(u32, u32)> = result_stream
+        .map(|x| x.unwrap())
+        .flatten()
+        .collect();
+    results.sort();
+
+    assert_eq!(results, vec![(0, 996), (0, 999), (1, 994), (1, 997), (2, 995), (2, 998)]);
+}
+
+#[test]
+fn sample_by_key_test() {
+    let mut conf = JobConf::new("sample_by_key_test");
+    let num_workers = 2;
+    conf.set_workers(num_workers);
+
+    let result_stream = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        move |input, output| {
+            input
+                .input_from((0_u32..1000).filter(move |x| *x % num_workers == index))?
+                .key_by(|x| Ok((x % 3, x)))?
+                .sample_by_key(5, |pair| (pair.value % 7) as f64)?
+                .map(|pair| Ok((pair.key, pair.value)))?
+                .collect::<Vec<(u32, u32)>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+
+    let results: Vec<(u32, u32)> = result_stream
+        .map(|x| x.unwrap())
+        .flatten()
+        .collect();
+
+    assert_eq!(results.len(), 15);
+    for k in 0..3 {
+        let group: Vec<&(u32, u32)> = results
+            .iter()
+            .filter(|(key, _)| *key == k)
+            .collect();
+        assert_eq!(group.len(), 5);
+        // the data of the largest priority 6 of each key are sampled
+        assert!(group.iter().all(|(_, v)| v % 7 == 6));
+    }
+}
+
+#[test]
+fn multi_scope_sort_limit_by_key_test() {
+    let mut conf = JobConf::new("multi_scope_sort_limit_by_key_test");
+    let num_workers = 2;
+    conf.set_workers(num_workers);
+
+    let result_stream = pegasus::run(conf, || {
+        let index = pegasus::get_current_worker().index;
+        move |input, output| {
+            input
+                .input_from((1_u32..5).filter(move |x| *x % num_workers == index))?
+                .apply(|sub| {
+                    sub.flat_map(|x| Ok(0..x * 2))?
+                        .key_by(|x| Ok((x % 2, x)))?
+                        .sort_limit_by_key(1, |a, b| a.value.cmp(&b.value))?
+                        .map(|pair| Ok(pair.value))?
+                        .collect::<Vec<u32>>()
+                })?
+                .map(|(x, mut mins)| {
+                    mins.sort();
+                    Ok((x, mins))
+                })?
+                .collect::<Vec<(u32, Vec<u32>)>>()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+
+    let mut results: Vec<(u32, Vec<u32>)> = result_stream
+        .map(|x| x.unwrap())
+        .flatten()
+        .collect();
+    results.sort();
+
+    assert_eq!(results, vec![(1, vec![0, 1]), (2, vec![0, 1]), (3, vec![0, 1]), (4, vec![0, 1])]);
+}
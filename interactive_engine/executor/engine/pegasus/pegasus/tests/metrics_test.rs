@@ -0,0 +1,128 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+//
+
+use pegasus::api::{Map, Sink};
+use pegasus::harness::{LocalCluster, Schedule};
+use pegasus::metrics::*;
+use pegasus::JobConf;
+
+lazy_static::lazy_static! {
+    static ref EXPORTER: InMemoryExporter = {
+        let exporter = InMemoryExporter::new();
+        exporter.install();
+        exporter
+    };
+}
+
+/// The sum of metric `name` of the job, over all data points matching `filter`;
+fn sum_of<F: Fn(&MetricPoint) -> bool>(job: &str, name: &str, filter: F) -> u64 {
+    EXPORTER
+        .collect()
+        .iter()
+        .filter(|p| p.name == name && p.attribute(JOB_NAME) == Some(job) && filter(p))
+        .map(|p| p.sum())
+        .sum()
+}
+
+#[test]
+fn metrics_records_test() {
+    let job = "metrics_records_test";
+    let mut conf = JobConf::new(job);
+    conf.set_workers(2);
+    conf.metrics_enable = true;
+    lazy_static::initialize(&EXPORTER);
+    let mut result = pegasus::run(conf, || {
+        |input, output| {
+            input
+                .input_from(0..100u64)?
+                .repartition(|x| Ok(*x))
+                .map(|x| Ok(x + 1))?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+    let mut count = 0;
+    while let Some(Ok(_)) = result.next() {
+        count += 1;
+    }
+    assert_eq!(count, 200);
+
+    let is_map = |p: &MetricPoint| p.attribute(OPERATOR_NAME) == Some("map");
+    assert_eq!(sum_of(job, RECORDS_IN, is_map), 200);
+    assert_eq!(sum_of(job, RECORDS_OUT, is_map), 200);
+    assert!(sum_of(job, BATCHES_IN, is_map) > 0);
+    assert!(sum_of(job, BATCHES_OUT, is_map) > 0);
+    let workers = EXPORTER
+        .collect()
+        .iter()
+        .filter(|p| p.name == RECORDS_IN && p.attribute(JOB_NAME) == Some(job) && is_map(p))
+        .map(|p| p.attribute(WORKER).unwrap().to_owned())
+        .collect::<std::collections::BTreeSet<_>>();
+    assert_eq!(workers.len(), 2);
+
+    let fires = EXPORTER
+        .collect()
+        .into_iter()
+        .filter(|p| p.name == FIRE_DURATION && p.attribute(JOB_NAME) == Some(job) && is_map(p))
+        .map(|p| match p.value {
+            MetricValue::Histogram { count, .. } => count,
+            _ => panic!("fire duration should be a histogram"),
+        })
+        .sum::<u64>();
+    assert!(fires > 0);
+}
+
+#[test]
+fn metrics_disabled_test() {
+    let job = "metrics_disabled_test";
+    let mut conf = JobConf::new(job);
+    conf.metrics_enable = false;
+    lazy_static::initialize(&EXPORTER);
+    let mut result = pegasus::run(conf, || |input, output| input.input_from(0..10u64)?.sink_into(output))
+        .expect("submit job failure");
+    while let Some(_) = result.next() {}
+    assert_eq!(sum_of(job, RECORDS_OUT, |_| true), 0);
+}
+
+#[test]
+fn metrics_bytes_sent_test() {
+    let job = "metrics_bytes_sent_test";
+    lazy_static::initialize(&EXPORTER);
+    let cluster = LocalCluster::start(2, Schedule::Fifo).unwrap();
+    let mut conf = JobConf::new(job);
+    conf.set_workers(1);
+    conf.metrics_enable = true;
+    let results = cluster
+        .run(conf, || {
+            |input, output| {
+                let index = pegasus::get_current_worker().index as u64;
+                input
+                    .input_from(index * 100..index * 100 + 100)?
+                    .repartition(|x| Ok(*x))
+                    .sink_into(output)
+            }
+        })
+        .expect("submit job failure");
+    let count = results
+        .into_iter()
+        .flat_map(|stream| stream.map(|item| item.expect("job failure")))
+        .count();
+    assert_eq!(count, 200);
+
+    let sent = sum_of(job, BYTES_SENT, |p| p.attribute(OPERATOR_NAME) == Some("source"));
+    // each server sends 50 numbers of 8 bytes to the other;
+    assert!(sent >= 2 * 50 * 8, "bytes sent {}", sent);
+}
@@ -0,0 +1,125 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+//
+
+use std::path::PathBuf;
+
+use pegasus::api::{Collect, Map, Sink};
+use pegasus::profile::{Exchange, JobProfile};
+use pegasus::JobConf;
+
+fn export_dir(name: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("pegasus_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn read_profile(dir: &PathBuf, conf: &JobConf) -> JobProfile {
+    let mut path = dir.clone();
+    path.push(format!("{}_{}_0.json", conf.job_name, conf.job_id));
+    let json = std::fs::read_to_string(&path).expect("profile not exported");
+    JobProfile::from_json(&json).expect("invalid profile")
+}
+
+#[test]
+fn profile_export_test() {
+    let dir = export_dir("profile_export_test");
+    let mut conf = JobConf::new("profile_export_test");
+    conf.set_workers(2);
+    conf.profile_export = Some(dir.to_str().unwrap().to_owned());
+    let mut result = pegasus::run(conf.clone(), || {
+        |input, output| {
+            let stream = input.input_from(0..100u64)?;
+            stream.set_plan_id(Some(1));
+            let stream = stream
+                .repartition(|x| Ok(*x))
+                .map(|x| Ok(x + 1))?;
+            stream.set_plan_id(Some(2));
+            stream.collect::<Vec<u64>>()?.sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+    let mut count = 0;
+    while let Some(Ok(_)) = result.next() {
+        count += 1;
+    }
+    assert_eq!(count, 1);
+
+    let profile = read_profile(&dir, &conf);
+    assert_eq!(profile.job_id, conf.job_id);
+    assert_eq!(profile.total_workers, 2);
+    assert_eq!(profile.operators[0].name, "root");
+    for (i, op) in profile.operators.iter().enumerate() {
+        assert_eq!(op.index, i);
+    }
+
+    let map = profile
+        .operators
+        .iter()
+        .find(|op| op.name == "map")
+        .expect("map not found");
+    assert_eq!(map.plan_id, Some(1));
+    let collect = profile
+        .operators
+        .iter()
+        .find(|op| op.name == "fold")
+        .expect("fold of collect not found");
+    assert_eq!(collect.plan_id, Some(2));
+
+    let to_map = profile
+        .channels
+        .iter()
+        .find(|ch| ch.target.index == map.index)
+        .expect("channel to map not found");
+    assert_eq!(to_map.exchange, Exchange::Shuffle);
+    let to_collect = profile
+        .channels
+        .iter()
+        .find(|ch| ch.target.index == collect.index)
+        .expect("channel to collect not found");
+    assert_eq!(to_collect.exchange, Exchange::Aggregate);
+
+    assert_eq!(profile.stats_of(map.index).count(), 2);
+    let records_in: u64 = profile
+        .stats_of(map.index)
+        .map(|s| s.records_in)
+        .sum();
+    let records_out: u64 = profile
+        .stats_of(map.index)
+        .map(|s| s.records_out)
+        .sum();
+    assert_eq!(records_in, 200);
+    assert_eq!(records_out, 200);
+    assert!(profile
+        .stats_of(map.index)
+        .all(|s| s.fire_times > 0));
+
+    let json = profile.to_json().unwrap();
+    assert_eq!(JobProfile::from_json(&json).unwrap(), profile);
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn profile_not_exported_test() {
+    let dir = export_dir("profile_not_exported_test");
+    let conf = JobConf::new("profile_not_exported_test");
+    let mut result =
+        pegasus::run(conf.clone(), || |input, output| input.input_from(0..10u64)?.sink_into(output))
+            .expect("submit job failure");
+    while let Some(_) = result.next() {}
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+    std::fs::remove_dir_all(&dir).ok();
+}
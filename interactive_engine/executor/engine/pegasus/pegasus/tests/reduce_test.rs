@@ -14,7 +14,9 @@
 //! limitations under the License.
 //
 
-use pegasus::api::{CorrelatedSubTask, KeyBy, Map, Reduce, ReduceByKey, Sink};
+use std::collections::HashMap;
+
+use pegasus::api::{CorrelatedSubTask, KeyBy, Map, Reduce, ReduceByKey, Sink, SkewConf};
 use pegasus::JobConf;
 
 #[test]
@@ -130,3 +132,30 @@ fn reduce_by_key_test() {
             * 2
     );
 }
+
+#[test]
+fn reduce_by_key_adaptive_test() {
+    let mut conf = JobConf::new("reduce_by_key_adaptive_test");
+    conf.set_workers(3);
+
+    let mut results = pegasus::run(conf, || {
+        |input, output| {
+            input
+                .input_from(0..3000u32)?
+                // a few keys take most of the records;
+                .key_by(|x| Ok((if x % 10 < 8 { x % 3 } else { x }, 1u32)))?
+                .reduce_by_key_adaptive(SkewConf::new(0.05, 64, 3), || |a, b| Ok(a + b))?
+                .sink_into(output)
+        }
+    })
+    .expect("");
+
+    let groups = results.next().unwrap().unwrap();
+    assert!(results.next().is_none());
+    let mut expected = HashMap::new();
+    for x in 0..3000u32 {
+        let key = if x % 10 < 8 { x % 3 } else { x };
+        *expected.entry(key).or_insert(0) += 3;
+    }
+    assert_eq!(groups, expected);
+}
@@ -0,0 +1,205 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+//
+use pegasus::api::{stream_channel, Collect, Count, Map, Sink};
+use pegasus::profile::JobProfile;
+use pegasus::JobConf;
+
+#[test]
+fn stream_source_per_epoch_count_test() {
+    let (tx, rx) = stream_channel::<u64>();
+    let mut conf = JobConf::new("stream_source_per_epoch_count_test");
+    conf.set_workers(2);
+    let mut results = pegasus::run(conf, move || {
+        let rx = rx.clone();
+        move |input, output| {
+            input
+                .input_from_stream(rx.clone())?
+                .repartition(|x| Ok(*x))
+                .count()?
+                .into_stream()?
+                .leave()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+
+    // each result is read before the next epoch is sent;
+    for epoch in 0..5u64 {
+        assert_eq!(tx.epoch() as u64, epoch);
+        for i in 0..epoch * 10 {
+            tx.send(i).unwrap();
+        }
+        assert_eq!(tx.advance() as u64, epoch);
+        assert_eq!(results.next().unwrap().unwrap(), epoch * 10);
+    }
+    drop(tx);
+    assert!(results.next().is_none());
+}
+
+#[test]
+fn stream_source_open_epoch_test() {
+    let (tx, rx) = stream_channel::<u64>();
+    let mut conf = JobConf::new("stream_source_open_epoch_test");
+    conf.set_workers(2);
+    let results = pegasus::run(conf, move || {
+        let rx = rx.clone();
+        move |input, output| {
+            input
+                .input_from_stream(rx.clone())?
+                .count()?
+                .into_stream()?
+                .leave()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+
+    tx.send(1).unwrap();
+    tx.advance();
+    // an empty epoch is still ended;
+    tx.advance();
+    tx.send(2).unwrap();
+    tx.send(3).unwrap();
+    // the open epoch is ended as the sender is dropped;
+    drop(tx);
+    // the result of each epoch is output by the worker owning it, so they are out of order;
+    let mut results = results
+        .map(|item| item.unwrap())
+        .collect::<Vec<u64>>();
+    results.sort();
+    assert_eq!(results, vec![0, 1, 2]);
+}
+
+#[test]
+fn stream_source_no_input_test() {
+    let (tx, rx) = stream_channel::<u64>();
+    let mut conf = JobConf::new("stream_source_no_input_test");
+    conf.set_workers(2);
+    let results = pegasus::run(conf, move || {
+        let rx = rx.clone();
+        move |input, output| {
+            input
+                .input_from_stream(rx.clone())?
+                .leave()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+    drop(tx);
+    assert_eq!(results.count(), 0);
+}
+
+#[test]
+fn stream_source_concurrent_senders_test() {
+    let (tx, rx) = stream_channel::<u64>();
+    let mut conf = JobConf::new("stream_source_concurrent_senders_test");
+    conf.set_workers(3);
+    let results = pegasus::run(conf, move || {
+        let rx = rx.clone();
+        move |input, output| {
+            input
+                .input_from_stream(rx.clone())?
+                .repartition(|x| Ok(*x))
+                .map(|x| Ok(x * 2))?
+                .collect::<Vec<_>>()?
+                .into_stream()?
+                .leave()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+
+    // epochs are closed while senders are sending, without waiting for results;
+    let mut senders = vec![];
+    for s in 0..2u64 {
+        let tx = tx.clone();
+        senders.push(std::thread::spawn(move || {
+            for i in 0..100u64 {
+                tx.send(s * 1000 + i).unwrap();
+            }
+        }));
+    }
+    let mut epochs = 0;
+    while senders.iter().any(|s| !s.is_finished()) {
+        tx.advance();
+        epochs += 1;
+        std::thread::sleep(std::time::Duration::from_micros(100));
+    }
+    drop(tx);
+
+    // one result for each closed epoch, and maybe one for the epoch open when senders are dropped;
+    let results = results
+        .map(|item| item.unwrap())
+        .collect::<Vec<_>>();
+    assert!(results.len() == epochs || results.len() == epochs + 1);
+    let mut all = results
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    all.sort();
+    let mut expected = (0..100u64)
+        .chain(1000..1100)
+        .map(|x| x * 2)
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(all, expected);
+}
+
+#[test]
+fn stream_source_idle_test() {
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("pegasus_stream_source_idle_test_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (tx, rx) = stream_channel::<u64>();
+    let mut conf = JobConf::new("stream_source_idle_test");
+    conf.profile_export = Some(dir.to_str().unwrap().to_owned());
+    let mut results = pegasus::run(conf.clone(), move || {
+        let rx = rx.clone();
+        move |input, output| {
+            input
+                .input_from_stream(rx.clone())?
+                .leave()?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+    tx.send(1).unwrap();
+    assert_eq!(results.next().unwrap().unwrap(), 1);
+    // no input for a while, the idle source should not be fired continuously;
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    tx.send(2).unwrap();
+    assert_eq!(results.next().unwrap().unwrap(), 2);
+    drop(tx);
+    assert!(results.next().is_none());
+
+    dir.push(format!("{}_{}_0.json", conf.job_name, conf.job_id));
+    let json = std::fs::read_to_string(&dir).expect("profile not exported");
+    let profile = JobProfile::from_json(&json).unwrap();
+    let source = profile
+        .operators
+        .iter()
+        .find(|op| op.name == "stream_source")
+        .expect("stream source not found");
+    let fire_times: u64 = profile
+        .stats_of(source.index)
+        .map(|s| s.fire_times)
+        .sum();
+    // it is polled about once per select interval(1ms) of the executor when idle, while spinning
+    // fires it hundreds of thousands of times in 200ms;
+    assert!(fire_times < 2000, "stream source fired {} times", fire_times);
+    dir.pop();
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,221 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+//
+use pegasus::api::{Map, Pair, Sink, Superstep, SuperstepConf};
+use pegasus::JobConf;
+
+fn collect_pairs<V: Clone>(results: pegasus::result::ResultStream<Pair<u32, V>>) -> Vec<(u32, V)> {
+    let mut results = results
+        .map(|item| {
+            let pair = item.unwrap();
+            (pair.key, pair.value)
+        })
+        .collect::<Vec<_>>();
+    results.sort_by_key(|(k, _)| *k);
+    results
+}
+
+#[test]
+fn superstep_connected_components_test() {
+    // components: 0-1-2-3, 4-5, 6;
+    let edges = vec![(0u32, 1u32), (1, 2), (2, 3), (4, 5)];
+    let mut conf = JobConf::new("superstep_connected_components_test");
+    conf.set_workers(2);
+    let results = pegasus::run(conf, move || {
+        let edges = edges.clone();
+        move |input, output| {
+            let index = input.get_worker_index();
+            let vertices = (0..7u32)
+                .filter(|v| v % 2 == index)
+                .map(|v| {
+                    let neighbors = edges
+                        .iter()
+                        .filter_map(|(s, t)| {
+                            if *s == v {
+                                Some(*t)
+                            } else if *t == v {
+                                Some(*s)
+                            } else {
+                                None
+                            }
+                        })
+                        .collect::<Vec<u32>>();
+                    Pair { key: v, value: (v, neighbors) }
+                })
+                .collect::<Vec<_>>();
+            let conf = SuperstepConf::<u32, ()>::new(100).combine_with(|a, b| a.min(b));
+            input
+                .input_from(vertices)?
+                .superstep(conf, |ctx| {
+                    let min = ctx.take_messages().into_iter().min();
+                    let changed = match min {
+                        Some(label) if label < ctx.value().0 => {
+                            ctx.value_mut().0 = label;
+                            true
+                        }
+                        _ => ctx.superstep() == 0,
+                    };
+                    if changed {
+                        let (label, neighbors) = ctx.value().clone();
+                        for n in neighbors {
+                            ctx.send_to(n, label);
+                        }
+                    }
+                    ctx.vote_to_halt();
+                    Ok(())
+                })?
+                .map(|pair| Ok(Pair { key: pair.key, value: pair.value.0 }))?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+
+    let results = collect_pairs(results);
+    assert_eq!(results, vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 4), (5, 4), (6, 6)]);
+}
+
+#[test]
+fn superstep_global_aggregate_test() {
+    let mut conf = JobConf::new("superstep_global_aggregate_test");
+    conf.set_workers(2);
+    let results = pegasus::run(conf, || {
+        move |input, output| {
+            let index = input.get_worker_index();
+            let vertices = (index * 5..(index + 1) * 5).map(|v| Pair { key: v, value: 0u64 });
+            let conf = SuperstepConf::<(), u64>::new(10).aggregate_with(0, |a, b| a + b);
+            input
+                .input_from(vertices)?
+                .superstep(conf, |ctx| {
+                    if ctx.superstep() == 0 {
+                        ctx.aggregate(1);
+                    } else {
+                        *ctx.value_mut() = *ctx.aggregated().unwrap();
+                        ctx.vote_to_halt();
+                    }
+                    Ok(())
+                })?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+
+    let results = collect_pairs(results);
+    assert_eq!(
+        results,
+        (0..10u32)
+            .map(|v| (v, 10u64))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn superstep_halt_when_test() {
+    let mut conf = JobConf::new("superstep_halt_when_test");
+    conf.set_workers(2);
+    let results = pegasus::run(conf, || {
+        move |input, output| {
+            let index = input.get_worker_index();
+            let vertices = (index * 2..(index + 1) * 2).map(|v| Pair { key: v, value: (100u64, 0u32) });
+            // the sum of values goes 400, 200, 100, 48, and stops after 4 supersteps;
+            let conf = SuperstepConf::<(), u64>::new(100)
+                .aggregate_with(0, |a, b| a + b)
+                .halt_when(|sum| *sum < 50);
+            input
+                .input_from(vertices)?
+                .superstep(conf, |ctx| {
+                    let (value, steps) = *ctx.value();
+                    ctx.aggregate(value);
+                    *ctx.value_mut() = (value / 2, steps + 1);
+                    Ok(())
+                })?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+
+    let results = collect_pairs(results);
+    assert_eq!(
+        results,
+        (0..4u32)
+            .map(|v| (v, (6u64, 4u32)))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn superstep_max_supersteps_test() {
+    let mut conf = JobConf::new("superstep_max_supersteps_test");
+    conf.set_workers(2);
+    let results = pegasus::run(conf, || {
+        move |input, output| {
+            let index = input.get_worker_index();
+            let vertices = (index * 5..(index + 1) * 5).map(|v| Pair { key: v, value: v });
+            let conf = SuperstepConf::<u32, ()>::new(5);
+            input
+                .input_from(vertices)?
+                .superstep(conf, |ctx| {
+                    *ctx.value_mut() += 1;
+                    Ok(())
+                })?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+
+    let results = collect_pairs(results);
+    assert_eq!(
+        results,
+        (0..10u32)
+            .map(|v| (v, v + 5))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn superstep_combiner_test() {
+    let mut conf = JobConf::new("superstep_combiner_test");
+    conf.set_workers(2);
+    let results = pegasus::run(conf, || {
+        move |input, output| {
+            let index = input.get_worker_index();
+            let vertices = (index * 5..(index + 1) * 5).map(|v| Pair { key: v, value: (0u32, 0u32) });
+            let conf = SuperstepConf::<u32, ()>::new(10).combine_with(|a, b| a + b);
+            input
+                .input_from(vertices)?
+                .superstep(conf, |ctx| {
+                    if ctx.superstep() == 0 {
+                        // every vertex sends 1 to vertex 0, and also to a vertex that doesn't exist;
+                        ctx.send_to(0, 1);
+                        ctx.send_to(100, 1);
+                    } else {
+                        let messages = ctx.messages().len() as u32;
+                        let sum = ctx.messages().iter().sum();
+                        *ctx.value_mut() = (messages, sum);
+                    }
+                    ctx.vote_to_halt();
+                    Ok(())
+                })?
+                .sink_into(output)
+        }
+    })
+    .expect("submit job failure");
+
+    let results = collect_pairs(results);
+    let mut expected = (0..10u32)
+        .map(|v| (v, (0, 0)))
+        .collect::<Vec<_>>();
+    expected[0].1 = (1, 10);
+    assert_eq!(results, expected);
+}
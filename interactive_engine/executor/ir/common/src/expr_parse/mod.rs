@@ -15,6 +15,7 @@
 //!
 
 pub mod error;
+pub mod simplify;
 pub mod token;
 
 use std::convert::{TryFrom, TryInto};
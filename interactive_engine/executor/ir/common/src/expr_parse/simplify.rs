@@ -0,0 +1,809 @@
+//
+//! Copyright 2023 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+//!
+
+//! A rule-based simplifier for `common_pb::Expression`, which is applied in the planner before
+//! the expression is shipped to the runtime. It currently:
+//!  * folds constant sub-expressions, e.g. `1 + 2 > 4` into `false`;
+//!  * eliminates `true`/`false` operands of `&&` and `||`;
+//!  * normalizes `!` over a comparison, e.g. `!(@.age < 10)` into `@.age >= 10`;
+//!  * flattens nested `&&`/`||`, e.g. `@.a && (@.b && @.c)` into `@.a && @.b && @.c`;
+//!  * converts a chain of `==` on the same variable joined by `||` into `within`,
+//!    e.g. `@.id == 1 || @.id == 2` into `@.id within [1, 2]`.
+//!
+//! The simplifier only rewrites expressions that consist of constants, variables, logical and
+//! arithmetic operators; any other expression is left as it is.
+
+use std::convert::TryFrom;
+
+use dyn_type::Object;
+
+use crate::expr_parse::ExprToken;
+use crate::generated::common as pb;
+
+/// The precedence of an operand, which is always higher than that of any operator.
+const OPERAND_PRECEDENCE: i32 = 200;
+
+#[derive(Clone, Debug, PartialEq)]
+enum ExprNode {
+    /// A constant, a variable or a dynamic parameter
+    Leaf(pb::ExprOpr),
+    /// `!` or `isNull`
+    Unary(pb::ExprOpr, Box<ExprNode>),
+    /// A binary logical or arithmetic operator
+    Binary(pb::ExprOpr, Box<ExprNode>, Box<ExprNode>),
+    /// An n-ary `&&` or `||`
+    Junction(pb::ExprOpr, Vec<ExprNode>),
+}
+
+fn get_logical(opr: &pb::ExprOpr) -> Option<pb::Logical> {
+    match opr.item {
+        Some(pb::expr_opr::Item::Logical(l)) => pb::Logical::from_i32(l),
+        _ => None,
+    }
+}
+
+fn get_arith(opr: &pb::ExprOpr) -> Option<pb::Arithmetic> {
+    match opr.item {
+        Some(pb::expr_opr::Item::Arith(a)) => pb::Arithmetic::from_i32(a),
+        _ => None,
+    }
+}
+
+fn with_node_type(mut opr: pb::ExprOpr, node_type: &Option<pb::IrDataType>) -> pb::ExprOpr {
+    opr.node_type = node_type.clone();
+    opr
+}
+
+impl ExprNode {
+    fn opr(&self) -> &pb::ExprOpr {
+        match self {
+            ExprNode::Leaf(opr)
+            | ExprNode::Unary(opr, _)
+            | ExprNode::Binary(opr, _, _)
+            | ExprNode::Junction(opr, _) => opr,
+        }
+    }
+
+    fn precedence(&self) -> i32 {
+        match self {
+            ExprNode::Leaf(_) => OPERAND_PRECEDENCE,
+            _ => self.opr().precedence(),
+        }
+    }
+
+    fn as_const(&self) -> Option<&pb::Value> {
+        match self {
+            ExprNode::Leaf(pb::ExprOpr { item: Some(pb::expr_opr::Item::Const(val)), .. }) => Some(val),
+            _ => None,
+        }
+    }
+
+    fn as_var(&self) -> Option<&pb::Variable> {
+        match self {
+            ExprNode::Leaf(pb::ExprOpr { item: Some(pb::expr_opr::Item::Var(var)), .. }) => Some(var),
+            _ => None,
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match self.as_const() {
+            Some(pb::Value { item: Some(pb::value::Item::Boolean(b)) }) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Whether the node is guaranteed to be evaluated to a boolean (or null) value.
+    fn is_boolean(&self) -> bool {
+        match self {
+            ExprNode::Leaf(_) => self.as_bool().is_some(),
+            ExprNode::Unary(_, _) | ExprNode::Junction(_, _) => true,
+            ExprNode::Binary(opr, _, _) => get_logical(opr).is_some(),
+        }
+    }
+
+    /// If the node is in the form of `var == const`, or `const == var`, return the var and const.
+    fn as_var_eq_const(&self) -> Option<(&pb::Variable, &pb::Value)> {
+        if let ExprNode::Binary(opr, left, right) = self {
+            if get_logical(opr) == Some(pb::Logical::Eq) {
+                if let (Some(var), Some(val)) = (left.as_var(), right.as_const()) {
+                    return Some((var, val));
+                } else if let (Some(val), Some(var)) = (left.as_const(), right.as_var()) {
+                    return Some((var, val));
+                }
+            }
+        }
+        None
+    }
+
+    fn const_node(val: pb::Value, node_type: &Option<pb::IrDataType>) -> ExprNode {
+        ExprNode::Leaf(with_node_type(val.into(), node_type))
+    }
+
+    fn into_oprs(self, oprs: &mut Vec<pb::ExprOpr>) {
+        match self {
+            ExprNode::Leaf(opr) => oprs.push(opr),
+            ExprNode::Unary(opr, child) => {
+                // a prefix unary operator must brace an operand that binds no tighter than itself
+                let is_braced = child.precedence() <= opr.precedence();
+                oprs.push(opr);
+                child.into_oprs_braced(oprs, is_braced);
+            }
+            ExprNode::Binary(opr, left, right) => {
+                // all binary operators are left-associative
+                let (left_braced, right_braced) =
+                    (left.precedence() < opr.precedence(), right.precedence() <= opr.precedence());
+                left.into_oprs_braced(oprs, left_braced);
+                oprs.push(opr.clone());
+                right.into_oprs_braced(oprs, right_braced);
+            }
+            ExprNode::Junction(opr, children) => {
+                for (idx, child) in children.into_iter().enumerate() {
+                    if idx == 0 {
+                        let is_braced = child.precedence() < opr.precedence();
+                        child.into_oprs_braced(oprs, is_braced);
+                    } else {
+                        let is_braced = child.precedence() <= opr.precedence();
+                        oprs.push(opr.clone());
+                        child.into_oprs_braced(oprs, is_braced);
+                    }
+                }
+            }
+        }
+    }
+
+    fn into_oprs_braced(self, oprs: &mut Vec<pb::ExprOpr>, is_braced: bool) {
+        if is_braced {
+            oprs.push(pb::ExprOpr { node_type: None, item: Some(pb::expr_opr::Item::Brace(0)) });
+            self.into_oprs(oprs);
+            oprs.push(pb::ExprOpr { node_type: None, item: Some(pb::expr_opr::Item::Brace(1)) });
+        } else {
+            self.into_oprs(oprs);
+        }
+    }
+}
+
+/// Parse the infix operators into an expression tree, following the same precedence as
+/// `to_suffix_expr()`. Return `None` if the expression contains any unsupported operator,
+/// or is malformed.
+fn build_tree(oprs: &[pb::ExprOpr], is_flattened: &mut bool) -> Option<ExprNode> {
+    use pb::expr_opr::Item;
+    // the boolean indicates whether the node is wrapped by braces in the original expression
+    let mut nodes: Vec<(ExprNode, bool)> = Vec::with_capacity(oprs.len());
+    let mut stack: Vec<&pb::ExprOpr> = Vec::with_capacity(oprs.len());
+
+    fn apply(opr: &pb::ExprOpr, nodes: &mut Vec<(ExprNode, bool)>, is_flattened: &mut bool) -> Option<()> {
+        if let Some(logical) = get_logical(opr) {
+            if logical.is_unary() {
+                let (child, _) = nodes.pop()?;
+                nodes.push((ExprNode::Unary(opr.clone(), Box::new(child)), false));
+                return Some(());
+            } else if logical == pb::Logical::And || logical == pb::Logical::Or {
+                let (right, right_braced) = nodes.pop()?;
+                let (left, left_braced) = nodes.pop()?;
+                let mut children = vec![];
+                for (child, is_braced) in [(left, left_braced), (right, right_braced)] {
+                    match child {
+                        ExprNode::Junction(child_opr, grand_children)
+                            if get_logical(&child_opr) == Some(logical) =>
+                        {
+                            // a left-deep chain without braces is already flat
+                            if is_braced {
+                                *is_flattened = true;
+                            }
+                            children.extend(grand_children);
+                        }
+                        _ => children.push(child),
+                    }
+                }
+                nodes.push((ExprNode::Junction(opr.clone(), children), false));
+                return Some(());
+            }
+        } else if get_arith(opr).is_none() {
+            return None;
+        }
+        let (right, _) = nodes.pop()?;
+        let (left, _) = nodes.pop()?;
+        nodes.push((ExprNode::Binary(opr.clone(), Box::new(left), Box::new(right)), false));
+        Some(())
+    }
+
+    for opr in oprs {
+        match opr.item.as_ref()? {
+            Item::Const(_)
+            | Item::Var(_)
+            | Item::Vars(_)
+            | Item::VarMap(_)
+            | Item::Map(_)
+            | Item::Param(_) => nodes.push((ExprNode::Leaf(opr.clone()), false)),
+            Item::Brace(0) => stack.push(opr),
+            Item::Brace(_) => {
+                loop {
+                    let top = stack.pop()?;
+                    if top.is_left_brace() {
+                        break;
+                    }
+                    apply(top, &mut nodes, is_flattened)?;
+                }
+                nodes.last_mut()?.1 = true;
+            }
+            Item::Logical(_) | Item::Arith(_) => {
+                while let Some(top) = stack.last() {
+                    if top.precedence() >= opr.precedence() {
+                        apply(stack.pop()?, &mut nodes, is_flattened)?;
+                    } else {
+                        break;
+                    }
+                }
+                stack.push(opr);
+            }
+            _ => return None,
+        }
+    }
+    while let Some(top) = stack.pop() {
+        if top.is_left_brace() {
+            return None;
+        }
+        apply(top, &mut nodes, is_flattened)?;
+    }
+
+    if nodes.len() == 1 {
+        nodes.pop().map(|(node, _)| node)
+    } else {
+        None
+    }
+}
+
+fn to_object(val: &pb::Value) -> Option<Object> {
+    match Object::try_from(val.clone()) {
+        Ok(Object::None) | Err(_) => None,
+        Ok(obj) => Some(obj),
+    }
+}
+
+fn is_array(val: &pb::Value) -> bool {
+    use pb::value::Item;
+    matches!(
+        val.item,
+        Some(Item::I32Array(_))
+            | Some(Item::I64Array(_))
+            | Some(Item::F64Array(_))
+            | Some(Item::StrArray(_))
+    )
+}
+
+fn is_empty_array(val: &pb::Value) -> bool {
+    use pb::value::Item;
+    match val.item.as_ref() {
+        Some(Item::I32Array(array)) => array.item.is_empty(),
+        Some(Item::I64Array(array)) => array.item.is_empty(),
+        Some(Item::F64Array(array)) => array.item.is_empty(),
+        Some(Item::StrArray(array)) => array.item.is_empty(),
+        _ => false,
+    }
+}
+
+fn fold_arith(arith: pb::Arithmetic, a: &pb::Value, b: &pb::Value) -> Option<pb::Value> {
+    use pb::value::Item;
+    use pb::Arithmetic::*;
+
+    fn fold_i64(arith: pb::Arithmetic, a: i64, b: i64) -> Option<i64> {
+        match arith {
+            Add => a.checked_add(b),
+            Sub => a.checked_sub(b),
+            Mul => a.checked_mul(b),
+            Div => a.checked_div(b),
+            Mod => a.checked_rem(b),
+            Exp => u32::try_from(b)
+                .ok()
+                .and_then(|b| a.checked_pow(b)),
+            Bitand => Some(a & b),
+            Bitor => Some(a | b),
+            Bitxor => Some(a ^ b),
+            Bitlshift | Bitrshift => None,
+        }
+    }
+
+    fn fold_f64(arith: pb::Arithmetic, a: f64, b: f64) -> Option<f64> {
+        match arith {
+            Add => Some(a + b),
+            Sub => Some(a - b),
+            Mul => Some(a * b),
+            Div => Some(a / b),
+            Mod => Some(a % b),
+            Exp => Some(a.powf(b)),
+            _ => None,
+        }
+    }
+
+    match (a.item.as_ref()?, b.item.as_ref()?) {
+        (Item::I32(a), Item::I32(b)) => fold_i64(arith, *a as i64, *b as i64)
+            .and_then(|r| i32::try_from(r).ok())
+            .map(|r| r.into()),
+        (Item::I32(a), Item::I64(b)) => fold_i64(arith, *a as i64, *b).map(|r| r.into()),
+        (Item::I64(a), Item::I32(b)) => fold_i64(arith, *a, *b as i64).map(|r| r.into()),
+        (Item::I64(a), Item::I64(b)) => fold_i64(arith, *a, *b).map(|r| r.into()),
+        (Item::F64(a), Item::F64(b)) => fold_f64(arith, *a, *b).map(|r| r.into()),
+        (Item::F64(a), Item::I32(b)) if arith != Exp => fold_f64(arith, *a, *b as f64).map(|r| r.into()),
+        (Item::F64(a), Item::I64(b)) if arith != Exp => fold_f64(arith, *a, *b as f64).map(|r| r.into()),
+        (Item::I32(a), Item::F64(b)) => fold_f64(arith, *a as f64, *b).map(|r| r.into()),
+        (Item::I64(a), Item::F64(b)) => fold_f64(arith, *a as f64, *b).map(|r| r.into()),
+        _ => None,
+    }
+}
+
+fn fold_compare(logical: pb::Logical, a: &pb::Value, b: &pb::Value) -> Option<bool> {
+    use pb::Logical::*;
+    let (a_obj, b_obj) = (to_object(a)?, to_object(b)?);
+    match logical {
+        Eq => Some(a_obj == b_obj),
+        Ne => Some(a_obj != b_obj),
+        Lt => Some(a_obj < b_obj),
+        Le => Some(a_obj <= b_obj),
+        Gt => Some(a_obj > b_obj),
+        Ge => Some(a_obj >= b_obj),
+        Within if is_array(b) => Some(b_obj.contains(&a_obj)),
+        Without if is_array(b) => Some(!b_obj.contains(&a_obj)),
+        Startswith | Endswith => match (&a_obj, &b_obj) {
+            (Object::String(a_str), Object::String(b_str)) => {
+                if logical == Startswith {
+                    Some(a_str.starts_with(b_str.as_str()))
+                } else {
+                    Some(a_str.ends_with(b_str.as_str()))
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The negation of a comparison operator, e.g., `<` to `>=`.
+fn negate_compare(logical: pb::Logical) -> Option<pb::Logical> {
+    use pb::Logical::*;
+    match logical {
+        Eq => Some(Ne),
+        Ne => Some(Eq),
+        Lt => Some(Ge),
+        Le => Some(Gt),
+        Gt => Some(Le),
+        Ge => Some(Lt),
+        Within => Some(Without),
+        Without => Some(Within),
+        _ => None,
+    }
+}
+
+/// Collect the constants of equalities into an array, if they are of the same primitive type.
+fn collect_const_array(vals: &[&pb::Value]) -> Option<pb::Value> {
+    use pb::value::Item;
+    match vals.first()?.item.as_ref()? {
+        Item::I32(_) | Item::I64(_) => {
+            let mut is_i32 = true;
+            let mut items = Vec::with_capacity(vals.len());
+            for val in vals {
+                match val.item.as_ref()? {
+                    Item::I32(i) => items.push(*i as i64),
+                    Item::I64(i) => {
+                        is_i32 = false;
+                        items.push(*i)
+                    }
+                    _ => return None,
+                }
+            }
+            if is_i32 {
+                let item = items.into_iter().map(|i| i as i32).collect();
+                Some(pb::Value { item: Some(Item::I32Array(pb::I32Array { item })) })
+            } else {
+                Some(items.into())
+            }
+        }
+        Item::F64(_) => {
+            let mut items = Vec::with_capacity(vals.len());
+            for val in vals {
+                match val.item.as_ref()? {
+                    Item::F64(f) => items.push(*f),
+                    _ => return None,
+                }
+            }
+            Some(items.into())
+        }
+        Item::Str(_) => {
+            let mut items = Vec::with_capacity(vals.len());
+            for val in vals {
+                match val.item.as_ref()? {
+                    Item::Str(s) => items.push(s.clone()),
+                    _ => return None,
+                }
+            }
+            Some(items.into())
+        }
+        _ => None,
+    }
+}
+
+struct Simplifier {
+    is_changed: bool,
+}
+
+impl Simplifier {
+    fn simplify(&mut self, node: ExprNode) -> ExprNode {
+        match node {
+            ExprNode::Leaf(_) => node,
+            ExprNode::Unary(opr, child) => {
+                let child = self.simplify(*child);
+                self.simplify_unary(opr, child)
+            }
+            ExprNode::Binary(opr, left, right) => {
+                let (left, right) = (self.simplify(*left), self.simplify(*right));
+                self.simplify_binary(opr, left, right)
+            }
+            ExprNode::Junction(opr, children) => {
+                let children = children
+                    .into_iter()
+                    .map(|child| self.simplify(child))
+                    .collect();
+                self.simplify_junction(opr, children)
+            }
+        }
+    }
+
+    fn simplify_unary(&mut self, opr: pb::ExprOpr, child: ExprNode) -> ExprNode {
+        match get_logical(&opr) {
+            Some(pb::Logical::Not) => {
+                if let Some(b) = child.as_bool() {
+                    self.is_changed = true;
+                    return ExprNode::const_node((!b).into(), &opr.node_type);
+                }
+                match child {
+                    ExprNode::Binary(child_opr, left, right) => {
+                        if let Some(negated) = get_logical(&child_opr).and_then(negate_compare) {
+                            self.is_changed = true;
+                            let negated_opr = with_node_type(negated.into(), &child_opr.node_type);
+                            ExprNode::Binary(negated_opr, left, right)
+                        } else {
+                            ExprNode::Unary(opr, Box::new(ExprNode::Binary(child_opr, left, right)))
+                        }
+                    }
+                    ExprNode::Unary(child_opr, grand_child)
+                        if get_logical(&child_opr) == Some(pb::Logical::Not)
+                            && grand_child.is_boolean() =>
+                    {
+                        self.is_changed = true;
+                        *grand_child
+                    }
+                    _ => ExprNode::Unary(opr, Box::new(child)),
+                }
+            }
+            Some(pb::Logical::Isnull) => {
+                if let Some(val) = child.as_const() {
+                    self.is_changed = true;
+                    let is_null = matches!(val.item, Some(pb::value::Item::None(_)) | None);
+                    ExprNode::const_node(is_null.into(), &opr.node_type)
+                } else {
+                    ExprNode::Unary(opr, Box::new(child))
+                }
+            }
+            _ => ExprNode::Unary(opr, Box::new(child)),
+        }
+    }
+
+    fn simplify_binary(&mut self, opr: pb::ExprOpr, left: ExprNode, right: ExprNode) -> ExprNode {
+        if let (Some(a), Some(b)) = (left.as_const(), right.as_const()) {
+            let folded = if let Some(arith) = get_arith(&opr) {
+                fold_arith(arith, a, b)
+            } else if let Some(logical) = get_logical(&opr) {
+                fold_compare(logical, a, b).map(|r| r.into())
+            } else {
+                None
+            };
+            if let Some(val) = folded {
+                self.is_changed = true;
+                return ExprNode::const_node(val, &opr.node_type);
+            }
+        }
+        ExprNode::Binary(opr, Box::new(left), Box::new(right))
+    }
+
+    fn simplify_junction(&mut self, opr: pb::ExprOpr, children: Vec<ExprNode>) -> ExprNode {
+        let logical = get_logical(&opr);
+        let is_and = logical == Some(pb::Logical::And);
+        let mut flat_children = Vec::with_capacity(children.len());
+        for child in children {
+            match child {
+                // the children may turn into the same junction after simplified
+                ExprNode::Junction(child_opr, grand_children) if get_logical(&child_opr) == logical => {
+                    self.is_changed = true;
+                    flat_children.extend(grand_children);
+                }
+                _ => flat_children.push(child),
+            }
+        }
+        // `false` for `&&` and `true` for `||` absorbs all the others
+        if flat_children
+            .iter()
+            .any(|child| child.as_bool() == Some(!is_and))
+        {
+            self.is_changed = true;
+            return ExprNode::const_node((!is_and).into(), &opr.node_type);
+        }
+        // `true` for `&&` and `false` for `||` can be eliminated, as long as the others are boolean
+        if flat_children
+            .iter()
+            .all(|child| child.is_boolean())
+        {
+            let len = flat_children.len();
+            flat_children.retain(|child| child.as_bool() != Some(is_and));
+            if flat_children.len() != len {
+                self.is_changed = true;
+            }
+        }
+        if !is_and {
+            flat_children = self.eq_chain_to_within(flat_children);
+        }
+
+        match flat_children.len() {
+            0 => ExprNode::const_node(is_and.into(), &opr.node_type),
+            1 => flat_children.pop().unwrap(),
+            _ => ExprNode::Junction(opr, flat_children),
+        }
+    }
+
+    /// Convert `@.a == c1 || @.b == c2 || @.a == c3` into `@.a within [c1, c3] || @.b == c2`
+    fn eq_chain_to_within(&mut self, children: Vec<ExprNode>) -> Vec<ExprNode> {
+        // the positions of the equalities that share the same variable
+        let mut groups: Vec<(pb::Variable, Vec<usize>)> = vec![];
+        for (idx, child) in children.iter().enumerate() {
+            if let Some((var, _)) = child.as_var_eq_const() {
+                if let Some((_, indices)) = groups.iter_mut().find(|(v, _)| *v == *var) {
+                    indices.push(idx);
+                } else {
+                    groups.push((var.clone(), vec![idx]));
+                }
+            }
+        }
+
+        let mut replaced: Vec<Option<ExprNode>> = vec![None; children.len()];
+        let mut removed = vec![false; children.len()];
+        for (var, indices) in groups {
+            if indices.len() < 2 {
+                continue;
+            }
+            let vals: Vec<&pb::Value> = indices
+                .iter()
+                .map(|idx| children[*idx].as_var_eq_const().unwrap().1)
+                .collect();
+            if let Some(array) = collect_const_array(&vals) {
+                self.is_changed = true;
+                let eq_opr = children[indices[0]].opr();
+                let within_opr = with_node_type(pb::Logical::Within.into(), &eq_opr.node_type);
+                replaced[indices[0]] = Some(ExprNode::Binary(
+                    within_opr,
+                    Box::new(ExprNode::Leaf(var.into())),
+                    Box::new(ExprNode::Leaf(array.into())),
+                ));
+                for idx in &indices[1..] {
+                    removed[*idx] = true;
+                }
+            }
+        }
+
+        children
+            .into_iter()
+            .zip(replaced.into_iter().zip(removed))
+            .filter_map(
+                |(child, (new_child, is_removed))| {
+                    if is_removed {
+                        None
+                    } else {
+                        Some(new_child.unwrap_or(child))
+                    }
+                },
+            )
+            .collect()
+    }
+}
+
+/// Whether the node is evaluated to `false` (or null, which is regarded as `false` in a predicate)
+/// for any input.
+fn is_always_false(node: &ExprNode) -> bool {
+    match node {
+        ExprNode::Leaf(_) => node.as_bool() == Some(false),
+        ExprNode::Binary(opr, _, right) => {
+            // `within` an empty array
+            get_logical(opr) == Some(pb::Logical::Within)
+                && right
+                    .as_const()
+                    .map(is_empty_array)
+                    .unwrap_or(false)
+        }
+        ExprNode::Unary(_, _) => false,
+        ExprNode::Junction(opr, children) => {
+            if get_logical(opr) == Some(pb::Logical::Or) {
+                children.iter().all(is_always_false)
+            } else {
+                if children.iter().any(is_always_false) {
+                    return true;
+                }
+                // contradictory equalities, e.g., `@.a == 1 && @.a == 2`
+                let mut equalities: Vec<(&pb::Variable, Object)> = vec![];
+                for child in children {
+                    if let Some((var, val)) = child.as_var_eq_const() {
+                        if let Some(obj) = to_object(val) {
+                            if equalities
+                                .iter()
+                                .any(|(v, o)| *v == var && *o != obj)
+                            {
+                                return true;
+                            }
+                            equalities.push((var, obj));
+                        }
+                    }
+                }
+                false
+            }
+        }
+    }
+}
+
+/// Simplify the expression in place, and return whether the expression has been changed.
+/// An expression that cannot be simplified, or contains unsupported operators, is kept as it is.
+pub fn simplify_expression(expr: &mut pb::Expression) -> bool {
+    let mut is_flattened = false;
+    if let Some(tree) = build_tree(&expr.operators, &mut is_flattened) {
+        let mut simplifier = Simplifier { is_changed: is_flattened };
+        let tree = simplifier.simplify(tree);
+        if simplifier.is_changed {
+            let mut operators = Vec::with_capacity(expr.operators.len());
+            tree.into_oprs(&mut operators);
+            expr.operators = operators;
+            return true;
+        }
+    }
+    false
+}
+
+impl pb::Expression {
+    /// Whether the expression, as a predicate, filters out any input. It is expected to be called
+    /// after `simplify_expression()`, such that the planner can prune the branch it filters.
+    pub fn is_always_false(&self) -> bool {
+        build_tree(&self.operators, &mut false)
+            .map(|tree| is_always_false(&tree))
+            .unwrap_or(false)
+    }
+
+    /// Whether the expression, as a predicate, keeps any input.
+    pub fn is_always_true(&self) -> bool {
+        build_tree(&self.operators, &mut false)
+            .map(|tree| tree.as_bool() == Some(true))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr_parse::str_to_expr_pb;
+
+    fn simplify_str(expr_str: &str) -> (pb::Expression, bool) {
+        let mut expr = str_to_expr_pb(expr_str.to_string()).unwrap();
+        let is_changed = simplify_expression(&mut expr);
+        (expr, is_changed)
+    }
+
+    fn assert_simplified(expr_str: &str, expected_str: &str) {
+        let (expr, is_changed) = simplify_str(expr_str);
+        assert!(is_changed);
+        assert_eq!(expr, str_to_expr_pb(expected_str.to_string()).unwrap());
+    }
+
+    #[test]
+    fn test_fold_constants() {
+        assert_simplified("1 + 2 * 3", "7");
+        assert_simplified("1 + 2 > @.age", "3 > @.age");
+        assert_simplified("(1 + 2) * 3 == 9", "true");
+        assert_simplified("2 ^^ 3 + 1.5", "9.5");
+        assert_simplified("\"abc\" within [\"abc\", \"def\"]", "true");
+        assert_simplified("@.age > 10 - 20 && 1 > 2", "false");
+        // division by zero and overflow are left to the runtime
+        assert!(!simplify_str("1 / 0").1);
+        assert!(!simplify_str("9223372036854775807 + 1").1);
+    }
+
+    #[test]
+    fn test_simplify_logical() {
+        assert_simplified("@.age > 10 && true", "@.age > 10");
+        assert_simplified("@.age > 10 || true", "true");
+        assert_simplified("@.age > 10 || false || @.age < 5", "@.age > 10 || @.age < 5");
+        assert_simplified("false || false", "false");
+        // `@.age` is not boolean, thus `&& true` cannot be eliminated
+        assert!(!simplify_str("@.age && true").1);
+    }
+
+    #[test]
+    fn test_normalize_not() {
+        assert_simplified("!(@.age < 10)", "@.age >= 10");
+        assert_simplified("!(@.name == \"marko\") && @.age > 10", "@.name != \"marko\" && @.age > 10");
+        assert_simplified("!(@.id within [1, 2])", "@.id without [1, 2]");
+        assert_simplified("!(!(@.age < 10 || @.age > 20))", "@.age < 10 || @.age > 20");
+        assert_simplified("!true", "false");
+        assert!(!simplify_str("!(@.age < 10 || @.age > 20)").1);
+    }
+
+    #[test]
+    fn test_flatten_junction() {
+        assert_simplified("@.a > 1 && (@.b > 2 && @.c > 3)", "@.a > 1 && @.b > 2 && @.c > 3");
+        assert_simplified("(@.a > 1 || @.b > 2) || @.c > 3", "@.a > 1 || @.b > 2 || @.c > 3");
+        assert_simplified("@.a > 1 && (@.b > 2 || @.c > 3 && true)", "@.a > 1 && (@.b > 2 || @.c > 3)");
+        // nothing to flatten
+        assert!(!simplify_str("(@.name == \"person\") && @a.~label == \"knows\"").1);
+        assert!(!simplify_str("@.a > 1 && (@.b > 2 || @.c > 3)").1);
+    }
+
+    #[test]
+    fn test_eq_chain_to_within() {
+        assert_simplified("@.id == 1 || @.id == 2 || 3 == @.id", "@.id within [1, 2, 3]");
+        assert_simplified(
+            "@.name == \"a\" || @.age > 10 || @.name == \"b\"",
+            "@.name within [\"a\", \"b\"] || @.age > 10",
+        );
+        assert_simplified(
+            "@.id == 1 || @.id == 2 && @.age > 10 || @.id == 3",
+            "@.id within [1, 3] || @.id == 2 && @.age > 10",
+        );
+        let (expr, _) = simplify_str("@.id == 1 || @.id == 2");
+        match &expr.operators[2].item {
+            Some(pb::expr_opr::Item::Const(pb::Value { item: Some(pb::value::Item::I64Array(arr)) })) => {
+                assert_eq!(arr.item, vec![1, 2])
+            }
+            _ => panic!("should be an i64 array"),
+        }
+        // of different types
+        assert!(!simplify_str("@.id == 1 || @.id == \"1\"").1);
+        assert!(!simplify_str("@.id == 1 || @.name == 1").1);
+    }
+
+    #[test]
+    fn test_always_false() {
+        let (expr, _) = simplify_str("@.age > 10 && 1 > 2");
+        assert!(expr.is_always_false());
+        let (expr, _) = simplify_str("@.name == \"a\" && @.age > 10 && @.name == \"b\"");
+        assert!(expr.is_always_false());
+        let (expr, _) = simplify_str(
+            "(@.name == \"a\" && @.name == \"b\") || (@.age > 10 && @.age == 1 && @.age == 2)",
+        );
+        assert!(expr.is_always_false());
+        let (expr, _) = simplify_str("(@.name == \"a\" && @.name == \"b\") || @.age > 10");
+        assert!(!expr.is_always_false());
+        let (expr, _) = simplify_str("@.name == \"a\" && @.name == \"a\"");
+        assert!(!expr.is_always_false());
+        let (expr, _) = simplify_str("@.age > 10 || 1 < 2");
+        assert!(expr.is_always_true());
+    }
+
+    #[test]
+    fn test_unsupported() {
+        // the tree is not touched if there is an unsupported operator
+        let mut expr = str_to_expr_pb("1 + 2".to_string()).unwrap();
+        expr.operators.push(pb::ExprOpr {
+            node_type: None,
+            item: Some(pb::expr_opr::Item::Extract(Default::default())),
+        });
+        assert!(!simplify_expression(&mut expr));
+        // malformed expression
+        assert!(!simplify_str("1 + ").1);
+        assert!(!simplify_str("(1 + 2").1);
+    }
+}
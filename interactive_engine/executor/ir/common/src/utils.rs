@@ -213,6 +213,13 @@ pub const LABEL_KEY: &'static str = "~label";
 pub const LENGTH_KEY: &'static str = "~len";
 pub const ALL_KEY: &'static str = "~all";
 
+/// Keys of the extra query parameters of an expand, with which the planner asks the storage for at
+/// most `ORDER_LIMIT_KEY` edges of each vertex in the order of the property of id `ORDER_BY_KEY`,
+/// descending if `ORDER_DESC_KEY` is "true". A storage that cannot do it reads edges as usual.
+pub const ORDER_BY_KEY: &'static str = "~order_by";
+pub const ORDER_DESC_KEY: &'static str = "~order_desc";
+pub const ORDER_LIMIT_KEY: &'static str = "~order_limit";
+
 impl From<String> for common_pb::Property {
     fn from(str: String) -> Self {
         if str == ID_KEY {
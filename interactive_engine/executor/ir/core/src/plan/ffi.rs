@@ -1558,7 +1558,7 @@ mod orderby {
     /// To initialize an orderby operator
     #[no_mangle]
     pub extern "C" fn init_orderby_operator() -> *const c_void {
-        let order = Box::new(pb::OrderBy { pairs: vec![], limit: None });
+        let order = Box::new(pb::OrderBy { pairs: vec![], limit: None, keys: vec![], seed: None });
         Box::into_raw(order) as *const c_void
     }
 
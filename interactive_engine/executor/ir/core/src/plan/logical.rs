@@ -22,6 +22,7 @@ use std::rc::Rc;
 
 use fraction::Fraction;
 use ir_common::error::ParsePbError;
+use ir_common::expr_parse::simplify::simplify_expression;
 use ir_common::generated::algebra as pb;
 use ir_common::generated::algebra::pattern::binder::Item;
 use ir_common::generated::common as common_pb;
@@ -1068,6 +1069,9 @@ fn preprocess_label(
 fn preprocess_expression(
     expr: &mut common_pb::Expression, meta: &StoreMeta, plan_meta: &mut PlanMeta, is_predicate: bool,
 ) -> IrResult<()> {
+    if simplify_expression(expr) {
+        debug!("simplified expression: {:?}", expr);
+    }
     let mut count = 0;
     for opr in expr.operators.iter_mut() {
         if let Some(item) = opr.item.as_mut() {
@@ -1549,6 +1553,9 @@ impl AsLogical for pb::OrderBy {
                 preprocess_var(key, meta, plan_meta, false)?;
             }
         }
+        for var in self.keys.iter_mut() {
+            preprocess_var(var, meta, plan_meta, false)?;
+        }
         process_columns_meta(plan_meta, false)?;
 
         Ok(())
@@ -2122,6 +2129,44 @@ mod test {
         );
     }
 
+    #[test]
+    fn preprocess_simplified_expr() {
+        let mut plan_meta = PlanMeta::default();
+        plan_meta.curr_node_meta_mut();
+        plan_meta.refer_to_nodes(0, vec![0]);
+        let meta = StoreMeta {
+            schema: Some(Schema::new(
+                vec![("person".to_string(), 0), ("software".to_string(), 1)],
+                vec![("knows".to_string(), 0), ("creates".to_string(), 1)],
+                vec![("id".to_string(), 0), ("name".to_string(), 1), ("age".to_string(), 2)],
+            )),
+        };
+
+        // the equalities are converted into `within`, and the labels are then mapped to ids
+        let mut expression =
+            str_to_expr_pb("@.~label == \"person\" || @.~label == \"software\"".to_string()).unwrap();
+        preprocess_expression(&mut expression, &meta, &mut plan_meta, true).unwrap();
+        assert_eq!(expression.operators.len(), 3);
+        match expression.operators[2].item.clone().unwrap() {
+            common_pb::expr_opr::Item::Const(val) => match val.item.unwrap() {
+                common_pb::value::Item::I32Array(arr) => assert_eq!(arr.item, vec![0, 1]),
+                _ => panic!(),
+            },
+            _ => panic!(),
+        }
+
+        let mut expression =
+            str_to_expr_pb("@.age > 10 + 20 && !(@.name == \"marko\")".to_string()).unwrap();
+        preprocess_expression(&mut expression, &meta, &mut plan_meta, true).unwrap();
+        let mut expected = str_to_expr_pb("@.age > 30 && @.name != \"marko\"".to_string()).unwrap();
+        preprocess_expression(&mut expected, &meta, &mut plan_meta, true).unwrap();
+        assert_eq!(expression, expected);
+
+        let mut expression = str_to_expr_pb("@.age > 10 && 1 > 2".to_string()).unwrap();
+        preprocess_expression(&mut expression, &meta, &mut plan_meta, true).unwrap();
+        assert!(expression.is_always_false());
+    }
+
     #[test]
     fn preprocess_scan() {
         let mut plan_meta = PlanMeta::default();
@@ -2449,7 +2494,8 @@ mod test {
             columns: vec![],
             is_all_columns: false,
             limit: None,
-            predicate: Some(str_to_expr_pb("@.age == 27 && (true)".to_string()).unwrap()),
+            // the `true` placeholder of the index triplet is removed by expression simplification
+            predicate: Some(str_to_expr_pb("@.age == 27".to_string()).unwrap()),
             sample_ratio: 1.0,
             extra: HashMap::new(),
         };
@@ -2507,7 +2553,7 @@ mod test {
             columns: vec![],
             is_all_columns: false,
             limit: None,
-            predicate: Some(str_to_expr_pb("true && @.age == 27".to_string()).unwrap()),
+            predicate: Some(str_to_expr_pb("@.age == 27".to_string()).unwrap()),
             sample_ratio: 1.0,
             extra: HashMap::new(),
         };
@@ -2744,6 +2790,8 @@ mod test {
                 order: 1,
             }],
             limit: None,
+            keys: vec![],
+            seed: None,
         };
         plan.append_operator_as_node(orderby.into(), vec![4])
             .unwrap();
@@ -3192,6 +3240,8 @@ mod test {
                 order: 0,
             }],
             limit: None,
+            keys: vec![],
+            seed: None,
         };
         plan.append_operator_as_node(order.into(), vec![2])
             .unwrap();
@@ -3447,6 +3497,8 @@ mod test {
                 order: 0,
             }],
             limit: None,
+            keys: vec![],
+            seed: None,
         };
         plan.append_operator_as_node(order.into(), vec![2])
             .unwrap();
@@ -26,6 +26,7 @@ use ir_common::generated::algebra as pb;
 use ir_common::generated::common as common_pb;
 use ir_common::generated::common::expr_opr::Item;
 use ir_common::generated::physical as physical_pb;
+use ir_common::utils::{ORDER_BY_KEY, ORDER_DESC_KEY, ORDER_LIMIT_KEY};
 use ir_common::KeyId;
 use ir_physical_client::physical_builder::PlanBuilder;
 
@@ -751,6 +752,69 @@ fn extract_expand_degree(node: NodeType) -> Option<pb::EdgeExpand> {
     None
 }
 
+/// If an `EdgeExpand(Opt=Edge)` is followed by an `OrderBy` with a limit on a single property of the
+/// expanded edges, returns a copy of the expand that asks the storage for only the first `upper`
+/// edges of each vertex in that order, which a storage with a sort key on the property can serve
+/// directly. The global top-k is among these edges, and the `OrderBy` is kept to compute it.
+fn extract_sorted_expand(plan: &LogicalPlan, node: NodeType) -> Option<pb::EdgeExpand> {
+    let node_ref = node.borrow();
+    let edgexpd = match &node_ref.opr.opr {
+        Some(pb::logical_plan::operator::Opr::Edge(edgexpd))
+            if edgexpd.expand_opt == pb::edge_expand::ExpandOpt::Edge as i32 =>
+        {
+            edgexpd
+        }
+        _ => return None,
+    };
+    if node_ref.children.len() != 1 {
+        return None;
+    }
+    let child = plan.get_node(node_ref.get_first_child()?)?;
+    let child_ref = child.borrow();
+    let order = match &child_ref.opr.opr {
+        Some(pb::logical_plan::operator::Opr::OrderBy(order)) => order,
+        _ => return None,
+    };
+    let upper = order.limit.as_ref()?.upper;
+    // a per-group top-k may need edges beyond the top-k of each vertex
+    if order.pairs.len() != 1 || upper <= 0 || !order.keys.is_empty() {
+        return None;
+    }
+    let pair = &order.pairs[0];
+    let descending = if pair.order == pb::order_by::ordering_pair::Order::Asc as i32 {
+        false
+    } else if pair.order == pb::order_by::ordering_pair::Order::Desc as i32 {
+        true
+    } else {
+        return None;
+    };
+    let key = pair.key.as_ref()?;
+    // the key must refer to the expanded edges, either as the head or by the alias of the expand
+    if key.tag.is_some() && key.tag != edgexpd.alias {
+        return None;
+    }
+    // storages read properties by id only
+    let prop_id = match key
+        .property
+        .as_ref()
+        .and_then(|p| p.item.as_ref())
+    {
+        Some(common_pb::property::Item::Key(common_pb::NameOrId {
+            item: Some(common_pb::name_or_id::Item::Id(prop_id)),
+        })) => *prop_id,
+        _ => return None,
+    };
+    let mut sorted_expand = edgexpd.clone();
+    let extra = &mut sorted_expand
+        .params
+        .get_or_insert_with(pb::QueryParams::default)
+        .extra;
+    extra.insert(ORDER_BY_KEY.to_string(), prop_id.to_string());
+    extra.insert(ORDER_DESC_KEY.to_string(), descending.to_string());
+    extra.insert(ORDER_LIMIT_KEY.to_string(), upper.to_string());
+    Some(sorted_expand)
+}
+
 fn extract_project_single_tag(node: NodeType) -> Option<common_pb::NameOrId> {
     if let Some(pb::logical_plan::operator::Opr::Project(project)) = &node.borrow().opr.opr {
         if project.mappings.len() == 1 {
@@ -863,6 +927,9 @@ impl AsPhysical for LogicalPlan {
                 } else {
                     return Err(IrError::MissingData("Apply::subplan".to_string()));
                 }
+            } else if let Some(sorted_expand) = extract_sorted_expand(self, curr_node.clone()) {
+                plan_meta.set_curr_node(curr_node_id);
+                sorted_expand.add_job_builder(builder, plan_meta)?;
             } else {
                 curr_node.add_job_builder(builder, plan_meta)?;
             }
@@ -1898,7 +1965,12 @@ mod test {
             meta_data: None,
         };
 
-        let topby_opr = pb::OrderBy { pairs: vec![], limit: Some(pb::Range { lower: 10, upper: 11 }) };
+        let topby_opr = pb::OrderBy {
+            pairs: vec![],
+            limit: Some(pb::Range { lower: 10, upper: 11 }),
+            keys: vec![],
+            seed: None,
+        };
 
         let mut logical_plan = LogicalPlan::with_node(Node::new(0, source_opr.clone().into()));
         logical_plan
@@ -1915,6 +1987,70 @@ mod test {
         assert_eq!(builder, expected_builder);
     }
 
+    #[test]
+    fn orderby_after_edgexpd_as_physical() {
+        // g.V().outE().order().by('2', desc).limit(10)
+        let order_by = |tag: Option<common_pb::NameOrId>| pb::OrderBy {
+            pairs: vec![pb::order_by::OrderingPair {
+                key: Some(common_pb::Variable {
+                    tag,
+                    property: Some(common_pb::Property {
+                        item: Some(common_pb::property::Item::Key(common_pb::NameOrId {
+                            item: Some(common_pb::name_or_id::Item::Id(2)),
+                        })),
+                    }),
+                    node_type: None,
+                }),
+                order: 2,
+            }],
+            limit: Some(pb::Range { lower: 0, upper: 10 }),
+            keys: vec![],
+            seed: None,
+        };
+        let expand = build_edgexpd(1, vec![], Some(1.into()));
+        let mut sorted_expand = expand.clone();
+        let extra = &mut sorted_expand.params.as_mut().unwrap().extra;
+        extra.insert(ORDER_BY_KEY.to_string(), "2".to_string());
+        extra.insert(ORDER_DESC_KEY.to_string(), "true".to_string());
+        extra.insert(ORDER_LIMIT_KEY.to_string(), "10".to_string());
+
+        // the order key refers to the expanded edges by the head or by the alias
+        for tag in vec![None, Some(1.into())] {
+            let mut plan = LogicalPlan::with_node(Node::new(0, build_scan(vec![]).into()));
+            plan.append_operator_as_node(expand.clone().into(), vec![0])
+                .unwrap();
+            plan.append_operator_as_node(order_by(tag.clone()).into(), vec![1])
+                .unwrap();
+            let mut job_builder = PlanBuilder::default();
+            let mut plan_meta = plan.meta.clone();
+            plan.add_job_builder(&mut job_builder, &mut plan_meta)
+                .unwrap();
+
+            let mut expected_builder = PlanBuilder::default();
+            expected_builder.add_scan_source(build_scan(vec![]));
+            expected_builder.edge_expand(sorted_expand.clone());
+            expected_builder.order(order_by(tag));
+            assert_eq!(job_builder, expected_builder);
+        }
+
+        // the order key refers to another tag
+        let mut plan = LogicalPlan::with_node(Node::new(0, build_scan(vec![]).into()));
+        plan.append_operator_as_node(expand.clone().into(), vec![0])
+            .unwrap();
+        plan.append_operator_as_node(order_by(Some(0.into())).into(), vec![1])
+            .unwrap();
+        let mut job_builder = PlanBuilder::default();
+        let mut plan_meta = plan.meta.clone();
+        plan.add_job_builder(&mut job_builder, &mut plan_meta)
+            .unwrap();
+
+        let mut expected_builder = PlanBuilder::default();
+        expected_builder.add_scan_source(build_scan(vec![]));
+        expected_builder.edge_expand(expand);
+        expected_builder.order(order_by(Some(0.into())));
+        assert_eq!(job_builder, expected_builder);
+    }
+
     #[test]
     fn apply_as_physical_case1() {
         let mut plan = LogicalPlan::with_root();
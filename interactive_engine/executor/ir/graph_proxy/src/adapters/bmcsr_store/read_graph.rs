@@ -0,0 +1,1019 @@
+//
+//! Copyright 2023 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+
+use ahash::HashMap;
+use bmcsr::columns::RefItem;
+use bmcsr::date::Date;
+use bmcsr::date_time::DateTime;
+use bmcsr::graph::{Direction as StoreDirection, IndexType};
+use bmcsr::graph_db::{GraphDB, LocalEdge, LocalVertex};
+use bmcsr::graph_modifier::{apply_write_operations_with_wal, WriteOperation};
+use bmcsr::ldbc_parser::LDBCVertexParser;
+use bmcsr::types::LabelId as StoreLabelId;
+use bmcsr::types::{DefaultId, InternalId};
+use bmcsr::wal::WriteAheadLog;
+use dyn_type::{BorrowObject, DateTimeFormats, Object, Primitives};
+use ir_common::{KeyId, LabelId, NameOrId, OneOrMany};
+use pegasus::configure_with_default;
+use pegasus_common::downcast::*;
+use pegasus_common::impl_as_any;
+
+use crate::apis::graph::PKV;
+use crate::apis::{
+    from_fn, ClusterInfo, Details, Direction, DynDetails, Edge, PropertyValue, QueryParams, ReadGraph,
+    Statement, Vertex, ID,
+};
+use crate::errors::{GraphProxyError, GraphProxyResult};
+use crate::{filter_limit, filter_sample_limit, limit_n, sample_limit};
+
+lazy_static! {
+    pub static ref BMCSR_PATH: String = configure_with_default!(String, "BMCSR_PATH", "".to_string());
+    pub static ref BMCSR_PARTITION_ID: usize = configure_with_default!(usize, "PARTITION_ID", 0);
+    pub static ref BMCSR: BmcsrGraph = {
+        let (graph, wal) = _init_bmcsr();
+        BmcsrGraph::new(graph, wal)
+    };
+}
+
+const BMCSR_STORE_PK: KeyId = 0;
+pub const LABEL_SHIFT_BITS: usize =
+    8 * (std::mem::size_of::<DefaultId>() - std::mem::size_of::<StoreLabelId>());
+
+fn _init_bmcsr() -> (GraphDB<DefaultId, InternalId>, WriteAheadLog) {
+    let graph = GraphDB::deserialize(&*(BMCSR_PATH), *BMCSR_PARTITION_ID, None).unwrap();
+    let wal = WriteAheadLog::open(&*(BMCSR_PATH), *BMCSR_PARTITION_ID).unwrap();
+    (graph, wal)
+}
+
+type GraphSnapshot = Arc<GraphDB<DefaultId, InternalId>>;
+
+/// The bmcsr graph shared by the query runtime and the graph writers.
+/// Queries read a pinned snapshot of the graph, while a batch of `WriteOperation`s builds the next
+/// version of the graph (copying the csrs and property tables shared with the snapshots on write),
+/// which is then published atomically for the subsequent queries. Every batch is logged in the
+/// write-ahead log of the partition before it is applied.
+pub struct BmcsrGraph {
+    writer: Mutex<BmcsrWriter>,
+    current: RwLock<GraphSnapshot>,
+}
+
+struct BmcsrWriter {
+    graph: GraphDB<DefaultId, InternalId>,
+    wal: WriteAheadLog,
+}
+
+impl BmcsrGraph {
+    pub fn new(graph: GraphDB<DefaultId, InternalId>, wal: WriteAheadLog) -> Self {
+        let current = RwLock::new(graph.snapshot());
+        BmcsrGraph { writer: Mutex::new(BmcsrWriter { graph, wal }), current }
+    }
+
+    /// Get the latest published version of the graph.
+    pub fn snapshot(&self) -> GraphSnapshot {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Apply a batch of write operations to the graph, the batches are applied one by one.
+    pub fn apply_write_operations(
+        &self, write_operations: Vec<WriteOperation>, parallel: u32,
+    ) -> GraphProxyResult<()> {
+        let mut writer = self.writer.lock().unwrap();
+        let BmcsrWriter { graph, wal } = &mut *writer;
+        let res = apply_write_operations_with_wal(graph, wal, write_operations, parallel);
+        // a failed checkpoint comes after the batch is applied, which is published all the same
+        *self.current.write().unwrap() = graph.snapshot();
+        res.map_err(|e| GraphProxyError::WriteGraphError(format!("{:?}", e)))
+    }
+}
+
+/// Borrow the graph of a snapshot with a `'static` lifetime.
+///
+/// # Safety
+///
+/// The returned reference is only valid while a clone of `snapshot` is alive, so every iterator,
+/// statement and lazy details holding the reference (or anything borrowed from it) must also own a
+/// clone of the snapshot, and must not be able to outlive that clone.
+#[inline]
+unsafe fn pin(snapshot: &GraphSnapshot) -> &'static GraphDB<DefaultId, InternalId> {
+    &*Arc::as_ptr(snapshot)
+}
+
+/// An iterator over a pinned graph, which holds the snapshot until the iteration is done.
+struct SnapshotIter<I> {
+    inner: I,
+    _snapshot: GraphSnapshot,
+}
+
+impl<I: Iterator> Iterator for SnapshotIter<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+pub fn create_bmcsr_store(cluster_info: Arc<dyn ClusterInfo>) -> Arc<BmcsrStore> {
+    lazy_static::initialize(&BMCSR);
+    Arc::new(BmcsrStore { store: &BMCSR, cluster_info })
+}
+
+pub struct BmcsrStore {
+    store: &'static BmcsrGraph,
+    cluster_info: Arc<dyn ClusterInfo>,
+}
+
+impl BmcsrStore {
+    pub fn graph(&self) -> &'static BmcsrGraph {
+        self.store
+    }
+}
+
+impl ReadGraph for BmcsrStore {
+    fn scan_vertex(
+        &self, params: &QueryParams,
+    ) -> GraphProxyResult<Box<dyn Iterator<Item = Vertex> + Send>> {
+        let label_ids = encode_storage_label(&params.labels);
+        let props = params.columns.clone();
+
+        let worker_index = self.cluster_info.get_worker_index()?;
+        let workers_num = self.cluster_info.get_local_worker_num()?;
+
+        let snapshot = self.store.snapshot();
+        // SAFETY: the returned iterator owns `snapshot` by the `map` closure, and each vertex keeps a
+        // clone of it in its lazy details.
+        let result = unsafe { pin(&snapshot) }
+            .get_partitioned_vertices(label_ids.as_ref(), worker_index, workers_num)
+            .map(move |v| to_runtime_vertex(v, props.clone(), &snapshot));
+        Ok(filter_sample_limit!(result, params.filter, params.sample_ratio, params.limit))
+    }
+
+    fn index_scan_vertex(
+        &self, label: LabelId, primary_key: &PKV, params: &QueryParams,
+    ) -> GraphProxyResult<Option<Vertex>> {
+        let outer_id = match primary_key {
+            OneOrMany::One(pkv) => pkv[0].1.as_u64().map_err(|e| {
+                GraphProxyError::query_store_error(&format!("cast outer_id as u64 failed {:?}", e))
+            })?,
+            OneOrMany::Many(_) => Err(GraphProxyError::unsupported_error(
+                "bmcsr storage only supports single-column primary key",
+            ))?,
+        };
+        let global_id =
+            LDBCVertexParser::<DefaultId>::to_global_id(outer_id as usize, encode_storage_label_id(label));
+        let snapshot = self.store.snapshot();
+        // SAFETY: the borrow ends in this call, and the vertex keeps a clone of `snapshot` in its lazy
+        // details.
+        Ok(unsafe { pin(&snapshot) }
+            .get_vertex(global_id)
+            .map(|v| to_runtime_vertex(v, params.columns.clone(), &snapshot)))
+    }
+
+    fn scan_edge(&self, params: &QueryParams) -> GraphProxyResult<Box<dyn Iterator<Item = Edge> + Send>> {
+        let label_ids = encode_storage_label(&params.labels);
+        let props = params.columns.clone();
+
+        let worker_index = self.cluster_info.get_worker_index()?;
+        let workers_num = self.cluster_info.get_local_worker_num()?;
+        let snapshot = self.store.snapshot();
+        // SAFETY: the returned iterator owns `snapshot` by the last `map` closure, and each edge keeps
+        // a clone of it in its lazy details.
+        let graph = unsafe { pin(&snapshot) };
+        let partition_id = graph.partition as u8;
+
+        let result = graph
+            .get_partitioned_vertices(None, worker_index, workers_num)
+            .flat_map(move |v| {
+                get_adj_edges(graph, v.get_id(), StoreDirection::Outgoing, label_ids.as_ref())
+            })
+            .map(move |e| to_runtime_edge(e, None, props.clone(), partition_id, &snapshot));
+        Ok(filter_sample_limit!(result, params.filter, params.sample_ratio, params.limit))
+    }
+
+    fn get_vertex(
+        &self, ids: &[ID], params: &QueryParams,
+    ) -> GraphProxyResult<Box<dyn Iterator<Item = Vertex> + Send>> {
+        let snapshot = self.store.snapshot();
+        let mut result = Vec::with_capacity(ids.len());
+        for id in ids {
+            // SAFETY: the borrow ends in this call, and the vertex keeps a clone of `snapshot` in
+            // its lazy details.
+            if let Some(local_vertex) = unsafe { pin(&snapshot) }.get_vertex(*id as DefaultId) {
+                let v = to_runtime_vertex(local_vertex, params.columns.clone(), &snapshot);
+                result.push(v);
+            }
+        }
+        Ok(filter_limit!(result.into_iter(), params.filter, params.limit))
+    }
+
+    fn get_edge(
+        &self, _ids: &[ID], _params: &QueryParams,
+    ) -> GraphProxyResult<Box<dyn Iterator<Item = Edge> + Send>> {
+        Err(GraphProxyError::unsupported_error("bmcsr storage does not support get_edge for now"))?
+    }
+
+    fn prepare_explore_vertex(
+        &self, direction: Direction, params: &QueryParams,
+    ) -> GraphProxyResult<Box<dyn Statement<ID, Vertex>>> {
+        let edge_label_ids = encode_storage_label(params.labels.as_ref());
+        let filter = params.filter.clone();
+        let limit = params.limit.clone();
+        let snapshot = self.store.snapshot();
+
+        let stmt = from_fn(move |v: ID| {
+            // SAFETY: the returned iterator is wrapped in a `SnapshotIter` owning a clone of
+            // `snapshot`, and the vertices it yields borrow nothing from the graph.
+            let iter = get_adj_edges_by_direction(
+                unsafe { pin(&snapshot) },
+                v as DefaultId,
+                direction,
+                edge_label_ids.as_ref(),
+            )
+            .map(move |e| {
+                if e.get_src_id() == v as DefaultId {
+                    to_empty_vertex(e.get_dst_id(), e.get_dst_label())
+                } else {
+                    to_empty_vertex(e.get_src_id(), e.get_src_label())
+                }
+            });
+            // the vertices are returned without details, so the snapshot is only held while
+            // iterating the adjacent edges
+            let iter = SnapshotIter { inner: iter, _snapshot: snapshot.clone() };
+            Ok(filter_limit!(iter, filter, limit))
+        });
+        Ok(stmt)
+    }
+
+    fn prepare_explore_edge(
+        &self, direction: Direction, params: &QueryParams,
+    ) -> GraphProxyResult<Box<dyn Statement<ID, Edge>>> {
+        let edge_label_ids = encode_storage_label(&params.labels);
+        let filter = params.filter.clone();
+        let limit = params.limit.clone();
+        let snapshot = self.store.snapshot();
+        let props = params.columns.clone();
+
+        let stmt = from_fn(move |v: ID| {
+            let props = props.clone();
+            let snapshot = snapshot.clone();
+            // SAFETY: the returned iterator owns this clone of `snapshot` by the `map` closure, and
+            // each edge keeps a clone of it in its lazy details.
+            let graph = unsafe { pin(&snapshot) };
+            let partition_id = graph.partition as u8;
+            let iter =
+                get_adj_edges_by_direction(graph, v as DefaultId, direction, edge_label_ids.as_ref())
+                    .map(move |e| to_runtime_edge(e, Some(v), props.clone(), partition_id, &snapshot));
+            Ok(filter_limit!(iter, filter, limit))
+        });
+        Ok(stmt)
+    }
+
+    fn get_primary_key(&self, id: &ID) -> GraphProxyResult<Option<PKV>> {
+        let outer_id = (*id << LABEL_SHIFT_BITS) >> LABEL_SHIFT_BITS;
+        let pk_val = Object::from(outer_id);
+        Ok(Some((BMCSR_STORE_PK.into(), pk_val).into()))
+    }
+
+    fn count_vertex(&self, params: &QueryParams) -> GraphProxyResult<u64> {
+        if params.filter.is_some() {
+            // the filter cannot be pushed down to store,
+            // so we need to scan all vertices with filter and then count
+            Ok(self.scan_vertex(params)?.count() as u64)
+        } else {
+            let worker_index = self.cluster_info.get_worker_index()?;
+            let workers_num = self.cluster_info.get_local_worker_num()?;
+            if worker_index % workers_num == 0 {
+                let graph = self.store.snapshot();
+                let count = all_vertex_labels(&graph, encode_storage_label(&params.labels))
+                    .into_iter()
+                    .map(|label| graph.vertex_map.actual_vertices_num(label))
+                    .sum::<usize>();
+                Ok(count as u64)
+            } else {
+                Ok(0)
+            }
+        }
+    }
+
+    fn count_edge(&self, params: &QueryParams) -> GraphProxyResult<u64> {
+        if params.filter.is_some() {
+            Ok(self.scan_edge(params)?.count() as u64)
+        } else {
+            let worker_index = self.cluster_info.get_worker_index()?;
+            let workers_num = self.cluster_info.get_local_worker_num()?;
+            if worker_index % workers_num == 0 {
+                let graph = self.store.snapshot();
+                let edge_labels = all_edge_labels(&graph, encode_storage_label(&params.labels));
+                let mut count = 0;
+                for src_label in 0..graph.vertex_label_num {
+                    for dst_label in 0..graph.vertex_label_num {
+                        for edge_label in edge_labels.iter() {
+                            count += graph.get_edges_num(
+                                src_label as StoreLabelId,
+                                *edge_label,
+                                dst_label as StoreLabelId,
+                            );
+                        }
+                    }
+                }
+                Ok(count as u64)
+            } else {
+                Ok(0)
+            }
+        }
+    }
+}
+
+#[inline]
+fn all_vertex_labels(
+    graph: &GraphDB<DefaultId, InternalId>, labels: Option<Vec<StoreLabelId>>,
+) -> Vec<StoreLabelId> {
+    labels.unwrap_or_else(|| {
+        (0..graph.vertex_label_num)
+            .map(|label| label as StoreLabelId)
+            .collect()
+    })
+}
+
+#[inline]
+fn all_edge_labels(
+    graph: &GraphDB<DefaultId, InternalId>, labels: Option<Vec<StoreLabelId>>,
+) -> Vec<StoreLabelId> {
+    labels.unwrap_or_else(|| {
+        (0..graph.edge_label_num)
+            .map(|label| label as StoreLabelId)
+            .collect()
+    })
+}
+
+/// Get the adjacent edges of vertex `v` by walking through the csrs of all the
+/// `src_label - edge_label - dst_label` triplets that `v` may reside in.
+fn get_adj_edges(
+    graph: &'static GraphDB<DefaultId, InternalId>, v: DefaultId, dir: StoreDirection,
+    edge_labels: Option<&Vec<StoreLabelId>>,
+) -> Box<dyn Iterator<Item = LocalEdge<'static, DefaultId, InternalId>> + Send> {
+    let (label, lid) = if let Some(internal_id) = graph.vertex_map.get_internal_id(v) {
+        internal_id
+    } else {
+        return Box::new(std::iter::empty());
+    };
+    let edge_labels = all_edge_labels(graph, edge_labels.cloned());
+    let mut iters: Vec<Box<dyn Iterator<Item = LocalEdge<'static, DefaultId, InternalId>> + Send>> = vec![];
+    for edge_label in edge_labels {
+        for other_label in 0..graph.vertex_label_num {
+            let other_label = other_label as StoreLabelId;
+            let (src_label, dst_label) = match dir {
+                StoreDirection::Outgoing => (label, other_label),
+                StoreDirection::Incoming => (other_label, label),
+            };
+            // the csrs of both directions are indexed by the `src_label - edge_label - dst_label` triplet
+            let index =
+                graph.edge_label_to_index(src_label, dst_label, edge_label, StoreDirection::Outgoing);
+            let (csr, prop_table) = match dir {
+                StoreDirection::Outgoing => (&graph.oe[index], graph.oe_edge_prop_table.get(&index)),
+                StoreDirection::Incoming => (&graph.ie[index], graph.ie_edge_prop_table.get(&index)),
+            };
+            let prop_table = prop_table.map(|table| table.as_ref());
+            // corner vertices and vertices inserted after the csr is built have no adjacent edges here
+            if lid.index() >= csr.vertex_num().index() {
+                continue;
+            }
+            if let Some(nbrs) = csr.get_edges_with_offset(lid) {
+                let vertex_map = graph.vertex_map.as_ref();
+                iters.push(Box::new(
+                    nbrs.filter(|(nbr, _)| *nbr != <InternalId as IndexType>::max())
+                        .map(move |(nbr, offset)| {
+                            let (start, end) = match dir {
+                                StoreDirection::Outgoing => (lid, nbr),
+                                StoreDirection::Incoming => (nbr, lid),
+                            };
+                            LocalEdge::new(
+                                start, end, edge_label, src_label, dst_label, vertex_map, offset,
+                                prop_table,
+                            )
+                        }),
+                ));
+            }
+        }
+    }
+    Box::new(iters.into_iter().flatten())
+}
+
+#[inline]
+fn get_adj_edges_by_direction(
+    graph: &'static GraphDB<DefaultId, InternalId>, v: DefaultId, direction: Direction,
+    edge_labels: Option<&Vec<StoreLabelId>>,
+) -> Box<dyn Iterator<Item = LocalEdge<'static, DefaultId, InternalId>> + Send> {
+    match direction {
+        Direction::Out => get_adj_edges(graph, v, StoreDirection::Outgoing, edge_labels),
+        Direction::In => get_adj_edges(graph, v, StoreDirection::Incoming, edge_labels),
+        Direction::Both => {
+            Box::new(get_adj_edges(graph, v, StoreDirection::Outgoing, edge_labels).chain(get_adj_edges(
+                graph,
+                v,
+                StoreDirection::Incoming,
+                edge_labels,
+            )))
+        }
+    }
+}
+
+#[inline]
+fn to_runtime_vertex(
+    v: LocalVertex<'static, DefaultId, InternalId>, prop_keys: Option<Vec<NameOrId>>,
+    snapshot: &GraphSnapshot,
+) -> Vertex {
+    // For vertices, we query properties via vid
+    let id = v.get_id() as ID;
+    let label = encode_runtime_label(v.get_label());
+    let details = LazyVertexDetails::new(v, prop_keys, snapshot.clone());
+    Vertex::new(id, Some(label), DynDetails::lazy(details))
+}
+
+#[inline]
+fn to_empty_vertex(id: DefaultId, label: StoreLabelId) -> Vertex {
+    Vertex::new(id as ID, Some(encode_runtime_label(label)), DynDetails::Empty)
+}
+
+#[inline]
+fn to_runtime_edge(
+    e: LocalEdge<'static, DefaultId, InternalId>, v: Option<ID>, prop_keys: Option<Vec<NameOrId>>,
+    partition_id: u8, snapshot: &GraphSnapshot,
+) -> Edge {
+    let src_id = e.get_src_id() as i64;
+    let dst_id = e.get_dst_id() as i64;
+    let src_label = e.get_src_label() as i64;
+    let dst_label = e.get_dst_label() as i64;
+    let label = e.get_label();
+    let offset = e.get_offset() as i64;
+    let edge_id = ((partition_id as i64) << 56)
+        + (src_label << 48)
+        + ((label as i64) << 40)
+        + (dst_label << 32)
+        + offset;
+    let mut e = if v.is_none() || v.unwrap() == src_id {
+        Edge::new(
+            edge_id,
+            Some(encode_runtime_label(label)),
+            src_id,
+            dst_id,
+            DynDetails::lazy(LazyEdgeDetails::new(e, prop_keys, snapshot.clone())),
+        )
+    } else {
+        Edge::with_from_src(
+            edge_id,
+            Some(encode_runtime_label(label)),
+            src_id,
+            dst_id,
+            false,
+            DynDetails::lazy(LazyEdgeDetails::new(e, prop_keys, snapshot.clone())),
+        )
+    };
+    e.set_src_label(encode_runtime_label(src_label as StoreLabelId));
+    e.set_dst_label(encode_runtime_label(dst_label as StoreLabelId));
+    e
+}
+
+/// LazyVertexDetails is used for local property fetching optimization.
+/// That is, the required properties will not be materialized until LazyVertexDetails need to be shuffled.
+#[allow(dead_code)]
+struct LazyVertexDetails {
+    // prop_keys specify the properties we would save for later queries after shuffle,
+    // excluding the ones used only when local property fetching.
+    // Specifically, Some(vec![]) indicates we need all properties
+    // and None indicates we do not need any property
+    prop_keys: Option<Vec<NameOrId>>,
+    inner: LocalVertex<'static, DefaultId, InternalId>,
+    // keep the snapshot that `inner` refers to alive
+    _snapshot: GraphSnapshot,
+}
+
+impl_as_any!(LazyVertexDetails);
+
+impl LazyVertexDetails {
+    pub fn new(
+        v: LocalVertex<'static, DefaultId, InternalId>, prop_keys: Option<Vec<NameOrId>>,
+        snapshot: GraphSnapshot,
+    ) -> Self {
+        LazyVertexDetails { prop_keys, inner: v, _snapshot: snapshot }
+    }
+}
+
+impl fmt::Debug for LazyVertexDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyVertexDetails")
+            .field("properties", &self.prop_keys)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl Details for LazyVertexDetails {
+    fn get_property(&self, key: &NameOrId) -> Option<PropertyValue> {
+        if let NameOrId::Str(key) = key {
+            if key == "id" {
+                let mask = (1_usize << LABEL_SHIFT_BITS) - 1;
+                let original_id = (self.inner.get_id() & mask) as i64;
+                Some(PropertyValue::Owned(Object::Primitive(Primitives::Long(original_id))))
+            } else {
+                self.inner
+                    .get_property(key)
+                    .map(|prop| to_property_value(prop))
+            }
+        } else {
+            info!("Have not support getting property by prop_id in bmcsr store yet");
+            None
+        }
+    }
+
+    fn get_all_properties(&self) -> Option<HashMap<NameOrId, Object>> {
+        // the case of get_all_properties from vertex;
+        if let Some(prop_key_vals) = self.inner.get_all_properties() {
+            let mut all_props: HashMap<NameOrId, Object> = prop_key_vals
+                .into_iter()
+                .map(|(prop_key, prop_val)| (prop_key.into(), to_object(prop_val)))
+                .collect();
+            let mask = (1_usize << LABEL_SHIFT_BITS) - 1;
+            let original_id = (self.inner.get_id() & mask) as i64;
+            all_props
+                .insert(NameOrId::Str("id".to_string()), Object::Primitive(Primitives::Long(original_id)));
+            Some(all_props)
+        } else {
+            None
+        }
+    }
+
+    fn get_property_keys(&self) -> Option<Vec<NameOrId>> {
+        self.prop_keys.clone()
+    }
+}
+
+/// LazyEdgeDetails is used for local property fetching optimization.
+/// That is, the required properties will not be materialized until LazyEdgeDetails need to be shuffled.
+#[allow(dead_code)]
+struct LazyEdgeDetails {
+    // prop_keys specify the properties we would save for later queries after shuffle,
+    // excluding the ones used only when local property fetching.
+    // Specifically, Some(vec![]) indicates we need all properties
+    // and None indicates we do not need any property,
+    prop_keys: Option<Vec<NameOrId>>,
+    inner: LocalEdge<'static, DefaultId, InternalId>,
+    // keep the snapshot that `inner` refers to alive
+    _snapshot: GraphSnapshot,
+}
+
+impl_as_any!(LazyEdgeDetails);
+
+impl LazyEdgeDetails {
+    pub fn new(
+        e: LocalEdge<'static, DefaultId, InternalId>, prop_keys: Option<Vec<NameOrId>>,
+        snapshot: GraphSnapshot,
+    ) -> Self {
+        LazyEdgeDetails { prop_keys, inner: e, _snapshot: snapshot }
+    }
+}
+
+impl fmt::Debug for LazyEdgeDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyEdgeDetails")
+            .field("prop_keys", &self.prop_keys)
+            .finish()
+    }
+}
+
+impl Details for LazyEdgeDetails {
+    fn get_property(&self, key: &NameOrId) -> Option<PropertyValue> {
+        if let NameOrId::Str(key) = key {
+            self.inner
+                .get_property(key)
+                .map(|prop| to_property_value(prop))
+        } else {
+            info!("Have not support getting property by prop_id in bmcsr store yet");
+            None
+        }
+    }
+
+    fn get_all_properties(&self) -> Option<HashMap<NameOrId, Object>> {
+        if let Some(prop_key_vals) = self.inner.get_all_properties() {
+            let all_props: HashMap<NameOrId, Object> = prop_key_vals
+                .into_iter()
+                .map(|(prop_key, prop_val)| (prop_key.into(), to_object(prop_val)))
+                .collect();
+            Some(all_props)
+        } else {
+            None
+        }
+    }
+
+    fn get_property_keys(&self) -> Option<Vec<NameOrId>> {
+        self.prop_keys.clone()
+    }
+}
+
+#[inline]
+fn to_object<'a>(ref_item: RefItem<'a>) -> Object {
+    match ref_item {
+        RefItem::Boolean(v) => Object::from(*v),
+        RefItem::Int32(v) => Object::Primitive(Primitives::Integer(*v)),
+        RefItem::UInt32(v) => Object::Primitive(Primitives::Integer(i32::try_from(*v).unwrap())),
+        RefItem::Int64(v) => Object::Primitive(Primitives::Long(*v)),
+        RefItem::UInt64(v) => Object::Primitive(Primitives::Long(i64::try_from(*v).unwrap())),
+        RefItem::Float(v) => Object::Primitive(Primitives::Float(*v as f64)),
+        RefItem::Double(v) => Object::Primitive(Primitives::Float(*v)),
+        RefItem::Date(v) => {
+            if let Some(date) = encode_date(v) {
+                Object::DateFormat(DateTimeFormats::Date(date))
+            } else {
+                Object::None
+            }
+        }
+        RefItem::DateTime(v) => {
+            if let Some(date_time) = encode_datetime(v) {
+                Object::DateFormat(DateTimeFormats::DateTime(date_time))
+            } else {
+                Object::None
+            }
+        }
+        RefItem::String(v) => Object::String(v.clone()),
+        RefItem::Int32List(v) => list_to_object(v),
+        RefItem::Int64List(v) => list_to_object(v),
+        RefItem::FloatList(v) => Object::Vector(
+            v.iter()
+                .map(|x| Object::from(*x as f64))
+                .collect(),
+        ),
+        RefItem::DoubleList(v) => list_to_object(v),
+        RefItem::StringList(v) => list_to_object(v),
+        _ => Object::None,
+    }
+}
+
+#[inline]
+fn to_property_value<'a>(ref_item: RefItem<'a>) -> PropertyValue {
+    match ref_item {
+        RefItem::Boolean(v) => Object::from(*v).into(),
+        RefItem::Int32(v) => BorrowObject::Primitive(Primitives::Integer(*v)).into(),
+        RefItem::UInt32(v) => {
+            BorrowObject::Primitive(Primitives::Integer(i32::try_from(*v).unwrap())).into()
+        }
+        RefItem::Int64(v) => BorrowObject::Primitive(Primitives::Long(*v)).into(),
+        RefItem::UInt64(v) => BorrowObject::Primitive(Primitives::Long(i64::try_from(*v).unwrap())).into(),
+        RefItem::Float(v) => BorrowObject::Primitive(Primitives::Float(*v as f64)).into(),
+        RefItem::Double(v) => BorrowObject::Primitive(Primitives::Float(*v)).into(),
+        RefItem::Date(v) => {
+            if let Some(date) = encode_date(v) {
+                Object::DateFormat(DateTimeFormats::Date(date)).into()
+            } else {
+                BorrowObject::None.into()
+            }
+        }
+        RefItem::DateTime(v) => {
+            if let Some(date_time) = encode_datetime(v) {
+                Object::DateFormat(DateTimeFormats::DateTime(date_time)).into()
+            } else {
+                BorrowObject::None.into()
+            }
+        }
+        RefItem::String(v) => BorrowObject::String(v).into(),
+        RefItem::Int32List(_)
+        | RefItem::Int64List(_)
+        | RefItem::FloatList(_)
+        | RefItem::DoubleList(_)
+        | RefItem::StringList(_) => to_object(ref_item).into(),
+        _ => BorrowObject::None.into(),
+    }
+}
+
+#[inline]
+fn list_to_object<T: Clone + Into<Object>>(list: &[T]) -> Object {
+    Object::Vector(list.iter().cloned().map(|x| x.into()).collect())
+}
+
+#[inline]
+fn encode_date(date: &Date) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDate::from_ymd_opt(date.year(), date.month(), date.day())
+}
+
+#[inline]
+fn encode_datetime(datetime: &DateTime) -> Option<chrono::NaiveDateTime> {
+    chrono::NaiveDateTime::from_timestamp_millis(datetime.to_i64())
+}
+
+#[inline]
+fn encode_runtime_label(l: StoreLabelId) -> LabelId {
+    l as LabelId
+}
+
+#[inline]
+pub(crate) fn encode_storage_label_id(label: LabelId) -> StoreLabelId {
+    label as StoreLabelId
+}
+
+/// Transform string-typed labels into a id-typed labels.
+/// `is_true_label` records whether the label is an actual label, or already transformed into
+/// an id-type.
+#[inline]
+fn encode_storage_label(labels: &Vec<LabelId>) -> Option<Vec<StoreLabelId>> {
+    if labels.is_empty() {
+        None
+    } else {
+        Some(
+            labels
+                .iter()
+                .map(|label| *label as StoreLabelId)
+                .collect::<Vec<StoreLabelId>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use bmcsr::graph_loader::GraphLoader;
+    use bmcsr::schema::CsrGraphSchema;
+    use bmcsr::types::{DIR_GRAPH_SCHEMA, FILE_SCHEMA};
+    use dyn_type::object;
+
+    use super::*;
+    use crate::apis::GraphElement;
+
+    const PERSON: StoreLabelId = 0;
+    const SOFTWARE: StoreLabelId = 1;
+    const KNOWS: LabelId = 0;
+    const CREATED: LabelId = 1;
+
+    struct SingleWorker;
+
+    impl ClusterInfo for SingleWorker {
+        fn get_server_num(&self) -> GraphProxyResult<u32> {
+            Ok(1)
+        }
+
+        fn get_server_index(&self) -> GraphProxyResult<u32> {
+            Ok(0)
+        }
+
+        fn get_local_worker_num(&self) -> GraphProxyResult<u32> {
+            Ok(1)
+        }
+
+        fn get_worker_index(&self) -> GraphProxyResult<u32> {
+            Ok(0)
+        }
+    }
+
+    /// Load the modern graph of the bmcsr crate into `root_dir` and open a store on it.
+    fn modern_store(root_dir: &Path) -> BmcsrStore {
+        let data_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../store/bmcsr/data");
+        let schema_file = data_dir.join("modern_schema.json");
+        let schema_dir = root_dir.join(DIR_GRAPH_SCHEMA);
+        std::fs::create_dir_all(&schema_dir).unwrap();
+        CsrGraphSchema::from_json_file(&schema_file)
+            .unwrap()
+            .to_json_file(schema_dir.join(FILE_SCHEMA))
+            .unwrap();
+        let mut loader = GraphLoader::<DefaultId, InternalId>::new(
+            data_dir.join("modern_graph"),
+            root_dir.to_path_buf(),
+            data_dir.join("modern_input.json"),
+            schema_file,
+            0,
+            1,
+        );
+        loader.skip_header();
+        loader.load().unwrap();
+
+        let graph = GraphDB::deserialize(root_dir.to_str().unwrap(), 0, None).unwrap();
+        let store: &'static BmcsrGraph = Box::leak(Box::new(BmcsrGraph::new(graph)));
+        BmcsrStore { store, cluster_info: Arc::new(SingleWorker) }
+    }
+
+    fn root_dir(name: &str) -> PathBuf {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("bmcsr_store_{}_{}", name, std::process::id()));
+        dir
+    }
+
+    fn vid(id: usize, label: StoreLabelId) -> ID {
+        LDBCVertexParser::<DefaultId>::to_global_id(id, label) as ID
+    }
+
+    fn sorted<T: Ord>(mut items: Vec<T>) -> Vec<T> {
+        items.sort();
+        items
+    }
+
+    #[test]
+    fn scan_vertex_test() {
+        let root = root_dir("scan_vertex_test");
+        let store = modern_store(&root);
+
+        let all = store
+            .scan_vertex(&QueryParams::default())
+            .unwrap()
+            .map(|v| v.id())
+            .collect();
+        let mut expected = vec![1, 2, 4, 6]
+            .into_iter()
+            .map(|id| vid(id, PERSON))
+            .collect::<Vec<_>>();
+        expected.extend(vec![vid(3, SOFTWARE), vid(5, SOFTWARE)]);
+        assert_eq!(sorted(all), sorted(expected));
+
+        let params = QueryParams { labels: vec![SOFTWARE as LabelId], ..Default::default() };
+        let software: Vec<Vertex> = store.scan_vertex(&params).unwrap().collect();
+        assert_eq!(software.len(), 2);
+        assert!(software
+            .iter()
+            .all(|v| v.label() == Some(SOFTWARE as LabelId)));
+        let names = software
+            .iter()
+            .map(|v| {
+                v.get_property(&"name".into())
+                    .unwrap()
+                    .try_to_owned()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(sorted(names), vec![object!("lop"), object!("ripple")]);
+        assert_eq!(store.count_vertex(&params).unwrap(), 2);
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn index_scan_vertex_test() {
+        let root = root_dir("index_scan_vertex_test");
+        let store = modern_store(&root);
+
+        let pk: PKV = (BMCSR_STORE_PK.into(), object!(1u64)).into();
+        let marko = store
+            .index_scan_vertex(PERSON as LabelId, &pk, &QueryParams::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(marko.id(), vid(1, PERSON));
+        assert_eq!(
+            marko
+                .get_property(&"age".into())
+                .unwrap()
+                .try_to_owned()
+                .unwrap(),
+            object!(29)
+        );
+        match store.get_primary_key(&marko.id()).unwrap() {
+            Some(OneOrMany::One([(key, value)])) => {
+                assert_eq!(key, NameOrId::from(BMCSR_STORE_PK));
+                assert_eq!(value, object!(1));
+            }
+            pk => panic!("unexpected primary key {:?}", pk),
+        }
+
+        let pk: PKV = (BMCSR_STORE_PK.into(), object!(7u64)).into();
+        assert!(store
+            .index_scan_vertex(PERSON as LabelId, &pk, &QueryParams::default())
+            .unwrap()
+            .is_none());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn scan_edge_test() {
+        let root = root_dir("scan_edge_test");
+        let store = modern_store(&root);
+
+        let all = store
+            .scan_edge(&QueryParams::default())
+            .unwrap()
+            .map(|e| (e.src_id, e.dst_id))
+            .collect();
+        let expected = vec![
+            (vid(1, PERSON), vid(2, PERSON)),
+            (vid(1, PERSON), vid(4, PERSON)),
+            (vid(1, PERSON), vid(3, SOFTWARE)),
+            (vid(4, PERSON), vid(5, SOFTWARE)),
+            (vid(4, PERSON), vid(3, SOFTWARE)),
+            (vid(6, PERSON), vid(3, SOFTWARE)),
+        ];
+        assert_eq!(sorted(all), sorted(expected));
+
+        let params = QueryParams { labels: vec![KNOWS], ..Default::default() };
+        let knows: Vec<Edge> = store.scan_edge(&params).unwrap().collect();
+        assert_eq!(knows.len(), 2);
+        for e in knows.iter() {
+            assert_eq!(e.label(), Some(KNOWS));
+            let weight = e
+                .get_property(&"weight".into())
+                .unwrap()
+                .try_to_owned()
+                .unwrap();
+            if e.dst_id == vid(2, PERSON) {
+                assert_eq!(weight, object!(0.5));
+            } else {
+                assert_eq!(weight, object!(1.0));
+            }
+        }
+        assert_eq!(store.count_edge(&params).unwrap(), 2);
+        assert_eq!(
+            store
+                .count_edge(&QueryParams::default())
+                .unwrap(),
+            6
+        );
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn explore_vertex_test() {
+        let root = root_dir("explore_vertex_test");
+        let store = modern_store(&root);
+        let expand = |direction: Direction, labels: Vec<LabelId>, v: ID| {
+            let params = QueryParams { labels, ..Default::default() };
+            let stmt = store
+                .prepare_explore_vertex(direction, &params)
+                .unwrap();
+            sorted(stmt.exec(v).unwrap().map(|v| v.id()).collect())
+        };
+
+        let marko = vid(1, PERSON);
+        assert_eq!(
+            expand(Direction::Out, vec![], marko),
+            sorted(vec![vid(2, PERSON), vid(4, PERSON), vid(3, SOFTWARE)])
+        );
+        assert_eq!(
+            expand(Direction::Out, vec![KNOWS], marko),
+            sorted(vec![vid(2, PERSON), vid(4, PERSON)])
+        );
+        assert_eq!(expand(Direction::In, vec![], marko), Vec::<ID>::new());
+
+        let lop = vid(3, SOFTWARE);
+        assert_eq!(
+            expand(Direction::In, vec![CREATED], lop),
+            sorted(vec![vid(1, PERSON), vid(4, PERSON), vid(6, PERSON)])
+        );
+
+        let josh = vid(4, PERSON);
+        assert_eq!(
+            expand(Direction::Both, vec![], josh),
+            sorted(vec![vid(1, PERSON), vid(3, SOFTWARE), vid(5, SOFTWARE)])
+        );
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn explore_edge_test() {
+        let root = root_dir("explore_edge_test");
+        let store = modern_store(&root);
+        let params = QueryParams { labels: vec![CREATED], ..Default::default() };
+
+        let lop = vid(3, SOFTWARE);
+        let stmt = store
+            .prepare_explore_edge(Direction::In, &params)
+            .unwrap();
+        let edges: Vec<Edge> = stmt.exec(lop).unwrap().collect();
+        assert_eq!(
+            sorted(edges.iter().map(|e| e.src_id).collect()),
+            sorted(vec![vid(1, PERSON), vid(4, PERSON), vid(6, PERSON)])
+        );
+        // the edges are expanded from their destination
+        assert!(edges
+            .iter()
+            .all(|e| e.dst_id == lop && e.get_other_id() == e.src_id));
+
+        let josh = vid(4, PERSON);
+        let stmt = store
+            .prepare_explore_edge(Direction::Out, &params)
+            .unwrap();
+        let weights = stmt
+            .exec(josh)
+            .unwrap()
+            .map(|e| {
+                (
+                    e.dst_id,
+                    e.get_property(&"weight".into())
+                        .unwrap()
+                        .try_to_owned()
+                        .unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(weights.len(), 2);
+        assert!(weights.contains(&(vid(5, SOFTWARE), object!(1.0))));
+        assert!(weights.contains(&(vid(3, SOFTWARE), object!(0.4))));
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,299 @@
+//
+//! Copyright 2023 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::collections::BTreeMap;
+
+use bmcsr::columns::*;
+use bmcsr::date::Date;
+use bmcsr::date_time::DateTime;
+use bmcsr::graph_modifier::{
+    ColumnMappings, ColumnMetadata, DataFrame, EdgeMappings, Input, VertexMappings, WriteOperation,
+};
+use bmcsr::ldbc_parser::LDBCVertexParser;
+use bmcsr::schema::Schema;
+use bmcsr::types::DefaultId;
+use bmcsr::types::LabelId as StoreLabelId;
+use chrono::Datelike;
+use dyn_type::{CastError, Object};
+use ir_common::{LabelId, NameOrId, OneOrMany};
+
+use super::read_graph::{encode_storage_label_id, BmcsrGraph};
+use crate::apis::graph::PKV;
+use crate::apis::{Details, DynDetails, WriteGraphProxy};
+use crate::{GraphProxyError, GraphProxyResult};
+
+/// The vertices or edges buffered for one label (or one `src_label - edge_label - dst_label` triplet),
+/// with their properties kept in the order of the property header in the graph schema.
+#[derive(Default)]
+struct BufferedRows {
+    ids: Vec<Vec<u64>>,
+    properties: Vec<Vec<Item>>,
+}
+
+/// A graph writer that buffers the vertices and edges to add, and transforms them into a batch of
+/// bmcsr `WriteOperation`s that is applied to the graph on `finish()`.
+pub struct BmcsrGraphWriter {
+    graph: &'static BmcsrGraph,
+    vertices: BTreeMap<StoreLabelId, BufferedRows>,
+    edges: BTreeMap<(StoreLabelId, StoreLabelId, StoreLabelId), BufferedRows>,
+    parallel: u32,
+}
+
+impl BmcsrGraphWriter {
+    pub fn new(graph: &'static BmcsrGraph, parallel: u32) -> Self {
+        BmcsrGraphWriter { graph, vertices: BTreeMap::new(), edges: BTreeMap::new(), parallel }
+    }
+
+    fn encode_global_id(&self, label: StoreLabelId, vertex_pk: PKV) -> GraphProxyResult<u64> {
+        match vertex_pk {
+            OneOrMany::One(pkv) => {
+                let outer_id = pkv[0].1.as_u64().map_err(|e| {
+                    GraphProxyError::write_graph_error(&format!("cast outer_id as u64 failed {:?}", e))
+                })?;
+                Ok(LDBCVertexParser::<DefaultId>::to_global_id(outer_id as usize, label) as u64)
+            }
+            OneOrMany::Many(_) => Err(GraphProxyError::write_graph_error(
+                "encode_global_id failed as bmcsr only support single-column pk",
+            )),
+        }
+    }
+
+    fn encode_details(
+        &self, header: Option<&[(String, DataType)]>, details: DynDetails,
+    ) -> GraphProxyResult<Vec<Item>> {
+        let mut properties = vec![];
+        if let Some(header) = header {
+            for (prop_name, data_type) in header {
+                let item = if let Some(prop_val) = details.get_property(&NameOrId::Str(prop_name.clone())) {
+                    let obj = prop_val.try_to_owned().ok_or_else(|| {
+                        GraphProxyError::write_graph_error(&format!(
+                            "get property {:?} of {:?} failed",
+                            prop_name, details
+                        ))
+                    })?;
+                    object_to_item(obj, *data_type)?
+                } else {
+                    Item::Null
+                };
+                properties.push(item);
+            }
+        }
+        Ok(properties)
+    }
+}
+
+impl WriteGraphProxy for BmcsrGraphWriter {
+    fn add_vertex(
+        &mut self, label: LabelId, vertex_pk: PKV, properties: DynDetails,
+    ) -> GraphProxyResult<()> {
+        let label = encode_storage_label_id(label);
+        let global_id = self.encode_global_id(label, vertex_pk)?;
+        let snapshot = self.graph.snapshot();
+        let header = snapshot.graph_schema.get_vertex_header(label);
+        if header.is_none() {
+            Err(GraphProxyError::write_graph_error(&format!("vertex label {} not found", label)))?
+        }
+        let properties = self.encode_details(header, properties)?;
+        let rows = self.vertices.entry(label).or_default();
+        rows.ids.push(vec![global_id]);
+        rows.properties.push(properties);
+        Ok(())
+    }
+
+    fn add_edge(
+        &mut self, label: LabelId, src_vertex_label: LabelId, src_vertex_pk: PKV,
+        dst_vertex_label: LabelId, dst_vertex_pk: PKV, properties: DynDetails,
+    ) -> GraphProxyResult<()> {
+        let edge_label = encode_storage_label_id(label);
+        let src_label = encode_storage_label_id(src_vertex_label);
+        let dst_label = encode_storage_label_id(dst_vertex_label);
+        let src_id = self.encode_global_id(src_label, src_vertex_pk)?;
+        let dst_id = self.encode_global_id(dst_label, dst_vertex_pk)?;
+        let snapshot = self.graph.snapshot();
+        let header = snapshot
+            .graph_schema
+            .get_edge_header(src_label, edge_label, dst_label);
+        if header.is_none() {
+            Err(GraphProxyError::write_graph_error(&format!(
+                "edge label {}_{}_{} not found",
+                src_label, edge_label, dst_label
+            )))?
+        }
+        let properties = self.encode_details(header, properties)?;
+        let rows = self
+            .edges
+            .entry((src_label, edge_label, dst_label))
+            .or_default();
+        rows.ids.push(vec![src_id, dst_id]);
+        rows.properties.push(properties);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> GraphProxyResult<()> {
+        let snapshot = self.graph.snapshot();
+        let graph_schema = &snapshot.graph_schema;
+        let mut write_operations = vec![];
+        // vertices are inserted ahead of edges, as the edges refer to their end vertices
+        for (label, rows) in std::mem::take(&mut self.vertices) {
+            let header = graph_schema
+                .get_vertex_header(label)
+                .unwrap_or(&[]);
+            let (data_frame, column_mappings) = build_data_frame(&["id"], header, rows);
+            write_operations.push(WriteOperation::insert_vertices(VertexMappings::new(
+                label,
+                vec![Input::memory(data_frame)],
+                column_mappings,
+            )));
+        }
+        for ((src_label, edge_label, dst_label), rows) in std::mem::take(&mut self.edges) {
+            let header = graph_schema
+                .get_edge_header(src_label, edge_label, dst_label)
+                .unwrap_or(&[]);
+            let (data_frame, mut column_mappings) = build_data_frame(&["src_id", "dst_id"], header, rows);
+            let prop_mappings = column_mappings.split_off(2);
+            let dst_mappings = vec![column_mappings.pop().unwrap()];
+            let src_mappings = vec![column_mappings.pop().unwrap()];
+            write_operations.push(WriteOperation::insert_edges(EdgeMappings::new(
+                src_label,
+                edge_label,
+                dst_label,
+                vec![Input::memory(data_frame)],
+                src_mappings,
+                dst_mappings,
+                prop_mappings,
+            )));
+        }
+        if !write_operations.is_empty() {
+            self.graph
+                .apply_write_operations(write_operations, self.parallel);
+        }
+        Ok(())
+    }
+}
+
+/// Build an in-memory `DataFrame` of the buffered rows, where the leading columns are the given
+/// id columns, followed by the property columns in the order of `header`.
+fn build_data_frame(
+    id_columns: &[&str], header: &[(String, DataType)], rows: BufferedRows,
+) -> (DataFrame, Vec<ColumnMappings>) {
+    let mut data_frame = DataFrame::new(vec![]);
+    let mut column_mappings = vec![];
+    for (index, column_name) in id_columns.iter().enumerate() {
+        let data = rows.ids.iter().map(|ids| ids[index]).collect();
+        data_frame.add_column(ColumnMetadata::new(
+            Box::new(UInt64Column { data }),
+            column_name.to_string(),
+            DataType::ID,
+        ));
+        column_mappings.push(ColumnMappings::new(
+            index as i32,
+            column_name.to_string(),
+            DataType::ID,
+            "id".to_string(),
+        ));
+    }
+    for (prop_index, (prop_name, data_type)) in header.iter().enumerate() {
+        let mut column = new_column(*data_type);
+        for properties in rows.properties.iter() {
+            column.push(properties[prop_index].clone());
+        }
+        let column_index = (id_columns.len() + prop_index) as i32;
+        data_frame.add_column(ColumnMetadata::new(column, prop_name.clone(), *data_type));
+        column_mappings.push(ColumnMappings::new(
+            column_index,
+            prop_name.clone(),
+            *data_type,
+            prop_name.clone(),
+        ));
+    }
+    (data_frame, column_mappings)
+}
+
+fn new_column(data_type: DataType) -> Box<dyn Column> {
+    match data_type {
+        DataType::Int32 => Box::new(Int32Column::new()),
+        DataType::UInt32 => Box::new(UInt32Column::new()),
+        DataType::Int64 => Box::new(Int64Column::new()),
+        DataType::UInt64 => Box::new(UInt64Column::new()),
+        DataType::Double => Box::new(DoubleColumn::new()),
+        DataType::String => Box::new(StringColumn::new()),
+        DataType::LCString => Box::new(LCStringColumn::new()),
+        DataType::Date => Box::new(DateColumn::new()),
+        DataType::DateTime => Box::new(DateTimeColumn::new()),
+        DataType::Boolean => Box::new(BooleanColumn::new()),
+        DataType::Float => Box::new(FloatColumn::new()),
+        DataType::Int32List => Box::new(Int32ListColumn::new()),
+        DataType::Int64List => Box::new(Int64ListColumn::new()),
+        DataType::FloatList => Box::new(FloatListColumn::new()),
+        DataType::DoubleList => Box::new(DoubleListColumn::new()),
+        DataType::StringList => Box::new(StringListColumn::new()),
+        DataType::ID | DataType::NULL => Box::new(IDColumn::new()),
+    }
+}
+
+fn object_to_item(obj: Object, data_type: DataType) -> GraphProxyResult<Item> {
+    let cast_err = |e: CastError| {
+        GraphProxyError::write_graph_error(&format!("cast property to {:?} failed {:?}", data_type, e))
+    };
+    if obj == Object::None {
+        return Ok(Item::Null);
+    }
+    let item = match data_type {
+        DataType::Int32 => Item::Int32(obj.as_i32().map_err(cast_err)?),
+        DataType::UInt32 => Item::UInt32(obj.as_u32().map_err(cast_err)?),
+        DataType::Int64 => Item::Int64(obj.as_i64().map_err(cast_err)?),
+        DataType::UInt64 => Item::UInt64(obj.as_u64().map_err(cast_err)?),
+        DataType::Double => Item::Double(obj.as_f64().map_err(cast_err)?),
+        DataType::String | DataType::LCString => Item::String(obj.as_str().map_err(cast_err)?.into_owned()),
+        DataType::Date => {
+            let date = obj
+                .as_date_format()
+                .and_then(|date_format| date_format.as_date())
+                .map_err(cast_err)?;
+            Item::Date(Date::new(date.year(), date.month(), date.day()))
+        }
+        DataType::DateTime => {
+            let date_time = obj
+                .as_date_format()
+                .and_then(|date_format| date_format.as_date_time())
+                .map_err(cast_err)?;
+            Item::DateTime(DateTime::new(date_time.timestamp_millis()))
+        }
+        DataType::ID => Item::VertexId(obj.as_u64().map_err(cast_err)? as usize),
+        DataType::Boolean => Item::Boolean(obj.as_bool().map_err(cast_err)?),
+        DataType::Float => Item::Float(obj.as_f64().map_err(cast_err)? as f32),
+        DataType::Int32List => Item::Int32List(object_to_list(&obj, |v| v.as_i32()).map_err(cast_err)?),
+        DataType::Int64List => Item::Int64List(object_to_list(&obj, |v| v.as_i64()).map_err(cast_err)?),
+        DataType::FloatList => {
+            Item::FloatList(object_to_list(&obj, |v| v.as_f64().map(|x| x as f32)).map_err(cast_err)?)
+        }
+        DataType::DoubleList => Item::DoubleList(object_to_list(&obj, |v| v.as_f64()).map_err(cast_err)?),
+        DataType::StringList => Item::StringList(
+            object_to_list(&obj, |v| v.as_str().map(|x| x.into_owned())).map_err(cast_err)?,
+        ),
+        DataType::NULL => Item::Null,
+    };
+    Ok(item)
+}
+
+fn object_to_list<T, F>(obj: &Object, f: F) -> Result<Vec<T>, CastError>
+where
+    F: Fn(&Object) -> Result<T, CastError>,
+{
+    match obj {
+        Object::Vector(vec) => vec.iter().map(f).collect(),
+        _ => Err(CastError::new::<Vec<T>>(obj.raw_type())),
+    }
+}
@@ -397,10 +397,12 @@ impl Details for LazyEdgeDetails {
 #[inline]
 fn to_object<'a>(ref_item: RefItem<'a>) -> Object {
     match ref_item {
+        RefItem::Boolean(v) => Object::from(*v),
         RefItem::Int32(v) => Object::Primitive(Primitives::Integer(*v)),
         RefItem::UInt32(v) => Object::Primitive(Primitives::Integer(i32::try_from(*v).unwrap())),
         RefItem::Int64(v) => Object::Primitive(Primitives::Long(*v)),
         RefItem::UInt64(v) => Object::Primitive(Primitives::Long(i64::try_from(*v).unwrap())),
+        RefItem::Float(v) => Object::Primitive(Primitives::Float(*v as f64)),
         RefItem::Double(v) => Object::Primitive(Primitives::Float(*v)),
         RefItem::Date(v) => {
             if let Some(date) = encode_date(v) {
@@ -417,6 +419,15 @@ fn to_object<'a>(ref_item: RefItem<'a>) -> Object {
             }
         }
         RefItem::String(v) => Object::String(v.clone()),
+        RefItem::Int32List(v) => list_to_object(v),
+        RefItem::Int64List(v) => list_to_object(v),
+        RefItem::FloatList(v) => Object::Vector(
+            v.iter()
+                .map(|x| Object::from(*x as f64))
+                .collect(),
+        ),
+        RefItem::DoubleList(v) => list_to_object(v),
+        RefItem::StringList(v) => list_to_object(v),
         _ => Object::None,
     }
 }
@@ -424,12 +435,14 @@ fn to_object<'a>(ref_item: RefItem<'a>) -> Object {
 #[inline]
 fn to_property_value<'a>(ref_item: RefItem<'a>) -> PropertyValue {
     match ref_item {
+        RefItem::Boolean(v) => Object::from(*v).into(),
         RefItem::Int32(v) => BorrowObject::Primitive(Primitives::Integer(*v)).into(),
         RefItem::UInt32(v) => {
             BorrowObject::Primitive(Primitives::Integer(i32::try_from(*v).unwrap())).into()
         }
         RefItem::Int64(v) => BorrowObject::Primitive(Primitives::Long(*v)).into(),
         RefItem::UInt64(v) => BorrowObject::Primitive(Primitives::Long(i64::try_from(*v).unwrap())).into(),
+        RefItem::Float(v) => BorrowObject::Primitive(Primitives::Float(*v as f64)).into(),
         RefItem::Double(v) => BorrowObject::Primitive(Primitives::Float(*v)).into(),
         RefItem::Date(v) => {
             if let Some(date) = encode_date(v) {
@@ -446,10 +459,20 @@ fn to_property_value<'a>(ref_item: RefItem<'a>) -> PropertyValue {
             }
         }
         RefItem::String(v) => BorrowObject::String(v).into(),
+        RefItem::Int32List(_)
+        | RefItem::Int64List(_)
+        | RefItem::FloatList(_)
+        | RefItem::DoubleList(_)
+        | RefItem::StringList(_) => to_object(ref_item).into(),
         _ => BorrowObject::None.into(),
     }
 }
 
+#[inline]
+fn list_to_object<T: Clone + Into<Object>>(list: &[T]) -> Object {
+    Object::Vector(list.iter().cloned().map(|x| x.into()).collect())
+}
+
 #[inline]
 fn encode_date(date: &Date) -> Option<chrono::NaiveDate> {
     chrono::NaiveDate::from_ymd_opt(date.year(), date.month(), date.day())
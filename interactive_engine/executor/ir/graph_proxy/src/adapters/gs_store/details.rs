@@ -16,11 +16,11 @@
 use std::fmt;
 
 use ahash::HashMap;
-use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
 use dyn_type::DateTimeFormats;
 use dyn_type::Object;
 use dyn_type::Primitives;
-use global_query::store_api::prelude::Property;
+use global_query::store_api::prelude::{decimal_to_string, Property};
 use global_query::store_api::PropId;
 use global_query::store_api::{Edge as StoreEdge, Vertex as StoreVertex};
 use ir_common::{KeyId, NameOrId};
@@ -28,17 +28,18 @@ use pegasus_common::downcast::*;
 
 use crate::apis::Details;
 use crate::apis::PropertyValue;
+use crate::errors::{GraphProxyError, GraphProxyResult};
 
 #[inline]
-fn encode_runtime_property(prop_id: PropId, prop_val: Property) -> (NameOrId, Object) {
+fn encode_runtime_property(prop_id: PropId, prop_val: Property) -> GraphProxyResult<(NameOrId, Object)> {
     let prop_key = NameOrId::Id(prop_id as KeyId);
-    let prop_val = encode_runtime_prop_val(prop_val);
-    (prop_key, prop_val)
+    let prop_val = encode_runtime_prop_val(prop_val)?;
+    Ok((prop_key, prop_val))
 }
 
 #[inline]
-fn encode_runtime_prop_val(prop_val: Property) -> Object {
-    match prop_val {
+fn encode_runtime_prop_val(prop_val: Property) -> GraphProxyResult<Object> {
+    let obj = match prop_val {
         Property::Bool(b) => b.into(),
         Property::Char(c) => {
             if c <= (i8::MAX as u8) {
@@ -60,14 +61,70 @@ fn encode_runtime_prop_val(prop_val: Property) -> Object {
                 Ok(time) => Object::DateFormat(DateTimeFormats::Time(time)),
                 Err(_) => match NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S.%6f") {
                     Ok(datetime) => Object::DateFormat(DateTimeFormats::DateTime(datetime)),
-                    Err(_) => {
-                        unimplemented!("Failed to parse the datetime/timestamp property value: '{}'", s)
-                    }
+                    Err(_) => Err(GraphProxyError::query_store_error(&format!(
+                        "Failed to parse the datetime/timestamp property value: '{}'",
+                        s
+                    )))?,
                 },
             },
         },
-        _ => unimplemented!(),
-    }
+        Property::DateTime(millis) => match DateTime::from_timestamp_millis(millis) {
+            Some(datetime) => Object::DateFormat(DateTimeFormats::DateTime(datetime.naive_utc())),
+            None => Err(GraphProxyError::query_store_error(&format!(
+                "Failed to parse the datetime property value: '{}'",
+                millis
+            )))?,
+        },
+        Property::DateTimeWithTz(millis, offset) => {
+            match FixedOffset::east_opt(offset).and_then(|tz| tz.timestamp_millis_opt(millis).single()) {
+                Some(datetime) => Object::DateFormat(DateTimeFormats::DateTimeWithTz(datetime)),
+                None => Err(GraphProxyError::query_store_error(&format!(
+                    "Failed to parse the datetime with timezone property value: '{}', '{}'",
+                    millis, offset
+                )))?,
+            }
+        }
+        // the runtime has no decimal type, a double keeps the decimals ordered numerically; parsing
+        // the exact notation rounds it to the nearest double
+        Property::Decimal(unscaled, scale) => {
+            let decimal = decimal_to_string(unscaled, scale);
+            match decimal.parse::<f64>() {
+                Ok(d) => Object::Primitive(Primitives::Float(d)),
+                Err(_) => Err(GraphProxyError::query_store_error(&format!(
+                    "Failed to parse the decimal property value: '{}'",
+                    decimal
+                )))?,
+            }
+        }
+        prop_val => Err(GraphProxyError::unsupported_error(&format!(
+            "Unsupported property value in gs_store: {:?}",
+            prop_val
+        )))?,
+    };
+    Ok(obj)
+}
+
+/// Encode a property value for `Details::get_property`, which can't return an error; the error is
+/// logged and the property is taken as absent.
+#[inline]
+fn encode_runtime_prop_val_or_log(prop_val: Property) -> Option<Object> {
+    encode_runtime_prop_val(prop_val)
+        .map_err(|e| error!("{}", e))
+        .ok()
+}
+
+/// Encode all the property values for `Details::get_all_properties`, skipping the ones failed to encode.
+#[inline]
+fn encode_runtime_properties<I: Iterator<Item = (PropId, Property)>>(
+    props: I,
+) -> HashMap<NameOrId, Object> {
+    props
+        .filter_map(|(prop_id, prop_val)| {
+            encode_runtime_property(prop_id, prop_val)
+                .map_err(|e| error!("{}", e))
+                .ok()
+        })
+        .collect()
 }
 
 /// LazyVertexDetails is used for local property fetching optimization.
@@ -114,7 +171,8 @@ where
         if let NameOrId::Id(key) = key {
             self.inner
                 .get_property(*key as PropId)
-                .map(|prop| PropertyValue::Owned(encode_runtime_prop_val(prop)))
+                .and_then(encode_runtime_prop_val_or_log)
+                .map(PropertyValue::Owned)
         } else {
             info!("Have not support getting property by prop_name in gs_store yet");
             None
@@ -122,12 +180,7 @@ where
     }
 
     fn get_all_properties(&self) -> Option<HashMap<NameOrId, Object>> {
-        Some(
-            self.inner
-                .get_properties()
-                .map(|(prop_id, prop_val)| encode_runtime_property(prop_id, prop_val))
-                .collect(),
-        )
+        Some(encode_runtime_properties(self.inner.get_properties()))
     }
 
     fn get_property_keys(&self) -> Option<Vec<NameOrId>> {
@@ -191,7 +244,8 @@ where
         if let NameOrId::Id(key) = key {
             self.inner
                 .get_property(*key as PropId)
-                .map(|prop| PropertyValue::Owned(encode_runtime_prop_val(prop)))
+                .and_then(encode_runtime_prop_val_or_log)
+                .map(PropertyValue::Owned)
         } else {
             info!("Have not support getting property by prop_name in gs_store yet");
             None
@@ -200,12 +254,7 @@ where
 
     fn get_all_properties(&self) -> Option<HashMap<NameOrId, Object>> {
         // the case of get_all_properties from vertex;
-        Some(
-            self.inner
-                .get_properties()
-                .map(|(prop_id, prop_val)| encode_runtime_property(prop_id, prop_val))
-                .collect(),
-        )
+        Some(encode_runtime_properties(self.inner.get_properties()))
     }
 
     fn get_property_keys(&self) -> Option<Vec<NameOrId>> {
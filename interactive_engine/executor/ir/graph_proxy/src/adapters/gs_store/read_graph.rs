@@ -27,6 +27,7 @@ use global_query::{
     GlobalGraphQuery, GraphPartitionManager, PartitionLabeledVertexIds, PartitionVertexIds,
 };
 use graph_store::utils::IterList;
+use ir_common::utils::{ORDER_BY_KEY, ORDER_DESC_KEY, ORDER_LIMIT_KEY};
 use ir_common::{KeyId, LabelId, NameOrId, OneOrMany};
 
 use crate::adapters::gs_store::details::{LazyEdgeDetails, LazyVertexDetails};
@@ -96,7 +97,7 @@ where
         debug!("scan_vertex worker_partitions: {:?}", worker_partitions);
         if !worker_partitions.is_empty() {
             let store = self.store.clone();
-            let si = get_snapshot_id(params);
+            let si = self.get_snapshot_id(params)?;
             let label_ids = encode_storage_labels(params.labels.as_ref())?;
             let row_filter = params.filter.clone();
 
@@ -191,7 +192,7 @@ where
         let worker_partitions = assign_worker_partitions(&self.server_partitions, &self.cluster_info)?;
         if !worker_partitions.is_empty() {
             let store = self.store.clone();
-            let si = get_snapshot_id(params);
+            let si = self.get_snapshot_id(params)?;
             let label_ids = encode_storage_labels(params.labels.as_ref())?;
             let row_filter = params.filter.clone();
 
@@ -236,7 +237,7 @@ where
         &self, ids: &[ID], params: &QueryParams,
     ) -> GraphProxyResult<Box<dyn Iterator<Item = Vertex> + Send>> {
         let store = self.store.clone();
-        let si = get_snapshot_id(params);
+        let si = self.get_snapshot_id(params)?;
 
         let column_filter_pushdown = self.column_filter_pushdown;
         // also need props in filter, because `filter_limit!`
@@ -279,7 +280,7 @@ where
         let limit = params.limit.clone();
         let store = self.store.clone();
         let partition_manager = self.partition_manager.clone();
-        let si = get_snapshot_id(params);
+        let si = self.get_snapshot_id(params)?;
         let edge_label_ids = encode_storage_labels(params.labels.as_ref())?;
 
         let stmt = from_fn(move |v: ID| {
@@ -340,7 +341,7 @@ where
         &self, direction: Direction, params: &QueryParams,
     ) -> GraphProxyResult<Box<dyn Statement<ID, Edge>>> {
         let store = self.store.clone();
-        let si = get_snapshot_id(params);
+        let si = self.get_snapshot_id(params)?;
 
         let partition_manager = self.partition_manager.clone();
         let row_filter = params.filter.clone();
@@ -364,20 +365,32 @@ where
         let limit = params.limit.clone();
         let columns = params.columns.clone();
         let edge_label_ids = encode_storage_labels(params.labels.as_ref())?;
+        let sorted_limit = self.get_sorted_limit(si, direction, &edge_label_ids, params);
 
         let stmt = from_fn(move |v: ID| {
             let src_id = get_partition_vertex_id(v, partition_manager.clone());
             let iter_list = match direction {
                 Direction::Out => {
-                    let mut res_iter = store.get_out_edges(
-                        si,
-                        vec![src_id],
-                        edge_label_ids.as_ref(),
-                        condition.as_ref(),
-                        None,
-                        prop_ids.as_ref(),
-                        limit.unwrap_or(0),
-                    );
+                    let mut res_iter = if let Some(sorted_limit) = sorted_limit {
+                        store.get_sorted_out_edges(
+                            si,
+                            vec![src_id],
+                            edge_label_ids[0],
+                            condition.as_ref(),
+                            prop_ids.as_ref(),
+                            sorted_limit,
+                        )
+                    } else {
+                        store.get_out_edges(
+                            si,
+                            vec![src_id],
+                            edge_label_ids.as_ref(),
+                            condition.as_ref(),
+                            None,
+                            prop_ids.as_ref(),
+                            limit.unwrap_or(0),
+                        )
+                    };
                     if let Some(ei) = res_iter.next().map(|(_src, ei)| ei) {
                         let iter = RuntimeEdgeIter::new(ei, true, columns.clone());
                         IterList::new(vec![iter])
@@ -386,15 +399,26 @@ where
                     }
                 }
                 Direction::In => {
-                    let mut res_iter = store.get_in_edges(
-                        si,
-                        vec![src_id],
-                        edge_label_ids.as_ref(),
-                        condition.as_ref(),
-                        None,
-                        prop_ids.as_ref(),
-                        limit.unwrap_or(0),
-                    );
+                    let mut res_iter = if let Some(sorted_limit) = sorted_limit {
+                        store.get_sorted_in_edges(
+                            si,
+                            vec![src_id],
+                            edge_label_ids[0],
+                            condition.as_ref(),
+                            prop_ids.as_ref(),
+                            sorted_limit,
+                        )
+                    } else {
+                        store.get_in_edges(
+                            si,
+                            vec![src_id],
+                            edge_label_ids.as_ref(),
+                            condition.as_ref(),
+                            None,
+                            prop_ids.as_ref(),
+                            limit.unwrap_or(0),
+                        )
+                    };
                     if let Some(ei) = res_iter.next().map(|(_dst, ei)| ei) {
                         let iter = RuntimeEdgeIter::new(ei, false, columns.clone());
                         IterList::new(vec![iter])
@@ -459,7 +483,7 @@ where
             let worker_partitions = assign_worker_partitions(&self.server_partitions, &self.cluster_info)?;
             if !worker_partitions.is_empty() {
                 let store = self.store.clone();
-                let si = get_snapshot_id(params);
+                let si = self.get_snapshot_id(params)?;
                 let label_ids = encode_storage_labels(params.labels.as_ref())?;
                 let count =
                     store.count_all_vertices(si, label_ids.as_ref(), None, worker_partitions.as_ref());
@@ -477,7 +501,7 @@ where
             let worker_partitions = assign_worker_partitions(&self.server_partitions, &self.cluster_info)?;
             if !worker_partitions.is_empty() {
                 let store = self.store.clone();
-                let si = get_snapshot_id(params);
+                let si = self.get_snapshot_id(params)?;
                 let label_ids = encode_storage_labels(params.labels.as_ref())?;
                 let count = store.count_all_edges(si, label_ids.as_ref(), None, worker_partitions.as_ref());
                 Ok(count)
@@ -488,15 +512,90 @@ where
     }
 }
 
-fn get_snapshot_id(params: &QueryParams) -> SnapshotId {
-    let si = params
-        .get_extra_param(SNAPSHOT_ID)
-        .map(|s| {
-            s.parse::<SnapshotId>()
-                .unwrap_or(DEFAULT_SNAPSHOT_ID)
+impl<V, VI, E, EI> GraphScopeStore<V, VI, E, EI>
+where
+    V: StoreVertex + 'static,
+    VI: Iterator<Item = V> + Send + 'static,
+    E: StoreEdge + 'static,
+    EI: Iterator<Item = E> + Send + 'static,
+{
+    /// The snapshot the query reads, which is the latest one unless the query is pinned to an earlier
+    /// snapshot by `SID` in the extra params, e.g., to reproduce an earlier answer for auditing. A
+    /// pinned snapshot must not be behind the gc watermark of the store, as the versions it needs
+    /// may have been collected, nor after the latest snapshot of the store, which has no data yet.
+    fn get_snapshot_id(&self, params: &QueryParams) -> GraphProxyResult<SnapshotId> {
+        resolve_snapshot_id(params, || {
+            (self.store.get_gc_snapshot_id(), self.store.get_latest_snapshot_id())
         })
-        .unwrap_or(DEFAULT_SNAPSHOT_ID);
-    si
+    }
+
+    /// The planner may ask for only the first edges of each vertex in the order of a property, if an
+    /// expand is followed by a top-k on the edges. Returns the number of edges to read through the
+    /// sort key of the only expanded label if the sort key is on that property in that order, or
+    /// `None` to read edges as usual, in which case the top-k still gives the right answer.
+    fn get_sorted_limit(
+        &self, si: SnapshotId, direction: Direction, edge_labels: &Vec<StoreLabelId>, params: &QueryParams,
+    ) -> Option<usize> {
+        if direction == Direction::Both || edge_labels.len() != 1 {
+            return None;
+        }
+        let prop_id = params
+            .get_extra_param(ORDER_BY_KEY)?
+            .parse::<PropId>()
+            .ok()?;
+        let descending = params
+            .get_extra_param(ORDER_DESC_KEY)?
+            .parse::<bool>()
+            .ok()?;
+        let sorted_limit = params
+            .get_extra_param(ORDER_LIMIT_KEY)?
+            .parse::<usize>()
+            .ok()?;
+        if self.store.get_edge_sort_key(si, edge_labels[0]) == Some((prop_id, descending)) {
+            Some(
+                params
+                    .limit
+                    .map_or(sorted_limit, |limit| limit.min(sorted_limit)),
+            )
+        } else {
+            None
+        }
+    }
+}
+
+/// The gc watermark and the latest snapshot of the store are only read if the query is pinned to
+/// a snapshot.
+fn resolve_snapshot_id<F>(params: &QueryParams, readable: F) -> GraphProxyResult<SnapshotId>
+where
+    F: FnOnce() -> (SnapshotId, SnapshotId),
+{
+    let si = match params.get_extra_param(SNAPSHOT_ID) {
+        Some(s) => s
+            .parse::<SnapshotId>()
+            .map_err(|_| GraphProxyError::query_store_error(&format!("invalid snapshot id {:?}", s)))?,
+        None => return Ok(DEFAULT_SNAPSHOT_ID),
+    };
+    let (gc_si, latest_si) = readable();
+    check_snapshot_id(si, gc_si, latest_si)?;
+    Ok(si)
+}
+
+fn check_snapshot_id(si: SnapshotId, gc_si: SnapshotId, latest_si: SnapshotId) -> GraphProxyResult<()> {
+    if si < 0 {
+        Err(GraphProxyError::query_store_error(&format!("invalid snapshot id {}", si)))
+    } else if si < gc_si {
+        Err(GraphProxyError::query_store_error(&format!(
+            "snapshot {} has been garbage collected, the earliest readable snapshot is {}",
+            si, gc_si
+        )))
+    } else if si > latest_si {
+        Err(GraphProxyError::query_store_error(&format!(
+            "snapshot {} has not been written yet, the latest readable snapshot is {}",
+            si, latest_si
+        )))
+    } else {
+        Ok(())
+    }
 }
 
 #[inline]
@@ -727,6 +826,7 @@ fn encode_store_prop_val(prop_val: Object) -> Property {
             }
         }
         Object::Blob(b) => Property::Bytes(b.to_vec()),
+        Object::DateFormat(d) => Property::from_date_format(&d).unwrap_or(Property::Unknown),
         Object::None => Property::Null,
         _ => Property::Unknown,
     }
@@ -784,3 +884,35 @@ fn get_partition_vertex_id(
     let partition_id = graph_partition_manager.get_partition_id(id as VertexId) as PartitionId;
     (partition_id, vec![id as VertexId])
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn pinned_at(si: &str) -> QueryParams {
+        let mut extra_params = HashMap::new();
+        extra_params.insert(SNAPSHOT_ID.to_string(), si.to_string());
+        QueryParams { extra_params: Some(extra_params), ..QueryParams::default() }
+    }
+
+    #[test]
+    fn test_latest_snapshot_id() {
+        let si = resolve_snapshot_id(&QueryParams::default(), || panic!("readable range read")).unwrap();
+        assert_eq!(si, DEFAULT_SNAPSHOT_ID);
+    }
+
+    #[test]
+    fn test_pinned_snapshot_id() {
+        assert_eq!(resolve_snapshot_id(&pinned_at("10"), || (10, 20)).unwrap(), 10);
+        assert_eq!(resolve_snapshot_id(&pinned_at("12"), || (10, 20)).unwrap(), 12);
+        assert_eq!(resolve_snapshot_id(&pinned_at("20"), || (10, 20)).unwrap(), 20);
+        // versions below the watermark may have been collected
+        assert!(resolve_snapshot_id(&pinned_at("9"), || (10, 20)).is_err());
+        // snapshots after the latest one have no data yet
+        assert!(resolve_snapshot_id(&pinned_at("21"), || (10, 20)).is_err());
+        assert!(resolve_snapshot_id(&pinned_at("-1"), || (0, 20)).is_err());
+        assert!(resolve_snapshot_id(&pinned_at("latest"), || (0, 20)).is_err());
+    }
+}
@@ -13,6 +13,7 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+mod bmcsr_store;
 mod csr_store;
 mod exp_store;
 #[cfg(feature = "with_global_query")]
@@ -20,6 +21,7 @@ mod gs_store;
 #[cfg(feature = "with_global_query")]
 mod vineyard_store;
 
+pub use bmcsr_store::{create_bmcsr_store, BmcsrGraph, BmcsrGraphWriter, BmcsrStore};
 pub use csr_store::create_csr_store;
 pub use exp_store::{create_exp_store, SimplePartition};
 #[cfg(feature = "with_global_query")]
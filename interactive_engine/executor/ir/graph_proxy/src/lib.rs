@@ -17,7 +17,10 @@
 extern crate log;
 #[macro_use]
 extern crate lazy_static;
-pub use adapters::{create_csr_store, create_exp_store, SimplePartition};
+pub use adapters::{
+    create_bmcsr_store, create_csr_store, create_exp_store, BmcsrGraph, BmcsrGraphWriter, BmcsrStore,
+    SimplePartition,
+};
 #[cfg(feature = "with_global_query")]
 pub use adapters::{
     create_gs_store, GraphScopeStore, GrootMultiPartition, VineyardGraphWriter, VineyardMultiPartition,
@@ -297,7 +297,7 @@ pub mod benchmark {
             })
             .collect();
         let limit = limit.map(|upper| pb::Range { lower: 0, upper });
-        pb::OrderBy { pairs, limit }
+        pb::OrderBy { pairs, limit, keys: vec![], seed: None }
     }
 
     pub fn default_count_pb() -> pb::GroupBy {
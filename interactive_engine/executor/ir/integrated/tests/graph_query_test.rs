@@ -22,8 +22,11 @@ mod test {
     use dyn_type::object;
     use dyn_type::Object;
     use graph_proxy::apis::GraphElement;
+    use graph_store::common::DefaultId;
+    use graph_store::ldbc::LDBCVertexParser;
     use ir_common::expr_parse::str_to_expr_pb;
     use ir_common::generated::algebra as pb;
+    use ir_common::generated::common as common_pb;
     use ir_physical_client::physical_builder::*;
     use pegasus_server::JobRequest;
     use runtime::process::entry::Entry;
@@ -268,4 +271,146 @@ mod test {
     fn get_all_property_after_shuffle_w2_test() {
         get_all_properties_after_shuffle(2)
     }
+
+    fn person_scan() -> pb::Scan {
+        pb::Scan {
+            scan_opt: 0,
+            alias: None,
+            params: Some(query_params(vec![PERSON_LABEL.into()], vec![], None)),
+            idx_predicate: None,
+            is_count_only: false,
+            meta_data: None,
+        }
+    }
+
+    fn collect_vertex_ids(request: JobRequest, worker_num: u32) -> Vec<i64> {
+        initialize();
+        let mut results = submit_query(request, worker_num);
+        let mut result_collection = vec![];
+        while let Some(result) = results.next() {
+            match result {
+                Ok(res) => {
+                    let entry = parse_result(res).unwrap();
+                    if let Some(vertex) = entry.get(None).unwrap().as_vertex() {
+                        result_collection.push(vertex.id() as i64);
+                    }
+                }
+                Err(e) => {
+                    panic!("err result {:?}", e);
+                }
+            }
+        }
+        result_collection
+    }
+
+    // g.V().hasLabel("person").range(1, 3)
+    fn skip_limit_query(worker_num: u32) {
+        let mut job_builder = JobBuilder::default();
+        job_builder.add_scan_source(person_scan());
+        job_builder.shuffle(None);
+        job_builder.limit(pb::Limit { range: Some(pb::Range { lower: 1, upper: 3 }) });
+        job_builder.sink(default_sink_pb());
+        let request = job_builder.build().unwrap();
+
+        let result_collection = collect_vertex_ids(request, worker_num);
+        // the offset is counted over all workers, so only 2 of the 4 persons are left
+        assert_eq!(result_collection.len(), 2);
+    }
+
+    #[test]
+    fn skip_limit_test() {
+        skip_limit_query(1)
+    }
+
+    #[test]
+    fn skip_limit_w2_test() {
+        skip_limit_query(2)
+    }
+
+    // g.V().hasLabel("person").order().range(1, 3)
+    fn order_skip_limit_query(worker_num: u32) {
+        let order_opr = pb::OrderBy {
+            pairs: vec![pb::order_by::OrderingPair {
+                key: Some(common_pb::Variable { tag: None, property: None, node_type: None }),
+                order: 1, // ascending
+            }],
+            limit: Some(pb::Range { lower: 1, upper: 3 }),
+            keys: vec![],
+            seed: None,
+        };
+        let mut job_builder = JobBuilder::default();
+        job_builder.add_scan_source(person_scan());
+        job_builder.shuffle(None);
+        job_builder.order(order_opr);
+        job_builder.sink(default_sink_pb());
+        let request = job_builder.build().unwrap();
+
+        let result_collection = collect_vertex_ids(request, worker_num);
+        assert_eq!(result_collection, vec![2, 4]);
+    }
+
+    #[test]
+    fn order_skip_limit_test() {
+        order_skip_limit_query(1)
+    }
+
+    #[test]
+    fn order_skip_limit_w2_test() {
+        order_skip_limit_query(2)
+    }
+
+    // g.V().group().by(label).by(order().by(desc).limit(1)), flattened
+    fn order_by_key_query(worker_num: u32, order: i32) -> Vec<i64> {
+        let source_opr = pb::Scan {
+            scan_opt: 0,
+            alias: None,
+            params: Some(query_params(vec![], vec![], None)),
+            idx_predicate: None,
+            is_count_only: false,
+            meta_data: None,
+        };
+        let order_opr = pb::OrderBy {
+            pairs: vec![pb::order_by::OrderingPair {
+                key: Some(common_pb::Variable { tag: None, property: None, node_type: None }),
+                order,
+            }],
+            limit: Some(pb::Range { lower: 0, upper: 1 }),
+            keys: vec![common_pb::Variable::from("@.~label".to_string())],
+            seed: None,
+        };
+        let mut job_builder = JobBuilder::default();
+        job_builder.add_scan_source(source_opr);
+        job_builder.order(order_opr);
+        job_builder.sink(default_sink_pb());
+        let request = job_builder.build().unwrap();
+
+        let mut result_collection = collect_vertex_ids(request, worker_num);
+        result_collection.sort();
+        result_collection
+    }
+
+    fn software_id(id: usize) -> i64 {
+        let global_id: DefaultId = LDBCVertexParser::to_global_id(id, 1);
+        global_id as i64
+    }
+
+    #[test]
+    fn order_by_key_test() {
+        assert_eq!(order_by_key_query(1, 2), vec![6, software_id(5)]);
+    }
+
+    #[test]
+    fn order_by_key_w2_test() {
+        assert_eq!(order_by_key_query(2, 2), vec![6, software_id(5)]);
+    }
+
+    // g.V().group().by(label).by(sample(1)), flattened
+    #[test]
+    fn sample_by_key_w2_test() {
+        let result_collection = order_by_key_query(2, 0);
+        assert_eq!(result_collection.len(), 2);
+        // one person and one software
+        assert!([1, 2, 4, 6].contains(&result_collection[0]));
+        assert!([software_id(3), software_id(5)].contains(&result_collection[1]));
+    }
 }
@@ -19,8 +19,13 @@ mod common;
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
+
     use graph_proxy::apis::GraphElement;
+    use graph_store::ldbc::LDBCVertexParser;
+    use graph_store::prelude::DefaultId;
     use ir_common::generated::algebra as pb;
+    use ir_common::generated::common as common_pb;
     use ir_physical_client::physical_builder::*;
     use pegasus_server::JobRequest;
     use runtime::process::entry::Entry;
@@ -98,6 +103,25 @@ mod test {
         job_builder.build().unwrap()
     }
 
+    // g.E().sample()
+    fn init_scan_edge_sample_request(sample: pb::Sample) -> JobRequest {
+        let source_opr = pb::Scan {
+            scan_opt: 1,
+            alias: None,
+            params: Some(query_params(vec![], vec!["weight".into()], None)),
+            idx_predicate: None,
+            is_count_only: false,
+            meta_data: None,
+        };
+
+        let mut job_builder = JobBuilder::default();
+        job_builder.add_scan_source(source_opr);
+        job_builder.sample(sample);
+        job_builder.sink(default_sink_pb());
+
+        job_builder.build().unwrap()
+    }
+
     fn scan_sample_by_num(worker_num: u32, sample_num: i32) -> usize {
         initialize();
         let sample_by_num = gen_sample_by_num_opr(sample_num, None);
@@ -214,9 +238,12 @@ mod test {
     }
 
     fn scan_sample_by_ratio_with_seed(sample_ratio: f64, seed: i32) -> Vec<i64> {
+        scan_sample_with_workers(gen_sample_by_ratio_opr(sample_ratio, Some(seed)), 2)
+    }
+
+    fn scan_out_sample_with_seed(sample: pb::Sample) -> Vec<i64> {
         initialize();
-        let sample_by_ratio = gen_sample_by_ratio_opr(sample_ratio, Some(seed));
-        let request = init_scan_sample_request(sample_by_ratio);
+        let request = init_scan_out_sample_request(sample);
         let mut results = submit_query(request, 2);
         let mut result_collection = vec![];
         while let Some(result) = results.next() {
@@ -236,6 +263,50 @@ mod test {
         result_collection
     }
 
+    fn scan_edge_sample(sample: pb::Sample) -> Vec<(DefaultId, DefaultId)> {
+        initialize();
+        let request = init_scan_edge_sample_request(sample);
+        let mut results = submit_query(request, 2);
+        let mut result_collection = vec![];
+        while let Some(result) = results.next() {
+            match result {
+                Ok(res) => {
+                    let record = parse_result(res).unwrap();
+                    if let Some(edge) = record.get(None).unwrap().as_edge() {
+                        result_collection.push((edge.src_id as DefaultId, edge.dst_id as DefaultId));
+                    }
+                }
+                Err(e) => {
+                    panic!("err result {:?}", e);
+                }
+            }
+        }
+        print!("{:?}", result_collection);
+        result_collection
+    }
+
+    fn scan_sample_with_workers(sample: pb::Sample, worker_num: u32) -> Vec<i64> {
+        initialize();
+        let request = init_scan_sample_request(sample);
+        let mut results = submit_query(request, worker_num);
+        let mut result_collection = vec![];
+        while let Some(result) = results.next() {
+            match result {
+                Ok(res) => {
+                    let record = parse_result(res).unwrap();
+                    if let Some(vertex) = record.get(None).unwrap().as_vertex() {
+                        result_collection.push(vertex.id());
+                    }
+                }
+                Err(e) => {
+                    panic!("err result {:?}", e);
+                }
+            }
+        }
+        print!("{:?}", result_collection);
+        result_collection
+    }
+
     // g.V().sample(2) with worker_num = 1
     #[test]
     fn scan_sample_by_num_test() {
@@ -346,4 +417,98 @@ mod test {
             assert_eq!(first_sample, try_sample);
         }
     }
+
+    // g.V().sample(2).with("REPEATABLE", 97) with worker_num = 1 and 2
+    #[test]
+    fn scan_sample_by_num_with_seed_workers_test() {
+        let mut w1_sample = scan_sample_with_workers(gen_sample_by_num_opr(2, Some(97)), 1);
+        let mut w2_sample = scan_sample_with_workers(gen_sample_by_num_opr(2, Some(97)), 2);
+        w1_sample.sort();
+        w2_sample.sort();
+        assert_eq!(w1_sample.len(), 2);
+        assert_eq!(w1_sample, w2_sample);
+    }
+
+    // g.V().coin(0.5).with("REPEATABLE", 97) with worker_num = 1 and 2
+    #[test]
+    fn scan_sample_by_coin_with_seed_workers_test() {
+        let mut w1_sample = scan_sample_with_workers(gen_sample_by_ratio_opr(0.5, Some(97)), 1);
+        let mut w2_sample = scan_sample_with_workers(gen_sample_by_ratio_opr(0.5, Some(97)), 2);
+        w1_sample.sort();
+        w2_sample.sort();
+        assert_eq!(w1_sample, w2_sample);
+    }
+
+    // g.V().out().coin(0.5).with("REPEATABLE", seed)
+    #[test]
+    fn scan_out_sample_by_coin_with_seed_duplicates_test() {
+        let mut all = HashMap::new();
+        for id in scan_out_sample_with_seed(gen_sample_by_ratio_opr(1.0, Some(97))) {
+            *all.entry(id).or_insert(0) += 1;
+        }
+        // vertex 3 is reached from vertex 1, 4 and 6
+        assert_eq!(all.values().max(), Some(&3));
+        for seed in 0..10 {
+            let mut sampled = HashMap::new();
+            for id in scan_out_sample_with_seed(gen_sample_by_ratio_opr(0.5, Some(seed))) {
+                *sampled.entry(id).or_insert(0) += 1;
+            }
+            // equal records get the same draw, so duplicates are sampled all together
+            for (id, count) in sampled {
+                assert_eq!(all.get(&id), Some(&count));
+            }
+        }
+    }
+
+    // g.E().sample(1).by('weight').with("REPEATABLE", seed)
+    #[test]
+    fn scan_sample_by_weight_test() {
+        let v1: DefaultId = LDBCVertexParser::to_global_id(1, 0);
+        let v3: DefaultId = LDBCVertexParser::to_global_id(3, 1);
+        let v4: DefaultId = LDBCVertexParser::to_global_id(4, 0);
+        let v5: DefaultId = LDBCVertexParser::to_global_id(5, 1);
+        let v6: DefaultId = LDBCVertexParser::to_global_id(6, 0);
+        let mut counts = HashMap::new();
+        for seed in 0..100 {
+            let mut sample = gen_sample_by_num_opr(1, Some(seed));
+            sample.sample_weight = Some(common_pb::Variable::from("@.weight".to_string()));
+            let edges = scan_edge_sample(sample);
+            assert_eq!(edges.len(), 1);
+            *counts.entry(edges[0]).or_insert(0) += 1;
+        }
+        // of the total weight 3.5, edges 1->4 and 4->5 weigh 1.0 each and edge 6->3 weighs 0.2, so
+        // they are expected to be sampled 57 and 6 times out of 100
+        let count_of = |edge: (DefaultId, DefaultId)| counts.get(&edge).copied().unwrap_or(0);
+        let heavy = count_of((v1, v4)) + count_of((v4, v5));
+        let light = count_of((v6, v3));
+        assert!(heavy > 40, "heavy edges sampled {} times", heavy);
+        assert!(light < 20, "light edge sampled {} times", light);
+    }
+
+    // g.V().sample(10).by('age')
+    #[test]
+    fn scan_sample_by_missing_weight_test() {
+        let mut sample = gen_sample_by_num_opr(10, None);
+        sample.sample_weight = Some(common_pb::Variable::from("@.age".to_string()));
+        let mut result = scan_sample_with_workers(sample, 2);
+        result.sort();
+        // software vertices have no age, thus are never sampled
+        assert_eq!(result, vec![1, 2, 4, 6]);
+    }
+
+    // g.V().sample(2).by('age').with("REPEATABLE", 97) with worker_num = 1 and 2
+    #[test]
+    fn scan_sample_by_weight_with_seed_test() {
+        let gen_sample = || {
+            let mut sample = gen_sample_by_num_opr(2, Some(97));
+            sample.sample_weight = Some(common_pb::Variable::from("@.age".to_string()));
+            sample
+        };
+        let mut w1_sample = scan_sample_with_workers(gen_sample(), 1);
+        let mut w2_sample = scan_sample_with_workers(gen_sample(), 2);
+        w1_sample.sort();
+        w2_sample.sort();
+        assert_eq!(w1_sample.len(), 2);
+        assert_eq!(w1_sample, w2_sample);
+    }
 }
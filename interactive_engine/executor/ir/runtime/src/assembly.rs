@@ -27,7 +27,7 @@ use ir_common::generated::physical::physical_opr::operator::OpKind;
 use pegasus::api::function::*;
 use pegasus::api::{
     Collect, CorrelatedSubTask, Count, Dedup, Filter, Fold, FoldByKey, HasAny, IterCondition, Iteration,
-    Join, KeyBy, Limit, Map, Merge, Sink, SortBy, SortLimitBy,
+    Join, KeyBy, Limit, Map, Merge, SampleByKey, Sink, SortBy, SortLimitBy, SortLimitByKey,
 };
 use pegasus::stream::Stream;
 use pegasus::{BuildJobError, Worker};
@@ -38,6 +38,7 @@ use prost::Message;
 use crate::error::{FnExecError, FnGenError, FnGenResult};
 use crate::process::functions::{ApplyGen, CompareFunction, FoldGen, GroupGen, JoinKeyGen, KeyFunction};
 use crate::process::operator::accum::accumulator::Accumulator;
+use crate::process::operator::accum::sample::sample_random;
 use crate::process::operator::accum::{SampleAccum, SampleAccumFactoryGen};
 use crate::process::operator::filter::FilterFuncGen;
 use crate::process::operator::flatmap::FlatMapFuncGen;
@@ -136,6 +137,10 @@ impl<P: PartitionInfo, C: ClusterInfo> FnGenerator<P, C> {
         Ok(opr.gen_key()?)
     }
 
+    fn gen_order_key(&self, opr: algebra_pb::OrderBy) -> FnGenResult<RecordKeySelector> {
+        Ok(opr.gen_key()?)
+    }
+
     fn gen_vertex(&self, opr: pb::GetV) -> FnGenResult<RecordFilterMap> {
         Ok(opr.gen_filter_map()?)
     }
@@ -198,7 +203,25 @@ impl<P: PartitionInfo, C: ClusterInfo> IRJobAssembly<P, C> {
         &self, mut stream: Stream<Record>, plan: &[pb::PhysicalOpr],
     ) -> Result<Stream<Record>, BuildJobError> {
         let mut prev_op_kind = pb::physical_opr::operator::OpKind::Root(pb::Root {});
-        for op in &plan[..] {
+        // only operators of the outermost plan are tagged, the ones of sub-plans share the tag of
+        // the operator they belong to;
+        let is_outermost = stream.get_plan_id().is_none();
+        // an always-false select filters out any input, so the operators before it are cut off and
+        // the input is dropped right away
+        let mut start = 0;
+        if let Some(cut) = plan.iter().rposition(is_always_false_select) {
+            if is_outermost {
+                stream.set_plan_id(Some(cut));
+            }
+            debug!("Runtime operators before {} are pruned for an always-false select", cut);
+            stream = stream.filter(|_| Ok(false))?;
+            prev_op_kind = to_op_kind(&plan[cut])?;
+            start = cut + 1;
+        }
+        for (plan_id, op) in plan.iter().enumerate().skip(start) {
+            if is_outermost {
+                stream.set_plan_id(Some(plan_id));
+            }
             let op_kind = to_op_kind(op)?;
             match op_kind {
                 OpKind::Repartition(repartition) => {
@@ -231,25 +254,68 @@ impl<P: PartitionInfo, C: ClusterInfo> IRJobAssembly<P, C> {
                     let range = limit.range.ok_or_else(|| {
                         FnGenError::from(ParsePbError::EmptyFieldError("pb::Limit::range".to_string()))
                     })?;
-                    // e.g., `limit(10)` would be translate as `Range{lower=0, upper=10}`
-                    if range.upper <= range.lower || range.lower != 0 {
+                    // e.g., `limit(10)` would be translate as `Range{lower=0, upper=10}`,
+                    // and `skip(20).limit(10)` as `Range{lower=20, upper=30}`
+                    if range.upper <= range.lower || range.lower < 0 {
                         Err(FnGenError::from(ParsePbError::ParseError(format!(
                             "range {:?} in Limit Operator",
                             range
                         ))))?;
                     }
-                    stream = stream.limit(range.upper as u32)?;
+                    let size = (range.upper - range.lower) as u32;
+                    if range.lower == 0 {
+                        stream = stream.limit(size)?;
+                    } else {
+                        stream = stream.skip_limit(range.lower as u32, size)?;
+                    }
+                }
+                OpKind::OrderBy(order) if !order.keys.is_empty() => {
+                    // per-group top-k, or per-group sample if ordering by `SHUFFLE` only
+                    let range = order
+                        .limit
+                        .clone()
+                        .filter(|range| range.lower == 0 && range.upper > 0)
+                        .ok_or_else(|| {
+                            FnGenError::from(ParsePbError::ParseError(format!(
+                                "range {:?} in Order Operator with keys",
+                                order.limit
+                            )))
+                        })?;
+                    let is_shuffle = order.pairs.iter().all(|pair| {
+                        pair.order == algebra_pb::order_by::ordering_pair::Order::Shuffle as i32
+                    });
+                    let selector = self.udf_gen.gen_order_key(order.clone())?;
+                    let keyed = stream.key_by(move |record| selector.get_kv(record))?;
+                    let sampled = if is_shuffle {
+                        let seed = order.seed.map(|seed| seed as u64);
+                        keyed.sample_by_key(range.upper as u32, move |pair| {
+                            sample_random(seed, &pair.value)
+                        })?
+                    } else {
+                        let cmp = self.udf_gen.gen_cmp(order)?;
+                        keyed.sort_limit_by_key(range.upper as u32, move |a, b| {
+                            cmp.compare(&a.value, &b.value)
+                        })?
+                    };
+                    stream = sampled.map(|pair| Ok(pair.value))?;
                 }
                 OpKind::OrderBy(order) => {
                     let cmp = self.udf_gen.gen_cmp(order.clone())?;
                     if let Some(range) = order.limit {
-                        if range.upper <= range.lower || range.lower != 0 {
+                        if range.upper <= range.lower || range.lower < 0 {
                             Err(FnGenError::from(ParsePbError::ParseError(format!(
                                 "range {:?} in Order Operator",
                                 range
                             ))))?;
                         }
-                        stream = stream.sort_limit_by(range.upper as u32, move |a, b| cmp.compare(a, b))?;
+                        let size = (range.upper - range.lower) as u32;
+                        if range.lower == 0 {
+                            stream = stream.sort_limit_by(size, move |a, b| cmp.compare(a, b))?;
+                        } else {
+                            stream = stream.sort_skip_limit_by(range.lower as u32, size, move |a, b| {
+                                cmp.compare(a, b)
+                            })?;
+                        }
                     } else {
                         stream = stream.sort_by(move |a, b| cmp.compare(a, b))?;
                     }
@@ -898,13 +964,6 @@ impl<P: PartitionInfo, C: ClusterInfo> IRJobAssembly<P, C> {
                     })?;
                 }
                 OpKind::Sample(sample) => {
-                    if let Some(sample_weight) = &sample.sample_weight {
-                        if sample_weight.tag.is_some() || sample_weight.property.is_some() {
-                            return Err(FnGenError::from(ParsePbError::ParseError(
-                                "sample_weight is not supported yet".to_string(),
-                            )))?;
-                        }
-                    }
                     if let Some(sample_type) = &sample.sample_type {
                         match &sample_type.inner {
                             // the case of Coin
@@ -916,6 +975,7 @@ impl<P: PartitionInfo, C: ClusterInfo> IRJobAssembly<P, C> {
                             Some(algebra_pb::sample::sample_type::Inner::SampleByNum(_)) => {
                                 let partial_sample_accum = self.udf_gen.gen_sample(sample)?;
                                 let sample_accum = partial_sample_accum.clone();
+                                // the partial samples are merged with their keys, to keep the global sample unbiased
                                 stream = stream
                                     .fold_partition(partial_sample_accum, move || {
                                         move |mut sample_accum, next| {
@@ -923,10 +983,10 @@ impl<P: PartitionInfo, C: ClusterInfo> IRJobAssembly<P, C> {
                                             Ok(sample_accum)
                                         }
                                     })?
-                                    .unfold(move |mut sample_accum| Ok(sample_accum.finalize()?))?
+                                    .into_stream()?
                                     .fold(sample_accum, move || {
-                                        move |mut sample_accum, next| {
-                                            sample_accum.accum(next)?;
+                                        move |mut sample_accum, partial| {
+                                            sample_accum.merge(partial);
                                             Ok(sample_accum)
                                         }
                                     })?
@@ -957,6 +1017,9 @@ impl<P: PartitionInfo, C: ClusterInfo> IRJobAssembly<P, C> {
 
             prev_op_kind = to_op_kind(op)?;
         }
+        if is_outermost {
+            stream.set_plan_id(None);
+        }
         Ok(stream)
     }
 }
@@ -976,6 +1039,7 @@ impl<P: PartitionInfo, C: ClusterInfo> JobAssembly<Record> for IRJobAssembly<P,
                 FnGenError::from(ParsePbError::EmptyFieldError("empty job plan".to_string()))
             })?;
             let ec = self.udf_gen.gen_sink(sink_opr.clone())?;
+            stream.set_plan_id(Some(plan_len - 1));
             match ec {
                 Sinker::DefaultSinker(default_sinker) => stream
                     .map(move |record| default_sinker.exec(record))?
@@ -1009,6 +1073,18 @@ fn to_op_kind(opr: &pb::PhysicalOpr) -> FnGenResult<OpKind> {
     Ok(opr.try_into()?)
 }
 
+#[inline]
+fn is_always_false_select(opr: &pb::PhysicalOpr) -> bool {
+    match to_op_kind(opr) {
+        Ok(OpKind::Select(select)) => select
+            .predicate
+            .as_ref()
+            .map(|predicate| predicate.is_always_false())
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
 struct PhysicalPlanPrinter<'a>(&'a pb::PhysicalPlan);
 struct PhysicalOprPrinter<'a>(&'a pb::PhysicalOpr);
 
@@ -13,46 +13,168 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BinaryHeap;
+use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
+
+use dyn_type::{Object, Primitives};
 use ir_common::error::ParsePbError;
 use ir_common::generated::algebra as algebra_pb;
 use pegasus::api::function::DynIter;
 use pegasus::codec::{Decode, Encode, ReadExt, WriteExt};
-use rand::prelude::StdRng;
-use rand::{Rng, SeedableRng};
+use rand::Rng;
 
-use crate::error::{FnExecResult, FnGenError, FnGenResult};
+use crate::error::{FnExecError, FnExecResult, FnGenError, FnGenResult};
+use crate::process::entry::{Entry, EntryType};
 use crate::process::operator::accum::accumulator::Accumulator;
 use crate::process::operator::accum::SampleAccumFactoryGen;
+use crate::process::operator::TagKey;
 use crate::process::record::Record;
 
+/// Returns a random number in (0, 1] for `record`. Given a `seed`, the number only depends on the seed and
+/// the entries of the record, so that a record is sampled the same way on whichever worker it arrives.
+/// Nothing else tells records apart independently of the workers, so records of equal entries, e.g.,
+/// a vertex reached through several untagged paths, get the same number and are sampled all together.
+pub fn sample_random(seed: Option<u64>, record: &Record) -> f64 {
+    let bits = if let Some(seed) = seed {
+        let mut hasher = DefaultHasher::new();
+        seed.hash(&mut hasher);
+        let columns = record.get_columns().iter().map(|(_, e)| e);
+        for entry in record.get(None).into_iter().chain(columns) {
+            if entry.get_type() != EntryType::Null {
+                entry.hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    } else {
+        rand::thread_rng().gen::<u64>()
+    };
+    // the highest 53 bits fill the mantissa of a f64
+    ((bits >> 11) as f64 + 1.0) / (1u64 << 53) as f64
+}
+
+#[derive(Clone, Debug)]
+struct SampleItem {
+    key: f64,
+    record: Record,
+}
+
+impl PartialEq for SampleItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SampleItem {}
+
+impl PartialOrd for SampleItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SampleItem {
+    // reversed, so that the item of the minimum key is on the top of the heap
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .key
+            .partial_cmp(&self.key)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
 /// Sample accumulator, which will keep a sampled vector of records, with the specified sample number.
-/// Implemented via Reservoir Sampling.
+/// Implemented via weighted reservoir sampling (A-Res): each record gets a key `u^(1/w)`, where `u` is
+/// a random number in (0, 1] and `w` is the sample weight of the record (1 by default), and the records
+/// of the top-`sample_num` keys are sampled. As the keys are kept along with the records, the samples
+/// of different partitions can be merged into the sample of all records by `merge()`.
 #[derive(Clone, Debug)]
 pub struct SampleAccum {
-    accumulator: Vec<Record>,
-    count: usize,
+    reservoir: BinaryHeap<SampleItem>,
     sample_num: usize,
-    rng: StdRng,
     seed: Option<u64>,
+    weight: Option<TagKey>,
+    // number of records never sampled, as they have no weight or a non-positive one
+    skipped: u64,
+}
+
+impl SampleAccum {
+    /// Returns the sample weight of `record`, or `None` if the record has no weight. Weights that are not
+    /// numbers are errors.
+    fn weight_of(&self, record: &Record) -> FnExecResult<Option<f64>> {
+        let weight = match self.weight.as_ref() {
+            Some(weight) => weight,
+            None => return Ok(Some(1.0)),
+        };
+        let entry = weight.get_arc_entry(record)?;
+        if entry.get_type() == EntryType::Null {
+            return Ok(None);
+        }
+        match entry.as_object() {
+            Some(Object::None) => Ok(None),
+            // longs beyond the range of `as_f64()` are still valid weights
+            Some(Object::Primitive(p)) => Ok(Some(match p {
+                Primitives::Byte(v) => *v as f64,
+                Primitives::Integer(v) => *v as f64,
+                Primitives::Long(v) => *v as f64,
+                Primitives::ULLong(v) => *v as f64,
+                Primitives::Float(v) => *v,
+            })),
+            _ => Err(FnExecError::unexpected_data_error(&format!(
+                "sample weight {:?} of record {:?} is not a number",
+                entry, record
+            ))),
+        }
+    }
+
+    fn offer(&mut self, item: SampleItem) {
+        if self.reservoir.len() < self.sample_num {
+            self.reservoir.push(item);
+        } else if let Some(min) = self.reservoir.peek() {
+            if item.key > min.key {
+                self.reservoir.pop();
+                self.reservoir.push(item);
+            }
+        }
+    }
+
+    /// Merge the samples of another partition into this one.
+    pub fn merge(&mut self, other: SampleAccum) {
+        for item in other.reservoir.into_iter() {
+            self.offer(item);
+        }
+        self.skipped += other.skipped;
+    }
 }
 
 impl Accumulator<Record, DynIter<Record>> for SampleAccum {
     fn accum(&mut self, next: Record) -> FnExecResult<()> {
-        if self.count < self.sample_num {
-            self.accumulator.push(next);
-        } else {
-            let index = self.rng.gen_range(0..=self.count);
-            if index < self.sample_num {
-                self.accumulator[index] = next;
+        // records without a positive weight are never sampled
+        match self.weight_of(&next)? {
+            Some(weight) if weight > 0.0 && weight.is_finite() => {
+                // compare `ln(u)/w` instead of `u^(1/w)` for precision, which keeps the order
+                let key = sample_random(self.seed, &next).ln() / weight;
+                self.offer(SampleItem { key, record: next });
             }
+            _ => self.skipped += 1,
         }
-        self.count = self.count + 1;
         Ok(())
     }
 
     fn finalize(&mut self) -> FnExecResult<DynIter<Record>> {
-        let collection = std::mem::replace(&mut self.accumulator, vec![]);
-        self.count = 0;
+        if self.skipped > 0 {
+            warn!("{} records are not sampled, as they have no weight or a non-positive one", self.skipped);
+            self.skipped = 0;
+        }
+        let reservoir = std::mem::replace(&mut self.reservoir, BinaryHeap::new());
+        // in the descending order of keys, which is deterministic given a seed
+        let collection: Vec<Record> = reservoir
+            .into_sorted_vec()
+            .into_iter()
+            .map(|item| item.record)
+            .collect();
         Ok(Box::new(collection.into_iter()))
     }
 }
@@ -65,16 +187,17 @@ impl SampleAccumFactoryGen for algebra_pb::Sample {
             })?;
             match sample_type {
                 algebra_pb::sample::sample_type::Inner::SampleByNum(num) => {
+                    let weight = self
+                        .sample_weight
+                        .filter(|w| w.tag.is_some() || w.property.is_some())
+                        .map(TagKey::try_from)
+                        .transpose()?;
                     let sample = SampleAccum {
+                        reservoir: BinaryHeap::with_capacity(num.num as usize),
                         sample_num: num.num as usize,
-                        accumulator: Vec::with_capacity(num.num as usize),
-                        count: 0,
-                        rng: if let Some(seed) = self.seed {
-                            StdRng::seed_from_u64(seed as u64)
-                        } else {
-                            StdRng::from_entropy()
-                        },
                         seed: self.seed.map(|s| s as u64),
+                        weight,
+                        skipped: 0,
                     };
                     if log_enabled!(log::Level::Debug) && pegasus::get_current_worker().index == 0 {
                         debug!("Runtime sample operator: {:?}", sample);
@@ -93,21 +216,98 @@ impl SampleAccumFactoryGen for algebra_pb::Sample {
 
 impl Encode for SampleAccum {
     fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
-        self.accumulator.write_to(writer)?;
-        writer.write_u64(self.count as u64)?;
+        writer.write_u64(self.reservoir.len() as u64)?;
+        for item in self.reservoir.iter() {
+            writer.write_f64(item.key)?;
+            item.record.write_to(writer)?;
+        }
         writer.write_u64(self.sample_num as u64)?;
         self.seed.write_to(writer)?;
+        self.weight.write_to(writer)?;
+        writer.write_u64(self.skipped)?;
         Ok(())
     }
 }
 
 impl Decode for SampleAccum {
     fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
-        let accumulator = <Vec<Record>>::read_from(reader)?;
-        let count = reader.read_u64()? as usize;
+        let len = reader.read_u64()? as usize;
+        let mut reservoir = BinaryHeap::with_capacity(len);
+        for _ in 0..len {
+            let key = reader.read_f64()?;
+            let record = <Record>::read_from(reader)?;
+            reservoir.push(SampleItem { key, record });
+        }
         let sample_num = reader.read_u64()? as usize;
         let seed = Option::<u64>::read_from(reader)?;
-        let rng = if let Some(seed) = seed { StdRng::seed_from_u64(seed) } else { StdRng::from_entropy() };
-        Ok(SampleAccum { accumulator, count, sample_num, rng, seed })
+        let weight = Option::<TagKey>::read_from(reader)?;
+        let skipped = reader.read_u64()?;
+        Ok(SampleAccum { reservoir, sample_num, seed, weight, skipped })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::operator::tests::{init_vertex1, init_vertex2, TAG_A};
+
+    fn weighted_accum(sample_num: usize, seed: u64) -> SampleAccum {
+        SampleAccum {
+            reservoir: BinaryHeap::new(),
+            sample_num,
+            seed: Some(seed),
+            weight: Some(TagKey::default()),
+            skipped: 0,
+        }
+    }
+
+    // records of equal entries are not told apart by a seeded draw, unless they differ in a tagged entry
+    #[test]
+    fn seeded_sample_random_test() {
+        let record = Record::new(init_vertex1(), None);
+        let draw = sample_random(Some(97), &record);
+        assert_eq!(draw, sample_random(Some(97), &record.clone()));
+        assert_ne!(draw, sample_random(Some(98), &record));
+        assert_ne!(draw, sample_random(Some(97), &Record::new(init_vertex2(), None)));
+        let mut tagged = record.clone();
+        tagged.append(init_vertex2(), Some(TAG_A.into()));
+        assert_ne!(draw, sample_random(Some(97), &tagged));
+    }
+
+    // a record of weight 3 beats one of weight 1 with a probability of 3/4
+    #[test]
+    fn sample_by_weight_test() {
+        let mut heavy_count = 0;
+        for seed in 0..1000 {
+            let mut accum = weighted_accum(1, seed);
+            accum
+                .accum(Record::new(object!(1), None))
+                .unwrap();
+            accum
+                .accum(Record::new(object!(3), None))
+                .unwrap();
+            let sampled: Vec<Record> = accum.finalize().unwrap().collect();
+            assert_eq!(sampled.len(), 1);
+            if sampled[0].get(None).unwrap().as_object() == Some(&object!(3)) {
+                heavy_count += 1;
+            }
+        }
+        assert!(heavy_count > 700 && heavy_count < 800, "heavy record sampled {} times", heavy_count);
+    }
+
+    #[test]
+    fn sample_by_invalid_weight_test() {
+        let mut accum = weighted_accum(2, 97);
+        accum
+            .accum(Record::new(Object::None, None))
+            .unwrap();
+        accum
+            .accum(Record::new(object!(0), None))
+            .unwrap();
+        assert_eq!(accum.skipped, 2);
+        assert!(accum
+            .accum(Record::new(object!("heavy"), None))
+            .is_err());
+        assert_eq!(accum.finalize().unwrap().count(), 0);
     }
 }
@@ -17,11 +17,10 @@ use ir_common::error::ParsePbError;
 use ir_common::generated::algebra as algebra_pb;
 use pegasus::api::function::FilterFunction;
 use pegasus::api::function::FnResult;
-use rand::prelude::StdRng;
-use rand::{Rng, SeedableRng};
 
 use crate::error::FnGenError;
 use crate::error::FnGenResult;
+use crate::process::operator::accum::sample::sample_random;
 use crate::process::operator::filter::FilterFuncGen;
 use crate::process::record::Record;
 
@@ -32,17 +31,9 @@ struct CoinOperator {
 }
 
 impl FilterFunction<Record> for CoinOperator {
-    fn test(&self, _input: &Record) -> FnResult<bool> {
-        let mut rng = if let Some(seed) = self.seed {
-            StdRng::seed_from_u64(seed as u64)
-        } else {
-            StdRng::from_entropy()
-        };
-        if rng.gen_bool(self.ratio) {
-            Ok(true)
-        } else {
-            Ok(false)
-        }
+    // Bernoulli sampling, where each record is sampled independently with the probability of `ratio`
+    fn test(&self, input: &Record) -> FnResult<bool> {
+        Ok(sample_random(self.seed.map(|s| s as u64), input) <= self.ratio)
     }
 }
 
@@ -59,6 +50,13 @@ impl FilterFuncGen for algebra_pb::Sample {
                             "SampleByRatio ratio should be in [0, 1]".into(),
                         ));
                     }
+                    if self
+                        .sample_weight
+                        .as_ref()
+                        .map_or(false, |w| w.tag.is_some() || w.property.is_some())
+                    {
+                        return Err(FnGenError::unsupported_error("sample_weight in SampleByRatio"));
+                    }
                     let coin = CoinOperator { seed: self.seed, ratio: ratio.ratio };
                     if log_enabled!(log::Level::Debug) && pegasus::get_current_worker().index == 0 {
                         debug!("Runtime coin operator: {:?}", coin);
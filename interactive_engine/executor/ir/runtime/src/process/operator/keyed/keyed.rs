@@ -68,6 +68,16 @@ impl KeyFunctionGen for pb::GroupBy {
     }
 }
 
+impl KeyFunctionGen for algebra_pb::OrderBy {
+    fn gen_key(self) -> FnGenResult<Box<dyn KeyFunction<Record, RecordKey, Record>>> {
+        let key_selector = KeySelector::with(self.keys)?;
+        if log_enabled!(log::Level::Debug) && pegasus::get_current_worker().index == 0 {
+            debug!("Runtime order operator key_selector: {:?}", key_selector);
+        }
+        Ok(Box::new(key_selector))
+    }
+}
+
 impl KeyFunctionGen for algebra_pb::Dedup {
     fn gen_key(self) -> FnGenResult<Box<dyn KeyFunction<Record, RecordKey, Record>>> {
         let key_selector = KeySelector::with(self.keys)?;
@@ -125,6 +125,8 @@ mod tests {
                 order: 1, // ascending
             }],
             limit: None,
+            keys: vec![],
+            seed: None,
         };
         let mut result = sort_test(init_source(), sort_opr);
         let mut result_ids = vec![];
@@ -146,6 +148,8 @@ mod tests {
                 order: 2, // descending
             }],
             limit: None,
+            keys: vec![],
+            seed: None,
         };
         let mut result = sort_test(init_source(), sort_opr);
         let mut result_ids = vec![];
@@ -167,6 +171,8 @@ mod tests {
                 order: 2, // descending
             }],
             limit: None,
+            keys: vec![],
+            seed: None,
         };
         let mut result = sort_test(init_source(), sort_opr);
         let mut result_name = vec![];
@@ -208,6 +214,8 @@ mod tests {
                 },
             ],
             limit: None,
+            keys: vec![],
+            seed: None,
         };
         let mut result = sort_test(source, sort_opr);
         let mut result_name_ages = vec![];
@@ -244,6 +252,8 @@ mod tests {
                 order: 2, // descending
             }],
             limit: None,
+            keys: vec![],
+            seed: None,
         };
         let mut result = sort_test(init_source_with_tag(), sort_opr);
         let mut result_ids = vec![];
@@ -265,6 +275,8 @@ mod tests {
                 order: 2, // descending
             }],
             limit: None,
+            keys: vec![],
+            seed: None,
         };
         let mut result = sort_test(init_source_with_tag(), sort_opr);
         let mut result_ids = vec![];
@@ -286,6 +298,8 @@ mod tests {
                 order: 2, // descending
             }],
             limit: None,
+            keys: vec![],
+            seed: None,
         };
         let mut source = init_source();
         // josh's age is null
@@ -321,6 +335,8 @@ mod tests {
                 order: 1, // ascending
             }],
             limit: None,
+            keys: vec![],
+            seed: None,
         };
         let mut source = init_source();
         // josh's age is null
@@ -49,6 +49,8 @@ pub struct SourceOperator {
     // to specify if the source is a fusion of scan and count
     // currently, it may fuse: 1) scan + count; 2) index_scan + count
     is_count_only: bool,
+    // the predicate of the source filters out any input, so the source is pruned as an empty one
+    is_always_false: bool,
 }
 
 impl Default for SourceOperator {
@@ -60,6 +62,7 @@ impl Default for SourceOperator {
             alias: None,
             source_type: SourceType::Dummy,
             is_count_only: false,
+            is_always_false: false,
         }
     }
 }
@@ -131,6 +134,14 @@ impl SourceOperator {
 
 impl SourceOperator {
     pub fn gen_source(self, worker_index: usize) -> FnGenResult<Box<dyn Iterator<Item = Record> + Send>> {
+        if self.is_always_false {
+            debug!("Runtime source op is pruned for an always-false predicate {:?}", self.query_params);
+            return if self.is_count_only {
+                Ok(Box::new(vec![Record::new(object!(0u64), self.alias.clone())].into_iter()))
+            } else {
+                Ok(Box::new(std::iter::empty()))
+            };
+        }
         let graph = get_graph().ok_or_else(|| FnGenError::NullGraphError)?;
 
         match self.source_type {
@@ -230,6 +241,12 @@ impl TryFrom<pb::Scan> for SourceOperator {
             algebra_pb::scan::ScanOpt::Edge => SourceType::Edge,
             algebra_pb::scan::ScanOpt::Table => SourceType::Table,
         };
+        let is_always_false = scan_pb
+            .params
+            .as_ref()
+            .and_then(|params| params.predicate.as_ref())
+            .map(|predicate| predicate.is_always_false())
+            .unwrap_or(false);
         let query_params = QueryParams::try_from(scan_pb.params)?;
         Ok(SourceOperator {
             query_params,
@@ -238,6 +255,51 @@ impl TryFrom<pb::Scan> for SourceOperator {
             alias: scan_pb.alias,
             source_type,
             is_count_only: scan_pb.is_count_only,
+            is_always_false,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ir_common::expr_parse::simplify::simplify_expression;
+    use ir_common::expr_parse::str_to_expr_pb;
+    use ir_common::generated::algebra as algebra_pb;
+    use ir_common::generated::physical as pb;
+
+    use super::SourceOperator;
+
+    // the predicate is simplified as it is in preprocessing the logical plan
+    fn scan_with_predicate(predicate: &str, is_count_only: bool) -> pb::Scan {
+        let mut predicate = str_to_expr_pb(predicate.to_string()).unwrap();
+        simplify_expression(&mut predicate);
+        pb::Scan {
+            scan_opt: 0,
+            alias: None,
+            params: Some(algebra_pb::QueryParams {
+                predicate: Some(predicate),
+                sample_ratio: 1.0,
+                ..Default::default()
+            }),
+            idx_predicate: None,
+            is_count_only,
+        }
+    }
+
+    #[test]
+    fn scan_always_false_test() {
+        let source = SourceOperator::try_from(scan_with_predicate("@.age > 10 && 1 > 2", false)).unwrap();
+        assert!(source.is_always_false);
+        // the pruned source does not touch the graph, which is not initialized in this test
+        assert_eq!(source.gen_source(0).unwrap().count(), 0);
+
+        let source = SourceOperator::try_from(scan_with_predicate("@.age > 10 && 1 > 2", true)).unwrap();
+        let counts: Vec<_> = source.gen_source(0).unwrap().collect();
+        assert_eq!(counts.len(), 1);
+
+        let source = SourceOperator::try_from(scan_with_predicate("@.age > 10", false)).unwrap();
+        assert!(!source.is_always_false);
+    }
+}
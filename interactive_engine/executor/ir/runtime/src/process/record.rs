@@ -63,6 +63,10 @@ impl Record {
         self.curr = entry;
     }
 
+    pub fn get_columns(&self) -> &VecMap<DynEntry> {
+        &self.columns
+    }
+
     pub fn get_columns_mut(&mut self) -> &mut VecMap<DynEntry> {
         self.columns.borrow_mut()
     }
@@ -108,6 +108,14 @@ impl<I: IndexType> BatchMutableCsr<I> {
     }
 }
 
+fn copy_array<T: Copy>(input: &ArrayType<T>) -> ArrayType<T> {
+    let mut output = ArrayType::with_capacity(input.len());
+    for v in input.iter() {
+        output.push(*v);
+    }
+    output
+}
+
 unsafe impl<I: IndexType> Send for BatchMutableCsr<I> {}
 unsafe impl<I: IndexType> Sync for BatchMutableCsr<I> {}
 
@@ -245,6 +253,19 @@ impl<I: IndexType> CsrTrait<I> for BatchMutableCsr<I> {
         self
     }
 
+    fn clone_box(&self) -> Box<dyn CsrTrait<I>> {
+        Box::new(BatchMutableCsr {
+            neighbors: copy_array(&self.neighbors),
+            offsets: copy_array(&self.offsets),
+            degree: copy_array(&self.degree),
+            edge_num: self.edge_num,
+        })
+    }
+
+    fn take_box(&mut self) -> Box<dyn CsrTrait<I>> {
+        Box::new(std::mem::replace(self, BatchMutableCsr::new()))
+    }
+
     fn delete_vertices(&mut self, vertices: &HashSet<I>) {
         for vertex in vertices {
             let vertex = vertex.index();
@@ -91,6 +91,14 @@ impl<I: IndexType> BatchMutableSingleCsrBuilder<I> {
     }
 }
 
+fn copy_array<T: Copy>(input: &ArrayType<T>) -> ArrayType<T> {
+    let mut output = ArrayType::with_capacity(input.len());
+    for v in input.iter() {
+        output.push(*v);
+    }
+    output
+}
+
 impl<I: IndexType> BatchMutableSingleCsr<I> {
     pub fn new() -> Self {
         BatchMutableSingleCsr { nbr_list: ArrayType::new(), vertex_num: 0, edge_num: 0, vertex_capacity: 0 }
@@ -240,6 +248,19 @@ impl<I: IndexType> CsrTrait<I> for BatchMutableSingleCsr<I> {
         self
     }
 
+    fn clone_box(&self) -> Box<dyn CsrTrait<I>> {
+        Box::new(BatchMutableSingleCsr {
+            nbr_list: copy_array(&self.nbr_list),
+            vertex_num: self.vertex_num,
+            edge_num: self.edge_num,
+            vertex_capacity: self.vertex_capacity,
+        })
+    }
+
+    fn take_box(&mut self) -> Box<dyn CsrTrait<I>> {
+        Box::new(std::mem::replace(self, BatchMutableSingleCsr::new()))
+    }
+
     fn delete_vertices(&mut self, vertices: &HashSet<I>) {
         for vertex in vertices {
             self.remove_vertex(*vertex);
@@ -26,13 +26,107 @@ use crate::date::parse_date;
 use crate::date_time::parse_datetime;
 use crate::error::GDBResult;
 
+/// Validity of the rows of a column, a set bit means that the row has a value. Bits beyond `len`
+/// are always cleared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidityBitmap {
+    bits: Vec<u64>,
+    len: usize,
+}
+
+impl ValidityBitmap {
+    pub fn new(len: usize, valid: bool) -> Self {
+        let mut bitmap = Self { bits: Vec::new(), len: 0 };
+        bitmap.resize(len, valid);
+        bitmap
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_valid(&self, index: usize) -> bool {
+        index < self.len && (self.bits[index / 64] >> (index % 64)) & 1 == 1
+    }
+
+    pub fn set(&mut self, index: usize, valid: bool) {
+        if valid {
+            self.bits[index / 64] |= 1 << (index % 64);
+        } else {
+            self.bits[index / 64] &= !(1 << (index % 64));
+        }
+    }
+
+    pub fn null_count(&self) -> usize {
+        self.len
+            - self
+                .bits
+                .iter()
+                .map(|word| word.count_ones() as usize)
+                .sum::<usize>()
+    }
+
+    /// Rows appended when growing are valid if `valid` is true.
+    pub fn resize(&mut self, len: usize, valid: bool) {
+        if len > self.len {
+            let old_len = self.len;
+            let old_capacity = self.bits.len() * 64;
+            self.bits
+                .resize((len + 63) / 64, if valid { u64::MAX } else { 0 });
+            self.len = len;
+            for index in old_len..len.min(old_capacity) {
+                self.set(index, valid);
+            }
+        } else {
+            self.bits.truncate((len + 63) / 64);
+            self.len = len;
+        }
+        if len % 64 != 0 {
+            if let Some(last) = self.bits.last_mut() {
+                *last &= (1 << (len % 64)) - 1;
+            }
+        }
+    }
+
+    fn serialize(&self, writer: &mut BufWriter<File>) -> std::io::Result<()> {
+        writer.write_u64::<LittleEndian>(self.len as u64)?;
+        for word in self.bits.iter() {
+            writer.write_u64::<LittleEndian>(*word)?;
+        }
+        Ok(())
+    }
+
+    fn deserialize(reader: &mut BufReader<File>) -> std::io::Result<Self> {
+        let len = reader.read_u64::<LittleEndian>()? as usize;
+        let mut bits = Vec::with_capacity((len + 63) / 64);
+        for _ in 0..(len + 63) / 64 {
+            bits.push(reader.read_u64::<LittleEndian>()?);
+        }
+        Ok(Self { bits, len })
+    }
+}
+
 #[derive(Debug)]
 pub struct ColTable {
     columns: Vec<Box<dyn Column>>,
+    /// Validity of each column, which is only allocated once a null value is written to the column,
+    /// rows beyond the bitmap are valid.
+    validity: Vec<Option<ValidityBitmap>>,
     pub header: HashMap<String, usize>,
     row_num: usize,
 }
 
+impl Clone for ColTable {
+    fn clone(&self) -> Self {
+        ColTable {
+            columns: self.columns.iter().map(clone_column).collect(),
+            validity: self.validity.clone(),
+            header: self.header.clone(),
+            row_num: self.row_num,
+        }
+    }
+}
+
 impl ColTable {
     pub fn new(types: Vec<(DataType, String)>) -> Self {
         let mut columns = Vec::<Box<dyn Column>>::with_capacity(types.len());
@@ -70,12 +164,34 @@ impl ColTable {
                 DataType::ID => {
                     columns.push(Box::new(IDColumn::new()));
                 }
+                DataType::Boolean => {
+                    columns.push(Box::new(BooleanColumn::new()));
+                }
+                DataType::Float => {
+                    columns.push(Box::new(FloatColumn::new()));
+                }
+                DataType::Int32List => {
+                    columns.push(Box::new(Int32ListColumn::new()));
+                }
+                DataType::Int64List => {
+                    columns.push(Box::new(Int64ListColumn::new()));
+                }
+                DataType::FloatList => {
+                    columns.push(Box::new(FloatListColumn::new()));
+                }
+                DataType::DoubleList => {
+                    columns.push(Box::new(DoubleListColumn::new()));
+                }
+                DataType::StringList => {
+                    columns.push(Box::new(StringListColumn::new()));
+                }
                 DataType::NULL => {
                     error!("Unexpected column type");
                 }
             }
         }
-        Self { columns, header, row_num: 0 }
+        let validity = vec![None; columns.len()];
+        Self { columns, validity, header, row_num: 0 }
     }
 
     pub fn new_empty(&self) -> Self {
@@ -98,6 +214,40 @@ impl ColTable {
         self.row_num
     }
 
+    /// Returns true if the value of column `col_i` in row `row_i` is null.
+    pub fn is_null(&self, col_i: usize, row_i: usize) -> bool {
+        match self.validity.get(col_i) {
+            Some(Some(bitmap)) => row_i < bitmap.len() && !bitmap.is_valid(row_i),
+            _ => false,
+        }
+    }
+
+    pub fn null_count(&self, col_i: usize) -> usize {
+        match self.validity.get(col_i) {
+            Some(Some(bitmap)) => bitmap.null_count(),
+            _ => 0,
+        }
+    }
+
+    fn set_valid(&mut self, col_i: usize, row_i: usize, valid: bool) {
+        let row_num = self.row_num.max(row_i + 1);
+        match &mut self.validity[col_i] {
+            Some(bitmap) => {
+                if bitmap.len() <= row_i {
+                    bitmap.resize(row_num, true);
+                }
+                bitmap.set(row_i, valid);
+            }
+            None => {
+                if !valid {
+                    let mut bitmap = ValidityBitmap::new(row_num, true);
+                    bitmap.set(row_i, false);
+                    self.validity[col_i] = Some(bitmap);
+                }
+            }
+        }
+    }
+
     pub fn push(&mut self, row: &Vec<Item>) {
         let col_num = self.columns.len();
         if row.len() < col_num {
@@ -106,6 +256,7 @@ impl ColTable {
         }
         for i in 0..col_num {
             self.columns[i].push(row[i].clone());
+            self.set_valid(i, self.row_num, !matches!(row[i], Item::Null));
         }
         self.row_num += 1;
     }
@@ -119,12 +270,16 @@ impl ColTable {
                 for _ in 0..null_num {
                     col.push(Item::Null);
                 }
+                for row_i in self.row_num..index {
+                    self.set_valid(i, row_i, false);
+                }
             }
             self.row_num = index;
             self.push(row);
         } else {
             for i in 0..col_num {
                 self.columns[i].set(index, row[i].clone());
+                self.set_valid(i, index, !matches!(row[i], Item::Null));
             }
         }
     }
@@ -133,6 +288,13 @@ impl ColTable {
         let property_num = self.columns.len();
         if !self.header.contains_key(&index_name) {
             self.header.insert(index_name, property_num);
+            // existing rows have no value of the new property until it is set
+            if self.row_num > 0 {
+                self.validity
+                    .push(Some(ValidityBitmap::new(self.row_num, false)));
+            } else {
+                self.validity.push(None);
+            }
             match data_type {
                 DataType::Int32 => {
                     let mut column = Int32Column::new();
@@ -184,6 +346,41 @@ impl ColTable {
                     column.resize(self.row_num);
                     self.columns.push(Box::new(column));
                 }
+                DataType::Boolean => {
+                    let mut column = BooleanColumn::new();
+                    column.resize(self.row_num);
+                    self.columns.push(Box::new(column));
+                }
+                DataType::Float => {
+                    let mut column = FloatColumn::new();
+                    column.resize(self.row_num);
+                    self.columns.push(Box::new(column));
+                }
+                DataType::Int32List => {
+                    let mut column = Int32ListColumn::new();
+                    column.resize(self.row_num);
+                    self.columns.push(Box::new(column));
+                }
+                DataType::Int64List => {
+                    let mut column = Int64ListColumn::new();
+                    column.resize(self.row_num);
+                    self.columns.push(Box::new(column));
+                }
+                DataType::FloatList => {
+                    let mut column = FloatListColumn::new();
+                    column.resize(self.row_num);
+                    self.columns.push(Box::new(column));
+                }
+                DataType::DoubleList => {
+                    let mut column = DoubleListColumn::new();
+                    column.resize(self.row_num);
+                    self.columns.push(Box::new(column));
+                }
+                DataType::StringList => {
+                    let mut column = StringListColumn::new();
+                    column.resize(self.row_num);
+                    self.columns.push(Box::new(column));
+                }
                 DataType::NULL => {
                     panic!("Data type of column can not be null");
                 }
@@ -193,8 +390,12 @@ impl ColTable {
 
     pub fn set_property(&mut self, prop_name: String, index: &Vec<usize>, data: Box<dyn Column>) {
         if let Some(prop_id) = self.header.get(&prop_name) {
-            let mut column = self.columns.get_mut(*prop_id).unwrap();
+            let prop_id = *prop_id;
+            let column = self.columns.get_mut(prop_id).unwrap();
             column.set_column_batch(index, &data);
+            for row_i in index.iter() {
+                self.set_valid(prop_id, *row_i, true);
+            }
         }
     }
 
@@ -209,14 +410,14 @@ impl ColTable {
 
     pub fn get_item(&self, col_name: &str, row_i: usize) -> Option<RefItem> {
         if let Some(col_i) = self.header.get(col_name) {
-            self.columns[*col_i].get(row_i)
+            self.get_item_by_index(*col_i, row_i)
         } else {
             None
         }
     }
 
     pub fn get_item_by_index(&self, col_i: usize, row_i: usize) -> Option<RefItem> {
-        if col_i < self.columns.len() {
+        if col_i < self.columns.len() && !self.is_null(col_i, row_i) {
             self.columns[col_i].get(row_i)
         } else {
             None
@@ -226,8 +427,11 @@ impl ColTable {
     pub fn get_row(&self, row_i: usize) -> Option<Vec<Item>> {
         if row_i < self.row_num {
             let mut row = Vec::new();
-            for col in self.columns.iter() {
-                row.push(col.get(row_i).unwrap().to_owned());
+            for col_i in 0..self.columns.len() {
+                match self.get_item_by_index(col_i, row_i) {
+                    Some(item) => row.push(item.to_owned()),
+                    None => row.push(Item::Null),
+                }
             }
             Some(row)
         } else {
@@ -241,12 +445,14 @@ impl ColTable {
         }
         for col_i in 0..self.col_num() {
             self.columns[col_i].set_column_elem(self_i, &other.columns[col_i], other_i);
+            self.set_valid(col_i, self_i, !other.is_null(col_i, other_i));
         }
     }
 
     pub fn move_row(&mut self, from: usize, to: usize) {
         for col_i in 0..self.col_num() {
             self.columns[col_i].move_elem(from, to);
+            self.set_valid(col_i, to, !self.is_null(col_i, from));
         }
     }
 
@@ -256,12 +462,21 @@ impl ColTable {
         }
         for col_i in 0..self.col_num() {
             self.columns[col_i].copy_range(self_i, &other.columns[col_i], other_i, num);
+            if self.validity[col_i].is_some() || other.validity[col_i].is_some() {
+                for k in 0..num {
+                    self.set_valid(col_i, self_i + k, !other.is_null(col_i, other_i + k));
+                }
+            }
         }
     }
 
+    /// Rows appended when growing hold the default value of each column and are not null.
     pub fn resize(&mut self, row_num: usize) {
         for col_i in 0..self.col_num() {
             self.columns[col_i].resize(row_num);
+            if let Some(bitmap) = &mut self.validity[col_i] {
+                bitmap.resize(row_num, true);
+            }
         }
         self.row_num = row_num;
     }
@@ -294,6 +509,21 @@ impl ColTable {
                 .unwrap();
             col.serialize(&mut writer).unwrap();
         }
+
+        writer
+            .write_u64::<LittleEndian>(self.validity.len() as u64)
+            .unwrap();
+        for bitmap in self.validity.iter() {
+            match bitmap {
+                Some(bitmap) => {
+                    writer.write_u8(1).unwrap();
+                    bitmap.serialize(&mut writer).unwrap();
+                }
+                None => {
+                    writer.write_u8(0).unwrap();
+                }
+            }
+        }
     }
 
     pub fn deserialize_table(&mut self, path: &String) {
@@ -367,12 +597,57 @@ impl ColTable {
                     col.deserialize(&mut reader).unwrap();
                     self.columns.push(Box::new(col));
                 }
+                DataType::Boolean => {
+                    let mut col = BooleanColumn::new();
+                    col.deserialize(&mut reader).unwrap();
+                    self.columns.push(Box::new(col));
+                }
+                DataType::Float => {
+                    let mut col = FloatColumn::new();
+                    col.deserialize(&mut reader).unwrap();
+                    self.columns.push(Box::new(col));
+                }
+                DataType::Int32List => {
+                    let mut col = Int32ListColumn::new();
+                    col.deserialize(&mut reader).unwrap();
+                    self.columns.push(Box::new(col));
+                }
+                DataType::Int64List => {
+                    let mut col = Int64ListColumn::new();
+                    col.deserialize(&mut reader).unwrap();
+                    self.columns.push(Box::new(col));
+                }
+                DataType::FloatList => {
+                    let mut col = FloatListColumn::new();
+                    col.deserialize(&mut reader).unwrap();
+                    self.columns.push(Box::new(col));
+                }
+                DataType::DoubleList => {
+                    let mut col = DoubleListColumn::new();
+                    col.deserialize(&mut reader).unwrap();
+                    self.columns.push(Box::new(col));
+                }
+                DataType::StringList => {
+                    let mut col = StringListColumn::new();
+                    col.deserialize(&mut reader).unwrap();
+                    self.columns.push(Box::new(col));
+                }
                 DataType::NULL => {
                     let col = Int32Column::new();
                     self.columns.push(Box::new(col));
                 }
             };
         }
+
+        self.validity = vec![None; self.columns.len()];
+        // tables serialized without validity bitmaps end here, all of their values are valid
+        if let Ok(validity_len) = reader.read_u64::<LittleEndian>() {
+            for col_i in 0..validity_len as usize {
+                if reader.read_u8().unwrap() == 1 {
+                    self.validity[col_i] = Some(ValidityBitmap::deserialize(&mut reader).unwrap());
+                }
+            }
+        }
     }
 
     pub fn is_same(&self, other: &Self) -> bool {
@@ -390,6 +665,12 @@ impl ColTable {
                 info!("column-{} type not same", i);
                 return false;
             }
+            if self.null_count(i) != other.null_count(i)
+                || (0..self.row_num).any(|row_i| self.is_null(i, row_i) != other.is_null(i, row_i))
+            {
+                info!("column-{} validity not same", i);
+                return false;
+            }
             match self.columns[i].get_type() {
                 DataType::Int32 => {
                     if !self.columns[i]
@@ -478,6 +759,23 @@ impl ColTable {
                         return false;
                     }
                 }
+                DataType::Int32List
+                | DataType::Int64List
+                | DataType::FloatList
+                | DataType::DoubleList
+                | DataType::StringList => {
+                    let same = match self.columns[i].get_type() {
+                        DataType::Int32List => is_same_list::<i32>(&self.columns[i], &other.columns[i]),
+                        DataType::Int64List => is_same_list::<i64>(&self.columns[i], &other.columns[i]),
+                        DataType::FloatList => is_same_list::<f32>(&self.columns[i], &other.columns[i]),
+                        DataType::DoubleList => is_same_list::<f64>(&self.columns[i], &other.columns[i]),
+                        _ => is_same_list::<String>(&self.columns[i], &other.columns[i]),
+                    };
+                    if !same {
+                        info!("column-{} data not same", i);
+                        return false;
+                    }
+                }
                 _ => {
                     info!("unexpected type");
                     return false;
@@ -488,16 +786,49 @@ impl ColTable {
     }
 }
 
+fn is_same_list<T: ListItem>(lhs: &Box<dyn Column>, rhs: &Box<dyn Column>) -> bool {
+    lhs.as_any()
+        .downcast_ref::<ListColumn<T>>()
+        .unwrap()
+        .is_same(
+            rhs.as_any()
+                .downcast_ref::<ListColumn<T>>()
+                .unwrap(),
+        )
+}
+
 unsafe impl Sync for ColTable {}
 
 unsafe impl Send for ColTable {}
 
+/// Returns true if `val` is an empty field of a non-string value type, which is loaded as null.
+/// Empty fields of string types are empty strings, and those of list types are empty lists.
+pub fn is_null_field(val: &str, data_type: DataType) -> bool {
+    val.is_empty()
+        && match data_type {
+            DataType::Int32
+            | DataType::UInt32
+            | DataType::Int64
+            | DataType::UInt64
+            | DataType::Double
+            | DataType::Date
+            | DataType::DateTime
+            | DataType::Boolean
+            | DataType::Float => true,
+            _ => false,
+        }
+}
+
 pub fn parse_properties(
     record: &StringRecord, header: &[(String, DataType)], selected: &[bool],
 ) -> GDBResult<Vec<Item>> {
     let mut properties = Vec::new();
     for (index, val) in record.iter().enumerate() {
         if selected[index] {
+            if is_null_field(val, header[index].1) {
+                properties.push(Item::Null);
+                continue;
+            }
             match header[index].1 {
                 DataType::Int32 => {
                     properties.push(Item::Int32(val.parse::<i32>()?));
@@ -523,6 +854,27 @@ pub fn parse_properties(
                 DataType::Double => {
                     properties.push(Item::Double(val.parse::<f64>()?));
                 }
+                DataType::Boolean => {
+                    properties.push(Item::Boolean(val.parse::<bool>()?));
+                }
+                DataType::Float => {
+                    properties.push(Item::Float(val.parse::<f32>()?));
+                }
+                DataType::Int32List => {
+                    properties.push(Item::Int32List(parse_list(val)?));
+                }
+                DataType::Int64List => {
+                    properties.push(Item::Int64List(parse_list(val)?));
+                }
+                DataType::FloatList => {
+                    properties.push(Item::FloatList(parse_list(val)?));
+                }
+                DataType::DoubleList => {
+                    properties.push(Item::DoubleList(parse_list(val)?));
+                }
+                DataType::StringList => {
+                    properties.push(Item::StringList(parse_list(val)?));
+                }
                 DataType::NULL => {
                     error!("Unexpected field type");
                 }
@@ -542,6 +894,10 @@ pub fn parse_properties_by_mappings(
     let mut properties = vec![];
     for (index, val) in record.iter().enumerate() {
         if index < mappings.len() && mappings[index] >= 0 {
+            if is_null_field(val, header[mappings[index] as usize].1) {
+                properties.push(Item::Null);
+                continue;
+            }
             match header[mappings[index] as usize].1 {
                 DataType::Int32 => {
                     properties.push(Item::Int32(val.parse::<i32>()?));
@@ -567,6 +923,27 @@ pub fn parse_properties_by_mappings(
                 DataType::Double => {
                     properties.push(Item::Double(val.parse::<f64>()?));
                 }
+                DataType::Boolean => {
+                    properties.push(Item::Boolean(val.parse::<bool>()?));
+                }
+                DataType::Float => {
+                    properties.push(Item::Float(val.parse::<f32>()?));
+                }
+                DataType::Int32List => {
+                    properties.push(Item::Int32List(parse_list(val)?));
+                }
+                DataType::Int64List => {
+                    properties.push(Item::Int64List(parse_list(val)?));
+                }
+                DataType::FloatList => {
+                    properties.push(Item::FloatList(parse_list(val)?));
+                }
+                DataType::DoubleList => {
+                    properties.push(Item::DoubleList(parse_list(val)?));
+                }
+                DataType::StringList => {
+                    properties.push(Item::StringList(parse_list(val)?));
+                }
                 DataType::NULL => {
                     error!("Unexpected field type");
                 }
@@ -579,3 +956,183 @@ pub fn parse_properties_by_mappings(
     }
     Ok(properties)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn person_table() -> ColTable {
+        ColTable::new(vec![(DataType::Int32, "age".to_string()), (DataType::String, "name".to_string())])
+    }
+
+    fn ages(table: &ColTable) -> Vec<Option<i32>> {
+        (0..table.row_num())
+            .map(|row_i| {
+                table
+                    .get_item("age", row_i)
+                    .map(|item| item.as_i32().unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_validity_bitmap() {
+        let mut bitmap = ValidityBitmap::new(70, true);
+        assert_eq!(bitmap.null_count(), 0);
+        bitmap.set(3, false);
+        bitmap.set(65, false);
+        assert!(!bitmap.is_valid(3) && !bitmap.is_valid(65) && bitmap.is_valid(64));
+        assert_eq!(bitmap.null_count(), 2);
+        bitmap.resize(130, false);
+        assert_eq!(bitmap.null_count(), 62);
+        bitmap.resize(60, true);
+        assert_eq!(bitmap.null_count(), 1);
+        assert!(!bitmap.is_valid(60));
+    }
+
+    #[test]
+    fn test_nullable_columns() {
+        let mut table = person_table();
+        table.push(&vec![Item::Int32(29), Item::String("marko".to_string())]);
+        assert_eq!(table.null_count(0), 0);
+        table.push(&vec![Item::Null, Item::String("vadas".to_string())]);
+        table.insert(4, &vec![Item::Int32(35), Item::Null]);
+        assert_eq!(ages(&table), vec![Some(29), None, None, None, Some(35)]);
+        assert!(table.get_item("name", 4).is_none());
+        assert_eq!(table.get_row(1).unwrap()[0].to_string(), "");
+        assert_eq!(table.null_count(0), 3);
+
+        table.insert(2, &vec![Item::Int32(27), Item::String("josh".to_string())]);
+        table.move_row(0, 3);
+        assert_eq!(ages(&table), vec![Some(29), None, Some(27), Some(29), Some(35)]);
+
+        let mut other = table.new_empty();
+        other.copy_range(0, &table, 1, 4);
+        assert_eq!(ages(&other), vec![None, Some(27), Some(29), Some(35)]);
+        other.set_table_row(4, &table, 1);
+        assert_eq!(ages(&other), vec![None, Some(27), Some(29), Some(35), None]);
+        other.resize(6);
+        assert_eq!(ages(&other)[5], Some(0));
+
+        // a new property is null until it is set
+        table.add_property("weight".to_string(), DataType::Double);
+        let mut weights = DoubleColumn::new();
+        weights.push(Item::Double(0.5));
+        table.set_property("weight".to_string(), &vec![2], Box::new(weights));
+        assert!(table.get_item("weight", 0).is_none());
+        assert!(table.get_item("weight", 2).is_some());
+        assert_eq!(table.null_count(2), 4);
+    }
+
+    #[test]
+    fn test_serialize_nullable_table() {
+        let mut table = person_table();
+        table.push(&vec![Item::Null, Item::String("marko".to_string())]);
+        table.push(&vec![Item::Int32(27), Item::String("vadas".to_string())]);
+        let dir = tempdir::TempDir::new("bmcsr_col_table").unwrap();
+        let path = dir
+            .path()
+            .join("table")
+            .to_str()
+            .unwrap()
+            .to_string();
+        table.serialize_table(&path);
+
+        let mut loaded = ColTable::new(vec![]);
+        loaded.deserialize_table(&path);
+        assert!(loaded.is_same(&table));
+        assert_eq!(ages(&loaded), vec![None, Some(27)]);
+    }
+
+    #[test]
+    fn test_list_columns() {
+        let mut table = ColTable::new(vec![
+            (DataType::Boolean, "flag".to_string()),
+            (DataType::Float, "score".to_string()),
+            (DataType::StringList, "tags".to_string()),
+            (DataType::FloatList, "embedding".to_string()),
+        ]);
+        let header: Vec<(String, DataType)> = vec![
+            ("flag".to_string(), DataType::Boolean),
+            ("score".to_string(), DataType::Float),
+            ("tags".to_string(), DataType::StringList),
+            ("embedding".to_string(), DataType::FloatList),
+        ];
+        for fields in [vec!["true", "0.5", "a;b", "1.5;2"], vec!["false", "", "", "3"]].iter() {
+            let record = StringRecord::from(fields.clone());
+            table.push(&parse_properties(&record, &header, &[true; 4]).unwrap());
+        }
+        assert!(matches!(table.get_item("flag", 0), Some(RefItem::Boolean(true))));
+        assert!(matches!(table.get_item("score", 0), Some(RefItem::Float(v)) if *v == 0.5));
+        assert!(table.get_item("score", 1).is_none());
+        assert_eq!(table.get_item("tags", 0).unwrap().to_string(), "a;b");
+        assert!(matches!(table.get_item("tags", 1), Some(RefItem::StringList(v)) if v.is_empty()));
+        assert!(matches!(table.get_item("embedding", 1), Some(RefItem::FloatList(v)) if v == &[3.0]));
+
+        // overwritten and moved rows keep their own values
+        table.insert(
+            0,
+            &vec![
+                Item::Boolean(false),
+                Item::Float(1.0),
+                Item::StringList(vec!["c".to_string()]),
+                Item::FloatList(vec![]),
+            ],
+        );
+        table.move_row(0, 1);
+        table.insert(
+            0,
+            &vec![
+                Item::Boolean(true),
+                Item::Float(2.0),
+                Item::StringList(vec!["d".to_string()]),
+                Item::FloatList(vec![4.0]),
+            ],
+        );
+        assert_eq!(table.get_item("tags", 0).unwrap().to_string(), "d");
+        assert_eq!(table.get_item("tags", 1).unwrap().to_string(), "c");
+
+        let dir = tempdir::TempDir::new("bmcsr_list_table").unwrap();
+        let path = dir
+            .path()
+            .join("table")
+            .to_str()
+            .unwrap()
+            .to_string();
+        table.serialize_table(&path);
+        let mut loaded = ColTable::new(vec![]);
+        loaded.deserialize_table(&path);
+        for row_i in 0..2 {
+            for col_i in 0..4 {
+                assert_eq!(
+                    format!("{:?}", loaded.get_item_by_index(col_i, row_i)),
+                    format!("{:?}", table.get_item_by_index(col_i, row_i))
+                );
+            }
+        }
+        let mut copied = table.clone();
+        copied.copy_range(0, &table, 1, 1);
+        assert_eq!(
+            copied
+                .get_item("embedding", 0)
+                .unwrap()
+                .to_string(),
+            ""
+        );
+        assert!(parse_list::<i32>("1;x").is_err());
+    }
+
+    #[test]
+    fn test_parse_empty_fields() {
+        let header = vec![
+            ("age".to_string(), DataType::Int32),
+            ("name".to_string(), DataType::String),
+            ("born".to_string(), DataType::Date),
+        ];
+        let record = StringRecord::from(vec!["", "", ""]);
+        let properties = parse_properties(&record, &header, &[true, true, true]).unwrap();
+        assert!(matches!(properties[0], Item::Null));
+        assert!(matches!(&properties[1], Item::String(name) if name.is_empty()));
+        assert!(matches!(properties[2], Item::Null));
+    }
+}
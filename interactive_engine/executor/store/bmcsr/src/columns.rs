@@ -31,6 +31,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::date::Date;
 use crate::date_time::DateTime;
+use crate::error::GDBResult;
 use crate::types::DefaultId;
 
 #[cfg(feature = "hugepage_table")]
@@ -51,6 +52,13 @@ pub enum DataType {
     DateTime = 8,
     LCString = 9,
     ID = 10,
+    Boolean = 11,
+    Float = 12,
+    Int32List = 13,
+    Int64List = 14,
+    FloatList = 15,
+    DoubleList = 16,
+    StringList = 17,
     NULL = 0,
 }
 
@@ -68,6 +76,13 @@ impl Encode for DataType {
             DataType::DateTime => writer.write_u8(8),
             DataType::LCString => writer.write_u8(9),
             DataType::ID => writer.write_u8(10),
+            DataType::Boolean => writer.write_u8(11),
+            DataType::Float => writer.write_u8(12),
+            DataType::Int32List => writer.write_u8(13),
+            DataType::Int64List => writer.write_u8(14),
+            DataType::FloatList => writer.write_u8(15),
+            DataType::DoubleList => writer.write_u8(16),
+            DataType::StringList => writer.write_u8(17),
         };
         Ok(())
     }
@@ -87,6 +102,13 @@ impl Decode for DataType {
             8 => DataType::DateTime,
             9 => DataType::LCString,
             10 => DataType::ID,
+            11 => DataType::Boolean,
+            12 => DataType::Float,
+            13 => DataType::Int32List,
+            14 => DataType::Int64List,
+            15 => DataType::FloatList,
+            16 => DataType::DoubleList,
+            17 => DataType::StringList,
             _ => panic!("Unknown data type"),
         };
         Ok(data_type)
@@ -107,6 +129,13 @@ impl DataType {
             8 => Some(Self::DateTime),
             9 => Some(Self::LCString),
             10 => Some(Self::ID),
+            11 => Some(Self::Boolean),
+            12 => Some(Self::Float),
+            13 => Some(Self::Int32List),
+            14 => Some(Self::Int64List),
+            15 => Some(Self::FloatList),
+            16 => Some(Self::DoubleList),
+            17 => Some(Self::StringList),
             _ => None,
         }
     }
@@ -124,6 +153,13 @@ impl DataType {
             Self::DateTime => 8,
             Self::LCString => 9,
             Self::ID => 10,
+            Self::Boolean => 11,
+            Self::Float => 12,
+            Self::Int32List => 13,
+            Self::Int64List => 14,
+            Self::FloatList => 15,
+            Self::DoubleList => 16,
+            Self::StringList => 17,
         }
     }
 }
@@ -153,6 +189,20 @@ impl<'a> From<&'a str> for DataType {
             DataType::ID
         } else if token == "LCString" {
             DataType::LCString
+        } else if token == "BOOL" || token == "BOOLEAN" {
+            DataType::Boolean
+        } else if token == "FLOAT" || token == "FLOAT32" {
+            DataType::Float
+        } else if token == "INT32_LIST" {
+            DataType::Int32List
+        } else if token == "INT64_LIST" {
+            DataType::Int64List
+        } else if token == "FLOAT_LIST" {
+            DataType::FloatList
+        } else if token == "DOUBLE_LIST" {
+            DataType::DoubleList
+        } else if token == "STRING_LIST" {
+            DataType::StringList
         } else {
             error!("Unsupported type {:?}", token);
             DataType::NULL
@@ -174,6 +224,11 @@ pub enum Item {
     DateTime(DateTime),
     VertexId(usize),
     EdgeId((u64, u64)),
+    Int32List(Vec<i32>),
+    Int64List(Vec<i64>),
+    FloatList(Vec<f32>),
+    DoubleList(Vec<f64>),
+    StringList(Vec<String>),
     Null,
 }
 
@@ -191,6 +246,11 @@ pub enum RefItem<'a> {
     VertexId(&'a usize),
     EdgeId((&'a u64, &'a u64)),
     String(&'a String),
+    Int32List(&'a [i32]),
+    Int64List(&'a [i64]),
+    FloatList(&'a [f32]),
+    DoubleList(&'a [f64]),
+    StringList(&'a [String]),
     Null,
 }
 
@@ -209,6 +269,11 @@ impl<'a> RefItem<'a> {
             RefItem::VertexId(v) => Item::VertexId(*v),
             RefItem::EdgeId((src, dst)) => Item::EdgeId((*src, *dst)),
             RefItem::String(v) => Item::String(v.clone()),
+            RefItem::Int32List(v) => Item::Int32List(v.to_vec()),
+            RefItem::Int64List(v) => Item::Int64List(v.to_vec()),
+            RefItem::FloatList(v) => Item::FloatList(v.to_vec()),
+            RefItem::DoubleList(v) => Item::DoubleList(v.to_vec()),
+            RefItem::StringList(v) => Item::StringList(v.to_vec()),
             RefItem::Null => Item::Null,
         }
     }
@@ -258,6 +323,27 @@ impl ConvertItem for () {
 impl Debug for Item {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            Item::Boolean(v) => {
+                write!(f, "boolean[{}]", v)
+            }
+            Item::Float(v) => {
+                write!(f, "float[{}]", v)
+            }
+            Item::Int32List(v) => {
+                write!(f, "int32_list[{:?}]", v)
+            }
+            Item::Int64List(v) => {
+                write!(f, "int64_list[{:?}]", v)
+            }
+            Item::FloatList(v) => {
+                write!(f, "float_list[{:?}]", v)
+            }
+            Item::DoubleList(v) => {
+                write!(f, "double_list[{:?}]", v)
+            }
+            Item::StringList(v) => {
+                write!(f, "string_list[{:?}]", v)
+            }
             Item::Int32(v) => {
                 write!(f, "int32[{}]", v)
             }
@@ -295,6 +381,13 @@ impl Debug for Item {
 impl ToString for Item {
     fn to_string(&self) -> String {
         match self {
+            Item::Boolean(v) => v.to_string(),
+            Item::Float(v) => v.to_string(),
+            Item::Int32List(v) => list_to_string(v),
+            Item::Int64List(v) => list_to_string(v),
+            Item::FloatList(v) => list_to_string(v),
+            Item::DoubleList(v) => list_to_string(v),
+            Item::StringList(v) => list_to_string(v),
             Item::Int32(v) => v.to_string(),
             Item::UInt32(v) => v.to_string(),
             Item::Int64(v) => v.to_string(),
@@ -312,6 +405,27 @@ impl ToString for Item {
 impl<'a> Debug for RefItem<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            RefItem::Boolean(v) => {
+                write!(f, "boolean[{}]", v)
+            }
+            RefItem::Float(v) => {
+                write!(f, "float[{}]", v)
+            }
+            RefItem::Int32List(v) => {
+                write!(f, "int32_list[{:?}]", v)
+            }
+            RefItem::Int64List(v) => {
+                write!(f, "int64_list[{:?}]", v)
+            }
+            RefItem::FloatList(v) => {
+                write!(f, "float_list[{:?}]", v)
+            }
+            RefItem::DoubleList(v) => {
+                write!(f, "double_list[{:?}]", v)
+            }
+            RefItem::StringList(v) => {
+                write!(f, "string_list[{:?}]", v)
+            }
             RefItem::Int32(v) => {
                 write!(f, "int32[{}]", v)
             }
@@ -349,6 +463,13 @@ impl<'a> Debug for RefItem<'a> {
 impl<'a> ToString for RefItem<'a> {
     fn to_string(&self) -> String {
         match self {
+            RefItem::Boolean(v) => v.to_string(),
+            RefItem::Float(v) => v.to_string(),
+            RefItem::Int32List(v) => list_to_string(v),
+            RefItem::Int64List(v) => list_to_string(v),
+            RefItem::FloatList(v) => list_to_string(v),
+            RefItem::DoubleList(v) => list_to_string(v),
+            RefItem::StringList(v) => list_to_string(v),
             RefItem::Int32(v) => v.to_string(),
             RefItem::UInt32(v) => v.to_string(),
             RefItem::Int64(v) => v.to_string(),
@@ -363,6 +484,16 @@ impl<'a> ToString for RefItem<'a> {
     }
 }
 
+/// Lists are written as their values separated by `LIST_DELIMITER`, the format they are loaded from.
+pub const LIST_DELIMITER: char = ';';
+
+fn list_to_string<T: ToString>(list: &[T]) -> String {
+    list.iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join(&LIST_DELIMITER.to_string())
+}
+
 impl<'a> RefItem<'a> {
     #[inline]
     pub fn as_u64(&self) -> Result<u64, CastError> {
@@ -1219,6 +1350,250 @@ impl Column for DoubleColumn {
     }
 }
 
+pub struct BooleanColumn {
+    pub data: ColumnContainer<bool>,
+}
+
+unsafe impl Send for BooleanColumn {}
+
+unsafe impl Sync for BooleanColumn {}
+
+impl BooleanColumn {
+    pub fn new() -> Self {
+        Self { data: ColumnContainer::new() }
+    }
+
+    #[cfg(feature = "hugepage_table")]
+    pub fn from(data: HugeVec<bool>) -> BooleanColumn {
+        BooleanColumn { data }
+    }
+
+    #[cfg(not(feature = "hugepage_table"))]
+    pub fn from(data: Vec<bool>) -> BooleanColumn {
+        BooleanColumn { data }
+    }
+
+    pub fn clone_from(other: &BooleanColumn) -> BooleanColumn {
+        BooleanColumn { data: other.data.clone() }
+    }
+}
+
+impl Debug for BooleanColumn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BooleanColumn: {:?}", self.data)
+    }
+}
+
+impl Column for BooleanColumn {
+    fn get_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
+    fn get(&self, index: usize) -> Option<RefItem> {
+        self.data
+            .get(index)
+            .map(|x| RefItem::Boolean(x))
+    }
+
+    fn set(&mut self, index: usize, val: Item) {
+        match val {
+            Item::Boolean(v) => {
+                self.data[index] = v;
+            }
+            _ => {
+                self.data[index] = false;
+            }
+        }
+    }
+
+    fn push(&mut self, val: Item) {
+        match val {
+            Item::Boolean(v) => {
+                self.data.push(v);
+            }
+            _ => {
+                self.data.push(false);
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn deserialize(&mut self, reader: &mut BufReader<File>) -> std::io::Result<()> {
+        let row_num = reader.read_u64::<LittleEndian>()? as usize;
+        let mut data = ColumnContainer::<bool>::with_capacity(row_num);
+        for _ in 0..row_num {
+            data.push(reader.read_u8()? != 0);
+        }
+        self.data = data;
+        Ok(())
+    }
+
+    fn serialize(&self, writer: &mut BufWriter<File>) -> std::io::Result<()> {
+        writer.write_u64::<LittleEndian>(self.data.len() as u64)?;
+        for v in self.data.iter() {
+            writer.write_u8(*v as u8)?;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, size: usize) {
+        self.data.resize(size, false);
+    }
+
+    fn set_column_batch(&mut self, index: &Vec<usize>, col: &Box<dyn Column>) {
+        if col.as_any().is::<Self>() {
+            let casted_col = col.as_any().downcast_ref::<Self>().unwrap();
+            for (index, i) in index.iter().enumerate() {
+                self.data[*i] = casted_col.data[index];
+            }
+        }
+    }
+
+    fn set_column_elem(&mut self, self_index: usize, col: &Box<dyn Column>, col_index: usize) {
+        let casted_col = col.as_any().downcast_ref::<Self>().unwrap();
+        self.data[self_index] = casted_col.data[col_index];
+    }
+
+    fn move_elem(&mut self, from: usize, to: usize) {
+        self.data[to] = self.data[from];
+    }
+
+    fn copy_range(&mut self, self_index: usize, col: &Box<dyn Column>, col_index: usize, num: usize) {
+        let casted_col = col.as_any().downcast_ref::<Self>().unwrap();
+        self.data[self_index..self_index + num]
+            .copy_from_slice(&casted_col.data[col_index..col_index + num]);
+    }
+}
+
+pub struct FloatColumn {
+    pub data: ColumnContainer<f32>,
+}
+
+unsafe impl Send for FloatColumn {}
+
+unsafe impl Sync for FloatColumn {}
+
+impl FloatColumn {
+    pub fn new() -> Self {
+        Self { data: ColumnContainer::new() }
+    }
+
+    #[cfg(feature = "hugepage_table")]
+    pub fn from(data: HugeVec<f32>) -> FloatColumn {
+        FloatColumn { data }
+    }
+
+    #[cfg(not(feature = "hugepage_table"))]
+    pub fn from(data: Vec<f32>) -> FloatColumn {
+        FloatColumn { data }
+    }
+
+    pub fn clone_from(other: &FloatColumn) -> FloatColumn {
+        FloatColumn { data: other.data.clone() }
+    }
+}
+
+impl Debug for FloatColumn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FloatColumn: {:?}", self.data)
+    }
+}
+
+impl Column for FloatColumn {
+    fn get_type(&self) -> DataType {
+        DataType::Float
+    }
+
+    fn get(&self, index: usize) -> Option<RefItem> {
+        self.data.get(index).map(|x| RefItem::Float(x))
+    }
+
+    fn set(&mut self, index: usize, val: Item) {
+        match val {
+            Item::Float(v) => {
+                self.data[index] = v;
+            }
+            _ => {
+                self.data[index] = 0_f32;
+            }
+        }
+    }
+
+    fn push(&mut self, val: Item) {
+        match val {
+            Item::Float(v) => {
+                self.data.push(v);
+            }
+            _ => {
+                self.data.push(0_f32);
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn deserialize(&mut self, reader: &mut BufReader<File>) -> std::io::Result<()> {
+        let row_num = reader.read_u64::<LittleEndian>()? as usize;
+        let mut data = ColumnContainer::<f32>::with_capacity(row_num);
+        for _ in 0..row_num {
+            data.push(reader.read_f32::<LittleEndian>()?);
+        }
+        self.data = data;
+        Ok(())
+    }
+
+    fn serialize(&self, writer: &mut BufWriter<File>) -> std::io::Result<()> {
+        writer.write_u64::<LittleEndian>(self.data.len() as u64)?;
+        for v in self.data.iter() {
+            writer.write_f32::<LittleEndian>(*v)?;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, size: usize) {
+        self.data.resize(size, 0.0);
+    }
+
+    fn set_column_batch(&mut self, index: &Vec<usize>, col: &Box<dyn Column>) {
+        if col.as_any().is::<Self>() {
+            let casted_col = col.as_any().downcast_ref::<Self>().unwrap();
+            for (index, i) in index.iter().enumerate() {
+                self.data[*i] = casted_col.data[index];
+            }
+        }
+    }
+
+    fn set_column_elem(&mut self, self_index: usize, col: &Box<dyn Column>, col_index: usize) {
+        let casted_col = col.as_any().downcast_ref::<Self>().unwrap();
+        self.data[self_index] = casted_col.data[col_index];
+    }
+
+    fn move_elem(&mut self, from: usize, to: usize) {
+        self.data[to] = self.data[from];
+    }
+
+    fn copy_range(&mut self, self_index: usize, col: &Box<dyn Column>, col_index: usize, num: usize) {
+        let casted_col = col.as_any().downcast_ref::<Self>().unwrap();
+        self.data[self_index..self_index + num]
+            .copy_from_slice(&casted_col.data[col_index..col_index + num]);
+    }
+}
+
 pub struct StringColumn {
     pub data: Vec<String>,
 }
@@ -1812,3 +2187,389 @@ impl Column for DateTimeColumn {
             .copy_from_slice(&casted_col.data[col_index..col_index + num]);
     }
 }
+
+/// Values of list columns.
+pub trait ListItem: Clone + Debug + Default + PartialEq + Send + Sync + 'static {
+    fn list_type() -> DataType;
+    fn to_list_item(list: Vec<Self>) -> Item;
+    fn to_list_ref_item(list: &[Self]) -> RefItem<'_>;
+    /// Returns an empty list if `item` is not a list of this type.
+    fn from_list_item(item: Item) -> Vec<Self>;
+    fn parse(val: &str) -> GDBResult<Self>;
+    fn write_value(&self, writer: &mut BufWriter<File>) -> std::io::Result<()>;
+    fn read_value(reader: &mut BufReader<File>) -> std::io::Result<Self>;
+}
+
+macro_rules! impl_list_item {
+    ($t:ty, $variant:ident, $write:ident, $read:ident) => {
+        impl ListItem for $t {
+            fn list_type() -> DataType {
+                DataType::$variant
+            }
+
+            fn to_list_item(list: Vec<Self>) -> Item {
+                Item::$variant(list)
+            }
+
+            fn to_list_ref_item(list: &[Self]) -> RefItem<'_> {
+                RefItem::$variant(list)
+            }
+
+            fn from_list_item(item: Item) -> Vec<Self> {
+                match item {
+                    Item::$variant(list) => list,
+                    _ => vec![],
+                }
+            }
+
+            fn parse(val: &str) -> GDBResult<Self> {
+                Ok(val.parse::<$t>()?)
+            }
+
+            fn write_value(&self, writer: &mut BufWriter<File>) -> std::io::Result<()> {
+                writer.$write::<LittleEndian>(*self)
+            }
+
+            fn read_value(reader: &mut BufReader<File>) -> std::io::Result<Self> {
+                reader.$read::<LittleEndian>()
+            }
+        }
+    };
+}
+
+impl_list_item!(i32, Int32List, write_i32, read_i32);
+impl_list_item!(i64, Int64List, write_i64, read_i64);
+impl_list_item!(f32, FloatList, write_f32, read_f32);
+impl_list_item!(f64, DoubleList, write_f64, read_f64);
+
+impl ListItem for String {
+    fn list_type() -> DataType {
+        DataType::StringList
+    }
+
+    fn to_list_item(list: Vec<Self>) -> Item {
+        Item::StringList(list)
+    }
+
+    fn to_list_ref_item(list: &[Self]) -> RefItem<'_> {
+        RefItem::StringList(list)
+    }
+
+    fn from_list_item(item: Item) -> Vec<Self> {
+        match item {
+            Item::StringList(list) => list,
+            _ => vec![],
+        }
+    }
+
+    fn parse(val: &str) -> GDBResult<Self> {
+        Ok(val.to_string())
+    }
+
+    fn write_value(&self, writer: &mut BufWriter<File>) -> std::io::Result<()> {
+        writer.write_i32::<LittleEndian>(self.len() as i32)?;
+        writer.write_all(self.as_bytes())
+    }
+
+    fn read_value(reader: &mut BufReader<File>) -> std::io::Result<Self> {
+        let length = reader.read_i32::<LittleEndian>()?;
+        let mut string_bytes = vec![0u8; length as usize];
+        reader.read_exact(&mut string_bytes)?;
+        Ok(String::from_utf8(string_bytes).unwrap())
+    }
+}
+
+/// Parse a list field whose values are separated by `LIST_DELIMITER`, an empty field is an empty
+/// list.
+pub fn parse_list<T: ListItem>(val: &str) -> GDBResult<Vec<T>> {
+    if val.is_empty() {
+        return Ok(vec![]);
+    }
+    val.split(LIST_DELIMITER)
+        .map(T::parse)
+        .collect()
+}
+
+/// A column of variable-length lists. The values of all rows are stored in `values`, and the list
+/// of row `i` is the `lengths[i]` values from `offsets[i]`. A row that is overwritten appends its
+/// new values, the values left behind are dropped when the column is serialized.
+/// Overwritten lists are reclaimed once at least this many values are dead and they make up more
+/// than half of the values.
+const LIST_COMPACT_MIN_DEAD: usize = 1024;
+
+pub struct ListColumn<T: ListItem> {
+    pub offsets: Vec<usize>,
+    pub lengths: Vec<usize>,
+    pub values: Vec<T>,
+    // values no longer referenced by a row, it may overcount values of rows shared by `move_elem`
+    dead: usize,
+}
+
+pub type Int32ListColumn = ListColumn<i32>;
+pub type Int64ListColumn = ListColumn<i64>;
+pub type FloatListColumn = ListColumn<f32>;
+pub type DoubleListColumn = ListColumn<f64>;
+pub type StringListColumn = ListColumn<String>;
+
+unsafe impl<T: ListItem> Send for ListColumn<T> {}
+
+unsafe impl<T: ListItem> Sync for ListColumn<T> {}
+
+impl<T: ListItem> ListColumn<T> {
+    pub fn new() -> Self {
+        Self { offsets: Vec::new(), lengths: Vec::new(), values: Vec::new(), dead: 0 }
+    }
+
+    pub fn get_list(&self, index: usize) -> Option<&[T]> {
+        if index < self.offsets.len() {
+            let offset = self.offsets[index];
+            Some(&self.values[offset..offset + self.lengths[index]])
+        } else {
+            None
+        }
+    }
+
+    pub fn push_list(&mut self, list: &[T]) {
+        self.offsets.push(self.values.len());
+        self.lengths.push(list.len());
+        self.values.extend_from_slice(list);
+    }
+
+    /// The old values of the row are left in place, as rows moved by `move_elem` may share them,
+    /// and reclaimed by a compaction once enough of them are dead.
+    pub fn set_list(&mut self, index: usize, list: &[T]) {
+        self.dead += self.lengths[index];
+        self.offsets[index] = self.values.len();
+        self.lengths[index] = list.len();
+        self.values.extend_from_slice(list);
+        self.compact_if_needed();
+    }
+
+    fn compact_if_needed(&mut self) {
+        if self.dead >= LIST_COMPACT_MIN_DEAD && self.dead * 2 > self.values.len() {
+            self.compact();
+        }
+    }
+
+    /// Copy the list of every row into new values, which drops dead values and gives rows shared by
+    /// `move_elem` their own copies.
+    pub fn compact(&mut self) {
+        let mut values = Vec::with_capacity(self.lengths.iter().sum());
+        for k in 0..self.offsets.len() {
+            let offset = self.offsets[k];
+            self.offsets[k] = values.len();
+            values.extend_from_slice(&self.values[offset..offset + self.lengths[k]]);
+        }
+        self.values = values;
+        self.dead = 0;
+    }
+
+    pub fn is_same(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+        (0..self.len()).all(|k| self.get_list(k) == other.get_list(k))
+    }
+
+    pub fn clone_from(other: &ListColumn<T>) -> ListColumn<T> {
+        ListColumn {
+            offsets: other.offsets.clone(),
+            lengths: other.lengths.clone(),
+            values: other.values.clone(),
+            dead: other.dead,
+        }
+    }
+}
+
+impl<T: ListItem> Debug for ListColumn<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lists: Vec<&[T]> = (0..self.len())
+            .map(|k| self.get_list(k).unwrap())
+            .collect();
+        write!(f, "ListColumn: {:?}", lists)
+    }
+}
+
+impl<T: ListItem> Column for ListColumn<T> {
+    fn get_type(&self) -> DataType {
+        T::list_type()
+    }
+
+    fn get(&self, index: usize) -> Option<RefItem> {
+        self.get_list(index)
+            .map(|x| T::to_list_ref_item(x))
+    }
+
+    fn set(&mut self, index: usize, val: Item) {
+        self.set_list(index, &T::from_list_item(val));
+    }
+
+    fn push(&mut self, val: Item) {
+        self.push_list(&T::from_list_item(val));
+    }
+
+    fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn deserialize(&mut self, reader: &mut BufReader<File>) -> std::io::Result<()> {
+        let row_num = reader.read_u64::<LittleEndian>()? as usize;
+        let mut offsets = Vec::with_capacity(row_num);
+        let mut lengths = Vec::with_capacity(row_num);
+        let mut values = Vec::new();
+        for _ in 0..row_num {
+            let length = reader.read_u64::<LittleEndian>()? as usize;
+            offsets.push(values.len());
+            lengths.push(length);
+            for _ in 0..length {
+                values.push(T::read_value(reader)?);
+            }
+        }
+        self.offsets = offsets;
+        self.lengths = lengths;
+        self.values = values;
+        self.dead = 0;
+        Ok(())
+    }
+
+    fn serialize(&self, writer: &mut BufWriter<File>) -> std::io::Result<()> {
+        writer.write_u64::<LittleEndian>(self.len() as u64)?;
+        for k in 0..self.len() {
+            let list = self.get_list(k).unwrap();
+            writer.write_u64::<LittleEndian>(list.len() as u64)?;
+            for v in list.iter() {
+                v.write_value(writer)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, size: usize) {
+        if size < self.lengths.len() {
+            self.dead += self.lengths[size..].iter().sum::<usize>();
+        }
+        self.offsets.resize(size, self.values.len());
+        self.lengths.resize(size, 0);
+        self.compact_if_needed();
+    }
+
+    fn set_column_batch(&mut self, index: &Vec<usize>, col: &Box<dyn Column>) {
+        if col.as_any().is::<Self>() {
+            let casted_col = col.as_any().downcast_ref::<Self>().unwrap();
+            for (index, i) in index.iter().enumerate() {
+                self.set_list(*i, casted_col.get_list(index).unwrap());
+            }
+        }
+    }
+
+    fn set_column_elem(&mut self, self_index: usize, col: &Box<dyn Column>, col_index: usize) {
+        let casted_col = col.as_any().downcast_ref::<Self>().unwrap();
+        self.set_list(self_index, casted_col.get_list(col_index).unwrap());
+    }
+
+    fn move_elem(&mut self, from: usize, to: usize) {
+        if from != to {
+            self.dead += self.lengths[to];
+        }
+        self.offsets[to] = self.offsets[from];
+        self.lengths[to] = self.lengths[from];
+    }
+
+    fn copy_range(&mut self, self_index: usize, col: &Box<dyn Column>, col_index: usize, num: usize) {
+        let casted_col = col.as_any().downcast_ref::<Self>().unwrap();
+        for i in 0..num {
+            self.set_list(self_index + i, casted_col.get_list(col_index + i).unwrap());
+        }
+    }
+}
+
+pub fn clone_column(input: &Box<dyn Column>) -> Box<dyn Column> {
+    if let Some(int32_column) = input.as_any().downcast_ref::<Int32Column>() {
+        Box::new(Int32Column::clone_from(int32_column))
+    } else if let Some(uint32_column) = input.as_any().downcast_ref::<UInt32Column>() {
+        Box::new(UInt32Column::clone_from(uint32_column))
+    } else if let Some(int64_column) = input.as_any().downcast_ref::<Int64Column>() {
+        Box::new(Int64Column::clone_from(int64_column))
+    } else if let Some(uint64_column) = input.as_any().downcast_ref::<UInt64Column>() {
+        Box::new(UInt64Column::clone_from(uint64_column))
+    } else if let Some(id_column) = input.as_any().downcast_ref::<IDColumn>() {
+        Box::new(IDColumn::clone_from(id_column))
+    } else if let Some(doule_column) = input.as_any().downcast_ref::<DoubleColumn>() {
+        Box::new(DoubleColumn::clone_from(doule_column))
+    } else if let Some(string_column) = input.as_any().downcast_ref::<StringColumn>() {
+        Box::new(StringColumn::clone_from(string_column))
+    } else if let Some(lc_string_column) = input.as_any().downcast_ref::<LCStringColumn>() {
+        Box::new(LCStringColumn::clone_from(lc_string_column))
+    } else if let Some(date_column) = input.as_any().downcast_ref::<DateColumn>() {
+        Box::new(DateColumn::clone_from(date_column))
+    } else if let Some(datetime_column) = input.as_any().downcast_ref::<DateTimeColumn>() {
+        Box::new(DateTimeColumn::clone_from(datetime_column))
+    } else if let Some(boolean_column) = input.as_any().downcast_ref::<BooleanColumn>() {
+        Box::new(BooleanColumn::clone_from(boolean_column))
+    } else if let Some(float_column) = input.as_any().downcast_ref::<FloatColumn>() {
+        Box::new(FloatColumn::clone_from(float_column))
+    } else if let Some(list_column) = input.as_any().downcast_ref::<Int32ListColumn>() {
+        Box::new(Int32ListColumn::clone_from(list_column))
+    } else if let Some(list_column) = input.as_any().downcast_ref::<Int64ListColumn>() {
+        Box::new(Int64ListColumn::clone_from(list_column))
+    } else if let Some(list_column) = input.as_any().downcast_ref::<FloatListColumn>() {
+        Box::new(FloatListColumn::clone_from(list_column))
+    } else if let Some(list_column) = input
+        .as_any()
+        .downcast_ref::<DoubleListColumn>()
+    {
+        Box::new(DoubleListColumn::clone_from(list_column))
+    } else if let Some(list_column) = input
+        .as_any()
+        .downcast_ref::<StringListColumn>()
+    {
+        Box::new(StringListColumn::clone_from(list_column))
+    } else {
+        panic!("Unknown column type")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_column_reclaims_overwrites() {
+        let mut column = Int32ListColumn::new();
+        for k in 0..10 {
+            column.push_list(&[k; 4]);
+        }
+        for k in 0..10_000 {
+            column.set_list((k % 10) as usize, &[k; 4]);
+        }
+        // dead values never exceed the live ones by more than the compaction threshold
+        assert!(column.values.len() <= 2 * (40 + LIST_COMPACT_MIN_DEAD));
+        for row in 0..10 {
+            assert_eq!(column.get_list(row), Some(&[9990 + row as i32; 4][..]));
+        }
+    }
+
+    #[test]
+    fn test_list_column_shared_rows() {
+        let mut column = Int32ListColumn::new();
+        column.push_list(&[1, 1]);
+        column.push_list(&[2]);
+        column.move_elem(0, 1);
+        column.set_list(0, &[3]);
+        assert_eq!(column.get_list(0), Some(&[3][..]));
+        assert_eq!(column.get_list(1), Some(&[1, 1][..]));
+
+        column.compact();
+        assert_eq!(column.values, vec![3, 1, 1]);
+        assert_eq!(column.get_list(0), Some(&[3][..]));
+        assert_eq!(column.get_list(1), Some(&[1, 1][..]));
+        column.resize(1);
+        column.compact();
+        assert_eq!(column.values, vec![3]);
+    }
+}
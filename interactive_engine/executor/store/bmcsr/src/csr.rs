@@ -115,6 +115,11 @@ pub trait CsrTrait<I: IndexType>: Send + Sync {
     fn as_any(&self) -> &dyn Any;
     fn as_mut_any(&mut self) -> &mut dyn Any;
 
+    /// Make a deep copy of the csr.
+    fn clone_box(&self) -> Box<dyn CsrTrait<I>>;
+    /// Move the content of the csr out, leaving an empty csr in place.
+    fn take_box(&mut self) -> Box<dyn CsrTrait<I>>;
+
     fn delete_vertices(&mut self, vertices: &HashSet<I>);
     fn parallel_delete_edges(&mut self, edges: &Vec<(I, I)>, reverse: bool, p: u32);
     fn parallel_delete_edges_with_props(
@@ -16,6 +16,7 @@
 use std::any::Any;
 use std::io::Error;
 use std::num::{ParseFloatError, ParseIntError};
+use std::str::ParseBoolError;
 
 use dyn_type::CastError;
 
@@ -44,6 +45,7 @@ pub enum GDBError {
     InvalidFunctionCallError,
     InvalidTypeError,
     FieldNotExistError,
+    ParquetError(parquet::errors::ParquetError),
 }
 
 impl From<std::io::Error> for GDBError {
@@ -64,6 +66,12 @@ impl From<std::num::ParseFloatError> for GDBError {
     }
 }
 
+impl From<ParseBoolError> for GDBError {
+    fn from(_error: ParseBoolError) -> Self {
+        GDBError::ParseError
+    }
+}
+
 impl From<serde_json::Error> for GDBError {
     fn from(error: serde_json::Error) -> Self {
         GDBError::JsonError(error)
@@ -76,6 +84,12 @@ impl From<Box<bincode::ErrorKind>> for GDBError {
     }
 }
 
+impl From<parquet::errors::ParquetError> for GDBError {
+    fn from(error: parquet::errors::ParquetError) -> Self {
+        GDBError::ParquetError(error)
+    }
+}
+
 impl From<()> for GDBError {
     fn from(_error: ()) -> Self {
         GDBError::UnknownError
@@ -13,8 +13,9 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+use std::any::Any;
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
+use std::fs::{create_dir_all, File};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -28,13 +29,14 @@ use crate::col_table::ColTable;
 use crate::columns::{Column, DataType, Item, RefItem};
 use crate::csr::CsrTrait;
 use crate::edge_trim::EdgeTrimJson;
-use crate::error::GDBResult;
+use crate::error::{GDBError, GDBResult};
 use crate::graph::{Direction, IndexType};
 use crate::schema::CsrGraphSchema;
 use crate::sub_graph::{SingleSubGraph, SubGraph};
 use crate::types::*;
 use crate::utils::{Iter, LabeledIterator, Range};
 use crate::vertex_map::VertexMap;
+use crate::wal;
 
 /// A data structure to maintain a local view of the vertex.
 #[derive(Debug, Clone)]
@@ -92,7 +94,9 @@ impl<'a, G: IndexType + Sync + Send, I: IndexType + Sync + Send> LocalVertex<'a,
         if let Some(prop) = self.table {
             let mut property_table = HashMap::new();
             for head in prop.header.keys() {
-                property_table.insert(head.clone(), prop.get_item(head, self.index.index()).unwrap());
+                if let Some(item) = prop.get_item(head, self.index.index()) {
+                    property_table.insert(head.clone(), item);
+                }
             }
             Some(property_table)
         } else {
@@ -177,7 +181,9 @@ impl<'a, G: IndexType + Sync + Send, I: IndexType + Sync + Send> LocalEdge<'a, G
         if let Some(prop) = self.table {
             let mut property_table = HashMap::new();
             for head in prop.header.keys() {
-                property_table.insert(head.clone(), prop.get_item(head, self.offset).unwrap());
+                if let Some(item) = prop.get_item(head, self.offset) {
+                    property_table.insert(head.clone(), item);
+                }
             }
             Some(property_table)
         } else {
@@ -186,18 +192,21 @@ impl<'a, G: IndexType + Sync + Send, I: IndexType + Sync + Send> LocalEdge<'a, G
     }
 }
 
+/// The csrs, property tables, vertex map and schema are shared with the snapshots taken by
+/// `snapshot()`, and are copied on write when a batch modifies any of them while they are shared.
+/// The vertex map is copied by label, see `VertexMap`.
 pub struct GraphDB<G: Send + Sync + IndexType = DefaultId, I: Send + Sync + IndexType = InternalId> {
     pub partition: usize,
-    pub ie: Vec<Box<dyn CsrTrait<I>>>,
-    pub oe: Vec<Box<dyn CsrTrait<I>>>,
+    pub ie: Vec<Arc<dyn CsrTrait<I>>>,
+    pub oe: Vec<Arc<dyn CsrTrait<I>>>,
 
-    pub graph_schema: CsrGraphSchema,
+    pub graph_schema: Arc<CsrGraphSchema>,
 
-    pub vertex_map: VertexMap<G, I>,
+    pub vertex_map: Arc<VertexMap<G, I>>,
 
-    pub vertex_prop_table: Vec<ColTable>,
-    pub ie_edge_prop_table: HashMap<usize, ColTable>,
-    pub oe_edge_prop_table: HashMap<usize, ColTable>,
+    pub vertex_prop_table: Vec<Arc<ColTable>>,
+    pub ie_edge_prop_table: HashMap<usize, Arc<ColTable>>,
+    pub oe_edge_prop_table: HashMap<usize, Arc<ColTable>>,
 
     pub vertex_label_num: usize,
     pub edge_label_num: usize,
@@ -265,16 +274,16 @@ where
             LocalVertex::with_property(
                 index,
                 label_id,
-                &self.vertex_map.index_to_global_id[label_id as usize],
-                &self.vertex_map.index_to_corner_global_id[label_id as usize],
-                Some(&self.vertex_prop_table[label_id as usize]),
+                self.vertex_map.native_global_ids(label_id),
+                self.vertex_map.corner_global_ids(label_id),
+                Some(self.vertex_prop_table[label_id as usize].as_ref()),
             )
         } else {
             LocalVertex::new(
                 index,
                 label_id,
-                &self.vertex_map.index_to_global_id[label_id as usize],
-                &self.vertex_map.index_to_corner_global_id[label_id as usize],
+                self.vertex_map.native_global_ids(label_id),
+                self.vertex_map.corner_global_ids(label_id),
             )
         }
     }
@@ -313,16 +322,70 @@ where
         }
     }
 
+    pub fn get_vertex(&self, id: G) -> Option<LocalVertex<G, I>> {
+        if let Some((label, index)) = self.vertex_map.get_internal_id(id) {
+            Some(self.index_to_local_vertex(label, index, true))
+        } else {
+            None
+        }
+    }
+
+    fn partitioned_range(&self, label: LabelId, worker_id: u32, worker_num: u32) -> Range<I> {
+        let local_id = (worker_id % worker_num) as usize;
+        let vertex_count = self.get_vertices_num(label);
+        let partial_count = (vertex_count + worker_num as usize - 1) / worker_num as usize;
+        let start_index = std::cmp::min(local_id * partial_count, vertex_count);
+        let end_index = std::cmp::min((local_id + 1) * partial_count, vertex_count);
+        Range::new(I::new(start_index), I::new(end_index))
+    }
+
+    /// Get the vertices of the given labels that are assigned to the `worker_id`-th of `worker_num`
+    /// workers, where each label is split into contiguous ranges of internal ids.
+    pub fn get_partitioned_vertices(
+        &self, labels: Option<&Vec<LabelId>>, worker_id: u32, worker_num: u32,
+    ) -> Iter<LocalVertex<G, I>> {
+        let got_labels: Vec<LabelId> = if let Some(labels) = labels {
+            labels.clone()
+        } else {
+            (0..self.vertex_label_num)
+                .map(|v| v as LabelId)
+                .collect()
+        };
+        let iters = got_labels
+            .iter()
+            .map(|label| {
+                self.partitioned_range(*label, worker_id, worker_num)
+                    .into_iter()
+            })
+            .collect();
+        Iter::from_iter(
+            LabeledIterator::new(got_labels, iters)
+                .map(move |(label, index)| self.index_to_local_vertex(label, index, true))
+                .filter(|v| v.is_valid()),
+        )
+    }
+
+    /// Load the given partition of the graph stored in `dir`.
+    ///
+    /// If the partition has a write-ahead log, an interrupted checkpoint is completed first, and
+    /// the batches logged after the last checkpoint are replayed on top of the loaded graph, so that
+    /// the graph is recovered to the state after the last durable batch.
     pub fn deserialize(dir: &str, partition: usize, trim_json_path: Option<String>) -> GDBResult<Self> {
         let root_dir = PathBuf::from_str(dir).unwrap();
-        let schema_path = root_dir
-            .join(DIR_GRAPH_SCHEMA)
-            .join(FILE_SCHEMA);
-        let graph_schema = CsrGraphSchema::from_json_file(schema_path)?;
-        // graph_schema.desc();
+        wal::recover_checkpoint(&root_dir, partition)?;
         let partition_dir = root_dir
             .join(DIR_BINARY_DATA)
             .join(format!("partition_{}", partition));
+        // a checkpoint keeps its own copy of the schema, as the batches may add index properties
+        let schema_path = if partition_dir.join(FILE_SCHEMA).exists() {
+            partition_dir.join(FILE_SCHEMA)
+        } else {
+            root_dir
+                .join(DIR_GRAPH_SCHEMA)
+                .join(FILE_SCHEMA)
+        };
+        let graph_schema = CsrGraphSchema::from_json_file(schema_path)?;
+        // graph_schema.desc();
 
         let (ie_enable, oe_enable) = if let Some(trim_json_path) = &trim_json_path {
             let edge_trim_path = PathBuf::from_str(trim_json_path).unwrap();
@@ -338,11 +401,11 @@ where
         let edge_label_num = graph_schema.edge_type_to_id.len();
 
         let csr_num = vertex_label_num * vertex_label_num * edge_label_num;
-        let mut ie: Vec<Box<dyn CsrTrait<I>>> = vec![];
-        let mut oe: Vec<Box<dyn CsrTrait<I>>> = vec![];
+        let mut ie: Vec<Arc<dyn CsrTrait<I>>> = vec![];
+        let mut oe: Vec<Arc<dyn CsrTrait<I>>> = vec![];
         for _ in 0..csr_num {
-            ie.push(Box::new(BatchMutableSingleCsr::<I>::new()));
-            oe.push(Box::new(BatchMutableSingleCsr::<I>::new()));
+            ie.push(Arc::new(BatchMutableSingleCsr::<I>::new()));
+            oe.push(Arc::new(BatchMutableSingleCsr::<I>::new()));
         }
 
         let mut csr_tasks = vec![];
@@ -425,13 +488,13 @@ where
             let index: usize =
                 src_label_i * vertex_label_num * edge_label_num + dst_label_i * edge_label_num + e_label_i;
             if dir == Direction::Outgoing {
-                oe[index] = csr;
+                oe[index] = Arc::from(csr);
             } else {
-                ie[index] = csr;
+                ie[index] = Arc::from(csr);
             }
         }
 
-        let vertex_prop_table: Vec<ColTable> = (0..vertex_label_num)
+        let vertex_prop_table: Vec<Arc<ColTable>> = (0..vertex_label_num)
             .into_par_iter()
             .map(|v_label| {
                 let v_label_name = graph_schema.vertex_label_names()[v_label].clone();
@@ -439,7 +502,7 @@ where
                 let table_path = &partition_dir.join(format!("vp_{}", v_label_name));
                 let table_path_str = table_path.to_str().unwrap().to_string();
                 table.deserialize_table(&table_path_str);
-                table
+                Arc::new(table)
             })
             .collect();
 
@@ -468,7 +531,7 @@ where
                             src_label_name, edge_label_name, dst_label_name, oe_edge_property_path_str
                         );
                         table.deserialize_table(&oe_edge_property_path_str);
-                        oe_edge_prop_table.insert(edge_index, table);
+                        oe_edge_prop_table.insert(edge_index, Arc::new(table));
                     }
 
                     let ie_edge_property_path = &partition_dir
@@ -484,7 +547,7 @@ where
                             src_label_name, edge_label_name, dst_label_name, oe_edge_property_path_str
                         );
                         table.deserialize_table(&ie_edge_property_path_str);
-                        ie_edge_prop_table.insert(edge_index, table);
+                        ie_edge_prop_table.insert(edge_index, Arc::new(table));
                     }
                 }
             }
@@ -495,20 +558,172 @@ where
         let vm_path_str = vm_path.to_str().unwrap().to_string();
         vertex_map.deserialize(&vm_path_str);
 
-        Ok(Self {
+        let mut graph = Self {
             partition,
             ie,
             oe,
-            graph_schema: graph_schema,
+            graph_schema: Arc::new(graph_schema),
             vertex_prop_table,
-            vertex_map,
+            vertex_map: Arc::new(vertex_map),
             ie_edge_prop_table,
             oe_edge_prop_table,
             vertex_label_num,
             edge_label_num,
+        };
+        // the write operations can only be applied to a graph of the default id types, a log that
+        // can't be replayed fails the load instead of silently losing its batches
+        if let Some(graph) = (&mut graph as &mut dyn Any).downcast_mut::<GraphDB<usize, usize>>() {
+            let replayed = wal::replay(graph, &root_dir, partition)?;
+            if replayed > 0 {
+                info!("replayed {} batches from the write-ahead log of partition {}", replayed, partition);
+            }
+        } else if wal::has_pending_records(&root_dir, partition)? {
+            error!(
+                "the write-ahead log of partition {} can't be replayed onto a graph of id types {} and {}",
+                partition,
+                std::any::type_name::<G>(),
+                std::any::type_name::<I>()
+            );
+            return Err(GDBError::InvalidTypeError);
+        }
+
+        Ok(graph)
+    }
+
+    /// Write the graph into `partition_dir` in the layout expected by `deserialize`, together with a
+    /// copy of the graph schema. Csrs that are never loaded or built are skipped.
+    pub fn serialize(&self, partition_dir: &Path) -> GDBResult<()> {
+        create_dir_all(partition_dir)?;
+        self.graph_schema
+            .to_json_file(partition_dir.join(FILE_SCHEMA))?;
+
+        let vertex_label_names = self.graph_schema.vertex_label_names();
+        let edge_label_names = self.graph_schema.edge_label_names();
+        for e_label_i in 0..self.edge_label_num {
+            for src_label_i in 0..self.vertex_label_num {
+                for dst_label_i in 0..self.vertex_label_num {
+                    let index = self.edge_label_to_index(
+                        src_label_i as LabelId,
+                        dst_label_i as LabelId,
+                        e_label_i as LabelId,
+                        Direction::Outgoing,
+                    );
+                    let name = format!(
+                        "{}_{}_{}",
+                        vertex_label_names[src_label_i],
+                        edge_label_names[e_label_i],
+                        vertex_label_names[dst_label_i]
+                    );
+                    let single_oe = self.graph_schema.is_single_oe(
+                        src_label_i as LabelId,
+                        e_label_i as LabelId,
+                        dst_label_i as LabelId,
+                    );
+                    let single_ie = self.graph_schema.is_single_ie(
+                        src_label_i as LabelId,
+                        e_label_i as LabelId,
+                        dst_label_i as LabelId,
+                    );
+                    if is_built_csr(&self.oe[index], single_oe) {
+                        let oe_path = partition_dir.join(format!("oe_{}", name));
+                        self.oe[index].serialize(&oe_path.to_str().unwrap().to_string());
+                    }
+                    if is_built_csr(&self.ie[index], single_ie) {
+                        let ie_path = partition_dir.join(format!("ie_{}", name));
+                        self.ie[index].serialize(&ie_path.to_str().unwrap().to_string());
+                    }
+                    if let Some(table) = self.oe_edge_prop_table.get(&index) {
+                        let table_path = partition_dir.join(format!("oep_{}", name));
+                        table.serialize_table(&table_path.to_str().unwrap().to_string());
+                    }
+                    if let Some(table) = self.ie_edge_prop_table.get(&index) {
+                        let table_path = partition_dir.join(format!("iep_{}", name));
+                        table.serialize_table(&table_path.to_str().unwrap().to_string());
+                    }
+                }
+            }
+        }
+
+        for (v_label_i, table) in self.vertex_prop_table.iter().enumerate() {
+            let table_path = partition_dir.join(format!("vp_{}", vertex_label_names[v_label_i]));
+            table.serialize_table(&table_path.to_str().unwrap().to_string());
+        }
+
+        let vm_path = partition_dir.join("vm");
+        self.vertex_map
+            .serialize(&vm_path.to_str().unwrap().to_string());
+
+        Ok(())
+    }
+
+    /// Take a read-only snapshot of the current version of the graph. The snapshot shares the csrs and
+    /// tables with the graph, and is not affected by the batches applied to the graph afterwards, as
+    /// any shared part is copied before being modified.
+    pub fn snapshot(&self) -> Arc<GraphDB<G, I>> {
+        Arc::new(GraphDB {
+            partition: self.partition,
+            ie: self.ie.clone(),
+            oe: self.oe.clone(),
+            graph_schema: self.graph_schema.clone(),
+            vertex_map: self.vertex_map.clone(),
+            vertex_prop_table: self.vertex_prop_table.clone(),
+            ie_edge_prop_table: self.ie_edge_prop_table.clone(),
+            oe_edge_prop_table: self.oe_edge_prop_table.clone(),
+            vertex_label_num: self.vertex_label_num,
+            edge_label_num: self.edge_label_num,
         })
     }
 
+    /// Take the incoming csr at `index` out for modification, leaving an empty csr in place.
+    /// The csr is copied if it is shared with any snapshot.
+    pub fn take_ie_csr(&mut self, index: usize) -> Box<dyn CsrTrait<I>> {
+        take_csr(&mut self.ie[index])
+    }
+
+    /// Take the outgoing csr at `index` out for modification, leaving an empty csr in place.
+    /// The csr is copied if it is shared with any snapshot.
+    pub fn take_oe_csr(&mut self, index: usize) -> Box<dyn CsrTrait<I>> {
+        take_csr(&mut self.oe[index])
+    }
+
+    pub fn set_ie_csr(&mut self, index: usize, csr: Box<dyn CsrTrait<I>>) {
+        self.ie[index] = Arc::from(csr);
+    }
+
+    pub fn set_oe_csr(&mut self, index: usize, csr: Box<dyn CsrTrait<I>>) {
+        self.oe[index] = Arc::from(csr);
+    }
+
+    /// Take the property table of the incoming edges at `index` out for modification, which is copied
+    /// if it is shared with any snapshot.
+    pub fn take_ie_edge_prop_table(&mut self, index: usize) -> Option<ColTable> {
+        self.ie_edge_prop_table
+            .remove(&index)
+            .map(|table| Arc::try_unwrap(table).unwrap_or_else(|table| table.as_ref().clone()))
+    }
+
+    /// Take the property table of the outgoing edges at `index` out for modification, which is copied
+    /// if it is shared with any snapshot.
+    pub fn take_oe_edge_prop_table(&mut self, index: usize) -> Option<ColTable> {
+        self.oe_edge_prop_table
+            .remove(&index)
+            .map(|table| Arc::try_unwrap(table).unwrap_or_else(|table| table.as_ref().clone()))
+    }
+
+    pub fn set_ie_edge_prop_table(&mut self, index: usize, table: ColTable) {
+        self.ie_edge_prop_table
+            .insert(index, Arc::new(table));
+    }
+
+    pub fn set_oe_edge_prop_table(&mut self, index: usize, table: ColTable) {
+        self.oe_edge_prop_table
+            .insert(index, Arc::new(table));
+    }
+
+    pub fn remove_vertex(&mut self, label: LabelId, internal_id: &I) {
+        Arc::make_mut(&mut self.vertex_map).remove_vertex(label, internal_id);
+    }
+
     pub fn get_sub_graph(
         &self, src_label: LabelId, edge_label: LabelId, dst_label: LabelId, dir: Direction,
     ) -> SubGraph<'_, G, I> {
@@ -531,7 +746,9 @@ where
                 dst_label,
                 edge_label,
                 &self.vertex_prop_table[src_label as usize],
-                self.oe_edge_prop_table.get(&index),
+                self.oe_edge_prop_table
+                    .get(&index)
+                    .map(|table| table.as_ref()),
             ),
             Direction::Incoming => SubGraph::new(
                 &self.ie[index]
@@ -543,7 +760,9 @@ where
                 dst_label,
                 edge_label,
                 &self.vertex_prop_table[src_label as usize],
-                self.ie_edge_prop_table.get(&index),
+                self.ie_edge_prop_table
+                    .get(&index)
+                    .map(|table| table.as_ref()),
             ),
         }
     }
@@ -570,7 +789,9 @@ where
                 dst_label,
                 edge_label,
                 &self.vertex_prop_table[src_label as usize],
-                self.oe_edge_prop_table.get(&index),
+                self.oe_edge_prop_table
+                    .get(&index)
+                    .map(|table| table.as_ref()),
             ),
             Direction::Incoming => SingleSubGraph::new(
                 &self.ie[index]
@@ -582,29 +803,33 @@ where
                 dst_label,
                 edge_label,
                 &self.vertex_prop_table[src_label as usize],
-                self.ie_edge_prop_table.get(&index),
+                self.ie_edge_prop_table
+                    .get(&index)
+                    .map(|table| table.as_ref()),
             ),
         }
     }
 
     pub fn insert_vertex(&mut self, label: LabelId, id: G, properties: Option<Vec<Item>>) {
-        let lid = self.vertex_map.add_vertex(id, label);
+        let lid = Arc::make_mut(&mut self.vertex_map).add_vertex(id, label);
         if let Some(properties) = properties {
-            self.vertex_prop_table[label as usize].insert(lid.index(), &properties);
+            Arc::make_mut(&mut self.vertex_prop_table[label as usize]).insert(lid.index(), &properties);
         }
     }
 
     pub fn init_vertex_index_prop(
         &mut self, index_name: String, vertex_label: LabelId, data_type: DataType,
     ) {
-        if let Some(prop_index) =
-            self.graph_schema
-                .add_vertex_index_prop(index_name.clone(), vertex_label, data_type)
-        {
-            if let Some(mut col_table) = self
+        if let Some(prop_index) = Arc::make_mut(&mut self.graph_schema).add_vertex_index_prop(
+            index_name.clone(),
+            vertex_label,
+            data_type,
+        ) {
+            if let Some(col_table) = self
                 .vertex_prop_table
                 .get_mut(vertex_label as usize)
             {
+                let col_table = Arc::make_mut(col_table);
                 if !col_table.header.contains_key(&index_name) {
                     col_table.add_property(index_name, data_type);
                 }
@@ -615,11 +840,11 @@ where
     pub fn set_vertex_index_prop(
         &mut self, index_name: String, vertex_label: LabelId, index: &Vec<usize>, data: Box<dyn Column>,
     ) {
-        if let Some(mut col_table) = self
+        if let Some(col_table) = self
             .vertex_prop_table
             .get_mut(vertex_label as usize)
         {
-            col_table.set_property(index_name, index, data);
+            Arc::make_mut(col_table).set_property(index_name, index, data);
         }
     }
 
@@ -627,7 +852,7 @@ where
         &mut self, index_name: String, src_label: LabelId, edge_label: LabelId, dst_label: LabelId,
         data_type: DataType,
     ) {
-        if let Some(prop_index) = self.graph_schema.add_edge_index_prop(
+        if let Some(prop_index) = Arc::make_mut(&mut self.graph_schema).add_edge_index_prop(
             index_name.clone(),
             src_label,
             edge_label,
@@ -638,10 +863,10 @@ where
                 + dst_label as usize * self.edge_label_num
                 + edge_label as usize;
             if let Some(in_col_table) = self.ie_edge_prop_table.get_mut(&edge_index) {
-                in_col_table.add_property(index_name.clone(), data_type);
+                Arc::make_mut(in_col_table).add_property(index_name.clone(), data_type);
             }
             if let Some(out_col_table) = self.oe_edge_prop_table.get_mut(&edge_index) {
-                out_col_table.add_property(index_name, data_type);
+                Arc::make_mut(out_col_table).add_property(index_name, data_type);
             }
         }
     }
@@ -655,14 +880,82 @@ where
             + dst_label as usize * self.edge_label_num
             + edge_label as usize;
         if in_index.is_some() {
-            if let Some(mut in_col_table) = self.ie_edge_prop_table.get_mut(&edge_index) {
-                in_col_table.set_property(index_name.clone(), in_index.unwrap(), in_data.unwrap());
+            if let Some(in_col_table) = self.ie_edge_prop_table.get_mut(&edge_index) {
+                Arc::make_mut(in_col_table).set_property(
+                    index_name.clone(),
+                    in_index.unwrap(),
+                    in_data.unwrap(),
+                );
             }
         }
         if out_index.is_some() {
-            if let Some(mut out_col_table) = self.oe_edge_prop_table.get_mut(&edge_index) {
-                out_col_table.set_property(index_name, out_index.unwrap(), out_data.unwrap());
+            if let Some(out_col_table) = self.oe_edge_prop_table.get_mut(&edge_index) {
+                Arc::make_mut(out_col_table).set_property(
+                    index_name,
+                    out_index.unwrap(),
+                    out_data.unwrap(),
+                );
             }
         }
     }
 }
+
+/// Whether the csr holds data loaded or built for the edge label, rather than the empty placeholder
+/// that `deserialize` leaves for the edge labels without a csr file.
+fn is_built_csr<I: IndexType>(csr: &Arc<dyn CsrTrait<I>>, is_single: bool) -> bool {
+    let csr_is_single = csr.as_any().is::<BatchMutableSingleCsr<I>>();
+    csr_is_single == is_single && (csr.edge_num() > 0 || csr.vertex_num().index() > 0)
+}
+
+fn take_csr<I: IndexType>(csr: &mut Arc<dyn CsrTrait<I>>) -> Box<dyn CsrTrait<I>> {
+    match Arc::get_mut(csr) {
+        Some(csr) => csr.take_box(),
+        None => {
+            let copied = csr.clone_box();
+            *csr = Arc::new(BatchMutableSingleCsr::<I>::new());
+            copied
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph_modifier::apply_write_operations;
+    use crate::schema::Schema;
+    use crate::test_utils::*;
+
+    #[test]
+    fn test_snapshot_isolated_from_batch() {
+        let root = tempdir::TempDir::new("bmcsr_snapshot").unwrap();
+        build_modern_graph(root.path());
+        let mut graph = load(root.path());
+        let before = summary(&graph);
+
+        let snapshot = graph.snapshot();
+        apply_write_operations(&mut graph, batch(), 1);
+        assert_eq!(summary(&snapshot), before);
+        assert_ne!(summary(&graph), before);
+        assert_eq!(summary(&graph.snapshot()), summary(&graph));
+    }
+
+    #[test]
+    fn test_write_copies_only_modified_labels() {
+        let root = tempdir::TempDir::new("bmcsr_snapshot_labels").unwrap();
+        build_modern_graph(root.path());
+        let mut graph = load(root.path());
+        let software = graph
+            .graph_schema
+            .get_vertex_label_id("software")
+            .unwrap();
+
+        let snapshot = graph.snapshot();
+        apply_write_operations(&mut graph, vec![insert_persons(vec![7, 8])], 1);
+        assert!(!graph
+            .vertex_map
+            .is_label_shared(&snapshot.vertex_map, PERSON));
+        assert!(graph
+            .vertex_map
+            .is_label_shared(&snapshot.vertex_map, software));
+        assert_eq!(snapshot.vertex_map.vertex_num(PERSON) + 2, graph.vertex_map.vertex_num(PERSON));
+    }
+}
@@ -17,18 +17,15 @@ use std::any::Any;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::fs::File;
-use std::io::{BufReader, Write};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Instant;
 
-use csv::ReaderBuilder;
 use pegasus_common::codec::{Decode, Encode};
 use pegasus_common::io::{ReadExt, WriteExt};
 use rayon::prelude::*;
-use rust_htslib::bgzf::Reader as GzReader;
 
-use crate::bmscsr::BatchMutableSingleCsr;
 use crate::col_table::{parse_properties, parse_properties_by_mappings, ColTable};
 use crate::columns::*;
 use crate::columns::*;
@@ -39,9 +36,11 @@ use crate::error::GDBResult;
 use crate::graph::{Direction, IndexType};
 use crate::graph_db::GraphDB;
 use crate::graph_loader::{get_files_list, get_files_list_beta};
+use crate::input_reader::{create_input_reader, FileFormat};
 use crate::ldbc_parser::{LDBCEdgeParser, LDBCVertexParser};
 use crate::schema::{CsrGraphSchema, InputSchema, Schema};
 use crate::types::{DefaultId, LabelId};
+use crate::wal::WriteAheadLog;
 
 #[derive(Clone, Copy)]
 pub enum WriteType {
@@ -129,6 +128,8 @@ pub struct FileInput {
     pub escape_char: String,
     pub block_size: String,
     pub location: String,
+    /// The format of the file, which is inferred from the file name if not specified
+    pub format: Option<FileFormat>,
 }
 
 impl Encode for FileInput {
@@ -141,6 +142,7 @@ impl Encode for FileInput {
         self.escape_char.write_to(writer)?;
         self.block_size.write_to(writer)?;
         self.location.write_to(writer)?;
+        self.format.write_to(writer)?;
         Ok(())
     }
 }
@@ -155,6 +157,7 @@ impl Decode for FileInput {
         let escape_char = String::read_from(reader)?;
         let block_size = String::read_from(reader)?;
         let location = String::read_from(reader)?;
+        let format = Option::<FileFormat>::read_from(reader)?;
         Ok(FileInput {
             delimiter,
             header_row,
@@ -164,6 +167,7 @@ impl Decode for FileInput {
             escape_char,
             block_size,
             location,
+            format,
         })
     }
 }
@@ -179,6 +183,7 @@ impl FileInput {
             escape_char: "".to_string(),
             block_size: "4Mb".to_string(),
             location,
+            format: None,
         }
     }
 }
@@ -356,32 +361,6 @@ fn read_column<R: ReadExt>(reader: &mut R) -> std::io::Result<Box<dyn Column>> {
     Ok(data)
 }
 
-fn clone_column(input: &Box<dyn Column>) -> Box<dyn Column> {
-    if let Some(int32_column) = input.as_any().downcast_ref::<Int32Column>() {
-        Box::new(Int32Column::clone_from(int32_column))
-    } else if let Some(uint32_column) = input.as_any().downcast_ref::<UInt32Column>() {
-        Box::new(UInt32Column::clone_from(uint32_column))
-    } else if let Some(int64_column) = input.as_any().downcast_ref::<Int64Column>() {
-        Box::new(Int64Column::clone_from(int64_column))
-    } else if let Some(uint64_column) = input.as_any().downcast_ref::<UInt64Column>() {
-        Box::new(UInt64Column::clone_from(uint64_column))
-    } else if let Some(id_column) = input.as_any().downcast_ref::<IDColumn>() {
-        Box::new(IDColumn::clone_from(id_column))
-    } else if let Some(doule_column) = input.as_any().downcast_ref::<DoubleColumn>() {
-        Box::new(DoubleColumn::clone_from(doule_column))
-    } else if let Some(string_column) = input.as_any().downcast_ref::<StringColumn>() {
-        Box::new(StringColumn::clone_from(string_column))
-    } else if let Some(lc_string_column) = input.as_any().downcast_ref::<LCStringColumn>() {
-        Box::new(LCStringColumn::clone_from(lc_string_column))
-    } else if let Some(date_column) = input.as_any().downcast_ref::<DateColumn>() {
-        Box::new(DateColumn::clone_from(date_column))
-    } else if let Some(datetime_column) = input.as_any().downcast_ref::<DateTimeColumn>() {
-        Box::new(DateTimeColumn::clone_from(datetime_column))
-    } else {
-        panic!("Unknown column type")
-    }
-}
-
 pub struct ColumnMetadata {
     data: Box<dyn Column>,
     column_name: String,
@@ -455,6 +434,10 @@ impl Decode for DataFrame {
 }
 
 impl DataFrame {
+    pub fn new(columns: Vec<ColumnMetadata>) -> Self {
+        DataFrame { columns }
+    }
+
     pub fn new_vertices_ids(data: Vec<u64>) -> Self {
         let columns =
             vec![ColumnMetadata::new(Box::new(UInt64Column { data }), "id".to_string(), DataType::ID)];
@@ -990,6 +973,20 @@ pub fn apply_write_operations(
     }
 }
 
+/// Apply the write operations as `apply_write_operations`, after logging them durably in `wal`,
+/// and checkpoint the graph if enough batches are logged since the last checkpoint.
+pub fn apply_write_operations_with_wal(
+    graph: &mut GraphDB<usize, usize>, wal: &mut WriteAheadLog, write_operations: Vec<WriteOperation>,
+    parallel: u32,
+) -> GDBResult<()> {
+    wal.append(&write_operations)?;
+    apply_write_operations(graph, write_operations, parallel);
+    if wal.should_checkpoint() {
+        wal.checkpoint(graph)?;
+    }
+    Ok(())
+}
+
 fn insert_vertices<G, I>(
     graph: &mut GraphDB<G, I>, vertex_label: LabelId, input: &Input, column_mappings: &Vec<ColumnMappings>,
     parallel: u32,
@@ -1035,6 +1032,9 @@ fn insert_vertices<G, I>(
                 if file_input.header_row {
                     modifier.skip_header();
                 }
+                if let Some(format) = file_input.format {
+                    modifier.file_format(format);
+                }
                 modifier.parallel(parallel);
                 let mut mappings = vec![-1; max_col as usize];
                 if let Some(vertex_header) = graph
@@ -1056,7 +1056,17 @@ fn insert_vertices<G, I>(
         }
         DataSource::Memory => {
             if let Some(memory_data) = input.memory_data() {
-                todo!()
+                let data = memory_data.columns();
+                let global_ids = memory_id_column::<G>(data, id_col);
+                let header = graph
+                    .graph_schema
+                    .get_vertex_header(vertex_label)
+                    .expect(&format!("vertex label {} not found", vertex_label))
+                    .to_vec();
+                for (row, global_id) in global_ids.into_iter().enumerate() {
+                    let properties = memory_row_by_header(data, &header, &column_map, row);
+                    graph.insert_vertex(vertex_label, global_id, Some(properties));
+                }
             }
         }
     }
@@ -1136,6 +1146,9 @@ pub fn insert_edges<G, I>(
                 if file_input.header_row {
                     modifier.skip_header();
                 }
+                if let Some(format) = file_input.format {
+                    modifier.file_format(format);
+                }
                 modifier.parallel(parallel);
                 let mut mappings = vec![-1; max_col as usize];
                 if let Some(edge_header) = graph
@@ -1160,12 +1173,95 @@ pub fn insert_edges<G, I>(
         }
         DataSource::Memory => {
             if let Some(memory_data) = input.memory_data() {
-                todo!()
+                let data = memory_data.columns();
+                let src_ids = memory_id_column::<G>(data, src_id_col);
+                let dst_ids = memory_id_column::<G>(data, dst_id_col);
+                let header = graph
+                    .graph_schema
+                    .get_edge_header(src_label, edge_label, dst_label)
+                    .expect(&format!("edge label {}_{}_{} not found", src_label, edge_label, dst_label))
+                    .to_vec();
+                let mut prop_table = ColTable::new(
+                    header
+                        .iter()
+                        .map(|(name, data_type)| (data_type.clone(), name.clone()))
+                        .collect(),
+                );
+                if !header.is_empty() {
+                    for row in 0..src_ids.len() {
+                        prop_table.push(&memory_row_by_header(data, &header, &column_map, row));
+                    }
+                }
+                let edges = src_ids
+                    .into_iter()
+                    .zip(dst_ids.into_iter())
+                    .collect();
+                let mut modifier = GraphModifier::new("");
+                modifier.parallel(parallel);
+                modifier
+                    .apply_edges_insert_with_data(
+                        graph,
+                        src_label,
+                        edge_label,
+                        dst_label,
+                        &edges,
+                        &prop_table,
+                    )
+                    .unwrap();
             }
         }
     }
 }
 
+/// Read the global ids kept in the `col_index`-th column of an in-memory input.
+fn memory_id_column<G: IndexType>(data: &Vec<ColumnMetadata>, col_index: i32) -> Vec<G> {
+    let id_column = data
+        .get(col_index as usize)
+        .expect("Failed to get id column");
+    if let Some(uint64_column) = id_column
+        .data()
+        .as_any()
+        .downcast_ref::<UInt64Column>()
+    {
+        uint64_column
+            .data
+            .iter()
+            .map(|x| G::new(*x as usize))
+            .collect()
+    } else if let Some(id_column) = id_column
+        .data()
+        .as_any()
+        .downcast_ref::<IDColumn>()
+    {
+        id_column
+            .data
+            .iter()
+            .map(|x| G::new(*x))
+            .collect()
+    } else {
+        panic!("DataType of id col is not VertexId")
+    }
+}
+
+/// Assemble the `row`-th row of an in-memory input in the order of the graph `header`,
+/// properties without a column mapping are filled with `Item::Null`.
+fn memory_row_by_header(
+    data: &Vec<ColumnMetadata>, header: &[(String, DataType)],
+    column_map: &HashMap<String, (i32, DataType)>, row: usize,
+) -> Vec<Item> {
+    header
+        .iter()
+        .map(|(property_name, _)| {
+            column_map
+                .get(property_name)
+                .and_then(|(column_index, _)| data.get(*column_index as usize))
+                .and_then(|column| column.data().get(row))
+                .map(|item| item.to_owned())
+                .unwrap_or(Item::Null)
+        })
+        .collect()
+}
+
 pub fn delete_vertices(
     graph: &mut GraphDB<usize, usize>, vertex_label: LabelId, input: &Input,
     column_mappings: &Vec<ColumnMappings>, parallel: u32,
@@ -1204,6 +1300,9 @@ pub fn delete_vertices(
                 if file_input.header_row {
                     modifier.skip_header();
                 }
+                if let Some(format) = file_input.format {
+                    modifier.file_format(format);
+                }
                 modifier.parallel(parallel);
                 modifier
                     .apply_vertices_delete_with_filename(graph, vertex_label, &filenames, id_col)
@@ -1294,6 +1393,9 @@ pub fn delete_edges(
                 if file_input.header_row {
                     modifier.skip_header();
                 }
+                if let Some(format) = file_input.format {
+                    modifier.file_format(format);
+                }
                 modifier.parallel(parallel);
 
                 modifier
@@ -1305,7 +1407,18 @@ pub fn delete_edges(
         }
         DataSource::Memory => {
             if let Some(memory_data) = input.memory_data() {
-                todo!()
+                let data = memory_data.columns();
+                let src_ids = memory_id_column::<usize>(data, src_id_col);
+                let dst_ids = memory_id_column::<usize>(data, dst_id_col);
+                let edges = src_ids
+                    .into_iter()
+                    .zip(dst_ids.into_iter())
+                    .collect();
+                let mut modifier = GraphModifier::new("");
+                modifier.parallel(parallel);
+                modifier
+                    .apply_edges_delete_with_ids(graph, src_label, edge_label, dst_label, &edges)
+                    .unwrap();
             }
         }
     }
@@ -1343,12 +1456,10 @@ pub fn delete_vertices_by_ids<G, I>(
                 e_label_i as LabelId,
                 Direction::Outgoing,
             );
-            let mut ie_csr =
-                std::mem::replace(&mut graph.ie[index], Box::new(BatchMutableSingleCsr::new()));
-            let mut ie_prop = graph.ie_edge_prop_table.remove(&index);
-            let mut oe_csr =
-                std::mem::replace(&mut graph.oe[index], Box::new(BatchMutableSingleCsr::new()));
-            let mut oe_prop = graph.oe_edge_prop_table.remove(&index);
+            let mut ie_csr = graph.take_ie_csr(index);
+            let mut ie_prop = graph.take_ie_edge_prop_table(index);
+            let mut oe_csr = graph.take_oe_csr(index);
+            let mut oe_prop = graph.take_oe_edge_prop_table(index);
             let mut ie_to_delete = Vec::new();
             for v in lids.iter() {
                 if let Some(ie_list) = ie_csr.get_edges(*v) {
@@ -1363,13 +1474,13 @@ pub fn delete_vertices_by_ids<G, I>(
             } else {
                 oe_csr.parallel_delete_edges(&ie_to_delete, false, parallel);
             }
-            graph.ie[index] = ie_csr;
+            graph.set_ie_csr(index, ie_csr);
             if let Some(table) = ie_prop {
-                graph.ie_edge_prop_table.insert(index, table);
+                graph.set_ie_edge_prop_table(index, table);
             }
-            graph.oe[index] = oe_csr;
+            graph.set_oe_csr(index, oe_csr);
             if let Some(table) = oe_prop {
-                graph.oe_edge_prop_table.insert(index, table);
+                graph.set_oe_edge_prop_table(index, table);
             }
         }
         for dst_label_i in 0..vertex_label_num {
@@ -1386,12 +1497,10 @@ pub fn delete_vertices_by_ids<G, I>(
                 e_label_i as LabelId,
                 Direction::Outgoing,
             );
-            let mut ie_csr =
-                std::mem::replace(&mut graph.ie[index], Box::new(BatchMutableSingleCsr::new()));
-            let mut ie_prop = graph.ie_edge_prop_table.remove(&index);
-            let mut oe_csr =
-                std::mem::replace(&mut graph.oe[index], Box::new(BatchMutableSingleCsr::new()));
-            let mut oe_prop = graph.oe_edge_prop_table.remove(&index);
+            let mut ie_csr = graph.take_ie_csr(index);
+            let mut ie_prop = graph.take_ie_edge_prop_table(index);
+            let mut oe_csr = graph.take_oe_csr(index);
+            let mut oe_prop = graph.take_oe_edge_prop_table(index);
             let mut oe_to_delete = Vec::new();
             for v in lids.iter() {
                 if let Some(oe_list) = oe_csr.get_edges(*v) {
@@ -1406,20 +1515,20 @@ pub fn delete_vertices_by_ids<G, I>(
             } else {
                 ie_csr.parallel_delete_edges(&oe_to_delete, true, parallel);
             }
-            graph.ie[index] = ie_csr;
+            graph.set_ie_csr(index, ie_csr);
             if let Some(table) = ie_prop {
-                graph.ie_edge_prop_table.insert(index, table);
+                graph.set_ie_edge_prop_table(index, table);
             }
-            graph.oe[index] = oe_csr;
+            graph.set_oe_csr(index, oe_csr);
             if let Some(table) = oe_prop {
-                graph.oe_edge_prop_table.insert(index, table);
+                graph.set_oe_edge_prop_table(index, table);
             }
         }
     }
 
     // delete vertices
     for v in lids.iter() {
-        graph.vertex_map.remove_vertex(vertex_label, v);
+        graph.remove_vertex(vertex_label, v);
     }
 }
 
@@ -1581,42 +1690,21 @@ pub fn set_edges(
     }
 }
 
-fn process_csv_rows<F>(path: &PathBuf, mut process_row: F, skip_header: bool, delim: u8)
-where
+fn process_rows<F>(
+    path: &PathBuf, mut process_row: F, skip_header: bool, delim: u8, format: Option<FileFormat>,
+) where
     F: FnMut(&csv::StringRecord),
 {
-    if let Some(path_str) = path.clone().to_str() {
-        if path_str.ends_with(".csv.gz") {
-            if let Ok(gz_reader) = GzReader::from_path(&path) {
-                let mut rdr = ReaderBuilder::new()
-                    .delimiter(delim)
-                    .buffer_capacity(4096)
-                    .comment(Some(b'#'))
-                    .flexible(true)
-                    .has_headers(skip_header)
-                    .from_reader(gz_reader);
-                for result in rdr.records() {
-                    if let Ok(record) = result {
-                        process_row(&record);
-                    }
-                }
-            }
-        } else if path_str.ends_with(".csv") {
-            if let Ok(file) = File::open(&path) {
-                let reader = BufReader::new(file);
-                let mut rdr = ReaderBuilder::new()
-                    .delimiter(delim)
-                    .buffer_capacity(4096)
-                    .comment(Some(b'#'))
-                    .flexible(true)
-                    .has_headers(skip_header)
-                    .from_reader(reader);
-                for result in rdr.records() {
+    if let Some(format) = format.or_else(|| FileFormat::from_path(path)) {
+        match create_input_reader(format, delim, skip_header, None).read_records(path) {
+            Ok(records) => {
+                for result in records {
                     if let Ok(record) = result {
                         process_row(&record);
                     }
                 }
             }
+            Err(e) => warn!("Read {:?} failed: {:?}", path, e),
         }
     }
 }
@@ -1677,7 +1765,7 @@ impl<G: FromStr + Send + Sync + IndexType + Eq + std::fmt::Display> DeleteGenera
         }
         let parser = LDBCVertexParser::<G>::new(label, 1);
         for file in files {
-            process_csv_rows(
+            process_rows(
                 &file,
                 |record| {
                     let vertex_meta = parser.parse_vertex_meta(&record);
@@ -1692,6 +1780,7 @@ impl<G: FromStr + Send + Sync + IndexType + Eq + std::fmt::Display> DeleteGenera
                 },
                 self.skip_header,
                 self.delim,
+                None,
             );
         }
 
@@ -2066,6 +2155,7 @@ pub struct GraphModifier {
     delim: u8,
     skip_header: bool,
     parallel: u32,
+    file_format: Option<FileFormat>,
 }
 
 struct CsrRep<I> {
@@ -2081,7 +2171,13 @@ struct CsrRep<I> {
 
 impl GraphModifier {
     pub fn new<D: AsRef<Path>>(input_dir: D) -> GraphModifier {
-        Self { input_dir: input_dir.as_ref().to_path_buf(), delim: b'|', skip_header: false, parallel: 0 }
+        Self {
+            input_dir: input_dir.as_ref().to_path_buf(),
+            delim: b'|',
+            skip_header: false,
+            parallel: 0,
+            file_format: None,
+        }
     }
 
     pub fn with_delimiter(mut self, delim: u8) -> Self {
@@ -2097,6 +2193,11 @@ impl GraphModifier {
         self.parallel = parallel;
     }
 
+    /// Read the input files in the given format, instead of inferring it from the file names.
+    pub fn file_format(&mut self, format: FileFormat) {
+        self.file_format = Some(format);
+    }
+
     fn take_csr<G, I>(
         &self, graph: &mut GraphDB<G, I>, src_label_i: LabelId, dst_label_i: LabelId, e_label_i: LabelId,
     ) -> CsrRep<I>
@@ -2111,10 +2212,10 @@ impl GraphModifier {
             edge_label: e_label_i,
             dst_label: dst_label_i,
 
-            ie_csr: std::mem::replace(&mut graph.ie[index], Box::new(BatchMutableSingleCsr::new())),
-            ie_prop: graph.ie_edge_prop_table.remove(&index),
-            oe_csr: std::mem::replace(&mut graph.oe[index], Box::new(BatchMutableSingleCsr::new())),
-            oe_prop: graph.oe_edge_prop_table.remove(&index),
+            ie_csr: graph.take_ie_csr(index),
+            ie_prop: graph.take_ie_edge_prop_table(index),
+            oe_csr: graph.take_oe_csr(index),
+            oe_prop: graph.take_oe_edge_prop_table(index),
         }
     }
 
@@ -2143,16 +2244,10 @@ impl GraphModifier {
                         src_label: label as LabelId,
                         edge_label: e_label_i as LabelId,
                         dst_label: label_i as LabelId,
-                        ie_csr: std::mem::replace(
-                            &mut graph.ie[index],
-                            Box::new(BatchMutableSingleCsr::new()),
-                        ),
-                        ie_prop: graph.ie_edge_prop_table.remove(&index),
-                        oe_csr: std::mem::replace(
-                            &mut graph.oe[index],
-                            Box::new(BatchMutableSingleCsr::new()),
-                        ),
-                        oe_prop: graph.oe_edge_prop_table.remove(&index),
+                        ie_csr: graph.take_ie_csr(index),
+                        ie_prop: graph.take_ie_edge_prop_table(index),
+                        oe_csr: graph.take_oe_csr(index),
+                        oe_prop: graph.take_oe_edge_prop_table(index),
                     });
                 }
                 if !graph
@@ -2171,16 +2266,10 @@ impl GraphModifier {
                             src_label: label_i as LabelId,
                             edge_label: e_label_i as LabelId,
                             dst_label: label as LabelId,
-                            ie_csr: std::mem::replace(
-                                &mut graph.ie[index],
-                                Box::new(BatchMutableSingleCsr::new()),
-                            ),
-                            ie_prop: graph.ie_edge_prop_table.remove(&index),
-                            oe_csr: std::mem::replace(
-                                &mut graph.oe[index],
-                                Box::new(BatchMutableSingleCsr::new()),
-                            ),
-                            oe_prop: graph.oe_edge_prop_table.remove(&index),
+                            ie_csr: graph.take_ie_csr(index),
+                            ie_prop: graph.take_ie_edge_prop_table(index),
+                            oe_csr: graph.take_oe_csr(index),
+                            oe_prop: graph.take_oe_edge_prop_table(index),
                         });
                     }
                 }
@@ -2224,16 +2313,10 @@ impl GraphModifier {
                         edge_label: e_label_i as LabelId,
                         dst_label: dst_label_i as LabelId,
 
-                        ie_csr: std::mem::replace(
-                            &mut graph.ie[index],
-                            Box::new(BatchMutableSingleCsr::new()),
-                        ),
-                        ie_prop: graph.ie_edge_prop_table.remove(&index),
-                        oe_csr: std::mem::replace(
-                            &mut graph.oe[index],
-                            Box::new(BatchMutableSingleCsr::new()),
-                        ),
-                        oe_prop: graph.oe_edge_prop_table.remove(&index),
+                        ie_csr: graph.take_ie_csr(index),
+                        ie_prop: graph.take_ie_edge_prop_table(index),
+                        oe_csr: graph.take_oe_csr(index),
+                        oe_prop: graph.take_oe_edge_prop_table(index),
                     });
                 }
             }
@@ -2250,13 +2333,13 @@ impl GraphModifier {
         let index =
             graph.edge_label_to_index(reps.src_label, reps.dst_label, reps.edge_label, Direction::Outgoing);
 
-        graph.ie[index] = reps.ie_csr;
+        graph.set_ie_csr(index, reps.ie_csr);
         if let Some(table) = reps.ie_prop {
-            graph.ie_edge_prop_table.insert(index, table);
+            graph.set_ie_edge_prop_table(index, table);
         }
-        graph.oe[index] = reps.oe_csr;
+        graph.set_oe_csr(index, reps.oe_csr);
         if let Some(table) = reps.oe_prop {
-            graph.oe_edge_prop_table.insert(index, table);
+            graph.set_oe_edge_prop_table(index, table);
         }
     }
 
@@ -2273,13 +2356,13 @@ impl GraphModifier {
                 Direction::Outgoing,
             );
 
-            graph.ie[index] = result.ie_csr;
+            graph.set_ie_csr(index, result.ie_csr);
             if let Some(table) = result.ie_prop {
-                graph.ie_edge_prop_table.insert(index, table);
+                graph.set_ie_edge_prop_table(index, table);
             }
-            graph.oe[index] = result.oe_csr;
+            graph.set_oe_csr(index, result.oe_csr);
             if let Some(table) = result.oe_prop {
-                graph.oe_edge_prop_table.insert(index, table);
+                graph.set_oe_edge_prop_table(index, table);
             }
         }
     }
@@ -2328,7 +2411,7 @@ impl GraphModifier {
 
         let edge_files = edge_files.unwrap();
         for edge_file in edge_files.iter() {
-            process_csv_rows(
+            process_rows(
                 edge_file,
                 |record| {
                     let edge_meta = parser.parse_edge_meta(&record);
@@ -2352,9 +2435,21 @@ impl GraphModifier {
                 },
                 self.skip_header,
                 self.delim,
+                self.file_format,
             );
         }
 
+        Self::delete_from_rep(input, src_delete_set, dst_delete_set, &delete_edge_set, p);
+    }
+
+    /// Delete the edges in `delete_edge_set` given by internal ids, together with the edges adjacent
+    /// to the vertices in `src_delete_set` and `dst_delete_set`.
+    fn delete_from_rep<I>(
+        input: &mut CsrRep<I>, src_delete_set: &HashSet<I>, dst_delete_set: &HashSet<I>,
+        delete_edge_set: &Vec<(I, I)>, p: u32,
+    ) where
+        I: Send + Sync + IndexType,
+    {
         if src_delete_set.is_empty() && dst_delete_set.is_empty() && delete_edge_set.is_empty() {
             return ();
         }
@@ -2385,14 +2480,14 @@ impl GraphModifier {
         if let Some(table) = input.oe_prop.as_mut() {
             input
                 .oe_csr
-                .parallel_delete_edges_with_props(&delete_edge_set, false, table, p);
+                .parallel_delete_edges_with_props(delete_edge_set, false, table, p);
             input
                 .oe_csr
                 .parallel_delete_edges_with_props(&ie_to_delete, false, table, p);
         } else {
             input
                 .oe_csr
-                .parallel_delete_edges(&delete_edge_set, false, p);
+                .parallel_delete_edges(delete_edge_set, false, p);
             input
                 .oe_csr
                 .parallel_delete_edges(&ie_to_delete, false, p);
@@ -2402,14 +2497,14 @@ impl GraphModifier {
         if let Some(table) = input.ie_prop.as_mut() {
             input
                 .ie_csr
-                .parallel_delete_edges_with_props(&delete_edge_set, true, table, p);
+                .parallel_delete_edges_with_props(delete_edge_set, true, table, p);
             input
                 .ie_csr
                 .parallel_delete_edges_with_props(&oe_to_delete, true, table, p);
         } else {
             input
                 .ie_csr
-                .parallel_delete_edges(&delete_edge_set, true, p);
+                .parallel_delete_edges(delete_edge_set, true, p);
             input
                 .ie_csr
                 .parallel_delete_edges(&oe_to_delete, true, p);
@@ -2434,7 +2529,7 @@ impl GraphModifier {
 
         let parser = LDBCVertexParser::<G>::new(label as LabelId, id_col as usize);
         for vertex_file in vertex_files.iter() {
-            process_csv_rows(
+            process_rows(
                 vertex_file,
                 |record| {
                     let vertex_meta = parser.parse_vertex_meta(&record);
@@ -2448,6 +2543,7 @@ impl GraphModifier {
                 },
                 self.skip_header,
                 self.delim,
+                self.file_format,
             );
         }
 
@@ -2472,7 +2568,7 @@ impl GraphModifier {
         self.set_csrs(graph, input_reps);
         let delete_set = &delete_sets[label as usize];
         for v in delete_set.iter() {
-            graph.vertex_map.remove_vertex(label, v);
+            graph.remove_vertex(label, v);
         }
 
         Ok(())
@@ -2507,6 +2603,45 @@ impl GraphModifier {
         Ok(())
     }
 
+    /// Delete edges of `src_label - edge_label - dst_label` given by global ids, edges whose
+    /// endpoints are not found in the graph are ignored.
+    pub fn apply_edges_delete_with_ids<G, I>(
+        &mut self, graph: &mut GraphDB<G, I>, src_label: LabelId, edge_label: LabelId, dst_label: LabelId,
+        edges: &Vec<(G, G)>,
+    ) -> GDBResult<()>
+    where
+        G: FromStr + Send + Sync + IndexType + Eq,
+        I: Send + Sync + IndexType,
+    {
+        if graph
+            .graph_schema
+            .get_edge_header(src_label, edge_label, dst_label)
+            .is_none()
+        {
+            return Ok(());
+        }
+        let mut delete_edge_set = Vec::new();
+        for (src, dst) in edges.iter() {
+            if let (Some((got_src_label, src_lid)), Some((got_dst_label, dst_lid))) =
+                (graph.vertex_map.get_internal_id(*src), graph.vertex_map.get_internal_id(*dst))
+            {
+                if got_src_label == src_label && got_dst_label == dst_label {
+                    delete_edge_set.push((src_lid, dst_lid));
+                }
+            }
+        }
+        let mut input_resp = self.take_csr(graph, src_label, dst_label, edge_label);
+        Self::delete_from_rep(
+            &mut input_resp,
+            &HashSet::new(),
+            &HashSet::new(),
+            &delete_edge_set,
+            self.parallel,
+        );
+        self.set_csr(graph, input_resp);
+        Ok(())
+    }
+
     fn apply_deletes<G, I>(
         &mut self, graph: &mut GraphDB<G, I>, delete_schema: &InputSchema,
     ) -> GDBResult<()>
@@ -2542,7 +2677,7 @@ impl GraphModifier {
                     }
                     let parser = LDBCVertexParser::<G>::new(v_label_i as LabelId, id_col);
                     for vertex_file in vertex_files.iter() {
-                        process_csv_rows(
+                        process_rows(
                             vertex_file,
                             |record| {
                                 let vertex_meta = parser.parse_vertex_meta(&record);
@@ -2556,6 +2691,7 @@ impl GraphModifier {
                             },
                             self.skip_header,
                             self.delim,
+                            self.file_format,
                         );
                     }
                 }
@@ -2590,9 +2726,7 @@ impl GraphModifier {
                 continue;
             }
             for v in delete_set.iter() {
-                graph
-                    .vertex_map
-                    .remove_vertex(v_label_i as LabelId, v);
+                graph.remove_vertex(v_label_i as LabelId, v);
             }
         }
 
@@ -2631,7 +2765,7 @@ impl GraphModifier {
             return Ok(());
         }
         for vertex_file in vertex_files.iter() {
-            process_csv_rows(
+            process_rows(
                 vertex_file,
                 |record| {
                     let vertex_meta = parser.parse_vertex_meta(&record);
@@ -2641,6 +2775,7 @@ impl GraphModifier {
                 },
                 self.skip_header,
                 self.delim,
+                self.file_format,
             );
         }
 
@@ -2700,7 +2835,7 @@ impl GraphModifier {
                     continue;
                 }
                 for vertex_file in vertex_files.iter() {
-                    process_csv_rows(
+                    process_rows(
                         vertex_file,
                         |record| {
                             let vertex_meta = parser.parse_vertex_meta(&record);
@@ -2716,6 +2851,7 @@ impl GraphModifier {
                         },
                         self.skip_header,
                         self.delim,
+                        self.file_format,
                     );
                 }
             }
@@ -2763,7 +2899,7 @@ impl GraphModifier {
 
         if table_header.is_empty() {
             for file in files.iter() {
-                process_csv_rows(
+                process_rows(
                     file,
                     |record| {
                         let edge_meta = parser.parse_edge_meta(&record);
@@ -2771,13 +2907,14 @@ impl GraphModifier {
                     },
                     self.skip_header,
                     self.delim,
+                    self.file_format,
                 );
             }
             Ok((edges, None))
         } else {
             let mut prop_table = ColTable::new(table_header);
             for file in files.iter() {
-                process_csv_rows(
+                process_rows(
                     file,
                     |record| {
                         let edge_meta = parser.parse_edge_meta(&record);
@@ -2788,6 +2925,7 @@ impl GraphModifier {
                     },
                     self.skip_header,
                     self.delim,
+                    self.file_format,
                 )
             }
             Ok((edges, Some(prop_table)))
@@ -2918,7 +3056,7 @@ impl GraphModifier {
             return Ok(());
         }
         let edge_files = edge_files.unwrap();
-        let mut input_reps = self.take_csr(graph, src_label, dst_label, edge_label);
+        let input_reps = self.take_csr(graph, src_label, dst_label, edge_label);
         let mut edges = vec![];
         let graph_header = graph
             .graph_schema
@@ -2931,7 +3069,7 @@ impl GraphModifier {
         let mut prop_table = ColTable::new(table_header.clone());
         if table_header.is_empty() {
             for file in edge_files {
-                process_csv_rows(
+                process_rows(
                     &file,
                     |record| {
                         let edge_meta = parser.parse_edge_meta(&record);
@@ -2939,11 +3077,12 @@ impl GraphModifier {
                     },
                     self.skip_header,
                     self.delim,
+                    self.file_format,
                 );
             }
         } else {
             for file in edge_files {
-                process_csv_rows(
+                process_rows(
                     &file,
                     |record| {
                         let edge_meta = parser.parse_edge_meta(&record);
@@ -2956,10 +3095,39 @@ impl GraphModifier {
                     },
                     self.skip_header,
                     self.delim,
+                    self.file_format,
                 )
             }
         }
 
+        self.insert_parsed_edges(graph, input_reps, &edges, &prop_table);
+        Ok(())
+    }
+
+    /// Insert edges of `src_label - edge_label - dst_label` given by global ids, with their properties
+    /// already organized as rows of `prop_table` following the edge header of the graph schema.
+    pub fn apply_edges_insert_with_data<G, I>(
+        &mut self, graph: &mut GraphDB<G, I>, src_label: LabelId, edge_label: LabelId, dst_label: LabelId,
+        edges: &Vec<(G, G)>, prop_table: &ColTable,
+    ) -> GDBResult<()>
+    where
+        I: Send + Sync + IndexType,
+        G: FromStr + Send + Sync + IndexType + Eq,
+    {
+        let input_reps = self.take_csr(graph, src_label, dst_label, edge_label);
+        self.insert_parsed_edges(graph, input_reps, edges, prop_table);
+        Ok(())
+    }
+
+    fn insert_parsed_edges<G, I>(
+        &self, graph: &mut GraphDB<G, I>, mut input_reps: CsrRep<I>, edges: &Vec<(G, G)>,
+        prop_table: &ColTable,
+    ) where
+        I: Send + Sync + IndexType,
+        G: FromStr + Send + Sync + IndexType + Eq,
+    {
+        let src_label = input_reps.src_label;
+        let dst_label = input_reps.dst_label;
         let parsed_edges: Vec<(I, I)> = edges
             .par_iter()
             .map(|(src, dst)| {
@@ -2978,7 +3146,7 @@ impl GraphModifier {
             Some(input_reps.oe_csr.insert_edges_with_prop(
                 new_src_num,
                 &parsed_edges,
-                &prop_table,
+                prop_table,
                 false,
                 self.parallel,
                 old_table,
@@ -2995,7 +3163,7 @@ impl GraphModifier {
             Some(input_reps.ie_csr.insert_edges_with_prop(
                 new_dst_num,
                 &parsed_edges,
-                &prop_table,
+                prop_table,
                 true,
                 self.parallel,
                 old_table,
@@ -3007,7 +3175,6 @@ impl GraphModifier {
             None
         };
         self.set_csr(graph, input_reps);
-        Ok(())
     }
 
     fn apply_edges_inserts<G, I>(
@@ -3052,3 +3219,95 @@ impl GraphModifier {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn knows_weight(graph: &GraphDB<usize, usize>, src: usize, dst: usize) -> Option<f64> {
+        let sub_graph = graph.get_sub_graph(PERSON, KNOWS, PERSON, Direction::Outgoing);
+        let src_lid = graph.get_internal_id_beta(person_id(src))?;
+        let dst_lid = graph.get_internal_id_beta(person_id(dst))?;
+        let (_, offset) = sub_graph
+            .get_adj_list_with_offset(src_lid)?
+            .find(|(nbr, _)| *nbr == dst_lid)?;
+        match sub_graph
+            .get_properties()?
+            .get_item("weight", offset)
+        {
+            Some(RefItem::Double(weight)) => Some(*weight),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_insert_vertices_from_memory() {
+        let root = tempdir::TempDir::new("bmcsr_insert_vertices").unwrap();
+        build_modern_graph(root.path());
+        let mut graph = load(root.path());
+        let (persons, knows) = summary(&graph);
+
+        apply_write_operations(&mut graph, vec![insert_persons(vec![7, 8])], 1);
+        let vertex = graph.get_vertex(person_id(7)).unwrap();
+        assert_eq!(vertex.get_label(), PERSON);
+        assert!(matches!(vertex.get_property("name"), Some(RefItem::String(name)) if name == "new_person"));
+        assert!(matches!(vertex.get_property("age"), Some(RefItem::Int32(&20))));
+        let (new_persons, new_knows) = summary(&graph);
+        assert_eq!(new_persons.len(), persons.len() + 2);
+        assert!(new_persons.contains(&person_id(8)));
+        assert_eq!(new_knows, knows);
+    }
+
+    #[test]
+    fn test_insert_edges_from_memory() {
+        let root = tempdir::TempDir::new("bmcsr_insert_edges").unwrap();
+        build_modern_graph(root.path());
+        let mut graph = load(root.path());
+
+        apply_write_operations(
+            &mut graph,
+            vec![insert_persons(vec![7]), insert_knows(vec![(1, 7), (7, 2)])],
+            1,
+        );
+        let (_, knows) = summary(&graph);
+        assert!(knows.contains(&(person_id(1), person_id(7))));
+        assert!(knows.contains(&(person_id(7), person_id(2))));
+        assert_eq!(knows_weight(&graph, 1, 7), Some(0.1));
+        // the properties of the existing edges are kept
+        assert_eq!(knows_weight(&graph, 1, 2), Some(0.5));
+        assert_eq!(knows_weight(&graph, 1, 4), Some(1.0));
+    }
+
+    #[test]
+    fn test_delete_vertices_from_memory() {
+        let root = tempdir::TempDir::new("bmcsr_delete_vertices").unwrap();
+        build_modern_graph(root.path());
+        let mut graph = load(root.path());
+        let (persons, _) = summary(&graph);
+
+        apply_write_operations(&mut graph, vec![delete_persons(vec![2])], 1);
+        assert!(graph.get_vertex(person_id(2)).is_none());
+        let (new_persons, knows) = summary(&graph);
+        assert_eq!(new_persons.len(), persons.len() - 1);
+        // the edges adjacent to the deleted vertex are deleted as well
+        assert_eq!(knows, vec![(person_id(1), person_id(4))]);
+        assert_eq!(knows_weight(&graph, 1, 4), Some(1.0));
+    }
+
+    #[test]
+    fn test_delete_edges_from_memory() {
+        let root = tempdir::TempDir::new("bmcsr_delete_edges").unwrap();
+        build_modern_graph(root.path());
+        let mut graph = load(root.path());
+        let (persons, _) = summary(&graph);
+
+        // the edge from 2 to 1 does not exist, and is ignored
+        apply_write_operations(&mut graph, vec![delete_knows(vec![(1, 2), (2, 1)])], 1);
+        let (new_persons, knows) = summary(&graph);
+        assert_eq!(new_persons, persons);
+        assert_eq!(knows, vec![(person_id(1), person_id(4))]);
+        assert_eq!(knows_weight(&graph, 1, 4), Some(1.0));
+        assert_eq!(knows_weight(&graph, 1, 2), None);
+    }
+}
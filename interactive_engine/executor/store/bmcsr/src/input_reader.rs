@@ -0,0 +1,369 @@
+//
+//! Copyright 2023 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! Readers of the vertex and edge input files. Whatever the format of a file is, its rows are
+//! given as `StringRecord`s, so that they can be parsed by the same parsers as plain csv files.
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use csv::{ReaderBuilder, StringRecord};
+use parquet::basic::{ConvertedType, LogicalType, Type as PhysicalType};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::reader::RowIter;
+use parquet::record::Field;
+use parquet::schema::types::{ColumnDescriptor, Type};
+use pegasus_common::codec::{Decode, Encode};
+use pegasus_common::io::{ReadExt, WriteExt};
+use rust_htslib::bgzf::Reader as GzReader;
+use serde::{Deserialize, Serialize};
+
+use crate::columns::DataType;
+use crate::error::{GDBError, GDBResult};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FileFormat {
+    Csv,
+    CsvGz,
+    Parquet,
+}
+
+impl FileFormat {
+    /// Infer the format of a file from the suffix of its name.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<FileFormat> {
+        let path_str = path.as_ref().to_str()?;
+        if path_str.ends_with(".csv.gz") {
+            Some(FileFormat::CsvGz)
+        } else if path_str.ends_with(".csv") {
+            Some(FileFormat::Csv)
+        } else if path_str.ends_with(".parquet") {
+            Some(FileFormat::Parquet)
+        } else {
+            None
+        }
+    }
+}
+
+impl Encode for FileFormat {
+    fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
+        match *self {
+            FileFormat::Csv => writer.write_u8(0),
+            FileFormat::CsvGz => writer.write_u8(1),
+            FileFormat::Parquet => writer.write_u8(2),
+        }
+    }
+}
+
+impl Decode for FileFormat {
+    fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
+        match reader.read_u8()? {
+            0 => Ok(FileFormat::Csv),
+            1 => Ok(FileFormat::CsvGz),
+            2 => Ok(FileFormat::Parquet),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::Other, "Unknown FileFormat type")),
+        }
+    }
+}
+
+pub trait InputReader {
+    /// Read the rows of the file at `path`.
+    fn read_records(&self, path: &Path) -> GDBResult<Box<dyn Iterator<Item = GDBResult<StringRecord>>>>;
+}
+
+/// Reader of delimited text files, which are optionally compressed by gzip.
+pub struct CsvInputReader {
+    delimiter: u8,
+    has_headers: bool,
+    compressed: bool,
+}
+
+impl CsvInputReader {
+    pub fn new(delimiter: u8, has_headers: bool) -> Self {
+        CsvInputReader { delimiter, has_headers, compressed: false }
+    }
+
+    pub fn compressed(mut self) -> Self {
+        self.compressed = true;
+        self
+    }
+}
+
+impl InputReader for CsvInputReader {
+    fn read_records(&self, path: &Path) -> GDBResult<Box<dyn Iterator<Item = GDBResult<StringRecord>>>> {
+        let reader: Box<dyn Read> = if self.compressed {
+            Box::new(BufReader::new(GzReader::from_path(path).map_err(|e| {
+                GDBError::IOError(std::io::Error::new(std::io::ErrorKind::Other, format!("{:?}", e)))
+            })?))
+        } else {
+            Box::new(BufReader::new(File::open(path)?))
+        };
+        let rdr = ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .buffer_capacity(4096)
+            .comment(Some(b'#'))
+            .flexible(true)
+            .has_headers(self.has_headers)
+            .from_reader(reader);
+        Ok(Box::new(
+            rdr.into_records()
+                .map(|result| result.map_err(|e| GDBError::IOError(std::io::Error::from(e)))),
+        ))
+    }
+}
+
+/// Reader of parquet files. When a header is given, only the columns named in the header are read,
+/// in the order of the header, and the types of the columns are checked against the header.
+pub struct ParquetInputReader {
+    projection: Option<Vec<(String, DataType)>>,
+}
+
+impl ParquetInputReader {
+    pub fn new() -> Self {
+        ParquetInputReader { projection: None }
+    }
+
+    pub fn with_projection(header: &[(String, DataType)]) -> Self {
+        ParquetInputReader { projection: Some(header.to_vec()) }
+    }
+
+    fn project(&self, reader: &SerializedFileReader<File>) -> GDBResult<Option<Type>> {
+        let projection = match self.projection.as_ref() {
+            Some(projection) => projection,
+            None => return Ok(None),
+        };
+        let schema = reader
+            .metadata()
+            .file_metadata()
+            .schema_descr_ptr();
+        let mut fields = Vec::with_capacity(projection.len());
+        for (name, data_type) in projection.iter() {
+            let index = (0..schema.num_columns())
+                .find(|i| schema.column(*i).name() == name)
+                .ok_or_else(|| {
+                    error!("column {} not found in parquet file", name);
+                    GDBError::FieldNotExistError
+                })?;
+            let column = schema.column(index);
+            if !is_compatible(*data_type, parquet_data_type(&column)) {
+                error!(
+                    "column {} of type {:?} can not be read as {:?}",
+                    name,
+                    column.physical_type(),
+                    data_type
+                );
+                return Err(GDBError::InvalidTypeError);
+            }
+            fields.push(schema.get_column_root_ptr(index));
+        }
+        let projection = Type::group_type_builder(schema.root_schema().name())
+            .with_fields(fields)
+            .build()?;
+        Ok(Some(projection))
+    }
+}
+
+impl InputReader for ParquetInputReader {
+    fn read_records(&self, path: &Path) -> GDBResult<Box<dyn Iterator<Item = GDBResult<StringRecord>>>> {
+        let reader = SerializedFileReader::new(File::open(path)?)?;
+        let projection = self.project(&reader)?;
+        let rows = RowIter::from_file_into(Box::new(reader)).project(projection)?;
+        Ok(Box::new(rows.map(|result| {
+            let row = result?;
+            Ok(row
+                .get_column_iter()
+                .map(|(_, field)| field_to_string(field))
+                .collect::<StringRecord>())
+        })))
+    }
+}
+
+pub fn create_input_reader(
+    format: FileFormat, delimiter: u8, has_headers: bool, header: Option<&[(String, DataType)]>,
+) -> Box<dyn InputReader> {
+    match format {
+        FileFormat::Csv => Box::new(CsvInputReader::new(delimiter, has_headers)),
+        FileFormat::CsvGz => Box::new(CsvInputReader::new(delimiter, has_headers).compressed()),
+        FileFormat::Parquet => match header {
+            Some(header) => Box::new(ParquetInputReader::with_projection(header)),
+            None => Box::new(ParquetInputReader::new()),
+        },
+    }
+}
+
+/// The `DataType` that the values of a parquet column are mapped to.
+pub fn parquet_data_type(column: &ColumnDescriptor) -> DataType {
+    match column.physical_type() {
+        PhysicalType::INT32 => match (column.logical_type(), column.converted_type()) {
+            (Some(LogicalType::Date), _) | (_, ConvertedType::DATE) => DataType::Date,
+            (Some(LogicalType::Integer { is_signed: false, .. }), _) | (_, ConvertedType::UINT_32) => {
+                DataType::UInt32
+            }
+            _ => DataType::Int32,
+        },
+        PhysicalType::INT64 => match (column.logical_type(), column.converted_type()) {
+            (Some(LogicalType::Timestamp { .. }), _)
+            | (_, ConvertedType::TIMESTAMP_MILLIS)
+            | (_, ConvertedType::TIMESTAMP_MICROS) => DataType::DateTime,
+            (Some(LogicalType::Integer { is_signed: false, .. }), _) | (_, ConvertedType::UINT_64) => {
+                DataType::UInt64
+            }
+            _ => DataType::Int64,
+        },
+        PhysicalType::BOOLEAN => DataType::Boolean,
+        PhysicalType::FLOAT => DataType::Float,
+        PhysicalType::DOUBLE => DataType::Double,
+        PhysicalType::BYTE_ARRAY | PhysicalType::FIXED_LEN_BYTE_ARRAY => DataType::String,
+        _ => DataType::NULL,
+    }
+}
+
+/// Whether the values of type `actual` can be read as `expected` without loss.
+fn is_compatible(expected: DataType, actual: DataType) -> bool {
+    match (expected, actual) {
+        (DataType::ID, DataType::Int32 | DataType::UInt32 | DataType::Int64 | DataType::UInt64) => true,
+        (DataType::Int64, DataType::Int32 | DataType::UInt32) => true,
+        (DataType::UInt64, DataType::UInt32) => true,
+        (DataType::Double, DataType::Int32 | DataType::UInt32 | DataType::Float) => true,
+        (DataType::LCString, DataType::String) => true,
+        (DataType::DateTime, DataType::Int64) => true,
+        (expected, actual) => expected == actual,
+    }
+}
+
+fn field_to_string(field: &Field) -> String {
+    match field {
+        Field::Null => "".to_string(),
+        Field::Str(s) => s.clone(),
+        Field::Bytes(bytes) => String::from_utf8_lossy(bytes.data()).to_string(),
+        Field::Date(days) => {
+            let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+            (epoch + chrono::Duration::days(*days as i64))
+                .format("%Y-%m-%d")
+                .to_string()
+        }
+        Field::TimestampMillis(millis) => millis.to_string(),
+        Field::TimestampMicros(micros) => (micros / 1000).to_string(),
+        _ => field.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use parquet::data_type::{ByteArray, ByteArrayType, Int32Type, Int64Type};
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+
+    use super::*;
+
+    fn write_persons(path: &Path) {
+        let schema = Arc::new(
+            parse_message_type(
+                "message person {
+                    REQUIRED INT32 age;
+                    REQUIRED BYTE_ARRAY name (UTF8);
+                    REQUIRED INT64 id;
+                    REQUIRED INT32 birthday (DATE);
+                }",
+            )
+            .unwrap(),
+        );
+        let mut writer =
+            SerializedFileWriter::new(File::create(path).unwrap(), schema, Default::default()).unwrap();
+        let mut row_group = writer.next_row_group().unwrap();
+        let mut index = 0;
+        while let Some(mut column) = row_group.next_column().unwrap() {
+            match index {
+                0 => column
+                    .typed::<Int32Type>()
+                    .write_batch(&[29, 27], None, None)
+                    .unwrap(),
+                1 => column
+                    .typed::<ByteArrayType>()
+                    .write_batch(&[ByteArray::from("marko"), ByteArray::from("vadas")], None, None)
+                    .unwrap(),
+                2 => column
+                    .typed::<Int64Type>()
+                    .write_batch(&[1, 2], None, None)
+                    .unwrap(),
+                _ => column
+                    .typed::<Int32Type>()
+                    .write_batch(&[0, 19000], None, None)
+                    .unwrap(),
+            };
+            column.close().unwrap();
+            index += 1;
+        }
+        row_group.close().unwrap();
+        writer.close().unwrap();
+    }
+
+    #[test]
+    fn test_read_parquet_with_projection() {
+        let dir = tempdir::TempDir::new("bmcsr_parquet").unwrap();
+        let path = dir.path().join("person.parquet");
+        write_persons(&path);
+        assert_eq!(FileFormat::from_path(&path), Some(FileFormat::Parquet));
+
+        let header = vec![
+            ("id".to_string(), DataType::ID),
+            ("name".to_string(), DataType::String),
+            ("age".to_string(), DataType::Int32),
+            ("birthday".to_string(), DataType::Date),
+        ];
+        let reader = create_input_reader(FileFormat::Parquet, b'|', false, Some(&header));
+        let records: Vec<Vec<String>> = reader
+            .read_records(&path)
+            .unwrap()
+            .map(|record| {
+                record
+                    .unwrap()
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                vec!["1".to_string(), "marko".to_string(), "29".to_string(), "1970-01-01".to_string()],
+                vec!["2".to_string(), "vadas".to_string(), "27".to_string(), "2022-01-08".to_string()],
+            ]
+        );
+
+        let all_columns: Vec<usize> = ParquetInputReader::new()
+            .read_records(&path)
+            .unwrap()
+            .map(|record| record.unwrap().len())
+            .collect();
+        assert_eq!(all_columns, vec![4, 4]);
+    }
+
+    #[test]
+    fn test_read_parquet_type_mismatch() {
+        let dir = tempdir::TempDir::new("bmcsr_parquet").unwrap();
+        let path = dir.path().join("person.parquet");
+        write_persons(&path);
+
+        let header = vec![("name".to_string(), DataType::Int64)];
+        let reader = ParquetInputReader::with_projection(&header);
+        assert!(matches!(reader.read_records(&path), Err(GDBError::InvalidTypeError)));
+        let header = vec![("email".to_string(), DataType::String)];
+        let reader = ParquetInputReader::with_projection(&header);
+        assert!(matches!(reader.read_records(&path), Err(GDBError::FieldNotExistError)));
+    }
+}
@@ -36,10 +36,14 @@ pub mod graph;
 pub mod graph_db;
 pub mod graph_loader;
 pub mod graph_modifier;
+pub mod input_reader;
 pub mod ldbc_parser;
 pub mod schema;
 pub mod sub_graph;
+#[cfg(test)]
+mod test_utils;
 pub mod traverse;
 pub mod types;
 pub mod utils;
 pub mod vertex_map;
+pub mod wal;
@@ -23,6 +23,7 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use crate::columns::DataType;
+use crate::input_reader::FileFormat;
 use crate::types::*;
 
 /// The starting id field in an edge file
@@ -457,6 +458,14 @@ pub struct InputSchema {
     vertex_files: HashMap<LabelId, Vec<String>>,
     /// Map for src_vertex, edge, dst_vertex label id to input file
     edge_files: HashMap<(LabelId, LabelId, LabelId), Vec<String>>,
+
+    /// Map from vertex label id to the format of input file, which is inferred from the
+    /// file name if not specified
+    #[serde(default)]
+    vertex_formats: HashMap<LabelId, FileFormat>,
+    /// Map from src_vertex, edge, dst_vertex label id to the format of input file
+    #[serde(default)]
+    edge_formats: HashMap<(LabelId, LabelId, LabelId), FileFormat>,
 }
 
 impl InputSchema {
@@ -485,10 +494,29 @@ impl InputSchema {
             .get(&(src_label, edge_label, dst_label))
     }
 
+    pub fn get_vertex_file_format<P: AsRef<Path>>(
+        &self, vertex_label: LabelId, file: P,
+    ) -> Option<FileFormat> {
+        self.vertex_formats
+            .get(&vertex_label)
+            .cloned()
+            .or_else(|| FileFormat::from_path(file))
+    }
+
+    pub fn get_edge_file_format<P: AsRef<Path>>(
+        &self, src_label: LabelId, edge_label: LabelId, dst_label: LabelId, file: P,
+    ) -> Option<FileFormat> {
+        self.edge_formats
+            .get(&(src_label, edge_label, dst_label))
+            .cloned()
+            .or_else(|| FileFormat::from_path(file))
+    }
+
     pub fn from_string(s: String, graph_schema: &CsrGraphSchema) -> std::io::Result<Self> {
         let input_json: InputSchemaJson = serde_json::from_str(&s)?;
         let mut vertex_headers = HashMap::new();
         let mut vertex_files = HashMap::new();
+        let mut vertex_formats = HashMap::new();
         for vertex in &input_json.vertex {
             if let Some(vertex_label) = graph_schema
                 .vertex_type_to_id
@@ -500,10 +528,14 @@ impl InputSchema {
                 }
                 vertex_headers.insert(*vertex_label, properties);
                 vertex_files.insert(*vertex_label, vertex.files.clone());
+                if let Some(format) = vertex.format {
+                    vertex_formats.insert(*vertex_label, format);
+                }
             }
         }
         let mut edge_headers = HashMap::new();
         let mut edge_files = HashMap::new();
+        let mut edge_formats = HashMap::new();
         for edge in &input_json.edge {
             if let (Some(src_label), Some(edge_label), Some(dst_label)) = (
                 graph_schema
@@ -520,9 +552,19 @@ impl InputSchema {
                 }
                 edge_headers.insert((*src_label, *edge_label, *dst_label), properties);
                 edge_files.insert((*src_label, *edge_label, *dst_label), edge.files.clone());
+                if let Some(format) = edge.format {
+                    edge_formats.insert((*src_label, *edge_label, *dst_label), format);
+                }
             }
         }
-        Ok(InputSchema { vertex_headers, edge_headers, vertex_files, edge_files })
+        Ok(InputSchema {
+            vertex_headers,
+            edge_headers,
+            vertex_files,
+            edge_files,
+            vertex_formats,
+            edge_formats,
+        })
     }
 
     pub fn from_json_file<P: AsRef<Path>>(path: P, graph_schema: &CsrGraphSchema) -> std::io::Result<Self> {
@@ -531,6 +573,7 @@ impl InputSchema {
             serde_json::from_reader::<File, InputSchemaJson>(file).map_err(std::io::Error::from)?;
         let mut vertex_headers = HashMap::new();
         let mut vertex_files = HashMap::new();
+        let mut vertex_formats = HashMap::new();
         for vertex in &input_json.vertex {
             if let Some(vertex_label) = graph_schema
                 .vertex_type_to_id
@@ -542,10 +585,14 @@ impl InputSchema {
                 }
                 vertex_headers.insert(*vertex_label, properties);
                 vertex_files.insert(*vertex_label, vertex.files.clone());
+                if let Some(format) = vertex.format {
+                    vertex_formats.insert(*vertex_label, format);
+                }
             }
         }
         let mut edge_headers = HashMap::new();
         let mut edge_files = HashMap::new();
+        let mut edge_formats = HashMap::new();
         for edge in &input_json.edge {
             if let (Some(src_label), Some(edge_label), Some(dst_label)) = (
                 graph_schema
@@ -562,9 +609,19 @@ impl InputSchema {
                 }
                 edge_headers.insert((*src_label, *edge_label, *dst_label), properties);
                 edge_files.insert((*src_label, *edge_label, *dst_label), edge.files.clone());
+                if let Some(format) = edge.format {
+                    edge_formats.insert((*src_label, *edge_label, *dst_label), format);
+                }
             }
         }
-        Ok(InputSchema { vertex_headers, edge_headers, vertex_files, edge_files })
+        Ok(InputSchema {
+            vertex_headers,
+            edge_headers,
+            vertex_files,
+            edge_files,
+            vertex_formats,
+            edge_formats,
+        })
     }
 }
 
@@ -579,6 +636,8 @@ struct InputVertex {
     label: String,
     columns: Vec<ColumnInfo>,
     files: Vec<String>,
+    #[serde(default)]
+    format: Option<FileFormat>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -588,6 +647,8 @@ struct InputEdge {
     label: String,
     columns: Vec<ColumnInfo>,
     files: Vec<String>,
+    #[serde(default)]
+    format: Option<FileFormat>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -0,0 +1,219 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! Helpers shared by the tests of the modifications on the modern graph in `data/`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::columns::{DataType, DoubleColumn, Int32Column, StringColumn, UInt64Column};
+use crate::graph::{Direction, IndexType};
+use crate::graph_db::GraphDB;
+use crate::graph_loader::GraphLoader;
+use crate::graph_modifier::{
+    ColumnMappings, ColumnMetadata, DataFrame, EdgeMappings, Input, VertexMappings, WriteOperation,
+};
+use crate::ldbc_parser::LDBCVertexParser;
+use crate::schema::{CsrGraphSchema, Schema};
+use crate::types::*;
+
+pub(crate) const PERSON: LabelId = 0;
+pub(crate) const KNOWS: LabelId = 0;
+
+pub(crate) fn build_modern_graph(root_dir: &Path) {
+    let data_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data");
+    let schema_file = data_dir.join("modern_schema.json");
+    let schema_dir = root_dir.join(DIR_GRAPH_SCHEMA);
+    fs::create_dir_all(&schema_dir).unwrap();
+    let graph_schema = CsrGraphSchema::from_json_file(&schema_file).unwrap();
+    assert_eq!(graph_schema.get_vertex_label_id("person"), Some(PERSON));
+    assert_eq!(graph_schema.get_edge_label_id("knows"), Some(KNOWS));
+    graph_schema
+        .to_json_file(schema_dir.join(FILE_SCHEMA))
+        .unwrap();
+
+    let mut loader = GraphLoader::<usize, usize>::new(
+        data_dir.join("modern_graph"),
+        root_dir.to_path_buf(),
+        data_dir.join("modern_input.json"),
+        schema_file,
+        0,
+        1,
+    );
+    loader.skip_header();
+    loader.load().unwrap();
+}
+
+pub(crate) fn load(root_dir: &Path) -> GraphDB<usize, usize> {
+    GraphDB::deserialize(root_dir.to_str().unwrap(), 0, None).unwrap()
+}
+
+pub(crate) fn person_id(id: usize) -> usize {
+    LDBCVertexParser::<usize>::to_global_id(id, PERSON)
+}
+
+pub(crate) fn insert_persons(ids: Vec<usize>) -> WriteOperation {
+    let num = ids.len();
+    let data_frame = DataFrame::new(vec![
+        ColumnMetadata::new(
+            Box::new(UInt64Column {
+                data: ids
+                    .into_iter()
+                    .map(|id| person_id(id) as u64)
+                    .collect(),
+            }),
+            "id".to_string(),
+            DataType::ID,
+        ),
+        ColumnMetadata::new(
+            Box::new(StringColumn { data: vec!["new_person".to_string(); num] }),
+            "name".to_string(),
+            DataType::String,
+        ),
+        ColumnMetadata::new(
+            Box::new(Int32Column { data: vec![20; num] }),
+            "age".to_string(),
+            DataType::Int32,
+        ),
+    ]);
+    let column_mappings = vec![
+        ColumnMappings::new(0, "id".to_string(), DataType::ID, "id".to_string()),
+        ColumnMappings::new(1, "name".to_string(), DataType::String, "name".to_string()),
+        ColumnMappings::new(2, "age".to_string(), DataType::Int32, "age".to_string()),
+    ];
+    WriteOperation::insert_vertices(VertexMappings::new(
+        PERSON,
+        vec![Input::memory(data_frame)],
+        column_mappings,
+    ))
+}
+
+pub(crate) fn insert_knows(edges: Vec<(usize, usize)>) -> WriteOperation {
+    let num = edges.len();
+    let data_frame = DataFrame::new(vec![
+        ColumnMetadata::new(
+            Box::new(UInt64Column {
+                data: edges
+                    .iter()
+                    .map(|e| person_id(e.0) as u64)
+                    .collect(),
+            }),
+            "src_id".to_string(),
+            DataType::ID,
+        ),
+        ColumnMetadata::new(
+            Box::new(UInt64Column {
+                data: edges
+                    .iter()
+                    .map(|e| person_id(e.1) as u64)
+                    .collect(),
+            }),
+            "dst_id".to_string(),
+            DataType::ID,
+        ),
+        ColumnMetadata::new(
+            Box::new(DoubleColumn { data: vec![0.1; num] }),
+            "weight".to_string(),
+            DataType::Double,
+        ),
+    ]);
+    WriteOperation::insert_edges(EdgeMappings::new(
+        PERSON,
+        KNOWS,
+        PERSON,
+        vec![Input::memory(data_frame)],
+        vec![ColumnMappings::new(0, "src_id".to_string(), DataType::ID, "id".to_string())],
+        vec![ColumnMappings::new(1, "dst_id".to_string(), DataType::ID, "id".to_string())],
+        vec![ColumnMappings::new(2, "weight".to_string(), DataType::Double, "weight".to_string())],
+    ))
+}
+
+pub(crate) fn delete_persons(ids: Vec<usize>) -> WriteOperation {
+    let data_frame = DataFrame::new_vertices_ids(
+        ids.into_iter()
+            .map(|id| person_id(id) as u64)
+            .collect(),
+    );
+    WriteOperation::delete_vertices(VertexMappings::new(
+        PERSON,
+        vec![Input::memory(data_frame)],
+        vec![ColumnMappings::new(0, "id".to_string(), DataType::ID, "id".to_string())],
+    ))
+}
+
+pub(crate) fn delete_knows(edges: Vec<(usize, usize)>) -> WriteOperation {
+    let data_frame = DataFrame::new(vec![
+        ColumnMetadata::new(
+            Box::new(UInt64Column {
+                data: edges
+                    .iter()
+                    .map(|e| person_id(e.0) as u64)
+                    .collect(),
+            }),
+            "src_id".to_string(),
+            DataType::ID,
+        ),
+        ColumnMetadata::new(
+            Box::new(UInt64Column {
+                data: edges
+                    .iter()
+                    .map(|e| person_id(e.1) as u64)
+                    .collect(),
+            }),
+            "dst_id".to_string(),
+            DataType::ID,
+        ),
+    ]);
+    WriteOperation::delete_edges(EdgeMappings::new(
+        PERSON,
+        KNOWS,
+        PERSON,
+        vec![Input::memory(data_frame)],
+        vec![ColumnMappings::new(0, "src_id".to_string(), DataType::ID, "id".to_string())],
+        vec![ColumnMappings::new(1, "dst_id".to_string(), DataType::ID, "id".to_string())],
+        vec![],
+    ))
+}
+
+/// The persons and the `knows` edges between them, in global ids.
+pub(crate) fn summary(graph: &GraphDB<usize, usize>) -> (Vec<usize>, Vec<(usize, usize)>) {
+    let mut persons: Vec<usize> = graph
+        .get_all_vertices(Some(&vec![PERSON]))
+        .filter(|v| v.is_valid())
+        .map(|v| v.get_id())
+        .collect();
+    persons.sort();
+    let mut knows = vec![];
+    let sub_graph = graph.get_sub_graph(PERSON, KNOWS, PERSON, Direction::Outgoing);
+    for src in 0..graph.get_vertices_num(PERSON) {
+        let src_id = graph.get_global_id(src, PERSON).unwrap();
+        if src_id == <usize as IndexType>::max() {
+            continue;
+        }
+        if let Some(nbrs) = sub_graph.get_adj_list(src) {
+            for dst in nbrs {
+                if *dst != <usize as IndexType>::max() {
+                    knows.push((src_id, graph.get_global_id(*dst, PERSON).unwrap()));
+                }
+            }
+        }
+    }
+    knows.sort();
+    (persons, knows)
+}
+
+pub(crate) fn batch() -> Vec<WriteOperation> {
+    vec![insert_persons(vec![7, 8]), insert_knows(vec![(1, 7), (7, 8)]), delete_persons(vec![2])]
+}
@@ -98,8 +98,7 @@ fn output_csr<G, I>(
                             "|{}",
                             table
                                 .get_item_by_index(c, offset)
-                                .unwrap()
-                                .to_string()
+                                .map_or(String::new(), |item| item.to_string())
                         )
                         .unwrap();
                     }
@@ -152,8 +151,7 @@ fn output_single_csr<G, I>(
                             "|{}",
                             table
                                 .get_item_by_index(c, offset)
-                                .unwrap()
-                                .to_string()
+                                .map_or(String::new(), |item| item.to_string())
                         )
                         .unwrap();
                     }
@@ -217,7 +215,10 @@ fn traverse_edges<G: Send + Sync + IndexType, I: Send + Sync + IndexType>(
                             graph,
                             oe_file_path.to_str().unwrap(),
                             csr,
-                            graph.oe_edge_prop_table.get(&oe_index),
+                            graph
+                                .oe_edge_prop_table
+                                .get(&oe_index)
+                                .map(|table| table.as_ref()),
                             src_label as LabelId,
                             dst_label as LabelId,
                             Direction::Outgoing,
@@ -232,7 +233,10 @@ fn traverse_edges<G: Send + Sync + IndexType, I: Send + Sync + IndexType>(
                             graph,
                             oe_file_path.to_str().unwrap(),
                             csr,
-                            graph.oe_edge_prop_table.get(&oe_index),
+                            graph
+                                .oe_edge_prop_table
+                                .get(&oe_index)
+                                .map(|table| table.as_ref()),
                             src_label as LabelId,
                             dst_label as LabelId,
                             Direction::Outgoing,
@@ -264,7 +268,10 @@ fn traverse_edges<G: Send + Sync + IndexType, I: Send + Sync + IndexType>(
                             graph,
                             ie_file_path.to_str().unwrap(),
                             csr,
-                            graph.ie_edge_prop_table.get(&ie_index),
+                            graph
+                                .ie_edge_prop_table
+                                .get(&ie_index)
+                                .map(|table| table.as_ref()),
                             dst_label as LabelId,
                             src_label as LabelId,
                             Direction::Incoming,
@@ -279,7 +286,10 @@ fn traverse_edges<G: Send + Sync + IndexType, I: Send + Sync + IndexType>(
                             graph,
                             ie_file_path.to_str().unwrap(),
                             csr,
-                            graph.ie_edge_prop_table.get(&ie_index),
+                            graph
+                                .ie_edge_prop_table
+                                .get(&ie_index)
+                                .map(|table| table.as_ref()),
                             dst_label as LabelId,
                             src_label as LabelId,
                             Direction::Incoming,
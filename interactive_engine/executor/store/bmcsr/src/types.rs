@@ -26,3 +26,4 @@ pub const DIR_GRAPH_SCHEMA: &'static str = "graph_schema";
 
 pub const DIR_BINARY_DATA: &'static str = "graph_data_bin";
 pub const DIR_SPLIT_RAW_DATA: &'static str = "graph_split_raw";
+pub const FILE_CHECKPOINT: &'static str = "checkpoint";
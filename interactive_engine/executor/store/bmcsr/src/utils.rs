@@ -21,7 +21,8 @@ use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use csv::{ReaderBuilder, StringRecord};
 use rust_htslib::bgzf::Reader as GzReader;
 
-use crate::columns::{DataType, Item};
+use crate::col_table::is_null_field;
+use crate::columns::{parse_list, DataType, Item};
 use crate::error::GDBResult;
 use crate::graph::IndexType;
 use crate::graph_loader::get_files_list;
@@ -265,6 +266,10 @@ pub fn parse_properties(
     let mut properties = Vec::new();
     for (index, val) in record.iter().enumerate() {
         if selected[index] > 0 {
+            if is_null_field(val, header[index].1) {
+                properties.push(Item::Null);
+                continue;
+            }
             match header[index].1 {
                 DataType::Int32 => {
                     properties.push(Item::Int32(val.parse::<i32>()?));
@@ -290,6 +295,27 @@ pub fn parse_properties(
                 DataType::Double => {
                     properties.push(Item::Double(val.parse::<f64>()?));
                 }
+                DataType::Boolean => {
+                    properties.push(Item::Boolean(val.parse::<bool>()?));
+                }
+                DataType::Float => {
+                    properties.push(Item::Float(val.parse::<f32>()?));
+                }
+                DataType::Int32List => {
+                    properties.push(Item::Int32List(parse_list(val)?));
+                }
+                DataType::Int64List => {
+                    properties.push(Item::Int64List(parse_list(val)?));
+                }
+                DataType::FloatList => {
+                    properties.push(Item::FloatList(parse_list(val)?));
+                }
+                DataType::DoubleList => {
+                    properties.push(Item::DoubleList(parse_list(val)?));
+                }
+                DataType::StringList => {
+                    properties.push(Item::StringList(parse_list(val)?));
+                }
                 DataType::NULL => {
                     error!("Unexpected field type");
                 }
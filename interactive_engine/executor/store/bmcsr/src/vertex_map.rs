@@ -15,6 +15,7 @@
 
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Write};
+use std::sync::Arc;
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use dashmap::DashMap;
@@ -24,80 +25,96 @@ use crate::graph::IndexType;
 use crate::ldbc_parser::LDBCVertexParser;
 use crate::types::*;
 
-pub struct VertexMap<G: Send + Sync + IndexType, I: Send + Sync + IndexType> {
-    // global_id_to_index: FnvHashMap<G, I>,
+/// The ids of the vertices of one label.
+#[derive(Clone)]
+struct LabelVertexMap<G: Send + Sync + IndexType, I: Send + Sync + IndexType> {
     global_id_to_index: DashMap<G, I>,
-    labeled_num: Vec<usize>,
-    vertices_num: Vec<usize>,
-    pub index_to_global_id: Vec<Vec<G>>,
-    labeled_corner_num: Vec<usize>,
-    pub index_to_corner_global_id: Vec<Vec<G>>,
-    label_num: LabelId,
+    labeled_num: usize,
+    vertices_num: usize,
+    index_to_global_id: Vec<G>,
+    labeled_corner_num: usize,
+    index_to_corner_global_id: Vec<G>,
 }
 
-impl<G, I> VertexMap<G, I>
+impl<G, I> LabelVertexMap<G, I>
 where
     G: Send + Sync + IndexType,
     I: Send + Sync + IndexType,
 {
-    pub fn new(num_labels: usize) -> Self {
-        let mut labeled_num = Vec::with_capacity(num_labels);
-        let mut vertices_num = Vec::with_capacity(num_labels);
-        let mut index_to_global_id = Vec::with_capacity(num_labels);
-        let mut labeled_corner_num = Vec::with_capacity(num_labels);
-        let mut index_to_corner_global_id = Vec::with_capacity(num_labels);
-        for _ in 0..num_labels {
-            labeled_num.push(0_usize);
-            vertices_num.push(0_usize);
-            index_to_global_id.push(Vec::new());
-            labeled_corner_num.push(0_usize);
-            index_to_corner_global_id.push(Vec::new());
-        }
+    fn new() -> Self {
         Self {
-            // global_id_to_index: FnvHashMap::default(),
             global_id_to_index: DashMap::new(),
-            labeled_num,
-            vertices_num,
-            index_to_global_id,
-            labeled_corner_num,
-            index_to_corner_global_id,
-            label_num: num_labels as LabelId,
+            labeled_num: 0,
+            vertices_num: 0,
+            index_to_global_id: Vec::new(),
+            labeled_corner_num: 0,
+            index_to_corner_global_id: Vec::new(),
         }
     }
+}
+
+/// Each label is kept behind its own `Arc`, so that cloning the map for a snapshot is cheap and a batch
+/// modifying the vertices of a label only copies that label from the snapshots sharing it.
+#[derive(Clone)]
+pub struct VertexMap<G: Send + Sync + IndexType, I: Send + Sync + IndexType> {
+    labels: Vec<Arc<LabelVertexMap<G, I>>>,
+    label_num: LabelId,
+}
+
+impl<G, I> VertexMap<G, I>
+where
+    G: Send + Sync + IndexType,
+    I: Send + Sync + IndexType,
+{
+    pub fn new(num_labels: usize) -> Self {
+        let labels = (0..num_labels)
+            .map(|_| Arc::new(LabelVertexMap::new()))
+            .collect();
+        Self { labels, label_num: num_labels as LabelId }
+    }
 
     pub fn remove_vertex(&mut self, label: LabelId, internal_id: &I) {
         let internal_id = internal_id.index();
-        if internal_id < self.labeled_num[label as usize] {
-            if self.index_to_global_id[label as usize].len() <= internal_id {
+        let label_map = &self.labels[label as usize];
+        if internal_id < label_map.labeled_num {
+            if label_map.index_to_global_id.len() <= internal_id {
                 return;
             }
-            let global_id = self.index_to_global_id[label as usize][internal_id].clone();
-            self.global_id_to_index.remove(&global_id);
-            self.index_to_global_id[label as usize][internal_id] = <G as IndexType>::max();
-            self.vertices_num[label as usize] -= 1;
+            let label_map = Arc::make_mut(&mut self.labels[label as usize]);
+            let global_id = label_map.index_to_global_id[internal_id].clone();
+            label_map.global_id_to_index.remove(&global_id);
+            label_map.index_to_global_id[internal_id] = <G as IndexType>::max();
+            label_map.vertices_num -= 1;
         } else {
             let index = <I as IndexType>::max().index() - internal_id - 1;
-            if self.index_to_corner_global_id[label as usize].len() <= index {
+            if label_map.index_to_corner_global_id.len() <= index {
                 return;
             }
-            let global_id = self.index_to_corner_global_id[label as usize][index].clone();
-            self.global_id_to_index.remove(&global_id);
-            self.index_to_corner_global_id[label as usize][index] = <G as IndexType>::max();
+            let label_map = Arc::make_mut(&mut self.labels[label as usize]);
+            let global_id = label_map.index_to_corner_global_id[index].clone();
+            label_map.global_id_to_index.remove(&global_id);
+            label_map.index_to_corner_global_id[index] = <G as IndexType>::max();
         }
     }
 
     pub fn add_vertex(&mut self, global_id: G, label: LabelId) -> I {
         assert_eq!(label, LDBCVertexParser::get_label_id(global_id));
 
-        if let Some(vertex) = self.global_id_to_index.get(&global_id) {
-            assert!(vertex.index() < self.labeled_num[label as usize]);
+        if let Some(vertex) = self.labels[label as usize]
+            .global_id_to_index
+            .get(&global_id)
+        {
+            assert!(vertex.index() < self.labels[label as usize].labeled_num);
             vertex.clone()
         } else {
-            let v = I::new(self.labeled_num[label as usize]);
-            self.labeled_num[label as usize] += 1;
-            self.vertices_num[label as usize] += 1;
-            self.index_to_global_id[label as usize].push(global_id);
-            self.global_id_to_index.insert(global_id, v);
+            let label_map = Arc::make_mut(&mut self.labels[label as usize]);
+            let v = I::new(label_map.labeled_num);
+            label_map.labeled_num += 1;
+            label_map.vertices_num += 1;
+            label_map.index_to_global_id.push(global_id);
+            label_map
+                .global_id_to_index
+                .insert(global_id, v);
             v
         }
     }
@@ -105,45 +122,73 @@ where
     pub fn add_corner_vertex(&mut self, global_id: G, label: LabelId) -> I {
         assert_eq!(label, LDBCVertexParser::get_label_id(global_id));
 
-        if let Some(vertex) = self.global_id_to_index.get(&global_id) {
+        if let Some(vertex) = self.labels[label as usize]
+            .global_id_to_index
+            .get(&global_id)
+        {
             vertex.clone()
         } else {
-            let v = I::new(<I as IndexType>::max().index() - self.labeled_corner_num[label as usize] - 1);
-            self.labeled_corner_num[label as usize] += 1;
-            self.index_to_corner_global_id[label as usize].push(global_id);
-            self.global_id_to_index.insert(global_id, v);
+            let label_map = Arc::make_mut(&mut self.labels[label as usize]);
+            let v = I::new(<I as IndexType>::max().index() - label_map.labeled_corner_num - 1);
+            label_map.labeled_corner_num += 1;
+            label_map
+                .index_to_corner_global_id
+                .push(global_id);
+            label_map
+                .global_id_to_index
+                .insert(global_id, v);
             v
         }
     }
 
     pub fn get_internal_id(&self, global_id: G) -> Option<(LabelId, I)> {
-        if let Some(internal_id) = self.global_id_to_index.get(&global_id) {
-            Some((LDBCVertexParser::get_label_id(global_id), *internal_id))
-        } else {
-            None
-        }
+        let label = LDBCVertexParser::get_label_id(global_id);
+        self.labels
+            .get(label as usize)
+            .and_then(|label_map| label_map.global_id_to_index.get(&global_id))
+            .map(|internal_id| (label, *internal_id))
     }
 
     pub fn get_global_id(&self, label: LabelId, internal_id: I) -> Option<G> {
         let internal_id = internal_id.index();
-        if internal_id < self.labeled_num[label as usize] {
-            self.index_to_global_id[label as usize]
+        let label_map = &self.labels[label as usize];
+        if internal_id < label_map.labeled_num {
+            label_map
+                .index_to_global_id
                 .get(internal_id)
                 .cloned()
         } else {
-            self.index_to_corner_global_id[label as usize]
+            label_map
+                .index_to_corner_global_id
                 .get(<I as IndexType>::max().index() - internal_id - 1)
                 .cloned()
         }
     }
 
+    /// The global ids of the vertices of `label` by their internal ids, removed ones are `G::max()`.
+    pub fn native_global_ids(&self, label: LabelId) -> &Vec<G> {
+        &self.labels[label as usize].index_to_global_id
+    }
+
+    /// The global ids of the corner vertices of `label`, from the largest internal id downwards.
+    pub fn corner_global_ids(&self, label: LabelId) -> &Vec<G> {
+        &self.labels[label as usize].index_to_corner_global_id
+    }
+
+    /// Whether the ids of the vertices of `label` are shared with `other`, i.e., neither map has
+    /// modified them since one was cloned from the other.
+    pub fn is_label_shared(&self, other: &Self, label: LabelId) -> bool {
+        Arc::ptr_eq(&self.labels[label as usize], &other.labels[label as usize])
+    }
+
     pub fn shrink_to_fit(&mut self) {
-        self.global_id_to_index.shrink_to_fit();
-        for list in &mut self.index_to_global_id {
-            list.shrink_to_fit();
-        }
-        for list in &mut self.index_to_corner_global_id {
-            list.shrink_to_fit();
+        for label_map in &mut self.labels {
+            let label_map = Arc::make_mut(label_map);
+            label_map.global_id_to_index.shrink_to_fit();
+            label_map.index_to_global_id.shrink_to_fit();
+            label_map
+                .index_to_corner_global_id
+                .shrink_to_fit();
         }
     }
 
@@ -152,19 +197,22 @@ where
     }
 
     pub fn vertex_num(&self, label: LabelId) -> usize {
-        self.labeled_num[label as usize]
+        self.labels[label as usize].labeled_num
     }
 
     pub fn actual_vertices_num(&self, label: LabelId) -> usize {
-        self.vertices_num[label as usize]
+        self.labels[label as usize].vertices_num
     }
 
     pub fn corner_vertex_num(&self, label: LabelId) -> usize {
-        self.labeled_corner_num[label as usize]
+        self.labels[label as usize].labeled_corner_num
     }
 
     pub fn total_vertex_num(&self) -> usize {
-        self.labeled_num.iter().sum()
+        self.labels
+            .iter()
+            .map(|label_map| label_map.labeled_num)
+            .sum()
     }
 
     pub fn serialize(&self, path: &String) {
@@ -172,28 +220,25 @@ where
         let mut writer = BufWriter::new(f);
         writer.write_u8(self.label_num).unwrap();
 
-        for n in self.labeled_num.iter() {
+        for label_map in self.labels.iter() {
             writer
-                .write_u64::<LittleEndian>(*n as u64)
+                .write_u64::<LittleEndian>(label_map.labeled_num as u64)
                 .unwrap();
         }
-        for n in self.labeled_corner_num.iter() {
+        for label_map in self.labels.iter() {
             writer
-                .write_u64::<LittleEndian>(*n as u64)
+                .write_u64::<LittleEndian>(label_map.labeled_corner_num as u64)
                 .unwrap();
         }
 
-        for i in 0..self.label_num {
-            assert_eq!(self.index_to_global_id[i as usize].len(), self.labeled_num[i as usize]);
-            assert_eq!(
-                self.index_to_corner_global_id[i as usize].len(),
-                self.labeled_corner_num[i as usize]
-            );
+        for label_map in self.labels.iter() {
+            assert_eq!(label_map.index_to_global_id.len(), label_map.labeled_num);
+            assert_eq!(label_map.index_to_corner_global_id.len(), label_map.labeled_corner_num);
 
-            for v in self.index_to_global_id[i as usize].iter() {
+            for v in label_map.index_to_global_id.iter() {
                 v.write(&mut writer).unwrap();
             }
-            for v in self.index_to_corner_global_id[i as usize].iter() {
+            for v in label_map.index_to_corner_global_id.iter() {
                 v.write(&mut writer).unwrap();
             }
         }
@@ -206,88 +251,82 @@ where
         let mut reader = BufReader::new(f);
         self.label_num = reader.read_u8().unwrap();
 
-        self.labeled_num.clear();
-        self.labeled_corner_num.clear();
+        let mut labeled_num = Vec::with_capacity(self.label_num as usize);
         for _ in 0..self.label_num {
-            self.labeled_num
-                .push(reader.read_u64::<LittleEndian>().unwrap() as usize);
+            labeled_num.push(reader.read_u64::<LittleEndian>().unwrap() as usize);
         }
-        self.vertices_num = self.labeled_num.clone();
+        let mut labeled_corner_num = Vec::with_capacity(self.label_num as usize);
         for _ in 0..self.label_num {
-            self.labeled_corner_num
-                .push(reader.read_u64::<LittleEndian>().unwrap() as usize);
+            labeled_corner_num.push(reader.read_u64::<LittleEndian>().unwrap() as usize);
         }
 
-        self.index_to_global_id.clear();
-        self.index_to_corner_global_id.clear();
-        self.global_id_to_index.clear();
+        self.labels.clear();
         for i in 0..self.label_num {
-            let iv_num = self.labeled_num[i as usize];
+            let iv_num = labeled_num[i as usize];
             let mut native_ids = Vec::<G>::with_capacity(iv_num);
             for _ in 0..iv_num {
                 native_ids.push(G::read(&mut reader).unwrap());
             }
 
-            let ov_num = self.labeled_corner_num[i as usize];
+            let ov_num = labeled_corner_num[i as usize];
             let mut corner_ids = Vec::<G>::with_capacity(ov_num);
             for _ in 0..ov_num {
                 corner_ids.push(G::read(&mut reader).unwrap());
             }
 
+            // the removed vertices are kept as placeholders of `G::max()`
+            let global_id_to_index = DashMap::new();
             native_ids
                 .par_iter()
                 .enumerate()
+                .filter(|(_, v)| **v != <G as IndexType>::max())
                 .for_each(|(index, v)| {
-                    self.global_id_to_index
-                        .insert(*v, I::new(index));
+                    global_id_to_index.insert(*v, I::new(index));
                 });
-
-            self.index_to_global_id.push(native_ids);
-            self.index_to_corner_global_id.push(corner_ids);
+            let vertices_num = native_ids
+                .iter()
+                .filter(|v| **v != <G as IndexType>::max())
+                .count();
+
+            self.labels.push(Arc::new(LabelVertexMap {
+                global_id_to_index,
+                labeled_num: iv_num,
+                vertices_num,
+                index_to_global_id: native_ids,
+                labeled_corner_num: ov_num,
+                index_to_corner_global_id: corner_ids,
+            }));
         }
-
-        // for i in 0..self.label_num {
-        //     let mut index = 0_usize;
-        //     for v in self.index_to_global_id[i as usize].iter() {
-        //         self.global_id_to_index
-        //             .insert(*v, I::new(index));
-        //         index += 1;
-        //     }
-        // }
     }
 
     pub fn desc(&self) {
-        info!("label_num = {}, entry num = {}", self.label_num, self.global_id_to_index.len());
-        for i in 0..self.label_num {
+        let entry_num: usize = self
+            .labels
+            .iter()
+            .map(|label_map| label_map.global_id_to_index.len())
+            .sum();
+        info!("label_num = {}, entry num = {}", self.label_num, entry_num);
+        for (i, label_map) in self.labels.iter().enumerate() {
             info!(
                 "label-{}: native: {}, corner: {}",
                 i,
-                self.index_to_global_id[i as usize].len(),
-                self.index_to_corner_global_id[i as usize].len()
+                label_map.index_to_global_id.len(),
+                label_map.index_to_corner_global_id.len()
             )
         }
     }
 
     pub fn is_same(&self, other: &Self) -> bool {
-        // if self.global_id_to_index != other.global_id_to_index {
-        //     return false;
-        // }
         if self.label_num != other.label_num {
             return false;
         }
-        if self.index_to_global_id != other.index_to_global_id {
-            return false;
-        }
-        if self.labeled_corner_num != other.labeled_corner_num {
-            return false;
-        }
-        if self.index_to_corner_global_id != other.index_to_corner_global_id {
-            return false;
-        }
-        if self.label_num != other.label_num {
-            return false;
-        }
-
-        return true;
+        self.labels
+            .iter()
+            .zip(other.labels.iter())
+            .all(|(a, b)| {
+                a.index_to_global_id == b.index_to_global_id
+                    && a.labeled_corner_num == b.labeled_corner_num
+                    && a.index_to_corner_global_id == b.index_to_corner_global_id
+            })
     }
 }
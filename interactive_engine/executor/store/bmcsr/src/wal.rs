@@ -0,0 +1,428 @@
+//
+//! Copyright 2023 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+//! The write-ahead log of the batch updates applied to a partition of `GraphDB`.
+//!
+//! Each batch of `WriteOperation`s is appended to the log (and synced to disk) before it is applied
+//! in place, as one record of `[seq: u64][len: u64][checksum: u64][payload: len bytes]`. A record
+//! torn by a crash fails its length or checksum check and is discarded together with the tail after
+//! it. A checkpoint serializes the whole partition into a temporary directory, swaps it with the
+//! partition directory, and then truncates the log; the sequence number of the last batch covered by
+//! the checkpoint is kept in the partition directory, so that a record is never replayed twice.
+
+use std::fs::{self, File, OpenOptions};
+use std::hash::Hasher;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use fnv::FnvHasher;
+use pegasus_common::codec::{Decode, Encode};
+
+use crate::error::GDBResult;
+use crate::graph::IndexType;
+use crate::graph_db::GraphDB;
+use crate::graph_modifier::{apply_write_operations, WriteOperation};
+use crate::types::*;
+
+const RECORD_HEADER_SIZE: usize = 24;
+
+/// A durable batch of write operations in the log.
+pub struct WalRecord {
+    pub seq: u64,
+    pub write_operations: Vec<WriteOperation>,
+}
+
+pub struct WriteAheadLog {
+    root_dir: PathBuf,
+    partition: usize,
+    file: File,
+    next_seq: u64,
+    checkpoint_interval: usize,
+    uncheckpointed: usize,
+}
+
+impl WriteAheadLog {
+    /// Open the log of the given partition of the graph stored in `dir`, discarding the torn tail
+    /// left by a crash if any.
+    pub fn open<P: AsRef<Path>>(dir: P, partition: usize) -> GDBResult<Self> {
+        let root_dir = dir.as_ref().to_path_buf();
+        recover_checkpoint(&root_dir, partition)?;
+        let path = wal_path(&root_dir, partition);
+        let (records, valid_len) = read_records(&path)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        if file.metadata()?.len() > valid_len {
+            warn!("discard the torn tail of write-ahead log {:?} from offset {}", path, valid_len);
+            file.set_len(valid_len)?;
+            file.sync_all()?;
+        }
+        let checkpoint_seq = read_checkpoint_seq(&partition_path(&root_dir, partition))?;
+        let last_seq = records
+            .last()
+            .map(|record| record.seq)
+            .unwrap_or(0)
+            .max(checkpoint_seq);
+        let uncheckpointed = records
+            .iter()
+            .filter(|record| record.seq > checkpoint_seq)
+            .count();
+        Ok(WriteAheadLog {
+            root_dir,
+            partition,
+            file,
+            next_seq: last_seq + 1,
+            checkpoint_interval: 0,
+            uncheckpointed,
+        })
+    }
+
+    /// Checkpoint the graph once `interval` batches are logged since the last checkpoint,
+    /// where `0` (the default) means never checkpointing automatically.
+    pub fn with_checkpoint_interval(mut self, interval: usize) -> Self {
+        self.checkpoint_interval = interval;
+        self
+    }
+
+    /// Append a batch of write operations to the log and sync it to disk, returning its sequence number.
+    pub fn append(&mut self, write_operations: &Vec<WriteOperation>) -> GDBResult<u64> {
+        let mut payload = vec![];
+        write_operations.write_to(&mut payload)?;
+        let seq = self.next_seq;
+        let mut record = Vec::with_capacity(RECORD_HEADER_SIZE + payload.len());
+        record.write_u64::<LittleEndian>(seq)?;
+        record.write_u64::<LittleEndian>(payload.len() as u64)?;
+        record.write_u64::<LittleEndian>(checksum(&payload))?;
+        record.extend_from_slice(&payload);
+        self.file.write_all(&record)?;
+        self.file.sync_data()?;
+        self.next_seq += 1;
+        self.uncheckpointed += 1;
+        Ok(seq)
+    }
+
+    pub fn should_checkpoint(&self) -> bool {
+        self.checkpoint_interval > 0 && self.uncheckpointed >= self.checkpoint_interval
+    }
+
+    /// Persist the graph, which must have applied every batch in the log, and truncate the log.
+    /// The graph should be loaded without edge trimming, as the trimmed csrs are not persisted.
+    pub fn checkpoint<G, I>(&mut self, graph: &GraphDB<G, I>) -> GDBResult<()>
+    where
+        G: Eq + IndexType + Send + Sync,
+        I: IndexType + Send + Sync,
+    {
+        let partition_dir = partition_path(&self.root_dir, self.partition);
+        let tmp_dir = checkpoint_tmp_path(&self.root_dir, self.partition);
+        let old_dir = checkpoint_old_path(&self.root_dir, self.partition);
+        if tmp_dir.exists() {
+            fs::remove_dir_all(&tmp_dir)?;
+        }
+        graph.serialize(&tmp_dir)?;
+        write_checkpoint_seq(&tmp_dir, self.next_seq - 1)?;
+        sync_dir(&tmp_dir)?;
+
+        // the renames are synced before the log is truncated, so that the log always covers the
+        // batches missing from the partition directory found after a crash
+        let parent_dir = self.root_dir.join(DIR_BINARY_DATA);
+        if partition_dir.exists() {
+            fs::rename(&partition_dir, &old_dir)?;
+            sync_file(&parent_dir)?;
+        }
+        fs::rename(&tmp_dir, &partition_dir)?;
+        sync_file(&parent_dir)?;
+        if old_dir.exists() {
+            fs::remove_dir_all(&old_dir)?;
+        }
+
+        self.file.set_len(0)?;
+        self.file.sync_all()?;
+        self.uncheckpointed = 0;
+        info!("checkpoint partition {} at batch {}", self.partition, self.next_seq - 1);
+        Ok(())
+    }
+}
+
+/// Replay the batches logged after the last checkpoint of the partition onto `graph`, which is
+/// freshly loaded from the partition directory. Return the number of replayed batches.
+pub fn replay(graph: &mut GraphDB<usize, usize>, root_dir: &Path, partition: usize) -> GDBResult<usize> {
+    let records = pending_records(root_dir, partition)?;
+    let parallel = rayon::current_num_threads() as u32;
+    let replayed = records.len();
+    for record in records {
+        apply_write_operations(graph, record.write_operations, parallel);
+    }
+    Ok(replayed)
+}
+
+/// Whether the log of the partition holds batches logged after its last checkpoint.
+pub fn has_pending_records(root_dir: &Path, partition: usize) -> GDBResult<bool> {
+    Ok(!pending_records(root_dir, partition)?.is_empty())
+}
+
+fn pending_records(root_dir: &Path, partition: usize) -> GDBResult<Vec<WalRecord>> {
+    let path = wal_path(root_dir, partition);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let checkpoint_seq = read_checkpoint_seq(&partition_path(root_dir, partition))?;
+    let (records, _) = read_records(&path)?;
+    Ok(records
+        .into_iter()
+        .filter(|record| record.seq > checkpoint_seq)
+        .collect())
+}
+
+/// Complete or roll back a checkpoint of the partition interrupted by a crash. The partition directory
+/// is only moved away after the temporary directory is complete, so a missing partition directory
+/// means the temporary one is to be moved in, and otherwise the temporary one is incomplete.
+pub fn recover_checkpoint(root_dir: &Path, partition: usize) -> GDBResult<()> {
+    let partition_dir = partition_path(root_dir, partition);
+    let tmp_dir = checkpoint_tmp_path(root_dir, partition);
+    let old_dir = checkpoint_old_path(root_dir, partition);
+    if !partition_dir.exists() && tmp_dir.exists() {
+        info!("complete the interrupted checkpoint of partition {}", partition);
+        fs::rename(&tmp_dir, &partition_dir)?;
+        sync_file(&root_dir.join(DIR_BINARY_DATA))?;
+    }
+    if tmp_dir.exists() {
+        info!("discard the incomplete checkpoint of partition {}", partition);
+        fs::remove_dir_all(&tmp_dir)?;
+    }
+    if old_dir.exists() {
+        fs::remove_dir_all(&old_dir)?;
+    }
+    Ok(())
+}
+
+/// Read the complete records of the log, together with the length of the valid prefix of the log.
+pub fn read_records(path: &Path) -> GDBResult<(Vec<WalRecord>, u64)> {
+    let mut records = vec![];
+    if !path.exists() {
+        return Ok((records, 0));
+    }
+    let mut buf = vec![];
+    File::open(path)?.read_to_end(&mut buf)?;
+    let mut offset = 0;
+    while buf.len() - offset >= RECORD_HEADER_SIZE {
+        let mut header = &buf[offset..offset + RECORD_HEADER_SIZE];
+        let seq = header.read_u64::<LittleEndian>()?;
+        let len = header.read_u64::<LittleEndian>()? as usize;
+        let expected_checksum = header.read_u64::<LittleEndian>()?;
+        let start = offset + RECORD_HEADER_SIZE;
+        if buf.len() - start < len {
+            break;
+        }
+        let payload = &buf[start..start + len];
+        if checksum(payload) != expected_checksum {
+            break;
+        }
+        let mut reader = payload;
+        let write_operations = Vec::<WriteOperation>::read_from(&mut reader)?;
+        records.push(WalRecord { seq, write_operations });
+        offset = start + len;
+    }
+    Ok((records, offset as u64))
+}
+
+fn checksum(payload: &[u8]) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hasher.write(payload);
+    hasher.finish()
+}
+
+fn read_checkpoint_seq(partition_dir: &Path) -> GDBResult<u64> {
+    let path = partition_dir.join(FILE_CHECKPOINT);
+    if path.exists() {
+        Ok(File::open(path)?.read_u64::<LittleEndian>()?)
+    } else {
+        Ok(0)
+    }
+}
+
+fn write_checkpoint_seq(partition_dir: &Path, seq: u64) -> GDBResult<()> {
+    let mut file = File::create(partition_dir.join(FILE_CHECKPOINT))?;
+    file.write_u64::<LittleEndian>(seq)?;
+    Ok(())
+}
+
+/// Sync the files in `dir` and then the directory itself, so that their entries are durable as well.
+fn sync_dir(dir: &Path) -> GDBResult<()> {
+    for entry in fs::read_dir(dir)? {
+        sync_file(&entry?.path())?;
+    }
+    sync_file(dir)
+}
+
+/// Sync a file or a directory. Syncing a directory makes the renames of its entries durable.
+fn sync_file(path: &Path) -> GDBResult<()> {
+    File::open(path)?.sync_all()?;
+    Ok(())
+}
+
+fn wal_path(root_dir: &Path, partition: usize) -> PathBuf {
+    root_dir
+        .join(DIR_BINARY_DATA)
+        .join(format!("partition_{}.wal", partition))
+}
+
+fn partition_path(root_dir: &Path, partition: usize) -> PathBuf {
+    root_dir
+        .join(DIR_BINARY_DATA)
+        .join(format!("partition_{}", partition))
+}
+
+fn checkpoint_tmp_path(root_dir: &Path, partition: usize) -> PathBuf {
+    root_dir
+        .join(DIR_BINARY_DATA)
+        .join(format!("partition_{}.checkpoint", partition))
+}
+
+fn checkpoint_old_path(root_dir: &Path, partition: usize) -> PathBuf {
+    root_dir
+        .join(DIR_BINARY_DATA)
+        .join(format!("partition_{}.old", partition))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn test_replay_batch_interrupted_before_delete() {
+        let expected_dir = tempdir::TempDir::new("bmcsr_wal_expected").unwrap();
+        build_modern_graph(expected_dir.path());
+        let mut expected = load(expected_dir.path());
+        apply_write_operations(&mut expected, batch(), 1);
+
+        let root = tempdir::TempDir::new("bmcsr_wal_replay").unwrap();
+        build_modern_graph(root.path());
+        let mut graph = load(root.path());
+        let mut wal = WriteAheadLog::open(root.path(), 0).unwrap();
+        let mut write_operations = batch();
+        assert_eq!(wal.append(&write_operations).unwrap(), 1);
+        // crash after the insert phase, before deleting the vertices
+        write_operations.truncate(2);
+        apply_write_operations(&mut graph, write_operations, 1);
+        drop(graph);
+        drop(wal);
+
+        let recovered = load(root.path());
+        assert_eq!(summary(&recovered), summary(&expected));
+        assert!(recovered.get_vertex(person_id(7)).is_some());
+        assert!(recovered.get_vertex(person_id(2)).is_none());
+    }
+
+    #[test]
+    fn test_discard_torn_record() {
+        let root = tempdir::TempDir::new("bmcsr_wal_torn").unwrap();
+        build_modern_graph(root.path());
+        let original = summary(&load(root.path()));
+
+        let mut wal = WriteAheadLog::open(root.path(), 0).unwrap();
+        wal.append(&vec![insert_persons(vec![7])])
+            .unwrap();
+        drop(wal);
+        // a crash in the middle of appending the second batch
+        let path = wal_path(root.path(), 0);
+        let mut record = vec![];
+        record.write_u64::<LittleEndian>(2).unwrap();
+        record.write_u64::<LittleEndian>(1024).unwrap();
+        record.write_u64::<LittleEndian>(0).unwrap();
+        record.extend_from_slice(&[1, 2, 3]);
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(&record)
+            .unwrap();
+
+        let recovered = load(root.path());
+        let (persons, _) = summary(&recovered);
+        assert_eq!(persons.len(), original.0.len() + 1);
+        assert!(recovered.get_vertex(person_id(7)).is_some());
+
+        let mut wal = WriteAheadLog::open(root.path(), 0).unwrap();
+        assert_eq!(
+            wal.append(&vec![insert_persons(vec![8])])
+                .unwrap(),
+            2
+        );
+        let (records, _) = read_records(&path).unwrap();
+        assert_eq!(
+            records
+                .iter()
+                .map(|r| r.seq)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_and_replay_tail() {
+        let expected_dir = tempdir::TempDir::new("bmcsr_wal_expected").unwrap();
+        build_modern_graph(expected_dir.path());
+        let mut expected = load(expected_dir.path());
+        apply_write_operations(&mut expected, batch(), 1);
+        apply_write_operations(&mut expected, vec![insert_knows(vec![(8, 1)])], 1);
+
+        let root = tempdir::TempDir::new("bmcsr_wal_checkpoint").unwrap();
+        build_modern_graph(root.path());
+        let mut graph = load(root.path());
+        let mut wal = WriteAheadLog::open(root.path(), 0)
+            .unwrap()
+            .with_checkpoint_interval(1);
+        crate::graph_modifier::apply_write_operations_with_wal(&mut graph, &mut wal, batch(), 1).unwrap();
+        assert_eq!(
+            read_records(&wal_path(root.path(), 0))
+                .unwrap()
+                .0
+                .len(),
+            0
+        );
+        assert_eq!(read_checkpoint_seq(&partition_path(root.path(), 0)).unwrap(), 1);
+
+        // the tail after the checkpoint is lost in memory, but kept in the log
+        wal.append(&vec![insert_knows(vec![(8, 1)])])
+            .unwrap();
+        drop(graph);
+        drop(wal);
+        assert_eq!(summary(&load(root.path())), summary(&expected));
+
+        // a crash after the partition directory is moved away for the next checkpoint
+        let graph = load(root.path());
+        let tmp_dir = checkpoint_tmp_path(root.path(), 0);
+        graph.serialize(&tmp_dir).unwrap();
+        write_checkpoint_seq(&tmp_dir, 2).unwrap();
+        fs::rename(partition_path(root.path(), 0), checkpoint_old_path(root.path(), 0)).unwrap();
+        drop(graph);
+
+        let recovered = load(root.path());
+        assert!(!tmp_dir.exists());
+        assert!(!checkpoint_old_path(root.path(), 0).exists());
+        // the log is not truncated, but the batch covered by the checkpoint is not replayed twice
+        assert_eq!(
+            read_records(&wal_path(root.path(), 0))
+                .unwrap()
+                .0
+                .len(),
+            1
+        );
+        assert_eq!(summary(&recovered), summary(&expected));
+    }
+}
@@ -46,6 +46,20 @@ pub trait GlobalGraphQuery: Send + Sync {
         condition: Option<&Condition>, dedup_prop_ids: Option<&Vec<PropId>>,
         output_prop_ids: Option<&Vec<PropId>>, limit: usize,
     ) -> Box<dyn Iterator<Item = (VertexId, Self::EI)>>;
+    /// Returns (property id, descending) of the sort key of `edge_label` at `si`, if adjacent edges of
+    /// the label can be read in the order of the property.
+    fn get_edge_sort_key(&self, si: SnapshotId, edge_label: LabelId) -> Option<(PropId, bool)>;
+    /// Like `get_out_edges` of a single `edge_label`, but out edges of each vertex are returned in
+    /// the order of the sort key of the label. It's only valid if `get_edge_sort_key` returns some.
+    fn get_sorted_out_edges(
+        &self, si: SnapshotId, src_ids: Vec<PartitionVertexIds>, edge_label: LabelId,
+        condition: Option<&Condition>, output_prop_ids: Option<&Vec<PropId>>, limit: usize,
+    ) -> Box<dyn Iterator<Item = (VertexId, Self::EI)>>;
+    /// Like `get_sorted_out_edges`, for in edges of each vertex.
+    fn get_sorted_in_edges(
+        &self, si: SnapshotId, dst_ids: Vec<PartitionVertexIds>, edge_label: LabelId,
+        condition: Option<&Condition>, output_prop_ids: Option<&Vec<PropId>>, limit: usize,
+    ) -> Box<dyn Iterator<Item = (VertexId, Self::EI)>>;
     fn count_out_edges(
         &self, si: SnapshotId, src_ids: Vec<PartitionVertexIds>, edge_labels: &Vec<LabelId>,
         condition: Option<&Condition>,
@@ -84,4 +98,11 @@ pub trait GlobalGraphQuery: Send + Sync {
     fn translate_vertex_id(&self, vertex_id: VertexId) -> VertexId;
 
     fn get_schema(&self, si: SnapshotId) -> Option<Arc<dyn Schema>>;
+
+    /// Snapshots before the returned one may have been garbage collected, so queries must not read
+    /// them.
+    fn get_gc_snapshot_id(&self) -> SnapshotId;
+
+    /// Snapshots after the returned one have not been written yet, so queries must not read them.
+    fn get_latest_snapshot_id(&self) -> SnapshotId;
 }
@@ -225,6 +225,71 @@ impl GlobalGraphQuery for GlobalGraph {
         Box::new(res.into_iter())
     }
 
+    fn get_edge_sort_key(&self, si: SnapshotId, edge_label: LabelId) -> Option<(PropId, bool)> {
+        // all partitions share the schema
+        let store = self.graph_partitions.values().next()?;
+        match store.get_edge_sort_key(si, edge_label as i32) {
+            Ok(sort_key) => sort_key.map(|(prop_id, descending)| (prop_id as PropId, descending)),
+            Err(e) => {
+                // without the sort key the edges are sorted by the runtime
+                error!("get sort key of edge#{} at si#{} failed: {:?}", edge_label, si, e);
+                None
+            }
+        }
+    }
+
+    fn get_sorted_out_edges(
+        &self, si: SnapshotId, src_ids: Vec<PartitionVertexIds>, edge_label: LabelId,
+        condition: Option<&Condition>, output_prop_ids: Option<&Vec<PropId>>, limit: usize,
+    ) -> Box<dyn Iterator<Item = (VertexId, Self::EI)>> {
+        let mut res: Vec<(VertexId, Self::EI)> = Vec::new();
+        let property_ids = Self::parse_property_id(output_prop_ids);
+        for (partition_id, vertex_ids) in src_ids {
+            if let Some(store) = self.graph_partitions.get(&partition_id) {
+                for vertex_id in vertex_ids {
+                    let edges = store
+                        .get_sorted_out_edges(
+                            si,
+                            vertex_id,
+                            edge_label as i32,
+                            condition,
+                            property_ids.as_ref(),
+                            limit,
+                        )
+                        .unwrap();
+                    res.push((vertex_id, Box::new(edges.map(|e| e.unwrap()))));
+                }
+            }
+        }
+        Box::new(res.into_iter())
+    }
+
+    fn get_sorted_in_edges(
+        &self, si: SnapshotId, dst_ids: Vec<PartitionVertexIds>, edge_label: LabelId,
+        condition: Option<&Condition>, output_prop_ids: Option<&Vec<PropId>>, limit: usize,
+    ) -> Box<dyn Iterator<Item = (VertexId, Self::EI)>> {
+        let mut res: Vec<(VertexId, Self::EI)> = Vec::new();
+        let property_ids = Self::parse_property_id(output_prop_ids);
+        for (partition_id, vertex_ids) in dst_ids {
+            if let Some(store) = self.graph_partitions.get(&partition_id) {
+                for vertex_id in vertex_ids {
+                    let edges = store
+                        .get_sorted_in_edges(
+                            si,
+                            vertex_id,
+                            edge_label as i32,
+                            condition,
+                            property_ids.as_ref(),
+                            limit,
+                        )
+                        .unwrap();
+                    res.push((vertex_id, Box::new(edges.map(|e| e.unwrap()))));
+                }
+            }
+        }
+        Box::new(res.into_iter())
+    }
+
     fn count_out_edges(
         &self, si: SnapshotId, src_ids: Vec<PartitionVertexIds>, edge_labels: &Vec<LabelId>,
         condition: Option<&Condition>,
@@ -408,6 +473,23 @@ impl GlobalGraphQuery for GlobalGraph {
         let graph_def = partition.get_graph_def().ok()?;
         Some(Arc::new(GlobalGraphSchema::new(graph_def)))
     }
+
+    fn get_gc_snapshot_id(&self) -> SnapshotId {
+        self.graph_partitions
+            .values()
+            .map(|partition| partition.get_gc_snapshot_id())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn get_latest_snapshot_id(&self) -> SnapshotId {
+        // partitions without writes at a snapshot don't see it, so take the most advanced one
+        self.graph_partitions
+            .values()
+            .map(|partition| partition.get_latest_snapshot_id())
+            .max()
+            .unwrap_or(0)
+    }
 }
 
 thread_local! {
@@ -532,6 +614,17 @@ impl GraphPartitionManager for GlobalGraph {
                             data.write(i.as_slice()).unwrap();
                         }
                     }
+                    Property::DateTime(v) => {
+                        data.write_i64::<BigEndian>(*v).unwrap();
+                    }
+                    Property::DateTimeWithTz(v, offset) => {
+                        data.write_i64::<BigEndian>(*v).unwrap();
+                        data.write_i32::<BigEndian>(*offset).unwrap();
+                    }
+                    Property::Decimal(v, scale) => {
+                        data.write_i128::<BigEndian>(*v).unwrap();
+                        data.write_u8(*scale).unwrap();
+                    }
                     Property::Null => {
                         unimplemented!()
                     }
@@ -558,3 +651,43 @@ fn floor_div(x: i64, y: i64) -> i64 {
 fn floor_mod(x: i64, y: i64) -> i64 {
     x - floor_div(x, y) * y
 }
+
+#[cfg(test)]
+mod tests {
+    use groot_store::db::api::GraphConfigBuilder;
+    use groot_store::db::util::fs;
+
+    use super::*;
+
+    fn open_store(path: &str) -> Arc<GraphStore> {
+        let mut builder = GraphConfigBuilder::new();
+        builder.set_storage_engine("rocksdb");
+        builder.add_storage_option("store.data.path", path);
+        Arc::new(GraphStore::open(&builder.build()).unwrap())
+    }
+
+    #[test]
+    fn test_gc_snapshot_id_after_reopen() {
+        let path = "global_graph_test/test_gc_snapshot_id_after_reopen";
+        fs::rmr(path).unwrap();
+        let partition_path = |partition: PartitionId| format!("{}/{}", path, partition);
+        {
+            let store = open_store(&partition_path(0));
+            store.gc(5).unwrap();
+            let mut graph = GlobalGraph::empty(2);
+            graph.add_partition(0, store);
+            graph.add_partition(1, open_store(&partition_path(1)));
+            assert_eq!(graph.get_gc_snapshot_id(), 5);
+            assert_eq!(graph.get_latest_snapshot_id(), 5);
+        }
+        // the watermark survives a restart, so pinned snapshots below it stay rejected
+        let mut graph = GlobalGraph::empty(2);
+        graph.add_partition(0, open_store(&partition_path(0)));
+        graph.add_partition(1, open_store(&partition_path(1)));
+        assert_eq!(graph.get_gc_snapshot_id(), 5);
+        // no write since the restart, the watermark is the latest snapshot known
+        assert_eq!(graph.get_latest_snapshot_id(), 5);
+        drop(graph);
+        fs::rmr(path).unwrap();
+    }
+}
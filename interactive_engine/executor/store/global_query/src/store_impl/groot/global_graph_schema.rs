@@ -62,6 +62,10 @@ impl Schema for GlobalGraphSchema {
             ValueType::FloatList => Some(DataType::ListFloat),
             ValueType::DoubleList => Some(DataType::ListDouble),
             ValueType::StringList => Some(DataType::ListString),
+            ValueType::Date => Some(DataType::Date),
+            ValueType::DateTime => Some(DataType::DateTime),
+            ValueType::DateTimeWithTz => Some(DataType::DateTimeWithTz),
+            ValueType::Decimal => Some(DataType::Decimal),
         }
     }
 
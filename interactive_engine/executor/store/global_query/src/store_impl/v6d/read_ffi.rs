@@ -1342,6 +1342,25 @@ impl GlobalGraphQuery for FFIGraphStore {
         )
     }
 
+    fn get_edge_sort_key(&self, _si: i64, _edge_label: LabelId) -> Option<(u32, bool)> {
+        // vineyard graphs have no sort keys
+        None
+    }
+
+    fn get_sorted_out_edges(
+        &self, _si: i64, _src_ids: Vec<(PartitionId, Vec<VertexId>)>, _edge_label: LabelId,
+        _condition: Option<&Condition>, _output_prop_ids: Option<&Vec<u32>>, _limit: usize,
+    ) -> Box<dyn Iterator<Item = (VertexId, Self::EI)>> {
+        unimplemented!("vineyard graphs have no sort keys")
+    }
+
+    fn get_sorted_in_edges(
+        &self, _si: i64, _dst_ids: Vec<(PartitionId, Vec<VertexId>)>, _edge_label: LabelId,
+        _condition: Option<&Condition>, _output_prop_ids: Option<&Vec<u32>>, _limit: usize,
+    ) -> Box<dyn Iterator<Item = (VertexId, Self::EI)>> {
+        unimplemented!("vineyard graphs have no sort keys")
+    }
+
     fn count_out_edges(
         &self, _si: i64, src_ids: Vec<(PartitionId, Vec<VertexId>)>, edge_labels: &Vec<LabelId>,
         _condition: Option<&Condition>,
@@ -1548,6 +1567,16 @@ impl GlobalGraphQuery for FFIGraphStore {
         let ret = FFISchema::new(schema);
         Some(Arc::new(ret))
     }
+
+    fn get_gc_snapshot_id(&self) -> i64 {
+        // vineyard graphs are immutable, there's only one snapshot
+        0
+    }
+
+    fn get_latest_snapshot_id(&self) -> i64 {
+        // every snapshot reads the same immutable graph
+        i64::MAX
+    }
 }
 
 unsafe impl Send for FFIGraphStore {}
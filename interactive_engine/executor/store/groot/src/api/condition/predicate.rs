@@ -58,6 +58,20 @@ pub struct CmpCondition {
     right: Operand,
 }
 
+impl CmpCondition {
+    pub fn get_left(&self) -> &Operand {
+        &self.left
+    }
+
+    pub fn get_op(&self) -> CmpOperator {
+        self.op
+    }
+
+    pub fn get_right(&self) -> &Operand {
+        &self.right
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CmpOperator {
     Equal,
@@ -19,7 +19,7 @@ use std::io::Read;
 use std::io::Write;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use dyn_type::{object::RawType, BorrowObject, Object, Primitives};
+use dyn_type::{object::RawType, BorrowObject, DateTimeFormats, Object, Primitives};
 
 use crate::error::*;
 use crate::schema::prelude::*;
@@ -44,6 +44,12 @@ pub enum Property {
     ListDouble(Vec<f64>),
     ListString(Vec<String>),
     ListBytes(Vec<Vec<u8>>),
+    /// milliseconds since 1970-01-01T00:00:00Z
+    DateTime(i64),
+    /// milliseconds since 1970-01-01T00:00:00Z and the offset east of UTC in seconds
+    DateTimeWithTz(i64, i32),
+    /// unscaled value and scale, the value is `unscaled * 10^(-scale)`
+    Decimal(i128, u8),
     Null,
     Unknown,
 }
@@ -58,6 +64,14 @@ impl PartialOrd for Property {
             (Property::Long(left), Property::Long(right)) => left.partial_cmp(right),
             (Property::Float(left), Property::Float(right)) => left.partial_cmp(right),
             (Property::Double(left), Property::Double(right)) => left.partial_cmp(right),
+            (Property::Decimal(l, ls), Property::Decimal(r, rs)) => cmp_decimal((*l, *ls), (*r, *rs)),
+            // cmp between datetimes of any time zone, by the instant
+            (Property::DateTime(left), Property::DateTime(right))
+            | (Property::DateTime(left), Property::DateTimeWithTz(right, _))
+            | (Property::DateTimeWithTz(left, _), Property::DateTime(right))
+            | (Property::DateTimeWithTz(left, _), Property::DateTimeWithTz(right, _)) => {
+                left.partial_cmp(right)
+            }
             // cmp between numbers, if types not match
             // if both are integers, cast to long
             // else cast to double
@@ -66,11 +80,13 @@ impl PartialOrd for Property {
             | (Property::Long(_), _)
             | (Property::Float(_), _)
             | (Property::Double(_), _)
+            | (Property::Decimal(..), _)
             | (_, Property::Short(_))
             | (_, Property::Int(_))
             | (_, Property::Long(_))
             | (_, Property::Float(_))
-            | (_, Property::Double(_)) => {
+            | (_, Property::Double(_))
+            | (_, Property::Decimal(..)) => {
                 if self.is_float_type() || other.is_float_type() {
                     let left = unwrap_ok_or!(self.get_double(), _, return None);
                     let right = unwrap_ok_or!(other.get_double(), _, return None);
@@ -126,6 +142,20 @@ impl PartialEq for Property {
 }
 
 impl Property {
+    /// groot has no time of day type, which can't be compared with any property and is rejected
+    pub fn from_date_format(d: &DateTimeFormats) -> GraphResult<Property> {
+        match d {
+            DateTimeFormats::Date(date) => Ok(Property::Date(date.format("%Y-%m-%d").to_string())),
+            DateTimeFormats::Time(time) => {
+                Err(GraphError::invalid_condition(format!("unsupport time of day {}", time)))
+            }
+            DateTimeFormats::DateTime(dt) => Ok(Property::DateTime(dt.and_utc().timestamp_millis())),
+            DateTimeFormats::DateTimeWithTz(dt) => {
+                Ok(Property::DateTimeWithTz(dt.timestamp_millis(), dt.offset().local_minus_utc()))
+            }
+        }
+    }
+
     fn from_primitive(p: &Primitives) -> GraphResult<Property> {
         match p {
             Primitives::Byte(v) => Ok(Property::Char(*v as u8)),
@@ -148,6 +178,7 @@ impl Property {
             BorrowObject::String(s) => Ok(Property::String(s.to_owned())),
             BorrowObject::Blob(bytes) => Ok(Property::Bytes(bytes.to_vec())),
             BorrowObject::Vector(v) => objects_to_list_property(v),
+            BorrowObject::DateFormat(d) => Self::from_date_format(d),
             _ => Err(GraphError::invalid_condition(format!("unsupport object type {:?}", bobj))),
         }
     }
@@ -201,6 +232,45 @@ impl Property {
     }
 }
 
+/// compare two decimals exactly if they can be rescaled to the same scale without overflow,
+/// else compare them as doubles
+pub fn cmp_decimal(left: (i128, u8), right: (i128, u8)) -> Option<std::cmp::Ordering> {
+    let (l, ls) = left;
+    let (r, rs) = right;
+    let rescaled = if ls >= rs {
+        10i128
+            .checked_pow((ls - rs) as u32)
+            .and_then(|m| r.checked_mul(m))
+            .map(|r| (l, r))
+    } else {
+        10i128
+            .checked_pow((rs - ls) as u32)
+            .and_then(|m| l.checked_mul(m))
+            .map(|l| (l, r))
+    };
+    match rescaled {
+        Some((l, r)) => Some(l.cmp(&r)),
+        None => decimal_to_double(l, ls).partial_cmp(&decimal_to_double(r, rs)),
+    }
+}
+
+fn decimal_to_double(unscaled: i128, scale: u8) -> f64 {
+    unscaled as f64 / 10f64.powi(scale as i32)
+}
+
+/// the exact notation of the decimal `unscaled * 10^(-scale)`, e.g. "-0.05" of (-5, 2)
+pub fn decimal_to_string(unscaled: i128, scale: u8) -> String {
+    let sign = if unscaled < 0 { "-" } else { "" };
+    let digits = unscaled.unsigned_abs().to_string();
+    let scale = scale as usize;
+    if scale == 0 {
+        return format!("{}{}", sign, digits);
+    }
+    let digits = format!("{:0>width$}", digits, width = scale + 1);
+    let (int, frac) = digits.split_at(digits.len() - scale);
+    format!("{}{}.{}", sign, int, frac)
+}
+
 fn objects_to_list_property(v: &[Object]) -> GraphResult<Property> {
     if v.len() == 0 {
         return Err(GraphError::invalid_condition("empty list object".to_owned()));
@@ -489,6 +559,20 @@ impl Property {
                 }
                 ret
             }
+            Property::DateTime(ref v) => {
+                ret.write_i64::<BigEndian>(*v).unwrap();
+                ret
+            }
+            Property::DateTimeWithTz(ref v, ref offset) => {
+                ret.write_i64::<BigEndian>(*v).unwrap();
+                ret.write_i32::<BigEndian>(*offset).unwrap();
+                ret
+            }
+            Property::Decimal(ref v, ref scale) => {
+                ret.write_i128::<BigEndian>(*v).unwrap();
+                ret.write_u8(*scale).unwrap();
+                ret
+            }
             Property::Null => {
                 panic!("property is null");
             }
@@ -575,7 +659,7 @@ impl Property {
 
     fn is_float_type(&self) -> bool {
         match self {
-            &Property::Float(_) | &Property::Double(_) => true,
+            &Property::Float(_) | &Property::Double(_) | &Property::Decimal(..) => true,
             _ => false,
         }
     }
@@ -823,6 +907,7 @@ impl Property {
             &Property::Long(d) => Ok(d as f64),
             &Property::Float(d) => Ok(d as f64),
             &Property::Double(d) => Ok(d),
+            &Property::Decimal(unscaled, scale) => Ok(decimal_to_double(unscaled, scale)),
             _ => Err(format!("get double value fail from property=>{:?}", self)),
         }
     }
@@ -970,6 +1055,8 @@ pub fn parse_proerty_as_string(data: Vec<u8>, data_type: &DataType) -> Option<St
 #[cfg(test)]
 mod tests {
 
+    use chrono::{FixedOffset, NaiveDate, NaiveTime, TimeZone};
+
     use super::*;
 
     #[test]
@@ -1116,4 +1203,40 @@ mod tests {
         let p2 = Property::Float(1.0);
         assert!(p1.contains(&p2).unwrap());
     }
+
+    #[test]
+    fn test_property_temporal_and_decimal_cmp() {
+        let dt = Property::DateTime(1582934400000);
+        assert_eq!(dt, Property::DateTimeWithTz(1582934400000, 8 * 3600));
+        assert!(dt < Property::DateTimeWithTz(1582934400001, -3600));
+        assert!(dt
+            .partial_cmp(&Property::Long(1582934400000))
+            .is_none());
+
+        assert_eq!(Property::Decimal(1500, 3), Property::Decimal(15, 1));
+        assert!(Property::Decimal(1500, 3) < Property::Decimal(16, 1));
+        assert_eq!(Property::Decimal(15, 1), Property::Double(1.5));
+        assert!(Property::Int(1) < Property::Decimal(15, 1));
+
+        assert_eq!(decimal_to_string(1500, 3), "1.500");
+        assert_eq!(decimal_to_string(-5, 2), "-0.05");
+        assert_eq!(decimal_to_string(42, 0), "42");
+        assert_eq!(decimal_to_string(i128::MIN, 38), "-1.70141183460469231731687303715884105728");
+
+        let date = NaiveDate::from_ymd_opt(2020, 2, 29).unwrap();
+        let p = Property::from_date_format(&DateTimeFormats::Date(date)).unwrap();
+        assert_eq!(p, Property::Date("2020-02-29".to_owned()));
+        let p = Property::from_date_format(&DateTimeFormats::DateTime(date.and_hms_opt(0, 0, 0).unwrap()))
+            .unwrap();
+        assert_eq!(p, dt);
+        let tz = FixedOffset::east_opt(8 * 3600).unwrap();
+        let p = Property::from_date_format(&DateTimeFormats::DateTimeWithTz(
+            tz.from_local_datetime(&date.and_hms_opt(8, 0, 0).unwrap())
+                .unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(p, Property::DateTimeWithTz(1582934400000, 8 * 3600));
+        let time = NaiveTime::from_hms_opt(8, 0, 0).unwrap();
+        assert!(Property::from_date_format(&DateTimeFormats::Time(time)).is_err());
+    }
 }
@@ -0,0 +1,50 @@
+//! Build the sst files of an offline data load, e.g.
+//!
+//!   bulk_load --schema graph_def.pb --config mapping.json --output /path/to/download/unique_path
+//!
+//! `--schema` is the `GraphDefPb` of the graph after `prepareDataLoad`, and the output directory
+//! should be the one the stores ingest from in `commitDataLoad`.
+
+use std::fs;
+use std::path::PathBuf;
+
+use groot_store::db::api::GraphDef;
+use groot_store::db::bulk_load::{build_ssts, LoadConfig};
+use groot_store::db::common::bytes::util::parse_pb;
+use groot_store::db::proto::model::GraphDefPb;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "bulk_load", about = "build sst files for groot from csv or parquet files")]
+struct Args {
+    /// file of the serialized GraphDefPb
+    #[structopt(short = "s", long = "schema")]
+    schema: PathBuf,
+
+    /// json file which maps the input files to vertices and edges
+    #[structopt(short = "c", long = "config")]
+    config: PathBuf,
+
+    /// directory of the output sst files
+    #[structopt(short = "o", long = "output")]
+    output: PathBuf,
+
+    /// overrides the partition count of the config
+    #[structopt(short = "p", long = "partitions")]
+    partitions: Option<u32>,
+}
+
+fn main() {
+    let args = Args::from_args();
+    let bytes = fs::read(&args.schema).expect("read schema failed");
+    let pb = parse_pb::<GraphDefPb>(&bytes).expect("parse schema failed");
+    let graph_def = GraphDef::from_proto(&pb).expect("invalid schema");
+    let mut config = LoadConfig::from_file(&args.config).expect("load config failed");
+    if let Some(partitions) = args.partitions {
+        config.partitions = partitions;
+    }
+    let files = build_ssts(&graph_def, &config, &args.output).expect("build sst files failed");
+    for file in files {
+        println!("{}", file.display());
+    }
+}
@@ -0,0 +1,98 @@
+//
+//! Copyright 2020 Alibaba Group Holding Limited.
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use std::collections::HashMap;
+
+use crate::db::api::{EdgeId, EdgeKind, GraphResult, LabelId, PropertyId, SnapshotId, Value, VertexId};
+use crate::db::proto::model::{ChangeEventBatchPb, ChangeEventPb, ChangeTypePb};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChangeType {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl ChangeType {
+    pub fn to_proto(&self) -> ChangeTypePb {
+        match *self {
+            ChangeType::Insert => ChangeTypePb::CHANGE_INSERT,
+            ChangeType::Update => ChangeTypePb::CHANGE_UPDATE,
+            ChangeType::Delete => ChangeTypePb::CHANGE_DELETE,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChangeElement {
+    Vertex(VertexId, LabelId),
+    Edge(EdgeId, EdgeKind),
+}
+
+/// A change of a vertex or an edge committed at snapshot `si`. `properties` are all properties of the
+/// element after an insert or update, and all properties before a delete.
+#[derive(Clone, Debug)]
+pub struct ChangeEvent {
+    pub si: SnapshotId,
+    pub change_type: ChangeType,
+    pub element: ChangeElement,
+    pub properties: HashMap<PropertyId, Value>,
+}
+
+impl ChangeEvent {
+    pub fn new(
+        si: SnapshotId, change_type: ChangeType, element: ChangeElement,
+        properties: HashMap<PropertyId, Value>,
+    ) -> Self {
+        ChangeEvent { si, change_type, element, properties }
+    }
+
+    pub fn to_proto(&self) -> GraphResult<ChangeEventPb> {
+        let mut pb = ChangeEventPb::new();
+        pb.set_snapshotId(self.si);
+        pb.set_changeType(self.change_type.to_proto());
+        match self.element {
+            ChangeElement::Vertex(id, label) => {
+                pb.mut_vertexId().set_id(id);
+                pb.mut_labelId().set_id(label);
+            }
+            ChangeElement::Edge(ref id, ref kind) => {
+                let edge_id = pb.mut_edgeId();
+                edge_id.mut_srcId().set_id(id.src_id);
+                edge_id.mut_dstId().set_id(id.dst_id);
+                edge_id.set_id(id.inner_id);
+                pb.set_edgeKind(kind.to_proto());
+            }
+        }
+        for (prop_id, value) in &self.properties {
+            pb.mut_props()
+                .insert(*prop_id, value.to_proto()?);
+        }
+        Ok(pb)
+    }
+}
+
+/// Encode the changes of `(from_si, to_si]` as a `ChangeEventBatchPb`.
+pub fn changes_to_proto(
+    from_si: SnapshotId, to_si: SnapshotId, events: &[ChangeEvent],
+) -> GraphResult<ChangeEventBatchPb> {
+    let mut pb = ChangeEventBatchPb::new();
+    pb.set_fromSnapshotId(from_si);
+    pb.set_toSnapshotId(to_si);
+    for event in events {
+        pb.mut_events().push(event.to_proto()?);
+    }
+    Ok(pb)
+}
@@ -9,6 +9,7 @@ use crate::db::proto::schema_common::EdgeKindPb;
 
 #[macro_use]
 pub mod error;
+pub mod change;
 mod config;
 pub mod multi_version_graph;
 pub mod partition_graph;
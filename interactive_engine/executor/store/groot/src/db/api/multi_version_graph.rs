@@ -14,6 +14,7 @@
 //! limitations under the License.
 
 use crate::api::Condition;
+use crate::db::api::change::ChangeEvent;
 use crate::db::api::types::{RocksEdge, RocksVertex};
 use crate::db::api::{
     BackupId, DataLoadTarget, EdgeId, EdgeKind, GraphResult, LabelId, PropertyId, PropertyMap, Records,
@@ -80,6 +81,25 @@ pub trait MultiVersionGraph {
         property_ids: Option<&Vec<PropertyId>>,
     ) -> GraphResult<Option<Self::E>>;
 
+    /// Returns (property id, descending) of the sort key of edge type `label_id` usable at `snapshot_id`.
+    fn get_edge_sort_key(
+        &self, snapshot_id: SnapshotId, label_id: LabelId,
+    ) -> GraphResult<Option<(PropertyId, bool)>>;
+
+    /// Get the first `limit` out edges of `vertex_id` with label `label_id` in the order of the sort key
+    /// of the edge type, a `limit` of 0 returns all of them. Returns `GraphError` if the edge type has
+    /// no sort key at `snapshot_id`.
+    fn get_sorted_out_edges(
+        &self, snapshot_id: SnapshotId, vertex_id: VertexId, label_id: LabelId,
+        condition: Option<&Condition>, property_ids: Option<&Vec<PropertyId>>, limit: usize,
+    ) -> GraphResult<Records<Self::E>>;
+
+    /// Get the first `limit` in edges of `vertex_id` in the order of the sort key, like `get_sorted_out_edges`.
+    fn get_sorted_in_edges(
+        &self, snapshot_id: SnapshotId, vertex_id: VertexId, label_id: LabelId,
+        condition: Option<&Condition>, property_ids: Option<&Vec<PropertyId>>, limit: usize,
+    ) -> GraphResult<Records<Self::E>>;
+
     /// Create a new vertex type with `label` and `type_def` at `si` and `schema_version`. This interface is thread safe.
     ///
     /// If vertex type already exists, `si` is smaller than last operation, get lock error, storage error
@@ -116,6 +136,49 @@ pub trait MultiVersionGraph {
         &self, si: SnapshotId, schema_version: i64, label: LabelId, type_def: &TypeDef,
     ) -> GraphResult<bool>;
 
+    /// Drop the properties in `type_def` from vertex type `label` at `si` and `schema_version`, the
+    /// codec of the vertex type is upgraded to the version of `type_def`. This interface is thread safe.
+    ///
+    /// Dropped properties are hidden from snapshots since `si` at once, the rows encoded with older
+    /// codecs are rewritten by `gc` after every snapshot before `si` is collected.
+    ///
+    /// If vertex type or property not found, the property is a primary key or indexed, `si` is smaller
+    /// than last operation, get lock error, storage error or other errors, `GraphError` will be returned.
+    /// Returns true if schema_version changed, false otherwise.
+    fn drop_vertex_type_properties(
+        &self, si: SnapshotId, schema_version: i64, label: LabelId, type_def: &TypeDef,
+    ) -> GraphResult<bool>;
+
+    /// Drop the properties in `type_def` from edge type `label` at `si` and `schema_version`, see
+    /// `drop_vertex_type_properties`. This interface is thread safe.
+    ///
+    /// If edge type or property not found, the property is a primary key, `si` is smaller than last
+    /// operation, get lock error, storage error or other errors, `GraphError` will be returned.
+    /// Returns true if schema_version changed, false otherwise.
+    fn drop_edge_type_properties(
+        &self, si: SnapshotId, schema_version: i64, label: LabelId, type_def: &TypeDef,
+    ) -> GraphResult<bool>;
+
+    /// Rename property `prop_id` of vertex type `label` to `new_name` at `si` and `schema_version`.
+    /// Stored rows are not touched. This interface is thread safe.
+    ///
+    /// If vertex type or property not found, another property is named `new_name`, `si` is smaller than
+    /// last operation, get lock error, storage error or other errors, `GraphError` will be returned.
+    /// Returns true if schema_version changed, false otherwise.
+    fn rename_vertex_type_property(
+        &self, si: SnapshotId, schema_version: i64, label: LabelId, prop_id: PropertyId, new_name: &str,
+    ) -> GraphResult<bool>;
+
+    /// Rename property `prop_id` of edge type `label` to `new_name` at `si` and `schema_version`.
+    /// Stored rows are not touched. This interface is thread safe.
+    ///
+    /// If edge type or property not found, another property is named `new_name`, `si` is smaller than
+    /// last operation, get lock error, storage error or other errors, `GraphError` will be returned.
+    /// Returns true if schema_version changed, false otherwise.
+    fn rename_edge_type_property(
+        &self, si: SnapshotId, schema_version: i64, label: LabelId, prop_id: PropertyId, new_name: &str,
+    ) -> GraphResult<bool>;
+
     /// Add a new edge kind of `kind` to edge type with `kind.label` at `si` and `schema_version`. This interface is thread safe.
     ///
     /// If edge type with `kind.label` not exists, edge kind `kind` already exists, `si` is smaller
@@ -152,6 +215,46 @@ pub trait MultiVersionGraph {
         &self, si: SnapshotId, schema_version: i64, edge_kind: &EdgeKind,
     ) -> GraphResult<bool>;
 
+    /// Create a secondary index on property `prop_id` of vertex type `label` at `si` and `schema_version`.
+    /// Index entries are stored in the key range of `table_id`, and existing vertices are indexed before
+    /// it returns. This interface is thread safe.
+    ///
+    /// If vertex type or property not found, the property type cannot be indexed, the index already exists,
+    /// `si` is smaller than last operation, storage error or other errors, `GraphError` will be returned.
+    /// Returns true if schema_version changed, false otherwise.
+    fn create_vertex_index(
+        &self, si: SnapshotId, schema_version: i64, label: LabelId, prop_id: PropertyId, table_id: i64,
+    ) -> GraphResult<bool>;
+
+    /// Drop the secondary index on property `prop_id` of vertex type `label` at `si` and `schema_version`.
+    /// This interface is thread safe.
+    ///
+    /// If index not found, `si` is smaller than last operation, get lock error or other errors,
+    /// `GraphError` will be returned.
+    /// Returns true if schema_version changed, false otherwise.
+    fn drop_vertex_index(
+        &self, si: SnapshotId, schema_version: i64, label: LabelId, prop_id: PropertyId,
+    ) -> GraphResult<bool>;
+
+    /// Create a sort key on property `prop_id` of edge type `label` at `si` and `schema_version`. Adjacent
+    /// edges of each vertex are materialized in the key range of `table_id` ordered by the property,
+    /// ascending or `descending`, and existing edges are added before it returns. This interface is thread safe.
+    ///
+    /// If edge type or property not found, the property type cannot be sorted, the edge type already has a
+    /// sort key, `si` is smaller than last operation, storage error or other errors, `GraphError` will be returned.
+    /// Returns true if schema_version changed, false otherwise.
+    fn create_edge_sort_key(
+        &self, si: SnapshotId, schema_version: i64, label: LabelId, prop_id: PropertyId, descending: bool,
+        table_id: i64,
+    ) -> GraphResult<bool>;
+
+    /// Drop the sort key of edge type `label` at `si` and `schema_version`. This interface is thread safe.
+    ///
+    /// If sort key not found, `si` is smaller than last operation, get lock error or other errors,
+    /// `GraphError` will be returned.
+    /// Returns true if schema_version changed, false otherwise.
+    fn drop_edge_sort_key(&self, si: SnapshotId, schema_version: i64, label: LabelId) -> GraphResult<bool>;
+
     /// realtime write interfaces
     /// These realtime write interfaces should be thread safe and user should ensure all data are in
     /// ascending order by si, or error will be return. The distribute protocol ensure si of all data
@@ -220,6 +323,24 @@ pub trait MultiVersionGraph {
     /// garbage collection at `si`
     fn gc(&self, si: SnapshotId) -> GraphResult<()>;
 
+    /// The watermark of the last `gc`, snapshots before it may have been collected and reading them
+    /// gives incomplete results.
+    fn get_gc_snapshot_id(&self) -> SnapshotId;
+
+    /// The latest snapshot written to, snapshots after it have no data yet and reading them gives
+    /// results that may still change. It's the watermark of the last `gc` until the first write
+    /// after a restart.
+    fn get_latest_snapshot_id(&self) -> SnapshotId;
+
+    /// Scan the vertex and edge changes committed in snapshots `(from_si, to_si]`. Changes of the same
+    /// element are returned in ascending order of si, changes of different elements are not ordered.
+    /// Schema changes are not reported, so elements of types dropped in the range are skipped.
+    ///
+    /// If `from_si` is behind the watermark of `gc`, `to_si` is smaller than `from_si`, the table of a
+    /// type is replaced by data loading in the range, storage error or other errors, `GraphError` will
+    /// be returned.
+    fn scan_changes(&self, from_si: SnapshotId, to_si: SnapshotId) -> GraphResult<Records<ChangeEvent>>;
+
     /// Returns current GraphDefPb bytes
     fn get_graph_def_blob(&self) -> GraphResult<Vec<u8>>;
 
@@ -3,10 +3,12 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::marker::PhantomData;
 
+use chrono::NaiveDate;
 use protobuf::ProtobufEnum;
 
 use super::error::*;
 use super::GraphResult;
+use crate::api::prelude::cmp_decimal;
 use crate::db::api::PropertyId;
 use crate::db::common::bytes::transform;
 use crate::db::common::bytes::util::{UnsafeBytesReader, UnsafeBytesWriter, LEN_SIZE};
@@ -45,6 +47,10 @@ pub enum ValueType {
     FloatList = 12,
     DoubleList = 13,
     StringList = 14,
+    Date = 15,
+    DateTime = 16,
+    DateTimeWithTz = 17,
+    Decimal = 18,
 }
 
 impl ValueType {
@@ -65,12 +71,16 @@ impl ValueType {
             ValueType::FloatList,
             ValueType::DoubleList,
             ValueType::StringList,
+            ValueType::Date,
+            ValueType::DateTime,
+            ValueType::DateTimeWithTz,
+            ValueType::Decimal,
         ]
     }
 
     #[cfg(test)]
     pub fn count() -> usize {
-        14
+        18
     }
 
     pub fn from_proto(pb: &DataTypePb) -> GraphResult<Self> {
@@ -89,6 +99,10 @@ impl ValueType {
             DataTypePb::FLOAT_LIST => Ok(ValueType::FloatList),
             DataTypePb::DOUBLE_LIST => Ok(ValueType::DoubleList),
             DataTypePb::STRING_LIST => Ok(ValueType::StringList),
+            DataTypePb::DATE32 => Ok(ValueType::Date),
+            DataTypePb::TIMESTAMP_MS => Ok(ValueType::DateTime),
+            DataTypePb::TIMESTAMP_MS_TZ => Ok(ValueType::DateTimeWithTz),
+            DataTypePb::DECIMAL => Ok(ValueType::Decimal),
             _ => {
                 let msg = format!("unsupported data type {:?}", pb);
                 let err = gen_graph_err!(ErrorCode::INVALID_DATA, msg, from_proto, pb);
@@ -113,6 +127,10 @@ impl ValueType {
             ValueType::FloatList => Ok(DataTypePb::FLOAT_LIST),
             ValueType::DoubleList => Ok(DataTypePb::DOUBLE_LIST),
             ValueType::StringList => Ok(DataTypePb::STRING_LIST),
+            ValueType::Date => Ok(DataTypePb::DATE32),
+            ValueType::DateTime => Ok(DataTypePb::TIMESTAMP_MS),
+            ValueType::DateTimeWithTz => Ok(DataTypePb::TIMESTAMP_MS_TZ),
+            ValueType::Decimal => Ok(DataTypePb::DECIMAL),
         }
     }
 
@@ -124,7 +142,11 @@ impl ValueType {
             | ValueType::Int
             | ValueType::Long
             | ValueType::Float
-            | ValueType::Double => true,
+            | ValueType::Double
+            | ValueType::Date
+            | ValueType::DateTime
+            | ValueType::DateTimeWithTz
+            | ValueType::Decimal => true,
             _ => false,
         }
     }
@@ -134,8 +156,10 @@ impl ValueType {
         match *self {
             ValueType::Bool | ValueType::Char => 1,
             ValueType::Short => 2,
-            ValueType::Int | ValueType::Float => 4,
-            ValueType::Long | ValueType::Double => 8,
+            ValueType::Int | ValueType::Float | ValueType::Date => 4,
+            ValueType::Long | ValueType::Double | ValueType::DateTime => 8,
+            ValueType::DateTimeWithTz => 12,
+            ValueType::Decimal => 17,
             _ => panic!("{:?} doesn't has fixed len", self),
         }
     }
@@ -215,6 +239,34 @@ impl<'a> ValueRef<'a> {
         Ok(self.data)
     }
 
+    /// days since 1970-01-01
+    pub fn get_date(&self) -> GraphResult<i32> {
+        let res = self.check_type_match(ValueType::Date);
+        res_unwrap!(res, get_date)?;
+        Ok(get_int(self.data))
+    }
+
+    /// milliseconds since 1970-01-01T00:00:00Z
+    pub fn get_datetime(&self) -> GraphResult<i64> {
+        let res = self.check_type_match(ValueType::DateTime);
+        res_unwrap!(res, get_datetime)?;
+        Ok(get_long(self.data))
+    }
+
+    /// milliseconds since 1970-01-01T00:00:00Z and the offset east of UTC in seconds
+    pub fn get_datetime_with_tz(&self) -> GraphResult<(i64, i32)> {
+        let res = self.check_type_match(ValueType::DateTimeWithTz);
+        res_unwrap!(res, get_datetime_with_tz)?;
+        Ok(get_datetime_with_tz(self.data))
+    }
+
+    /// the unscaled value and the scale, the decimal is `unscaled * 10^(-scale)`
+    pub fn get_decimal(&self) -> GraphResult<(i128, u8)> {
+        let res = self.check_type_match(ValueType::Decimal);
+        res_unwrap!(res, get_decimal)?;
+        Ok(get_decimal(self.data))
+    }
+
     pub fn get_int_list(&self) -> GraphResult<NumericArray<i32>> {
         let res = self
             .check_type_match(ValueType::IntList)
@@ -285,6 +337,21 @@ impl<'a> ValueRef<'a> {
             ValueType::Long => Some(get_long(self.data) as f64),
             ValueType::Float => Some(get_float(self.data) as f64),
             ValueType::Double => Some(get_double(self.data)),
+            ValueType::Decimal => {
+                let (unscaled, scale) = get_decimal(self.data);
+                Some(unscaled as f64 / 10f64.powi(scale as i32))
+            }
+            _ => None,
+        }
+    }
+
+    /// transform value to milliseconds since epoch if it is a date or time, else return None.
+    /// A date is taken as the midnight of that day in UTC.
+    pub fn to_epoch_millis(&self) -> Option<i64> {
+        match self.r#type {
+            ValueType::Date => Some(get_int(self.data) as i64 * MILLIS_PER_DAY),
+            ValueType::DateTime => Some(get_long(self.data)),
+            ValueType::DateTimeWithTz => Some(get_datetime_with_tz(self.data).0),
             _ => None,
         }
     }
@@ -355,6 +422,16 @@ impl std::fmt::Debug for ValueRef<'_> {
                 write!(f, "DoubleArray({:?})", self.get_double_list().unwrap())
             }
             ValueType::StringList => write!(f, "StringArray({:?})", self.get_str_list().unwrap()),
+            ValueType::Date => write!(f, "Date({})", date_to_str(get_int(self.data))),
+            ValueType::DateTime => write!(f, "DateTime({})", get_long(self.data)),
+            ValueType::DateTimeWithTz => {
+                let (millis, offset) = get_datetime_with_tz(self.data);
+                write!(f, "DateTimeWithTz({}, {})", millis, offset)
+            }
+            ValueType::Decimal => {
+                let (unscaled, scale) = get_decimal(self.data);
+                write!(f, "Decimal({}, {})", unscaled, scale)
+            }
         }
     }
 }
@@ -369,7 +446,7 @@ impl PartialEq for ValueRef<'_> {
                     | ValueType::Short
                     | ValueType::Int
                     | ValueType::Long => self.to_long().unwrap() == other.to_long().unwrap(),
-                    ValueType::Float | ValueType::Double => {
+                    ValueType::Float | ValueType::Double | ValueType::Decimal => {
                         self.to_double().unwrap() == other.to_double().unwrap()
                     }
                     _ => false,
@@ -382,9 +459,17 @@ impl PartialEq for ValueRef<'_> {
                 | ValueType::Int
                 | ValueType::Long
                 | ValueType::Float
-                | ValueType::Double => self.to_double().unwrap() == other.to_double().unwrap(),
+                | ValueType::Double
+                | ValueType::Decimal => self.to_double().unwrap() == other.to_double().unwrap(),
                 _ => false,
             },
+            ValueType::Decimal => self.partial_cmp(other) == Some(Ordering::Equal),
+            ValueType::Date | ValueType::DateTime | ValueType::DateTimeWithTz => {
+                match (self.to_epoch_millis(), other.to_epoch_millis()) {
+                    (Some(left), Some(right)) => left == right,
+                    _ => false,
+                }
+            }
             _ => self.r#type == other.r#type && self.data == other.data,
         }
     }
@@ -404,7 +489,7 @@ impl PartialOrd for ValueRef<'_> {
                             .unwrap()
                             .cmp(&other.to_long().unwrap()),
                     ),
-                    ValueType::Float | ValueType::Double => self
+                    ValueType::Float | ValueType::Double | ValueType::Decimal => self
                         .to_double()
                         .unwrap()
                         .partial_cmp(&other.to_double().unwrap()),
@@ -412,6 +497,21 @@ impl PartialOrd for ValueRef<'_> {
                 }
             }
             ValueType::Float | ValueType::Double => match other.r#type {
+                ValueType::Bool
+                | ValueType::Char
+                | ValueType::Short
+                | ValueType::Int
+                | ValueType::Long
+                | ValueType::Float
+                | ValueType::Double
+                | ValueType::Decimal => self
+                    .to_double()
+                    .unwrap()
+                    .partial_cmp(&other.to_double().unwrap()),
+                _ => None,
+            },
+            ValueType::Decimal => match other.r#type {
+                ValueType::Decimal => cmp_decimal(get_decimal(self.data), get_decimal(other.data)),
                 ValueType::Bool
                 | ValueType::Char
                 | ValueType::Short
@@ -424,6 +524,11 @@ impl PartialOrd for ValueRef<'_> {
                     .partial_cmp(&other.to_double().unwrap()),
                 _ => None,
             },
+            ValueType::Date | ValueType::DateTime | ValueType::DateTimeWithTz => {
+                let left = self.to_epoch_millis()?;
+                let right = other.to_epoch_millis()?;
+                left.partial_cmp(&right)
+            }
             _ => {
                 if self.r#type == other.r#type {
                     match self.r#type {
@@ -517,6 +622,41 @@ fn get_double(data: &[u8]) -> f64 {
     f64::from_bits(reader.read_u64(0).to_be())
 }
 
+fn get_datetime_with_tz(data: &[u8]) -> (i64, i32) {
+    debug_assert_eq!(data.len(), 12);
+    (get_long(&data[0..8]), get_int(&data[8..12]))
+}
+
+fn get_decimal(data: &[u8]) -> (i128, u8) {
+    debug_assert_eq!(data.len(), 17);
+    let mut unscaled = [0u8; 16];
+    unscaled.copy_from_slice(&data[0..16]);
+    (i128::from_be_bytes(unscaled), data[16])
+}
+
+const MILLIS_PER_DAY: i64 = 24 * 3600 * 1000;
+
+fn epoch_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
+}
+
+/// format days since 1970-01-01 as `%Y-%m-%d`
+pub fn date_to_str(days: i32) -> String {
+    match epoch_date().checked_add_signed(chrono::Duration::days(days as i64)) {
+        Some(date) => date.format("%Y-%m-%d").to_string(),
+        None => format!("{}", days),
+    }
+}
+
+/// parse a date in `%Y-%m-%d` to days since 1970-01-01
+pub fn str_to_date(s: &str) -> Option<i32> {
+    let date = NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?;
+    Some(
+        date.signed_duration_since(epoch_date())
+            .num_days() as i32,
+    )
+}
+
 /// value encoding(all value is format in big endian):
 /// bool: true -> [1], false [0], 1B
 /// char: x -> [x], 1B
@@ -527,6 +667,21 @@ fn get_double(data: &[u8]) -> f64 {
 /// double: y = x.to_bits() -> [y1, y2, y3, y4, y5, y6, y7, y8] where x==((y1<<56)|(y2<<48)|(y3<<40)|(y4<<32)|(y5<<24)|(y6<<16)|(y7<<8)|y8), 8B
 /// string: x -> x.as_bytes()
 /// bytes: x -> x.as_bytes()
+/// date: days since 1970-01-01 in int format above, 4B
+/// datetime: milliseconds since 1970-01-01T00:00:00Z in long format above, 8B
+/// datetime with tz:
+///     +--------+--------+
+///     | millis | offset |
+///     +--------+--------+
+///     |   8B   |   4B   |
+///     +--------+--------+ millis is the utc instant in datetime format above, offset is the
+///     seconds east of utc in int format above
+/// decimal:
+///     +----------+-------+
+///     | unscaled | scale |
+///     +----------+-------+
+///     |   16B    |  1B   |
+///     +----------+-------+ unscaled is a big endian i128, the value is unscaled * 10^(-scale)
 /// int array:
 ///     +-----+----+----+-----+----+
 ///     | len | x1 | x2 | ... | xn |
@@ -649,6 +804,33 @@ impl Value {
         Value::new(ValueType::Bytes, data)
     }
 
+    /// `v` is days since 1970-01-01
+    pub fn date(v: i32) -> Self {
+        let data = transform::i32_to_vec(v.to_be());
+        Value::new(ValueType::Date, data)
+    }
+
+    /// `v` is milliseconds since 1970-01-01T00:00:00Z
+    pub fn datetime(v: i64) -> Self {
+        let data = transform::i64_to_vec(v.to_be());
+        Value::new(ValueType::DateTime, data)
+    }
+
+    /// `millis` is the utc instant and `offset` is the seconds east of utc of the time zone
+    pub fn datetime_with_tz(millis: i64, offset: i32) -> Self {
+        let mut data = Vec::with_capacity(12);
+        data.extend_from_slice(&millis.to_be_bytes());
+        data.extend_from_slice(&offset.to_be_bytes());
+        Value::new(ValueType::DateTimeWithTz, data)
+    }
+
+    pub fn decimal(unscaled: i128, scale: u8) -> Self {
+        let mut data = Vec::with_capacity(17);
+        data.extend_from_slice(&unscaled.to_be_bytes());
+        data.push(scale);
+        Value::new(ValueType::Decimal, data)
+    }
+
     pub fn int_list(v: &[i32]) -> Self {
         let data = gen_array!(v, i32, write_i32);
         Value::new(ValueType::IntList, data)
@@ -738,6 +920,22 @@ impl Value {
         unsafe { Ok(::std::mem::transmute(ret)) }
     }
 
+    pub fn get_date(&self) -> GraphResult<i32> {
+        res_unwrap!(self.as_ref().get_date(), get_date)
+    }
+
+    pub fn get_datetime(&self) -> GraphResult<i64> {
+        res_unwrap!(self.as_ref().get_datetime(), get_datetime)
+    }
+
+    pub fn get_datetime_with_tz(&self) -> GraphResult<(i64, i32)> {
+        res_unwrap!(self.as_ref().get_datetime_with_tz(), get_datetime_with_tz)
+    }
+
+    pub fn get_decimal(&self) -> GraphResult<(i128, u8)> {
+        res_unwrap!(self.as_ref().get_decimal(), get_decimal)
+    }
+
     pub fn get_int_list(&self) -> GraphResult<NumericArray<i32>> {
         let r = self.as_ref();
         let ret = res_unwrap!(r.get_int_list(), get_int_list)?;
@@ -1266,6 +1464,40 @@ mod tests {
         assert!(Value::int_list(&[1, 3, 2]) > Value::int_list(&[1, 2, 3, 4]));
     }
 
+    #[test]
+    fn value_temporal_and_decimal_test() {
+        let date = Value::date(str_to_date("2020-02-29").unwrap());
+        assert_eq!(date_to_str(date.get_date().unwrap()), "2020-02-29");
+        assert_eq!(str_to_date("1970-01-01"), Some(0));
+        assert_eq!(str_to_date("1969-12-31"), Some(-1));
+        assert!(date.get_int().is_err());
+
+        let millis = 1582934400000;
+        assert_eq!(date.as_ref().to_epoch_millis(), Some(millis));
+        assert_eq!(date, Value::datetime(millis));
+        assert!(date < Value::datetime(millis + 1));
+        assert_eq!(Value::datetime(millis).get_datetime().unwrap(), millis);
+
+        // the same instant in different time zones
+        let utc = Value::datetime_with_tz(millis, 0);
+        let east8 = Value::datetime_with_tz(millis, 8 * 3600);
+        assert_eq!(east8.get_datetime_with_tz().unwrap(), (millis, 8 * 3600));
+        assert_eq!(utc, east8);
+        assert_eq!(utc, Value::datetime(millis));
+        assert!(Value::datetime_with_tz(millis - 1, 8 * 3600) < utc);
+        assert_ne!(date, Value::long(millis));
+
+        let d1 = Value::decimal(12345, 2);
+        let d2 = Value::decimal(123450, 3);
+        assert_eq!(d1.get_decimal().unwrap(), (12345, 2));
+        assert_eq!(d1, d2);
+        assert!(Value::decimal(12344, 2) < d2);
+        assert!(Value::decimal(i128::MAX, 0) > Value::decimal(1, 30));
+        assert_eq!(d1, Value::double(123.45));
+        assert!(Value::int(123) < d1);
+        assert!(d1 < Value::long(124));
+    }
+
     fn check_numeric_array<T: ToBigEndian + PartialEq + Debug>(array: NumericArray<T>, ans: &[T]) {
         assert_eq!(array.len(), ans.len());
         for i in 0..ans.len() {
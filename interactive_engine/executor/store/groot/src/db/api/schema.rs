@@ -77,6 +77,8 @@ impl GraphDef {
                 .insert(property.name.clone(), property.id);
         }
         self.label_to_types.insert(label, type_def);
+        // properties may have been dropped or renamed
+        self.retain_property_names();
         Ok(())
     }
 
@@ -90,18 +92,22 @@ impl GraphDef {
 
     pub fn remove_type(&mut self, label_id: &LabelId) {
         if let Some(_typedef) = self.label_to_types.remove(label_id) {
-            let mut current_property_names = HashSet::new();
-            for t in self.label_to_types.values() {
-                for p in t.get_prop_defs() {
-                    current_property_names.insert(&p.name);
-                }
-            }
-            self.property_name_to_id
-                .retain(|k, _v| current_property_names.contains(k));
+            self.retain_property_names();
             self.vertex_table_ids.remove(label_id);
         }
     }
 
+    fn retain_property_names(&mut self) {
+        let mut current_property_names = HashSet::new();
+        for t in self.label_to_types.values() {
+            for p in t.get_prop_defs() {
+                current_property_names.insert(&p.name);
+            }
+        }
+        self.property_name_to_id
+            .retain(|k, _v| current_property_names.contains(k));
+    }
+
     pub fn add_edge_kind(&mut self, edge_kind: EdgeKind) {
         self.edge_kinds.insert(edge_kind);
     }
@@ -139,6 +145,41 @@ impl GraphDef {
         self.version
     }
 
+    pub fn from_proto(proto: &GraphDefPb) -> GraphResult<Self> {
+        let mut label_to_types = HashMap::new();
+        for type_def_pb in proto.get_typeDefs() {
+            let type_def = TypeDef::from_proto(type_def_pb)?;
+            label_to_types.insert(type_def.get_label_id(), type_def);
+        }
+        let edge_kinds = proto
+            .get_edgeKinds()
+            .iter()
+            .map(EdgeKind::from_proto)
+            .collect();
+        let property_name_to_id = proto.get_propertyNameToId().clone();
+        let vertex_table_ids = proto
+            .get_vertexTableIds()
+            .iter()
+            .map(|entry| (entry.get_labelId().get_id(), entry.get_tableId()))
+            .collect();
+        let edge_table_ids = proto
+            .get_edgeTableIds()
+            .iter()
+            .map(|entry| (EdgeKind::from_proto(entry.get_edgeKind()), entry.get_tableId()))
+            .collect();
+        Ok(Self::new(
+            proto.get_version(),
+            label_to_types,
+            edge_kinds,
+            property_name_to_id,
+            proto.get_labelIdx(),
+            proto.get_propertyIdx(),
+            vertex_table_ids,
+            edge_table_ids,
+            proto.get_tableIdx(),
+        ))
+    }
+
     pub fn to_proto(&self) -> GraphResult<GraphDefPb> {
         let mut pb = GraphDefPb::new();
         pb.set_version(self.version);
@@ -181,6 +222,7 @@ pub struct TypeDef {
     label_id: LabelId,
     properties: HashMap<PropertyId, PropDef>,
     type_enum: TypeEnumPb,
+    ttl_ms: Option<i64>,
 }
 
 impl TypeDef {
@@ -208,10 +250,35 @@ impl TypeDef {
         self.properties.insert(prop.id, prop);
     }
 
+    pub fn remove_property(&mut self, prop_id: PropertyId) -> Option<PropDef> {
+        self.properties.remove(&prop_id)
+    }
+
+    /// Returns false if there's no property of `prop_id`.
+    pub fn rename_property(&mut self, prop_id: PropertyId, new_name: &str) -> bool {
+        match self.properties.get_mut(&prop_id) {
+            Some(prop) => {
+                prop.name = new_name.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn set_version(&mut self, new_version: i32) {
         self.version = new_version;
     }
 
+    /// Records of this type written more than `ttl_ms` milliseconds ago are expired, `None` means
+    /// records never expire.
+    pub fn get_ttl_ms(&self) -> Option<i64> {
+        self.ttl_ms
+    }
+
+    pub fn set_ttl_ms(&mut self, ttl_ms: Option<i64>) {
+        self.ttl_ms = ttl_ms;
+    }
+
     pub fn from_proto(proto: &TypeDefPb) -> GraphResult<Self> {
         let version_id = proto.get_version_id();
         let label = proto.get_label();
@@ -222,7 +289,8 @@ impl TypeDef {
             properties.insert(property_def.id, property_def);
         }
         let type_enum = proto.get_type_enum();
-        Ok(Self::new(version_id, label.to_string(), label_id, properties, type_enum))
+        let ttl_ms = if proto.get_ttl_ms() > 0 { Some(proto.get_ttl_ms()) } else { None };
+        Ok(Self::new(version_id, label.to_string(), label_id, properties, type_enum, ttl_ms))
     }
 
     pub fn to_proto(&self) -> GraphResult<TypeDefPb> {
@@ -236,6 +304,7 @@ impl TypeDef {
                 .push(property_def.to_proto()?);
         }
         typedef_pb.set_type_enum(self.type_enum);
+        typedef_pb.set_ttl_ms(self.ttl_ms.unwrap_or(0));
         Ok(typedef_pb)
     }
 
@@ -257,9 +326,9 @@ impl TypeDef {
 
     fn new(
         version: i32, label: String, label_id: LabelId, properties: HashMap<PropertyId, PropDef>,
-        type_enum: TypeEnumPb,
+        type_enum: TypeEnumPb, ttl_ms: Option<i64>,
     ) -> Self {
-        TypeDef { version, label, label_id, properties, type_enum }
+        TypeDef { version, label, label_id, properties, type_enum, ttl_ms }
     }
 
     #[cfg(test)]
@@ -320,6 +389,11 @@ impl TypeDefBuilder {
         self
     }
 
+    pub fn set_ttl_ms(&mut self, ttl_ms: i64) -> &mut Self {
+        self.type_def.ttl_ms = Some(ttl_ms);
+        self
+    }
+
     pub fn build(self) -> TypeDef {
         self.type_def
     }
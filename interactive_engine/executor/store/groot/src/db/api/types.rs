@@ -34,6 +34,10 @@ pub enum DataType {
     FloatList,
     DoubleList,
     StringList,
+    Date,
+    DateTime,
+    DateTimeWithTz,
+    Decimal,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -53,6 +57,14 @@ pub enum PropertyValue {
     FloatList(Vec<f32>),
     DoubleList(Vec<f64>),
     StringList(Vec<String>),
+    /// days since 1970-01-01
+    Date(i32),
+    /// milliseconds since 1970-01-01T00:00:00Z
+    DateTime(i64),
+    /// milliseconds since 1970-01-01T00:00:00Z and the offset east of UTC in seconds
+    DateTimeWithTz(i64, i32),
+    /// unscaled value and scale
+    Decimal(i128, u8),
 }
 
 impl From<ValueRef<'_>> for PropertyValue {
@@ -103,6 +115,16 @@ impl From<ValueRef<'_>> for PropertyValue {
                     .map(String::from)
                     .collect(),
             ),
+            ValueType::Date => PropertyValue::Date(value_ref.get_date().unwrap()),
+            ValueType::DateTime => PropertyValue::DateTime(value_ref.get_datetime().unwrap()),
+            ValueType::DateTimeWithTz => {
+                let (millis, offset) = value_ref.get_datetime_with_tz().unwrap();
+                PropertyValue::DateTimeWithTz(millis, offset)
+            }
+            ValueType::Decimal => {
+                let (unscaled, scale) = value_ref.get_decimal().unwrap();
+                PropertyValue::Decimal(unscaled, scale)
+            }
         }
     }
 }
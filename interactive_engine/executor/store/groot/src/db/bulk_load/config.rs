@@ -0,0 +1,106 @@
+use std::fs::File;
+use std::path::Path;
+
+use super::reader::FileFormat;
+use crate::db::api::*;
+
+/// Describes how the rows of the input files are mapped to vertices and edges, e.g.
+/// ```json
+/// {
+///   "partitions": 2,
+///   "vertices": [
+///     {"label_id": 1, "table_id": 10, "path": "person.csv",
+///      "columns": [{"column": "id", "property_id": 1}, {"column": "name", "property_id": 2}]}
+///   ],
+///   "edges": [
+///     {"label_id": 2, "src_label_id": 1, "dst_label_id": 1, "table_id": 11, "path": "knows.csv",
+///      "src_columns": ["src_id"], "dst_columns": ["dst_id"],
+///      "columns": [{"column": "weight", "property_id": 3}]}
+///   ]
+/// }
+/// ```
+/// `table_id` is the id returned by `prepareDataLoad` for the label.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LoadConfig {
+    pub partitions: u32,
+    #[serde(default)]
+    pub vertices: Vec<VertexMapping>,
+    #[serde(default)]
+    pub edges: Vec<EdgeMapping>,
+}
+
+impl LoadConfig {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> GraphResult<Self> {
+        let file = File::open(path.as_ref()).map_err(|e| {
+            let msg = format!("open {:?} failed because {}", path.as_ref(), e);
+            gen_graph_err!(ErrorCode::INVALID_ARGUMENT, msg)
+        })?;
+        serde_json::from_reader(file).map_err(|e| {
+            let msg = format!("parse {:?} failed because {}", path.as_ref(), e);
+            gen_graph_err!(ErrorCode::INVALID_ARGUMENT, msg)
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InputConfig {
+    pub path: String,
+    /// inferred from the suffix of `path` if absent
+    #[serde(default)]
+    pub format: Option<FileFormat>,
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    /// whether the first line of a csv file is the header, columns are referred by their index
+    /// if there's no header
+    #[serde(default = "default_header")]
+    pub header: bool,
+    /// delimiter of the elements of list properties
+    #[serde(default = "default_list_delimiter")]
+    pub list_delimiter: char,
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_header() -> bool {
+    true
+}
+
+fn default_list_delimiter() -> char {
+    ';'
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    /// name of the column, or its index if the file has no header
+    pub column: String,
+    pub property_id: PropertyId,
+}
+
+/// Primary key properties must be mapped, the vertex id is the hash of them in the order of
+/// property id, which is the same as the java side.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VertexMapping {
+    pub label_id: LabelId,
+    pub table_id: i64,
+    #[serde(flatten)]
+    pub input: InputConfig,
+    pub columns: Vec<ColumnMapping>,
+}
+
+/// `src_columns` and `dst_columns` are the primary keys of the src and dst vertices, in the order
+/// of property id.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EdgeMapping {
+    pub label_id: LabelId,
+    pub src_label_id: LabelId,
+    pub dst_label_id: LabelId,
+    pub table_id: i64,
+    #[serde(flatten)]
+    pub input: InputConfig,
+    pub src_columns: Vec<String>,
+    pub dst_columns: Vec<String>,
+    #[serde(default)]
+    pub columns: Vec<ColumnMapping>,
+}
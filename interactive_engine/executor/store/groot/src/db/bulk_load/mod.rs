@@ -0,0 +1,403 @@
+//! Builds the sst files of an offline data load without the hadoop pipeline. Rows of the input
+//! files are encoded by the codec of their type and keyed like the records written by the store,
+//! then they are partitioned by vertex id the same way as the java side and written into
+//! `part-r-{partition:05}.sst`, which are ready to be ingested by `commit_data_load`.
+//!
+//! All records are kept in memory before they are sorted and written, so it's meant for graphs
+//! which fit in the memory of a single machine.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rocksdb::{Options, SstFileWriter};
+
+pub use self::config::*;
+pub use self::reader::FileFormat;
+use crate::db::api::*;
+use crate::db::graph::bin::{edge_key, vertex_key};
+use crate::db::graph::codec::{Codec, Encoder};
+use crate::db::graph::{
+    get_edge_inner_id_by_primary_keys, get_edge_inner_id_by_seq, get_vertex_id_by_primary_keys,
+};
+
+mod config;
+mod reader;
+
+type Record = (Vec<u8>, Vec<u8>);
+
+/// Build the sst files described by `config` into `output_dir`, returns the files written.
+pub fn build_ssts(
+    graph_def: &GraphDef, config: &LoadConfig, output_dir: &Path,
+) -> GraphResult<Vec<PathBuf>> {
+    let mut builder = SstBuilder::new(graph_def, config.partitions)?;
+    for mapping in &config.vertices {
+        let count = builder.add_vertices(mapping)?;
+        info!("encoded {} vertices of label {} from {}", count, mapping.label_id, mapping.input.path);
+    }
+    for mapping in &config.edges {
+        let count = builder.add_edges(mapping)?;
+        info!("encoded {} edges of label {} from {}", count, mapping.label_id, mapping.input.path);
+    }
+    builder.finish(output_dir)
+}
+
+pub struct SstBuilder<'a> {
+    graph_def: &'a GraphDef,
+    partitions: Vec<Vec<Record>>,
+    edge_seq: i64,
+}
+
+impl<'a> SstBuilder<'a> {
+    pub fn new(graph_def: &'a GraphDef, partition_count: u32) -> GraphResult<Self> {
+        if partition_count == 0 {
+            let msg = "partition count must be positive".to_string();
+            return Err(gen_graph_err!(ErrorCode::INVALID_ARGUMENT, msg, new));
+        }
+        let partitions = (0..partition_count)
+            .map(|_| Vec::new())
+            .collect();
+        Ok(SstBuilder { graph_def, partitions, edge_seq: 0 })
+    }
+
+    /// Returns the number of vertices read
+    pub fn add_vertices(&mut self, mapping: &VertexMapping) -> GraphResult<usize> {
+        let type_def = self.get_type_def(mapping.label_id)?;
+        let pk_ids = get_pk_ids(type_def);
+        if pk_ids.is_empty() {
+            let msg = format!("label {} has no primary key", mapping.label_id);
+            return Err(gen_graph_err!(ErrorCode::ILLEGAL_SCHEMA, msg, add_vertices));
+        }
+        let encoder = Encoder::new(Arc::new(Codec::from(type_def)));
+        let (header, rows) = open_input(&mapping.input)?;
+        let columns = resolve_columns(type_def, &mapping.columns, header.as_ref())?;
+        let mut buf = Vec::new();
+        let mut count = 0;
+        for row in rows {
+            let row = row?;
+            let props = parse_row(&row, &columns, mapping.input.list_delimiter)?;
+            let pks = get_pks(&props, &pk_ids, &mapping.input.path)?;
+            let vertex_id = get_vertex_id_by_primary_keys(mapping.label_id, pks.iter());
+            encoder.encode(&props, &mut buf)?;
+            let key = vertex_key(mapping.table_id, vertex_id, 0);
+            self.put(vertex_id, key.to_vec(), buf.clone());
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Returns the number of edges read, every edge is written in both directions.
+    pub fn add_edges(&mut self, mapping: &EdgeMapping) -> GraphResult<usize> {
+        let type_def = self.get_type_def(mapping.label_id)?;
+        let pk_ids = get_pk_ids(type_def);
+        let encoder = Encoder::new(Arc::new(Codec::from(type_def)));
+        let (header, rows) = open_input(&mapping.input)?;
+        let columns = resolve_columns(type_def, &mapping.columns, header.as_ref())?;
+        let src_columns =
+            self.resolve_pk_columns(mapping.src_label_id, &mapping.src_columns, header.as_ref())?;
+        let dst_columns =
+            self.resolve_pk_columns(mapping.dst_label_id, &mapping.dst_columns, header.as_ref())?;
+        let src_pk_ids = get_column_ids(&src_columns);
+        let dst_pk_ids = get_column_ids(&dst_columns);
+        let list_delimiter = mapping.input.list_delimiter;
+        let mut buf = Vec::new();
+        let mut count = 0;
+        for row in rows {
+            let row = row?;
+            let src_props = parse_row(&row, &src_columns, list_delimiter)?;
+            let src_pks = get_pks(&src_props, &src_pk_ids, &mapping.input.path)?;
+            let src_id = get_vertex_id_by_primary_keys(mapping.src_label_id, src_pks.iter());
+            let dst_props = parse_row(&row, &dst_columns, list_delimiter)?;
+            let dst_pks = get_pks(&dst_props, &dst_pk_ids, &mapping.input.path)?;
+            let dst_id = get_vertex_id_by_primary_keys(mapping.dst_label_id, dst_pks.iter());
+            let props = parse_row(&row, &columns, list_delimiter)?;
+            let inner_id = if pk_ids.is_empty() {
+                self.edge_seq += 1;
+                get_edge_inner_id_by_seq(src_id, dst_id, mapping.label_id, self.edge_seq)
+            } else {
+                let pks = get_pks(&props, &pk_ids, &mapping.input.path)?;
+                get_edge_inner_id_by_primary_keys(src_id, dst_id, mapping.label_id, pks.iter())
+            };
+            encoder.encode(&props, &mut buf)?;
+            let edge_id = EdgeId::new(src_id, dst_id, inner_id);
+            let out_key = edge_key(mapping.table_id, edge_id, EdgeDirection::Out, 0);
+            self.put(src_id, out_key.to_vec(), buf.clone());
+            let in_key = edge_key(mapping.table_id, edge_id, EdgeDirection::In, 0);
+            self.put(dst_id, in_key.to_vec(), buf.clone());
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Sort the records of every partition and write them into `output_dir`, partitions without
+    /// any record are skipped. When some records have the same key, the last added one is kept.
+    pub fn finish(self, output_dir: &Path) -> GraphResult<Vec<PathBuf>> {
+        fs::create_dir_all(output_dir).map_err(|e| {
+            let msg = format!("create {:?} failed because {}", output_dir, e);
+            gen_graph_err!(ErrorCode::INVALID_ARGUMENT, msg, finish)
+        })?;
+        let options = Options::default();
+        let mut files = Vec::new();
+        for (partition, mut records) in self.partitions.into_iter().enumerate() {
+            if records.is_empty() {
+                continue;
+            }
+            records.sort_by(|a, b| a.0.cmp(&b.0));
+            records.reverse();
+            records.dedup_by(|a, b| a.0 == b.0);
+            records.reverse();
+            let path = output_dir.join(format!("part-r-{:05}.sst", partition));
+            let mut writer = SstFileWriter::create(&options);
+            writer
+                .open(&path)
+                .map_err(|e| sst_err(&path, e))?;
+            for (key, value) in records {
+                writer
+                    .put(key, value)
+                    .map_err(|e| sst_err(&path, e))?;
+            }
+            writer.finish().map_err(|e| sst_err(&path, e))?;
+            files.push(path);
+        }
+        Ok(files)
+    }
+
+    fn put(&mut self, vertex_id: VertexId, key: Vec<u8>, value: Vec<u8>) {
+        let partition = vertex_id.rem_euclid(self.partitions.len() as i64) as usize;
+        self.partitions[partition].push((key, value));
+    }
+
+    fn get_type_def(&self, label_id: LabelId) -> GraphResult<&'a TypeDef> {
+        self.graph_def
+            .get_type(&label_id)
+            .ok_or_else(|| {
+                let msg = format!("label {} not found", label_id);
+                gen_graph_err!(ErrorCode::TYPE_NOT_FOUND, msg, get_type_def)
+            })
+    }
+
+    fn resolve_pk_columns(
+        &self, label_id: LabelId, columns: &[String], header: Option<&Vec<String>>,
+    ) -> GraphResult<Vec<(usize, PropDef)>> {
+        let type_def = self.get_type_def(label_id)?;
+        let pk_ids = get_pk_ids(type_def);
+        if pk_ids.len() != columns.len() {
+            let msg = format!(
+                "label {} has {} primary keys but {} columns are given",
+                label_id,
+                pk_ids.len(),
+                columns.len()
+            );
+            return Err(gen_graph_err!(ErrorCode::INVALID_ARGUMENT, msg, resolve_pk_columns));
+        }
+        let mappings: Vec<ColumnMapping> = columns
+            .iter()
+            .zip(pk_ids)
+            .map(|(column, property_id)| ColumnMapping { column: column.clone(), property_id })
+            .collect();
+        resolve_columns(type_def, &mappings, header)
+    }
+}
+
+/// Primary key property ids in the order they are hashed
+fn get_pk_ids(type_def: &TypeDef) -> Vec<PropertyId> {
+    let mut pk_ids: Vec<PropertyId> = type_def
+        .get_prop_defs()
+        .filter(|p| p.pk)
+        .map(|p| p.id)
+        .collect();
+    pk_ids.sort();
+    pk_ids
+}
+
+fn get_column_ids(columns: &[(usize, PropDef)]) -> Vec<PropertyId> {
+    columns.iter().map(|(_, p)| p.id).collect()
+}
+
+fn get_pks(
+    props: &HashMap<PropertyId, Value>, pk_ids: &[PropertyId], path: &str,
+) -> GraphResult<Vec<Vec<u8>>> {
+    pk_ids
+        .iter()
+        .map(|id| {
+            props
+                .get(id)
+                .map(|v| v.as_bytes().to_vec())
+                .ok_or_else(|| {
+                    let msg = format!("primary key {} is missing in a row of {}", id, path);
+                    gen_graph_err!(ErrorCode::INVALID_DATA, msg, get_pks)
+                })
+        })
+        .collect()
+}
+
+fn open_input(input: &InputConfig) -> GraphResult<(Option<Vec<String>>, reader::Rows)> {
+    let path = Path::new(&input.path);
+    let format = match input
+        .format
+        .or_else(|| FileFormat::from_path(path))
+    {
+        Some(format) => format,
+        None => {
+            let msg = format!("unknown format of {}", input.path);
+            return Err(gen_graph_err!(ErrorCode::INVALID_ARGUMENT, msg, open_input));
+        }
+    };
+    reader::read_rows(path, format, input.delimiter as u8, input.header)
+}
+
+fn resolve_columns(
+    type_def: &TypeDef, mappings: &[ColumnMapping], header: Option<&Vec<String>>,
+) -> GraphResult<Vec<(usize, PropDef)>> {
+    let mut columns = Vec::with_capacity(mappings.len());
+    for mapping in mappings {
+        let prop_def = type_def
+            .get_prop_def(mapping.property_id)
+            .ok_or_else(|| {
+                let msg = format!(
+                    "property {} not found in label {}",
+                    mapping.property_id,
+                    type_def.get_label_id()
+                );
+                gen_graph_err!(ErrorCode::PROPERTY_NOT_FOUND, msg, resolve_columns)
+            })?;
+        let index = match header {
+            Some(header) => header
+                .iter()
+                .position(|name| *name == mapping.column),
+            None => mapping.column.parse::<usize>().ok(),
+        };
+        let index = index.ok_or_else(|| {
+            let msg = format!("column {} not found", mapping.column);
+            gen_graph_err!(ErrorCode::INVALID_ARGUMENT, msg, resolve_columns)
+        })?;
+        columns.push((index, prop_def.clone()));
+    }
+    Ok(columns)
+}
+
+/// Empty cells are null, except for string and bytes properties.
+fn parse_row(
+    row: &[String], columns: &[(usize, PropDef)], list_delimiter: char,
+) -> GraphResult<HashMap<PropertyId, Value>> {
+    let mut props = HashMap::with_capacity(columns.len());
+    for (index, prop_def) in columns {
+        let cell = match row.get(*index) {
+            Some(cell) => cell.as_str(),
+            None => continue,
+        };
+        if cell.is_empty() && !matches!(prop_def.r#type, ValueType::String | ValueType::Bytes) {
+            continue;
+        }
+        let value = parse_value(cell, prop_def.r#type, list_delimiter).ok_or_else(|| {
+            let msg =
+                format!("cannot parse {:?} as {:?} of property {}", cell, prop_def.r#type, prop_def.name);
+            gen_graph_err!(ErrorCode::INVALID_DATA, msg, parse_row)
+        })?;
+        props.insert(prop_def.id, value);
+    }
+    Ok(props)
+}
+
+fn parse_value(s: &str, r#type: ValueType, list_delimiter: char) -> Option<Value> {
+    let value = match r#type {
+        ValueType::Bool => match s.to_lowercase().as_str() {
+            "true" | "1" => Value::bool(true),
+            "false" | "0" => Value::bool(false),
+            _ => return None,
+        },
+        ValueType::Char => Value::char(*s.as_bytes().first()?),
+        ValueType::Short => Value::short(s.parse().ok()?),
+        ValueType::Int => Value::int(s.parse().ok()?),
+        ValueType::Long => Value::long(s.parse().ok()?),
+        ValueType::Float => Value::float(s.parse().ok()?),
+        ValueType::Double => Value::double(s.parse().ok()?),
+        ValueType::String => Value::string(s),
+        ValueType::Bytes => Value::bytes(s.as_bytes()),
+        ValueType::IntList => Value::int_list(&parse_list(s, list_delimiter)?),
+        ValueType::LongList => Value::long_list(&parse_list(s, list_delimiter)?),
+        ValueType::FloatList => Value::float_list(&parse_list(s, list_delimiter)?),
+        ValueType::DoubleList => Value::double_list(&parse_list(s, list_delimiter)?),
+        ValueType::StringList => Value::string_list(&parse_list(s, list_delimiter)?),
+        ValueType::Date => Value::date(str_to_date(s)?),
+        ValueType::DateTime => Value::datetime(parse_datetime(s)?),
+        ValueType::DateTimeWithTz => {
+            let datetime = chrono::DateTime::parse_from_rfc3339(s).ok()?;
+            Value::datetime_with_tz(datetime.timestamp_millis(), datetime.offset().local_minus_utc())
+        }
+        ValueType::Decimal => {
+            let (unscaled, scale) = parse_decimal(s)?;
+            Value::decimal(unscaled, scale)
+        }
+    };
+    Some(value)
+}
+
+fn parse_list<T: std::str::FromStr>(s: &str, delimiter: char) -> Option<Vec<T>> {
+    if s.is_empty() {
+        return Some(Vec::new());
+    }
+    s.split(delimiter)
+        .map(|x| x.parse().ok())
+        .collect()
+}
+
+/// milliseconds since epoch, or a rfc3339 string, or `%Y-%m-%d %H:%M:%S%.f` in utc
+fn parse_datetime(s: &str) -> Option<i64> {
+    if let Ok(millis) = s.parse::<i64>() {
+        return Some(millis);
+    }
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Some(datetime.timestamp_millis());
+    }
+    chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|datetime| datetime.and_utc().timestamp_millis())
+}
+
+/// "-12.345" -> (-12345, 3)
+fn parse_decimal(s: &str) -> Option<(i128, u8)> {
+    let (int_part, frac_part) = match s.find('.') {
+        Some(pos) => (&s[..pos], &s[pos + 1..]),
+        None => (s, ""),
+    };
+    if !frac_part.chars().all(|c| c.is_ascii_digit()) || frac_part.len() > u8::MAX as usize {
+        return None;
+    }
+    let unscaled = format!("{}{}", int_part, frac_part)
+        .parse::<i128>()
+        .ok()?;
+    Some((unscaled, frac_part.len() as u8))
+}
+
+fn sst_err(path: &Path, e: rocksdb::Error) -> GraphError {
+    let msg = format!("write {:?} failed because {}", path, e.into_string());
+    gen_graph_err!(ErrorCode::EXTERNAL_STORAGE_ERROR, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_value() {
+        let v = parse_value("-12.345", ValueType::Decimal, ';').unwrap();
+        assert_eq!(v.as_ref().get_decimal().unwrap(), (-12345, 3));
+        let v = parse_value("1;2;3", ValueType::LongList, ';').unwrap();
+        assert_eq!(
+            v.as_ref()
+                .get_long_list()
+                .unwrap()
+                .iter()
+                .collect::<Vec<i64>>(),
+            vec![1, 2, 3]
+        );
+        let v = parse_value("2024-01-02T03:04:05+08:00", ValueType::DateTimeWithTz, ';').unwrap();
+        assert_eq!(v.as_ref().get_datetime_with_tz().unwrap(), (1704135845000, 8 * 3600));
+        let v = parse_value("2024-01-02 03:04:05", ValueType::DateTime, ';').unwrap();
+        assert_eq!(v.as_ref().get_datetime().unwrap(), 1704164645000);
+        assert!(parse_value("yes", ValueType::Bool, ';').is_none());
+        assert!(parse_value("1.5", ValueType::Int, ';').is_none());
+    }
+}
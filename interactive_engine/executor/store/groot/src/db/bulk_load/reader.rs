@@ -0,0 +1,131 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use csv::ReaderBuilder;
+use flate2::read::GzDecoder;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::reader::RowIter;
+use parquet::record::Field;
+
+use crate::db::api::*;
+
+pub type Rows = Box<dyn Iterator<Item = GraphResult<Vec<String>>>>;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FileFormat {
+    Csv,
+    CsvGz,
+    Parquet,
+}
+
+impl FileFormat {
+    /// Infer the format of a file from the suffix of its name.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<FileFormat> {
+        let path_str = path.as_ref().to_str()?;
+        if path_str.ends_with(".csv.gz") {
+            Some(FileFormat::CsvGz)
+        } else if path_str.ends_with(".csv") {
+            Some(FileFormat::Csv)
+        } else if path_str.ends_with(".parquet") {
+            Some(FileFormat::Parquet)
+        } else {
+            None
+        }
+    }
+}
+
+/// Open the file at `path`, returns the column names if the file has them and its rows.
+pub fn read_rows(
+    path: &Path, format: FileFormat, delimiter: u8, has_header: bool,
+) -> GraphResult<(Option<Vec<String>>, Rows)> {
+    match format {
+        FileFormat::Csv => {
+            let file = File::open(path).map_err(|e| io_err(path, e))?;
+            read_csv(path, Box::new(BufReader::new(file)), delimiter, has_header)
+        }
+        FileFormat::CsvGz => {
+            let file = File::open(path).map_err(|e| io_err(path, e))?;
+            read_csv(path, Box::new(BufReader::new(GzDecoder::new(file))), delimiter, has_header)
+        }
+        FileFormat::Parquet => read_parquet(path),
+    }
+}
+
+fn read_csv(
+    path: &Path, reader: Box<dyn Read>, delimiter: u8, has_header: bool,
+) -> GraphResult<(Option<Vec<String>>, Rows)> {
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .comment(Some(b'#'))
+        .flexible(true)
+        .has_headers(has_header)
+        .from_reader(reader);
+    let header = if has_header {
+        let header = rdr
+            .headers()
+            .map_err(|e| csv_err(path, e))?
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        Some(header)
+    } else {
+        None
+    };
+    let path = path.to_path_buf();
+    let rows = rdr.into_records().map(move |record| {
+        record
+            .map(|r| r.iter().map(|s| s.to_string()).collect())
+            .map_err(|e| csv_err(&path, e))
+    });
+    Ok((header, Box::new(rows)))
+}
+
+fn read_parquet(path: &Path) -> GraphResult<(Option<Vec<String>>, Rows)> {
+    let file = File::open(path).map_err(|e| io_err(path, e))?;
+    let reader = SerializedFileReader::new(file).map_err(|e| parquet_err(path, e))?;
+    let schema = reader
+        .metadata()
+        .file_metadata()
+        .schema_descr_ptr();
+    let header = (0..schema.num_columns())
+        .map(|i| schema.column(i).name().to_string())
+        .collect();
+    let rows = RowIter::from_file_into(Box::new(reader));
+    let path = path.to_path_buf();
+    let rows = rows.map(move |row| {
+        let row = row.map_err(|e| parquet_err(&path, e))?;
+        Ok(row
+            .get_column_iter()
+            .map(|(_, field)| field_to_string(field))
+            .collect())
+    });
+    Ok((Some(header), Box::new(rows)))
+}
+
+fn field_to_string(field: &Field) -> String {
+    match field {
+        Field::Null => "".to_string(),
+        Field::Str(s) => s.clone(),
+        Field::Bytes(bytes) => String::from_utf8_lossy(bytes.data()).to_string(),
+        Field::Date(days) => date_to_str(*days),
+        Field::TimestampMillis(millis) => millis.to_string(),
+        Field::TimestampMicros(micros) => (micros / 1000).to_string(),
+        _ => field.to_string(),
+    }
+}
+
+fn io_err(path: &Path, e: std::io::Error) -> GraphError {
+    let msg = format!("read {:?} failed because {}", path, e);
+    gen_graph_err!(ErrorCode::INVALID_ARGUMENT, msg)
+}
+
+fn csv_err(path: &Path, e: csv::Error) -> GraphError {
+    let msg = format!("read {:?} failed because {}", path, e);
+    gen_graph_err!(ErrorCode::INVALID_DATA, msg)
+}
+
+fn parquet_err(path: &Path, e: parquet::errors::ParquetError) -> GraphError {
+    let msg = format!("read {:?} failed because {}", path, e);
+    gen_graph_err!(ErrorCode::INVALID_DATA, msg)
+}
@@ -94,3 +94,69 @@ pub fn edge_prefix(id: TableId, vertex_id: VertexId, direction: EdgeDirection) -
     writer.write_i64(8, vertex_id.to_be());
     ret
 }
+
+/// Key of a secondary index entry: [index table prefix][encoded value][vertex id][!si]. The index
+/// table prefix is computed the same way as a vertex table prefix, so index entries of a table can
+/// be scanned and deleted like the vertex table. Index entries are versioned by the snapshot id
+/// they were written at, not relatively to the start of a table.
+pub fn vertex_index_key(table_id: TableId, value: &[u8], id: VertexId, si: SnapshotId) -> Vec<u8> {
+    let mut ret = Vec::with_capacity(24 + value.len());
+    ret.extend_from_slice(&vertex_table_prefix_key(table_id));
+    ret.extend_from_slice(value);
+    ret.extend_from_slice(&transform::i64_to_arr(id.to_be()));
+    ret.extend_from_slice(&transform::i64_to_arr((!si).to_be()));
+    ret
+}
+
+/// return (encoded value, vertex_id, si)
+pub fn parse_vertex_index_key(key: &[u8]) -> GraphResult<(&[u8], VertexId, SnapshotId)> {
+    if key.len() < 24 {
+        let msg = format!("invalid index key, key len is {}", key.len());
+        let err = gen_graph_err!(ErrorCode::INVALID_DATA, msg, parse_vertex_index_key);
+        return Err(err);
+    }
+    let value_end = key.len() - 16;
+    let reader = UnsafeBytesReader::new(&key[value_end..]);
+    let vertex_id = reader.read_i64(0).to_be();
+    let si = !reader.read_i64(8).to_be();
+    Ok((&key[8..value_end], vertex_id, si))
+}
+
+/// Exclusive end of the key range of a vertex or index table.
+pub fn vertex_table_end_key(table_id: TableId) -> [u8; 8] {
+    let end = vertex_table_prefix(table_id) + 1;
+    transform::i64_to_arr(end.to_be())
+}
+
+/// Key of an edge sort entry: [sort table prefix][vertex id][encoded value][other vertex id]
+/// [edge inner id][!si]. The sort table prefix is computed the same way as an edge table prefix, so
+/// entries of a vertex in a direction share the prefix `edge_prefix(table_id, vertex_id, direction)`
+/// and are ordered by the encoded value. `vertex_id` is the source of out entries and the
+/// destination of in entries.
+pub fn edge_sort_key(
+    table_id: TableId, direction: EdgeDirection, vertex_id: VertexId, value: &[u8], other_id: VertexId,
+    inner_id: EdgeInnerId, si: SnapshotId,
+) -> Vec<u8> {
+    let mut ret = Vec::with_capacity(40 + value.len());
+    ret.extend_from_slice(&edge_prefix(table_id, vertex_id, direction));
+    ret.extend_from_slice(value);
+    ret.extend_from_slice(&transform::i64_to_arr(other_id.to_be()));
+    ret.extend_from_slice(&transform::i64_to_arr(inner_id.to_be()));
+    ret.extend_from_slice(&transform::i64_to_arr((!si).to_be()));
+    ret
+}
+
+/// return (encoded value, other vertex id, edge inner id, si)
+pub fn parse_edge_sort_key(key: &[u8]) -> GraphResult<(&[u8], VertexId, EdgeInnerId, SnapshotId)> {
+    if key.len() < 40 {
+        let msg = format!("invalid sort key, key len is {}", key.len());
+        let err = gen_graph_err!(ErrorCode::INVALID_DATA, msg, parse_edge_sort_key);
+        return Err(err);
+    }
+    let value_end = key.len() - 24;
+    let reader = UnsafeBytesReader::new(&key[value_end..]);
+    let other_id = reader.read_i64(0).to_be();
+    let inner_id = reader.read_i64(8).to_be();
+    let si = !reader.read_i64(16).to_be();
+    Ok((&key[16..value_end], other_id, inner_id, si))
+}
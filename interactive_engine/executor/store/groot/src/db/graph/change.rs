@@ -0,0 +1,159 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use crate::db::api::change::{ChangeElement, ChangeEvent, ChangeType};
+use crate::db::api::*;
+use crate::db::graph::bin::{
+    edge_table_prefix_key, parse_edge_key, parse_vertex_key, vertex_table_prefix_key,
+};
+use crate::db::graph::codec::{get_codec_version, Decoder};
+use crate::db::graph::table_manager::Table;
+use crate::db::graph::types::{EdgeKindInfo, VertexTypeInfo};
+use crate::db::storage::rocksdb::RocksDB;
+use crate::db::storage::KvPair;
+
+type ParseKeyFn = Box<dyn Fn(&[u8]) -> GraphResult<(ChangeElement, SnapshotId)> + Send>;
+type DecoderFn = Box<dyn Fn(&[u8]) -> GraphResult<Decoder> + Send>;
+
+/// Changes of the vertices in `table` of `info` committed in `(from_si, to_si]`.
+pub fn scan_vertex_changes(
+    storage: &RocksDB, from_si: SnapshotId, to_si: SnapshotId, info: Arc<VertexTypeInfo>, table: Table,
+) -> GraphResult<Records<ChangeEvent>> {
+    let label = info.get_label();
+    let prefix = vertex_table_prefix_key(table.id);
+    let parse_key: ParseKeyFn =
+        Box::new(move |key| parse_vertex_key(key).map(|(id, ts)| (ChangeElement::Vertex(id, label), ts)));
+    let get_decoder: DecoderFn = Box::new(move |data| info.get_decoder(to_si, get_codec_version(data)));
+    let iter = ChangeIter::new(storage.new_scan(&prefix)?, from_si, to_si, table, parse_key, get_decoder);
+    Ok(Box::new(iter))
+}
+
+/// Changes of the edges in `table` of `info` committed in `(from_si, to_si]`, only the forward
+/// edges are scanned, so each edge is reported once.
+pub fn scan_edge_changes(
+    storage: &RocksDB, from_si: SnapshotId, to_si: SnapshotId, info: Arc<EdgeKindInfo>, table: Table,
+) -> GraphResult<Records<ChangeEvent>> {
+    let edge_kind = info.get_type().clone();
+    let prefix = edge_table_prefix_key(table.id, EdgeDirection::Out);
+    let parse_key: ParseKeyFn = Box::new(move |key| {
+        let (id, ts) = parse_edge_key(key);
+        Ok((ChangeElement::Edge(id, edge_kind.clone()), ts))
+    });
+    let get_decoder: DecoderFn = Box::new(move |data| info.get_decoder(to_si, get_codec_version(data)));
+    let iter = ChangeIter::new(storage.new_scan(&prefix)?, from_si, to_si, table, parse_key, get_decoder);
+    Ok(Box::new(iter))
+}
+
+/// Keys of an element are sorted from the newest version to the oldest one, so versions of the
+/// current element are buffered until the first version not newer than `from_si`, which is the
+/// base to tell an insert from an update, then turned into events in ascending order of si.
+struct ChangeIter {
+    iter: Box<dyn Iterator<Item = KvPair> + Send>,
+    start_si: SnapshotId,
+    from_ts: SnapshotId,
+    to_ts: SnapshotId,
+    parse_key: ParseKeyFn,
+    get_decoder: DecoderFn,
+    current: Option<ChangeElement>,
+    // (ts, data) of current element in descending order of ts
+    versions: Vec<(SnapshotId, Vec<u8>)>,
+    has_base: bool,
+    pending: VecDeque<GraphResult<ChangeEvent>>,
+}
+
+impl ChangeIter {
+    fn new(
+        iter: Box<dyn Iterator<Item = KvPair> + Send>, from_si: SnapshotId, to_si: SnapshotId,
+        table: Table, parse_key: ParseKeyFn, get_decoder: DecoderFn,
+    ) -> Self {
+        ChangeIter {
+            iter,
+            start_si: table.start_si,
+            from_ts: from_si - table.start_si,
+            to_ts: to_si - table.start_si,
+            parse_key,
+            get_decoder,
+            current: None,
+            versions: Vec::new(),
+            has_base: false,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn flush(&mut self) {
+        let element = match self.current.take() {
+            Some(element) => element,
+            None => return,
+        };
+        let versions = ::std::mem::replace(&mut self.versions, Vec::new());
+        let mut prev: Option<&[u8]> = None;
+        for (ts, data) in versions.iter().rev() {
+            let alive = data.len() >= 4;
+            if *ts <= self.from_ts {
+                prev = if alive { Some(data.as_slice()) } else { None };
+                continue;
+            }
+            let (change_type, props_data) = match (alive, prev) {
+                (true, None) => (ChangeType::Insert, data.as_slice()),
+                (true, Some(_)) => (ChangeType::Update, data.as_slice()),
+                (false, Some(prev_data)) => (ChangeType::Delete, prev_data),
+                (false, None) => continue,
+            };
+            let si = self.start_si + *ts;
+            let event = self
+                .decode_properties(props_data)
+                .map(|properties| ChangeEvent::new(si, change_type, element.clone(), properties));
+            self.pending.push_back(event);
+            prev = if alive { Some(data.as_slice()) } else { None };
+        }
+        self.has_base = false;
+    }
+
+    fn decode_properties(&self, data: &[u8]) -> GraphResult<HashMap<PropertyId, Value>> {
+        let decoder = (self.get_decoder)(data)?;
+        let properties = decoder
+            .decode_all(data)
+            .iter()
+            .map(|(prop_id, v)| (*prop_id, Value::from_value_ref(v)))
+            .collect();
+        Ok(properties)
+    }
+}
+
+impl Iterator for ChangeIter {
+    type Item = GraphResult<ChangeEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            let (raw_key, raw_val) = match self.iter.next() {
+                Some(kv) => kv,
+                None => {
+                    if self.current.is_none() {
+                        return None;
+                    }
+                    self.flush();
+                    continue;
+                }
+            };
+            let (element, ts) = match (self.parse_key)(raw_key.to_slice()) {
+                Ok(res) => res,
+                Err(e) => return Some(Err(e)),
+            };
+            if self.current.as_ref() != Some(&element) {
+                self.flush();
+                self.current = Some(element);
+            }
+            if self.has_base || ts > self.to_ts {
+                continue;
+            }
+            self.versions
+                .push((ts, raw_val.to_slice().to_vec()));
+            if ts <= self.from_ts {
+                self.has_base = true;
+            }
+        }
+    }
+}
@@ -11,6 +11,7 @@ use super::version::*;
 use crate::db::api::*;
 use crate::db::common::bytes::util::{UnsafeBytesReader, UnsafeBytesWriter};
 use crate::db::util::lock::GraphMutexLock;
+use crate::db::util::time::current_time_millis;
 
 pub type CodecVersion = i32;
 
@@ -26,6 +27,9 @@ pub type CodecVersion = i32;
 /// | var len prop1 | var len prop2 .... | ... | var len propM |
 /// +---------------+--------------------+-----+---------------+
 /// ↑ this is `var_len_prop_start_offset`
+///
+/// If the type has a ttl, an 8 bytes big endian write time in milliseconds follows the var len
+/// properties, decoders never read beyond the properties so it's invisible to them.
 #[allow(dead_code)]
 #[derive(Clone, Debug)]
 pub struct Codec {
@@ -39,6 +43,7 @@ pub struct Codec {
     fixed_len_prop_count: usize,
     var_len_prop_start_offset: usize,
     null_bytes: Vec<u8>,
+    ttl_ms: Option<i64>,
 }
 
 impl Codec {
@@ -46,6 +51,10 @@ impl Codec {
         self.version
     }
 
+    pub fn get_ttl_ms(&self) -> Option<i64> {
+        self.ttl_ms
+    }
+
     pub fn from(type_def: &TypeDef) -> Self {
         let mut prop_defs: Vec<&PropDef> = type_def.get_prop_defs().collect();
         prop_defs.sort_by(|a, b| {
@@ -93,6 +102,7 @@ impl Codec {
             fixed_len_prop_count,
             var_len_prop_start_offset,
             null_bytes,
+            ttl_ms: type_def.get_ttl_ms(),
         }
     }
 }
@@ -176,8 +186,10 @@ impl Decoder {
         let bytes = match info.r#type {
             ValueType::Bool | ValueType::Char => reader.read_bytes(offset, 1),
             ValueType::Short => reader.read_bytes(offset, 2),
-            ValueType::Int | ValueType::Float => reader.read_bytes(offset, 4),
-            ValueType::Double | ValueType::Long => reader.read_bytes(offset, 8),
+            ValueType::Int | ValueType::Float | ValueType::Date => reader.read_bytes(offset, 4),
+            ValueType::Double | ValueType::Long | ValueType::DateTime => reader.read_bytes(offset, 8),
+            ValueType::DateTimeWithTz => reader.read_bytes(offset, 12),
+            ValueType::Decimal => reader.read_bytes(offset, 17),
             _ => unreachable!(),
         };
         let ret = ValueRef::new(info.r#type, bytes);
@@ -309,6 +321,13 @@ impl Encoder {
     }
 
     pub fn encode(&self, props: &dyn PropertyMap, buf: &mut Vec<u8>) -> GraphResult<()> {
+        self.encode_at(props, buf, current_time_millis() as i64)
+    }
+
+    /// Encode `props` written at `write_time_ms`, which is kept in the value if the codec has a ttl.
+    pub fn encode_at(
+        &self, props: &dyn PropertyMap, buf: &mut Vec<u8>, write_time_ms: i64,
+    ) -> GraphResult<()> {
         // the vector pass to encoder may be not filled with zeros, so encoder should make sure
         // every bit is written by itself and set the vector's len to real length.
         let size = res_unwrap!(self.check_and_cal_size(props), encode)?;
@@ -318,15 +337,28 @@ impl Encoder {
         unsafe {
             buf.set_len(size);
         }
-        let mut writer = UnsafeBytesWriter::new(buf);
-        writer.write_i32(0, self.codec.version.to_be());
-        let mut null_byte = 0;
-        // write fixed len property
-        self.encode_fix_len_properties(&mut writer, props, &mut null_byte)?;
-        self.encode_var_len_properties(&mut writer, props, &mut null_byte)?;
+        {
+            let mut writer = UnsafeBytesWriter::new(buf);
+            writer.write_i32(0, self.codec.version.to_be());
+            let mut null_byte = 0;
+            // write fixed len property
+            self.encode_fix_len_properties(&mut writer, props, &mut null_byte)?;
+            self.encode_var_len_properties(&mut writer, props, &mut null_byte)?;
+        }
+        if self.codec.ttl_ms.is_some() {
+            buf.extend_from_slice(&write_time_ms.to_be_bytes());
+        }
         Ok(())
     }
 
+    pub fn get_ttl_ms(&self) -> Option<i64> {
+        self.codec.ttl_ms
+    }
+
+    pub fn get_version(&self) -> CodecVersion {
+        self.codec.version
+    }
+
     fn encode_fix_len_properties(
         &self, writer: &mut UnsafeBytesWriter, props: &dyn PropertyMap, null_byte: &mut u8,
     ) -> GraphResult<()> {
@@ -482,8 +514,10 @@ fn check_fixed_prop_len(r#type: ValueType, data: &[u8]) -> bool {
     match r#type {
         ValueType::Bool | ValueType::Char => data.len() == 1,
         ValueType::Short => data.len() == 2,
-        ValueType::Int | ValueType::Float => data.len() == 4,
-        ValueType::Long | ValueType::Double => data.len() == 8,
+        ValueType::Int | ValueType::Float | ValueType::Date => data.len() == 4,
+        ValueType::Long | ValueType::Double | ValueType::DateTime => data.len() == 8,
+        ValueType::DateTimeWithTz => data.len() == 12,
+        ValueType::Decimal => data.len() == 17,
         _ => {
             panic!("need fixed len type but meet {:?}", r#type)
         }
@@ -609,6 +643,39 @@ impl CodecManager {
         Err(err)
     }
 
+    /// The ttl of records written by the codec of `version`.
+    pub fn get_ttl_ms(&self, version: CodecVersion) -> Option<i64> {
+        let guard = epoch::pin();
+        let map = self.get_map(&guard);
+        unsafe { map.as_ref() }
+            .and_then(|map_ref| map_ref.get(&version))
+            .and_then(|codec| codec.ttl_ms)
+    }
+
+    /// Whether any codec version has a ttl.
+    pub fn has_ttl(&self) -> bool {
+        let guard = epoch::pin();
+        let map = self.get_map(&guard);
+        unsafe { map.as_ref() }.map_or(false, |map_ref| {
+            map_ref
+                .values()
+                .any(|codec| codec.ttl_ms.is_some())
+        })
+    }
+
+    /// The ttl of each codec version that has one.
+    pub fn get_ttl_versions(&self) -> HashMap<CodecVersion, i64> {
+        let guard = epoch::pin();
+        let map = self.get_map(&guard);
+        match unsafe { map.as_ref() } {
+            Some(map_ref) => map_ref
+                .iter()
+                .filter_map(|(version, codec)| codec.ttl_ms.map(|ttl_ms| (*version, ttl_ms)))
+                .collect(),
+            None => HashMap::new(),
+        }
+    }
+
     #[allow(dead_code)]
     pub fn drop_codec(&self, version: CodecVersion) -> GraphResult<()> {
         let _lock = res_unwrap!(self.lock.lock(), drop_codec, version)?;
@@ -646,6 +713,7 @@ fn get_codec(map: &CodecMap, version: CodecVersion) -> GraphResult<Arc<Codec>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::db::storage::{is_expired, WRITE_TIME_LEN};
 
     #[test]
     fn test_bytes_len_transform() {
@@ -739,6 +807,95 @@ mod tests {
         check_properties(decoder, &buf, test_data());
     }
 
+    #[test]
+    fn test_encode_write_time() {
+        let mut type_def = create_test_type_def();
+        type_def.set_ttl_ms(Some(1000));
+        let codec = Arc::new(Codec::from(&type_def));
+        let mut expected = Vec::new();
+        Encoder::new(Arc::new(create_test_codec()))
+            .encode(&test_data(), &mut expected)
+            .unwrap();
+        let mut buf = vec![255; 1000];
+        Encoder::new(codec.clone())
+            .encode_at(&test_data(), &mut buf, 5000)
+            .unwrap();
+        assert_eq!(buf.len(), expected.len() + WRITE_TIME_LEN);
+        // the write time trailer is invisible to decoders
+        let decoder = Decoder::new(codec.clone(), codec);
+        check_properties(decoder, &buf, test_data());
+        assert!(!is_expired(&buf, 1000, 5999));
+        assert!(is_expired(&buf, 1000, 6000));
+        // tombstones never expire
+        assert!(!is_expired(&[], 1000, 6000));
+    }
+
+    #[test]
+    fn test_codec_manager_ttl_versions() {
+        let manager = CodecManager::new();
+        manager
+            .add_codec(1, create_test_codec())
+            .unwrap();
+        let mut type_def = create_test_type_def();
+        type_def.set_version(11);
+        type_def.set_ttl_ms(Some(1000));
+        manager
+            .add_codec(2, Codec::from(&type_def))
+            .unwrap();
+        // records keep the ttl of the codec that wrote them
+        assert_eq!(manager.get_ttl_ms(10), None);
+        assert_eq!(manager.get_ttl_ms(11), Some(1000));
+        assert_eq!(manager.get_ttl_ms(12), None);
+        let expected: HashMap<CodecVersion, i64> = vec![(11, 1000)].into_iter().collect();
+        assert_eq!(manager.get_ttl_versions(), expected);
+        assert!(manager.has_ttl());
+        assert!(!CodecManager::new().has_ttl());
+    }
+
+    #[test]
+    fn test_codec_version_compatibility() {
+        // version 11 appends temporal and decimal properties to the type of version 10
+        let old_codec = Arc::new(create_test_codec());
+        let mut builder = TypeDefBuilder::new();
+        builder.version(11);
+        for (prop_id, inner_id, r#type) in test_prop_list()
+            .into_iter()
+            .chain(temporal_prop_list())
+        {
+            builder.add_property(
+                prop_id,
+                inner_id,
+                prop_id.to_string(),
+                r#type,
+                None,
+                false,
+                "cmt".to_string(),
+            );
+        }
+        let new_codec = Arc::new(Codec::from(&builder.build()));
+        assert_eq!(new_codec.fixed_len_prop_count, 11);
+
+        // data written before the type is altered can be read with the new type
+        let mut buf = Vec::new();
+        create_encoder(old_codec.clone())
+            .encode(&test_data(), &mut buf)
+            .unwrap();
+        let decoder = Decoder::new(new_codec.clone(), old_codec.clone());
+        check_properties(decoder.clone(), &buf, test_data());
+        for (prop_id, _, _) in temporal_prop_list() {
+            assert!(decoder.decode_property(&buf, prop_id).is_none());
+        }
+
+        // data written with the new type can be read with both types
+        let mut data = test_data();
+        data.extend(temporal_data());
+        create_encoder(new_codec.clone())
+            .encode(&data, &mut buf)
+            .unwrap();
+        check_properties(create_decoder(new_codec.clone()), &buf, data);
+        check_properties(Decoder::new(old_codec, new_codec), &buf, test_data());
+    }
+
     #[test]
     fn test_default_value() {
         let codec = Arc::new(create_default_value_codec());
@@ -824,6 +981,24 @@ mod tests {
         ]
     }
 
+    fn temporal_prop_list() -> Vec<(PropertyId, PropertyId, ValueType)> {
+        vec![
+            (15, 15, ValueType::Date),
+            (16, 16, ValueType::DateTime),
+            (17, 17, ValueType::DateTimeWithTz),
+            (18, 18, ValueType::Decimal),
+        ]
+    }
+
+    fn temporal_data() -> HashMap<PropertyId, Value> {
+        let mut map = HashMap::new();
+        map.insert(15, Value::date(18321));
+        map.insert(16, Value::datetime(1582934400123));
+        map.insert(17, Value::datetime_with_tz(1582934400123, -5 * 3600));
+        map.insert(18, Value::decimal(-3141592653589793238462643383279, 30));
+        map
+    }
+
     fn default_value() -> HashMap<PropertyId, Value> {
         let mut map = HashMap::new();
         map.insert(1, Value::string("default-string"));
@@ -863,6 +1038,10 @@ mod tests {
     }
 
     fn create_test_codec() -> Codec {
+        Codec::from(&create_test_type_def())
+    }
+
+    fn create_test_type_def() -> TypeDef {
         let mut builder = TypeDefBuilder::new();
         builder.version(10);
         for (prop_id, inner_id, r#type) in test_prop_list() {
@@ -876,8 +1055,7 @@ mod tests {
                 "cmt".to_string(),
             );
         }
-        let type_def = builder.build();
-        Codec::from(&type_def)
+        builder.build()
     }
 
     fn create_default_value_codec() -> Codec {
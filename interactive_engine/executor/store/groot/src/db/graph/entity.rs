@@ -4,7 +4,7 @@ use std::fmt::{Debug, Formatter};
 use super::codec::SpecIterDecoder;
 use crate::api::{Edge, Vertex};
 use crate::db::api::types::{Property, PropertyReader, PropertyValue, RocksEdge, RocksVertex};
-use crate::db::api::{EdgeId, EdgeKind, GraphResult, LabelId, PropertyId, ValueRef, VertexId};
+use crate::db::api::{date_to_str, EdgeId, EdgeKind, GraphResult, LabelId, PropertyId, ValueRef, VertexId};
 use crate::db::graph::codec::{Decoder, IterDecoder};
 use crate::db::storage::RawBytes;
 use crate::schema::PropId;
@@ -42,6 +42,14 @@ impl PropertyImpl {
             PropertyValue::FloatList(fl) => crate::api::prelude::Property::ListFloat(fl),
             PropertyValue::DoubleList(dl) => crate::api::prelude::Property::ListDouble(dl),
             PropertyValue::StringList(sl) => crate::api::prelude::Property::ListString(sl),
+            PropertyValue::Date(d) => crate::api::prelude::Property::Date(date_to_str(d)),
+            PropertyValue::DateTime(dt) => crate::api::prelude::Property::DateTime(dt),
+            PropertyValue::DateTimeWithTz(dt, offset) => {
+                crate::api::prelude::Property::DateTimeWithTz(dt, offset)
+            }
+            PropertyValue::Decimal(unscaled, scale) => {
+                crate::api::prelude::Property::Decimal(unscaled, scale)
+            }
         };
         (self.property_id as PropId, p)
     }
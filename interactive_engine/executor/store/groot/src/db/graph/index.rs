@@ -0,0 +1,564 @@
+use std::collections::HashMap;
+use std::ops::Bound;
+use std::sync::Arc;
+
+use super::bin::*;
+use super::table_manager::TableId;
+use crate::api::condition::{CmpOperator, Operand, PredCondition};
+use crate::api::prelude::Property;
+use crate::api::Condition;
+use crate::db::api::*;
+use crate::db::storage::rocksdb::RocksDB;
+use crate::db::storage::KvPair;
+use crate::db::util::lock::GraphMutexLock;
+
+/// Value of an index entry of a live (label, property) pair. Entries written when a vertex is
+/// deleted or its indexed property changes have an empty value, like deleted vertices do.
+pub const INDEX_ENTRY_ALIVE: [u8; 1] = [1];
+
+/// A secondary index on one property of a vertex type. Index entries live in the key range of
+/// `table_id`, which is allocated by the frontend like any other table id.
+#[derive(Debug)]
+pub struct VertexIndexInfo {
+    label: LabelId,
+    prop_id: PropertyId,
+    table_id: TableId,
+    value_type: ValueType,
+    start_si: SnapshotId,
+}
+
+impl VertexIndexInfo {
+    pub fn get_label(&self) -> LabelId {
+        self.label
+    }
+
+    pub fn get_prop_id(&self) -> PropertyId {
+        self.prop_id
+    }
+
+    pub fn get_table_id(&self) -> TableId {
+        self.table_id
+    }
+
+    pub fn get_value_type(&self) -> ValueType {
+        self.value_type
+    }
+
+    pub fn get_start_si(&self) -> SnapshotId {
+        self.start_si
+    }
+
+    fn is_alive_at(&self, si: SnapshotId) -> bool {
+        self.start_si <= si
+    }
+}
+
+#[derive(Default)]
+struct IndexMap {
+    indexes: HashMap<LabelId, Vec<Arc<VertexIndexInfo>>>,
+    // (drop si, table id) of dropped indexes whose entries are waiting for gc
+    dropped: Vec<(SnapshotId, TableId)>,
+}
+
+pub struct VertexIndexManager {
+    inner: GraphMutexLock<IndexMap>,
+}
+
+impl VertexIndexManager {
+    pub fn new() -> Self {
+        VertexIndexManager { inner: GraphMutexLock::new(IndexMap::default()) }
+    }
+
+    pub fn contains_index(&self, label: LabelId, prop_id: PropertyId) -> bool {
+        match self.inner.lock() {
+            Ok(inner) => inner
+                .indexes
+                .get(&label)
+                .map_or(false, |list| list.iter().any(|i| i.prop_id == prop_id)),
+            Err(_) => false,
+        }
+    }
+
+    pub fn create_index(
+        &self, si: SnapshotId, label: LabelId, prop_id: PropertyId, value_type: ValueType,
+        table_id: TableId,
+    ) -> GraphResult<Arc<VertexIndexInfo>> {
+        if !is_indexable(value_type) {
+            let msg = format!("property#{} of type {:?} cannot be indexed", prop_id, value_type);
+            let err =
+                gen_graph_err!(ErrorCode::UNSUPPORTED_OPERATION, msg, create_index, si, label, prop_id);
+            return Err(err);
+        }
+        let mut inner = self.inner.lock()?;
+        let list = inner
+            .indexes
+            .entry(label)
+            .or_insert_with(Vec::new);
+        if list.iter().any(|i| i.prop_id == prop_id) {
+            let msg = format!("index on property#{} of vertex#{} already exists", prop_id, label);
+            let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, create_index, si, label, prop_id);
+            return Err(err);
+        }
+        let info = Arc::new(VertexIndexInfo { label, prop_id, table_id, value_type, start_si: si });
+        list.push(info.clone());
+        Ok(info)
+    }
+
+    pub fn drop_index(&self, si: SnapshotId, label: LabelId, prop_id: PropertyId) -> GraphResult<()> {
+        let mut inner = self.inner.lock()?;
+        let dropped = match inner.indexes.get_mut(&label) {
+            Some(list) => match list.iter().position(|i| i.prop_id == prop_id) {
+                Some(pos) => list.remove(pos),
+                None => {
+                    let msg = format!("index on property#{} of vertex#{} not found", prop_id, label);
+                    let err =
+                        gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, drop_index, si, label, prop_id);
+                    return Err(err);
+                }
+            },
+            None => {
+                let msg = format!("vertex#{} has no index", label);
+                let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, drop_index, si, label, prop_id);
+                return Err(err);
+            }
+        };
+        inner.dropped.push((si, dropped.table_id));
+        Ok(())
+    }
+
+    /// Drop all indexes of `label`, used when the vertex type itself is dropped.
+    pub fn drop_label(&self, si: SnapshotId, label: LabelId) -> GraphResult<()> {
+        let mut inner = self.inner.lock()?;
+        if let Some(list) = inner.indexes.remove(&label) {
+            for info in list {
+                inner.dropped.push((si, info.table_id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the indexes of `label` that are usable at `si`.
+    pub fn get_indexes(&self, si: SnapshotId, label: LabelId) -> GraphResult<Vec<Arc<VertexIndexInfo>>> {
+        let inner = self.inner.lock()?;
+        let ret = match inner.indexes.get(&label) {
+            Some(list) => list
+                .iter()
+                .filter(|i| i.is_alive_at(si))
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+        Ok(ret)
+    }
+
+    /// Returns table ids of all indexes that are not dropped.
+    pub fn get_table_ids(&self) -> GraphResult<Vec<TableId>> {
+        let inner = self.inner.lock()?;
+        let ret = inner
+            .indexes
+            .values()
+            .flat_map(|list| list.iter().map(|i| i.table_id))
+            .collect();
+        Ok(ret)
+    }
+
+    /// Returns table ids of indexes dropped before `si`, whose entries can be removed.
+    pub fn gc(&self, si: SnapshotId) -> GraphResult<Vec<TableId>> {
+        let mut inner = self.inner.lock()?;
+        let mut table_ids = Vec::new();
+        inner.dropped.retain(|(drop_si, table_id)| {
+            if *drop_si <= si {
+                table_ids.push(*table_id);
+                false
+            } else {
+                true
+            }
+        });
+        Ok(table_ids)
+    }
+}
+
+pub fn is_indexable(value_type: ValueType) -> bool {
+    match value_type {
+        ValueType::IntList
+        | ValueType::LongList
+        | ValueType::FloatList
+        | ValueType::DoubleList
+        | ValueType::StringList
+        | ValueType::Decimal => false,
+        _ => true,
+    }
+}
+
+/// Encode `value` so that the byte order of the result is the order of the values. Integers
+/// are widened to i64 and floats to f64 so that a query constant of another width of the same
+/// kind encodes to a comparable key.
+pub fn encode_index_value(value: &ValueRef) -> Option<Vec<u8>> {
+    let mut buf = Vec::new();
+    match value.get_type() {
+        ValueType::Bool => buf.push(value.get_bool().ok()? as u8),
+        ValueType::Char => buf.push(value.get_char().ok()?),
+        ValueType::Short | ValueType::Int | ValueType::Long => encode_long(value.to_long()?, &mut buf),
+        ValueType::Float | ValueType::Double => encode_double(value.to_double()?, &mut buf),
+        ValueType::String => encode_bytes(value.get_str().ok()?.as_bytes(), &mut buf),
+        ValueType::Bytes => encode_bytes(value.get_bytes().ok()?, &mut buf),
+        ValueType::Date => encode_long(value.get_date().ok()? as i64, &mut buf),
+        // datetimes of any time zone are ordered by the instant
+        ValueType::DateTime | ValueType::DateTimeWithTz => encode_long(value.to_epoch_millis()?, &mut buf),
+        _ => return None,
+    }
+    Some(buf)
+}
+
+/// Encode a query constant for an index on a property of `value_type`, returns `None` if the
+/// constant cannot be compared through the index.
+fn encode_index_const(value_type: ValueType, value: &Property) -> Option<Vec<u8>> {
+    let mut buf = Vec::new();
+    match (value_type, value) {
+        (ValueType::Bool, Property::Bool(v)) => buf.push(*v as u8),
+        (ValueType::Char, Property::Char(v)) => buf.push(*v),
+        (ValueType::Short, _) | (ValueType::Int, _) | (ValueType::Long, _) => match value {
+            Property::Short(v) => encode_long(*v as i64, &mut buf),
+            Property::Int(v) => encode_long(*v as i64, &mut buf),
+            Property::Long(v) => encode_long(*v, &mut buf),
+            _ => return None,
+        },
+        (ValueType::Float, _) | (ValueType::Double, _) => match value {
+            Property::Short(v) => encode_double(*v as f64, &mut buf),
+            Property::Int(v) => encode_double(*v as f64, &mut buf),
+            Property::Long(v) => encode_double(*v as f64, &mut buf),
+            Property::Float(v) => encode_double(*v as f64, &mut buf),
+            Property::Double(v) => encode_double(*v, &mut buf),
+            _ => return None,
+        },
+        (ValueType::String, Property::String(v)) => encode_bytes(v.as_bytes(), &mut buf),
+        (ValueType::Bytes, Property::Bytes(v)) => encode_bytes(v, &mut buf),
+        (ValueType::Date, Property::Date(v)) => encode_long(str_to_date(v)? as i64, &mut buf),
+        (ValueType::DateTime, _) | (ValueType::DateTimeWithTz, _) => match value {
+            Property::DateTime(v) | Property::DateTimeWithTz(v, _) => encode_long(*v, &mut buf),
+            _ => return None,
+        },
+        _ => return None,
+    }
+    Some(buf)
+}
+
+fn encode_long(v: i64, buf: &mut Vec<u8>) {
+    buf.extend_from_slice(&((v as u64) ^ (1 << 63)).to_be_bytes());
+}
+
+fn encode_double(v: f64, buf: &mut Vec<u8>) {
+    // -0.0 equals to 0.0, so they must share a same encoding
+    let v = if v == 0.0 { 0.0 } else { v };
+    let bits = v.to_bits();
+    let bits = if bits >> 63 == 1 { !bits } else { bits ^ (1 << 63) };
+    buf.extend_from_slice(&bits.to_be_bytes());
+}
+
+/// 0x00 is escaped as 0x00 0xff and the value is terminated by 0x00 0x01, so a value never is a
+/// prefix of another one and the order of keys follows the order of the raw bytes.
+fn encode_bytes(v: &[u8], buf: &mut Vec<u8>) {
+    for b in v {
+        buf.push(*b);
+        if *b == 0 {
+            buf.push(0xff);
+        }
+    }
+    buf.push(0);
+    buf.push(1);
+}
+
+/// Range of encoded values selected by a condition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexRange {
+    lower: Bound<Vec<u8>>,
+    upper: Bound<Vec<u8>>,
+}
+
+impl IndexRange {
+    fn all() -> Self {
+        IndexRange { lower: Bound::Unbounded, upper: Bound::Unbounded }
+    }
+
+    fn from_cmp(op: CmpOperator, value: Vec<u8>) -> Option<Self> {
+        let range = match op {
+            CmpOperator::Equal => {
+                IndexRange { lower: Bound::Included(value.clone()), upper: Bound::Included(value) }
+            }
+            CmpOperator::LessThan => IndexRange { lower: Bound::Unbounded, upper: Bound::Excluded(value) },
+            CmpOperator::LessEqual => IndexRange { lower: Bound::Unbounded, upper: Bound::Included(value) },
+            CmpOperator::GreaterThan => {
+                IndexRange { lower: Bound::Excluded(value), upper: Bound::Unbounded }
+            }
+            CmpOperator::GreaterEqual => {
+                IndexRange { lower: Bound::Included(value), upper: Bound::Unbounded }
+            }
+            _ => return None,
+        };
+        Some(range)
+    }
+
+    fn intersect(self, other: IndexRange) -> IndexRange {
+        let lower = max_lower(self.lower, other.lower);
+        let upper = min_upper(self.upper, other.upper);
+        IndexRange { lower, upper }
+    }
+
+    fn contains(&self, value: &[u8]) -> bool {
+        let above_lower = match &self.lower {
+            Bound::Included(v) => value >= v.as_slice(),
+            Bound::Excluded(v) => value > v.as_slice(),
+            Bound::Unbounded => true,
+        };
+        above_lower && !self.is_above_upper(value)
+    }
+
+    fn is_above_upper(&self, value: &[u8]) -> bool {
+        match &self.upper {
+            Bound::Included(v) => value > v.as_slice(),
+            Bound::Excluded(v) => value >= v.as_slice(),
+            Bound::Unbounded => false,
+        }
+    }
+}
+
+fn max_lower(a: Bound<Vec<u8>>, b: Bound<Vec<u8>>) -> Bound<Vec<u8>> {
+    match (&a, &b) {
+        (Bound::Unbounded, _) => b,
+        (_, Bound::Unbounded) => a,
+        (Bound::Included(x), Bound::Included(y)) | (Bound::Excluded(x), Bound::Excluded(y)) => {
+            if x >= y {
+                a
+            } else {
+                b
+            }
+        }
+        (Bound::Included(x), Bound::Excluded(y)) => {
+            if x > y {
+                a
+            } else {
+                b
+            }
+        }
+        (Bound::Excluded(x), Bound::Included(y)) => {
+            if x >= y {
+                a
+            } else {
+                b
+            }
+        }
+    }
+}
+
+fn min_upper(a: Bound<Vec<u8>>, b: Bound<Vec<u8>>) -> Bound<Vec<u8>> {
+    match (&a, &b) {
+        (Bound::Unbounded, _) => b,
+        (_, Bound::Unbounded) => a,
+        (Bound::Included(x), Bound::Included(y)) | (Bound::Excluded(x), Bound::Excluded(y)) => {
+            if x <= y {
+                a
+            } else {
+                b
+            }
+        }
+        (Bound::Included(x), Bound::Excluded(y)) => {
+            if x < y {
+                a
+            } else {
+                b
+            }
+        }
+        (Bound::Excluded(x), Bound::Included(y)) => {
+            if x <= y {
+                a
+            } else {
+                b
+            }
+        }
+    }
+}
+
+/// Extract the range of values of `info`'s property that `condition` can match. Returns `None`
+/// if the condition does not constrain the property with an equality or range predicate, in which
+/// case the index is useless for it.
+pub fn extract_index_range(condition: &Condition, info: &VertexIndexInfo) -> Option<IndexRange> {
+    match condition {
+        Condition::Pred(PredCondition::Cmp(cmp)) => {
+            let (op, value) = match (cmp.get_left(), cmp.get_right()) {
+                (Operand::PropId(prop_id), Operand::Const(value))
+                    if *prop_id as PropertyId == info.prop_id =>
+                {
+                    (cmp.get_op(), value)
+                }
+                (Operand::Const(value), Operand::PropId(prop_id))
+                    if *prop_id as PropertyId == info.prop_id =>
+                {
+                    (flip_operator(cmp.get_op()), value)
+                }
+                _ => return None,
+            };
+            let value = encode_index_const(info.value_type, value)?;
+            IndexRange::from_cmp(op, value)
+        }
+        Condition::And(and) => {
+            let mut ret: Option<IndexRange> = None;
+            for sub in and.sub_conditions.iter() {
+                if let Some(range) = extract_index_range(sub, info) {
+                    let current = ret.unwrap_or_else(IndexRange::all);
+                    ret = Some(current.intersect(range));
+                }
+            }
+            ret
+        }
+        _ => None,
+    }
+}
+
+fn flip_operator(op: CmpOperator) -> CmpOperator {
+    match op {
+        CmpOperator::LessThan => CmpOperator::GreaterThan,
+        CmpOperator::LessEqual => CmpOperator::GreaterEqual,
+        CmpOperator::GreaterThan => CmpOperator::LessThan,
+        CmpOperator::GreaterEqual => CmpOperator::LessEqual,
+        op => op,
+    }
+}
+
+/// Scan ids of vertices whose indexed value at `si` falls in `range`. Entries of a same
+/// (value, vertex) pair are ordered from the newest to the oldest, and only the newest one not
+/// after `si` decides whether the pair is alive. A vertex has at most one alive pair at `si`, as
+/// changing or deleting its value kills the pair of the old value, so no id is returned twice.
+pub fn scan_index(
+    storage: &RocksDB, si: SnapshotId, info: &VertexIndexInfo, range: &IndexRange,
+) -> GraphResult<IndexScan> {
+    let prefix = vertex_table_prefix_key(info.table_id);
+    let mut start = prefix.to_vec();
+    match &range.lower {
+        Bound::Included(v) | Bound::Excluded(v) => start.extend_from_slice(v),
+        Bound::Unbounded => {}
+    }
+    let end = vertex_table_end_key(info.table_id);
+    let iter = storage.new_range_scan(&start, &end)?;
+    Ok(IndexScan { iter, si, range: range.clone(), decided: None, done: false })
+}
+
+/// Lazy iterator of the vertex ids returned by `scan_index`.
+pub struct IndexScan {
+    iter: Box<dyn Iterator<Item = KvPair> + Send>,
+    si: SnapshotId,
+    range: IndexRange,
+    decided: Option<(Vec<u8>, VertexId)>,
+    done: bool,
+}
+
+impl Iterator for IndexScan {
+    type Item = GraphResult<VertexId>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        while let Some((k, v)) = self.iter.next() {
+            let (value, vertex_id, ts) = match parse_vertex_index_key(k.to_slice()) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+            if self.range.is_above_upper(value) {
+                break;
+            }
+            if ts > self.si || !self.range.contains(value) {
+                continue;
+            }
+            if let Some((ref decided_value, decided_id)) = self.decided {
+                if decided_id == vertex_id && decided_value.as_slice() == value {
+                    continue;
+                }
+            }
+            self.decided = Some((value.to_vec(), vertex_id));
+            if !v.to_slice().is_empty() {
+                return Some(Ok(vertex_id));
+            }
+        }
+        self.done = true;
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::condition::AndCondition;
+
+    fn info(value_type: ValueType) -> VertexIndexInfo {
+        VertexIndexInfo { label: 1, prop_id: 2, table_id: 3, value_type, start_si: 1 }
+    }
+
+    fn cmp(left: Operand, op: CmpOperator, right: Operand) -> Condition {
+        Condition::new(PredCondition::new_predicate(left, op, right))
+    }
+
+    #[test]
+    fn test_encode_index_value_order() {
+        let longs = [i64::min_value(), -100, -1, 0, 1, 100, i64::max_value()];
+        let encoded: Vec<Vec<u8>> = longs
+            .iter()
+            .map(|v| encode_index_value(&Value::long(*v).as_ref()).unwrap())
+            .collect();
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+
+        let doubles = [f64::NEG_INFINITY, -2.5, -0.5, 0.0, 0.5, 2.5, f64::INFINITY];
+        let encoded: Vec<Vec<u8>> = doubles
+            .iter()
+            .map(|v| encode_index_value(&Value::double(*v).as_ref()).unwrap())
+            .collect();
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+
+        let strs = ["", "\u{0}", "a", "a\u{0}b", "ab", "b"];
+        let encoded: Vec<Vec<u8>> = strs
+            .iter()
+            .map(|v| encode_index_value(&Value::string(v).as_ref()).unwrap())
+            .collect();
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+
+        assert_eq!(
+            encode_index_value(&Value::int(42).as_ref()),
+            encode_index_const(ValueType::Long, &Property::Int(42))
+        );
+        assert!(encode_index_value(&Value::int_list(&[1, 2]).as_ref()).is_none());
+    }
+
+    #[test]
+    fn test_extract_index_range() {
+        let info = info(ValueType::Long);
+        let eq = cmp(Operand::PropId(2), CmpOperator::Equal, Operand::Const(Property::Long(5)));
+        let range = extract_index_range(&eq, &info).unwrap();
+        let five = encode_index_const(ValueType::Long, &Property::Long(5)).unwrap();
+        assert_eq!(
+            range,
+            IndexRange { lower: Bound::Included(five.clone()), upper: Bound::Included(five) }
+        );
+
+        let other_prop = cmp(Operand::PropId(3), CmpOperator::Equal, Operand::Const(Property::Long(5)));
+        assert!(extract_index_range(&other_prop, &info).is_none());
+        let not_equal = cmp(Operand::PropId(2), CmpOperator::NotEqual, Operand::Const(Property::Long(5)));
+        assert!(extract_index_range(&not_equal, &info).is_none());
+        let wrong_type =
+            cmp(Operand::PropId(2), CmpOperator::Equal, Operand::Const(Property::String("5".to_owned())));
+        assert!(extract_index_range(&wrong_type, &info).is_none());
+
+        // 3 < p and p <= 10 and p > 4
+        let and = Condition::And(AndCondition::new(vec![
+            cmp(Operand::Const(Property::Int(3)), CmpOperator::LessThan, Operand::PropId(2)),
+            cmp(Operand::PropId(2), CmpOperator::LessEqual, Operand::Const(Property::Long(10))),
+            cmp(Operand::PropId(2), CmpOperator::GreaterThan, Operand::Const(Property::Long(4))),
+            cmp(Operand::PropId(3), CmpOperator::Equal, Operand::Const(Property::Long(1))),
+        ]));
+        let range = extract_index_range(&and, &info).unwrap();
+        let four = encode_index_const(ValueType::Long, &Property::Long(4)).unwrap();
+        let ten = encode_index_const(ValueType::Long, &Property::Long(10)).unwrap();
+        assert_eq!(range, IndexRange { lower: Bound::Excluded(four), upper: Bound::Included(ten) });
+    }
+}
@@ -49,6 +49,9 @@ impl IntoIterator for VertexTypeScan {
             let label = self.vertex_type_info.get_label();
             let prefix = vertex_table_prefix_key(table.id);
             let data_ts = si - table.start_si;
+            let checker = self
+                .vertex_type_info
+                .get_expire_checker(self.storage.get_clock().now_ms());
             let mut previous_vertex = None;
             let iter = self.storage.new_scan(&prefix).unwrap();
             let iter = iter.filter_map(move |(raw_key, raw_val)| {
@@ -60,7 +63,7 @@ impl IntoIterator for VertexTypeScan {
                             return None;
                         }
                         previous_vertex = Some(vertex_id);
-                        if val.len() < 4 {
+                        if val.len() < 4 || checker.is_expired(val) {
                             return None;
                         }
                         if self.with_prop {
@@ -160,13 +163,21 @@ impl IntoIterator for EdgeKindScan {
         let si = self.si as i64;
         if let Some(table) = self.edge_kind_info.get_table(si) {
             let data_ts = si - table.start_si;
-            let scan_iter = match self.direction {
-                EdgeDirection::In | EdgeDirection::Out => {
-                    let prefix = edge_prefix(table.id, self.vertex_id.unwrap() as i64, self.direction);
+            let checker = self
+                .edge_kind_info
+                .get_expire_checker(self.storage.get_clock().now_ms());
+            let scan_iter = match (self.direction, self.vertex_id) {
+                (EdgeDirection::Both, _) => {
+                    let prefix = edge_table_prefix_key(table.id, EdgeDirection::Out);
                     self.storage.new_scan(&prefix)
                 }
-                EdgeDirection::Both => {
-                    let prefix = edge_table_prefix_key(table.id, EdgeDirection::Out);
+                (direction, Some(vertex_id)) => {
+                    let prefix = edge_prefix(table.id, vertex_id as i64, direction);
+                    self.storage.new_scan(&prefix)
+                }
+                // all edges of the table in one direction
+                (direction, None) => {
+                    let prefix = edge_table_prefix_key(table.id, direction);
                     self.storage.new_scan(&prefix)
                 }
             };
@@ -181,7 +192,7 @@ impl IntoIterator for EdgeKindScan {
                         return None;
                     }
                     prev_id = Some(edge_id);
-                    if val.len() < 4 {
+                    if val.len() < 4 || checker.is_expired(val) {
                         return None;
                     }
                     if self.with_prop {
@@ -3,6 +3,8 @@ use std::sync::Arc;
 
 use protobuf::Message;
 
+use super::index::{is_indexable, VertexIndexManager};
+use super::sort_key::EdgeSortKeyManager;
 use super::table_manager::*;
 use super::types::*;
 use crate::db::api::ErrorCode::INVALID_DATA;
@@ -31,7 +33,9 @@ impl Meta {
         &self.graph_def_lock
     }
 
-    pub fn recover(&self) -> GraphResult<(VertexTypeManager, EdgeTypeManager)> {
+    pub fn recover(
+        &self,
+    ) -> GraphResult<(VertexTypeManager, EdgeTypeManager, VertexIndexManager, EdgeSortKeyManager)> {
         debug!("meta graph recover start");
         {
             let mut graph_def_val = self.graph_def_lock.lock()?;
@@ -110,6 +114,51 @@ impl Meta {
                 .into_iter()
                 .map(|i| MetaItem::AddEdgeProperty(i)),
         );
+        let drop_vertex_property_items =
+            res_unwrap!(get_items::<DropVertexPropertyItem>(store_ref), recover)?;
+        all.extend(
+            drop_vertex_property_items
+                .into_iter()
+                .map(|i| MetaItem::DropVertexProperty(i)),
+        );
+        let drop_edge_property_items = res_unwrap!(get_items::<DropEdgePropertyItem>(store_ref), recover)?;
+        all.extend(
+            drop_edge_property_items
+                .into_iter()
+                .map(|i| MetaItem::DropEdgeProperty(i)),
+        );
+        let rename_property_items = res_unwrap!(get_items::<RenamePropertyItem>(store_ref), recover)?;
+        all.extend(
+            rename_property_items
+                .into_iter()
+                .map(|i| MetaItem::RenameProperty(i)),
+        );
+        let create_vertex_index_items =
+            res_unwrap!(get_items::<CreateVertexIndexItem>(store_ref), recover)?;
+        all.extend(
+            create_vertex_index_items
+                .into_iter()
+                .map(|i| MetaItem::CreateVertexIndex(i)),
+        );
+        let drop_vertex_index_items = res_unwrap!(get_items::<DropVertexIndexItem>(store_ref), recover)?;
+        all.extend(
+            drop_vertex_index_items
+                .into_iter()
+                .map(|i| MetaItem::DropVertexIndex(i)),
+        );
+        let create_edge_sort_key_items =
+            res_unwrap!(get_items::<CreateEdgeSortKeyItem>(store_ref), recover)?;
+        all.extend(
+            create_edge_sort_key_items
+                .into_iter()
+                .map(|i| MetaItem::CreateEdgeSortKey(i)),
+        );
+        let drop_edge_sort_key_items = res_unwrap!(get_items::<DropEdgeSortKeyItem>(store_ref), recover)?;
+        all.extend(
+            drop_edge_sort_key_items
+                .into_iter()
+                .map(|i| MetaItem::DropEdgeSortKey(i)),
+        );
         all.sort_by(|a, b| {
             let s1 = a.get_schema_version();
             let s2 = b.get_schema_version();
@@ -118,6 +167,8 @@ impl Meta {
 
         let mut vertex_manager_builder = VertexTypeManagerBuilder::new();
         let mut edge_manager_builder = EdgeManagerBuilder::new();
+        let index_manager = VertexIndexManager::new();
+        let sort_key_manager = EdgeSortKeyManager::new();
         for item in all {
             match item {
                 MetaItem::CreateVertexType(x) => {
@@ -143,6 +194,7 @@ impl Meta {
                 }
                 MetaItem::DropVertexType(x) => {
                     vertex_manager_builder.drop(x.si, x.label_id)?;
+                    index_manager.drop_label(x.si, x.label_id)?;
                     let mut graph_def = self.graph_def_lock.lock()?;
                     graph_def.remove_type(&x.label_id);
                     graph_def.increase_version();
@@ -178,6 +230,7 @@ impl Meta {
                 }
                 MetaItem::DropEdgeType(x) => {
                     edge_manager_builder.drop_edge_type(x.si, x.label_id)?;
+                    sort_key_manager.drop_label(x.si, x.label_id)?;
                     let mut graph_def = self.graph_def_lock.lock()?;
                     graph_def.remove_type(&x.label_id);
                     graph_def.increase_version();
@@ -236,10 +289,67 @@ impl Meta {
                     graph_def.increase_version();
                     edge_manager_builder.update_edge_type(x.si, x.label_id, &x.type_def)?;
                 }
+                MetaItem::DropVertexProperty(x) => {
+                    info!(
+                        "DropVertexProperty label {:?}, si {:?}, type def {:?}",
+                        x.label_id, x.si, x.type_def
+                    );
+                    let mut graph_def = self.graph_def_lock.lock()?;
+                    graph_def.update_type(x.label_id, x.type_def.clone())?;
+                    graph_def.increase_version();
+                    vertex_manager_builder.update_type(x.si, x.label_id, &x.type_def)?;
+                }
+                MetaItem::DropEdgeProperty(x) => {
+                    info!(
+                        "DropEdgeProperty label {:?}, si {:?}, type def {:?}",
+                        x.label_id, x.si, x.type_def
+                    );
+                    let mut graph_def = self.graph_def_lock.lock()?;
+                    graph_def.update_type(x.label_id, x.type_def.clone())?;
+                    graph_def.increase_version();
+                    edge_manager_builder.update_edge_type(x.si, x.label_id, &x.type_def)?;
+                }
+                MetaItem::RenameProperty(x) => {
+                    // names are not encoded, so codecs are not changed
+                    let mut graph_def = self.graph_def_lock.lock()?;
+                    graph_def.update_type(x.label_id, x.type_def.clone())?;
+                    graph_def.increase_version();
+                }
+                MetaItem::CreateVertexIndex(x) => {
+                    let mut graph_def = self.graph_def_lock.lock()?;
+                    let value_type = get_index_value_type(&graph_def, x.label_id, x.prop_id)?;
+                    index_manager.create_index(x.si, x.label_id, x.prop_id, value_type, x.table_id)?;
+                    graph_def.set_table_idx(x.table_id);
+                    graph_def.increase_version();
+                }
+                MetaItem::DropVertexIndex(x) => {
+                    index_manager.drop_index(x.si, x.label_id, x.prop_id)?;
+                    let mut graph_def = self.graph_def_lock.lock()?;
+                    graph_def.increase_version();
+                }
+                MetaItem::CreateEdgeSortKey(x) => {
+                    let mut graph_def = self.graph_def_lock.lock()?;
+                    let value_type = get_index_value_type(&graph_def, x.label_id, x.prop_id)?;
+                    sort_key_manager.create_sort_key(
+                        x.si,
+                        x.label_id,
+                        x.prop_id,
+                        value_type,
+                        x.descending,
+                        x.table_id,
+                    )?;
+                    graph_def.set_table_idx(x.table_id);
+                    graph_def.increase_version();
+                }
+                MetaItem::DropEdgeSortKey(x) => {
+                    sort_key_manager.drop_sort_key(x.si, x.label_id)?;
+                    let mut graph_def = self.graph_def_lock.lock()?;
+                    graph_def.increase_version();
+                }
             }
         }
         debug!("meta graph recovered");
-        Ok((vertex_manager_builder.build(), edge_manager_builder.build()))
+        Ok((vertex_manager_builder.build(), edge_manager_builder.build(), index_manager, sort_key_manager))
     }
 
     pub fn check_version(&self, schema_version: i64) -> GraphResult<()> {
@@ -418,6 +528,70 @@ impl Meta {
         }
     }
 
+    /// Drop the properties of `type_def` from vertex type `label_id`, the new codec version is the
+    /// version of `type_def`. Returns the `TypeDef` after dropping.
+    pub fn drop_vertex_type_properties(
+        &self, si: SnapshotId, schema_version: i64, label_id: LabelId, type_def: &TypeDef,
+    ) -> GraphResult<TypeDef> {
+        self.check_version(schema_version)?;
+        let mut graph_def = self.graph_def_lock.lock()?;
+        let dropped = drop_properties(&graph_def, label_id, type_def)?;
+        let item = DropVertexPropertyItem::new(si, schema_version, label_id, dropped.clone());
+        self.write_item(item)?;
+        graph_def.update_type(label_id, dropped.clone())?;
+        graph_def.increase_version();
+        Ok(dropped)
+    }
+
+    /// Drop the properties of `type_def` from edge type `label_id`, the new codec version is the
+    /// version of `type_def`. Returns the `TypeDef` after dropping.
+    pub fn drop_edge_type_properties(
+        &self, si: SnapshotId, schema_version: i64, label_id: LabelId, type_def: &TypeDef,
+    ) -> GraphResult<TypeDef> {
+        self.check_version(schema_version)?;
+        let mut graph_def = self.graph_def_lock.lock()?;
+        let dropped = drop_properties(&graph_def, label_id, type_def)?;
+        let item = DropEdgePropertyItem::new(si, schema_version, label_id, dropped.clone());
+        self.write_item(item)?;
+        graph_def.update_type(label_id, dropped.clone())?;
+        graph_def.increase_version();
+        Ok(dropped)
+    }
+
+    /// Rename property `prop_id` of vertex or edge type `label_id` to `new_name`.
+    pub fn rename_type_property(
+        &self, si: SnapshotId, schema_version: i64, label_id: LabelId, prop_id: PropertyId, new_name: &str,
+    ) -> GraphResult<()> {
+        self.check_version(schema_version)?;
+        let mut graph_def = self.graph_def_lock.lock()?;
+        let mut cloned = match graph_def.get_type(&label_id) {
+            Some(type_def) => type_def.clone(),
+            None => {
+                let msg = format!("current label id {} not exist.", label_id);
+                return Err(GraphError::new(ErrorCode::INVALID_OPERATION, msg));
+            }
+        };
+        if cloned
+            .get_prop_defs()
+            .any(|p| p.id != prop_id && p.name == new_name)
+        {
+            let msg = format!(
+                "Property with name '{}' already exists in type for label_id {}",
+                new_name, label_id
+            );
+            return Err(GraphError::new(ErrorCode::INVALID_OPERATION, msg));
+        }
+        if !cloned.rename_property(prop_id, new_name) {
+            let msg = format!("property#{} not found in type for label_id {}", prop_id, label_id);
+            return Err(GraphError::new(ErrorCode::INVALID_OPERATION, msg));
+        }
+        let item = RenamePropertyItem::new(si, schema_version, label_id, cloned.clone());
+        self.write_item(item)?;
+        graph_def.update_type(label_id, cloned)?;
+        graph_def.increase_version();
+        Ok(())
+    }
+
     pub fn add_edge_kind(
         &self, si: SnapshotId, schema_version: i64, edge_kind: &EdgeKind, table_id: i64,
     ) -> GraphResult<Table> {
@@ -462,6 +636,85 @@ impl Meta {
         Ok(())
     }
 
+    /// Persist the creation of an index on `prop_id` of vertex type `label_id`, returns the value
+    /// type of the indexed property.
+    pub fn create_vertex_index(
+        &self, si: SnapshotId, schema_version: i64, label_id: LabelId, prop_id: PropertyId, table_id: i64,
+    ) -> GraphResult<ValueType> {
+        self.check_version(schema_version)?;
+        let mut graph_def = self.graph_def_lock.lock()?;
+        let value_type = get_index_value_type(&graph_def, label_id, prop_id)?;
+        if !is_indexable(value_type) {
+            let msg = format!("property#{} of type {:?} cannot be indexed", prop_id, value_type);
+            let err = gen_graph_err!(
+                ErrorCode::UNSUPPORTED_OPERATION,
+                msg,
+                create_vertex_index,
+                label_id,
+                prop_id
+            );
+            return Err(err);
+        }
+        let item = CreateVertexIndexItem::new(si, schema_version, label_id, prop_id, table_id);
+        self.write_item(item)?;
+        graph_def.set_table_idx(table_id);
+        graph_def.increase_version();
+        Ok(value_type)
+    }
+
+    pub fn drop_vertex_index(
+        &self, si: SnapshotId, schema_version: i64, label_id: LabelId, prop_id: PropertyId,
+    ) -> GraphResult<()> {
+        self.check_version(schema_version)?;
+        let item = DropVertexIndexItem::new(si, schema_version, label_id, prop_id);
+        self.write_item(item)?;
+        {
+            let mut graph_def = self.graph_def_lock.lock()?;
+            graph_def.increase_version();
+        }
+        Ok(())
+    }
+
+    /// Persist the creation of a sort key on `prop_id` of edge type `label_id`, returns the value
+    /// type of the property.
+    pub fn create_edge_sort_key(
+        &self, si: SnapshotId, schema_version: i64, label_id: LabelId, prop_id: PropertyId,
+        descending: bool, table_id: i64,
+    ) -> GraphResult<ValueType> {
+        self.check_version(schema_version)?;
+        let mut graph_def = self.graph_def_lock.lock()?;
+        let value_type = get_index_value_type(&graph_def, label_id, prop_id)?;
+        if !is_indexable(value_type) {
+            let msg = format!("property#{} of type {:?} cannot be a sort key", prop_id, value_type);
+            let err = gen_graph_err!(
+                ErrorCode::UNSUPPORTED_OPERATION,
+                msg,
+                create_edge_sort_key,
+                label_id,
+                prop_id
+            );
+            return Err(err);
+        }
+        let item = CreateEdgeSortKeyItem::new(si, schema_version, label_id, prop_id, descending, table_id);
+        self.write_item(item)?;
+        graph_def.set_table_idx(table_id);
+        graph_def.increase_version();
+        Ok(value_type)
+    }
+
+    pub fn drop_edge_sort_key(
+        &self, si: SnapshotId, schema_version: i64, label_id: LabelId,
+    ) -> GraphResult<()> {
+        self.check_version(schema_version)?;
+        let item = DropEdgeSortKeyItem::new(si, schema_version, label_id);
+        self.write_item(item)?;
+        {
+            let mut graph_def = self.graph_def_lock.lock()?;
+            graph_def.increase_version();
+        }
+        Ok(())
+    }
+
     pub fn _gen_next_table_id(&self) -> GraphResult<TableId> {
         let key = _gen_key("NextTableId");
         let table_id = match res_unwrap!(self.store.get(&key), get_next_table_id)? {
@@ -489,12 +742,102 @@ impl Meta {
         Ok(table_id)
     }
 
+    /// Persist the gc watermark, snapshots before it may have been garbage collected.
+    pub fn put_gc_snapshot_id(&self, si: SnapshotId) -> GraphResult<()> {
+        let key = _gen_key("GcSnapshotId");
+        let v = transform::i64_to_vec(si.to_be());
+        res_unwrap!(self.store.put(&key, &v), put_gc_snapshot_id, si)
+    }
+
+    /// The persisted gc watermark, 0 if nothing is garbage collected yet.
+    pub fn get_gc_snapshot_id(&self) -> GraphResult<SnapshotId> {
+        let key = _gen_key("GcSnapshotId");
+        match res_unwrap!(self.store.get(&key), get_gc_snapshot_id)? {
+            Some(v) => {
+                let si = res_unwrap!(transform::bytes_to_i64(v.as_bytes()), get_gc_snapshot_id)?;
+                Ok(si.to_be())
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// Persist the types whose rows are waiting to be rewritten, label -> si of the last drop of
+    /// their properties.
+    pub fn put_pending_rewrites(&self, pending: &HashMap<LabelId, SnapshotId>) -> GraphResult<()> {
+        let key = _gen_key("PendingRewrites");
+        let mut v = Vec::with_capacity(pending.len() * 12);
+        for (label, si) in pending {
+            v.extend_from_slice(&label.to_be_bytes());
+            v.extend_from_slice(&si.to_be_bytes());
+        }
+        res_unwrap!(self.store.put(&key, &v), put_pending_rewrites)
+    }
+
+    pub fn get_pending_rewrites(&self) -> GraphResult<HashMap<LabelId, SnapshotId>> {
+        let key = _gen_key("PendingRewrites");
+        let mut pending = HashMap::new();
+        if let Some(v) = res_unwrap!(self.store.get(&key), get_pending_rewrites)? {
+            for item in v.as_bytes().chunks_exact(12) {
+                let mut label = [0; 4];
+                label.copy_from_slice(&item[0..4]);
+                let mut si = [0; 8];
+                si.copy_from_slice(&item[4..12]);
+                pending.insert(LabelId::from_be_bytes(label), SnapshotId::from_be_bytes(si));
+            }
+        }
+        Ok(pending)
+    }
+
     fn write_item<I: ItemCommon>(&self, item: I) -> GraphResult<()> {
         let (k, v) = item.to_kv()?;
         res_unwrap!(self.store.put(&k, &v), write_item)
     }
 }
 
+fn get_index_value_type(
+    graph_def: &GraphDef, label_id: LabelId, prop_id: PropertyId,
+) -> GraphResult<ValueType> {
+    match graph_def
+        .get_type(&label_id)
+        .and_then(|type_def| type_def.get_prop_def(prop_id))
+    {
+        Some(prop_def) => Ok(prop_def.r#type),
+        None => {
+            let msg = format!("property#{} of label#{} not found", prop_id, label_id);
+            let err =
+                gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, get_index_value_type, label_id, prop_id);
+            Err(err)
+        }
+    }
+}
+
+/// Returns the `TypeDef` of `label_id` without the properties of `type_def`. Primary keys cannot be
+/// dropped as ids of existing elements are computed from them.
+fn drop_properties(graph_def: &GraphDef, label_id: LabelId, type_def: &TypeDef) -> GraphResult<TypeDef> {
+    let mut cloned = match graph_def.get_type(&label_id) {
+        Some(current) => current.clone(),
+        None => {
+            let msg = format!("current label id {} not exist.", label_id);
+            return Err(GraphError::new(ErrorCode::INVALID_OPERATION, msg));
+        }
+    };
+    for prop in type_def.get_prop_defs() {
+        match cloned.remove_property(prop.id) {
+            Some(removed) if removed.pk => {
+                let msg = format!("cannot drop primary key '{}' of label_id {}", removed.name, label_id);
+                return Err(GraphError::new(ErrorCode::INVALID_OPERATION, msg));
+            }
+            Some(_) => {}
+            None => {
+                let msg = format!("property#{} not found in type for label_id {}", prop.id, label_id);
+                return Err(GraphError::new(ErrorCode::INVALID_OPERATION, msg));
+            }
+        }
+    }
+    cloned.set_version(type_def.get_version());
+    Ok(cloned)
+}
+
 fn _gen_key(key: &str) -> Vec<u8> {
     let mut buf = Vec::new();
     buf.extend(transform::i64_to_vec(META_TABLE_ID.to_be()));
@@ -520,6 +863,13 @@ enum MetaItem {
     CommitDataLoad(CommitDataLoadItem),
     AddVertexProperty(AddVertexPropertyItem),
     AddEdgeProperty(AddEdgePropertyItem),
+    DropVertexProperty(DropVertexPropertyItem),
+    DropEdgeProperty(DropEdgePropertyItem),
+    RenameProperty(RenamePropertyItem),
+    CreateVertexIndex(CreateVertexIndexItem),
+    DropVertexIndex(DropVertexIndexItem),
+    CreateEdgeSortKey(CreateEdgeSortKeyItem),
+    DropEdgeSortKey(DropEdgeSortKeyItem),
 }
 
 impl MetaItem {
@@ -535,6 +885,13 @@ impl MetaItem {
             MetaItem::CommitDataLoad(ref item) => item.schema_version,
             MetaItem::AddVertexProperty(ref item) => item.schema_version,
             MetaItem::AddEdgeProperty(ref item) => item.schema_version,
+            MetaItem::DropVertexProperty(ref item) => item.schema_version,
+            MetaItem::DropEdgeProperty(ref item) => item.schema_version,
+            MetaItem::RenameProperty(ref item) => item.schema_version,
+            MetaItem::CreateVertexIndex(ref item) => item.schema_version,
+            MetaItem::DropVertexIndex(ref item) => item.schema_version,
+            MetaItem::CreateEdgeSortKey(ref item) => item.schema_version,
+            MetaItem::DropEdgeSortKey(ref item) => item.schema_version,
         }
     }
 }
@@ -960,6 +1317,270 @@ impl ItemCommon for AddEdgePropertyItem {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+struct DropVertexPropertyItem {
+    si: SnapshotId,
+    schema_version: i64,
+    label_id: LabelId,
+    type_def: TypeDef,
+}
+
+impl DropVertexPropertyItem {
+    fn new(si: SnapshotId, schema_version: i64, label_id: LabelId, type_def: TypeDef) -> Self {
+        DropVertexPropertyItem { si, schema_version, label_id, type_def }
+    }
+}
+
+impl ItemCommon for DropVertexPropertyItem {
+    fn from_kv(k: &[u8], v: &[u8]) -> GraphResult<Self> {
+        let items = res_unwrap!(common_parse_key(k, Self::prefix(), 4), from_kv)?;
+        let label_id = res_unwrap!(parse_str(items[1]), from_kv)?;
+        let si = res_unwrap!(parse_str(items[2]), from_kv)?;
+        let schema_version = res_unwrap!(parse_str(items[3]), from_kv)?;
+        let type_def = TypeDef::from_bytes(v)?;
+        Ok(Self::new(si, schema_version, label_id, type_def))
+    }
+
+    fn prefix() -> &'static str {
+        "DropVertexProperty"
+    }
+
+    fn to_kv(&self) -> GraphResult<(Vec<u8>, Vec<u8>)> {
+        let key = format!("{}#{}#{}#{}", Self::prefix(), self.label_id, self.si, self.schema_version);
+        Ok((meta_key(&key), self.type_def.to_bytes()?))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct DropEdgePropertyItem {
+    si: SnapshotId,
+    schema_version: i64,
+    label_id: LabelId,
+    type_def: TypeDef,
+}
+
+impl DropEdgePropertyItem {
+    fn new(si: SnapshotId, schema_version: i64, label_id: LabelId, type_def: TypeDef) -> Self {
+        DropEdgePropertyItem { si, schema_version, label_id, type_def }
+    }
+}
+
+impl ItemCommon for DropEdgePropertyItem {
+    fn from_kv(k: &[u8], v: &[u8]) -> GraphResult<Self> {
+        let items = res_unwrap!(common_parse_key(k, Self::prefix(), 4), from_kv)?;
+        let label_id = res_unwrap!(parse_str(items[1]), from_kv)?;
+        let si = res_unwrap!(parse_str(items[2]), from_kv)?;
+        let schema_version = res_unwrap!(parse_str(items[3]), from_kv)?;
+        let type_def = TypeDef::from_bytes(v)?;
+        Ok(Self::new(si, schema_version, label_id, type_def))
+    }
+
+    fn prefix() -> &'static str {
+        "DropEdgeProperty"
+    }
+
+    fn to_kv(&self) -> GraphResult<(Vec<u8>, Vec<u8>)> {
+        let key = format!("{}#{}#{}#{}", Self::prefix(), self.label_id, self.si, self.schema_version);
+        Ok((meta_key(&key), self.type_def.to_bytes()?))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RenamePropertyItem {
+    si: SnapshotId,
+    schema_version: i64,
+    label_id: LabelId,
+    type_def: TypeDef,
+}
+
+impl RenamePropertyItem {
+    fn new(si: SnapshotId, schema_version: i64, label_id: LabelId, type_def: TypeDef) -> Self {
+        RenamePropertyItem { si, schema_version, label_id, type_def }
+    }
+}
+
+impl ItemCommon for RenamePropertyItem {
+    fn from_kv(k: &[u8], v: &[u8]) -> GraphResult<Self> {
+        let items = res_unwrap!(common_parse_key(k, Self::prefix(), 4), from_kv)?;
+        let label_id = res_unwrap!(parse_str(items[1]), from_kv)?;
+        let si = res_unwrap!(parse_str(items[2]), from_kv)?;
+        let schema_version = res_unwrap!(parse_str(items[3]), from_kv)?;
+        let type_def = TypeDef::from_bytes(v)?;
+        Ok(Self::new(si, schema_version, label_id, type_def))
+    }
+
+    fn prefix() -> &'static str {
+        "RenameProperty"
+    }
+
+    fn to_kv(&self) -> GraphResult<(Vec<u8>, Vec<u8>)> {
+        let key = format!("{}#{}#{}#{}", Self::prefix(), self.label_id, self.si, self.schema_version);
+        Ok((meta_key(&key), self.type_def.to_bytes()?))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CreateVertexIndexItem {
+    si: SnapshotId,
+    schema_version: i64,
+    label_id: LabelId,
+    prop_id: PropertyId,
+    table_id: TableId,
+}
+
+impl CreateVertexIndexItem {
+    fn new(
+        si: SnapshotId, schema_version: i64, label_id: LabelId, prop_id: PropertyId, table_id: TableId,
+    ) -> Self {
+        CreateVertexIndexItem { si, schema_version, label_id, prop_id, table_id }
+    }
+}
+
+impl ItemCommon for CreateVertexIndexItem {
+    fn from_kv(k: &[u8], _v: &[u8]) -> GraphResult<Self> {
+        let items = res_unwrap!(common_parse_key(k, Self::prefix(), 6), from_kv)?;
+        let label_id = res_unwrap!(parse_str(items[1]), from_kv)?;
+        let prop_id = res_unwrap!(parse_str(items[2]), from_kv)?;
+        let si = res_unwrap!(parse_str(items[3]), from_kv)?;
+        let schema_version = res_unwrap!(parse_str(items[4]), from_kv)?;
+        let table_id = res_unwrap!(parse_str(items[5]), from_kv)?;
+        Ok(Self::new(si, schema_version, label_id, prop_id, table_id))
+    }
+
+    fn prefix() -> &'static str {
+        "CreateVertexIndex"
+    }
+
+    fn to_kv(&self) -> GraphResult<(Vec<u8>, Vec<u8>)> {
+        let key = format!(
+            "{}#{}#{}#{}#{}#{}",
+            Self::prefix(),
+            self.label_id,
+            self.prop_id,
+            self.si,
+            self.schema_version,
+            self.table_id
+        );
+        Ok((meta_key(&key), Vec::new()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct DropVertexIndexItem {
+    si: SnapshotId,
+    schema_version: i64,
+    label_id: LabelId,
+    prop_id: PropertyId,
+}
+
+impl DropVertexIndexItem {
+    fn new(si: SnapshotId, schema_version: i64, label_id: LabelId, prop_id: PropertyId) -> Self {
+        DropVertexIndexItem { si, schema_version, label_id, prop_id }
+    }
+}
+
+impl ItemCommon for DropVertexIndexItem {
+    fn from_kv(k: &[u8], v: &[u8]) -> GraphResult<Self> {
+        let items = res_unwrap!(common_parse_key(k, Self::prefix(), 4), from_kv)?;
+        let label_id = res_unwrap!(parse_str(items[1]), from_kv)?;
+        let prop_id = res_unwrap!(parse_str(items[2]), from_kv)?;
+        let schema_version = res_unwrap!(parse_str(items[3]), from_kv)?;
+        let si = res_unwrap!(transform::bytes_to_i64(v), from_kv)?.to_be();
+        Ok(Self::new(si, schema_version, label_id, prop_id))
+    }
+
+    fn prefix() -> &'static str {
+        "DropVertexIndex"
+    }
+
+    fn to_kv(&self) -> GraphResult<(Vec<u8>, Vec<u8>)> {
+        let key = format!("{}#{}#{}#{}", Self::prefix(), self.label_id, self.prop_id, self.schema_version);
+        Ok((meta_key(&key), transform::i64_to_vec(self.si.to_be())))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CreateEdgeSortKeyItem {
+    si: SnapshotId,
+    schema_version: i64,
+    label_id: LabelId,
+    prop_id: PropertyId,
+    descending: bool,
+    table_id: TableId,
+}
+
+impl CreateEdgeSortKeyItem {
+    fn new(
+        si: SnapshotId, schema_version: i64, label_id: LabelId, prop_id: PropertyId, descending: bool,
+        table_id: TableId,
+    ) -> Self {
+        CreateEdgeSortKeyItem { si, schema_version, label_id, prop_id, descending, table_id }
+    }
+}
+
+impl ItemCommon for CreateEdgeSortKeyItem {
+    fn from_kv(k: &[u8], _v: &[u8]) -> GraphResult<Self> {
+        let items = res_unwrap!(common_parse_key(k, Self::prefix(), 7), from_kv)?;
+        let label_id = res_unwrap!(parse_str(items[1]), from_kv)?;
+        let prop_id = res_unwrap!(parse_str(items[2]), from_kv)?;
+        let descending = res_unwrap!(parse_str(items[3]), from_kv)?;
+        let si = res_unwrap!(parse_str(items[4]), from_kv)?;
+        let schema_version = res_unwrap!(parse_str(items[5]), from_kv)?;
+        let table_id = res_unwrap!(parse_str(items[6]), from_kv)?;
+        Ok(Self::new(si, schema_version, label_id, prop_id, descending, table_id))
+    }
+
+    fn prefix() -> &'static str {
+        "CreateEdgeSortKey"
+    }
+
+    fn to_kv(&self) -> GraphResult<(Vec<u8>, Vec<u8>)> {
+        let key = format!(
+            "{}#{}#{}#{}#{}#{}#{}",
+            Self::prefix(),
+            self.label_id,
+            self.prop_id,
+            self.descending,
+            self.si,
+            self.schema_version,
+            self.table_id
+        );
+        Ok((meta_key(&key), Vec::new()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct DropEdgeSortKeyItem {
+    si: SnapshotId,
+    schema_version: i64,
+    label_id: LabelId,
+}
+
+impl DropEdgeSortKeyItem {
+    fn new(si: SnapshotId, schema_version: i64, label_id: LabelId) -> Self {
+        DropEdgeSortKeyItem { si, schema_version, label_id }
+    }
+}
+
+impl ItemCommon for DropEdgeSortKeyItem {
+    fn from_kv(k: &[u8], v: &[u8]) -> GraphResult<Self> {
+        let items = res_unwrap!(common_parse_key(k, Self::prefix(), 3), from_kv)?;
+        let label_id = res_unwrap!(parse_str(items[1]), from_kv)?;
+        let schema_version = res_unwrap!(parse_str(items[2]), from_kv)?;
+        let si = res_unwrap!(transform::bytes_to_i64(v), from_kv)?.to_be();
+        Ok(Self::new(si, schema_version, label_id))
+    }
+
+    fn prefix() -> &'static str {
+        "DropEdgeSortKey"
+    }
+
+    fn to_kv(&self) -> GraphResult<(Vec<u8>, Vec<u8>)> {
+        let key = format!("{}#{}#{}", Self::prefix(), self.label_id, self.schema_version);
+        Ok((meta_key(&key), transform::i64_to_vec(self.si.to_be())))
+    }
+}
+
 fn get_items<I: ItemCommon>(store: &RocksDB) -> GraphResult<Vec<I>> {
     let mut ret = Vec::new();
     let mut prefix = Vec::new();
@@ -1016,6 +1637,41 @@ mod tests {
         let (k, v) = item.to_kv().unwrap();
         let item2 = RemoveEdgeKindItem::from_kv(&k, &v).unwrap();
         assert_eq!(item, item2);
+
+        let item = CreateVertexIndexItem::new(5, 6, 1, 11, 7);
+        let (k, v) = item.to_kv().unwrap();
+        let item2 = CreateVertexIndexItem::from_kv(&k, &v).unwrap();
+        assert_eq!(item, item2);
+
+        let item = DropVertexIndexItem::new(8, 9, 1, 11);
+        let (k, v) = item.to_kv().unwrap();
+        let item2 = DropVertexIndexItem::from_kv(&k, &v).unwrap();
+        assert_eq!(item, item2);
+
+        let item = DropVertexPropertyItem::new(3, 4, 1, type_def.clone());
+        let (k, v) = item.to_kv().unwrap();
+        let item2 = DropVertexPropertyItem::from_kv(&k, &v).unwrap();
+        assert_eq!(item, item2);
+
+        let item = DropEdgePropertyItem::new(3, 4, 1, type_def.clone());
+        let (k, v) = item.to_kv().unwrap();
+        let item2 = DropEdgePropertyItem::from_kv(&k, &v).unwrap();
+        assert_eq!(item, item2);
+
+        let item = RenamePropertyItem::new(5, 6, 1, type_def.clone());
+        let (k, v) = item.to_kv().unwrap();
+        let item2 = RenamePropertyItem::from_kv(&k, &v).unwrap();
+        assert_eq!(item, item2);
+
+        let item = CreateEdgeSortKeyItem::new(5, 6, 3, 11, true, 7);
+        let (k, v) = item.to_kv().unwrap();
+        let item2 = CreateEdgeSortKeyItem::from_kv(&k, &v).unwrap();
+        assert_eq!(item, item2);
+
+        let item = DropEdgeSortKeyItem::new(8, 9, 3);
+        let (k, v) = item.to_kv().unwrap();
+        let item2 = DropEdgeSortKeyItem::from_kv(&k, &v).unwrap();
+        assert_eq!(item, item2);
     }
 
     #[test]
@@ -1053,7 +1709,7 @@ mod tests {
                     schema_version += 1;
                 }
             }
-            let (vertex_manager, edge_manager) = meta.recover().unwrap();
+            let (vertex_manager, edge_manager, _, _) = meta.recover().unwrap();
             check_vertex_manager(&vertex_manager, &label_to_vertex_table);
             check_edge_manager(&edge_manager, &label_to_edge_table);
         }
@@ -4,16 +4,20 @@ use std::ops::Deref;
 
 use byteorder::{BigEndian, WriteBytesExt};
 
-use crate::db::api::{LabelId, VertexId};
+use crate::db::api::{EdgeInnerId, LabelId, VertexId};
 
 #[cfg(test)]
 mod bench;
 pub mod bin;
+mod change;
 pub mod codec;
 pub mod entity;
+mod index;
 pub mod iter;
 mod meta;
 mod property;
+mod rewrite;
+mod sort_key;
 pub mod store;
 mod table_manager;
 #[cfg(test)]
@@ -45,6 +49,47 @@ pub fn get_vertex_id_by_primary_keys<'a, T: Deref<Target = Vec<u8>>>(
     })
 }
 
+/// Same as `PkHashUtils.hash(srcId, dstId, labelId, pks)` of the java side
+pub fn get_edge_inner_id_by_primary_keys<T: Deref<Target = Vec<u8>>>(
+    src_id: VertexId, dst_id: VertexId, label_id: LabelId, pks: impl Iterator<Item = T>,
+) -> EdgeInnerId {
+    BUFFER.with(|bytes| {
+        let mut bytes = bytes.borrow_mut();
+        bytes.clear();
+        bytes.write_i64::<BigEndian>(src_id).unwrap();
+        bytes.write_i64::<BigEndian>(dst_id).unwrap();
+        bytes
+            .write_i32::<BigEndian>(label_id as i32)
+            .unwrap();
+        for pk in pks {
+            let pk = pk.as_slice();
+            bytes
+                .write_i32::<BigEndian>(pk.len() as i32)
+                .unwrap();
+            bytes.write(pk).unwrap();
+        }
+        hash64(bytes.as_slice(), bytes.len())
+    })
+}
+
+/// Same as `PkHashUtils.hash(srcId, dstId, labelId, nanoTime)` of the java side, `seq` should be
+/// unique among the edges between the same vertices.
+pub fn get_edge_inner_id_by_seq(
+    src_id: VertexId, dst_id: VertexId, label_id: LabelId, seq: i64,
+) -> EdgeInnerId {
+    BUFFER.with(|bytes| {
+        let mut bytes = bytes.borrow_mut();
+        bytes.clear();
+        bytes.write_i64::<BigEndian>(src_id).unwrap();
+        bytes.write_i64::<BigEndian>(dst_id).unwrap();
+        bytes
+            .write_i32::<BigEndian>(label_id as i32)
+            .unwrap();
+        bytes.write_i64::<BigEndian>(seq).unwrap();
+        hash64(bytes.as_slice(), bytes.len())
+    })
+}
+
 pub fn hash64(data: &[u8], length: usize) -> i64 {
     let seed = 0xc70f6907;
     hash64_with_seed(data, length, seed)
@@ -114,6 +159,9 @@ mod test {
         Bytes(Vec<u8>),
         String(String),
         Date(String),
+        DateTime(i64),
+        DateTimeWithTz(i64, i32),
+        Decimal(i128, u8),
         ListInt(Vec<i32>),
         ListLong(Vec<i64>),
         ListFloat(Vec<f32>),
@@ -270,6 +318,17 @@ mod test {
                             data.write(i.as_slice()).unwrap();
                         }
                     }
+                    Property::DateTime(v) => {
+                        data.write_i64::<BigEndian>(*v).unwrap();
+                    }
+                    Property::DateTimeWithTz(v, offset) => {
+                        data.write_i64::<BigEndian>(*v).unwrap();
+                        data.write_i32::<BigEndian>(*offset).unwrap();
+                    }
+                    Property::Decimal(v, scale) => {
+                        data.write_i128::<BigEndian>(*v).unwrap();
+                        data.write_u8(*scale).unwrap();
+                    }
                     Property::Null => {
                         unimplemented!()
                     }
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use super::bin::*;
+use super::codec::*;
+use super::meta::Meta;
+use super::table_manager::{Table, TableId};
+use super::types::*;
+use crate::db::api::*;
+use crate::db::storage::get_write_time;
+use crate::db::storage::rocksdb::RocksDB;
+use crate::db::util::lock::GraphMutexLock;
+
+const REWRITE_BATCH_SIZE: usize = 1024;
+
+/// Rewrites the rows of types whose properties were dropped with the codec at the gc watermark, so
+/// the values of dropped properties are reclaimed. Every readable snapshot decodes with that codec
+/// or a newer one, so the rewrite is invisible to readers.
+///
+/// The rewrite runs in a background thread started by `gc`. Rows are written in batches, each batch
+/// holds the ddl lock of the store only while it's written and is skipped once the type is dropped
+/// or its table is replaced, so rows are never written back into a table that gc deletes. Pending
+/// types are persisted in meta, so a rewrite interrupted by a restart is started again by the next
+/// gc, rows already rewritten are skipped by their codec version.
+pub struct RowRewriter {
+    inner: Arc<RewriterInner>,
+    worker: GraphMutexLock<Option<JoinHandle<()>>>,
+}
+
+struct RewriterInner {
+    storage: Arc<RocksDB>,
+    meta: Arc<Meta>,
+    // the ddl lock of the store
+    lock: Arc<GraphMutexLock<()>>,
+    // label -> si of the last drop of its properties
+    pending: GraphMutexLock<HashMap<LabelId, SnapshotId>>,
+    stopped: AtomicBool,
+}
+
+/// Rows of a type to rewrite, for edges every kind of the type is rewritten.
+struct RewriteTask {
+    label: LabelId,
+    si: SnapshotId,
+    targets: Vec<RewriteTarget>,
+}
+
+enum RewriteTarget {
+    Vertex(Arc<VertexTypeInfo>),
+    EdgeKind(Arc<EdgeKindInfo>),
+}
+
+impl RewriteTarget {
+    fn get_table(&self, si: SnapshotId) -> Option<Table> {
+        match self {
+            RewriteTarget::Vertex(info) => info.get_table(si),
+            RewriteTarget::EdgeKind(info) => info.get_table(si),
+        }
+    }
+
+    fn get_encoder(&self, si: SnapshotId) -> GraphResult<Encoder> {
+        match self {
+            RewriteTarget::Vertex(info) => info.get_encoder(si),
+            RewriteTarget::EdgeKind(info) => info.get_encoder(si),
+        }
+    }
+
+    fn get_decoder(&self, si: SnapshotId, version: CodecVersion) -> GraphResult<Decoder> {
+        match self {
+            RewriteTarget::Vertex(info) => info.get_decoder(si, version),
+            RewriteTarget::EdgeKind(info) => info.get_decoder(si, version),
+        }
+    }
+
+    fn get_ttl_versions(&self) -> HashMap<CodecVersion, i64> {
+        match self {
+            RewriteTarget::Vertex(info) => info.get_ttl_versions(),
+            RewriteTarget::EdgeKind(info) => info.get_ttl_versions(),
+        }
+    }
+
+    fn is_dropped(&self) -> bool {
+        match self {
+            RewriteTarget::Vertex(info) => info.is_dropped(),
+            RewriteTarget::EdgeKind(info) => info.is_dropped(),
+        }
+    }
+
+    fn get_prefixes(&self, table_id: TableId) -> Vec<[u8; 8]> {
+        match self {
+            RewriteTarget::Vertex(_) => vec![vertex_table_prefix_key(table_id)],
+            RewriteTarget::EdgeKind(_) => vec![
+                edge_table_prefix_key(table_id, EdgeDirection::Out),
+                edge_table_prefix_key(table_id, EdgeDirection::In),
+            ],
+        }
+    }
+
+    /// Whether rows can still be written into `table_id`, must be called with the ddl lock held.
+    fn is_current(&self, table_id: TableId) -> bool {
+        !self.is_dropped()
+            && self
+                .get_table(INFINITE_SI)
+                .map_or(false, |table| table.id == table_id)
+    }
+}
+
+impl RowRewriter {
+    pub fn new(storage: Arc<RocksDB>, meta: Arc<Meta>, lock: Arc<GraphMutexLock<()>>) -> GraphResult<Self> {
+        let pending = res_unwrap!(meta.get_pending_rewrites(), new)?;
+        let inner = RewriterInner {
+            storage,
+            meta,
+            lock,
+            pending: GraphMutexLock::new(pending),
+            stopped: AtomicBool::new(false),
+        };
+        Ok(RowRewriter { inner: Arc::new(inner), worker: GraphMutexLock::new(None) })
+    }
+
+    /// Rows of `label` written before `si` are to be rewritten once no snapshot before `si` is
+    /// readable.
+    pub fn add(&self, label: LabelId, si: SnapshotId) -> GraphResult<()> {
+        let mut pending = self.inner.pending.lock()?;
+        pending.insert(label, si);
+        res_unwrap!(self.inner.meta.put_pending_rewrites(&pending), add, label, si)
+    }
+
+    /// Start to rewrite the types whose properties were dropped before `gc_si` in the background,
+    /// unless the previous rewrite is still running, in which case they are left to the next gc.
+    pub fn schedule(
+        &self, gc_si: SnapshotId, vertex_manager: &VertexTypeManager, edge_manager: &EdgeTypeManager,
+    ) -> GraphResult<()> {
+        let mut worker = self.worker.lock()?;
+        if worker
+            .as_ref()
+            .map_or(false, |handle| !handle.is_finished())
+        {
+            return Ok(());
+        }
+        if let Some(handle) = worker.take() {
+            if handle.join().is_err() {
+                error!("rewrite rows thread panicked");
+            }
+        }
+        let due: Vec<(LabelId, SnapshotId)> = self
+            .inner
+            .pending
+            .lock()?
+            .iter()
+            .filter(|(_, si)| **si <= gc_si)
+            .map(|(label, si)| (*label, *si))
+            .collect();
+        if due.is_empty() {
+            return Ok(());
+        }
+        let mut tasks = Vec::with_capacity(due.len());
+        for (label, si) in due {
+            let targets = if let Ok(info) = vertex_manager.get_type(gc_si, label) {
+                vec![RewriteTarget::Vertex(info)]
+            } else if let Ok(edge_info) = edge_manager.get_edge_info(gc_si, label) {
+                edge_info
+                    .lock()
+                    .iter_kinds()
+                    .filter(|info| info.is_alive_at(gc_si))
+                    .map(|info| RewriteTarget::EdgeKind(info.clone()))
+                    .collect()
+            } else {
+                // the type is dropped, so are its rows
+                vec![]
+            };
+            tasks.push(RewriteTask { label, si, targets });
+        }
+        let inner = self.inner.clone();
+        let handle = thread::Builder::new()
+            .name("rewrite-rows".to_string())
+            .spawn(move || inner.run(gc_si, tasks))
+            .map_err(|e| {
+                let msg = format!("spawn rewrite thread failed: {}", e);
+                gen_graph_err!(ErrorCode::RESOURCE_EXHAUSTED, msg, schedule, gc_si)
+            })?;
+        *worker = Some(handle);
+        Ok(())
+    }
+
+    /// Wait for the running rewrite to finish.
+    pub fn wait(&self) -> GraphResult<()> {
+        let mut worker = self.worker.lock()?;
+        if let Some(handle) = worker.take() {
+            if handle.join().is_err() {
+                error!("rewrite rows thread panicked");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for RowRewriter {
+    fn drop(&mut self) {
+        // rows left are rewritten after the next start
+        self.inner
+            .stopped
+            .store(true, Ordering::Relaxed);
+        if let Err(e) = self.wait() {
+            error!("stop rewrite rows thread failed: {:?}", e);
+        }
+    }
+}
+
+impl RewriterInner {
+    fn run(&self, gc_si: SnapshotId, tasks: Vec<RewriteTask>) {
+        for task in tasks {
+            for target in task.targets.iter() {
+                match self.rewrite(gc_si, target) {
+                    Ok(count) => info!("rewrite {} rows of label#{} at si#{}", count, task.label, gc_si),
+                    Err(e) => {
+                        error!("rewrite rows of label#{} at si#{} failed: {:?}", task.label, gc_si, e);
+                        return;
+                    }
+                }
+            }
+            if self.stopped.load(Ordering::Relaxed) {
+                return;
+            }
+            if let Err(e) = self.finish(task.label, task.si) {
+                error!("finish rewrite of label#{} failed: {:?}", task.label, e);
+            }
+        }
+    }
+
+    /// Returns the number of rewritten rows, rows are left as they are once the type is dropped, its
+    /// table is replaced or the store is closed.
+    fn rewrite(&self, gc_si: SnapshotId, target: &RewriteTarget) -> GraphResult<usize> {
+        let table = match target.get_table(gc_si) {
+            Some(table) => table,
+            None => return Ok(0),
+        };
+        let encoder = target.get_encoder(gc_si)?;
+        let target_version = encoder.get_version();
+        let ttl_versions = target.get_ttl_versions();
+        let mut count = 0;
+        let mut batch = Vec::with_capacity(REWRITE_BATCH_SIZE);
+        let mut buf = Vec::new();
+        for prefix in target.get_prefixes(table.id) {
+            for (key, val) in self.storage.new_scan(&prefix)? {
+                let data = val.to_slice();
+                // tombstone
+                if data.len() < 4 {
+                    continue;
+                }
+                let version = get_codec_version(data);
+                if version >= target_version {
+                    continue;
+                }
+                let props = target
+                    .get_decoder(gc_si, version)?
+                    .decode_all(data);
+                // rows of a codec with a ttl keep their write time, others start to live from now
+                let write_time_ms = if ttl_versions.contains_key(&version) {
+                    get_write_time(data)
+                } else {
+                    self.storage.get_clock().now_ms()
+                };
+                encoder.encode_at(&props, &mut buf, write_time_ms)?;
+                batch.push((key.to_slice().to_vec(), buf.clone()));
+                if batch.len() >= REWRITE_BATCH_SIZE {
+                    if !self.flush(target, table.id, &mut batch)? {
+                        return Ok(count);
+                    }
+                    count += REWRITE_BATCH_SIZE;
+                }
+            }
+        }
+        let rest = batch.len();
+        if rest > 0 && self.flush(target, table.id, &mut batch)? {
+            count += rest;
+        }
+        Ok(count)
+    }
+
+    /// Returns false if the batch is not written.
+    fn flush(
+        &self, target: &RewriteTarget, table_id: TableId, batch: &mut Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> GraphResult<bool> {
+        if self.stopped.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        let _guard = res_unwrap!(self.lock.lock(), flush, table_id)?;
+        if !target.is_current(table_id) {
+            return Ok(false);
+        }
+        self.storage.put_batch(batch)?;
+        batch.clear();
+        Ok(true)
+    }
+
+    /// The type is done unless its properties were dropped again during the rewrite.
+    fn finish(&self, label: LabelId, si: SnapshotId) -> GraphResult<()> {
+        let mut pending = self.pending.lock()?;
+        if pending.get(&label) == Some(&si) {
+            pending.remove(&label);
+            res_unwrap!(self.meta.put_pending_rewrites(&pending), finish, label, si)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::bin::*;
+use super::index::{encode_index_value, is_indexable};
+use super::table_manager::TableId;
+use crate::db::api::*;
+use crate::db::common::bytes::util::{UnsafeBytesReader, UnsafeBytesWriter};
+use crate::db::storage::rocksdb::RocksDB;
+use crate::db::util::lock::GraphMutexLock;
+
+/// A sort key of an edge type: adjacent edges of a vertex are materialized in the key range of
+/// `table_id` ordered by the value of property `prop_id`, so that they can be read in that order
+/// without sorting. Like a vertex index, `table_id` is allocated by the frontend.
+#[derive(Debug)]
+pub struct EdgeSortKeyInfo {
+    label: LabelId,
+    prop_id: PropertyId,
+    table_id: TableId,
+    value_type: ValueType,
+    descending: bool,
+    start_si: SnapshotId,
+}
+
+impl EdgeSortKeyInfo {
+    pub fn get_label(&self) -> LabelId {
+        self.label
+    }
+
+    pub fn get_prop_id(&self) -> PropertyId {
+        self.prop_id
+    }
+
+    pub fn get_table_id(&self) -> TableId {
+        self.table_id
+    }
+
+    pub fn get_value_type(&self) -> ValueType {
+        self.value_type
+    }
+
+    pub fn is_descending(&self) -> bool {
+        self.descending
+    }
+
+    pub fn get_start_si(&self) -> SnapshotId {
+        self.start_si
+    }
+
+    fn is_alive_at(&self, si: SnapshotId) -> bool {
+        self.start_si <= si
+    }
+
+    /// Encode `value` so that the byte order of the result is the sort order, the order of index
+    /// values is reversed for a descending sort key.
+    pub fn encode_value(&self, value: &ValueRef) -> Option<Vec<u8>> {
+        let mut encoded = encode_index_value(value)?;
+        if self.descending {
+            // encoded values are never a prefix of each other, so the complement reverses the order
+            encoded.iter_mut().for_each(|b| *b = !*b);
+        }
+        Some(encoded)
+    }
+}
+
+#[derive(Default)]
+struct SortKeyMap {
+    sort_keys: HashMap<LabelId, Arc<EdgeSortKeyInfo>>,
+    // (drop si, table id) of dropped sort keys whose entries are waiting for gc
+    dropped: Vec<(SnapshotId, TableId)>,
+}
+
+/// Sort keys of edge types, an edge type has at most one sort key.
+pub struct EdgeSortKeyManager {
+    inner: GraphMutexLock<SortKeyMap>,
+}
+
+impl EdgeSortKeyManager {
+    pub fn new() -> Self {
+        EdgeSortKeyManager { inner: GraphMutexLock::new(SortKeyMap::default()) }
+    }
+
+    pub fn contains_sort_key(&self, label: LabelId) -> bool {
+        match self.inner.lock() {
+            Ok(inner) => inner.sort_keys.contains_key(&label),
+            Err(_) => false,
+        }
+    }
+
+    pub fn is_sort_key(&self, label: LabelId, prop_id: PropertyId) -> bool {
+        match self.inner.lock() {
+            Ok(inner) => inner
+                .sort_keys
+                .get(&label)
+                .map_or(false, |info| info.prop_id == prop_id),
+            Err(_) => false,
+        }
+    }
+
+    pub fn create_sort_key(
+        &self, si: SnapshotId, label: LabelId, prop_id: PropertyId, value_type: ValueType,
+        descending: bool, table_id: TableId,
+    ) -> GraphResult<Arc<EdgeSortKeyInfo>> {
+        if !is_indexable(value_type) {
+            let msg = format!("property#{} of type {:?} cannot be a sort key", prop_id, value_type);
+            let err =
+                gen_graph_err!(ErrorCode::UNSUPPORTED_OPERATION, msg, create_sort_key, si, label, prop_id);
+            return Err(err);
+        }
+        let mut inner = self.inner.lock()?;
+        if inner.sort_keys.contains_key(&label) {
+            let msg = format!("sort key of edge#{} already exists", label);
+            let err =
+                gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, create_sort_key, si, label, prop_id);
+            return Err(err);
+        }
+        let info =
+            Arc::new(EdgeSortKeyInfo { label, prop_id, table_id, value_type, descending, start_si: si });
+        inner.sort_keys.insert(label, info.clone());
+        Ok(info)
+    }
+
+    pub fn drop_sort_key(&self, si: SnapshotId, label: LabelId) -> GraphResult<()> {
+        let mut inner = self.inner.lock()?;
+        match inner.sort_keys.remove(&label) {
+            Some(info) => {
+                inner.dropped.push((si, info.table_id));
+                Ok(())
+            }
+            None => {
+                let msg = format!("edge#{} has no sort key", label);
+                let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, drop_sort_key, si, label);
+                Err(err)
+            }
+        }
+    }
+
+    /// Drop the sort key of `label` if any, used when the edge type itself is dropped.
+    pub fn drop_label(&self, si: SnapshotId, label: LabelId) -> GraphResult<()> {
+        let mut inner = self.inner.lock()?;
+        if let Some(info) = inner.sort_keys.remove(&label) {
+            inner.dropped.push((si, info.table_id));
+        }
+        Ok(())
+    }
+
+    /// Returns the sort key of `label` if it is usable at `si`.
+    pub fn get_sort_key(
+        &self, si: SnapshotId, label: LabelId,
+    ) -> GraphResult<Option<Arc<EdgeSortKeyInfo>>> {
+        let inner = self.inner.lock()?;
+        let ret = inner
+            .sort_keys
+            .get(&label)
+            .filter(|info| info.is_alive_at(si))
+            .cloned();
+        Ok(ret)
+    }
+
+    /// Returns table ids of sort keys dropped before `si`, whose entries can be removed.
+    pub fn gc(&self, si: SnapshotId) -> GraphResult<Vec<TableId>> {
+        let mut inner = self.inner.lock()?;
+        let mut table_ids = Vec::new();
+        inner.dropped.retain(|(drop_si, table_id)| {
+            if *drop_si <= si {
+                table_ids.push(*table_id);
+                false
+            } else {
+                true
+            }
+        });
+        Ok(table_ids)
+    }
+}
+
+/// Value of a live sort entry, which keeps the vertex labels of the edge to locate its data. Entries
+/// written when an edge is deleted or its sort value changes have an empty value.
+pub fn sort_entry_value(edge_kind: &EdgeKind) -> [u8; 8] {
+    let mut ret = [0; 8];
+    let mut writer = UnsafeBytesWriter::new(&mut ret);
+    writer.write_i32(0, edge_kind.src_vertex_label_id.to_be());
+    writer.write_i32(4, edge_kind.dst_vertex_label_id.to_be());
+    ret
+}
+
+fn parse_sort_entry_value(label: LabelId, value: &[u8]) -> Option<EdgeKind> {
+    if value.len() != 8 {
+        return None;
+    }
+    let reader = UnsafeBytesReader::new(value);
+    let src_label = reader.read_i32(0).to_be();
+    let dst_label = reader.read_i32(4).to_be();
+    Some(EdgeKind::new(label, src_label, dst_label))
+}
+
+/// Visit the edges of `vertex_id` in `direction` in the order of the sort key at `si`, until
+/// `visit` returns false. Entries of a same (value, edge) pair are ordered from the newest to the
+/// oldest, and only the newest one not after `si` decides whether the pair is alive. The visited
+/// edges may have expired or been removed with their edge kind, callers have to check their data.
+pub fn scan_sort_key<F>(
+    storage: &RocksDB, si: SnapshotId, info: &EdgeSortKeyInfo, vertex_id: VertexId,
+    direction: EdgeDirection, mut visit: F,
+) -> GraphResult<()>
+where
+    F: FnMut(EdgeId, EdgeKind) -> GraphResult<bool>,
+{
+    let prefix = edge_prefix(info.table_id, vertex_id, direction);
+    let mut iter = storage.scan_prefix(&prefix)?;
+    let mut decided: Option<(Vec<u8>, VertexId, EdgeInnerId)> = None;
+    while let Some((k, v)) = iter.next() {
+        let (value, other_id, inner_id, ts) = parse_edge_sort_key(k)?;
+        if ts > si {
+            continue;
+        }
+        if let Some((ref decided_value, decided_other, decided_inner)) = decided {
+            if decided_other == other_id && decided_inner == inner_id && decided_value.as_slice() == value {
+                continue;
+            }
+        }
+        decided = Some((value.to_vec(), other_id, inner_id));
+        if let Some(edge_kind) = parse_sort_entry_value(info.label, v) {
+            let edge_id = match direction {
+                EdgeDirection::Out => EdgeId::new(vertex_id, other_id, inner_id),
+                _ => EdgeId::new(other_id, vertex_id, inner_id),
+            };
+            if !visit(edge_id, edge_kind)? {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(descending: bool) -> EdgeSortKeyInfo {
+        EdgeSortKeyInfo {
+            label: 1,
+            prop_id: 2,
+            table_id: 3,
+            value_type: ValueType::Long,
+            descending,
+            start_si: 1,
+        }
+    }
+
+    #[test]
+    fn test_encode_sort_value_order() {
+        let longs = [i64::min_value(), -1, 0, 1, i64::max_value()];
+        let asc: Vec<Vec<u8>> = longs
+            .iter()
+            .map(|v| {
+                info(false)
+                    .encode_value(&Value::long(*v).as_ref())
+                    .unwrap()
+            })
+            .collect();
+        assert!(asc.windows(2).all(|w| w[0] < w[1]));
+        let desc: Vec<Vec<u8>> = longs
+            .iter()
+            .map(|v| {
+                info(true)
+                    .encode_value(&Value::long(*v).as_ref())
+                    .unwrap()
+            })
+            .collect();
+        assert!(desc.windows(2).all(|w| w[0] > w[1]));
+
+        let strs = ["", "a", "ab", "b"];
+        let desc: Vec<Vec<u8>> = strs
+            .iter()
+            .map(|v| {
+                info(true)
+                    .encode_value(&Value::string(v).as_ref())
+                    .unwrap()
+            })
+            .collect();
+        assert!(desc.windows(2).all(|w| w[0] > w[1]));
+    }
+
+    #[test]
+    fn test_sort_entry_value() {
+        let edge_kind = EdgeKind::new(1, 2, 3);
+        let value = sort_entry_value(&edge_kind);
+        assert_eq!(parse_sort_entry_value(1, &value), Some(edge_kind));
+        assert_eq!(parse_sort_entry_value(1, &[]), None);
+    }
+}
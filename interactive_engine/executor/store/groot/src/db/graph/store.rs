@@ -1,4 +1,5 @@
 #![allow(dead_code)]
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fs;
@@ -11,35 +12,49 @@ use protobuf::Message;
 
 use super::bin::*;
 use super::codec::*;
+use super::index::*;
 use super::meta::*;
+use super::rewrite::RowRewriter;
+use super::sort_key::*;
 use super::types::*;
 use crate::api::elem::Edge;
 use crate::api::Condition;
 use crate::api::ElemFilter;
 use crate::api::PropId;
+use crate::db::api::change::{changes_to_proto, ChangeEvent};
 use crate::db::api::multi_version_graph::{GraphBackup, MultiVersionGraph};
-use crate::db::api::types::RocksEdge;
+use crate::db::api::types::{RocksEdge, RocksVertex};
 use crate::db::api::ErrorCode::{INVALID_DATA, TYPE_NOT_FOUND};
 use crate::db::api::*;
 use crate::db::common::bytes::transform;
+use crate::db::graph::change::{scan_edge_changes, scan_vertex_changes};
 use crate::db::graph::entity::{RocksEdgeImpl, RocksVertexImpl};
 use crate::db::graph::iter::{EdgeKindScan, EdgeTypeScan, VertexTypeScan};
 use crate::db::graph::table_manager::Table;
-use crate::db::storage::rocksdb::{RocksDB, RocksDBBackupEngine};
+use crate::db::storage::rocksdb::{RocksDB, RocksDBBackupEngine, TtlTable};
 use crate::db::storage::RawBytes;
 use crate::db::util::lock::GraphMutexLock;
 
+/// Number of entries put in one write when indexes are built from existing data.
+const BUILD_BATCH_SIZE: usize = 1024;
+
 pub struct GraphStore {
     config: GraphConfig,
-    meta: Meta,
+    meta: Arc<Meta>,
     vertex_manager: VertexTypeManager,
     edge_manager: EdgeTypeManager,
+    index_manager: VertexIndexManager,
+    sort_key_manager: EdgeSortKeyManager,
     storage: Arc<RocksDB>,
     data_root: String,
     data_download_root: String,
     // ensure all modification to graph is in ascending order of snapshot id
     si_guard: AtomicIsize,
-    lock: GraphMutexLock<()>,
+    // snapshots before it may have been garbage collected, persisted in meta to survive restarts
+    gc_si: AtomicIsize,
+    // rewrites rows of types whose properties are dropped once no snapshot before the drop is readable
+    rewriter: RowRewriter,
+    lock: Arc<GraphMutexLock<()>>,
 }
 
 pub struct GraphBackupEngine {
@@ -141,9 +156,24 @@ impl MultiVersionGraph for GraphStore {
                     .get_type_info(si as i64, label_id as i32)
                 {
                     Ok(vertex_type_info) => {
-                        let scan =
-                            VertexTypeScan::new(self.storage.clone(), si, vertex_type_info, with_prop);
-                        scan.into_iter()
+                        let indexed = match condition {
+                            Some(condition) => {
+                                self.scan_vertex_by_index(si, &vertex_type_info, condition)?
+                            }
+                            None => None,
+                        };
+                        match indexed {
+                            Some(iter) => iter,
+                            None => {
+                                let scan = VertexTypeScan::new(
+                                    self.storage.clone(),
+                                    si,
+                                    vertex_type_info,
+                                    with_prop,
+                                );
+                                scan.into_iter()
+                            }
+                        }
                     }
                     Err(e) => {
                         if let TYPE_NOT_FOUND = e.get_error_code() {
@@ -246,6 +276,32 @@ impl MultiVersionGraph for GraphStore {
         edges_iter.nth(k as usize).transpose()
     }
 
+    fn get_edge_sort_key(
+        &self, si: SnapshotId, label_id: LabelId,
+    ) -> GraphResult<Option<(PropertyId, bool)>> {
+        let ret = self
+            .sort_key_manager
+            .get_sort_key(si, label_id)?
+            .map(|info| (info.get_prop_id(), info.is_descending()));
+        Ok(ret)
+    }
+
+    fn get_sorted_out_edges(
+        &self, si: SnapshotId, vertex_id: VertexId, label_id: LabelId, condition: Option<&Condition>,
+        property_ids: Option<&Vec<PropertyId>>, limit: usize,
+    ) -> GraphResult<Records<Self::E>> {
+        debug!("get_sorted_out_edges {:?}, {:?}, {}", vertex_id, label_id, limit);
+        self.query_sorted_edges(si, vertex_id, EdgeDirection::Out, label_id, condition, property_ids, limit)
+    }
+
+    fn get_sorted_in_edges(
+        &self, si: SnapshotId, vertex_id: VertexId, label_id: LabelId, condition: Option<&Condition>,
+        property_ids: Option<&Vec<PropertyId>>, limit: usize,
+    ) -> GraphResult<Records<Self::E>> {
+        debug!("get_sorted_in_edges {:?}, {:?}, {}", vertex_id, label_id, limit);
+        self.query_sorted_edges(si, vertex_id, EdgeDirection::In, label_id, condition, property_ids, limit)
+    }
+
     fn create_vertex_type(
         &self, si: i64, schema_version: i64, label_id: LabelId, type_def: &TypeDef, table_id: i64,
     ) -> GraphResult<bool> {
@@ -268,6 +324,7 @@ impl MultiVersionGraph for GraphStore {
                     .create_type(si, label_id, codec, table)
             })
             .map(|_| self.update_si_guard(si))?;
+        self.sync_ttl_tables();
         Ok(true)
     }
 
@@ -293,6 +350,7 @@ impl MultiVersionGraph for GraphStore {
                     .update_type(si, label_id, codec, table)
             })
             .map(|_| self.update_si_guard(si))?;
+        self.sync_ttl_tables();
         Ok(true)
     }
 
@@ -317,6 +375,7 @@ impl MultiVersionGraph for GraphStore {
                     .create_edge_type(si, label_id, type_def)
             })
             .map(|_| self.update_si_guard(si))?;
+        self.sync_ttl_tables();
         Ok(true)
     }
 
@@ -341,6 +400,116 @@ impl MultiVersionGraph for GraphStore {
                     .update_edge_type(si, label_id, &cloned)
             })
             .map(|_| self.update_si_guard(si))?;
+        self.sync_ttl_tables();
+        Ok(true)
+    }
+
+    fn drop_vertex_type_properties(
+        &self, si: i64, schema_version: i64, label_id: LabelId, type_def: &TypeDef,
+    ) -> GraphResult<bool> {
+        info!("drop_vertex_type_properties");
+        let _guard = res_unwrap!(self.lock.lock(), drop_vertex_type_properties)?;
+        self.check_si_guard(si)?;
+        if let Err(_) = self.meta.check_version(schema_version) {
+            return Ok(false);
+        }
+        let info = res_unwrap!(self.vertex_manager.get_type(si, label_id), drop_vertex_type_properties)?;
+        for prop in type_def.get_prop_defs() {
+            if self
+                .index_manager
+                .contains_index(label_id, prop.id)
+            {
+                let msg = format!("property#{} of vertex#{} is indexed", prop.id, label_id);
+                let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, drop_vertex_type_properties);
+                return Err(err);
+            }
+        }
+        let table = info.get_table(si).ok_or_else(|| {
+            let msg = format!("table of vertex#{} not found", label_id);
+            gen_graph_err!(ErrorCode::NOT_FOUND, msg, drop_vertex_type_properties)
+        })?;
+        self.meta
+            .drop_vertex_type_properties(si, schema_version, label_id, type_def)
+            .and_then(|dropped| {
+                let codec = Codec::from(&dropped);
+                self.vertex_manager
+                    .update_type(si, label_id, codec, Table::new(si, table.id))
+            })
+            .map(|_| self.update_si_guard(si))?;
+        self.rewriter.add(label_id, si)?;
+        Ok(true)
+    }
+
+    fn drop_edge_type_properties(
+        &self, si: i64, schema_version: i64, label_id: LabelId, type_def: &TypeDef,
+    ) -> GraphResult<bool> {
+        info!("drop_edge_type_properties");
+        let _guard = res_unwrap!(self.lock.lock(), drop_edge_type_properties)?;
+        self.check_si_guard(si)?;
+        if let Err(_) = self.meta.check_version(schema_version) {
+            return Ok(false);
+        }
+        if !self.edge_manager.contains_edge(label_id) {
+            let msg = format!("edge#{} does not exist", label_id);
+            let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, drop_edge_type_properties);
+            return Err(err);
+        }
+        if let Some(prop) = type_def.get_prop_defs().find(|p| {
+            self.sort_key_manager
+                .is_sort_key(label_id, p.id)
+        }) {
+            let msg = format!("property#{} of edge#{} is the sort key", prop.id, label_id);
+            let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, drop_edge_type_properties);
+            return Err(err);
+        }
+        self.meta
+            .drop_edge_type_properties(si, schema_version, label_id, type_def)
+            .and_then(|dropped| {
+                self.edge_manager
+                    .update_edge_type(si, label_id, &dropped)
+            })
+            .map(|_| self.update_si_guard(si))?;
+        self.rewriter.add(label_id, si)?;
+        Ok(true)
+    }
+
+    fn rename_vertex_type_property(
+        &self, si: i64, schema_version: i64, label_id: LabelId, prop_id: PropertyId, new_name: &str,
+    ) -> GraphResult<bool> {
+        info!("rename_vertex_type_property");
+        let _guard = res_unwrap!(self.lock.lock(), rename_vertex_type_property)?;
+        self.check_si_guard(si)?;
+        if let Err(_) = self.meta.check_version(schema_version) {
+            return Ok(false);
+        }
+        if !self.vertex_manager.contains_type(si, label_id) {
+            let msg = format!("vertex#{} does not exist", label_id);
+            let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, rename_vertex_type_property);
+            return Err(err);
+        }
+        self.meta
+            .rename_type_property(si, schema_version, label_id, prop_id, new_name)
+            .map(|_| self.update_si_guard(si))?;
+        Ok(true)
+    }
+
+    fn rename_edge_type_property(
+        &self, si: i64, schema_version: i64, label_id: LabelId, prop_id: PropertyId, new_name: &str,
+    ) -> GraphResult<bool> {
+        info!("rename_edge_type_property");
+        let _guard = res_unwrap!(self.lock.lock(), rename_edge_type_property)?;
+        self.check_si_guard(si)?;
+        if let Err(_) = self.meta.check_version(schema_version) {
+            return Ok(false);
+        }
+        if !self.edge_manager.contains_edge(label_id) {
+            let msg = format!("edge#{} does not exist", label_id);
+            let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, rename_edge_type_property);
+            return Err(err);
+        }
+        self.meta
+            .rename_type_property(si, schema_version, label_id, prop_id, new_name)
+            .map(|_| self.update_si_guard(si))?;
         Ok(true)
     }
 
@@ -369,6 +538,7 @@ impl MultiVersionGraph for GraphStore {
                 info.online_table(table)
             })
             .map(|_| self.update_si_guard(si))?;
+        self.sync_ttl_tables();
         Ok(true)
     }
 
@@ -382,7 +552,9 @@ impl MultiVersionGraph for GraphStore {
         self.meta
             .drop_vertex_type(si, schema_version, label_id)
             .and_then(|_| self.vertex_manager.drop_type(si, label_id))
+            .and_then(|_| self.index_manager.drop_label(si, label_id))
             .map(|_| self.update_si_guard(si))?;
+        self.sync_index_tables();
         Ok(true)
     }
 
@@ -396,6 +568,7 @@ impl MultiVersionGraph for GraphStore {
         self.meta
             .drop_edge_type(si, schema_version, label_id)
             .and_then(|_| self.edge_manager.drop_edge_type(si, label_id))
+            .and_then(|_| self.sort_key_manager.drop_label(si, label_id))
             .map(|_| self.update_si_guard(si))?;
         Ok(true)
     }
@@ -417,6 +590,123 @@ impl MultiVersionGraph for GraphStore {
         Ok(true)
     }
 
+    fn create_vertex_index(
+        &self, si: i64, schema_version: i64, label_id: LabelId, prop_id: PropertyId, table_id: i64,
+    ) -> GraphResult<bool> {
+        info!("create_vertex_index");
+        let _guard = res_unwrap!(self.lock.lock(), create_vertex_index, si, label_id, prop_id)?;
+        self.check_si_guard(si)?;
+        if let Err(_) = self.meta.check_version(schema_version) {
+            return Ok(false);
+        }
+        let info = res_unwrap!(self.vertex_manager.get_type(si, label_id), create_vertex_index)?;
+        if self
+            .index_manager
+            .contains_index(label_id, prop_id)
+        {
+            let msg = format!("index on property#{} of vertex#{} already exists", prop_id, label_id);
+            let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, create_vertex_index, si, label_id);
+            return Err(err);
+        }
+        self.meta
+            .create_vertex_index(si, schema_version, label_id, prop_id, table_id)
+            .and_then(|value_type| {
+                self.index_manager
+                    .create_index(si, label_id, prop_id, value_type, table_id)
+            })
+            .and_then(|index| self.build_vertex_index(si, info.as_ref(), index.as_ref()))
+            .map(|_| self.update_si_guard(si))?;
+        self.sync_index_tables();
+        Ok(true)
+    }
+
+    fn drop_vertex_index(
+        &self, si: i64, schema_version: i64, label_id: LabelId, prop_id: PropertyId,
+    ) -> GraphResult<bool> {
+        info!("drop_vertex_index");
+        let _guard = res_unwrap!(self.lock.lock(), drop_vertex_index, si, label_id, prop_id)?;
+        self.check_si_guard(si)?;
+        if let Err(_) = self.meta.check_version(schema_version) {
+            return Ok(false);
+        }
+        if !self
+            .index_manager
+            .contains_index(label_id, prop_id)
+        {
+            let msg = format!("index on property#{} of vertex#{} does not exist", prop_id, label_id);
+            let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, drop_vertex_index, si, label_id);
+            return Err(err);
+        }
+        self.meta
+            .drop_vertex_index(si, schema_version, label_id, prop_id)
+            .and_then(|_| {
+                self.index_manager
+                    .drop_index(si, label_id, prop_id)
+            })
+            .map(|_| self.update_si_guard(si))?;
+        self.sync_index_tables();
+        Ok(true)
+    }
+
+    fn create_edge_sort_key(
+        &self, si: i64, schema_version: i64, label_id: LabelId, prop_id: PropertyId, descending: bool,
+        table_id: i64,
+    ) -> GraphResult<bool> {
+        info!("create_edge_sort_key");
+        let _guard = res_unwrap!(self.lock.lock(), create_edge_sort_key, si, label_id, prop_id)?;
+        self.check_si_guard(si)?;
+        if let Err(_) = self.meta.check_version(schema_version) {
+            return Ok(false);
+        }
+        if !self.edge_manager.contains_edge(label_id) {
+            let msg = format!("edge#{} does not exist", label_id);
+            let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, create_edge_sort_key, si, label_id);
+            return Err(err);
+        }
+        if self
+            .sort_key_manager
+            .contains_sort_key(label_id)
+        {
+            let msg = format!("sort key of edge#{} already exists", label_id);
+            let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, create_edge_sort_key, si, label_id);
+            return Err(err);
+        }
+        self.meta
+            .create_edge_sort_key(si, schema_version, label_id, prop_id, descending, table_id)
+            .and_then(|value_type| {
+                self.sort_key_manager
+                    .create_sort_key(si, label_id, prop_id, value_type, descending, table_id)
+            })
+            .and_then(|sort_key| self.build_edge_sort_key(si, sort_key.as_ref()))
+            .map(|_| self.update_si_guard(si))?;
+        Ok(true)
+    }
+
+    fn drop_edge_sort_key(&self, si: i64, schema_version: i64, label_id: LabelId) -> GraphResult<bool> {
+        info!("drop_edge_sort_key");
+        let _guard = res_unwrap!(self.lock.lock(), drop_edge_sort_key, si, label_id)?;
+        self.check_si_guard(si)?;
+        if let Err(_) = self.meta.check_version(schema_version) {
+            return Ok(false);
+        }
+        if !self
+            .sort_key_manager
+            .contains_sort_key(label_id)
+        {
+            let msg = format!("sort key of edge#{} does not exist", label_id);
+            let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, drop_edge_sort_key, si, label_id);
+            return Err(err);
+        }
+        self.meta
+            .drop_edge_sort_key(si, schema_version, label_id)
+            .and_then(|_| {
+                self.sort_key_manager
+                    .drop_sort_key(si, label_id)
+            })
+            .map(|_| self.update_si_guard(si))?;
+        Ok(true)
+    }
+
     fn insert_overwrite_vertex(
         &self, si: SnapshotId, id: VertexId, label: LabelId, properties: &dyn PropertyMap,
     ) -> GraphResult<()> {
@@ -485,6 +775,13 @@ impl MultiVersionGraph for GraphStore {
         self.check_si_guard(si)?;
         let info = res_unwrap!(self.vertex_manager.get_type(si, label), si, id, label)?;
         if let Some(table) = info.get_table(si) {
+            res_unwrap!(
+                self.update_vertex_indexes(si, info.as_ref(), id, None),
+                delete_vertex,
+                si,
+                id,
+                label
+            )?;
             let ts = si - table.start_si;
             let key = vertex_key(table.id, id, ts);
             let res = self.storage.put(&key, &[]);
@@ -626,7 +923,97 @@ impl MultiVersionGraph for GraphStore {
             let out_table_prefix = edge_table_prefix(et, EdgeDirection::Out);
             self.delete_table_by_prefix(out_table_prefix, false)?;
         }
-        Ok(())
+        let index_tables = self.index_manager.gc(si)?;
+        if !index_tables.is_empty() {
+            info!("garbage collect vertex index table {:?}", index_tables);
+        }
+        for it in index_tables {
+            let table_prefix = vertex_table_prefix(it);
+            self.delete_table_by_prefix(table_prefix, true)?;
+        }
+        let sort_key_tables = self.sort_key_manager.gc(si)?;
+        if !sort_key_tables.is_empty() {
+            info!("garbage collect edge sort key table {:?}", sort_key_tables);
+        }
+        for st in sort_key_tables {
+            let table_prefix = edge_table_prefix(st, EdgeDirection::Out);
+            self.delete_table_by_prefix(table_prefix, false)?;
+        }
+        let prev_gc_si = self
+            .gc_si
+            .fetch_max(si as isize, Ordering::Relaxed);
+        if si as isize > prev_gc_si {
+            self.meta.put_gc_snapshot_id(si)?;
+        }
+        self.storage.set_gc_snapshot(si);
+        self.rewriter
+            .schedule(si, &self.vertex_manager, &self.edge_manager)
+    }
+
+    fn get_gc_snapshot_id(&self) -> SnapshotId {
+        self.gc_si.load(Ordering::Relaxed) as SnapshotId
+    }
+
+    fn get_latest_snapshot_id(&self) -> SnapshotId {
+        let si_guard = self.si_guard.load(Ordering::Relaxed) as SnapshotId;
+        si_guard.max(self.get_gc_snapshot_id())
+    }
+
+    fn scan_changes(&self, from_si: SnapshotId, to_si: SnapshotId) -> GraphResult<Records<ChangeEvent>> {
+        let gc_si = self.gc_si.load(Ordering::Relaxed) as SnapshotId;
+        if from_si < gc_si || to_si < from_si {
+            let msg = format!("invalid change range ({}, {}], gc watermark is {}", from_si, to_si, gc_si);
+            let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, scan_changes, from_si, to_si);
+            return Err(err);
+        }
+        let mut res: Records<ChangeEvent> = Box::new(::std::iter::empty());
+        // labels are scanned in a stable order, so that a range of changes can be read by pages
+        let mut vertex_labels = {
+            let guard = epoch::pin();
+            let map = self.vertex_manager.get_map(&guard);
+            let map_ref = unsafe { map.deref() };
+            map_ref
+                .keys()
+                .cloned()
+                .collect::<Vec<LabelId>>()
+        };
+        vertex_labels.sort();
+        for label in vertex_labels {
+            let info = match self.vertex_manager.get_type_info(to_si, label) {
+                Ok(info) => info,
+                Err(_) => continue,
+            };
+            if let Some(table) = info.get_table(to_si) {
+                check_change_table(info.get_table(from_si), &table, from_si, to_si)?;
+                let iter = scan_vertex_changes(&self.storage, from_si, to_si, info, table)?;
+                res = Box::new(res.chain(iter));
+            }
+        }
+        let mut edge_kinds = {
+            let guard = epoch::pin();
+            let inner = self.edge_manager.get_inner(&guard);
+            let edge_mgr = unsafe { inner.deref() };
+            edge_mgr
+                .get_edge_kinds()
+                .cloned()
+                .collect::<Vec<EdgeKind>>()
+        };
+        edge_kinds.sort_by_key(|k| (k.edge_label_id, k.src_vertex_label_id, k.dst_vertex_label_id));
+        for edge_kind in edge_kinds {
+            let info = match self
+                .edge_manager
+                .get_edge_kind(to_si, &edge_kind)
+            {
+                Ok(info) => info,
+                Err(_) => continue,
+            };
+            if let Some(table) = info.get_table(to_si) {
+                check_change_table(info.get_table(from_si), &table, from_si, to_si)?;
+                let iter = scan_edge_changes(&self.storage, from_si, to_si, info, table)?;
+                res = Box::new(res.chain(iter));
+            }
+        }
+        Ok(res)
     }
 
     fn get_graph_def_blob(&self) -> GraphResult<Vec<u8>> {
@@ -679,15 +1066,34 @@ impl MultiVersionGraph for GraphStore {
             let info = self
                 .edge_manager
                 .get_edge_kind(si, &edge_kind)?;
+            let sort_key = self
+                .sort_key_manager
+                .get_sort_key(si, target.label_id)?;
+            // edges of the replaced table are gone from `si` on, and so are their sort entries
+            if let Some(ref sort_key) = sort_key {
+                self.write_edge_kind_sort_entries(si, &info, sort_key.as_ref(), false)?;
+            }
             info.online_table(Table::new(si, table_id))?;
             info!("online edge. target {:?}, tableId {}, si {}", target, table_id, si);
+            // the loaded data carries no sort key entries, add them for the new table from `si` on
+            if let Some(ref sort_key) = sort_key {
+                self.write_edge_kind_sort_entries(si, &info, sort_key.as_ref(), true)?;
+            }
         } else {
             let info = self
                 .vertex_manager
                 .get_type(si, target.label_id)?;
             info.online_table(Table::new(si, table_id))?;
             info!("online vertex. labelId {}, tableId {}, si {}", target.label_id, table_id, si);
+            // the loaded data carries no index entries, index the new table from `si` on
+            for index in self
+                .index_manager
+                .get_indexes(si, target.label_id)?
+            {
+                self.build_vertex_index(si, info.as_ref(), index.as_ref())?;
+            }
         }
+        self.sync_ttl_tables();
         Ok(true)
     }
 
@@ -744,8 +1150,11 @@ impl GraphStore {
     }
 
     fn init(config: &GraphConfig, storage: Arc<RocksDB>, path: &str) -> GraphResult<Self> {
-        let meta = Meta::new(storage.clone());
-        let (vertex_manager, edge_manager) = res_unwrap!(meta.recover(), init)?;
+        let meta = Arc::new(Meta::new(storage.clone()));
+        let (vertex_manager, edge_manager, index_manager, sort_key_manager) =
+            res_unwrap!(meta.recover(), init)?;
+        let gc_si = res_unwrap!(meta.get_gc_snapshot_id(), init)?;
+        storage.set_gc_snapshot(gc_si);
         let data_root = path.to_string();
         let mut download_root = "".to_string();
         download_root = config
@@ -756,29 +1165,90 @@ impl GraphStore {
             download_root = format!("{}/../{}", data_root, "download");
         }
 
+        let lock = Arc::new(GraphMutexLock::new(()));
+        let rewriter = res_unwrap!(RowRewriter::new(storage.clone(), meta.clone(), lock.clone()), init)?;
         let ret = GraphStore {
             config: config.clone(),
             meta,
             vertex_manager,
             edge_manager,
+            index_manager,
+            sort_key_manager,
             storage,
             data_root: data_root,
             data_download_root: download_root,
             si_guard: AtomicIsize::new(0),
-            lock: GraphMutexLock::new(()),
+            gc_si: AtomicIsize::new(gc_si as isize),
+            rewriter,
+            lock,
         };
+        ret.sync_ttl_tables();
+        ret.sync_index_tables();
         Ok(ret)
     }
 
+    /// Register the current tables of types with a ttl to the storage, so that their expired records
+    /// are dropped by compaction.
+    fn sync_ttl_tables(&self) {
+        let mut tables = HashMap::new();
+        let guard = epoch::pin();
+        let map = self.vertex_manager.get_map(&guard);
+        let map_ref = unsafe { map.deref() };
+        for info in map_ref.values() {
+            let ttl_ms = info.get_ttl_versions();
+            if ttl_ms.is_empty() {
+                continue;
+            }
+            if let Some(table) = info.get_table(INFINITE_SI) {
+                let ttl_table = TtlTable { ttl_ms, start_si: table.start_si };
+                tables.insert(vertex_table_prefix(table.id), ttl_table);
+            }
+        }
+        let inner = self.edge_manager.get_inner(&guard);
+        let edge_mgr = unsafe { inner.deref() };
+        for info in edge_mgr.get_edge_kind_infos() {
+            let ttl_ms = info.get_ttl_versions();
+            if ttl_ms.is_empty() {
+                continue;
+            }
+            if let Some(table) = info.get_table(INFINITE_SI) {
+                let ttl_table = TtlTable { ttl_ms, start_si: table.start_si };
+                tables.insert(edge_table_prefix(table.id, EdgeDirection::Out), ttl_table.clone());
+                tables.insert(edge_table_prefix(table.id, EdgeDirection::In), ttl_table);
+            }
+        }
+        if !tables.is_empty() {
+            info!("ttl tables {:?}", tables);
+        }
+        self.storage.set_ttl_tables(tables);
+    }
+
+    /// Register the tables of current indexes to the storage, so that their entries superseded
+    /// before the gc watermark are dropped by compaction.
+    fn sync_index_tables(&self) {
+        match self.index_manager.get_table_ids() {
+            Ok(table_ids) => {
+                let tables = table_ids
+                    .into_iter()
+                    .map(vertex_table_prefix)
+                    .collect();
+                self.storage.set_index_tables(tables);
+            }
+            Err(e) => error!("sync index tables failed: {:?}", e),
+        }
+    }
+
     fn get_vertex_data(
         &self, si: SnapshotId, id: VertexId, info: &VertexTypeInfo,
     ) -> GraphResult<Option<Vec<u8>>> {
         debug!("get_vertex_data");
         if let Some(table) = info.get_table(si) {
             let key = vertex_key(table.id, id, si - table.start_si);
+            let checker = info.get_expire_checker(self.storage.get_clock().now_ms());
             let mut iter = self.storage.scan_from(&key)?;
             if let Some((k, v)) = iter.next() {
-                if k.len() == key.len() && k[0..16] == key[0..16] && v.len() >= 4 {
+                if k.len() == key.len() && k[0..16] == key[0..16] && v.len() >= 4 && !checker.is_expired(v)
+                {
                     let ret = v.to_vec();
                     return Ok(Some(ret));
                 }
@@ -794,9 +1264,11 @@ impl GraphStore {
         if let Some(table) = info.get_table(si) {
             let ts = si - table.start_si;
             let key = edge_key(table.id, id, direction, ts);
+            let checker = info.get_expire_checker(self.storage.get_clock().now_ms());
             let mut iter = self.storage.scan_from(&key)?;
             if let Some((k, v)) = iter.next() {
-                if k.len() == key.len() && k[0..32] == key[0..32] && v.len() >= 4 {
+                if k.len() == key.len() && k[0..32] == key[0..32] && v.len() >= 4 && !checker.is_expired(v)
+                {
                     let ret = v.to_vec();
                     return Ok(Some(ret));
                 }
@@ -813,8 +1285,10 @@ impl GraphStore {
         if let Some(table) = info.get_table(si) {
             let encoder = res_unwrap!(info.get_encoder(si), do_insert_vertex_data)?;
             let mut buf = Vec::new();
+            let now_ms = self.storage.get_clock().now_ms();
             return encoder
-                .encode(properties, &mut buf)
+                .encode_at(properties, &mut buf, now_ms)
+                .and_then(|_| self.update_vertex_indexes(si, info, id, Some(&buf)))
                 .and_then(|_| {
                     let ts = si - table.start_si;
                     let key = vertex_key(table.id, id, ts);
@@ -826,6 +1300,114 @@ impl GraphStore {
         Err(err)
     }
 
+    /// Maintain the indexes of `info` for vertex `id`, whose encoded data at `si` becomes `new_data`,
+    /// or `None` if it is deleted. This must be called before the new data is written, as it reads
+    /// the current data to invalidate index entries of changed values.
+    fn update_vertex_indexes(
+        &self, si: SnapshotId, info: &VertexTypeInfo, id: VertexId, new_data: Option<&[u8]>,
+    ) -> GraphResult<()> {
+        let indexes = self
+            .index_manager
+            .get_indexes(si, info.get_label())?;
+        if indexes.is_empty() {
+            return Ok(());
+        }
+        let old_data = self.get_vertex_data(si, id, info)?;
+        let old_decoder = match old_data {
+            Some(ref data) => Some(info.get_decoder(si, get_codec_version(data))?),
+            None => None,
+        };
+        let new_decoder = match new_data {
+            Some(data) => Some(info.get_decoder(si, get_codec_version(data))?),
+            None => None,
+        };
+        for index in indexes {
+            let prop_id = index.get_prop_id();
+            let old_value = match (&old_data, &old_decoder) {
+                (Some(data), Some(decoder)) => decoder
+                    .decode_property(data, prop_id)
+                    .and_then(|v| encode_index_value(&v)),
+                _ => None,
+            };
+            let new_value = match (new_data, &new_decoder) {
+                (Some(data), Some(decoder)) => decoder
+                    .decode_property(data, prop_id)
+                    .and_then(|v| encode_index_value(&v)),
+                _ => None,
+            };
+            if old_value == new_value {
+                continue;
+            }
+            if let Some(old_value) = old_value {
+                let key = vertex_index_key(index.get_table_id(), &old_value, id, si);
+                self.storage.put(&key, &[])?;
+            }
+            if let Some(new_value) = new_value {
+                let key = vertex_index_key(index.get_table_id(), &new_value, id, si);
+                self.storage.put(&key, &INDEX_ENTRY_ALIVE)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Index all vertices of `info` visible at `si`, the entries are written at `si` which is the
+    /// snapshot the index starts to serve queries from.
+    fn build_vertex_index(
+        &self, si: SnapshotId, info: &VertexTypeInfo, index: &VertexIndexInfo,
+    ) -> GraphResult<()> {
+        let type_info = self
+            .vertex_manager
+            .get_type_info(si, info.get_label())?;
+        let prop_id = index.get_prop_id();
+        let mut batch = Vec::with_capacity(BUILD_BATCH_SIZE);
+        let mut count = 0;
+        for vertex in VertexTypeScan::new(self.storage.clone(), si, type_info, false).into_iter() {
+            let id = RocksVertex::get_vertex_id(&vertex?);
+            if let Some(data) = self.get_vertex_data(si, id, info)? {
+                let decoder = info.get_decoder(si, get_codec_version(&data))?;
+                if let Some(value) = decoder
+                    .decode_property(&data, prop_id)
+                    .and_then(|v| encode_index_value(&v))
+                {
+                    let key = vertex_index_key(index.get_table_id(), &value, id, si);
+                    batch.push((key, INDEX_ENTRY_ALIVE.to_vec()));
+                }
+            }
+            if batch.len() >= BUILD_BATCH_SIZE {
+                self.storage.put_batch(&batch)?;
+                count += batch.len();
+                batch.clear();
+            }
+        }
+        self.storage.put_batch(&batch)?;
+        count += batch.len();
+        info!("built index on property#{} of vertex#{} with {} entries", prop_id, info.get_label(), count);
+        Ok(())
+    }
+
+    /// Scan vertices of `info` through an index if `condition` has an equality or range predicate on
+    /// an indexed property. Returns `None` if no index can serve the condition. The condition still
+    /// has to be applied on the returned vertices.
+    fn scan_vertex_by_index(
+        &self, si: SnapshotId, info: &Arc<VertexTypeInfo>, condition: &Condition,
+    ) -> GraphResult<Option<Records<RocksVertexImpl>>> {
+        let label_id = info.get_label();
+        for index in self.index_manager.get_indexes(si, label_id)? {
+            if let Some(range) = extract_index_range(condition, index.as_ref()) {
+                debug!("scan vertex#{} by index on property#{}", label_id, index.get_prop_id());
+                let ids = scan_index(self.storage.as_ref(), si, index.as_ref(), &range)?;
+                let storage = self.storage.clone();
+                let info = info.clone();
+                let vertices = ids.filter_map(move |id| {
+                    id.and_then(|id| get_vertex_with_info(&storage, si, id, &info, Some(HashSet::new())))
+                        .transpose()
+                });
+                return Ok(Some(Box::new(vertices)));
+            }
+        }
+        Ok(None)
+    }
+
     fn do_insert_edge_data(
         &self, si: SnapshotId, edge_id: EdgeId, info: &EdgeKindInfo, direction: EdgeDirection,
         properties: &dyn PropertyMap,
@@ -834,8 +1416,10 @@ impl GraphStore {
         if let Some(table) = info.get_table(si) {
             let encoder = res_unwrap!(info.get_encoder(si), do_insert_edge_data)?;
             let mut buf = Vec::new();
+            let now_ms = self.storage.get_clock().now_ms();
             return encoder
-                .encode(properties, &mut buf)
+                .encode_at(properties, &mut buf, now_ms)
+                .and_then(|_| self.update_edge_sort_key(si, info, edge_id, direction, Some(&buf)))
                 .and_then(|_| {
                     let ts = si - table.start_si;
                     let key = edge_key(table.id, edge_id, direction, ts);
@@ -847,6 +1431,196 @@ impl GraphStore {
         Err(err)
     }
 
+    /// Maintain the sort key entries of edge `edge_id` in `direction`, whose encoded data at `si`
+    /// becomes `new_data`, or `None` if it is deleted. Like `update_vertex_indexes`, this must be
+    /// called before the new data is written.
+    fn update_edge_sort_key(
+        &self, si: SnapshotId, info: &EdgeKindInfo, edge_id: EdgeId, direction: EdgeDirection,
+        new_data: Option<&[u8]>,
+    ) -> GraphResult<()> {
+        let edge_kind = info.get_type();
+        let sort_key = match self
+            .sort_key_manager
+            .get_sort_key(si, edge_kind.edge_label_id)?
+        {
+            Some(sort_key) => sort_key,
+            None => return Ok(()),
+        };
+        let old_data = self.get_edge_data(si, edge_id, info, direction)?;
+        let old_value = match old_data {
+            Some(ref data) => self.encode_sort_value(si, info, sort_key.as_ref(), data)?,
+            None => None,
+        };
+        let new_value = match new_data {
+            Some(data) => self.encode_sort_value(si, info, sort_key.as_ref(), data)?,
+            None => None,
+        };
+        if old_value == new_value {
+            return Ok(());
+        }
+        let (vertex_id, other_id) = match direction {
+            EdgeDirection::Out => (edge_id.src_id, edge_id.dst_id),
+            _ => (edge_id.dst_id, edge_id.src_id),
+        };
+        let table_id = sort_key.get_table_id();
+        if let Some(old_value) = old_value {
+            let key =
+                edge_sort_key(table_id, direction, vertex_id, &old_value, other_id, edge_id.inner_id, si);
+            self.storage.put(&key, &[])?;
+        }
+        if let Some(new_value) = new_value {
+            let key =
+                edge_sort_key(table_id, direction, vertex_id, &new_value, other_id, edge_id.inner_id, si);
+            self.storage
+                .put(&key, &sort_entry_value(edge_kind))?;
+        }
+        Ok(())
+    }
+
+    fn encode_sort_value(
+        &self, si: SnapshotId, info: &EdgeKindInfo, sort_key: &EdgeSortKeyInfo, data: &[u8],
+    ) -> GraphResult<Option<Vec<u8>>> {
+        let decoder = info.get_decoder(si, get_codec_version(data))?;
+        let ret = decoder
+            .decode_property(data, sort_key.get_prop_id())
+            .and_then(|v| sort_key.encode_value(&v));
+        Ok(ret)
+    }
+
+    /// Add sort key entries of all edges of `sort_key`'s edge type visible at `si` in both
+    /// directions, the entries are written at `si` which is the snapshot the sort key starts to serve
+    /// queries from.
+    fn build_edge_sort_key(&self, si: SnapshotId, sort_key: &EdgeSortKeyInfo) -> GraphResult<()> {
+        let label_id = sort_key.get_label();
+        let edge_info = self.edge_manager.get_edge_info(si, label_id)?;
+        let kinds: Vec<Arc<EdgeKindInfo>> = edge_info
+            .lock()
+            .iter_kinds()
+            .filter(|kind| kind.is_alive_at(si))
+            .cloned()
+            .collect();
+        let mut count = 0;
+        for kind in kinds {
+            count += self.write_edge_kind_sort_entries(si, &kind, sort_key, true)?;
+        }
+        info!(
+            "built sort key on property#{} of edge#{} with {} entries",
+            sort_key.get_prop_id(),
+            label_id,
+            count
+        );
+        Ok(())
+    }
+
+    /// Write a sort key entry at `si` for every edge of `kind` visible at `si` in both directions,
+    /// live entries if `alive` or deleted ones otherwise. Returns the number of written entries.
+    fn write_edge_kind_sort_entries(
+        &self, si: SnapshotId, kind: &Arc<EdgeKindInfo>, sort_key: &EdgeSortKeyInfo, alive: bool,
+    ) -> GraphResult<usize> {
+        let entry_value = if alive { sort_entry_value(kind.get_type()).to_vec() } else { vec![] };
+        let mut batch = Vec::with_capacity(BUILD_BATCH_SIZE);
+        let mut count = 0;
+        for direction in [EdgeDirection::Out, EdgeDirection::In]
+            .iter()
+            .cloned()
+        {
+            for edge in EdgeKindScan::new(self.storage.clone(), si, kind.clone(), None, direction, false) {
+                let id = *RocksEdge::get_edge_id(&edge?);
+                if let Some(data) = self.get_edge_data(si, id, kind.as_ref(), direction)? {
+                    if let Some(value) = self.encode_sort_value(si, kind.as_ref(), sort_key, &data)? {
+                        let (vertex_id, other_id) = match direction {
+                            EdgeDirection::Out => (id.src_id, id.dst_id),
+                            _ => (id.dst_id, id.src_id),
+                        };
+                        let key = edge_sort_key(
+                            sort_key.get_table_id(),
+                            direction,
+                            vertex_id,
+                            &value,
+                            other_id,
+                            id.inner_id,
+                            si,
+                        );
+                        batch.push((key, entry_value.clone()));
+                    }
+                }
+                if batch.len() >= BUILD_BATCH_SIZE {
+                    self.storage.put_batch(&batch)?;
+                    count += batch.len();
+                    batch.clear();
+                }
+            }
+        }
+        self.storage.put_batch(&batch)?;
+        count += batch.len();
+        Ok(count)
+    }
+
+    /// Read edges of `vertex_id` in `direction` through the sort key of `label_id`. The data of each
+    /// edge is read from the table of `direction`, as edges of other partitions only have a copy of
+    /// that direction in this store.
+    fn query_sorted_edges(
+        &self, si: SnapshotId, vertex_id: VertexId, direction: EdgeDirection, label_id: LabelId,
+        condition: Option<&Condition>, property_ids: Option<&Vec<PropertyId>>, limit: usize,
+    ) -> GraphResult<Records<RocksEdgeImpl>> {
+        let sort_key = match self
+            .sort_key_manager
+            .get_sort_key(si, label_id)?
+        {
+            Some(sort_key) => sort_key,
+            None => {
+                let msg = format!("edge#{} has no sort key at {}", label_id, si);
+                let err =
+                    gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, query_sorted_edges, si, label_id);
+                return Err(err);
+            }
+        };
+        let columns = Self::parse_columns(property_ids);
+        // edge kinds are resolved once, kinds removed at `si` are None
+        let mut kinds: HashMap<EdgeKind, Option<Arc<EdgeKindInfo>>> = HashMap::new();
+        let mut edges = Vec::new();
+        scan_sort_key(
+            self.storage.as_ref(),
+            si,
+            sort_key.as_ref(),
+            vertex_id,
+            direction,
+            |edge_id, edge_kind| {
+                let info = match kinds.entry(edge_kind) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        let info = self
+                            .edge_manager
+                            .get_edge_kind(si, entry.key())
+                            .ok();
+                        entry.insert(info)
+                    }
+                };
+                if let Some(info) = info {
+                    if let Some(data) = self.get_edge_data(si, edge_id, info.as_ref(), direction)? {
+                        let decoder = info.get_decoder(si, get_codec_version(&data))?;
+                        let mut edge = RocksEdgeImpl::new(
+                            edge_id,
+                            info.get_type().clone(),
+                            Some(decoder),
+                            RawBytes::new(&data),
+                        );
+                        let matched = match condition {
+                            Some(condition) => condition.filter_edge(&edge).unwrap_or(false),
+                            None => true,
+                        };
+                        if matched {
+                            edge.set_columns(columns.clone());
+                            edges.push(Ok(edge));
+                        }
+                    }
+                }
+                Ok(limit == 0 || edges.len() < limit)
+            },
+        )?;
+        Ok(Box::new(edges.into_iter()))
+    }
+
     fn check_si_guard(&self, si: SnapshotId) -> GraphResult<()> {
         let guard = self.si_guard.load(Ordering::Relaxed) as SnapshotId;
         if si < guard {
@@ -881,26 +1655,8 @@ impl GraphStore {
         let vertex_type_info = self
             .vertex_manager
             .get_type_info(si, label_id as i32)?;
-        if let Some(table) = vertex_type_info.get_table(si) {
-            let key = vertex_key(table.id, vertex_id as i64, si - table.start_si);
-            let mut iter = self.storage.scan_from(&key)?;
-            if let Some((k, v)) = iter.next() {
-                if k[0..16] == key[0..16] && v.len() > 4 {
-                    let codec_version = get_codec_version(v);
-                    let decoder = vertex_type_info.get_decoder(si, codec_version)?;
-                    let columns = Self::parse_columns(property_ids);
-                    let vertex = RocksVertexImpl::with_columns(
-                        vertex_id,
-                        vertex_type_info.get_label() as LabelId,
-                        Some(decoder),
-                        RawBytes::new(v),
-                        columns,
-                    );
-                    return Ok(Some(vertex));
-                }
-            }
-        }
-        Ok(None)
+        let columns = Self::parse_columns(property_ids);
+        get_vertex_with_info(&self.storage, si, vertex_id, &vertex_type_info, columns)
     }
 
     fn get_edge_from_relation(
@@ -914,9 +1670,10 @@ impl GraphStore {
             .get_edge_kind(si, &edge_relation.into())?;
         if let Some(table) = info.get_table(si) {
             let key = edge_key(table.id, edge_id.into(), EdgeDirection::Out, si - table.start_si);
+            let checker = info.get_expire_checker(self.storage.get_clock().now_ms());
             let mut iter = self.storage.scan_from(&key)?;
             if let Some((k, v)) = iter.next() {
-                if k[0..32] == key[0..32] && v.len() >= 4 {
+                if k[0..32] == key[0..32] && v.len() >= 4 && !checker.is_expired(v) {
                     let codec_version = get_codec_version(v);
                     let decoder = info.get_decoder(si, codec_version)?;
                     let columns = Self::parse_columns(property_ids);
@@ -1023,6 +1780,13 @@ impl GraphStore {
         let info = res_unwrap!(self.edge_manager.get_edge_kind(si, edge_kind), si, id, edge_kind)?;
         let direction = if forward { EdgeDirection::Out } else { EdgeDirection::In };
         if let Some(table) = info.get_table(si) {
+            res_unwrap!(
+                self.update_edge_sort_key(si, info.as_ref(), id, direction, None),
+                delete_edge,
+                si,
+                id,
+                edge_kind
+            )?;
             let ts = si - table.start_si;
             let key = edge_key(table.id, id, direction, ts);
             res_unwrap!(self.storage.put(&key, &[]), delete_edge, si, id, edge_kind)?;
@@ -1087,6 +1851,22 @@ impl GraphStore {
             .map_err(|e| GraphError::new(INVALID_DATA, format!("{:?}", e)))
     }
 
+    /// Returns a page of at most `limit` changes in `(from_si, to_si]` after the first `offset` ones,
+    /// encoded as `ChangeEventBatchPb` bytes. Changes of a range are scanned in a stable order, the
+    /// range is read by pages of increasing offsets until a page has less than `limit` changes.
+    pub fn get_changes_blob(
+        &self, from_si: SnapshotId, to_si: SnapshotId, offset: usize, limit: usize,
+    ) -> GraphResult<Vec<u8>> {
+        let events = self
+            .scan_changes(from_si, to_si)?
+            .skip(offset)
+            .take(limit)
+            .collect::<GraphResult<Vec<ChangeEvent>>>()?;
+        let pb = changes_to_proto(from_si, to_si, &events)?;
+        pb.write_to_bytes()
+            .map_err(|e| GraphError::new(INVALID_DATA, format!("{:?}", e)))
+    }
+
     pub fn get_statistics(&self, si: SnapshotId) -> GraphResult<GraphPartitionStatistics> {
         let vertex_labels_statistics = self.get_vertex_statistics(si)?;
         let edge_labels_statistics = self.get_edge_statistics(si)?;
@@ -1150,6 +1930,51 @@ impl GraphStore {
     }
 }
 
+/// Versions of different tables are not comparable, so a range in which data loading replaces the
+/// table of a type cannot be scanned for changes.
+/// Get vertex `vertex_id` of `info` at `si`, decoding only `columns` if given.
+fn get_vertex_with_info(
+    storage: &RocksDB, si: SnapshotId, vertex_id: VertexId, info: &VertexTypeInfo,
+    columns: Option<HashSet<PropId>>,
+) -> GraphResult<Option<RocksVertexImpl>> {
+    if let Some(table) = info.get_table(si) {
+        let key = vertex_key(table.id, vertex_id as i64, si - table.start_si);
+        let checker = info.get_expire_checker(storage.get_clock().now_ms());
+        let mut iter = storage.scan_from(&key)?;
+        if let Some((k, v)) = iter.next() {
+            if k[0..16] == key[0..16] && v.len() > 4 && !checker.is_expired(v) {
+                let codec_version = get_codec_version(v);
+                let decoder = info.get_decoder(si, codec_version)?;
+                let vertex = RocksVertexImpl::with_columns(
+                    vertex_id,
+                    info.get_label() as LabelId,
+                    Some(decoder),
+                    RawBytes::new(v),
+                    columns,
+                );
+                return Ok(Some(vertex));
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn check_change_table(
+    from_table: Option<Table>, to_table: &Table, from_si: SnapshotId, to_si: SnapshotId,
+) -> GraphResult<()> {
+    if let Some(from_table) = from_table {
+        if from_table.id != to_table.id {
+            let msg = format!(
+                "table#{} is replaced by table#{} at si#{}, changes in ({}, {}] cannot be scanned",
+                from_table.id, to_table.id, to_table.start_si, from_si, to_si
+            );
+            let err = gen_graph_err!(ErrorCode::INVALID_OPERATION, msg, check_change_table);
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
 fn merge_updates<'a>(old: &mut HashMap<PropertyId, ValueRef<'a>>, updates: &'a dyn PropertyMap) {
     for (prop_id, v) in updates.as_map() {
         old.insert(prop_id, v);
@@ -1198,6 +2023,18 @@ mod tests {
         do_test(path, |graph| tests::vertex::test_drop_vertex_type(graph));
     }
 
+    #[test]
+    fn test_vertex_index() {
+        let path = "test_vertex_index";
+        do_test(path, |graph| tests::vertex::test_vertex_index(graph, uses_index));
+    }
+
+    #[test]
+    fn test_edge_sort_key() {
+        let path = "test_edge_sort_key";
+        do_test(path, |graph| tests::sort_key::test_edge_sort_key(graph));
+    }
+
     #[test]
     fn test_get_edge() {
         let path = "test_get_edge";
@@ -1246,6 +2083,644 @@ mod tests {
         do_test(path, |graph| tests::graph::test_si_guard(graph));
     }
 
+    #[test]
+    fn test_scan_changes() {
+        let path = "test_scan_changes";
+        do_test(path, |graph| tests::change::test_scan_changes(graph));
+    }
+
+    #[test]
+    fn test_ttl() {
+        let path = "test_ttl";
+        do_test(path, |graph| {
+            tests::ttl::test_ttl(graph, |graph, ms| graph.storage.get_clock().advance(ms))
+        });
+    }
+
+    #[test]
+    fn test_drop_property() {
+        let path = "test_drop_property";
+        do_test(path, |graph| tests::property::test_drop_property(graph));
+    }
+
+    #[test]
+    fn test_rename_property() {
+        let path = "test_rename_property";
+        do_test(path, |graph| tests::property::test_rename_property(graph));
+    }
+
+    #[test]
+    fn test_property_rewrite() {
+        let path = "test_property_rewrite";
+        do_test(path, |graph| {
+            tests::property::prepare_dropped_property(&graph, 10, 11);
+            let codec_versions = |prefix: &[u8]| {
+                graph
+                    .storage
+                    .new_scan(prefix)
+                    .unwrap()
+                    .map(|(_, v)| get_codec_version(v.to_slice()))
+                    .collect::<Vec<CodecVersion>>()
+            };
+            let prefixes = vec![
+                vertex_table_prefix_key(10),
+                edge_table_prefix_key(11, EdgeDirection::Out),
+                edge_table_prefix_key(11, EdgeDirection::In),
+            ];
+            // rows are kept until no snapshot before the drop is readable
+            graph.gc(3).unwrap();
+            for prefix in prefixes.iter() {
+                assert_eq!(codec_versions(prefix), vec![0; 3]);
+            }
+            graph.gc(4).unwrap();
+            graph.rewriter.wait().unwrap();
+            for prefix in prefixes.iter() {
+                assert_eq!(codec_versions(prefix), vec![1; 3]);
+            }
+            assert!(graph
+                .meta
+                .get_pending_rewrites()
+                .unwrap()
+                .is_empty());
+        });
+    }
+
+    #[test]
+    fn test_property_rewrite_after_reopen() {
+        let path = "store_test/test_property_rewrite_after_reopen";
+        fs::rmr(&path).unwrap();
+        {
+            let graph = create_empty_graph(&path);
+            tests::property::prepare_dropped_property(&graph, 10, 11);
+        }
+        // the pending rewrites survive a restart
+        let graph = create_empty_graph(&path);
+        let pending = graph.meta.get_pending_rewrites().unwrap();
+        let expected: HashMap<LabelId, SnapshotId> =
+            vec![(tests::property::VERTEX_LABEL, 4), (tests::property::EDGE_LABEL, 4)]
+                .into_iter()
+                .collect();
+        assert_eq!(pending, expected);
+        graph.gc(4).unwrap();
+        graph.rewriter.wait().unwrap();
+        let versions: Vec<CodecVersion> = graph
+            .storage
+            .new_scan(&vertex_table_prefix_key(10))
+            .unwrap()
+            .map(|(_, v)| get_codec_version(v.to_slice()))
+            .collect();
+        assert_eq!(versions, vec![1; 3]);
+        assert!(graph
+            .meta
+            .get_pending_rewrites()
+            .unwrap()
+            .is_empty());
+        drop(graph);
+        fs::rmr(&path).unwrap();
+    }
+
+    #[test]
+    fn test_property_rewrite_keeps_write_time() {
+        let path = "test_property_rewrite_keeps_write_time";
+        do_test(path, |graph| {
+            let label = 1;
+            let mut type_def = tests::types::create_test_type_def(label);
+            type_def.set_ttl_ms(Some(tests::ttl::TTL_MS));
+            let mut helper = tests::helper::GraphTestHelper::new(&graph);
+            helper
+                .create_vertex_type(1, 1, label, type_def.clone())
+                .unwrap();
+            helper.insert_vertex(2, label, vec![1]).unwrap();
+            let clock = graph.storage.get_clock();
+            clock.advance(tests::ttl::TTL_MS / 2);
+            let prop = type_def.get_prop_defs().next().unwrap();
+            let mut builder = TypeDefBuilder::new();
+            builder.add_property(
+                prop.id,
+                prop.inner_id,
+                prop.name.clone(),
+                prop.r#type,
+                None,
+                false,
+                "".to_string(),
+            );
+            builder.set_label_id(label);
+            builder.version(1);
+            graph
+                .drop_vertex_type_properties(3, 2, label, &builder.build())
+                .unwrap();
+            graph.gc(3).unwrap();
+            graph.rewriter.wait().unwrap();
+            let table = graph
+                .vertex_manager
+                .get_type(3, label)
+                .unwrap()
+                .get_table(3)
+                .unwrap();
+            let versions: Vec<CodecVersion> = graph
+                .storage
+                .new_scan(&vertex_table_prefix_key(table.id))
+                .unwrap()
+                .map(|(_, v)| get_codec_version(v.to_slice()))
+                .collect();
+            assert_eq!(versions, vec![1]);
+            assert!(graph
+                .get_vertex(3, 1, Some(label), None)
+                .unwrap()
+                .is_some());
+            // the rewritten row expires at the time of the original write
+            clock.advance(tests::ttl::TTL_MS / 2);
+            assert!(graph
+                .get_vertex(3, 1, Some(label), None)
+                .unwrap()
+                .is_none());
+        });
+    }
+
+    #[test]
+    fn test_ttl_compaction() {
+        let path = "test_ttl_compaction";
+        do_test(path, |graph| {
+            let mut helper = tests::helper::GraphTestHelper::new(&graph);
+            let label = 1;
+            let mut type_def = tests::types::create_test_type_def(label);
+            type_def.set_ttl_ms(Some(tests::ttl::TTL_MS));
+            helper
+                .create_vertex_type(1, 1, label, type_def.clone())
+                .unwrap();
+            helper
+                .insert_vertex(2, label, vec![1, 2])
+                .unwrap();
+            let clock = graph.storage.get_clock();
+            clock.advance(tests::ttl::TTL_MS);
+            helper.insert_vertex(3, label, vec![3]).unwrap();
+            // records written after the ttl is dropped never expire, earlier ones keep their ttl
+            type_def.set_version(type_def.get_version() + 1);
+            type_def.set_ttl_ms(None);
+            graph
+                .vertex_manager
+                .get_type(3, label)
+                .unwrap()
+                .update_codec(4, Codec::from(&type_def))
+                .unwrap();
+            graph.sync_ttl_tables();
+            helper.insert_vertex(4, label, vec![4]).unwrap();
+            let table = graph
+                .vertex_manager
+                .get_type(4, label)
+                .unwrap()
+                .get_table(4)
+                .unwrap();
+            // expired records are replaced by tombstones, which are not counted
+            let count_records = || {
+                graph
+                    .storage
+                    .new_scan(&vertex_table_prefix_key(table.id))
+                    .unwrap()
+                    .filter(|(_, v)| v.to_slice().len() >= 4)
+                    .count()
+            };
+
+            // expired records of snapshots not garbage collected are kept
+            graph.compact().unwrap();
+            assert_eq!(count_records(), 4);
+            assert_eq!(graph.scan_changes(1, 4).unwrap().count(), 4);
+
+            graph.gc(2).unwrap();
+            graph.compact().unwrap();
+            assert_eq!(count_records(), 2);
+            helper.check_get_vertex(4, label, &vec![3, 4]);
+            assert!(graph
+                .get_vertex(4, 1, Some(label), None)
+                .unwrap()
+                .is_none());
+            assert_eq!(graph.scan_changes(2, 4).unwrap().count(), 2);
+
+            clock.advance(tests::ttl::TTL_MS);
+            helper.check_get_vertex(4, label, &vec![4]);
+            assert!(graph
+                .get_vertex(4, 3, Some(label), None)
+                .unwrap()
+                .is_none());
+            graph.gc(4).unwrap();
+            graph.compact().unwrap();
+            assert_eq!(count_records(), 1);
+        });
+    }
+
+    #[test]
+    fn test_ttl_compaction_hides_older_versions() {
+        let path = "test_ttl_compaction_hides_older_versions";
+        do_test(path, |graph| {
+            let mut helper = tests::helper::GraphTestHelper::new(&graph);
+            let label = 1;
+            let mut type_def = tests::types::create_test_type_def(label);
+            helper
+                .create_vertex_type(1, 1, label, type_def.clone())
+                .unwrap();
+            helper.insert_vertex(2, label, vec![1]).unwrap();
+            // the ttl is added after the vertex is written, then the vertex is overwritten
+            type_def.set_version(type_def.get_version() + 1);
+            type_def.set_ttl_ms(Some(tests::ttl::TTL_MS));
+            graph
+                .vertex_manager
+                .get_type(2, label)
+                .unwrap()
+                .update_codec(3, Codec::from(&type_def))
+                .unwrap();
+            graph.sync_ttl_tables();
+            helper.insert_vertex(3, label, vec![1]).unwrap();
+            helper.check_get_vertex(3, label, &vec![1]);
+
+            graph
+                .storage
+                .get_clock()
+                .advance(tests::ttl::TTL_MS);
+            graph.gc(3).unwrap();
+            graph.compact().unwrap();
+            // the version without a ttl written at si 2 stays hidden
+            for si in 3..5 {
+                assert!(graph
+                    .get_vertex(si, 1, Some(label), None)
+                    .unwrap()
+                    .is_none());
+                assert_eq!(
+                    graph
+                        .scan_vertex(si, Some(label), None, None)
+                        .unwrap()
+                        .count(),
+                    0
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn test_index_compaction() {
+        use crate::api::condition::{CmpOperator, Operand, PredCondition};
+        use crate::api::prelude::Property;
+
+        let path = "test_index_compaction";
+        do_test(path, |graph| {
+            let label = 1;
+            let mut type_def_builder = TypeDefBuilder::new();
+            type_def_builder.add_property(
+                1,
+                1,
+                "id".to_string(),
+                ValueType::Long,
+                None,
+                true,
+                "".to_string(),
+            );
+            type_def_builder.add_property(
+                2,
+                2,
+                "age".to_string(),
+                ValueType::Long,
+                None,
+                false,
+                "".to_string(),
+            );
+            type_def_builder.set_label_id(label);
+            graph
+                .create_vertex_type(1, 1, label, &type_def_builder.build(), 1)
+                .unwrap();
+            graph
+                .create_vertex_index(2, 2, label, 2, 2)
+                .unwrap();
+            let put = |si: SnapshotId, id: VertexId, age: i64| {
+                let mut props = HashMap::new();
+                props.insert(1, Value::long(id));
+                props.insert(2, Value::long(age));
+                graph
+                    .insert_overwrite_vertex(si, id, label, &props)
+                    .unwrap();
+            };
+            put(3, 1, 10);
+            put(3, 2, 10);
+            put(4, 1, 20);
+            graph.delete_vertex(4, 2, label).unwrap();
+            put(5, 1, 10);
+            put(6, 1, 20);
+            let count_entries = || {
+                graph
+                    .storage
+                    .new_scan(&vertex_table_prefix_key(2))
+                    .unwrap()
+                    .count()
+            };
+            let scan_by_age = |si: SnapshotId, age: i64| -> Vec<VertexId> {
+                let condition = Condition::new(PredCondition::new_predicate(
+                    Operand::PropId(2),
+                    CmpOperator::Equal,
+                    Operand::Const(Property::Long(age)),
+                ));
+                assert!(uses_index(&graph, si, label, &condition));
+                graph
+                    .scan_vertex(si, Some(label), Some(&condition), None)
+                    .unwrap()
+                    .map(|v| v.unwrap().get_vertex_id())
+                    .collect()
+            };
+            assert_eq!(count_entries(), 9);
+
+            // entries of a pair older than its newest one not after the gc watermark are dropped
+            graph.gc(4).unwrap();
+            graph.compact().unwrap();
+            assert_eq!(count_entries(), 7);
+            assert_eq!(scan_by_age(4, 10), Vec::<VertexId>::new());
+            assert_eq!(scan_by_age(4, 20), vec![1]);
+            assert_eq!(scan_by_age(5, 10), vec![1]);
+            assert_eq!(scan_by_age(5, 20), Vec::<VertexId>::new());
+
+            // the newest dead entries are kept to hide older ones out of the compaction
+            graph.gc(6).unwrap();
+            graph.compact().unwrap();
+            assert_eq!(count_entries(), 3);
+            for si in 6..8 {
+                assert_eq!(scan_by_age(si, 10), Vec::<VertexId>::new());
+                assert_eq!(scan_by_age(si, 20), vec![1]);
+            }
+        });
+    }
+
+    #[test]
+    fn test_gc_snapshot_persisted() {
+        let path = "store_test/test_gc_snapshot_persisted";
+        fs::rmr(&path).unwrap();
+        {
+            let graph = create_empty_graph(&path);
+            let mut helper = tests::helper::GraphTestHelper::new(&graph);
+            let type_def = tests::types::create_test_type_def(1);
+            helper
+                .create_vertex_type(1, 1, 1, type_def)
+                .unwrap();
+            helper.insert_vertex(2, 1, vec![1, 2]).unwrap();
+            graph.gc(2).unwrap();
+            // the watermark never goes back
+            graph.gc(1).unwrap();
+            assert_eq!(graph.get_gc_snapshot_id(), 2);
+        }
+        let graph = create_empty_graph(&path);
+        assert_eq!(graph.get_gc_snapshot_id(), 2);
+        assert!(graph.scan_changes(1, 2).is_err());
+        assert!(graph.scan_changes(2, 2).is_ok());
+        drop(graph);
+        fs::rmr(&path).unwrap();
+    }
+
+    #[test]
+    fn test_changes_blob_pages() {
+        use crate::db::common::bytes::util::parse_pb;
+        use crate::db::proto::model::{ChangeEventBatchPb, ChangeEventPb};
+
+        let path = "test_changes_blob_pages";
+        do_test(path, |graph| {
+            let mut helper = tests::helper::GraphTestHelper::new(&graph);
+            let type_def = tests::types::create_test_type_def(1);
+            helper
+                .create_vertex_type(1, 1, 1, type_def)
+                .unwrap();
+            helper
+                .insert_vertex(2, 1, vec![1, 2, 3, 4, 5])
+                .unwrap();
+            let page = |offset: usize, limit: usize| {
+                let blob = graph
+                    .get_changes_blob(1, 2, offset, limit)
+                    .unwrap();
+                parse_pb::<ChangeEventBatchPb>(&blob)
+                    .unwrap()
+                    .take_events()
+                    .into_vec()
+            };
+            let mut events: Vec<ChangeEventPb> = Vec::new();
+            loop {
+                let mut events_of_page = page(events.len(), 2);
+                let is_last = events_of_page.len() < 2;
+                events.append(&mut events_of_page);
+                if is_last {
+                    break;
+                }
+            }
+            assert_eq!(events.len(), 5);
+            assert_eq!(events, page(0, usize::MAX));
+        });
+    }
+
+    #[test]
+    fn test_bulk_load() {
+        use std::path::PathBuf;
+
+        use crate::api::condition::{CmpOperator, Operand, PredCondition};
+        use crate::api::prelude::Property;
+        use crate::db::api::types::{Property as _, PropertyReader, PropertyValue};
+        use crate::db::bulk_load::{build_ssts, LoadConfig};
+        use crate::db::graph::{get_edge_inner_id_by_seq, get_vertex_id_by_primary_keys};
+
+        let test_dir = "store_test/test_bulk_load";
+        fs::rmr(&test_dir).unwrap();
+        let mut builder = GraphConfigBuilder::new();
+        builder.set_storage_engine("rocksdb");
+        builder.add_storage_option("store.data.path", &format!("{}/store", test_dir));
+        builder.add_storage_option("store.data.download.path", &format!("{}/download", test_dir));
+        let graph = GraphStore::open(&builder.build()).unwrap();
+
+        let mut type_def_builder = TypeDefBuilder::new();
+        type_def_builder.add_property(1, 1, "id".to_string(), ValueType::Long, None, true, "".to_string());
+        type_def_builder.add_property(
+            2,
+            2,
+            "name".to_string(),
+            ValueType::String,
+            None,
+            false,
+            "".to_string(),
+        );
+        type_def_builder.set_label_id(1);
+        graph
+            .create_vertex_type(1, 1, 1, &type_def_builder.build(), 1)
+            .unwrap();
+        let mut type_def_builder = TypeDefBuilder::new();
+        type_def_builder.add_property(
+            3,
+            3,
+            "weight".to_string(),
+            ValueType::Double,
+            None,
+            false,
+            "".to_string(),
+        );
+        type_def_builder.set_label_id(2);
+        graph
+            .create_edge_type(2, 2, 2, &type_def_builder.build())
+            .unwrap();
+        let edge_kind = EdgeKind::new(2, 1, 1);
+        graph
+            .add_edge_kind(3, 3, &edge_kind, 2)
+            .unwrap();
+        graph
+            .create_vertex_index(3, 4, 1, 1, 5)
+            .unwrap();
+        graph
+            .create_edge_sort_key(3, 5, 2, 3, true, 6)
+            .unwrap();
+        let vertex_id = |id: i64| get_vertex_id_by_primary_keys(1, vec![Value::long(id).into_vec()].iter());
+        // the second loaded edge overwrites this one, its sort entry must not survive the load
+        let loaded_id = EdgeId::new(
+            vertex_id(1),
+            vertex_id(3),
+            get_edge_inner_id_by_seq(vertex_id(1), vertex_id(3), 2, 2),
+        );
+        let mut props = HashMap::new();
+        props.insert(3, Value::double(2.0));
+        for forward in [true, false].iter() {
+            graph
+                .insert_overwrite_edge(3, loaded_id, &edge_kind, *forward, &props)
+                .unwrap();
+        }
+        let sorted_out = |si: SnapshotId| -> Vec<VertexId> {
+            graph
+                .get_sorted_out_edges(si, vertex_id(1), 2, None, None, 0)
+                .unwrap()
+                .map(|e| RocksEdge::get_edge_id(&e.unwrap()).dst_id)
+                .collect()
+        };
+        assert_eq!(sorted_out(3), vec![vertex_id(3)]);
+        let vertex_target = DataLoadTarget::new(1, 0, 0);
+        let edge_target = DataLoadTarget::new(2, 1, 1);
+        assert!(graph
+            .prepare_data_load(4, 6, &vertex_target, 3)
+            .unwrap());
+        assert!(graph
+            .prepare_data_load(4, 7, &edge_target, 4)
+            .unwrap());
+
+        // vertices are partitioned by vertex id, take ids until both partitions have vertices
+        let partition_of = |id: i64| vertex_id(id).rem_euclid(2);
+        let mut person_ids = vec![1, 2, 3];
+        let covers_both = |ids: &Vec<i64>| (0..2).all(|p| ids.iter().any(|id| partition_of(*id) == p));
+        while !covers_both(&person_ids) {
+            person_ids.push(person_ids.len() as i64 + 1);
+        }
+        let name_of = |id: i64| format!("person{}", id);
+        let mut person_csv = "id|name\n".to_string();
+        for id in &person_ids {
+            person_csv.push_str(&format!("{}|{}\n", id, name_of(*id)));
+        }
+        let input_dir = format!("{}/input", test_dir);
+        std::fs::create_dir_all(&input_dir).unwrap();
+        std::fs::write(format!("{}/person.csv", input_dir), person_csv).unwrap();
+        std::fs::write(format!("{}/knows.csv", input_dir), "src|dst|weight\n1|2|0.5\n1|3|1.0\n").unwrap();
+        let config = json!({
+            "partitions": 2,
+            "vertices": [{
+                "label_id": 1, "table_id": 3, "path": format!("{}/person.csv", input_dir), "delimiter": "|",
+                "columns": [{"column": "id", "property_id": 1}, {"column": "name", "property_id": 2}]
+            }],
+            "edges": [{
+                "label_id": 2, "src_label_id": 1, "dst_label_id": 1, "table_id": 4,
+                "path": format!("{}/knows.csv", input_dir), "delimiter": "|",
+                "src_columns": ["src"], "dst_columns": ["dst"],
+                "columns": [{"column": "weight", "property_id": 3}]
+            }]
+        });
+        let config: LoadConfig = serde_json::from_value(config).unwrap();
+        let output_dir = format!("{}/download/unique", test_dir);
+        let files = build_ssts(&graph.get_graph_def().unwrap(), &config, Path::new(&output_dir)).unwrap();
+        let part_path = |partition: i32| format!("{}/part-r-{:0>5}.sst", output_dir, partition);
+        let expected: Vec<PathBuf> = (0..2)
+            .map(|p| PathBuf::from(part_path(p)))
+            .collect();
+        assert_eq!(files, expected);
+
+        // this store holds both partitions, partition 1 is ingested directly and partition 0 by
+        // the commit
+        graph.ingest(&part_path(1)).unwrap();
+        assert!(graph
+            .commit_data_load(5, 8, &vertex_target, 3, 0, "unique")
+            .unwrap());
+        assert!(graph
+            .commit_data_load(5, 9, &edge_target, 4, 0, "unique")
+            .unwrap());
+
+        // vertices of both partitions are loaded
+        for id in &person_ids {
+            let v = graph
+                .get_vertex(5, vertex_id(*id), Some(1), None)
+                .unwrap()
+                .unwrap();
+            let val = PropertyReader::get_property(&v, 2).unwrap();
+            assert_eq!(*val.get_property_value(), PropertyValue::String(name_of(*id)));
+        }
+        assert_eq!(
+            graph
+                .scan_vertex(5, Some(1), None, None)
+                .unwrap()
+                .count(),
+            person_ids.len()
+        );
+        assert!(graph
+            .get_vertex(4, vertex_id(1), Some(1), None)
+            .unwrap()
+            .is_none());
+        // the loaded vertices are indexed by the commit
+        let condition = Condition::new(PredCondition::new_predicate(
+            Operand::PropId(1),
+            CmpOperator::Equal,
+            Operand::Const(Property::Long(2)),
+        ));
+        assert!(uses_index(&graph, 5, 1, &condition));
+        let ids: Vec<VertexId> = graph
+            .scan_vertex(5, Some(1), Some(&condition), None)
+            .unwrap()
+            .map(|v| v.unwrap().get_vertex_id())
+            .collect();
+        assert_eq!(ids, vec![vertex_id(2)]);
+        let mut weights: Vec<f64> = graph
+            .get_out_edges(5, vertex_id(1), Some(2), None, None)
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                let dst_id = RocksEdge::get_edge_id(&e).dst_id;
+                assert!(dst_id == vertex_id(2) || dst_id == vertex_id(3));
+                match PropertyReader::get_property(&e, 3)
+                    .unwrap()
+                    .get_property_value()
+                {
+                    PropertyValue::Double(w) => *w,
+                    _ => unreachable!(),
+                }
+            })
+            .collect();
+        weights.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(weights, vec![0.5, 1.0]);
+        assert_eq!(
+            graph
+                .get_in_edges(5, vertex_id(3), Some(2), None, None)
+                .unwrap()
+                .count(),
+            1
+        );
+        // the loaded edges are sorted by weight, the replaced edge is gone
+        assert_eq!(sorted_out(5), vec![vertex_id(3), vertex_id(2)]);
+        let weight = graph
+            .get_sorted_out_edges(5, vertex_id(1), 2, None, None, 1)
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                assert_eq!(*RocksEdge::get_edge_id(&e), loaded_id);
+                PropertyReader::get_property(&e, 3)
+                    .unwrap()
+                    .get_property_value()
+                    .clone()
+            })
+            .next();
+        assert_eq!(weight, Some(PropertyValue::Double(1.0)));
+        assert_eq!(sorted_out(4), vec![vertex_id(3)]);
+        fs::rmr(&test_dir).unwrap();
+    }
+
     #[test]
     fn test_backup_engine() {
         let test_dir = "store_test/test_backup_engine";
@@ -1256,6 +2731,17 @@ mod tests {
         fs::rmr(&test_dir).unwrap();
     }
 
+    fn uses_index(graph: &GraphStore, si: SnapshotId, label: LabelId, condition: &Condition) -> bool {
+        let info = graph
+            .vertex_manager
+            .get_type_info(si, label)
+            .unwrap();
+        graph
+            .scan_vertex_by_index(si, &info, condition)
+            .unwrap()
+            .is_some()
+    }
+
     fn do_test<F: Fn(GraphStore)>(path: &str, func: F) {
         let path = format!("store_test/{}", path);
         fs::rmr(&path).unwrap();
@@ -0,0 +1,107 @@
+use super::data;
+use super::helper::GraphTestHelper;
+use super::types;
+use crate::db::api::change::{ChangeElement, ChangeEvent, ChangeType};
+use crate::db::api::multi_version_graph::MultiVersionGraph;
+use crate::db::api::*;
+
+pub fn test_scan_changes<G: MultiVersionGraph>(graph: G) {
+    let mut helper = GraphTestHelper::new(&graph);
+    let label = 1;
+    let type_def = types::create_full_type_def(label);
+    helper
+        .create_vertex_type(10, 1, label, type_def.clone())
+        .unwrap();
+    let edge_label = 2;
+    helper
+        .create_edge_type(11, 2, edge_label, types::create_full_type_def(edge_label))
+        .unwrap();
+    let edge_kind = types::create_edge_kind(12, edge_label);
+    helper.add_edge_kind(12, 3, &edge_kind).unwrap();
+    helper
+        .insert_vertex(13, label, vec![1, 2, 3])
+        .unwrap();
+    helper
+        .update_vertex(14, label, vec![1])
+        .unwrap();
+    helper
+        .delete_vertex(15, label, vec![2])
+        .unwrap();
+    let edge_id = EdgeId::new(1, 3, 1);
+    helper
+        .insert_edge(16, &edge_kind, vec![edge_id].into_iter())
+        .unwrap();
+    helper
+        .delete_edge(17, &edge_kind, vec![edge_id])
+        .unwrap();
+    // deleting a deleted vertex changes nothing
+    helper
+        .delete_vertex(18, label, vec![2])
+        .unwrap();
+
+    let vertex = |id| ChangeElement::Vertex(id, label);
+    let edge = ChangeElement::Edge(edge_id, edge_kind.clone());
+    let changes = scan_changes(&graph, 12, 18);
+    let expected = vec![
+        (13, ChangeType::Insert, vertex(1)),
+        (13, ChangeType::Insert, vertex(2)),
+        (13, ChangeType::Insert, vertex(3)),
+        (14, ChangeType::Update, vertex(1)),
+        (15, ChangeType::Delete, vertex(2)),
+        (16, ChangeType::Insert, edge.clone()),
+        (17, ChangeType::Delete, edge.clone()),
+    ];
+    assert_eq!(summarize(&changes), expected);
+    for event in &changes {
+        if event.si == 13 {
+            if let ChangeElement::Vertex(id, _) = event.element {
+                let properties = data::gen_vertex_properties(13, label, id, &type_def);
+                assert_eq!(event.properties, properties);
+            }
+        }
+    }
+    // a delete carries the properties before it
+    let delete = changes.iter().find(|e| e.si == 15).unwrap();
+    assert_eq!(delete.properties, data::gen_vertex_properties(13, label, 2, &type_def));
+
+    let changes = scan_changes(&graph, 13, 15);
+    let expected = vec![(14, ChangeType::Update, vertex(1)), (15, ChangeType::Delete, vertex(2))];
+    assert_eq!(summarize(&changes), expected);
+    assert!(scan_changes(&graph, 14, 14).is_empty());
+    assert!(graph.scan_changes(16, 15).is_err());
+
+    graph.gc(14).unwrap();
+    assert!(graph.scan_changes(13, 18).is_err());
+    let changes = scan_changes(&graph, 14, 18);
+    let expected = vec![
+        (15, ChangeType::Delete, vertex(2)),
+        (16, ChangeType::Insert, edge.clone()),
+        (17, ChangeType::Delete, edge),
+    ];
+    assert_eq!(summarize(&changes), expected);
+}
+
+fn scan_changes<G: MultiVersionGraph>(
+    graph: &G, from_si: SnapshotId, to_si: SnapshotId,
+) -> Vec<ChangeEvent> {
+    graph
+        .scan_changes(from_si, to_si)
+        .unwrap()
+        .collect::<GraphResult<Vec<ChangeEvent>>>()
+        .unwrap()
+}
+
+fn summarize(changes: &Vec<ChangeEvent>) -> Vec<(SnapshotId, ChangeType, ChangeElement)> {
+    let mut ret: Vec<(SnapshotId, ChangeType, ChangeElement)> = changes
+        .iter()
+        .map(|e| (e.si, e.change_type, e.element.clone()))
+        .collect();
+    ret.sort_by_key(|(si, _, element)| {
+        let id = match element {
+            ChangeElement::Vertex(id, _) => *id,
+            ChangeElement::Edge(id, _) => id.inner_id,
+        };
+        (*si, id)
+    });
+    ret
+}
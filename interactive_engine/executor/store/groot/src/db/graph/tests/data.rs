@@ -138,6 +138,23 @@ fn vertex_prop(si: SnapshotId, label: LabelId, id: VertexId, r#type: ValueType)
             let v = vec![format!("{}", x), format!("{}", y), format!("{}_{}", x, y), format!("{}", s)];
             Value::string_list(&v)
         }
+        ValueType::Date => {
+            let v = (x * 7 + y * 3 + s) % 36500;
+            Value::date(v as i32)
+        }
+        ValueType::DateTime => {
+            let v = 1600000000000 + x * 3600000 + y * 1000 + s;
+            Value::datetime(v)
+        }
+        ValueType::DateTimeWithTz => {
+            let v = 1600000000000 + x * 3600000 + y * 1000 + s;
+            let offset = ((x + y) % 24 - 12) * 3600;
+            Value::datetime_with_tz(v, offset as i32)
+        }
+        ValueType::Decimal => {
+            let v = (x * 1000003 + y * 101 + s) as i128 * 1000000007;
+            Value::decimal(v, ((x + y) % 20) as u8)
+        }
     }
 }
 
@@ -213,5 +230,22 @@ fn edge_prop(si: SnapshotId, edge_type: &EdgeKind, id: &EdgeId, r#type: ValueTyp
             let v = vec![format!("{}", x), format!("{}", y), format!("{}_{}", x, y), format!("{}", s)];
             Value::string_list(&v)
         }
+        ValueType::Date => {
+            let v = (x * 7 + y * 3 + s) % 36500;
+            Value::date(v as i32)
+        }
+        ValueType::DateTime => {
+            let v = 1600000000000 + x * 3600000 + y * 1000 + s;
+            Value::datetime(v)
+        }
+        ValueType::DateTimeWithTz => {
+            let v = 1600000000000 + x * 3600000 + y * 1000 + s;
+            let offset = ((x + y) % 24 - 12) * 3600;
+            Value::datetime_with_tz(v, offset as i32)
+        }
+        ValueType::Decimal => {
+            let v = (x * 1000003 + y * 101 + s) as i128 * 1000000007;
+            Value::decimal(v, ((x + y) % 20) as u8)
+        }
     }
 }
@@ -1,7 +1,11 @@
 pub mod backup;
+pub mod change;
 pub mod data;
 pub mod edge;
 pub mod graph;
 pub mod helper;
+pub mod property;
+pub mod sort_key;
+pub mod ttl;
 pub mod types;
 pub mod vertex;
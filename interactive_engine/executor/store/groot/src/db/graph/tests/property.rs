@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use crate::db::api::multi_version_graph::MultiVersionGraph;
+use crate::db::api::types::{Property, PropertyReader, PropertyValue};
+use crate::db::api::*;
+use crate::db::common::bytes::util::parse_pb;
+use crate::db::proto::model::GraphDefPb;
+
+pub const VERTEX_LABEL: LabelId = 1;
+pub const EDGE_LABEL: LabelId = 2;
+
+pub fn create_type_def(label: LabelId) -> TypeDef {
+    let mut builder = TypeDefBuilder::new();
+    builder.add_property(1, 1, "id".to_string(), ValueType::Long, None, true, "".to_string());
+    builder.add_property(2, 2, "name".to_string(), ValueType::String, None, false, "".to_string());
+    builder.add_property(3, 3, "age".to_string(), ValueType::Int, None, false, "".to_string());
+    builder.set_label_id(label);
+    builder.build()
+}
+
+fn props_type_def(label: LabelId, version: i32, prop_ids: &[PropertyId]) -> TypeDef {
+    let full = create_type_def(label);
+    let mut builder = TypeDefBuilder::new();
+    for prop_id in prop_ids {
+        let p = full.get_prop_def(*prop_id).unwrap();
+        builder.add_property(p.id, p.inner_id, p.name.clone(), p.r#type, None, p.pk, "".to_string());
+    }
+    builder.set_label_id(label);
+    builder.version(version);
+    builder.build()
+}
+
+fn properties(id: i64) -> HashMap<PropertyId, Value> {
+    let mut map = HashMap::new();
+    map.insert(1, Value::long(id));
+    map.insert(2, Value::string(&format!("name-{}", id)));
+    map.insert(3, Value::int(id as i32 * 10));
+    map
+}
+
+/// Creates a vertex type and an edge type with 3 properties, writes some rows at si 3 and drops
+/// property 2 of both types at si 4.
+pub fn prepare_dropped_property<G: MultiVersionGraph>(graph: &G, vertex_table_id: i64, edge_table_id: i64) {
+    let type_def = create_type_def(VERTEX_LABEL);
+    graph
+        .create_vertex_type(1, 1, VERTEX_LABEL, &type_def, vertex_table_id)
+        .unwrap();
+    graph
+        .create_edge_type(1, 2, EDGE_LABEL, &create_type_def(EDGE_LABEL))
+        .unwrap();
+    let edge_kind = EdgeKind::new(EDGE_LABEL, VERTEX_LABEL, VERTEX_LABEL);
+    graph
+        .add_edge_kind(2, 3, &edge_kind, edge_table_id)
+        .unwrap();
+    for id in 1..=3 {
+        graph
+            .insert_overwrite_vertex(3, id, VERTEX_LABEL, &properties(id))
+            .unwrap();
+        let edge_id = EdgeId::new(id, id + 1, id);
+        graph
+            .insert_overwrite_edge(3, edge_id, &edge_kind, true, &properties(id))
+            .unwrap();
+    }
+    graph
+        .drop_vertex_type_properties(4, 4, VERTEX_LABEL, &props_type_def(VERTEX_LABEL, 1, &[2]))
+        .unwrap();
+    graph
+        .drop_edge_type_properties(4, 5, EDGE_LABEL, &props_type_def(EDGE_LABEL, 1, &[2]))
+        .unwrap();
+}
+
+pub fn test_drop_property<G: MultiVersionGraph>(graph: G) {
+    prepare_dropped_property(&graph, 10, 11);
+    let edge_kind = EdgeKind::new(EDGE_LABEL, VERTEX_LABEL, VERTEX_LABEL);
+    for id in 1..=3 {
+        // snapshots before the drop still see the property
+        check_vertex(&graph, 3, id, &[1, 2, 3]);
+        check_vertex(&graph, 4, id, &[1, 3]);
+        check_edge(&graph, 3, EdgeId::new(id, id + 1, id), &edge_kind, &[1, 2, 3]);
+        check_edge(&graph, 4, EdgeId::new(id, id + 1, id), &edge_kind, &[1, 3]);
+    }
+    // new rows are encoded without the dropped property
+    graph
+        .insert_overwrite_vertex(5, 4, VERTEX_LABEL, &properties(4))
+        .unwrap();
+    check_vertex(&graph, 5, 4, &[1, 3]);
+
+    // primary keys and unknown properties cannot be dropped
+    assert!(graph
+        .drop_vertex_type_properties(6, 6, VERTEX_LABEL, &props_type_def(VERTEX_LABEL, 2, &[1]))
+        .is_err());
+    assert!(graph
+        .drop_vertex_type_properties(6, 6, VERTEX_LABEL, &props_type_def(VERTEX_LABEL, 2, &[2]))
+        .is_err());
+
+    // rows are rewritten once the snapshots before the drop are collected
+    graph.gc(5).unwrap();
+    for id in 1..=3 {
+        check_vertex(&graph, 5, id, &[1, 3]);
+        check_edge(&graph, 5, EdgeId::new(id, id + 1, id), &edge_kind, &[1, 3]);
+    }
+}
+
+pub fn test_rename_property<G: MultiVersionGraph>(graph: G) {
+    let type_def = create_type_def(VERTEX_LABEL);
+    graph
+        .create_vertex_type(1, 1, VERTEX_LABEL, &type_def, 10)
+        .unwrap();
+    graph
+        .create_edge_type(1, 2, EDGE_LABEL, &create_type_def(EDGE_LABEL))
+        .unwrap();
+    graph
+        .insert_overwrite_vertex(2, 1, VERTEX_LABEL, &properties(1))
+        .unwrap();
+    graph
+        .rename_vertex_type_property(3, 3, VERTEX_LABEL, 2, "full_name")
+        .unwrap();
+    graph
+        .rename_edge_type_property(3, 4, EDGE_LABEL, 3, "weight")
+        .unwrap();
+    let graph_def = get_graph_def(&graph);
+    let vertex_type = graph_def.get_type(&VERTEX_LABEL).unwrap();
+    assert_eq!(vertex_type.get_prop_def(2).unwrap().name, "full_name");
+    let edge_type = graph_def.get_type(&EDGE_LABEL).unwrap();
+    assert_eq!(edge_type.get_prop_def(3).unwrap().name, "weight");
+    assert!(!graph_def
+        .property_name_to_id
+        .contains_key("name"));
+    assert_eq!(graph_def.property_name_to_id.get("age"), Some(&3));
+    // rows are not touched
+    check_vertex(&graph, 3, 1, &[1, 2, 3]);
+
+    // names are unique in a type
+    assert!(graph
+        .rename_vertex_type_property(4, 5, VERTEX_LABEL, 3, "full_name")
+        .is_err());
+    assert!(graph
+        .rename_vertex_type_property(4, 5, VERTEX_LABEL, 4, "other")
+        .is_err());
+    assert!(graph
+        .rename_edge_type_property(4, 5, VERTEX_LABEL, 3, "other")
+        .is_err());
+}
+
+fn get_graph_def<G: MultiVersionGraph>(graph: &G) -> GraphDef {
+    let bytes = graph.get_graph_def_blob().unwrap();
+    let pb = parse_pb::<GraphDefPb>(&bytes).unwrap();
+    GraphDef::from_proto(&pb).unwrap()
+}
+
+fn check_vertex<G: MultiVersionGraph>(graph: &G, si: SnapshotId, id: VertexId, prop_ids: &[PropertyId]) {
+    let v = graph
+        .get_vertex(si, id, Some(VERTEX_LABEL), None)
+        .unwrap()
+        .unwrap();
+    let expected = properties(id);
+    let mut iter = v.get_property_iterator();
+    let mut found = Vec::new();
+    while let Some(p) = iter.next() {
+        let p = p.unwrap();
+        let ans = expected.get(&p.get_property_id()).unwrap();
+        assert_eq!(*p.get_property_value(), PropertyValue::from(ans.as_ref()));
+        found.push(p.get_property_id());
+    }
+    found.sort();
+    assert_eq!(found, prop_ids.to_vec());
+}
+
+fn check_edge<G: MultiVersionGraph>(
+    graph: &G, si: SnapshotId, id: EdgeId, edge_kind: &EdgeKind, prop_ids: &[PropertyId],
+) {
+    let e = graph
+        .get_edge(si, id, Some(edge_kind), None)
+        .unwrap()
+        .unwrap();
+    let expected = properties(id.src_id);
+    let mut iter = e.get_property_iterator();
+    let mut found = Vec::new();
+    while let Some(p) = iter.next() {
+        let p = p.unwrap();
+        let ans = expected.get(&p.get_property_id()).unwrap();
+        assert_eq!(*p.get_property_value(), PropertyValue::from(ans.as_ref()));
+        found.push(p.get_property_id());
+    }
+    found.sort();
+    assert_eq!(found, prop_ids.to_vec());
+}
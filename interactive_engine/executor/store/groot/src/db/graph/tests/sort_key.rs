@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use super::property::create_type_def;
+use crate::api::condition::{CmpOperator, Operand, PredCondition};
+use crate::api::prelude::Property;
+use crate::api::Condition;
+use crate::db::api::multi_version_graph::MultiVersionGraph;
+use crate::db::api::types::RocksEdge;
+use crate::db::api::*;
+
+const VERTEX_LABEL: LabelId = 1;
+const EDGE_LABEL: LabelId = 2;
+const SORT_PROP: PropertyId = 3;
+
+fn properties(id: i64, age: i32) -> HashMap<PropertyId, Value> {
+    let mut map = HashMap::new();
+    map.insert(1, Value::long(id));
+    map.insert(2, Value::string(&format!("name-{}", id)));
+    map.insert(SORT_PROP, Value::int(age));
+    map
+}
+
+fn insert_edge<G: MultiVersionGraph>(graph: &G, si: SnapshotId, id: EdgeId, age: i32) {
+    let edge_kind = EdgeKind::new(EDGE_LABEL, VERTEX_LABEL, VERTEX_LABEL);
+    for forward in [true, false].iter() {
+        graph
+            .insert_overwrite_edge(si, id, &edge_kind, *forward, &properties(id.inner_id, age))
+            .unwrap();
+    }
+}
+
+fn sorted_out<G: MultiVersionGraph>(
+    graph: &G, si: SnapshotId, src_id: VertexId, condition: Option<&Condition>, limit: usize,
+) -> Vec<VertexId> {
+    graph
+        .get_sorted_out_edges(si, src_id, EDGE_LABEL, condition, None, limit)
+        .unwrap()
+        .map(|e| e.unwrap().get_edge_id().dst_id)
+        .collect()
+}
+
+fn sorted_in<G: MultiVersionGraph>(
+    graph: &G, si: SnapshotId, dst_id: VertexId, limit: usize,
+) -> Vec<VertexId> {
+    graph
+        .get_sorted_in_edges(si, dst_id, EDGE_LABEL, None, None, limit)
+        .unwrap()
+        .map(|e| e.unwrap().get_edge_id().src_id)
+        .collect()
+}
+
+pub fn test_edge_sort_key<G: MultiVersionGraph>(graph: G) {
+    graph
+        .create_vertex_type(1, 1, VERTEX_LABEL, &create_type_def(VERTEX_LABEL), 10)
+        .unwrap();
+    graph
+        .create_edge_type(1, 2, EDGE_LABEL, &create_type_def(EDGE_LABEL))
+        .unwrap();
+    let edge_kind = EdgeKind::new(EDGE_LABEL, VERTEX_LABEL, VERTEX_LABEL);
+    graph
+        .add_edge_kind(2, 3, &edge_kind, 11)
+        .unwrap();
+    // out edges of vertex 1 and in edges of vertex 100 existing before the sort key
+    let ages = [(10, 30), (11, 10), (12, 50), (13, 20), (14, 40)];
+    for (dst_id, age) in ages.iter() {
+        insert_edge(&graph, 3, EdgeId::new(1, *dst_id, *dst_id), *age);
+        insert_edge(&graph, 3, EdgeId::new(*dst_id, 100, *dst_id), *age);
+    }
+    assert!(graph
+        .create_edge_sort_key(4, 4, EDGE_LABEL, 4, true, 20)
+        .is_err());
+    assert!(graph
+        .get_sorted_out_edges(4, 1, EDGE_LABEL, None, None, 0)
+        .is_err());
+    graph
+        .create_edge_sort_key(4, 4, EDGE_LABEL, SORT_PROP, true, 20)
+        .unwrap();
+    assert!(graph
+        .create_edge_sort_key(4, 5, EDGE_LABEL, SORT_PROP, false, 21)
+        .is_err());
+    assert_eq!(graph.get_edge_sort_key(3, EDGE_LABEL).unwrap(), None);
+    assert_eq!(graph.get_edge_sort_key(4, EDGE_LABEL).unwrap(), Some((SORT_PROP, true)));
+
+    assert_eq!(sorted_out(&graph, 4, 1, None, 3), vec![12, 14, 10]);
+    assert_eq!(sorted_out(&graph, 4, 1, None, 0), vec![12, 14, 10, 13, 11]);
+    assert_eq!(sorted_in(&graph, 4, 100, 2), vec![12, 14]);
+
+    // updates and deletes move edges in the order from their snapshot on
+    insert_edge(&graph, 5, EdgeId::new(1, 11, 11), 60);
+    graph
+        .delete_edge(6, EdgeId::new(1, 12, 12), &edge_kind, true)
+        .unwrap();
+    insert_edge(&graph, 6, EdgeId::new(1, 15, 15), 35);
+    assert_eq!(sorted_out(&graph, 4, 1, None, 2), vec![12, 14]);
+    assert_eq!(sorted_out(&graph, 5, 1, None, 2), vec![11, 12]);
+    assert_eq!(sorted_out(&graph, 6, 1, None, 3), vec![11, 14, 15]);
+
+    // the condition is applied before the limit
+    let condition = Condition::new(PredCondition::new_predicate(
+        Operand::PropId(SORT_PROP as u32),
+        CmpOperator::LessThan,
+        Operand::Const(Property::Int(36)),
+    ));
+    assert_eq!(sorted_out(&graph, 6, 1, Some(&condition), 2), vec![15, 10]);
+
+    // the sort key property cannot be dropped
+    let mut builder = TypeDefBuilder::new();
+    builder.add_property(
+        SORT_PROP,
+        SORT_PROP,
+        "age".to_string(),
+        ValueType::Int,
+        None,
+        false,
+        "".to_string(),
+    );
+    builder.set_label_id(EDGE_LABEL);
+    builder.version(1);
+    assert!(graph
+        .drop_edge_type_properties(7, 5, EDGE_LABEL, &builder.build())
+        .is_err());
+
+    graph
+        .drop_edge_sort_key(7, 5, EDGE_LABEL)
+        .unwrap();
+    assert_eq!(graph.get_edge_sort_key(7, EDGE_LABEL).unwrap(), None);
+    assert!(graph
+        .get_sorted_out_edges(7, 1, EDGE_LABEL, None, None, 0)
+        .is_err());
+    assert!(graph
+        .drop_edge_sort_key(8, 6, EDGE_LABEL)
+        .is_err());
+    graph.gc(8).unwrap();
+    // plain adjacency is not affected
+    let count = graph
+        .get_out_edges(8, 1, Some(EDGE_LABEL), None, None)
+        .unwrap()
+        .count();
+    assert_eq!(count, 5);
+}
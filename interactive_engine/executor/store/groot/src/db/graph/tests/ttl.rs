@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+
+use super::helper::GraphTestHelper;
+use super::types;
+use crate::db::api::multi_version_graph::MultiVersionGraph;
+use crate::db::api::types::RocksVertex;
+use crate::db::api::*;
+
+pub const TTL_MS: i64 = 60_000;
+
+/// `advance` moves the clock of `graph` forward by the given milliseconds.
+pub fn test_ttl<G: MultiVersionGraph, F: Fn(&G, i64)>(graph: G, advance: F) {
+    let mut helper = GraphTestHelper::new(&graph);
+    let ttl_label = 1;
+    let mut type_def = types::create_test_type_def(ttl_label);
+    type_def.set_ttl_ms(Some(TTL_MS));
+    helper
+        .create_vertex_type(10, 1, ttl_label, type_def)
+        .unwrap();
+    let label = 2;
+    helper
+        .create_vertex_type(11, 2, label, types::create_test_type_def(label))
+        .unwrap();
+    let edge_label = 3;
+    let mut type_def = types::create_test_type_def(edge_label);
+    type_def.set_ttl_ms(Some(TTL_MS));
+    helper
+        .create_edge_type(12, 3, edge_label, type_def)
+        .unwrap();
+    let edge_kind = types::create_edge_kind(13, edge_label);
+    helper.add_edge_kind(13, 4, &edge_kind).unwrap();
+
+    let ids = vec![1, 2, 3];
+    helper
+        .insert_vertex(14, ttl_label, ids.clone())
+        .unwrap();
+    let other_ids = vec![11, 12, 13];
+    helper
+        .insert_vertex(14, label, other_ids.clone())
+        .unwrap();
+    let edge_ids = vec![EdgeId::new(1, 2, 1), EdgeId::new(1, 3, 2)];
+    helper
+        .insert_edge(15, &edge_kind, edge_ids.clone().into_iter())
+        .unwrap();
+    helper.check_get_vertex(15, ttl_label, &ids);
+    helper.check_query_vertices(15, Some(ttl_label), ids.clone().into_iter().collect());
+    helper.check_get_edge(15, &edge_kind, edge_ids.iter());
+
+    advance(&graph, TTL_MS);
+    // expired records are invisible at every snapshot
+    for si in 14..=16 {
+        check_expired_vertices(&graph, si, ttl_label, &ids);
+        helper.check_query_vertices_empty(si, ttl_label);
+        check_expired_edges(&graph, si, &edge_kind, &edge_ids);
+    }
+    // types without ttl are not affected
+    helper.check_get_vertex(16, label, &other_ids);
+    helper.check_query_vertices(16, Some(label), other_ids.into_iter().collect());
+
+    // records written after expiry are visible again
+    helper
+        .insert_vertex(17, ttl_label, vec![1, 4])
+        .unwrap();
+    helper.check_get_vertex(17, ttl_label, &vec![1, 4]);
+    check_expired_vertices(&graph, 17, ttl_label, &vec![2, 3]);
+    let ids: HashSet<VertexId> = graph
+        .scan_vertex(17, Some(ttl_label), None, None)
+        .unwrap()
+        .map(|v| v.unwrap().get_vertex_id())
+        .collect();
+    assert_eq!(ids, vec![1, 4].into_iter().collect());
+}
+
+fn check_expired_vertices<G: MultiVersionGraph>(
+    graph: &G, si: SnapshotId, label: LabelId, ids: &Vec<VertexId>,
+) {
+    for id in ids {
+        assert!(graph
+            .get_vertex(si, *id, Some(label), None)
+            .unwrap()
+            .is_none());
+        assert!(graph
+            .get_vertex(si, *id, None, None)
+            .unwrap()
+            .is_none());
+    }
+}
+
+fn check_expired_edges<G: MultiVersionGraph>(
+    graph: &G, si: SnapshotId, edge_kind: &EdgeKind, ids: &Vec<EdgeId>,
+) {
+    for id in ids {
+        assert!(graph
+            .get_edge(si, *id, Some(edge_kind), None)
+            .unwrap()
+            .is_none());
+        assert!(graph
+            .get_out_edges(si, id.src_id, Some(edge_kind.edge_label_id), None, None)
+            .unwrap()
+            .next()
+            .is_none());
+        assert!(graph
+            .get_in_edges(si, id.dst_id, Some(edge_kind.edge_label_id), None, None)
+            .unwrap()
+            .next()
+            .is_none());
+    }
+    assert!(graph
+        .scan_edge(si, Some(edge_kind.edge_label_id), None, None)
+        .unwrap()
+        .next()
+        .is_none());
+}
@@ -28,10 +28,28 @@ pub fn test_drop_vertex_type<G: MultiVersionGraph>(graph: G) {
     tester.execute();
 }
 
+/// `uses_index` tells whether `graph` scans vertices of a label by an index for a condition at a
+/// snapshot, so that the tester checks the index is really used.
+pub fn test_vertex_index<G, F>(graph: G, uses_index: F)
+where
+    G: MultiVersionGraph,
+    F: Fn(&G, SnapshotId, LabelId, &crate::api::Condition) -> bool,
+{
+    let tester = tester::VertexIndexTester::new(graph, uses_index);
+    tester.execute();
+}
+
 mod tester {
+    use std::collections::HashMap;
+
     use super::common::*;
     use super::*;
+    use crate::api::condition::{CmpOperator, Operand, PredCondition};
+    use crate::api::prelude::Property;
+    use crate::api::{Condition, PropId};
     use crate::db::api::multi_version_graph::MultiVersionGraph;
+    use crate::db::api::types::RocksVertex;
+    use crate::db::graph::tests::data;
 
     pub struct GetVertexTester<G: MultiVersionGraph> {
         graph: G,
@@ -474,6 +492,93 @@ mod tester {
             }
         }
     }
+
+    pub struct VertexIndexTester<G: MultiVersionGraph, F> {
+        graph: G,
+        uses_index: F,
+    }
+
+    impl<G, F> VertexIndexTester<G, F>
+    where
+        G: MultiVersionGraph,
+        F: Fn(&G, SnapshotId, LabelId, &Condition) -> bool,
+    {
+        pub fn new(graph: G, uses_index: F) -> Self {
+            VertexIndexTester { graph, uses_index }
+        }
+
+        pub fn execute(&self) {
+            let label = 1;
+            // property 5 is of long type in the full type def
+            let prop_id = 5;
+            let type_def = types::create_full_type_def(label);
+            self.graph
+                .create_vertex_type(10, 1, label, &type_def, 1)
+                .unwrap();
+            for id in 1..=10 {
+                self.insert(11, label, id, &type_def, prop_id, id % 5);
+            }
+            self.graph
+                .create_vertex_index(12, 2, label, prop_id, 2)
+                .unwrap();
+            assert!(self
+                .graph
+                .create_vertex_index(12, 3, label, prop_id, 3)
+                .is_err());
+            for id in 11..=15 {
+                self.insert(13, label, id, &type_def, prop_id, id % 5);
+            }
+            let mut update = HashMap::new();
+            update.insert(prop_id, Value::long(3));
+            self.graph
+                .insert_update_vertex(14, 1, label, &update)
+                .unwrap();
+            self.graph.delete_vertex(15, 2, label).unwrap();
+
+            let query = |si: SnapshotId, op: CmpOperator, v: i64| {
+                let condition = Condition::new(PredCondition::new_predicate(
+                    Operand::PropId(prop_id as PropId),
+                    op,
+                    Operand::Const(Property::Long(v)),
+                ));
+                // the index serves queries from its creation until it is dropped
+                let indexed = si >= 12 && si < 16;
+                assert_eq!((self.uses_index)(&self.graph, si, label, &condition), indexed);
+                let mut ids: Vec<VertexId> = self
+                    .graph
+                    .scan_vertex(si, Some(label), Some(&condition), Some(&vec![]))
+                    .unwrap()
+                    .map(|v| v.unwrap().get_vertex_id())
+                    .collect();
+                ids.sort();
+                ids
+            };
+            // before the index is created
+            assert_eq!(query(11, CmpOperator::Equal, 1), vec![1, 6]);
+            assert_eq!(query(12, CmpOperator::Equal, 1), vec![1, 6]);
+            assert_eq!(query(13, CmpOperator::Equal, 1), vec![1, 6, 11]);
+            assert_eq!(query(14, CmpOperator::Equal, 1), vec![6, 11]);
+            assert_eq!(query(14, CmpOperator::Equal, 3), vec![1, 3, 8, 13]);
+            assert_eq!(query(15, CmpOperator::LessThan, 3), vec![5, 6, 7, 10, 11, 12, 15]);
+            assert_eq!(query(15, CmpOperator::GreaterEqual, 3), vec![1, 3, 4, 8, 9, 13, 14]);
+
+            self.graph
+                .drop_vertex_index(16, 3, label, prop_id)
+                .unwrap();
+            assert_eq!(query(16, CmpOperator::Equal, 1), vec![6, 11]);
+        }
+
+        fn insert(
+            &self, si: SnapshotId, label: LabelId, id: VertexId, type_def: &TypeDef, prop_id: PropertyId,
+            v: i64,
+        ) {
+            let mut properties = data::gen_vertex_properties(si, label, id, type_def);
+            properties.insert(prop_id, Value::long(v));
+            self.graph
+                .insert_overwrite_vertex(si, id, label, &properties)
+                .unwrap();
+        }
+    }
 }
 
 mod common {
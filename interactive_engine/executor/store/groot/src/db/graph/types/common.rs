@@ -1,9 +1,11 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use super::super::codec::*;
 use super::super::table_manager::*;
 use crate::db::api::{GraphResult, SnapshotId};
 use crate::db::common::concurrency::volatile::Volatile;
+use crate::db::storage::is_expired;
 
 pub const INFINITE_SI: SnapshotId = SnapshotId::max_value();
 
@@ -37,6 +39,17 @@ impl TypeCommon {
         self.table_manager.get(si)
     }
 
+    /// The ttl of each codec version that has one, records expire by the codec that wrote them.
+    pub fn get_ttl_versions(&self) -> HashMap<CodecVersion, i64> {
+        self.codec_manager.get_ttl_versions()
+    }
+
+    pub fn get_expire_checker(&self, now_ms: i64) -> ExpireChecker {
+        let codec_manager =
+            if self.codec_manager.has_ttl() { Some(self.codec_manager.clone()) } else { None };
+        ExpireChecker { codec_manager, now_ms }
+    }
+
     pub fn online_table(&self, table: Table) -> GraphResult<()> {
         res_unwrap!(self.table_manager.add(table.start_si, table.id), online_table, table)
     }
@@ -50,6 +63,26 @@ impl TypeCommon {
     }
 }
 
+/// Tells whether the records of a type are expired at `now_ms`, so that all records read by a query
+/// are judged against the same clock. A record lives for the ttl of the codec version it was written
+/// by, types without any ttl codec skip the lookup.
+#[derive(Clone)]
+pub struct ExpireChecker {
+    codec_manager: Option<Arc<CodecManager>>,
+    now_ms: i64,
+}
+
+impl ExpireChecker {
+    pub fn is_expired(&self, data: &[u8]) -> bool {
+        match self.codec_manager {
+            Some(ref codec_manager) if data.len() >= 4 => codec_manager
+                .get_ttl_ms(get_codec_version(data))
+                .map_or(false, |ttl_ms| is_expired(data, ttl_ms, self.now_ms)),
+            _ => false,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct LifeTime {
     start_si: Volatile<SnapshotId>,
@@ -78,6 +111,10 @@ impl LifeTime {
         self.end_si.get()
     }
 
+    pub fn has_end(&self) -> bool {
+        self.end_si.get() != INFINITE_SI
+    }
+
     pub fn set_end(&self, si: SnapshotId) {
         if self.end_si.get() > si {
             self.end_si.set(si);
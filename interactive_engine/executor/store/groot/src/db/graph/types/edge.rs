@@ -48,10 +48,23 @@ impl EdgeKindInfo {
         res_unwrap!(self.info.get_encoder(si), get_encoder, si)
     }
 
+    pub fn get_ttl_versions(&self) -> HashMap<CodecVersion, i64> {
+        self.info.get_ttl_versions()
+    }
+
+    pub fn get_expire_checker(&self, now_ms: i64) -> ExpireChecker {
+        self.info.get_expire_checker(now_ms)
+    }
+
     pub fn is_alive_at(&self, si: SnapshotId) -> bool {
         self.lifetime.is_alive_at(si)
     }
 
+    /// Whether the type is dropped at some snapshot, it's still readable before that snapshot.
+    pub fn is_dropped(&self) -> bool {
+        self.lifetime.has_end()
+    }
+
     fn new(si: SnapshotId, edge_kind: EdgeKind, codec_manager: Arc<CodecManager>) -> Self {
         EdgeKindInfo {
             edge_kind,
@@ -532,4 +545,8 @@ impl EdgeManagerInner {
         debug!("EdgeManagerInner::get_all_edges");
         self.type_map.keys()
     }
+
+    pub(crate) fn get_edge_kind_infos(&self) -> impl Iterator<Item = &Arc<EdgeKindInfo>> {
+        self.type_map.values().flatten()
+    }
 }
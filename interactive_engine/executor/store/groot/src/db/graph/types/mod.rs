@@ -2,5 +2,6 @@ mod common;
 pub mod edge;
 pub mod vertex;
 
+pub use self::common::ExpireChecker;
 pub use self::edge::*;
 pub use self::vertex::*;
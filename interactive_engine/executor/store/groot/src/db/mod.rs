@@ -1,5 +1,6 @@
 #[macro_use]
 pub mod api;
+pub mod bulk_load;
 pub mod common;
 pub mod graph;
 #[allow(bare_trait_objects)]
@@ -29,6 +29,26 @@ impl<'a> StorageIter<'a> {
 
 pub type KvPair = (RawBytes, RawBytes);
 
+/// Length of the write time appended to the values of types with a ttl.
+pub const WRITE_TIME_LEN: usize = 8;
+
+/// Whether `value` written by a codec whose records live for `ttl_ms` milliseconds is expired at
+/// `now_ms`. Only values of codecs with a ttl carry a write time, callers pick `ttl_ms` by the codec
+/// version in the value header. Tombstones carry no write time and never expire.
+pub fn is_expired(value: &[u8], ttl_ms: i64, now_ms: i64) -> bool {
+    if value.len() < 4 + WRITE_TIME_LEN {
+        return false;
+    }
+    get_write_time(value).saturating_add(ttl_ms) <= now_ms
+}
+
+/// The write time at the end of `value`, which must be written by a codec with a ttl.
+pub fn get_write_time(value: &[u8]) -> i64 {
+    let mut bytes = [0; WRITE_TIME_LEN];
+    bytes.copy_from_slice(&value[value.len() - WRITE_TIME_LEN..]);
+    i64::from_be_bytes(bytes)
+}
+
 pub struct RawBytes {
     ptr: *const u8,
     len: usize,
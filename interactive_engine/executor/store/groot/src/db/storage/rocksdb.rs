@@ -1,22 +1,178 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ffi::CStr;
 use std::path::Path;
-use std::sync::atomic::Ordering;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
 use ::rocksdb::backup::{BackupEngine, BackupEngineOptions, RestoreOptions};
-use ::rocksdb::{DBRawIterator, Env, IngestExternalFileOptions, Options, ReadOptions, DB};
+use ::rocksdb::compaction_filter::CompactionFilter;
+use ::rocksdb::compaction_filter_factory::{CompactionFilterContext, CompactionFilterFactory};
+use ::rocksdb::{
+    CompactionDecision, DBRawIterator, Env, IngestExternalFileOptions, Options, ReadOptions, DB,
+};
 use crossbeam_epoch::{self as epoch, Atomic, Owned};
 use rocksdb::WriteBatch;
 
 use super::{StorageIter, StorageRes};
 use crate::db::api::*;
-use crate::db::storage::{KvPair, RawBytes};
+use crate::db::storage::{is_expired, KvPair, RawBytes};
+use crate::db::util::time::Clock;
 
 pub struct RocksDB {
     db: Atomic<Arc<DB>>,
     options: HashMap<String, String>,
     is_secondary: bool,
+    ttl_filter: Arc<TtlFilter>,
+    index_filter: Arc<IndexFilter>,
+    clock: Arc<Clock>,
+}
+
+/// A table of a type with a ttl. Its records expire `ttl_ms` milliseconds after they are written,
+/// where `ttl_ms` is the one of the codec version in the value header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TtlTable {
+    pub ttl_ms: HashMap<i32, i64>,
+    pub start_si: SnapshotId,
+}
+
+/// Drops expired records of ttl tables during compaction. Only versions of snapshots that have been
+/// garbage collected are dropped, so readers of live snapshots and change scans never see a record
+/// vanish under them, they rely on the expiry check at read time instead. An expired version is
+/// replaced by a tombstone rather than removed, otherwise an older version of the key, e.g., one
+/// written before the type had a ttl, would become the newest one and be read again.
+struct TtlFilter {
+    // table prefix of keys to the table
+    tables: RwLock<HashMap<i64, TtlTable>>,
+    gc_si: AtomicI64,
+    clock: Arc<Clock>,
+}
+
+impl TtlFilter {
+    fn new(clock: Arc<Clock>) -> Self {
+        TtlFilter { tables: RwLock::new(HashMap::new()), gc_si: AtomicI64::new(0), clock }
+    }
+
+    fn filter(&self, key: &[u8], value: &[u8]) -> CompactionDecision {
+        // keys of vertices and edges are [table prefix]...[!ts], values are [codec version]...
+        if key.len() < 16 || value.len() < 4 {
+            return CompactionDecision::Keep;
+        }
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&key[0..8]);
+        let prefix = i64::from_be_bytes(bytes);
+        let tables = match self.tables.read() {
+            Ok(tables) => tables,
+            Err(_) => return CompactionDecision::Keep,
+        };
+        let table = match tables.get(&prefix) {
+            Some(table) => table,
+            None => return CompactionDecision::Keep,
+        };
+        bytes.copy_from_slice(&key[key.len() - 8..]);
+        let si = table.start_si + !i64::from_be_bytes(bytes);
+        if si > self.gc_si.load(Ordering::Relaxed) {
+            return CompactionDecision::Keep;
+        }
+        let mut version = [0; 4];
+        version.copy_from_slice(&value[0..4]);
+        match table.ttl_ms.get(&i32::from_be_bytes(version)) {
+            Some(ttl_ms) if is_expired(value, *ttl_ms, self.clock.now_ms()) => {
+                CompactionDecision::Change(&[])
+            }
+            _ => CompactionDecision::Keep,
+        }
+    }
+}
+
+/// Drops index entries superseded before the gc watermark during compaction. Entries of a same
+/// (value, vertex) pair are ordered from the newest to the oldest, and readers of live snapshots
+/// stop at the newest entry not newer than the watermark, so the older ones are never read again.
+/// That newest entry is kept even if it is dead, as older entries of the pair may be in files out
+/// of the compaction and would be read instead.
+struct IndexFilter {
+    // table prefixes of index tables
+    tables: RwLock<HashSet<i64>>,
+}
+
+impl IndexFilter {
+    fn new() -> Self {
+        IndexFilter { tables: RwLock::new(HashSet::new()) }
+    }
+
+    /// Returns `None` if `key` is not an index entry. `last_pair` is the pair of the last entry kept
+    /// in the compaction that is not newer than `gc_si`.
+    fn filter(&self, key: &[u8], gc_si: SnapshotId, last_pair: &mut Vec<u8>) -> Option<CompactionDecision> {
+        // keys of index entries are [table prefix][value][vertex id][!si]
+        if key.len() < 24 {
+            return None;
+        }
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&key[0..8]);
+        match self.tables.read() {
+            Ok(tables) if tables.contains(&i64::from_be_bytes(bytes)) => {}
+            _ => return None,
+        }
+        bytes.copy_from_slice(&key[key.len() - 8..]);
+        let si = !i64::from_be_bytes(bytes);
+        if si > gc_si {
+            return Some(CompactionDecision::Keep);
+        }
+        let pair = &key[..key.len() - 8];
+        if last_pair.as_slice() == pair {
+            return Some(CompactionDecision::Remove);
+        }
+        last_pair.clear();
+        last_pair.extend_from_slice(pair);
+        Some(CompactionDecision::Keep)
+    }
+}
+
+/// Creates a `GcFilter` for each compaction, as dropping superseded index entries depends on the
+/// entries seen before in the same compaction.
+struct GcFilterFactory {
+    ttl_filter: Arc<TtlFilter>,
+    index_filter: Arc<IndexFilter>,
+}
+
+impl CompactionFilterFactory for GcFilterFactory {
+    type Filter = GcFilter;
+
+    fn create(&mut self, _context: CompactionFilterContext) -> GcFilter {
+        GcFilter {
+            ttl_filter: self.ttl_filter.clone(),
+            index_filter: self.index_filter.clone(),
+            last_pair: Vec::new(),
+        }
+    }
+
+    fn name(&self) -> &CStr {
+        CStr::from_bytes_with_nul(b"gc_filter_factory\0").expect("invalid filter name")
+    }
+}
+
+/// Drops expired records of ttl tables and superseded entries of index tables.
+struct GcFilter {
+    ttl_filter: Arc<TtlFilter>,
+    index_filter: Arc<IndexFilter>,
+    last_pair: Vec<u8>,
+}
+
+impl CompactionFilter for GcFilter {
+    fn filter(&mut self, _level: u32, key: &[u8], value: &[u8]) -> CompactionDecision {
+        let gc_si = self.ttl_filter.gc_si.load(Ordering::Relaxed);
+        match self
+            .index_filter
+            .filter(key, gc_si, &mut self.last_pair)
+        {
+            Some(decision) => decision,
+            None => self.ttl_filter.filter(key, value),
+        }
+    }
+
+    fn name(&self) -> &CStr {
+        CStr::from_bytes_with_nul(b"gc_filter\0").expect("invalid filter name")
+    }
 }
 
 pub struct RocksDBBackupEngine {
@@ -26,7 +182,14 @@ pub struct RocksDBBackupEngine {
 
 impl RocksDB {
     pub fn open(options: &HashMap<String, String>) -> GraphResult<Self> {
-        let opts = init_options(options);
+        let mut opts = init_options(options);
+        let clock = Arc::new(Clock::default());
+        let ttl_filter = Arc::new(TtlFilter::new(clock.clone()));
+        let index_filter = Arc::new(IndexFilter::new());
+        opts.set_compaction_filter_factory(GcFilterFactory {
+            ttl_filter: ttl_filter.clone(),
+            index_filter: index_filter.clone(),
+        });
         let path = options
             .get("store.data.path")
             .expect("invalid config, missing store.data.path");
@@ -34,7 +197,14 @@ impl RocksDB {
             let msg = format!("open rocksdb at {} failed: {}", path, e.into_string());
             gen_graph_err!(ErrorCode::EXTERNAL_STORAGE_ERROR, msg, open, options, path)
         })?;
-        let ret = RocksDB { db: Atomic::new(Arc::new(db)), options: options.clone(), is_secondary: false };
+        let ret = RocksDB {
+            db: Atomic::new(Arc::new(db)),
+            options: options.clone(),
+            is_secondary: false,
+            ttl_filter,
+            index_filter,
+            clock,
+        };
         Ok(ret)
     }
 
@@ -44,7 +214,15 @@ impl RocksDB {
             gen_graph_err!(ErrorCode::EXTERNAL_STORAGE_ERROR, msg, open_as_secondary)
         })?;
 
-        let ret = RocksDB { db: Atomic::new(Arc::new(db)), options: options.clone(), is_secondary: true };
+        let clock = Arc::new(Clock::default());
+        let ret = RocksDB {
+            db: Atomic::new(Arc::new(db)),
+            options: options.clone(),
+            is_secondary: true,
+            ttl_filter: Arc::new(TtlFilter::new(clock.clone())),
+            index_filter: Arc::new(IndexFilter::new()),
+            clock,
+        };
         Ok(ret)
     }
 
@@ -127,6 +305,23 @@ impl RocksDB {
         })
     }
 
+    /// Put `kvs` in one atomic write.
+    pub fn put_batch(&self, kvs: &[(Vec<u8>, Vec<u8>)]) -> GraphResult<()> {
+        if self.is_secondary {
+            info!("Cannot put_batch in secondary instance");
+            return Ok(());
+        }
+        let mut batch = WriteBatch::default();
+        for (key, val) in kvs {
+            batch.put(key, val);
+        }
+        let db = self.get_db()?;
+        db.write(batch).map_err(|e| {
+            let msg = format!("rocksdb.put_batch failed because {}", e.into_string());
+            gen_graph_err!(ErrorCode::EXTERNAL_STORAGE_ERROR, msg)
+        })
+    }
+
     pub fn scan_prefix(&self, prefix: &[u8]) -> GraphResult<StorageIter> {
         let db = self.get_db()?;
         Ok(StorageIter::RocksDB(RocksDBIter::new_prefix(db, prefix)))
@@ -168,6 +363,34 @@ impl RocksDB {
         Ok(())
     }
 
+    /// Replace the tables whose expired records are dropped by compaction, keyed by table prefix.
+    pub fn set_ttl_tables(&self, tables: HashMap<i64, TtlTable>) {
+        if let Ok(mut current) = self.ttl_filter.tables.write() {
+            *current = tables;
+        }
+    }
+
+    /// Replace the index tables whose superseded entries are dropped by compaction, keyed by table
+    /// prefix.
+    pub fn set_index_tables(&self, tables: HashSet<i64>) {
+        if let Ok(mut current) = self.index_filter.tables.write() {
+            *current = tables;
+        }
+    }
+
+    /// The clock of write times and expiry of records of types with a ttl.
+    pub fn get_clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// Expired records and superseded index entries of snapshots not newer than `si` can be dropped
+    /// by compaction.
+    pub fn set_gc_snapshot(&self, si: SnapshotId) {
+        self.ttl_filter
+            .gc_si
+            .fetch_max(si, Ordering::Relaxed);
+    }
+
     pub fn compact(&self) -> GraphResult<()> {
         info!("begin to compact rocksdb");
         if self.is_secondary {
@@ -225,6 +448,14 @@ impl RocksDB {
         Ok(Box::new(Scan::new(db, prefix)))
     }
 
+    /// Like `scan_range`, but the returned iterator owns the db and outlives `self`.
+    pub fn new_range_scan(
+        &self, start: &[u8], end: &[u8],
+    ) -> GraphResult<Box<dyn Iterator<Item = KvPair> + Send>> {
+        let db = self.get_db()?;
+        Ok(Box::new(Scan::with_range(db, start, end)))
+    }
+
     pub fn try_catch_up_with_primary(&self) -> GraphResult<()> {
         if !self.is_secondary {
             return Ok(());
@@ -280,6 +511,10 @@ impl<'a> Scan<'a> {
     pub fn new(db: Arc<DB>, prefix: &[u8]) -> Self {
         Scan { inner_iter: RocksDBIter::new_prefix(db, prefix) }
     }
+
+    pub fn with_range(db: Arc<DB>, start: &[u8], end: &[u8]) -> Self {
+        Scan { inner_iter: RocksDBIter::new_range(db, start, end) }
+    }
 }
 
 impl<'a> Iterator for Scan<'a> {
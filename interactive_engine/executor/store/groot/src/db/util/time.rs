@@ -13,7 +13,7 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
@@ -23,6 +23,23 @@ pub fn current_time_millis() -> u64 {
     d.as_secs() * 1000 + (d.subsec_nanos() / 1000000) as u64
 }
 
+/// Wall clock in milliseconds that can be moved forward, tests advance it to expire records instead
+/// of sleeping.
+#[derive(Debug, Default)]
+pub struct Clock {
+    offset_ms: AtomicI64,
+}
+
+impl Clock {
+    pub fn now_ms(&self) -> i64 {
+        current_time_millis() as i64 + self.offset_ms.load(Ordering::Relaxed)
+    }
+
+    pub fn advance(&self, ms: i64) {
+        self.offset_ms.fetch_add(ms, Ordering::Relaxed);
+    }
+}
+
 pub fn current_time_secs() -> u64 {
     let now = SystemTime::now();
     now.duration_since(UNIX_EPOCH)
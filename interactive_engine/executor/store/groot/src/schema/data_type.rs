@@ -33,6 +33,9 @@ pub enum DataType {
     ListDouble = 14,
     ListString = 15,
     ListBytes = 16,
+    DateTime = 17,
+    DateTimeWithTz = 18,
+    Decimal = 19,
     Map = 100,
     Unknown = 1000,
 }
@@ -71,6 +74,9 @@ impl DataType {
             14 => DataType::ListDouble,
             15 => DataType::ListString,
             16 => DataType::ListBytes,
+            17 => DataType::DateTime,
+            18 => DataType::DateTimeWithTz,
+            19 => DataType::Decimal,
             _ => DataType::Unknown,
         }
     }
@@ -80,7 +86,9 @@ impl DataType {
             DataType::Bool | DataType::Char => 1,
             DataType::Short => 2,
             DataType::Int | DataType::Float => 4,
-            DataType::Long | DataType::Double => 8,
+            DataType::Long | DataType::Double | DataType::DateTime => 8,
+            DataType::DateTimeWithTz => 12,
+            DataType::Decimal => 17,
             _ => unimplemented!(),
         }
     }
@@ -93,7 +101,10 @@ impl DataType {
             | DataType::Int
             | DataType::Long
             | DataType::Float
-            | DataType::Double => true,
+            | DataType::Double
+            | DataType::DateTime
+            | DataType::DateTimeWithTz
+            | DataType::Decimal => true,
             _ => false,
         }
     }
@@ -111,6 +122,9 @@ pub fn parse_str_to_data_type(value: &str) -> Result<DataType, String> {
         "bytes" => Ok(DataType::Bytes),
         "string" => Ok(DataType::String),
         "date" => Ok(DataType::Date),
+        "datetime" => Ok(DataType::DateTime),
+        "datetime_with_tz" => Ok(DataType::DateTimeWithTz),
+        "decimal" => Ok(DataType::Decimal),
         v => {
             if v.starts_with("list<") {
                 let tmp = &v[5..v.len() - 1];
@@ -150,6 +164,9 @@ impl<'a> From<&'a str> for DataType {
             "bytes" => DataType::Bytes,
             "string" => DataType::String,
             "date" => DataType::Date,
+            "datetime" => DataType::DateTime,
+            "datetime_with_tz" => DataType::DateTimeWithTz,
+            "decimal" => DataType::Decimal,
             v => {
                 if v.starts_with("list<") {
                     let tmp = &v[5..v.len() - 1];
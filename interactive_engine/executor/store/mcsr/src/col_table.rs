@@ -26,9 +26,92 @@ use crate::date::parse_date;
 use crate::date_time::parse_datetime;
 use crate::error::GDBResult;
 
+/// Validity of the rows of a column, a set bit means that the row has a value. Bits beyond `len`
+/// are always cleared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidityBitmap {
+    bits: Vec<u64>,
+    len: usize,
+}
+
+impl ValidityBitmap {
+    pub fn new(len: usize, valid: bool) -> Self {
+        let mut bitmap = Self { bits: Vec::new(), len: 0 };
+        bitmap.resize(len, valid);
+        bitmap
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_valid(&self, index: usize) -> bool {
+        index < self.len && (self.bits[index / 64] >> (index % 64)) & 1 == 1
+    }
+
+    pub fn set(&mut self, index: usize, valid: bool) {
+        if valid {
+            self.bits[index / 64] |= 1 << (index % 64);
+        } else {
+            self.bits[index / 64] &= !(1 << (index % 64));
+        }
+    }
+
+    pub fn null_count(&self) -> usize {
+        self.len
+            - self
+                .bits
+                .iter()
+                .map(|word| word.count_ones() as usize)
+                .sum::<usize>()
+    }
+
+    /// Rows appended when growing are valid if `valid` is true.
+    pub fn resize(&mut self, len: usize, valid: bool) {
+        if len > self.len {
+            let old_len = self.len;
+            let old_capacity = self.bits.len() * 64;
+            self.bits
+                .resize((len + 63) / 64, if valid { u64::MAX } else { 0 });
+            self.len = len;
+            for index in old_len..len.min(old_capacity) {
+                self.set(index, valid);
+            }
+        } else {
+            self.bits.truncate((len + 63) / 64);
+            self.len = len;
+        }
+        if len % 64 != 0 {
+            if let Some(last) = self.bits.last_mut() {
+                *last &= (1 << (len % 64)) - 1;
+            }
+        }
+    }
+
+    fn serialize(&self, f: &mut File) -> std::io::Result<()> {
+        f.write_u64(self.len as u64)?;
+        for word in self.bits.iter() {
+            f.write_u64(*word)?;
+        }
+        Ok(())
+    }
+
+    fn deserialize(f: &mut File) -> std::io::Result<Self> {
+        let len = f.read_u64()? as usize;
+        let mut bits = Vec::with_capacity((len + 63) / 64);
+        for _ in 0..(len + 63) / 64 {
+            bits.push(f.read_u64()?);
+        }
+        Ok(Self { bits, len })
+    }
+}
+
 #[derive(Debug)]
 pub struct ColTable {
     columns: Vec<Box<dyn Column>>,
+    /// Validity of each column, which is only allocated once a null value is written to the column,
+    /// rows beyond the bitmap are valid.
+    validity: Vec<Option<ValidityBitmap>>,
     pub header: HashMap<String, usize>,
     row_num: usize,
 }
@@ -70,12 +153,34 @@ impl ColTable {
                 DataType::ID => {
                     columns.push(Box::new(IDColumn::new()));
                 }
+                DataType::Boolean => {
+                    columns.push(Box::new(BooleanColumn::new()));
+                }
+                DataType::Float => {
+                    columns.push(Box::new(FloatColumn::new()));
+                }
+                DataType::Int32List => {
+                    columns.push(Box::new(Int32ListColumn::new()));
+                }
+                DataType::Int64List => {
+                    columns.push(Box::new(Int64ListColumn::new()));
+                }
+                DataType::FloatList => {
+                    columns.push(Box::new(FloatListColumn::new()));
+                }
+                DataType::DoubleList => {
+                    columns.push(Box::new(DoubleListColumn::new()));
+                }
+                DataType::StringList => {
+                    columns.push(Box::new(StringListColumn::new()));
+                }
                 DataType::NULL => {
                     error!("Unexpected column type");
                 }
             }
         }
-        Self { columns, header, row_num: 0 }
+        let validity = vec![None; columns.len()];
+        Self { columns, validity, header, row_num: 0 }
     }
 
     pub fn col_num(&self) -> usize {
@@ -86,6 +191,40 @@ impl ColTable {
         self.row_num
     }
 
+    /// Returns true if the value of column `col_i` in row `row_i` is null.
+    pub fn is_null(&self, col_i: usize, row_i: usize) -> bool {
+        match self.validity.get(col_i) {
+            Some(Some(bitmap)) => row_i < bitmap.len() && !bitmap.is_valid(row_i),
+            _ => false,
+        }
+    }
+
+    pub fn null_count(&self, col_i: usize) -> usize {
+        match self.validity.get(col_i) {
+            Some(Some(bitmap)) => bitmap.null_count(),
+            _ => 0,
+        }
+    }
+
+    fn set_valid(&mut self, col_i: usize, row_i: usize, valid: bool) {
+        let row_num = self.row_num.max(row_i + 1);
+        match &mut self.validity[col_i] {
+            Some(bitmap) => {
+                if bitmap.len() <= row_i {
+                    bitmap.resize(row_num, true);
+                }
+                bitmap.set(row_i, valid);
+            }
+            None => {
+                if !valid {
+                    let mut bitmap = ValidityBitmap::new(row_num, true);
+                    bitmap.set(row_i, false);
+                    self.validity[col_i] = Some(bitmap);
+                }
+            }
+        }
+    }
+
     pub fn push(&mut self, row: &Vec<Item>) {
         let col_num = self.columns.len();
         if row.len() < col_num {
@@ -94,6 +233,7 @@ impl ColTable {
         }
         for i in 0..col_num {
             self.columns[i].push(row[i].clone());
+            self.set_valid(i, self.row_num, !matches!(row[i], Item::Null));
         }
         self.row_num += 1;
     }
@@ -107,12 +247,16 @@ impl ColTable {
                 for _ in 0..null_num {
                     col.push(Item::Null);
                 }
+                for row_i in self.row_num..index {
+                    self.set_valid(i, row_i, false);
+                }
             }
             self.row_num = index;
             self.push(row);
         } else {
             for i in 0..col_num {
                 self.columns[i].set(index, row[i].clone());
+                self.set_valid(i, index, !matches!(row[i], Item::Null));
             }
         }
     }
@@ -128,14 +272,14 @@ impl ColTable {
 
     pub fn get_item(&self, col_name: &str, row_i: usize) -> Option<RefItem> {
         if let Some(col_i) = self.header.get(col_name) {
-            self.columns[*col_i].get(row_i)
+            self.get_item_by_index(*col_i, row_i)
         } else {
             None
         }
     }
 
     pub fn get_item_by_index(&self, col_i: usize, row_i: usize) -> Option<RefItem> {
-        if col_i < self.columns.len() {
+        if col_i < self.columns.len() && !self.is_null(col_i, row_i) {
             self.columns[col_i].get(row_i)
         } else {
             None
@@ -233,11 +377,73 @@ impl ColTable {
                         .unwrap()
                         .serialize(&mut f);
                 }
+                DataType::Boolean => {
+                    f.write_u8(9).unwrap();
+                    col.as_any()
+                        .downcast_ref::<BooleanColumn>()
+                        .unwrap()
+                        .serialize(&mut f);
+                }
+                DataType::Float => {
+                    f.write_u8(10).unwrap();
+                    col.as_any()
+                        .downcast_ref::<FloatColumn>()
+                        .unwrap()
+                        .serialize(&mut f);
+                }
+                DataType::Int32List => {
+                    f.write_u8(11).unwrap();
+                    col.as_any()
+                        .downcast_ref::<Int32ListColumn>()
+                        .unwrap()
+                        .serialize(&mut f);
+                }
+                DataType::Int64List => {
+                    f.write_u8(12).unwrap();
+                    col.as_any()
+                        .downcast_ref::<Int64ListColumn>()
+                        .unwrap()
+                        .serialize(&mut f);
+                }
+                DataType::FloatList => {
+                    f.write_u8(13).unwrap();
+                    col.as_any()
+                        .downcast_ref::<FloatListColumn>()
+                        .unwrap()
+                        .serialize(&mut f);
+                }
+                DataType::DoubleList => {
+                    f.write_u8(14).unwrap();
+                    col.as_any()
+                        .downcast_ref::<DoubleListColumn>()
+                        .unwrap()
+                        .serialize(&mut f);
+                }
+                DataType::StringList => {
+                    f.write_u8(15).unwrap();
+                    col.as_any()
+                        .downcast_ref::<StringListColumn>()
+                        .unwrap()
+                        .serialize(&mut f);
+                }
                 _ => {
                     info!("unexpected type...");
                 }
             }
         }
+
+        f.write_u64(self.validity.len() as u64).unwrap();
+        for bitmap in self.validity.iter() {
+            match bitmap {
+                Some(bitmap) => {
+                    f.write_u8(1).unwrap();
+                    bitmap.serialize(&mut f).unwrap();
+                }
+                None => {
+                    f.write_u8(0).unwrap();
+                }
+            }
+        }
     }
 
     pub fn deserialize_table(&mut self, path: &String) {
@@ -302,10 +508,48 @@ impl ColTable {
                 let mut col = LCStringColumn::new();
                 col.deserialize(&mut f);
                 self.columns.push(Box::new(col));
+            } else if t == 9 {
+                let mut col = BooleanColumn::new();
+                col.deserialize(&mut f);
+                self.columns.push(Box::new(col));
+            } else if t == 10 {
+                let mut col = FloatColumn::new();
+                col.deserialize(&mut f);
+                self.columns.push(Box::new(col));
+            } else if t == 11 {
+                let mut col = Int32ListColumn::new();
+                col.deserialize(&mut f);
+                self.columns.push(Box::new(col));
+            } else if t == 12 {
+                let mut col = Int64ListColumn::new();
+                col.deserialize(&mut f);
+                self.columns.push(Box::new(col));
+            } else if t == 13 {
+                let mut col = FloatListColumn::new();
+                col.deserialize(&mut f);
+                self.columns.push(Box::new(col));
+            } else if t == 14 {
+                let mut col = DoubleListColumn::new();
+                col.deserialize(&mut f);
+                self.columns.push(Box::new(col));
+            } else if t == 15 {
+                let mut col = StringListColumn::new();
+                col.deserialize(&mut f);
+                self.columns.push(Box::new(col));
             } else {
                 info!("unexpected type...");
             }
         }
+
+        self.validity = vec![None; self.columns.len()];
+        // tables serialized without validity bitmaps end here, all of their values are valid
+        if let Ok(validity_len) = f.read_u64() {
+            for col_i in 0..validity_len as usize {
+                if f.read_u8().unwrap() == 1 {
+                    self.validity[col_i] = Some(ValidityBitmap::deserialize(&mut f).unwrap());
+                }
+            }
+        }
     }
 
     pub fn is_same(&self, other: &Self) -> bool {
@@ -323,6 +567,12 @@ impl ColTable {
                 info!("column-{} type not same", i);
                 return false;
             }
+            if self.null_count(i) != other.null_count(i)
+                || (0..self.row_num).any(|row_i| self.is_null(i, row_i) != other.is_null(i, row_i))
+            {
+                info!("column-{} validity not same", i);
+                return false;
+            }
             match self.columns[i].get_type() {
                 DataType::Int32 => {
                     if !self.columns[i]
@@ -411,6 +661,23 @@ impl ColTable {
                         return false;
                     }
                 }
+                DataType::Int32List
+                | DataType::Int64List
+                | DataType::FloatList
+                | DataType::DoubleList
+                | DataType::StringList => {
+                    let same = match self.columns[i].get_type() {
+                        DataType::Int32List => is_same_list::<i32>(&self.columns[i], &other.columns[i]),
+                        DataType::Int64List => is_same_list::<i64>(&self.columns[i], &other.columns[i]),
+                        DataType::FloatList => is_same_list::<f32>(&self.columns[i], &other.columns[i]),
+                        DataType::DoubleList => is_same_list::<f64>(&self.columns[i], &other.columns[i]),
+                        _ => is_same_list::<String>(&self.columns[i], &other.columns[i]),
+                    };
+                    if !same {
+                        info!("column-{} data not same", i);
+                        return false;
+                    }
+                }
                 _ => {
                     info!("unexpected type");
                     return false;
@@ -421,14 +688,47 @@ impl ColTable {
     }
 }
 
+fn is_same_list<T: ListItem>(lhs: &Box<dyn Column>, rhs: &Box<dyn Column>) -> bool {
+    lhs.as_any()
+        .downcast_ref::<ListColumn<T>>()
+        .unwrap()
+        .is_same(
+            rhs.as_any()
+                .downcast_ref::<ListColumn<T>>()
+                .unwrap(),
+        )
+}
+
 unsafe impl Sync for ColTable {}
 
+/// Returns true if `val` is an empty field of a non-string value type, which is loaded as null.
+/// Empty fields of string types are empty strings, and those of list types are empty lists.
+pub fn is_null_field(val: &str, data_type: &DataType) -> bool {
+    val.is_empty()
+        && match data_type {
+            DataType::Int32
+            | DataType::UInt32
+            | DataType::Int64
+            | DataType::UInt64
+            | DataType::Double
+            | DataType::Date
+            | DataType::DateTime
+            | DataType::Boolean
+            | DataType::Float => true,
+            _ => false,
+        }
+}
+
 pub fn parse_properties(
     record: &StringRecord, header: &[(String, DataType)], selected: &[bool],
 ) -> GDBResult<Vec<Item>> {
     let mut properties = Vec::new();
     for (index, val) in record.iter().enumerate() {
         if selected[index] {
+            if is_null_field(val, &header[index].1) {
+                properties.push(Item::Null);
+                continue;
+            }
             match header[index].1 {
                 DataType::Int32 => {
                     properties.push(Item::Int32(val.parse::<i32>()?));
@@ -454,6 +754,27 @@ pub fn parse_properties(
                 DataType::Double => {
                     properties.push(Item::Double(val.parse::<f64>()?));
                 }
+                DataType::Boolean => {
+                    properties.push(Item::Boolean(val.parse::<bool>()?));
+                }
+                DataType::Float => {
+                    properties.push(Item::Float(val.parse::<f32>()?));
+                }
+                DataType::Int32List => {
+                    properties.push(Item::Int32List(parse_list(val)?));
+                }
+                DataType::Int64List => {
+                    properties.push(Item::Int64List(parse_list(val)?));
+                }
+                DataType::FloatList => {
+                    properties.push(Item::FloatList(parse_list(val)?));
+                }
+                DataType::DoubleList => {
+                    properties.push(Item::DoubleList(parse_list(val)?));
+                }
+                DataType::StringList => {
+                    properties.push(Item::StringList(parse_list(val)?));
+                }
                 DataType::NULL => {
                     error!("Unexpected field type");
                 }
@@ -466,3 +787,96 @@ pub fn parse_properties(
     }
     Ok(properties)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn person_table() -> ColTable {
+        ColTable::new(vec![(DataType::Int32, "age".to_string()), (DataType::String, "name".to_string())])
+    }
+
+    fn ages(table: &ColTable) -> Vec<Option<i32>> {
+        (0..table.row_num())
+            .map(|row_i| {
+                table
+                    .get_item("age", row_i)
+                    .map(|item| item.as_i32().unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_validity_bitmap() {
+        let mut bitmap = ValidityBitmap::new(70, true);
+        assert_eq!(bitmap.null_count(), 0);
+        bitmap.set(3, false);
+        bitmap.set(65, false);
+        assert!(!bitmap.is_valid(3) && !bitmap.is_valid(65) && bitmap.is_valid(64));
+        assert_eq!(bitmap.null_count(), 2);
+        bitmap.resize(130, false);
+        assert_eq!(bitmap.null_count(), 62);
+        bitmap.resize(60, true);
+        assert_eq!(bitmap.null_count(), 1);
+        assert!(!bitmap.is_valid(60));
+    }
+
+    #[test]
+    fn test_nullable_columns() {
+        let mut table = person_table();
+        table.push(&vec![Item::Int32(29), Item::String("marko".to_string())]);
+        assert_eq!(table.null_count(0), 0);
+        table.push(&vec![Item::Null, Item::String("vadas".to_string())]);
+        table.insert(4, &vec![Item::Int32(35), Item::Null]);
+        assert_eq!(ages(&table), vec![Some(29), None, None, None, Some(35)]);
+        assert!(table.get_item("name", 4).is_none());
+        assert!(table.get_item_by_index(1, 0).is_some());
+        assert_eq!(table.null_count(0), 3);
+
+        // overwriting a null row makes it valid, and the other way around
+        table.insert(2, &vec![Item::Int32(27), Item::String("josh".to_string())]);
+        table.insert(0, &vec![Item::Null, Item::String("marko".to_string())]);
+        assert_eq!(ages(&table), vec![None, None, Some(27), None, Some(35)]);
+        assert_eq!(table.null_count(0), 3);
+    }
+
+    #[test]
+    fn test_serialize_nullable_table() {
+        let mut table = person_table();
+        table.push(&vec![Item::Null, Item::String("marko".to_string())]);
+        table.push(&vec![Item::Int32(27), Item::String("vadas".to_string())]);
+        let dir = tempdir::TempDir::new("mcsr_col_table").unwrap();
+        let path = dir
+            .path()
+            .join("table")
+            .to_str()
+            .unwrap()
+            .to_string();
+        table.serialize_table(&path);
+
+        let mut loaded = ColTable::new(vec![]);
+        loaded.deserialize_table(&path);
+        assert!(loaded.is_same(&table));
+        assert_eq!(ages(&loaded), vec![None, Some(27)]);
+    }
+
+    #[test]
+    fn test_parse_null_fields() {
+        let header: Vec<(String, DataType)> = vec![
+            ("age".to_string(), DataType::Int32),
+            ("name".to_string(), DataType::String),
+            ("tags".to_string(), DataType::StringList),
+        ];
+        let record = StringRecord::from(vec!["", "", ""]);
+        let mut table = ColTable::new(
+            header
+                .iter()
+                .map(|(name, data_type)| (data_type.clone(), name.clone()))
+                .collect(),
+        );
+        table.push(&parse_properties(&record, &header, &[true; 3]).unwrap());
+        assert!(table.get_item("age", 0).is_none());
+        assert_eq!(table.get_item("name", 0).unwrap().to_string(), "");
+        assert!(table.get_item("tags", 0).is_some());
+    }
+}
@@ -29,6 +29,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::date::Date;
 use crate::date_time::DateTime;
+use crate::error::GDBResult;
 use crate::types::DefaultId;
 
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
@@ -43,6 +44,13 @@ pub enum DataType {
     DateTime,
     LCString,
     ID,
+    Boolean,
+    Float,
+    Int32List,
+    Int64List,
+    FloatList,
+    DoubleList,
+    StringList,
     NULL,
 }
 
@@ -71,6 +79,20 @@ impl<'a> From<&'a str> for DataType {
             DataType::ID
         } else if token == "LCString" {
             DataType::LCString
+        } else if token == "BOOL" || token == "BOOLEAN" {
+            DataType::Boolean
+        } else if token == "FLOAT" || token == "FLOAT32" {
+            DataType::Float
+        } else if token == "INT32_LIST" {
+            DataType::Int32List
+        } else if token == "INT64_LIST" {
+            DataType::Int64List
+        } else if token == "FLOAT_LIST" {
+            DataType::FloatList
+        } else if token == "DOUBLE_LIST" {
+            DataType::DoubleList
+        } else if token == "STRING_LIST" {
+            DataType::StringList
         } else {
             error!("Unsupported type {:?}", token);
             DataType::NULL
@@ -80,35 +102,50 @@ impl<'a> From<&'a str> for DataType {
 
 #[derive(Clone)]
 pub enum Item {
+    Boolean(bool),
     Int32(i32),
     UInt32(u32),
     Int64(i64),
     UInt64(u64),
+    Float(f32),
     Double(f64),
     String(String),
     Date(Date),
     DateTime(DateTime),
     ID(DefaultId),
+    Int32List(Vec<i32>),
+    Int64List(Vec<i64>),
+    FloatList(Vec<f32>),
+    DoubleList(Vec<f64>),
+    StringList(Vec<String>),
     Null,
 }
 
 #[derive(Clone)]
 pub enum RefItem<'a> {
+    Boolean(&'a bool),
     Int32(&'a i32),
     UInt32(&'a u32),
     Int64(&'a i64),
     UInt64(&'a u64),
+    Float(&'a f32),
     Double(&'a f64),
     Date(&'a Date),
     DateTime(&'a DateTime),
     ID(&'a DefaultId),
     String(&'a String),
+    Int32List(&'a [i32]),
+    Int64List(&'a [i64]),
+    FloatList(&'a [f32]),
+    DoubleList(&'a [f64]),
+    StringList(&'a [String]),
     Null,
 }
 
 impl<'a> RefItem<'a> {
     pub fn to_owned(self) -> Item {
         match self {
+            RefItem::Boolean(v) => Item::Boolean(*v),
             RefItem::Int32(v) => Item::Int32(*v),
             RefItem::UInt32(v) => Item::UInt32(*v),
             RefItem::Int64(v) => Item::Int64(*v),
@@ -117,7 +154,13 @@ impl<'a> RefItem<'a> {
             RefItem::Date(v) => Item::Date(*v),
             RefItem::DateTime(v) => Item::DateTime(*v),
             RefItem::ID(v) => Item::ID(*v),
+            RefItem::Float(v) => Item::Float(*v),
             RefItem::String(v) => Item::String(v.clone()),
+            RefItem::Int32List(v) => Item::Int32List(v.to_vec()),
+            RefItem::Int64List(v) => Item::Int64List(v.to_vec()),
+            RefItem::FloatList(v) => Item::FloatList(v.to_vec()),
+            RefItem::DoubleList(v) => Item::DoubleList(v.to_vec()),
+            RefItem::StringList(v) => Item::StringList(v.to_vec()),
             RefItem::Null => Item::Null,
         }
     }
@@ -167,6 +210,27 @@ impl ConvertItem for () {
 impl Debug for Item {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            Item::Boolean(v) => {
+                write!(f, "boolean[{}]", v)
+            }
+            Item::Float(v) => {
+                write!(f, "float[{}]", v)
+            }
+            Item::Int32List(v) => {
+                write!(f, "int32_list[{:?}]", v)
+            }
+            Item::Int64List(v) => {
+                write!(f, "int64_list[{:?}]", v)
+            }
+            Item::FloatList(v) => {
+                write!(f, "float_list[{:?}]", v)
+            }
+            Item::DoubleList(v) => {
+                write!(f, "double_list[{:?}]", v)
+            }
+            Item::StringList(v) => {
+                write!(f, "string_list[{:?}]", v)
+            }
             Item::Int32(v) => {
                 write!(f, "int32[{}]", v)
             }
@@ -204,6 +268,13 @@ impl Debug for Item {
 impl ToString for Item {
     fn to_string(&self) -> String {
         match self {
+            Item::Boolean(v) => v.to_string(),
+            Item::Float(v) => v.to_string(),
+            Item::Int32List(v) => list_to_string(v),
+            Item::Int64List(v) => list_to_string(v),
+            Item::FloatList(v) => list_to_string(v),
+            Item::DoubleList(v) => list_to_string(v),
+            Item::StringList(v) => list_to_string(v),
             Item::Int32(v) => v.to_string(),
             Item::UInt32(v) => v.to_string(),
             Item::Int64(v) => v.to_string(),
@@ -221,6 +292,27 @@ impl ToString for Item {
 impl<'a> Debug for RefItem<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            RefItem::Boolean(v) => {
+                write!(f, "boolean[{}]", v)
+            }
+            RefItem::Float(v) => {
+                write!(f, "float[{}]", v)
+            }
+            RefItem::Int32List(v) => {
+                write!(f, "int32_list[{:?}]", v)
+            }
+            RefItem::Int64List(v) => {
+                write!(f, "int64_list[{:?}]", v)
+            }
+            RefItem::FloatList(v) => {
+                write!(f, "float_list[{:?}]", v)
+            }
+            RefItem::DoubleList(v) => {
+                write!(f, "double_list[{:?}]", v)
+            }
+            RefItem::StringList(v) => {
+                write!(f, "string_list[{:?}]", v)
+            }
             RefItem::Int32(v) => {
                 write!(f, "int32[{}]", v)
             }
@@ -258,6 +350,13 @@ impl<'a> Debug for RefItem<'a> {
 impl<'a> ToString for RefItem<'a> {
     fn to_string(&self) -> String {
         match self {
+            RefItem::Boolean(v) => v.to_string(),
+            RefItem::Float(v) => v.to_string(),
+            RefItem::Int32List(v) => list_to_string(v),
+            RefItem::Int64List(v) => list_to_string(v),
+            RefItem::FloatList(v) => list_to_string(v),
+            RefItem::DoubleList(v) => list_to_string(v),
+            RefItem::StringList(v) => list_to_string(v),
             RefItem::Int32(v) => v.to_string(),
             RefItem::UInt32(v) => v.to_string(),
             RefItem::Int64(v) => v.to_string(),
@@ -272,6 +371,16 @@ impl<'a> ToString for RefItem<'a> {
     }
 }
 
+/// Lists are written as their values separated by `LIST_DELIMITER`, the format they are loaded from.
+pub const LIST_DELIMITER: char = ';';
+
+fn list_to_string<T: ToString>(list: &[T]) -> String {
+    list.iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join(&LIST_DELIMITER.to_string())
+}
+
 impl<'a> RefItem<'a> {
     #[inline]
     pub fn as_u64(&self) -> Result<u64, CastError> {
@@ -944,6 +1053,198 @@ impl Column for DoubleColumn {
     }
 }
 
+pub struct BooleanColumn {
+    pub data: Vec<bool>,
+}
+
+impl BooleanColumn {
+    pub fn new() -> Self {
+        Self { data: Vec::new() }
+    }
+
+    pub fn serialize(&self, f: &mut File) {
+        let row_num = self.data.len();
+        f.write_u64(row_num as u64).unwrap();
+        unsafe {
+            let data_slice = slice::from_raw_parts(
+                self.data.as_ptr() as *const u8,
+                row_num * std::mem::size_of::<bool>(),
+            );
+            f.write_all(data_slice).unwrap();
+        }
+        f.flush().unwrap();
+    }
+
+    pub fn deserialize(&mut self, f: &mut File) {
+        let row_num = f.read_u64().unwrap() as usize;
+        self.data.resize(row_num, false);
+        unsafe {
+            let data_slice = slice::from_raw_parts_mut(
+                self.data.as_mut_ptr() as *mut u8,
+                row_num * std::mem::size_of::<bool>(),
+            );
+            f.read_exact(data_slice).unwrap();
+        }
+    }
+}
+
+impl Debug for BooleanColumn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BooleanColumn: {:?}", self.data)
+    }
+}
+
+impl Encode for BooleanColumn {
+    fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.data.write_to(writer)
+    }
+}
+
+impl Decode for BooleanColumn {
+    fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
+        let vec = Vec::<bool>::read_from(reader)?;
+        info!("boolean column: {}", vec.capacity());
+        Ok(Self { data: vec })
+    }
+}
+
+impl Column for BooleanColumn {
+    fn get_type(&self) -> DataType {
+        DataType::Boolean
+    }
+
+    fn get(&self, index: usize) -> Option<RefItem> {
+        self.data
+            .get(index)
+            .map(|x| RefItem::Boolean(x))
+    }
+
+    fn set(&mut self, index: usize, val: Item) {
+        match val {
+            Item::Boolean(v) => {
+                self.data[index] = v;
+            }
+            _ => {
+                self.data[index] = false;
+            }
+        }
+    }
+
+    fn push(&mut self, val: Item) {
+        match val {
+            Item::Boolean(v) => {
+                self.data.push(v);
+            }
+            _ => {
+                self.data.push(false);
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct FloatColumn {
+    pub data: Vec<f32>,
+}
+
+impl FloatColumn {
+    pub fn new() -> Self {
+        Self { data: Vec::new() }
+    }
+
+    pub fn serialize(&self, f: &mut File) {
+        let row_num = self.data.len();
+        f.write_u64(row_num as u64).unwrap();
+        unsafe {
+            let data_slice = slice::from_raw_parts(
+                self.data.as_ptr() as *const u8,
+                row_num * std::mem::size_of::<f32>(),
+            );
+            f.write_all(data_slice).unwrap();
+        }
+        f.flush().unwrap();
+    }
+
+    pub fn deserialize(&mut self, f: &mut File) {
+        let row_num = f.read_u64().unwrap() as usize;
+        self.data.resize(row_num, 0_f32);
+        unsafe {
+            let data_slice = slice::from_raw_parts_mut(
+                self.data.as_mut_ptr() as *mut u8,
+                row_num * std::mem::size_of::<f32>(),
+            );
+            f.read_exact(data_slice).unwrap();
+        }
+    }
+}
+
+impl Debug for FloatColumn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FloatColumn: {:?}", self.data)
+    }
+}
+
+impl Encode for FloatColumn {
+    fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.data.write_to(writer)
+    }
+}
+
+impl Decode for FloatColumn {
+    fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
+        let vec = Vec::<f32>::read_from(reader)?;
+        info!("float column: {}", vec.capacity() * 4_usize);
+        Ok(Self { data: vec })
+    }
+}
+
+impl Column for FloatColumn {
+    fn get_type(&self) -> DataType {
+        DataType::Float
+    }
+
+    fn get(&self, index: usize) -> Option<RefItem> {
+        self.data.get(index).map(|x| RefItem::Float(x))
+    }
+
+    fn set(&mut self, index: usize, val: Item) {
+        match val {
+            Item::Float(v) => {
+                self.data[index] = v;
+            }
+            _ => {
+                self.data[index] = 0_f32;
+            }
+        }
+    }
+
+    fn push(&mut self, val: Item) {
+        match val {
+            Item::Float(v) => {
+                self.data.push(v);
+            }
+            _ => {
+                self.data.push(0_f32);
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
 pub struct StringColumn {
     pub data: Vec<String>,
 }
@@ -1387,3 +1688,233 @@ impl Column for DateTimeColumn {
         self
     }
 }
+
+/// Values of list columns.
+pub trait ListItem: Clone + Debug + Default + PartialEq + Encode + Decode + Send + Sync + 'static {
+    fn list_type() -> DataType;
+    fn to_list_item(list: Vec<Self>) -> Item;
+    fn to_list_ref_item(list: &[Self]) -> RefItem<'_>;
+    /// Returns an empty list if `item` is not a list of this type.
+    fn from_list_item(item: Item) -> Vec<Self>;
+    fn parse(val: &str) -> GDBResult<Self>;
+}
+
+macro_rules! impl_list_item {
+    ($t:ty, $variant:ident) => {
+        impl ListItem for $t {
+            fn list_type() -> DataType {
+                DataType::$variant
+            }
+
+            fn to_list_item(list: Vec<Self>) -> Item {
+                Item::$variant(list)
+            }
+
+            fn to_list_ref_item(list: &[Self]) -> RefItem<'_> {
+                RefItem::$variant(list)
+            }
+
+            fn from_list_item(item: Item) -> Vec<Self> {
+                match item {
+                    Item::$variant(list) => list,
+                    _ => vec![],
+                }
+            }
+
+            fn parse(val: &str) -> GDBResult<Self> {
+                Ok(val.parse::<$t>()?)
+            }
+        }
+    };
+}
+
+impl_list_item!(i32, Int32List);
+impl_list_item!(i64, Int64List);
+impl_list_item!(f32, FloatList);
+impl_list_item!(f64, DoubleList);
+
+impl ListItem for String {
+    fn list_type() -> DataType {
+        DataType::StringList
+    }
+
+    fn to_list_item(list: Vec<Self>) -> Item {
+        Item::StringList(list)
+    }
+
+    fn to_list_ref_item(list: &[Self]) -> RefItem<'_> {
+        RefItem::StringList(list)
+    }
+
+    fn from_list_item(item: Item) -> Vec<Self> {
+        match item {
+            Item::StringList(list) => list,
+            _ => vec![],
+        }
+    }
+
+    fn parse(val: &str) -> GDBResult<Self> {
+        Ok(val.to_string())
+    }
+}
+
+/// Parse a list field whose values are separated by `LIST_DELIMITER`, an empty field is an empty
+/// list.
+pub fn parse_list<T: ListItem>(val: &str) -> GDBResult<Vec<T>> {
+    if val.is_empty() {
+        return Ok(vec![]);
+    }
+    val.split(LIST_DELIMITER)
+        .map(T::parse)
+        .collect()
+}
+
+/// A column of variable-length lists. The values of all rows are stored in `values`, and the list
+/// of row `i` is the `lengths[i]` values from `offsets[i]`. A row that is overwritten appends its
+/// new values, the values left behind are dropped when the column is encoded.
+/// Overwritten lists are reclaimed once at least this many values are dead and they make up more
+/// than half of the values.
+const LIST_COMPACT_MIN_DEAD: usize = 1024;
+
+pub struct ListColumn<T: ListItem> {
+    pub offsets: Vec<usize>,
+    pub lengths: Vec<usize>,
+    pub values: Vec<T>,
+    // values no longer referenced by a row
+    dead: usize,
+}
+
+pub type Int32ListColumn = ListColumn<i32>;
+pub type Int64ListColumn = ListColumn<i64>;
+pub type FloatListColumn = ListColumn<f32>;
+pub type DoubleListColumn = ListColumn<f64>;
+pub type StringListColumn = ListColumn<String>;
+
+impl<T: ListItem> ListColumn<T> {
+    pub fn new() -> Self {
+        Self { offsets: Vec::new(), lengths: Vec::new(), values: Vec::new(), dead: 0 }
+    }
+
+    /// The old values of the row are reclaimed by a compaction once enough of them are dead.
+    pub fn set_list(&mut self, index: usize, list: Vec<T>) {
+        self.dead += self.lengths[index];
+        self.offsets[index] = self.values.len();
+        self.lengths[index] = list.len();
+        self.values.extend(list);
+        if self.dead >= LIST_COMPACT_MIN_DEAD && self.dead * 2 > self.values.len() {
+            self.compact();
+        }
+    }
+
+    /// Copy the list of every row into new values, which drops dead values.
+    pub fn compact(&mut self) {
+        let mut values = Vec::with_capacity(self.lengths.iter().sum());
+        for k in 0..self.offsets.len() {
+            let offset = self.offsets[k];
+            self.offsets[k] = values.len();
+            values.extend_from_slice(&self.values[offset..offset + self.lengths[k]]);
+        }
+        self.values = values;
+        self.dead = 0;
+    }
+
+    pub fn get_list(&self, index: usize) -> Option<&[T]> {
+        if index < self.offsets.len() {
+            let offset = self.offsets[index];
+            Some(&self.values[offset..offset + self.lengths[index]])
+        } else {
+            None
+        }
+    }
+
+    pub fn serialize(&self, f: &mut File) {
+        let mut column_bytes = Vec::new();
+        self.write_to(&mut column_bytes).unwrap();
+        f.write_u64(column_bytes.len() as u64).unwrap();
+        f.write_all(&column_bytes).unwrap();
+        f.flush().unwrap();
+    }
+
+    pub fn deserialize(&mut self, f: &mut File) {
+        let column_bytes_len = f.read_u64().unwrap() as usize;
+        let mut column_bytes = vec![0_u8; column_bytes_len];
+        f.read_exact(&mut column_bytes).unwrap();
+        *self = Self::read_from(&mut column_bytes.as_slice()).unwrap();
+    }
+
+    pub fn is_same(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+        (0..self.len()).all(|k| self.get_list(k) == other.get_list(k))
+    }
+}
+
+impl<T: ListItem> Debug for ListColumn<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lists: Vec<&[T]> = (0..self.len())
+            .map(|k| self.get_list(k).unwrap())
+            .collect();
+        write!(f, "ListColumn: {:?}", lists)
+    }
+}
+
+impl<T: ListItem> Encode for ListColumn<T> {
+    fn write_to<W: WriteExt>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_u64(self.len() as u64)?;
+        for k in 0..self.len() {
+            let list = self.get_list(k).unwrap();
+            writer.write_u64(list.len() as u64)?;
+            for v in list.iter() {
+                v.write_to(writer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: ListItem> Decode for ListColumn<T> {
+    fn read_from<R: ReadExt>(reader: &mut R) -> std::io::Result<Self> {
+        let row_num = reader.read_u64()? as usize;
+        let mut column = Self::new();
+        for _ in 0..row_num {
+            let length = reader.read_u64()? as usize;
+            column.offsets.push(column.values.len());
+            column.lengths.push(length);
+            for _ in 0..length {
+                column.values.push(T::read_from(reader)?);
+            }
+        }
+        Ok(column)
+    }
+}
+
+impl<T: ListItem> Column for ListColumn<T> {
+    fn get_type(&self) -> DataType {
+        T::list_type()
+    }
+
+    fn get(&self, index: usize) -> Option<RefItem> {
+        self.get_list(index)
+            .map(|x| T::to_list_ref_item(x))
+    }
+
+    fn set(&mut self, index: usize, val: Item) {
+        self.set_list(index, T::from_list_item(val));
+    }
+
+    fn push(&mut self, val: Item) {
+        let list = T::from_list_item(val);
+        self.offsets.push(self.values.len());
+        self.lengths.push(list.len());
+        self.values.extend(list);
+    }
+
+    fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -80,7 +80,9 @@ impl<'a, G: IndexType + Sync + Send, I: IndexType + Sync + Send> LocalVertex<'a,
         if let Some(prop) = self.table {
             let mut property_table = HashMap::new();
             for head in prop.header.keys() {
-                property_table.insert(head.clone(), prop.get_item(head, self.index.index()).unwrap());
+                if let Some(item) = prop.get_item(head, self.index.index()) {
+                    property_table.insert(head.clone(), item);
+                }
             }
             Some(property_table)
         } else {
@@ -165,7 +167,9 @@ impl<'a, G: IndexType + Sync + Send, I: IndexType + Sync + Send> LocalEdge<'a, G
         if let Some(prop) = self.table {
             let mut property_table = HashMap::new();
             for head in prop.header.keys() {
-                property_table.insert(head.clone(), prop.get_item(head, self.offset).unwrap());
+                if let Some(item) = prop.get_item(head, self.offset) {
+                    property_table.insert(head.clone(), item);
+                }
             }
             Some(property_table)
         } else {
@@ -14,21 +14,20 @@
 //! limitations under the License.
 
 use std::collections::HashSet;
-use std::fs::{create_dir_all, read_dir, File};
-use std::io::{BufReader, Read};
+use std::fs::{create_dir_all, read_dir};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 
-use csv::{Reader, ReaderBuilder};
+use csv::StringRecord;
 use regex::Regex;
-use rust_htslib::bgzf::Reader as GzReader;
 
 use crate::col_table::{parse_properties, ColTable};
 use crate::columns::Item;
 use crate::error::{GDBError, GDBResult};
 use crate::graph::IndexType;
 use crate::graph_db::CsrTrait;
+use crate::input_reader::create_input_reader;
 use crate::ldbc_parser::{LDBCEdgeParser, LDBCVertexParser};
 use crate::mcsr::MutableCsr;
 use crate::schema::{CsrGraphSchema, InputSchema, Schema};
@@ -133,8 +132,9 @@ impl<G: FromStr + Send + Sync + IndexType + Eq, I: Send + Sync + IndexType> Grap
         self.skip_header = true;
     }
 
-    fn load_vertices<R: Read>(
-        &mut self, vertex_type: LabelId, mut rdr: Reader<R>, table: &mut ColTable, is_static: bool,
+    fn load_vertices(
+        &mut self, vertex_type: LabelId, records: Box<dyn Iterator<Item = GDBResult<StringRecord>>>,
+        table: &mut ColTable, is_static: bool,
     ) {
         let input_header = self
             .input_schema
@@ -161,7 +161,7 @@ impl<G: FromStr + Send + Sync + IndexType + Eq, I: Send + Sync + IndexType> Grap
         let parser = LDBCVertexParser::new(vertex_type, id_col_id);
         info!("loading vertex-{}", vertex_type);
         if is_static {
-            for result in rdr.records() {
+            for result in records {
                 if let Ok(record) = result {
                     let vertex_meta = parser.parse_vertex_meta(&record);
                     if let Ok(properties) = parse_properties(&record, input_header, selected.as_slice()) {
@@ -175,7 +175,7 @@ impl<G: FromStr + Send + Sync + IndexType + Eq, I: Send + Sync + IndexType> Grap
                 }
             }
         } else {
-            for result in rdr.records() {
+            for result in records {
                 if let Ok(record) = result {
                     let vertex_meta = parser.parse_vertex_meta(&record);
                     if keep_vertex(vertex_meta.global_id, self.peers, self.work_id) {
@@ -194,9 +194,10 @@ impl<G: FromStr + Send + Sync + IndexType + Eq, I: Send + Sync + IndexType> Grap
         }
     }
 
-    fn load_edges<R: Read>(
+    fn load_edges(
         &mut self, src_vertex_type: LabelId, dst_vertex_type: LabelId, edge_type: LabelId,
-        is_src_static: bool, is_dst_static: bool, mut rdr: Reader<R>, idegree: &mut Vec<i64>,
+        is_src_static: bool, is_dst_static: bool,
+        records: Box<dyn Iterator<Item = GDBResult<StringRecord>>>, idegree: &mut Vec<i64>,
         odegree: &mut Vec<i64>, parsed_edges: &mut Vec<(I, I, Vec<Item>)>,
     ) {
         info!("loading edge-{}-{}-{}", src_vertex_type, edge_type, dst_vertex_type);
@@ -232,7 +233,7 @@ impl<G: FromStr + Send + Sync + IndexType + Eq, I: Send + Sync + IndexType> Grap
         parser.with_endpoint_col_id(src_col_id, dst_col_id);
 
         if is_src_static && is_dst_static {
-            for result in rdr.records() {
+            for result in records {
                 if let Ok(record) = result {
                     let edge_meta = parser.parse_edge_meta(&record);
                     if let Ok(properties) = parse_properties(&record, input_header, selected.as_slice()) {
@@ -253,7 +254,7 @@ impl<G: FromStr + Send + Sync + IndexType + Eq, I: Send + Sync + IndexType> Grap
                 }
             }
         } else if is_src_static && !is_dst_static {
-            for result in rdr.records() {
+            for result in records {
                 if let Ok(record) = result {
                     let edge_meta = parser.parse_edge_meta(&record);
                     if let Ok(properties) = parse_properties(&record, input_header, selected.as_slice()) {
@@ -278,7 +279,7 @@ impl<G: FromStr + Send + Sync + IndexType + Eq, I: Send + Sync + IndexType> Grap
                 }
             }
         } else if !is_src_static && is_dst_static {
-            for result in rdr.records() {
+            for result in records {
                 if let Ok(record) = result {
                     let edge_meta = parser.parse_edge_meta(&record);
                     if let Ok(properties) = parse_properties(&record, input_header, selected.as_slice()) {
@@ -301,7 +302,7 @@ impl<G: FromStr + Send + Sync + IndexType + Eq, I: Send + Sync + IndexType> Grap
                 }
             }
         } else {
-            for result in rdr.records() {
+            for result in records {
                 if let Ok(record) = result {
                     let edge_meta = parser.parse_edge_meta(&record);
                     if let Ok(properties) = parse_properties(&record, input_header, selected.as_slice()) {
@@ -349,41 +350,20 @@ impl<G: FromStr + Send + Sync + IndexType + Eq, I: Send + Sync + IndexType> Grap
             let vertex_files = get_files_list(&self.input_dir, vertex_file_strings).unwrap();
 
             for vertex_file in vertex_files.iter() {
-                if vertex_file
-                    .clone()
-                    .to_str()
-                    .unwrap()
-                    .ends_with(".csv")
-                {
-                    let rdr = ReaderBuilder::new()
-                        .delimiter(self.delim)
-                        .buffer_capacity(4096)
-                        .comment(Some(b'#'))
-                        .flexible(true)
-                        .has_headers(self.skip_header)
-                        .from_reader(BufReader::new(File::open(&vertex_file).unwrap()));
-                    self.load_vertices(
-                        v_label_i,
-                        rdr,
-                        &mut table,
-                        self.graph_schema.is_static_vertex(v_label_i),
-                    );
-                } else if vertex_file
-                    .clone()
-                    .to_str()
-                    .unwrap()
-                    .ends_with(".csv.gz")
+                if let Some(format) = self
+                    .input_schema
+                    .get_vertex_file_format(v_label_i, vertex_file)
                 {
-                    let rdr = ReaderBuilder::new()
-                        .delimiter(self.delim)
-                        .buffer_capacity(4096)
-                        .comment(Some(b'#'))
-                        .flexible(true)
-                        .has_headers(self.skip_header)
-                        .from_reader(BufReader::new(GzReader::from_path(&vertex_file).unwrap()));
+                    let input_header = self
+                        .input_schema
+                        .get_vertex_header(v_label_i)
+                        .unwrap();
+                    let records =
+                        create_input_reader(format, self.delim, self.skip_header, Some(input_header))
+                            .read_records(vertex_file)?;
                     self.load_vertices(
                         v_label_i,
-                        rdr,
+                        records,
                         &mut table,
                         self.graph_schema.is_static_vertex(v_label_i),
                     );
@@ -424,50 +404,30 @@ impl<G: FromStr + Send + Sync + IndexType + Eq, I: Send + Sync + IndexType> Grap
                         let edge_files = get_files_list(&self.input_dir, edge_file_strings).unwrap();
                         for edge_file in edge_files.iter() {
                             info!("reading from file: {}", edge_file.clone().to_str().unwrap());
-                            if edge_file
-                                .clone()
-                                .to_str()
-                                .unwrap()
-                                .ends_with(".csv")
-                            {
-                                let rdr = ReaderBuilder::new()
-                                    .delimiter(self.delim)
-                                    .buffer_capacity(4096)
-                                    .comment(Some(b'#'))
-                                    .flexible(true)
-                                    .has_headers(self.skip_header)
-                                    .from_reader(BufReader::new(File::open(&edge_file).unwrap()));
-                                self.load_edges(
-                                    src_label_i,
-                                    dst_label_i,
-                                    e_label_i,
-                                    self.graph_schema.is_static_vertex(src_label_i),
-                                    self.graph_schema.is_static_vertex(dst_label_i),
-                                    rdr,
-                                    &mut idegree,
-                                    &mut odegree,
-                                    &mut parsed_edges,
-                                );
-                            } else if edge_file
-                                .clone()
-                                .to_str()
-                                .unwrap()
-                                .ends_with(".csv.gz")
-                            {
-                                let rdr = ReaderBuilder::new()
-                                    .delimiter(self.delim)
-                                    .buffer_capacity(4096)
-                                    .comment(Some(b'#'))
-                                    .flexible(true)
-                                    .has_headers(self.skip_header)
-                                    .from_reader(BufReader::new(GzReader::from_path(&edge_file).unwrap()));
+                            if let Some(format) = self.input_schema.get_edge_file_format(
+                                src_label_i,
+                                e_label_i,
+                                dst_label_i,
+                                edge_file,
+                            ) {
+                                let input_header = self
+                                    .input_schema
+                                    .get_edge_header(src_label_i, e_label_i, dst_label_i)
+                                    .unwrap();
+                                let records = create_input_reader(
+                                    format,
+                                    self.delim,
+                                    self.skip_header,
+                                    Some(input_header),
+                                )
+                                .read_records(edge_file)?;
                                 self.load_edges(
                                     src_label_i,
                                     dst_label_i,
                                     e_label_i,
                                     self.graph_schema.is_static_vertex(src_label_i),
                                     self.graph_schema.is_static_vertex(dst_label_i),
-                                    rdr,
+                                    records,
                                     &mut idegree,
                                     &mut odegree,
                                     &mut parsed_edges,
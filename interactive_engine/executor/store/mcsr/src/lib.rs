@@ -34,6 +34,7 @@ pub mod graph_db;
 pub mod graph_db_impl;
 pub mod graph_loader;
 pub mod graph_partitioner;
+pub mod input_reader;
 pub mod io;
 pub mod ldbc_parser;
 pub mod mcsr;
@@ -16,6 +16,7 @@ package com.alibaba.graphscope.groot.store;
 import com.alibaba.graphscope.groot.operation.OperationBatch;
 import com.alibaba.graphscope.groot.store.backup.GraphPartitionBackup;
 import com.alibaba.graphscope.groot.store.external.ExternalStorage;
+import com.alibaba.graphscope.proto.groot.ChangeEventBatchPb;
 import com.alibaba.graphscope.proto.groot.GraphDefPb;
 import com.alibaba.graphscope.proto.groot.Statistics;
 
@@ -36,6 +37,20 @@ public interface GraphPartition extends Closeable {
 
     Statistics getGraphStatisticsBlob(long si) throws IOException;
 
+    /**
+     * Changes of a range are returned in a stable order, read them by pages of increasing offsets
+     * until a page has less than {@code limit} changes.
+     *
+     * @param fromSi exclusive start of the snapshot range, must not be behind the gc watermark
+     * @param toSi inclusive end of the snapshot range
+     * @param offset number of changes of the range to skip
+     * @param limit max number of changes to return
+     * @return vertex and edge changes committed in the range
+     * @throws IOException
+     */
+    ChangeEventBatchPb getGraphChanges(long fromSi, long toSi, long offset, int limit)
+            throws IOException;
+
     void ingestExternalFile(ExternalStorage storage, String fullPath) throws IOException;
 
     GraphPartitionBackup openBackupEngine();
@@ -30,6 +30,9 @@ public interface GraphLibrary extends Library {
 
     JnaResponse getGraphStatistics(Pointer storePointer, long snapshotId);
 
+    JnaResponse getGraphChanges(
+            Pointer storePointer, long fromSnapshotId, long toSnapshotId, long offset, int limit);
+
     Pointer openGraphBackupEngine(Pointer storePointer, String backupPath);
 
     void closeGraphBackupEngine(Pointer bePointer);
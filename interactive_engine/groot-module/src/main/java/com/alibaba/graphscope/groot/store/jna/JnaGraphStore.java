@@ -21,6 +21,7 @@ import com.alibaba.graphscope.groot.operation.OperationBatch;
 import com.alibaba.graphscope.groot.store.GraphPartition;
 import com.alibaba.graphscope.groot.store.backup.GraphPartitionBackup;
 import com.alibaba.graphscope.groot.store.external.ExternalStorage;
+import com.alibaba.graphscope.proto.groot.ChangeEventBatchPb;
 import com.alibaba.graphscope.proto.groot.GraphDefPb;
 import com.alibaba.graphscope.proto.groot.Statistics;
 import com.sun.jna.Pointer;
@@ -135,6 +136,20 @@ public class JnaGraphStore implements GraphPartition {
         }
     }
 
+    @Override
+    public ChangeEventBatchPb getGraphChanges(long fromSi, long toSi, long offset, int limit)
+            throws IOException {
+        logger.debug("getGraphChanges");
+        try (JnaResponse jnaResponse =
+                GraphLibrary.INSTANCE.getGraphChanges(this.pointer, fromSi, toSi, offset, limit)) {
+            if (!jnaResponse.success()) {
+                String errMsg = jnaResponse.getErrMsg();
+                throw new ExternalStorageErrorException(errMsg);
+            }
+            return ChangeEventBatchPb.parseFrom(jnaResponse.getData());
+        }
+    }
+
     @Override
     public void ingestExternalFile(ExternalStorage storage, String sstPath) throws IOException {
         logger.debug("ingestExternalFile");